@@ -0,0 +1,42 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+use glium_shapes::options::WithTransform;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // KEY POINT: Use an AabbWireBuilder to build a new AABB wire.
+    // Here we draw the edges of a box that is oriented by rotating it.
+    let aabb_wire = glium_shapes::aabb_wire::AabbWireBuilder::new()
+        .corners(
+            cgmath::Point3::<f32>::new(-0.5, -0.25, -0.5),
+            cgmath::Point3::<f32>::new(0.5, 0.25, 0.5),
+        )
+        .rotate_y(0.5)
+        .build(&display)
+        .expect("Failed to build AABB wire shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the AABB wire shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(
+                &aabb_wire,
+                &aabb_wire,
+                &data.program,
+                &uniforms,
+                &data.draw_params,
+            )
+            .expect("Failed to draw AABB wire shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
@@ -0,0 +1,41 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // KEY POINT: Use an AnnulusBuilder to build a new annulus.
+    // Use the methods on the builder object to customise the resultant
+    // shape. In this case we will create a thin ring with an inner radius
+    // of 3 and an outer radius of 4.
+    let annulus = glium_shapes::annulus::AnnulusBuilder::new()
+        .inner_radius(3.0)
+        .outer_radius(4.0)
+        .segments(48)
+        .build(&display)
+        .expect("Failed to build annulus shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the annulus shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(
+                &annulus,
+                &annulus,
+                &data.program,
+                &uniforms,
+                &data.draw_params,
+            )
+            .expect("Failed to draw annulus shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
@@ -0,0 +1,38 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // KEY POINT: Use an AntiprismBuilder to build a new antiprism.
+    // Here we build a hexagonal antiprism, squashed vertically.
+    let antiprism = glium_shapes::antiprism::AntiprismBuilder::new()
+        .sides(6)
+        .height(0.5)
+        .build(&display)
+        .expect("Failed to build antiprism shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the antiprism shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(
+                &antiprism,
+                &antiprism,
+                &data.program,
+                &uniforms,
+                &data.draw_params,
+            )
+            .expect("Failed to draw antiprism shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
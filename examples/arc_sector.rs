@@ -0,0 +1,39 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // KEY POINT: Use an ArcSectorBuilder to build a new arc sector.
+    // The sector sweeps two thirds of a turn, and is extruded into a solid
+    // wedge.
+    let arc_sector = glium_shapes::arc_sector::ArcSectorBuilder::new()
+        .sweep_angle(4.0 * std::f32::consts::FRAC_PI_3)
+        .thickness(0.2)
+        .build(&display)
+        .expect("Failed to build arc sector shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the arc sector shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(
+                &arc_sector,
+                &arc_sector,
+                &data.program,
+                &uniforms,
+                &data.draw_params,
+            )
+            .expect("Failed to draw arc sector shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
@@ -0,0 +1,37 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // KEY POINT: Use an ArrowBuilder to build a new arrow.
+    // Use the methods on the builder object to customise the resultant
+    // shape. In this case we will create an arrow pointing between two
+    // points.
+    let arrow = glium_shapes::arrow::ArrowBuilder::new()
+        .segments(16)
+        .pointing(
+            cgmath::Point3::<f32>::new(-4.0, -4.0, 0.0),
+            cgmath::Point3::<f32>::new(4.0, 4.0, 0.0),
+        )
+        .build(&display)
+        .expect("Failed to build arrow shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the arrow shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(&arrow, &arrow, &data.program, &uniforms, &data.draw_params)
+            .expect("Failed to draw arrow shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
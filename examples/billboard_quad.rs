@@ -0,0 +1,37 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // KEY POINT: Use a BillboardQuadBuilder to build a new billboard quad.
+    // This builds a sprite quad that pivots about the centre of its base.
+    let billboard_quad = glium_shapes::billboard_quad::BillboardQuadBuilder::new()
+        .anchor(glium_shapes::billboard_quad::Anchor::BottomCenter)
+        .build(&display)
+        .expect("Failed to build billboard quad shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the billboard quad shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(
+                &billboard_quad,
+                &billboard_quad,
+                &data.program,
+                &uniforms,
+                &data.draw_params,
+            )
+            .expect("Failed to draw billboard quad shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
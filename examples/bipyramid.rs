@@ -0,0 +1,38 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+use glium_shapes::options::WithTransform;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // KEY POINT: Use a BipyramidBuilder to build a new bipyramid.
+    // Here we build the default square-based diamond, stretched vertically.
+    let bipyramid = glium_shapes::bipyramid::BipyramidBuilder::new()
+        .scale(0.75, 1.5, 0.75)
+        .build(&display)
+        .expect("Failed to build bipyramid shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the bipyramid shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(
+                &bipyramid,
+                &bipyramid,
+                &data.program,
+                &uniforms,
+                &data.draw_params,
+            )
+            .expect("Failed to draw bipyramid shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
@@ -0,0 +1,40 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+use glium_shapes::options::WithTransform;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // KEY POINT: Use a CameraFrustumBuilder to build a new camera frustum.
+    // Here we visualise the frustum of a camera at the origin looking along the
+    // negative z-axis, with near and far planes at distances of 0.5 and 2.
+    let camera_frustum = glium_shapes::camera_frustum::CameraFrustumBuilder::new()
+        .perspective(std::f32::consts::FRAC_PI_3, 1.5, 0.5, 2.0)
+        .translate(0.0, 0.0, 1.0)
+        .build(&display)
+        .expect("Failed to build camera frustum shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the camera frustum shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(
+                &camera_frustum,
+                &camera_frustum,
+                &data.program,
+                &uniforms,
+                &data.draw_params,
+            )
+            .expect("Failed to draw camera frustum shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
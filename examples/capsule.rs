@@ -0,0 +1,42 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+use glium_shapes::options::WithTransform;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // KEY POINT: Use a CapsuleBuilder to build a new capsule.
+    // Use the methods on the builder object to customise the resultant
+    // shape. In this case we will create a capsule of radius 2 with a
+    // total height of 10, leaning towards the camera.
+    let capsule = glium_shapes::capsule::CapsuleBuilder::new()
+        .radius(2.0)
+        .half_height(3.0)
+        .rotate_x(0.5)
+        .build(&display)
+        .expect("Failed to build capsule shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the capsule shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(
+                &capsule,
+                &capsule,
+                &data.program,
+                &uniforms,
+                &data.draw_params,
+            )
+            .expect("Failed to draw capsule shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
@@ -0,0 +1,37 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // KEY POINT: Use a CircleOutlineBuilder to build a new circle outline.
+    // This builds a smooth circle of 64 segments.
+    let circle_outline = glium_shapes::circle_outline::CircleOutlineBuilder::new()
+        .segments(64)
+        .build(&display)
+        .expect("Failed to build circle outline shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the circle outline shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(
+                &circle_outline,
+                &circle_outline,
+                &data.program,
+                &uniforms,
+                &data.draw_params,
+            )
+            .expect("Failed to draw circle outline shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
@@ -85,10 +85,12 @@ pub fn setup<'a>() -> (glium::glutin::EventsLoop, Display, ExampleData<'a>) {
 pub fn process_events(ev: &mut glium::glutin::EventsLoop) -> bool {
     let mut result = true;
     ev.poll_events(|event| {
-        if let glium::glutin::Event::WindowEvent { ref event, .. } = event {
-            if let glium::glutin::WindowEvent::CloseRequested = event {
-                result = false;
-            }
+        if let glium::glutin::Event::WindowEvent {
+            event: glium::glutin::WindowEvent::CloseRequested,
+            ..
+        } = event
+        {
+            result = false;
         }
     });
     result
@@ -0,0 +1,35 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+use glium_shapes::options::WithTransform;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // KEY POINT: Use a ConeBuilder to build a new cone.
+    // Use the methods on the builder object to customise the resultant
+    // shape. In this case we will create a cone with a base of radius 3
+    // and a height of 6.
+    let cone = glium_shapes::cone::ConeBuilder::new()
+        .segments(32)
+        .scale(6.0, 6.0, 6.0)
+        .build(&display)
+        .expect("Failed to build cone shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the cone shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(&cone, &cone, &data.program, &uniforms, &data.draw_params)
+            .expect("Failed to draw cone shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
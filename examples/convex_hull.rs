@@ -0,0 +1,48 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // KEY POINT: Use a ConvexHullBuilder to build a new convex hull.
+    // The hull is computed from an irregular cloud of points, some of which
+    // lie within the hull and do not contribute to it.
+    let convex_hull = glium_shapes::convex_hull::ConvexHullBuilder::new()
+        .points(&[
+            cgmath::Point3::<f32>::new(0.0, 0.6, 0.0),
+            cgmath::Point3::<f32>::new(0.5, -0.3, 0.2),
+            cgmath::Point3::<f32>::new(-0.4, -0.4, 0.4),
+            cgmath::Point3::<f32>::new(-0.1, -0.5, -0.5),
+            cgmath::Point3::<f32>::new(0.4, 0.2, -0.4),
+            cgmath::Point3::<f32>::new(-0.5, 0.3, -0.1),
+            cgmath::Point3::<f32>::new(0.1, 0.0, 0.5),
+            cgmath::Point3::<f32>::new(0.0, 0.0, 0.0),
+            cgmath::Point3::<f32>::new(0.1, -0.1, 0.1),
+        ])
+        .build(&display)
+        .expect("Failed to build convex hull shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the convex hull shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(
+                &convex_hull,
+                &convex_hull,
+                &data.program,
+                &uniforms,
+                &data.draw_params,
+            )
+            .expect("Failed to draw convex hull shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
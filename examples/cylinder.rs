@@ -0,0 +1,42 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+use glium_shapes::options::WithTransform;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // KEY POINT: Use a CylinderBuilder to build a new cylinder.
+    // Use the methods on the builder object to customise the resultant
+    // shape. In this case we will create a cylinder of radius 2 and height 6
+    // with 32 radial segments, tipped over onto its side.
+    let cylinder = glium_shapes::cylinder::CylinderBuilder::new()
+        .segments(32)
+        .scale(4.0, 6.0, 4.0)
+        .rotate_x(1.0)
+        .build(&display)
+        .expect("Failed to build cylinder shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the cylinder shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(
+                &cylinder,
+                &cylinder,
+                &data.program,
+                &uniforms,
+                &data.draw_params,
+            )
+            .expect("Failed to draw cylinder shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
@@ -0,0 +1,36 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+use glium_shapes::options::WithTransform;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // KEY POINT: Use a DiscBuilder to build a new disc.
+    // Use the methods on the builder object to customise the resultant
+    // shape. In this case we will create an octagonal disc of radius 4,
+    // spinning it slightly about the y-axis.
+    let disc = glium_shapes::disc::DiscBuilder::new()
+        .segments(8)
+        .scale(8.0, 8.0, 8.0)
+        .rotate_y(0.5)
+        .build(&display)
+        .expect("Failed to build disc shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the disc shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(&disc, &disc, &data.program, &uniforms, &data.draw_params)
+            .expect("Failed to draw disc shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
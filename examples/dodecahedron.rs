@@ -0,0 +1,41 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+use glium_shapes::options::WithTransform;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // KEY POINT: Use a DodecahedronBuilder to build a new dodecahedron.
+    // Use the methods on the builder object to customise the resultant
+    // shape. In this case we will create a dodecahedron inscribed in a
+    // sphere of diameter 10.
+    let dodecahedron = glium_shapes::dodecahedron::DodecahedronBuilder::new()
+        .scale(10.0, 10.0, 10.0)
+        .rotate_y(0.3)
+        .build(&display)
+        .expect("Failed to build dodecahedron shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the dodecahedron shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(
+                &dodecahedron,
+                &dodecahedron,
+                &data.program,
+                &uniforms,
+                &data.draw_params,
+            )
+            .expect("Failed to draw dodecahedron shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
@@ -0,0 +1,32 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // KEY POINT: Use an ElbowBuilder to build a new elbow.
+    // Here we bend a thicker pipe through a third of a turn.
+    let elbow = glium_shapes::elbow::ElbowBuilder::new()
+        .pipe_radius(0.2)
+        .bend_angle(2.0 * std::f32::consts::PI / 3.0)
+        .build(&display)
+        .expect("Failed to build elbow shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the elbow shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(&elbow, &elbow, &data.program, &uniforms, &data.draw_params)
+            .expect("Failed to draw elbow shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
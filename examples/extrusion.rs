@@ -0,0 +1,51 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+use glium_shapes::options::WithTransform;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // KEY POINT: Use an ExtrusionBuilder to build a new extrusion.
+    // Use the methods on the builder object to customise the resultant
+    // shape. In this case we will extrude a concave, arrow-shaped outline
+    // with a size of 8.
+    let extrusion = glium_shapes::extrusion::ExtrusionBuilder::new()
+        .polygon(&[
+            cgmath::Point2::<f32>::new(-0.5, -0.2),
+            cgmath::Point2::<f32>::new(0.1, -0.2),
+            cgmath::Point2::<f32>::new(0.1, -0.5),
+            cgmath::Point2::<f32>::new(0.5, 0.0),
+            cgmath::Point2::<f32>::new(0.1, 0.5),
+            cgmath::Point2::<f32>::new(0.1, 0.2),
+            cgmath::Point2::<f32>::new(-0.5, 0.2),
+        ])
+        .depth(0.25)
+        .scale(8.0, 8.0, 8.0)
+        .rotate_y(0.5)
+        .build(&display)
+        .expect("Failed to build extrusion shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the extrusion shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(
+                &extrusion,
+                &extrusion,
+                &data.program,
+                &uniforms,
+                &data.draw_params,
+            )
+            .expect("Failed to draw extrusion shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
@@ -0,0 +1,45 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+use glium_shapes::cap_mode::CapMode;
+use glium_shapes::options::{WithCaps, WithTransform};
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // KEY POINT: Use a FrustumBuilder to build a new frustum.
+    // Use the methods on the builder object to customise the resultant
+    // shape. In this case we will create a lampshade-like frustum with a
+    // height of 6, open at both ends.
+    let frustum = glium_shapes::frustum::FrustumBuilder::new()
+        .bottom_radius(0.5)
+        .top_radius(0.3)
+        .segments(32)
+        .caps(CapMode::Neither)
+        .scale(6.0, 6.0, 6.0)
+        .build(&display)
+        .expect("Failed to build frustum shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the frustum shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(
+                &frustum,
+                &frustum,
+                &data.program,
+                &uniforms,
+                &data.draw_params,
+            )
+            .expect("Failed to draw frustum shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
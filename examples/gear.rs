@@ -0,0 +1,31 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // KEY POINT: Use a GearBuilder to build a new gear.
+    // This builds a gear with 20 teeth.
+    let gear = glium_shapes::gear::GearBuilder::new()
+        .teeth(20)
+        .build(&display)
+        .expect("Failed to build gear shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the gear shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(&gear, &gear, &data.program, &uniforms, &data.draw_params)
+            .expect("Failed to draw gear shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
@@ -0,0 +1,33 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+use glium_shapes::options::WithTransform;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // KEY POINT: Use a GemBuilder to build a new gem.
+    // Here we build a sixteen-sided gem, scaled up to fill the view.
+    let gem = glium_shapes::gem::GemBuilder::new()
+        .sides(16)
+        .scale(1.5, 1.5, 1.5)
+        .build(&display)
+        .expect("Failed to build gem shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the gem shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(&gem, &gem, &data.program, &uniforms, &data.draw_params)
+            .expect("Failed to draw gem shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
@@ -0,0 +1,41 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+use glium_shapes::options::WithTransform;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // KEY POINT: Use a GeodesicDomeBuilder to build a new geodesic dome.
+    // Here we clip a thrice-subdivided icosphere above the equator, and scale it
+    // down to sit within the view.
+    let geodesic_dome = glium_shapes::geodesic_dome::GeodesicDomeBuilder::new()
+        .subdivisions(3)
+        .latitude(0.3)
+        .scale(0.75, 0.75, 0.75)
+        .build(&display)
+        .expect("Failed to build geodesic dome shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the geodesic dome shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(
+                &geodesic_dome,
+                &geodesic_dome,
+                &data.program,
+                &uniforms,
+                &data.draw_params,
+            )
+            .expect("Failed to draw geodesic dome shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
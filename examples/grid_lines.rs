@@ -0,0 +1,39 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // KEY POINT: Use a GridLinesBuilder to build a new grid.
+    // This builds a 20x20 grid of lines.
+    let grid_lines = glium_shapes::grid_lines::GridLinesBuilder::new()
+        .cells_x(20)
+        .cells_z(20)
+        .spacing(0.05)
+        .build(&display)
+        .expect("Failed to build grid shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the grid shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(
+                &grid_lines,
+                &grid_lines,
+                &data.program,
+                &uniforms,
+                &data.draw_params,
+            )
+            .expect("Failed to draw grid shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
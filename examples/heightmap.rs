@@ -0,0 +1,51 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+use glium_shapes::options::WithTransform;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // Generate a grid of rolling hills to use as the heightmap samples.
+    let (width, depth) = (32, 32);
+    let heights = (0..width * depth)
+        .map(|i| {
+            let (x, z) = ((i % width) as f32, (i / width) as f32);
+            ((x * 0.4).sin() + (z * 0.3).cos()) * 0.05
+        })
+        .collect::<Vec<f32>>();
+
+    // KEY POINT: Use a HeightmapBuilder to build a new heightmap.
+    // Use the methods on the builder object to customise the resultant
+    // shape. In this case we will scale the terrain to a size of 10, and
+    // tilt it towards the camera.
+    let heightmap = glium_shapes::heightmap::HeightmapBuilder::new()
+        .heights(width, depth, &heights)
+        .scale(10.0, 10.0, 10.0)
+        .rotate_x(0.6)
+        .build(&display)
+        .expect("Failed to build heightmap shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the heightmap shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(
+                &heightmap,
+                &heightmap,
+                &data.program,
+                &uniforms,
+                &data.draw_params,
+            )
+            .expect("Failed to draw heightmap shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
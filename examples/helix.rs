@@ -0,0 +1,31 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // KEY POINT: Use a HelixBuilder to build a new helix.
+    // This builds a spring of 5 turns.
+    let helix = glium_shapes::helix::HelixBuilder::new()
+        .turns(5.0)
+        .build(&display)
+        .expect("Failed to build helix shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the helix shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(&helix, &helix, &data.program, &uniforms, &data.draw_params)
+            .expect("Failed to draw helix shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
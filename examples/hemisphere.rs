@@ -0,0 +1,43 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+use glium_shapes::options::WithTransform;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // KEY POINT: Use a HemisphereBuilder to build a new hemisphere.
+    // Use the methods on the builder object to customise the resultant
+    // shape. In this case we will create a dome with a radius of 5, tilted
+    // towards the camera.
+    let hemisphere = glium_shapes::hemisphere::HemisphereBuilder::new()
+        .segments(32)
+        .rings(8)
+        .scale(5.0, 5.0, 5.0)
+        .rotate_x(0.5)
+        .build(&display)
+        .expect("Failed to build hemisphere shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the hemisphere shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(
+                &hemisphere,
+                &hemisphere,
+                &data.program,
+                &uniforms,
+                &data.draw_params,
+            )
+            .expect("Failed to draw hemisphere shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
@@ -0,0 +1,42 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+use glium_shapes::options::WithTransform;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // KEY POINT: Use a HexGridBuilder to build a new hex grid.
+    // Here we build a grid of flat-topped cells, tilted towards the camera.
+    let hex_grid = glium_shapes::hex_grid::HexGridBuilder::new()
+        .columns(6)
+        .rows(5)
+        .cell_size(0.2)
+        .orientation(glium_shapes::hex_grid::HexOrientation::FlatTop)
+        .rotate_x(1.0)
+        .build(&display)
+        .expect("Failed to build hex grid shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the hex grid shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(
+                &hex_grid,
+                &hex_grid,
+                &data.program,
+                &uniforms,
+                &data.draw_params,
+            )
+            .expect("Failed to draw hex grid shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
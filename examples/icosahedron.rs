@@ -0,0 +1,41 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+use glium_shapes::options::WithTransform;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // KEY POINT: Use an IcosahedronBuilder to build a new icosahedron.
+    // Use the methods on the builder object to customise the resultant
+    // shape. In this case we will create an icosahedron inscribed in a
+    // sphere of diameter 10.
+    let icosahedron = glium_shapes::icosahedron::IcosahedronBuilder::new()
+        .scale(10.0, 10.0, 10.0)
+        .rotate_x(0.3)
+        .build(&display)
+        .expect("Failed to build icosahedron shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the icosahedron shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(
+                &icosahedron,
+                &icosahedron,
+                &data.program,
+                &uniforms,
+                &data.draw_params,
+            )
+            .expect("Failed to draw icosahedron shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
@@ -0,0 +1,41 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+use glium_shapes::options::WithTransform;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // KEY POINT: Use an IcoSphereBuilder to build a new icosphere.
+    // Use the methods on the builder object to customise the resultant
+    // shape. In this case we will create an icosphere of radius 4, with
+    // each face of the icosahedron subdivided three times.
+    let icosphere = glium_shapes::icosphere::IcoSphereBuilder::new()
+        .subdivisions(3)
+        .scale(4.0, 4.0, 4.0)
+        .build(&display)
+        .expect("Failed to build icosphere shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the icosphere shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(
+                &icosphere,
+                &icosphere,
+                &data.program,
+                &uniforms,
+                &data.draw_params,
+            )
+            .expect("Failed to draw icosphere shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
@@ -0,0 +1,43 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+use glium_shapes::options::WithTransform;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // KEY POINT: Use a LatheBuilder to build a new lathe.
+    // Use the methods on the builder object to customise the resultant
+    // shape. In this case we will revolve a vase-like profile, with a
+    // height of 8.
+    let lathe = glium_shapes::lathe::LatheBuilder::new()
+        .profile(&[
+            cgmath::Point2::<f32>::new(0.0, -0.5),
+            cgmath::Point2::<f32>::new(0.2, -0.5),
+            cgmath::Point2::<f32>::new(0.3, -0.3),
+            cgmath::Point2::<f32>::new(0.25, 0.0),
+            cgmath::Point2::<f32>::new(0.1, 0.3),
+            cgmath::Point2::<f32>::new(0.15, 0.5),
+        ])
+        .segments(32)
+        .scale(8.0, 8.0, 8.0)
+        .build(&display)
+        .expect("Failed to build lathe shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the lathe shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(&lathe, &lathe, &data.program, &uniforms, &data.draw_params)
+            .expect("Failed to draw lathe shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
@@ -0,0 +1,37 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // KEY POINT: Use a MobiusStripBuilder to build a new Möbius strip.
+    // This builds a wider band than the default.
+    let mobius_strip = glium_shapes::mobius_strip::MobiusStripBuilder::new()
+        .width(0.5)
+        .build(&display)
+        .expect("Failed to build Möbius strip shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the Möbius strip shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(
+                &mobius_strip,
+                &mobius_strip,
+                &data.program,
+                &uniforms,
+                &data.draw_params,
+            )
+            .expect("Failed to draw Möbius strip shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
@@ -0,0 +1,56 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+use glium_shapes::options::WithTransform;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // Build a sphere whose normals we wish to visualise.
+    let sphere_builder = glium_shapes::sphere::SphereBuilder::new().scale(0.75, 0.75, 0.75);
+    let sphere = sphere_builder
+        .build_vertices()
+        .expect("Failed to build sphere vertices");
+
+    // KEY POINT: Use a NormalsVisualizerBuilder to build a new normals visualizer
+    // from the vertices of the sphere.
+    let normals_visualizer = glium_shapes::normals_visualizer::NormalsVisualizerBuilder::new()
+        .vertices(&sphere)
+        .build(&display)
+        .expect("Failed to build normals visualizer shape");
+    let sphere = sphere_builder
+        .build(&display)
+        .expect("Failed to build sphere shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the normals visualizer shape alongside the sphere,
+        // by passing each as a source of both vertices and indices to glium.
+        frame
+            .draw(
+                &sphere,
+                &sphere,
+                &data.program,
+                &uniforms,
+                &data.draw_params,
+            )
+            .expect("Failed to draw sphere shape");
+        frame
+            .draw(
+                &normals_visualizer,
+                &normals_visualizer,
+                &data.program,
+                &uniforms,
+                &data.draw_params,
+            )
+            .expect("Failed to draw normals visualizer shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
@@ -0,0 +1,41 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+use glium_shapes::options::WithTransform;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // KEY POINT: Use an OctahedronBuilder to build a new octahedron.
+    // Use the methods on the builder object to customise the resultant
+    // shape. In this case we will create an elongated octahedron, like a
+    // gem or a bone marker, with a height of 8 and a width of 4.
+    let octahedron = glium_shapes::octahedron::OctahedronBuilder::new()
+        .scale(4.0, 8.0, 4.0)
+        .rotate_y(0.4)
+        .build(&display)
+        .expect("Failed to build octahedron shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the octahedron shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(
+                &octahedron,
+                &octahedron,
+                &data.program,
+                &uniforms,
+                &data.draw_params,
+            )
+            .expect("Failed to draw octahedron shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
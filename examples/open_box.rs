@@ -0,0 +1,40 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+use glium_shapes::options::WithTransform;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // KEY POINT: Use an OpenBoxBuilder to build a new open box.
+    // The box is tilted such that its cavity is seen from above.
+    let open_box = glium_shapes::open_box::OpenBoxBuilder::new()
+        .thickness(0.15)
+        .rotate_x(0.6)
+        .rotate_y(-0.5)
+        .build(&display)
+        .expect("Failed to build open box shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the open box shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(
+                &open_box,
+                &open_box,
+                &data.program,
+                &uniforms,
+                &data.draw_params,
+            )
+            .expect("Failed to draw open box shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
@@ -0,0 +1,43 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // KEY POINT: Use a ParametricSurfaceBuilder to build a new parametric surface.
+    // The surface is a rippled sheet, defined by a closure that is sampled
+    // over a grid from -0.5 to 0.5 in each parameter.
+    let parametric_surface = glium_shapes::parametric_surface::ParametricSurfaceBuilder::new()
+        .function(|u, v| {
+            let ripple = 0.05 * ((u * u + v * v).sqrt() * 24.0).cos();
+            cgmath::Point3::<f32>::new(u, v, ripple)
+        })
+        .domain(-0.5..0.5, -0.5..0.5)
+        .segments(48, 48)
+        .build(&display)
+        .expect("Failed to build parametric surface shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the parametric surface shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(
+                &parametric_surface,
+                &parametric_surface,
+                &data.program,
+                &uniforms,
+                &data.draw_params,
+            )
+            .expect("Failed to draw parametric surface shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
@@ -0,0 +1,37 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+use glium_shapes::options::WithTransform;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // KEY POINT: Use a PlaneBuilder to build a new plane.
+    // Use the methods on the builder object to customise the resultant
+    // shape. In this case we will create a 12x12 floor, subdivided into an
+    // 8x8 grid, located below the origin and tilted towards the camera.
+    let plane = glium_shapes::plane::PlaneBuilder::new()
+        .subdivisions(7, 7)
+        .scale(12.0, 1.0, 12.0)
+        .translate(0.0, -3.0, 0.0)
+        .rotate_x(0.5)
+        .build(&display)
+        .expect("Failed to build plane shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the plane shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(&plane, &plane, &data.program, &uniforms, &data.draw_params)
+            .expect("Failed to draw plane shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
@@ -0,0 +1,43 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // KEY POINT: Use a PolylineBuilder to build a new polyline.
+    // This builds a closed zig-zag through four points.
+    let polyline = glium_shapes::polyline::PolylineBuilder::new()
+        .points(&[
+            cgmath::Point3::<f32>::new(-0.5, -0.5, 0.0),
+            cgmath::Point3::<f32>::new(0.5, -0.5, 0.0),
+            cgmath::Point3::<f32>::new(-0.5, 0.5, 0.0),
+            cgmath::Point3::<f32>::new(0.5, 0.5, 0.0),
+        ])
+        .closed(true)
+        .build(&display)
+        .expect("Failed to build polyline shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the polyline shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(
+                &polyline,
+                &polyline,
+                &data.program,
+                &uniforms,
+                &data.draw_params,
+            )
+            .expect("Failed to draw polyline shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
@@ -0,0 +1,35 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+use glium_shapes::options::WithTransform;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // KEY POINT: Use a PrismBuilder to build a new prism.
+    // Use the methods on the builder object to customise the resultant
+    // shape. In this case we will create a hexagonal prism of unit height
+    // and diameter, scaled to a size of 10.
+    let prism = glium_shapes::prism::PrismBuilder::new()
+        .scale(10.0, 10.0, 10.0)
+        .rotate_x(0.3)
+        .build(&display)
+        .expect("Failed to build prism shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the prism shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(&prism, &prism, &data.program, &uniforms, &data.draw_params)
+            .expect("Failed to draw prism shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
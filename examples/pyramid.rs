@@ -0,0 +1,41 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+use glium_shapes::options::WithTransform;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // KEY POINT: Use a PyramidBuilder to build a new pyramid.
+    // Use the methods on the builder object to customise the resultant
+    // shape. In this case we will create a square-based pyramid of height
+    // 6, turned so that one of its corners faces the camera.
+    let pyramid = glium_shapes::pyramid::PyramidBuilder::new()
+        .scale(6.0, 6.0, 6.0)
+        .rotate_y(0.785)
+        .build(&display)
+        .expect("Failed to build pyramid shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the pyramid shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(
+                &pyramid,
+                &pyramid,
+                &data.program,
+                &uniforms,
+                &data.draw_params,
+            )
+            .expect("Failed to draw pyramid shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
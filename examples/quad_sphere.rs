@@ -0,0 +1,39 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+use glium_shapes::options::WithTransform;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // KEY POINT: Use a QuadSphereBuilder to build a new quad sphere.
+    // The quad sphere is scaled down to fit the view.
+    let quad_sphere = glium_shapes::quad_sphere::QuadSphereBuilder::new()
+        .subdivisions(12)
+        .scale(0.5, 0.5, 0.5)
+        .build(&display)
+        .expect("Failed to build quad sphere shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the quad sphere shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(
+                &quad_sphere,
+                &quad_sphere,
+                &data.program,
+                &uniforms,
+                &data.draw_params,
+            )
+            .expect("Failed to draw quad sphere shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
@@ -0,0 +1,45 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+use glium_shapes::options::WithTransform;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // KEY POINT: Use a RoundedCuboidBuilder to build a new rounded cuboid.
+    // Use the methods on the builder object to customise the resultant
+    // shape. In this case we will create a flattened, bevelled box with a
+    // size of 8.
+    let rounded_cuboid = glium_shapes::rounded_cuboid::RoundedCuboidBuilder::new()
+        .extents(1.0, 0.5, 1.0)
+        .corner_radius(0.15)
+        .corner_segments(6)
+        .scale(8.0, 8.0, 8.0)
+        .rotate_x(0.5)
+        .rotate_y(0.6)
+        .build(&display)
+        .expect("Failed to build rounded cuboid shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the rounded cuboid shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(
+                &rounded_cuboid,
+                &rounded_cuboid,
+                &data.program,
+                &uniforms,
+                &data.draw_params,
+            )
+            .expect("Failed to draw rounded cuboid shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
@@ -0,0 +1,38 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // KEY POINT: Use a RoundedRectBuilder to build a new rounded rectangle.
+    // This builds a wide panel with generously rounded corners.
+    let rounded_rect = glium_shapes::rounded_rect::RoundedRectBuilder::new()
+        .width(1.6)
+        .corner_radius(0.2)
+        .build(&display)
+        .expect("Failed to build rounded rectangle shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the rounded rectangle shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(
+                &rounded_rect,
+                &rounded_rect,
+                &data.program,
+                &uniforms,
+                &data.draw_params,
+            )
+            .expect("Failed to draw rounded rectangle shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
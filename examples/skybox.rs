@@ -0,0 +1,38 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+use glium_shapes::options::WithTransform;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // KEY POINT: Use a SkyboxBuilder to build a new skybox.
+    // Viewed from outside, only the far faces of the skybox are visible.
+    let skybox = glium_shapes::skybox::SkyboxBuilder::new()
+        .scale(1.5, 1.5, 1.5)
+        .build(&display)
+        .expect("Failed to build skybox shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the skybox shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(
+                &skybox,
+                &skybox,
+                &data.program,
+                &uniforms,
+                &data.draw_params,
+            )
+            .expect("Failed to draw skybox shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
@@ -0,0 +1,39 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+use glium_shapes::options::WithTransform;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // KEY POINT: Use a StairsBuilder to build a new staircase.
+    // The staircase is turned such that its steps are seen from the side.
+    let stairs = glium_shapes::stairs::StairsBuilder::new()
+        .steps(6)
+        .rotate_y(-0.6)
+        .build(&display)
+        .expect("Failed to build staircase shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the staircase shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(
+                &stairs,
+                &stairs,
+                &data.program,
+                &uniforms,
+                &data.draw_params,
+            )
+            .expect("Failed to draw staircase shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
@@ -0,0 +1,37 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // KEY POINT: Use a StarPrismBuilder to build a new star prism.
+    // This builds a six-pointed star.
+    let star_prism = glium_shapes::star_prism::StarPrismBuilder::new()
+        .points(6)
+        .build(&display)
+        .expect("Failed to build star prism shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the star prism shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(
+                &star_prism,
+                &star_prism,
+                &data.program,
+                &uniforms,
+                &data.draw_params,
+            )
+            .expect("Failed to draw star prism shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
@@ -0,0 +1,39 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // KEY POINT: Use a SuperellipsoidBuilder to build a new superellipsoid.
+    // Small exponents produce a rounded cube, which is finely segmented such
+    // that its rounded edges appear smooth.
+    let superellipsoid = glium_shapes::superellipsoid::SuperellipsoidBuilder::new()
+        .exponents(0.25, 0.25)
+        .segments(64, 32)
+        .build(&display)
+        .expect("Failed to build superellipsoid shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the superellipsoid shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(
+                &superellipsoid,
+                &superellipsoid,
+                &data.program,
+                &uniforms,
+                &data.draw_params,
+            )
+            .expect("Failed to draw superellipsoid shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
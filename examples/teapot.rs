@@ -0,0 +1,38 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // KEY POINT: Use a TeapotBuilder to build a new teapot.
+    // The teapot is tessellated more finely than the default, such that it
+    // appears smooth up close.
+    let teapot = glium_shapes::teapot::TeapotBuilder::new()
+        .tessellation(12)
+        .build(&display)
+        .expect("Failed to build teapot shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the teapot shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(
+                &teapot,
+                &teapot,
+                &data.program,
+                &uniforms,
+                &data.draw_params,
+            )
+            .expect("Failed to draw teapot shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
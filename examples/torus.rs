@@ -0,0 +1,38 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+use glium_shapes::options::WithTransform;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // KEY POINT: Use a TorusBuilder to build a new torus.
+    // Use the methods on the builder object to customise the resultant
+    // shape. In this case we will create a torus with a major radius of 4
+    // and a minor radius of 1, tilted towards the camera.
+    let torus = glium_shapes::torus::TorusBuilder::new()
+        .major_radius(4.0)
+        .minor_radius(1.0)
+        .major_segments(48)
+        .minor_segments(16)
+        .rotate_x(1.0)
+        .build(&display)
+        .expect("Failed to build torus shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the torus shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(&torus, &torus, &data.program, &uniforms, &data.draw_params)
+            .expect("Failed to draw torus shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
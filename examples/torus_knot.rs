@@ -0,0 +1,38 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // KEY POINT: Use a TorusKnotBuilder to build a new torus knot.
+    // This builds a (3, 5) torus knot.
+    let torus_knot = glium_shapes::torus_knot::TorusKnotBuilder::new()
+        .p(3)
+        .q(5)
+        .build(&display)
+        .expect("Failed to build torus knot shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the torus knot shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(
+                &torus_knot,
+                &torus_knot,
+                &data.program,
+                &uniforms,
+                &data.draw_params,
+            )
+            .expect("Failed to draw torus knot shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
@@ -0,0 +1,44 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+use glium_shapes::options::WithTransform;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // KEY POINT: Use a TruncatedPyramidBuilder to build a new truncated pyramid.
+    // Use the methods on the builder object to customise the resultant
+    // shape. In this case we will create a ramp-like block with a size
+    // of 10.
+    let truncated_pyramid = glium_shapes::truncated_pyramid::TruncatedPyramidBuilder::new()
+        .bottom_extents(1.0, 1.0)
+        .top_extents(1.0, 0.25)
+        .height(0.5)
+        .scale(10.0, 10.0, 10.0)
+        .rotate_y(0.6)
+        .build(&display)
+        .expect("Failed to build truncated pyramid shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the truncated pyramid shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(
+                &truncated_pyramid,
+                &truncated_pyramid,
+                &data.program,
+                &uniforms,
+                &data.draw_params,
+            )
+            .expect("Failed to draw truncated pyramid shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
@@ -0,0 +1,41 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // KEY POINT: Use a TubeBuilder to build a new tube.
+    // Use the methods on the builder object to customise the resultant
+    // shape. In this case we will create a thin pipe that is swept along a
+    // zig-zagging path.
+    let tube = glium_shapes::tube::TubeBuilder::new()
+        .path(&[
+            cgmath::Point3::<f32>::new(-4.0, -4.0, 0.0),
+            cgmath::Point3::<f32>::new(-2.0, 4.0, 0.0),
+            cgmath::Point3::<f32>::new(0.0, -4.0, 2.0),
+            cgmath::Point3::<f32>::new(2.0, 4.0, 0.0),
+            cgmath::Point3::<f32>::new(4.0, -4.0, 0.0),
+        ])
+        .radius(0.5)
+        .segments(16)
+        .build(&display)
+        .expect("Failed to build tube shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the tube shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(&tube, &tube, &data.program, &uniforms, &data.draw_params)
+            .expect("Failed to draw tube shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
@@ -0,0 +1,44 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+use glium_shapes::options::WithTransform;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // KEY POINT: Use a VoxelChunkBuilder to build a new voxel chunk.
+    // Here we fill the voxels of an 8x8x8 grid that lie within a sphere, and
+    // scale the chunk down to fit the view.
+    let voxel_chunk = glium_shapes::voxel_chunk::VoxelChunkBuilder::new()
+        .dimensions(8, 8, 8)
+        .voxels_fn(|x, y, z| {
+            let d = |i: usize| i as f32 - 3.5;
+            d(x) * d(x) + d(y) * d(y) + d(z) * d(z) < 16.0
+        })
+        .scale(0.15, 0.15, 0.15)
+        .build(&display)
+        .expect("Failed to build voxel chunk shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the voxel chunk shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(
+                &voxel_chunk,
+                &voxel_chunk,
+                &data.program,
+                &uniforms,
+                &data.draw_params,
+            )
+            .expect("Failed to draw voxel chunk shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
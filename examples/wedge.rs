@@ -0,0 +1,32 @@
+extern crate glium;
+extern crate glium_shapes;
+mod common;
+use glium::Surface;
+use glium_shapes::options::WithTransform;
+
+fn main() {
+    // Setup glium display and shared example data (program, uniforms, draw params, etc)
+    let (mut ev, display, data) = common::setup();
+
+    // KEY POINT: Use a WedgeBuilder to build a new wedge.
+    // The wedge is turned such that its slope faces towards the camera.
+    let wedge = glium_shapes::wedge::WedgeBuilder::new()
+        .rotate_y(-std::f32::consts::FRAC_PI_2)
+        .build(&display)
+        .expect("Failed to build wedge shape");
+
+    // Loop until the user closes the display window.
+    while common::process_events(&mut ev) {
+        // Begin a new frame.
+        let (mut frame, uniforms) = common::begin_frame(&display);
+
+        // KEY POINT: Draw the wedge shape by passing it as a source
+        // of both vertices and indices to glium.
+        frame
+            .draw(&wedge, &wedge, &data.program, &uniforms, &data.draw_params)
+            .expect("Failed to draw wedge shape");
+
+        // Finish the frame.
+        common::end_frame(frame);
+    }
+}
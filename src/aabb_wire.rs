@@ -0,0 +1,267 @@
+//! A module for constructing wireframe axis-aligned bounding box shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::errors::ShapeCreationError;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{HasOptions, HasVertices, ShapeOptions, WithColors, WithTransform};
+use crate::vertex::Vertex;
+
+/// An `AabbWire` object, made of lines along the edges of a box.
+///
+/// This object is constructed using an `AabbWireBuilder` object.
+pub struct AabbWire {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows an `AabbWire` object to be passed as a source of vertices.
+impl<'a> From<&'a AabbWire> for glium::vertex::VerticesSource<'a> {
+    fn from(aabb: &'a AabbWire) -> glium::vertex::VerticesSource<'a> {
+        (&aabb.vertices).into()
+    }
+}
+
+/// Allows an `AabbWire` object to be passed as a source of indices.
+impl<'a> From<&'a AabbWire> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a AabbWire) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::LinesList,
+        }
+    }
+}
+
+/// Responsible for building and returning an `AabbWire` object.
+///
+/// The box is constructed as a list of lines along its 12 edges, between its
+/// minimum and maximum corners - the 4 edges along the x-axis, followed by the
+/// 4 edges along the y-axis, followed by the 4 edges along the z-axis. By
+/// default, the corners are at `(-0.5, -0.5, -0.5)` and `(0.5, 0.5, 0.5)`,
+/// such that the box fits the unit cube. This can be overriden using the
+/// transformation methods on this object, which allows oriented boxes to be
+/// drawn from the default corners. The box may be flat (or even a single
+/// point) along any axis.
+///
+/// Vertex normals store the direction of the axis along which each edge lies,
+/// transformed as a direction rather than as a surface normal, such that they
+/// remain valid for flat boxes. Vertex texture coordinates map the u
+/// coordinate from 0 at the minimum end of each edge to 1 at its maximum end,
+/// with the v coordinate always 0.
+pub struct AabbWireBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    options: ShapeOptions,
+    min: Point3<f32>,
+    max: Point3<f32>,
+}
+
+impl Default for AabbWireBuilder {
+    fn default() -> Self {
+        AabbWireBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            options: ShapeOptions::default(),
+            min: Point3::<f32>::new(-0.5, -0.5, -0.5),
+            max: Point3::<f32>::new(0.5, 0.5, 0.5),
+        }
+    }
+}
+
+impl AabbWireBuilder {
+    /// Create a new `AabbWireBuilder` object.
+    pub fn new() -> AabbWireBuilder {
+        Default::default()
+    }
+
+    /// Specify the minimum and maximum corners of the box. The minimum corner
+    /// must not exceed the maximum corner along any axis to build a valid box.
+    pub fn corners(mut self, min: impl Into<Point3<f32>>, max: impl Into<Point3<f32>>) -> Self {
+        self.min = min.into();
+        self.max = max.into();
+        self
+    }
+
+    /// Apply an arbitrary transformation matrix to the shape, in the same way
+    /// as the `scale`, `translate`, and `rotate` functions.
+    pub(crate) fn transform(mut self, matrix: Matrix4<f32>) -> Self {
+        self.matrix = matrix * self.matrix;
+        self
+    }
+
+    /// Build a new `AabbWire` object.
+    pub fn build<F>(self, display: &F) -> Result<AabbWire, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the box vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// an `AabbWire` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.vertices_with::<Selected>()
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        // Ensure the corners produce a valid box.
+        for axis in 0..3 {
+            let (min, max) = (self.min[axis], self.max[axis]);
+            if !min.is_finite() || !max.is_finite() || min > max {
+                return Err(ShapeCreationError::InvalidDimensions);
+            }
+        }
+
+        // Compute the transformation of the positions and directions.
+        let transform = Transformation::<M>::new(self.matrix);
+
+        // Build the vertices, with each edge running along one axis at one of
+        // the four combinations of minimum and maximum along the other axes.
+        let mut vertices = Vec::<Vertex>::with_capacity(self.num_vertices());
+        for axis in 0..3 {
+            let mut direction = Vector3::<f32>::zero();
+            direction[axis] = 1.0;
+            let normal = transform.direction(direction);
+            for corner in 0..4 {
+                let mut start = self.min;
+                let mut end = self.max;
+                for (bit, other) in [(axis + 1) % 3, (axis + 2) % 3].iter().enumerate() {
+                    let value = if corner & (1 << bit) == 0 {
+                        self.min[*other]
+                    } else {
+                        self.max[*other]
+                    };
+                    start[*other] = value;
+                    end[*other] = value;
+                }
+                for &(position, u) in [(start, 0.0), (end, 1.0)].iter() {
+                    vertices.push(Vertex {
+                        position: transform.point(position),
+                        normal,
+                        texcoord: [u, 0.0],
+                    });
+                }
+            }
+        }
+
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    pub fn num_vertices(&self) -> usize {
+        12 * 2
+    }
+}
+
+impl HasOptions for AabbWireBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for AabbWireBuilder {
+    type Shape = AabbWire;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> AabbWire {
+        AabbWire { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for AabbWireBuilder {}
+
+impl WithColors for AabbWireBuilder {}
+
+#[test]
+pub fn ensure_aabb_wire_has_twelve_distinct_edges() {
+    let vertices = AabbWireBuilder::new()
+        .corners(Point3::new(-1.0, 0.0, 2.0), Point3::new(1.0, 3.0, 4.0))
+        .build_vertices()
+        .expect("Failed to build vertices");
+    assert_eq!(vertices.len(), 24);
+    let mut edges = std::collections::HashSet::new();
+    for line in vertices.chunks(2) {
+        let (a, b) = (
+            Vector3::from(line[0].position),
+            Vector3::from(line[1].position),
+        );
+
+        // Each edge lies along its normal, between the corners of the box.
+        assert_ulps_eq!((b - a).normalize(), Vector3::from(line[0].normal));
+        for vertex in line {
+            let [x, y, z] = vertex.position;
+            assert!(x == -1.0 || x == 1.0);
+            assert!(y == 0.0 || y == 3.0);
+            assert!(z == 2.0 || z == 4.0);
+        }
+        edges.insert((
+            line[0].position.map(f32::to_bits),
+            line[1].position.map(f32::to_bits),
+        ));
+    }
+    assert_eq!(edges.len(), 12);
+}
+
+#[test]
+pub fn ensure_flat_aabb_wire_has_finite_normals() {
+    let vertices = AabbWireBuilder::new()
+        .corners(Point3::new(0.0, 1.0, 0.0), Point3::new(1.0, 1.0, 1.0))
+        .scale(1.0, 0.0, 1.0)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        assert_eq!(vertex.position[1], 0.0);
+        assert!(vertex.normal.iter().all(|x| x.is_finite()));
+        assert_ulps_eq!(Vector3::from(vertex.normal).magnitude(), 1.0);
+    }
+}
+
+#[test]
+pub fn ensure_aabb_wire_with_inverted_corners_fails_to_build() {
+    match AabbWireBuilder::new()
+        .corners(Point3::new(0.0, 1.0, 0.0), Point3::new(1.0, 0.0, 1.0))
+        .build_vertices()
+    {
+        Err(ShapeCreationError::InvalidDimensions) => (),
+        _ => panic!("Expected InvalidDimensions"),
+    }
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_aabb_wire() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = AabbWireBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
+
+#[test]
+pub fn ensure_colored_aabb_wire_is_painted() {
+    crate::options::ensure_colors(AabbWireBuilder::new);
+}
@@ -0,0 +1,365 @@
+//! A module for constructing annulus (ring) shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::errors::ShapeCreationError;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{
+    BuildAs, HasOptions, HasVertices, ShapeOptions, WithBackFaces, WithColors, WithLightmap,
+    WithModifiers, WithOrientation, WithShading, WithTangents, WithTexcoords, WithTransform,
+};
+use crate::resolution::{Resolution, WithResolution};
+use crate::shading::Shading;
+use crate::vertex::Vertex;
+
+use std::f32;
+
+/// A polygonal `Annulus` object.
+///
+/// This object is constructed using an `AnnulusBuilder` object.
+pub struct Annulus {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows an `Annulus` object to be passed as a source of vertices.
+impl<'a> From<&'a Annulus> for glium::vertex::VerticesSource<'a> {
+    fn from(annulus: &'a Annulus) -> glium::vertex::VerticesSource<'a> {
+        (&annulus.vertices).into()
+    }
+}
+
+/// Allows an `Annulus` object to be passed as a source of indices.
+impl<'a> From<&'a Annulus> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a Annulus) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::TrianglesList,
+        }
+    }
+}
+
+/// Responsible for building and returning an `Annulus` object.
+///
+/// By default, the annulus is defined as a flat ring in the xy-plane, facing
+/// the positive z-axis, with an inner radius of 0.25 and an outer radius of 0.5,
+/// and with its centre located at the origin. This can be overriden using the
+/// radius and transformation methods on this object.
+///
+/// The resultant geometry is constructed to suit OpenGL defaults - assuming
+/// a right-handed coordinate system, front-facing polygons are defined in
+/// counter-clock-wise order. Vertex normals point in the direction of the ring.
+/// Vertex texture coordinates map the angle around the ring to the u coordinate,
+/// and the distance from the inner edge to the outer edge to the v coordinate.
+pub struct AnnulusBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    inner_radius: f32,
+    outer_radius: f32,
+    segments: usize,
+    options: ShapeOptions,
+}
+
+impl Default for AnnulusBuilder {
+    fn default() -> Self {
+        AnnulusBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            inner_radius: 0.25,
+            outer_radius: 0.5,
+            segments: 24,
+            options: ShapeOptions::default(),
+        }
+    }
+}
+
+impl AnnulusBuilder {
+    /// Create a new `AnnulusBuilder` object.
+    pub fn new() -> AnnulusBuilder {
+        Default::default()
+    }
+
+    /// Specify the inner radius of the ring. By default, this is 0.25. The
+    /// inner radius must be non-negative, and smaller than the outer radius.
+    pub fn inner_radius(mut self, radius: f32) -> Self {
+        self.inner_radius = radius;
+        self
+    }
+
+    /// Specify the outer radius of the ring. By default, this is 0.5.
+    pub fn outer_radius(mut self, radius: f32) -> Self {
+        self.outer_radius = radius;
+        self
+    }
+
+    /// Specify the number of segments around the ring. By default, the builder
+    /// will use 24 segments. At least 3 segments are required to build a valid
+    /// ring.
+    pub fn segments(mut self, segments: usize) -> Self {
+        self.segments = segments;
+        self
+    }
+
+    /// Build a new `Annulus` object.
+    pub fn build<F>(self, display: &F) -> Result<Annulus, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the shape vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// an `Annulus` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        let vertices = self.vertices_with::<Selected>()?;
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.options.check_shading(Self::SHADINGS)?;
+
+        // Ensure the parameters produce valid ring geometry.
+        if self.segments < 3 {
+            return Err(ShapeCreationError::NotEnoughDivisionsInU);
+        }
+
+        if self.inner_radius < 0.0 || self.inner_radius >= self.outer_radius {
+            return Err(ShapeCreationError::InvalidRadii);
+        }
+
+        // Build a lookup table of (cos, sin) pairs around the ring.
+        let angle = 2.0 * f32::consts::PI / self.segments as f32;
+        let ring = (0..=self.segments)
+            .map(|x| {
+                let a = ((x % self.segments) as f32) * angle;
+                Vector2::<f32>::new(a.cos(), a.sin())
+            })
+            .collect::<Vec<Vector2<f32>>>();
+        let radii = [self.inner_radius, self.outer_radius];
+
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<M>::new(self.matrix);
+        let normal = transform.normal(Vector3::<f32>::new(0.0, 0.0, 1.0));
+
+        // Build a quad between the inner and outer edges for each segment.
+        let mut vertices = Vec::<Vertex>::with_capacity(self.num_vertices());
+        let quad = [(0, 0), (0, 1), (1, 1), (0, 0), (1, 1), (1, 0)];
+        for seg in 0..self.segments {
+            for &(du, dv) in quad.iter() {
+                let point = ring[seg + du] * radii[dv];
+                let position = Vector3::<f32>::new(point.x, point.y, 0.0);
+                vertices.push(Vertex {
+                    position: transform.point(position),
+                    normal,
+                    texcoord: [(seg + du) as f32 / self.segments as f32, dv as f32],
+                });
+            }
+        }
+
+        self.options.map_texcoords(&mut vertices);
+        Ok(vertices)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    pub fn num_vertices(&self) -> usize {
+        self.segments * 6 * self.options.copies()
+    }
+}
+
+impl HasOptions for AnnulusBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for AnnulusBuilder {
+    type Shape = Annulus;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> Annulus {
+        Annulus { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for AnnulusBuilder {}
+
+impl BuildAs for AnnulusBuilder {}
+
+impl WithTangents for AnnulusBuilder {}
+
+impl WithLightmap for AnnulusBuilder {}
+
+impl WithOrientation for AnnulusBuilder {}
+
+impl WithBackFaces for AnnulusBuilder {}
+
+impl WithModifiers for AnnulusBuilder {}
+
+impl WithTexcoords for AnnulusBuilder {}
+
+impl WithColors for AnnulusBuilder {}
+
+impl WithShading for AnnulusBuilder {
+    /// By default, the annulus is flat shaded. Both modes are supported, and
+    /// build the same normals, since the annulus is planar.
+    const SHADINGS: &'static [Shading] = &[Shading::Flat, Shading::Smooth];
+}
+
+impl WithResolution for AnnulusBuilder {
+    /// At least 3 segments around the annulus. The annulus is a single ring of
+    /// quads without segments across its width, such that any `along` count
+    /// is accepted.
+    const MIN_RESOLUTION: Resolution = Resolution {
+        around: 3,
+        along: 0,
+    };
+
+    /// Specify the number of segments around the annulus. The `along` count is
+    /// ignored.
+    fn resolution(mut self, resolution: Resolution) -> Self {
+        self.segments = resolution.around as usize;
+        self
+    }
+}
+
+#[test]
+pub fn ensure_annulus_vertices_lie_on_inner_or_outer_edge() {
+    let vertices = AnnulusBuilder::new()
+        .inner_radius(1.0)
+        .outer_radius(2.0)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        let pos = Vector3::<f32>::from(vertex.position);
+        let radius = pos.magnitude();
+        assert!(abs_diff_eq!(pos.z, 0.0));
+        assert!(
+            abs_diff_eq!(radius, 1.0, epsilon = 0.0001)
+                || abs_diff_eq!(radius, 2.0, epsilon = 0.0001)
+        );
+    }
+}
+
+#[test]
+pub fn ensure_default_annulus_has_centroid_at_origin() {
+    let vertices = AnnulusBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let mut sum = Vector3::<f32>::zero();
+    for vertex in &vertices {
+        sum += Vector3::<f32>::from(vertex.position);
+    }
+    assert_ulps_eq!(sum, Vector3::<f32>::zero(), epsilon = 0.0001);
+}
+
+#[test]
+pub fn ensure_default_annulus_has_ccw_triangles() {
+    let vertices = AnnulusBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let eyepos = v0 + Vector3::<f32>::from(chunk[0].normal);
+        let e0 = v1 - v0;
+        let e1 = v2 - v0;
+        let n = e0.cross(e1);
+        assert!(n.dot(v0 - eyepos) <= 0.0);
+        assert!(n.dot(v1 - eyepos) <= 0.0);
+        assert!(n.dot(v2 - eyepos) <= 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_annulus_uvs_map_angle_and_radius() {
+    let vertices = AnnulusBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        let pos = Vector3::<f32>::from(vertex.position);
+        let expected_v = if pos.magnitude() > 0.375 { 1.0 } else { 0.0 };
+        assert_eq!(vertex.texcoord[1], expected_v);
+        let mut angle = pos.y.atan2(pos.x) / (2.0 * f32::consts::PI);
+        if angle < 0.0 || (angle == 0.0 && vertex.texcoord[0] == 1.0) {
+            angle += 1.0;
+        }
+        assert!(abs_diff_eq!(vertex.texcoord[0], angle, epsilon = 0.0001));
+    }
+}
+
+#[test]
+pub fn ensure_annulus_with_invalid_parameters_fails_to_build() {
+    match AnnulusBuilder::new().segments(2).build_vertices() {
+        Err(ShapeCreationError::NotEnoughDivisionsInU) => (),
+        _ => panic!("Expected NotEnoughDivisionsInU"),
+    }
+    match AnnulusBuilder::new().inner_radius(0.5).build_vertices() {
+        Err(ShapeCreationError::InvalidRadii) => (),
+        _ => panic!("Expected InvalidRadii"),
+    }
+}
+
+#[test]
+pub fn ensure_annulus_applies_late_pipeline() {
+    use crate::options::WithTexcoords;
+    use crate::uv_mapping::UvMapping;
+
+    crate::options::ensure_late_pipeline(
+        || {
+            AnnulusBuilder::new()
+                .uv_mapping(UvMapping::Box)
+                .double_sided()
+        },
+        AnnulusBuilder::build_vertices,
+    );
+}
+
+#[test]
+pub fn ensure_annulus_with_invalid_resolution_fails_to_build() {
+    crate::resolution::ensure_min_resolution(
+        AnnulusBuilder::new,
+        AnnulusBuilder::build_vertices,
+        ShapeCreationError::NotEnoughDivisionsInU,
+        None,
+    );
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_annulus() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = AnnulusBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
+
+#[test]
+pub fn ensure_annulus_supports_its_shadings() {
+    crate::options::ensure_shadings(AnnulusBuilder::new, AnnulusBuilder::build_vertices);
+}
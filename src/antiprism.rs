@@ -0,0 +1,379 @@
+//! A module for constructing antiprism shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::errors::ShapeCreationError;
+use crate::facet::push_triangulated_polygon;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{
+    BuildAs, HasOptions, HasVertices, ShapeOptions, WithBackFaces, WithColors, WithLightmap,
+    WithModifiers, WithOrientation, WithShading, WithTangents, WithTexcoords, WithTransform,
+};
+use crate::shading::Shading;
+use crate::vertex::Vertex;
+
+use std::f32;
+
+/// A polygonal `Antiprism` object.
+///
+/// This object is constructed using an `AntiprismBuilder` object.
+pub struct Antiprism {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows an `Antiprism` object to be passed as a source of vertices.
+impl<'a> From<&'a Antiprism> for glium::vertex::VerticesSource<'a> {
+    fn from(antiprism: &'a Antiprism) -> glium::vertex::VerticesSource<'a> {
+        (&antiprism.vertices).into()
+    }
+}
+
+/// Allows an `Antiprism` object to be passed as a source of indices.
+impl<'a> From<&'a Antiprism> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a Antiprism) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::TrianglesList,
+        }
+    }
+}
+
+/// Responsible for building and returning an `Antiprism` object.
+///
+/// The antiprism is constructed as two parallel, regular polygons, rotated
+/// half a step relative to each other, and joined by a band of triangles. By
+/// default, the polygons are squares whose vertices lie on circles of radius
+/// 0.5 about the y-axis, with the bottom polygon at `y = -0.5` (its first
+/// vertex on the positive x-axis) and the top polygon at `y = 0.5`, such that
+/// the antiprism fits the unit cube. This can be overriden using the
+/// transformation methods on this object.
+///
+/// The resultant geometry is constructed to suit OpenGL defaults - assuming
+/// a right-handed coordinate system, front-facing polygons are defined in
+/// counter-clock-wise order. Vertex normals point in the direction of their
+/// respective face (such that the shape appears faceted when lit). Each cap is
+/// a fan of triangles about its centre, with texture coordinates defining a
+/// planar-projection on the cap. Texture coordinates on the band wrap around
+/// the y-axis, mapping the angle about the y-axis (measured counter-clock-wise
+/// from the positive x-axis when looking down) to the u coordinate, and the
+/// height to the v coordinate. As the top polygon is rotated half a step, the
+/// u coordinate of its last vertex extends half a step beyond 1, such that the
+/// band tiles seamlessly with a repeating texture.
+pub struct AntiprismBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    sides: usize,
+    height: f32,
+    options: ShapeOptions,
+}
+
+impl Default for AntiprismBuilder {
+    fn default() -> Self {
+        AntiprismBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            sides: 4,
+            height: 1.0,
+            options: ShapeOptions::default(),
+        }
+    }
+}
+
+impl AntiprismBuilder {
+    /// Create a new `AntiprismBuilder` object.
+    pub fn new() -> AntiprismBuilder {
+        Default::default()
+    }
+
+    /// Specify the number of sides of each polygon of the antiprism. By
+    /// default, the builder will use squares with 4 sides. At least 3 sides
+    /// are required to build a valid antiprism.
+    pub fn sides(mut self, sides: usize) -> Self {
+        self.sides = sides;
+        self
+    }
+
+    /// Specify the distance between the polygons of the antiprism. By default,
+    /// the height is 1. The height must be greater than zero to build a valid
+    /// antiprism.
+    pub fn height(mut self, height: f32) -> Self {
+        self.height = height;
+        self
+    }
+
+    /// Build a new `Antiprism` object.
+    pub fn build<F>(self, display: &F) -> Result<Antiprism, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the shape vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// an `Antiprism` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        let vertices = self.vertices_with::<Selected>()?;
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.options.check_shading(Self::SHADINGS)?;
+
+        // Ensure the parameters produce valid antiprism geometry.
+        if self.sides < 3 {
+            return Err(ShapeCreationError::NotEnoughDivisionsInU);
+        }
+        if self.height <= 0.0 || !self.height.is_finite() {
+            return Err(ShapeCreationError::InvalidDimensions);
+        }
+
+        // Build the polygons, with the top polygon rotated by half a step.
+        let n = self.sides;
+        let y = self.height * 0.5;
+        let angle = 2.0 * f32::consts::PI / n as f32;
+        let ring = |offset: f32, y: f32| {
+            (0..n)
+                .map(|x| {
+                    let a = (x as f32 + offset) * angle;
+                    Vector3::<f32>::new(a.cos() * 0.5, y, -a.sin() * 0.5)
+                })
+                .collect::<Vec<Vector3<f32>>>()
+        };
+        let bottom = ring(0.0, -y);
+        let top = ring(0.5, y);
+
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<M>::new(self.matrix);
+
+        // Emit the band, as a pair of triangles for each side of the bottom
+        // polygon.
+        let mut vertices = Vec::<Vertex>::with_capacity(self.num_vertices());
+        let mut push_triangle = |corners: [(Vector3<f32>, [f32; 2]); 3]| {
+            let normal = (corners[1].0 - corners[0].0).cross(corners[2].0 - corners[0].0);
+            let normal: [f32; 3] = transform.normal(normal);
+            for &(position, texcoord) in corners.iter() {
+                vertices.push(Vertex {
+                    position: transform.point(position),
+                    normal,
+                    texcoord,
+                });
+            }
+        };
+        for side in 0..n {
+            let next = (side + 1) % n;
+            let u0 = side as f32 / n as f32;
+            let u1 = (side as f32 + 0.5) / n as f32;
+            let u2 = (side as f32 + 1.0) / n as f32;
+            let u3 = (side as f32 + 1.5) / n as f32;
+            push_triangle([
+                (bottom[side], [u0, 0.0]),
+                (bottom[next], [u2, 0.0]),
+                (top[side], [u1, 1.0]),
+            ]);
+            push_triangle([
+                (top[side], [u1, 1.0]),
+                (bottom[next], [u2, 0.0]),
+                (top[next], [u3, 1.0]),
+            ]);
+        }
+
+        // Emit the caps, as fans about their centres. The bottom polygon is
+        // reversed such that it is wound counter-clock-wise from below.
+        let fan = (0..n)
+            .map(|side| [0, side + 1, (side + 1) % n + 1])
+            .collect::<Vec<[usize; 3]>>();
+        for (polygon, y) in [(top, y), (bottom.into_iter().rev().collect(), -y)] {
+            let cap = std::iter::once(Vector3::<f32>::new(0.0, y, 0.0))
+                .chain(polygon)
+                .collect::<Vec<Vector3<f32>>>();
+            push_triangulated_polygon(&mut vertices, &transform, &cap, &fan);
+        }
+
+        self.options.map_texcoords(&mut vertices);
+        Ok(vertices)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    pub fn num_vertices(&self) -> usize {
+        self.sides * 4 * 3 * self.options.copies()
+    }
+}
+
+impl HasOptions for AntiprismBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for AntiprismBuilder {
+    type Shape = Antiprism;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> Antiprism {
+        Antiprism { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for AntiprismBuilder {}
+
+impl BuildAs for AntiprismBuilder {}
+
+impl WithTangents for AntiprismBuilder {}
+
+impl WithLightmap for AntiprismBuilder {}
+
+impl WithOrientation for AntiprismBuilder {}
+
+impl WithBackFaces for AntiprismBuilder {}
+
+impl WithModifiers for AntiprismBuilder {}
+
+impl WithTexcoords for AntiprismBuilder {}
+
+impl WithColors for AntiprismBuilder {}
+
+impl WithShading for AntiprismBuilder {
+    /// Only flat shading is supported by the antiprism.
+    const SHADINGS: &'static [Shading] = &[Shading::Flat];
+}
+
+#[test]
+pub fn ensure_antiprism_has_four_triangles_per_side() {
+    for sides in 3..9 {
+        let builder = AntiprismBuilder::new().sides(sides);
+        let vertices = builder.build_vertices().expect("Failed to build vertices");
+        assert_eq!(vertices.len() / 3, sides * 4);
+        assert_eq!(vertices.len(), builder.num_vertices());
+    }
+}
+
+#[test]
+pub fn ensure_default_antiprism_has_unit_dimensions() {
+    let vertices = AntiprismBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        let pos = Vector3::<f32>::from(vertex.position);
+        assert!(pos.y == 0.5 || pos.y == -0.5);
+        let radius = Vector2::<f32>::new(pos.x, pos.z).magnitude();
+        assert!(radius == 0.0 || ulps_eq!(radius, 0.5));
+    }
+
+    // The top square is rotated by 45 degrees relative to the bottom square.
+    let bottom = vertices
+        .iter()
+        .find(|v| v.position[1] < 0.0 && v.position[0] > 0.49)
+        .expect("Missing bottom vertex on the positive x-axis");
+    assert!(abs_diff_eq!(bottom.position[2], 0.0, epsilon = 0.0001));
+    assert!(vertices
+        .iter()
+        .filter(|v| v.position[1] > 0.0)
+        .all(|v| v.position[0].abs() < 0.36 && v.position[2].abs() < 0.36));
+}
+
+#[test]
+pub fn ensure_antiprism_has_ccw_faceted_triangles() {
+    let vertices = AntiprismBuilder::new()
+        .sides(5)
+        .height(2.0)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let n = (v1 - v0).cross(v2 - v0).normalize();
+        for vertex in chunk {
+            assert_ulps_eq!(n, Vector3::<f32>::from(vertex.normal), epsilon = 0.0001);
+        }
+
+        // The faces point away from the centre of the antiprism.
+        assert!(n.dot(v0 + v1 + v2) > 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_antiprism_band_uvs_wrap_around() {
+    let sides = 6;
+    let vertices = AntiprismBuilder::new()
+        .sides(sides)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let band = &vertices[..sides * 2 * 3];
+    for vertex in band {
+        let [x, y, z] = vertex.position;
+        let angle = (-z).atan2(x).rem_euclid(2.0 * f32::consts::PI);
+        let u = vertex.texcoord[0];
+        let turn = 2.0 * f32::consts::PI;
+        let delta = (u * turn - angle + f32::consts::PI).rem_euclid(turn) - f32::consts::PI;
+        assert!(delta.abs() < 0.0001);
+        assert!((0.0..=1.0 + 0.5 / sides as f32 + 0.0001).contains(&u));
+        assert_eq!(vertex.texcoord[1], y + 0.5);
+    }
+    for vertex in &vertices[sides * 2 * 3..] {
+        assert!(vertex.texcoord[0] >= -0.0001 && vertex.texcoord[0] <= 1.0001);
+        assert!(vertex.texcoord[1] >= -0.0001 && vertex.texcoord[1] <= 1.0001);
+    }
+}
+
+#[test]
+pub fn ensure_antiprism_with_invalid_parameters_fails_to_build() {
+    match AntiprismBuilder::new().sides(2).build_vertices() {
+        Err(ShapeCreationError::NotEnoughDivisionsInU) => (),
+        _ => panic!("Expected NotEnoughDivisionsInU"),
+    }
+    match AntiprismBuilder::new().height(0.0).build_vertices() {
+        Err(ShapeCreationError::InvalidDimensions) => (),
+        _ => panic!("Expected InvalidDimensions"),
+    }
+}
+
+#[test]
+pub fn ensure_antiprism_applies_late_pipeline() {
+    use crate::options::WithTexcoords;
+    use crate::uv_mapping::UvMapping;
+
+    crate::options::ensure_late_pipeline(
+        || {
+            AntiprismBuilder::new()
+                .uv_mapping(UvMapping::Box)
+                .double_sided()
+        },
+        AntiprismBuilder::build_vertices,
+    );
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_antiprism() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = AntiprismBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
@@ -0,0 +1,525 @@
+//! A module for constructing circular sector shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::errors::ShapeCreationError;
+use crate::facet::flatten_normals;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{
+    BuildAs, HasOptions, HasVertices, ShapeOptions, WithBackFaces, WithColors, WithLightmap,
+    WithModifiers, WithOrientation, WithShading, WithTangents, WithTexcoords, WithTransform,
+};
+use crate::resolution::{Resolution, WithResolution};
+use crate::shading::Shading;
+use crate::vertex::Vertex;
+
+use std::f32;
+
+/// A polygonal `ArcSector` object.
+///
+/// This object is constructed using an `ArcSectorBuilder` object.
+pub struct ArcSector {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows an `ArcSector` object to be passed as a source of vertices.
+impl<'a> From<&'a ArcSector> for glium::vertex::VerticesSource<'a> {
+    fn from(sector: &'a ArcSector) -> glium::vertex::VerticesSource<'a> {
+        (&sector.vertices).into()
+    }
+}
+
+/// Allows an `ArcSector` object to be passed as a source of indices.
+impl<'a> From<&'a ArcSector> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a ArcSector) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::TrianglesList,
+        }
+    }
+}
+
+/// Responsible for building and returning an `ArcSector` object.
+///
+/// By default, the sector is defined as a flat quarter of a unit-diameter disc
+/// in the xy-plane, facing the positive z-axis, with its centre located at the
+/// origin. The sector sweeps counter-clock-wise from its start angle, measured
+/// from the positive x-axis. A sector that sweeps a full turn closes into a
+/// disc. This can be overriden using the transformation methods on this
+/// object.
+///
+/// The sector may optionally be given a thickness, in which case it is
+/// extruded along the z-axis (centred on the xy-plane), with a wall along its
+/// arc and, unless it sweeps a full turn, a flat wall along each of its radial
+/// edges. The resultant geometry is constructed to suit OpenGL defaults -
+/// assuming a right-handed coordinate system, front-facing polygons are
+/// defined in counter-clock-wise order. The faces of the sector are
+/// triangulated as fans of triangles about its centre. Vertex normals point in
+/// the direction of the faces, except along the arc, where they point away
+/// from the centre (such that the arc appears smooth when lit). Vertex texture
+/// coordinates map the proportion of the sweep to the u coordinate, and the
+/// proportion of the radius to the v coordinate on the faces. The walls map
+/// the proportion of the sweep (or radius) to the u coordinate, and the
+/// proportion of the thickness to the v coordinate.
+pub struct ArcSectorBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    radius: f32,
+    start_angle: f32,
+    sweep_angle: f32,
+    segments: usize,
+    thickness: f32,
+    options: ShapeOptions,
+}
+
+impl Default for ArcSectorBuilder {
+    fn default() -> Self {
+        ArcSectorBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            radius: 0.5,
+            start_angle: 0.0,
+            sweep_angle: f32::consts::FRAC_PI_2,
+            segments: 8,
+            thickness: 0.0,
+            options: ShapeOptions {
+                shading: Shading::Smooth,
+                ..ShapeOptions::default()
+            },
+        }
+    }
+}
+
+impl ArcSectorBuilder {
+    /// Create a new `ArcSectorBuilder` object.
+    pub fn new() -> ArcSectorBuilder {
+        Default::default()
+    }
+
+    /// Specify the radius of the sector. By default, the radius is 0.5. The
+    /// radius must be greater than zero to build a valid sector.
+    pub fn radius(mut self, radius: f32) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    /// Specify the angle at which the sector starts, in radians measured
+    /// counter-clock-wise from the positive x-axis. By default, the start
+    /// angle is 0.
+    pub fn start_angle(mut self, radians: f32) -> Self {
+        self.start_angle = radians;
+        self
+    }
+
+    /// Specify the angle swept by the sector, in radians. By default, the
+    /// sweep angle is a quarter turn. The sweep angle must be greater than
+    /// zero, and no greater than a full turn, to build a valid sector.
+    pub fn sweep_angle(mut self, radians: f32) -> Self {
+        self.sweep_angle = radians;
+        self
+    }
+
+    /// Specify the number of segments along the arc of the sector. By default,
+    /// the builder will use 8 segments. At least 1 segment is required to
+    /// build a valid sector.
+    pub fn segments(mut self, segments: usize) -> Self {
+        self.segments = segments;
+        self
+    }
+
+    /// Specify the thickness of the sector along the z-axis. By default, the
+    /// thickness is 0, such that the sector is flat. The thickness must not
+    /// be negative to build a valid sector.
+    pub fn thickness(mut self, thickness: f32) -> Self {
+        self.thickness = thickness;
+        self
+    }
+
+    /// Build a new `ArcSector` object.
+    pub fn build<F>(self, display: &F) -> Result<ArcSector, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the shape vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// an `ArcSector` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        let vertices = self.vertices_with::<Selected>()?;
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.options.check_shading(Self::SHADINGS)?;
+
+        // Ensure there are enough segments to produce valid sector geometry.
+        if self.segments < 1 {
+            return Err(ShapeCreationError::NotEnoughDivisionsInU);
+        }
+        if self.radius <= 0.0 || !self.radius.is_finite() {
+            return Err(ShapeCreationError::InvalidRadii);
+        }
+        let full = self.is_full_turn();
+        if self.sweep_angle <= 0.0 || !(full || self.sweep_angle < 2.0 * f32::consts::PI) {
+            return Err(ShapeCreationError::InvalidDimensions);
+        }
+        if self.thickness < 0.0 || !self.thickness.is_finite() {
+            return Err(ShapeCreationError::InvalidDimensions);
+        }
+
+        // Build a lookup table of directions along the arc. A full turn reuses
+        // the first direction at the end of the arc, such that the sector
+        // closes seamlessly.
+        let rim = (0..=self.segments)
+            .map(|x| {
+                let x = if full { x % self.segments } else { x };
+                let a = self.start_angle + self.sweep_angle * (x as f32 / self.segments as f32);
+                Vector2::<f32>::new(a.cos(), a.sin())
+            })
+            .collect::<Vec<Vector2<f32>>>();
+
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<M>::new(self.matrix);
+
+        let mut vertices = Vec::<Vertex>::with_capacity(self.num_vertices());
+        let mut push = |position: Vector3<f32>, normal: Vector3<f32>, texcoord: [f32; 2]| {
+            vertices.push(Vertex {
+                position: transform.point(position),
+                normal: transform.normal(normal),
+                texcoord,
+            });
+        };
+        let u = |x: f32| x / self.segments as f32;
+        let half = self.thickness * 0.5;
+        let point = |x: usize, r: f32, z: f32| (rim[x] * (self.radius * r)).extend(z);
+
+        // Build the triangle fan on the front face, which is also the only
+        // face of a flat sector.
+        let front = Vector3::<f32>::unit_z();
+        for seg in 0..self.segments {
+            push(point(seg, 0.0, half), front, [u(seg as f32 + 0.5), 0.0]);
+            push(point(seg, 1.0, half), front, [u(seg as f32), 1.0]);
+            push(point(seg + 1, 1.0, half), front, [u(seg as f32 + 1.0), 1.0]);
+        }
+        if self.thickness == 0.0 {
+            if self.options.shading == Shading::Flat {
+                flatten_normals(&mut vertices);
+            }
+            self.options.map_texcoords(&mut vertices);
+            return Ok(vertices);
+        }
+
+        // Build the triangle fan on the back face, followed by the wall along
+        // the arc.
+        for seg in 0..self.segments {
+            push(point(seg, 0.0, -half), -front, [u(seg as f32 + 0.5), 0.0]);
+            push(
+                point(seg + 1, 1.0, -half),
+                -front,
+                [u(seg as f32 + 1.0), 1.0],
+            );
+            push(point(seg, 1.0, -half), -front, [u(seg as f32), 1.0]);
+        }
+        for seg in 0..self.segments {
+            for &(x, v) in [(0, 0), (1, 0), (1, 1), (0, 0), (1, 1), (0, 1)].iter() {
+                let z = if v == 0 { -half } else { half };
+                push(
+                    point(seg + x, 1.0, z),
+                    rim[seg + x].extend(0.0),
+                    [u((seg + x) as f32), v as f32],
+                );
+            }
+        }
+
+        // Build the radial walls, unless the sector sweeps a full turn.
+        if !full {
+            let start = Vector3::<f32>::new(rim[0].y, -rim[0].x, 0.0);
+            let end = rim[self.segments];
+            let end = Vector3::<f32>::new(-end.y, end.x, 0.0);
+            for &(x, normal, flip) in [(0, start, false), (self.segments, end, true)].iter() {
+                for &(r, v) in [(0, 0), (1, 0), (1, 1), (0, 0), (1, 1), (0, 1)].iter() {
+                    let (r, v) = if flip { (r, 1 - v) } else { (r, v) };
+                    let z = if v == 0 { -half } else { half };
+                    push(point(x, r as f32, z), normal, [r as f32, v as f32]);
+                }
+            }
+        }
+
+        if self.options.shading == Shading::Flat {
+            flatten_normals(&mut vertices);
+        }
+
+        self.options.map_texcoords(&mut vertices);
+        Ok(vertices)
+    }
+
+    /// Returns true if the sector sweeps a full turn, such that it closes into
+    /// a disc (or a cylinder, if it is given a thickness).
+    fn is_full_turn(&self) -> bool {
+        ulps_eq!(self.sweep_angle, 2.0 * f32::consts::PI)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    pub fn num_vertices(&self) -> usize {
+        if self.thickness == 0.0 {
+            self.segments * 3 * self.options.copies()
+        } else {
+            let walls = if self.is_full_turn() { 0 } else { 2 * 6 };
+            ((self.segments * 3 * 2) + (self.segments * 6) + walls) * self.options.copies()
+        }
+    }
+}
+
+impl HasOptions for ArcSectorBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for ArcSectorBuilder {
+    type Shape = ArcSector;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> ArcSector {
+        ArcSector { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for ArcSectorBuilder {}
+
+impl BuildAs for ArcSectorBuilder {}
+
+impl WithTangents for ArcSectorBuilder {}
+
+impl WithLightmap for ArcSectorBuilder {}
+
+impl WithOrientation for ArcSectorBuilder {}
+
+impl WithBackFaces for ArcSectorBuilder {}
+
+impl WithModifiers for ArcSectorBuilder {}
+
+impl WithTexcoords for ArcSectorBuilder {}
+
+impl WithColors for ArcSectorBuilder {}
+
+impl WithShading for ArcSectorBuilder {
+    /// By default, the arc sector is smooth shaded. When flat shaded, each
+    /// vertex normal points in the direction of its respective triangle
+    /// instead.
+    const SHADINGS: &'static [Shading] = &[Shading::Flat, Shading::Smooth];
+}
+
+impl WithResolution for ArcSectorBuilder {
+    /// At least 1 segment along the arc. The sector has no segments across its
+    /// radius, such that any `along` count is accepted.
+    const MIN_RESOLUTION: Resolution = Resolution {
+        around: 1,
+        along: 0,
+    };
+
+    /// Specify the number of segments along the arc of the sector. The `along`
+    /// count is ignored.
+    fn resolution(mut self, resolution: Resolution) -> Self {
+        self.segments = resolution.around as usize;
+        self
+    }
+}
+
+#[test]
+pub fn ensure_default_arc_sector_is_flat_quarter_disc() {
+    let vertices = ArcSectorBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    assert_eq!(vertices.len(), 8 * 3);
+    for chunk in vertices.chunks(3) {
+        assert_eq!(Vector3::<f32>::from(chunk[0].position), Vector3::zero());
+        for vertex in chunk {
+            let pos = Vector3::<f32>::from(vertex.position);
+            assert_eq!(Vector3::<f32>::from(vertex.normal), Vector3::unit_z());
+            assert!(pos.x >= -0.0001 && pos.y >= -0.0001 && pos.z == 0.0);
+        }
+        for vertex in &chunk[1..] {
+            let pos = Vector3::<f32>::from(vertex.position);
+            assert!(abs_diff_eq!(pos.magnitude(), 0.5, epsilon = 0.0001));
+        }
+    }
+}
+
+#[test]
+pub fn ensure_arc_sector_has_polar_uvs() {
+    let vertices = ArcSectorBuilder::new()
+        .start_angle(1.0)
+        .sweep_angle(2.0)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        let pos = Vector2::<f32>::new(vertex.position[0], vertex.position[1]);
+        assert!(abs_diff_eq!(
+            vertex.texcoord[1],
+            pos.magnitude() / 0.5,
+            epsilon = 0.0001
+        ));
+        if vertex.texcoord[1] > 0.0 {
+            let angle = pos.y.atan2(pos.x);
+            assert!(abs_diff_eq!(
+                vertex.texcoord[0],
+                (angle - 1.0) / 2.0,
+                epsilon = 0.0001
+            ));
+        }
+    }
+}
+
+#[test]
+pub fn ensure_arc_sector_has_ccw_triangles() {
+    for &(sweep, thickness) in [(1.0, 0.0), (4.0, 0.2), (2.0 * f32::consts::PI, 0.2)].iter() {
+        let vertices = ArcSectorBuilder::new()
+            .sweep_angle(sweep)
+            .thickness(thickness)
+            .build_vertices()
+            .expect("Failed to build vertices");
+        for chunk in vertices.chunks(3) {
+            let v0 = Vector3::<f32>::from(chunk[0].position);
+            let v1 = Vector3::<f32>::from(chunk[1].position);
+            let v2 = Vector3::<f32>::from(chunk[2].position);
+            let eyepos = v0 + Vector3::<f32>::from(chunk[0].normal);
+            let e0 = v1 - v0;
+            let e1 = v2 - v0;
+            let n = e0.cross(e1);
+            assert!(n.dot(v0 - eyepos) <= 0.0);
+            assert!(n.dot(v1 - eyepos) <= 0.0);
+            assert!(n.dot(v2 - eyepos) <= 0.0);
+        }
+    }
+}
+
+#[test]
+pub fn ensure_thick_arc_sector_has_outward_facing_normals() {
+    let (start, sweep) = (0.5, 4.0);
+    let vertices = ArcSectorBuilder::new()
+        .start_angle(start)
+        .sweep_angle(sweep)
+        .thickness(0.2)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        // Stepping a short distance along the normal leaves the solid.
+        let outside =
+            Vector3::<f32>::from(vertex.position) + Vector3::<f32>::from(vertex.normal) * 0.01;
+        let mut angle = outside.y.atan2(outside.x) - start;
+        while angle < 0.0 {
+            angle += 2.0 * f32::consts::PI;
+        }
+        let inside = outside.z.abs() < 0.1 && outside.truncate().magnitude() < 0.5 && angle < sweep;
+        assert!(!inside);
+    }
+}
+
+#[test]
+pub fn ensure_arc_sectors_are_watertight() {
+    use std::collections::HashMap;
+    for &sweep in [2.5, 2.0 * f32::consts::PI].iter() {
+        let vertices = ArcSectorBuilder::new()
+            .start_angle(0.3)
+            .sweep_angle(sweep)
+            .segments(5)
+            .thickness(0.25)
+            .build_vertices()
+            .expect("Failed to build vertices");
+        let mut edges = HashMap::<([u32; 3], [u32; 3]), usize>::new();
+        for chunk in vertices.chunks(3) {
+            for k in 0..3 {
+                let a = chunk[k].position.map(f32::to_bits);
+                let b = chunk[(k + 1) % 3].position.map(f32::to_bits);
+                *edges
+                    .entry(if a < b { (a, b) } else { (b, a) })
+                    .or_insert(0) += 1;
+            }
+        }
+        assert!(edges.values().all(|&count| count == 2));
+    }
+}
+
+#[test]
+pub fn ensure_arc_sector_with_invalid_parameters_fails_to_build() {
+    match ArcSectorBuilder::new().segments(0).build_vertices() {
+        Err(ShapeCreationError::NotEnoughDivisionsInU) => (),
+        _ => panic!("Expected NotEnoughDivisionsInU"),
+    }
+    match ArcSectorBuilder::new().radius(0.0).build_vertices() {
+        Err(ShapeCreationError::InvalidRadii) => (),
+        _ => panic!("Expected InvalidRadii"),
+    }
+    for &(sweep, thickness) in [(0.0, 0.0), (-1.0, 0.0), (7.0, 0.0), (1.0, -0.1)].iter() {
+        match ArcSectorBuilder::new()
+            .sweep_angle(sweep)
+            .thickness(thickness)
+            .build_vertices()
+        {
+            Err(ShapeCreationError::InvalidDimensions) => (),
+            _ => panic!("Expected InvalidDimensions"),
+        }
+    }
+}
+
+#[test]
+pub fn ensure_arc_sector_applies_late_pipeline() {
+    use crate::options::WithTexcoords;
+    use crate::uv_mapping::UvMapping;
+
+    crate::options::ensure_late_pipeline(
+        || {
+            ArcSectorBuilder::new()
+                .uv_mapping(UvMapping::Box)
+                .double_sided()
+        },
+        ArcSectorBuilder::build_vertices,
+    );
+}
+
+#[test]
+pub fn ensure_arc_sector_with_invalid_resolution_fails_to_build() {
+    crate::resolution::ensure_min_resolution(
+        ArcSectorBuilder::new,
+        ArcSectorBuilder::build_vertices,
+        ShapeCreationError::NotEnoughDivisionsInU,
+        None,
+    );
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_arc_sector() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = ArcSectorBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
@@ -0,0 +1,530 @@
+//! A module for constructing arrow shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::errors::ShapeCreationError;
+use crate::facet::flatten_normals;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{
+    BuildAs, HasOptions, HasVertices, ShapeOptions, WithBackFaces, WithColors, WithLightmap,
+    WithModifiers, WithOrientation, WithShading, WithTangents, WithTexcoords, WithTransform,
+};
+use crate::resolution::{Resolution, WithResolution};
+use crate::shading::Shading;
+use crate::vertex::Vertex;
+
+use std::f32;
+
+/// A polygonal `Arrow` object.
+///
+/// This object is constructed using an `ArrowBuilder` object.
+pub struct Arrow {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows an `Arrow` object to be passed as a source of vertices.
+impl<'a> From<&'a Arrow> for glium::vertex::VerticesSource<'a> {
+    fn from(arrow: &'a Arrow) -> glium::vertex::VerticesSource<'a> {
+        (&arrow.vertices).into()
+    }
+}
+
+/// Allows an `Arrow` object to be passed as a source of indices.
+impl<'a> From<&'a Arrow> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a Arrow) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::TrianglesList,
+        }
+    }
+}
+
+/// Responsible for building and returning an `Arrow` object.
+///
+/// By default, the arrow is defined as a cylindrical shaft of radius 0.05
+/// topped with a conical head of radius 0.1 and length 0.25, pointing along the
+/// positive y-axis. The arrow has unit length, with the centre of its base
+/// located at the origin and the tip of its head located at `(0, 1, 0)`, such
+/// that scaling the arrow along the y-axis sets its length. This can be
+/// overriden using the transformation methods on this object.
+///
+/// The shaft, the underside of the head, and the head itself share their
+/// vertex positions where they meet, such that the arrow is watertight.
+///
+/// The resultant geometry is constructed to suit OpenGL defaults - assuming
+/// a right-handed coordinate system, front-facing polygons are defined in
+/// counter-clock-wise order. Vertex normals on the shaft and head are
+/// perpendicular to their surfaces (such that they appear smooth when lit),
+/// whereas the normals of the base and the underside of the head point down
+/// the axis. Vertex texture coordinates define a cylindrical-projection on the
+/// shaft and head, with the v coordinate equal to the distance along the arrow,
+/// and a planar-projection on the base and the underside of the head.
+pub struct ArrowBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    shaft_radius: f32,
+    head_radius: f32,
+    head_length: f32,
+    segments: usize,
+    options: ShapeOptions,
+}
+
+impl Default for ArrowBuilder {
+    fn default() -> Self {
+        ArrowBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            shaft_radius: 0.05,
+            head_radius: 0.1,
+            head_length: 0.25,
+            segments: 24,
+            options: ShapeOptions {
+                shading: Shading::Smooth,
+                ..ShapeOptions::default()
+            },
+        }
+    }
+}
+
+impl ArrowBuilder {
+    /// Create a new `ArrowBuilder` object.
+    pub fn new() -> ArrowBuilder {
+        Default::default()
+    }
+
+    /// Specify the radius of the shaft of the arrow. By default, the shaft
+    /// radius is 0.05. The shaft radius must be greater than zero, and smaller
+    /// than the head radius.
+    pub fn shaft_radius(mut self, radius: f32) -> Self {
+        self.shaft_radius = radius;
+        self
+    }
+
+    /// Specify the radius of the base of the head of the arrow. By default,
+    /// the head radius is 0.1. The head radius must be greater than the shaft
+    /// radius.
+    pub fn head_radius(mut self, radius: f32) -> Self {
+        self.head_radius = radius;
+        self
+    }
+
+    /// Specify the length of the head of the arrow, as a proportion of the
+    /// total length of the arrow. By default, the head length is 0.25. The
+    /// head length must lie between 0 and 1 (exclusive).
+    pub fn head_length(mut self, length: f32) -> Self {
+        self.head_length = length;
+        self
+    }
+
+    /// Specify the number of radial segments around the arrow. By default,
+    /// the builder will use 24 segments. At least 3 segments are required to
+    /// build a valid arrow.
+    pub fn segments(mut self, segments: usize) -> Self {
+        self.segments = segments;
+        self
+    }
+
+    /// Apply a transformation to the shape such that the arrow starts at the
+    /// `from` point, and its tip lies at the `to` point.
+    ///
+    /// Only the length of the arrow is scaled, such that the radii of the
+    /// shaft and head are unchanged. The transformation accumulates with the
+    /// `scale`, `translate`, and `rotate` functions, but is typically intended
+    /// to be applied last (e.g. to draw a debug vector with a fixed thickness).
+    pub fn pointing(mut self, from: impl Into<Point3<f32>>, to: impl Into<Point3<f32>>) -> Self {
+        let (from, to) = (from.into(), to.into());
+        let direction = to - from;
+        let length = direction.magnitude();
+        let rotation = if length > 0.0 {
+            Quaternion::<f32>::from_arc(Vector3::<f32>::unit_y(), direction / length, None)
+        } else {
+            Quaternion::<f32>::one()
+        };
+        self.matrix = cgmath::Matrix4::<f32>::from_translation(from.to_vec())
+            * cgmath::Matrix4::<f32>::from(rotation)
+            * cgmath::Matrix4::<f32>::from_nonuniform_scale(1.0, length, 1.0)
+            * self.matrix;
+        self
+    }
+
+    /// Build a new `Arrow` object.
+    pub fn build<F>(self, display: &F) -> Result<Arrow, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the shape vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// an `Arrow` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        let vertices = self.vertices_with::<Selected>()?;
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.options.check_shading(Self::SHADINGS)?;
+
+        // Ensure there are enough segments to produce valid arrow geometry.
+        if self.segments < 3 {
+            return Err(ShapeCreationError::NotEnoughDivisionsInU);
+        }
+
+        // Ensure the radii and head length describe a valid arrow.
+        let (rs, rh) = (self.shaft_radius, self.head_radius);
+        if rs <= 0.0 || rh <= rs {
+            return Err(ShapeCreationError::InvalidRadii);
+        }
+        if self.head_length <= 0.0 || self.head_length >= 1.0 {
+            return Err(ShapeCreationError::InvalidDimensions);
+        }
+        let neck = 1.0 - self.head_length;
+
+        // Build a lookup table of (cos, sin) pairs around the arrow.
+        let angle = 2.0 * f32::consts::PI / self.segments as f32;
+        let ring = (0..=self.segments)
+            .map(|x| {
+                let a = ((x % self.segments) as f32) * angle;
+                [a.cos(), a.sin()]
+            })
+            .collect::<Vec<[f32; 2]>>();
+        let rim = |index: usize, y: f32, r: f32| {
+            let [c, s] = ring[index];
+            Vector3::<f32>::new(c * r, y, -s * r)
+        };
+        let u = |index: usize| index as f32 / self.segments as f32;
+
+        // The head normal for a given (cos, sin) pair. The normal rises by the
+        // ratio of the head radius to the head length for every unit it
+        // extends outward.
+        let head_normal = |[c, s]: [f32; 2]| Vector3::<f32>::new(c, rh / self.head_length, -s);
+        let down = Vector3::<f32>::new(0.0, -1.0, 0.0);
+
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<M>::new(self.matrix);
+
+        let mut vertices = Vec::<Vertex>::with_capacity(self.num_vertices());
+        let mut emit = |position: Vector3<f32>, normal: Vector3<f32>, texcoord: [f32; 2]| {
+            vertices.push(Vertex {
+                position: transform.point(position),
+                normal: transform.normal(normal),
+                texcoord,
+            });
+        };
+
+        // Emit each segment of the arrow, from the base up to the tip. Angles
+        // increase counter-clock-wise when viewed from above, such that the u
+        // coordinate increases from left to right when viewed from outside.
+        for seg in 0..self.segments {
+            let next = seg + 1;
+
+            // Emit the base as a triangle fan, wound counter-clock-wise when
+            // viewed from below.
+            emit(Vector3::<f32>::zero(), down, [0.5, 0.5]);
+            for &index in [next, seg].iter() {
+                let pos = rim(index, 0.0, rs);
+                emit(
+                    pos,
+                    down,
+                    [pos.x / (rs * 2.0) + 0.5, pos.z / (rs * 2.0) + 0.5],
+                );
+            }
+
+            // Emit the shaft.
+            let corners = [
+                (seg, 0.0),
+                (next, 0.0),
+                (next, neck),
+                (seg, 0.0),
+                (next, neck),
+                (seg, neck),
+            ];
+            for &(index, y) in corners.iter() {
+                let [c, s] = ring[index];
+                emit(
+                    rim(index, y, rs),
+                    Vector3::<f32>::new(c, 0.0, -s),
+                    [u(index), y],
+                );
+            }
+
+            // Emit the underside of the head, wound counter-clock-wise when
+            // viewed from below.
+            let corners = [
+                (seg, rs),
+                (next, rs),
+                (next, rh),
+                (seg, rs),
+                (next, rh),
+                (seg, rh),
+            ];
+            for &(index, r) in corners.iter() {
+                let pos = rim(index, neck, r);
+                emit(
+                    pos,
+                    down,
+                    [pos.x / (rh * 2.0) + 0.5, pos.z / (rh * 2.0) + 0.5],
+                );
+            }
+
+            // Emit the head, with the tip taking the normal and u coordinate of
+            // the middle of the segment.
+            let mid = (seg as f32 + 0.5) * angle;
+            emit(rim(seg, neck, rh), head_normal(ring[seg]), [u(seg), neck]);
+            emit(
+                rim(next, neck, rh),
+                head_normal(ring[next]),
+                [u(next), neck],
+            );
+            emit(
+                Vector3::<f32>::new(0.0, 1.0, 0.0),
+                head_normal([mid.cos(), mid.sin()]),
+                [(u(seg) + u(next)) * 0.5, 1.0],
+            );
+        }
+
+        if self.options.shading == Shading::Flat {
+            flatten_normals(&mut vertices);
+        }
+
+        self.options.map_texcoords(&mut vertices);
+        Ok(vertices)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    pub fn num_vertices(&self) -> usize {
+        ((self.segments * 3) + (self.segments * 6) + (self.segments * 6) + (self.segments * 3))
+            * self.options.copies()
+    }
+}
+
+impl HasOptions for ArrowBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for ArrowBuilder {
+    type Shape = Arrow;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> Arrow {
+        Arrow { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for ArrowBuilder {}
+
+impl BuildAs for ArrowBuilder {}
+
+impl WithTangents for ArrowBuilder {}
+
+impl WithLightmap for ArrowBuilder {}
+
+impl WithOrientation for ArrowBuilder {}
+
+impl WithBackFaces for ArrowBuilder {}
+
+impl WithModifiers for ArrowBuilder {}
+
+impl WithTexcoords for ArrowBuilder {}
+
+impl WithColors for ArrowBuilder {}
+
+impl WithShading for ArrowBuilder {
+    /// By default, the arrow is smooth shaded. When flat shaded, each vertex
+    /// normal points in the direction of its respective triangle instead.
+    const SHADINGS: &'static [Shading] = &[Shading::Flat, Shading::Smooth];
+}
+
+impl WithResolution for ArrowBuilder {
+    /// At least 3 segments around the arrow. The shaft and head of the arrow
+    /// have no segments along their length, such that any `along` count is
+    /// accepted.
+    const MIN_RESOLUTION: Resolution = Resolution {
+        around: 3,
+        along: 0,
+    };
+
+    /// Specify the number of segments around the arrow. The `along` count is
+    /// ignored.
+    fn resolution(mut self, resolution: Resolution) -> Self {
+        self.segments = resolution.around as usize;
+        self
+    }
+}
+
+#[test]
+pub fn ensure_default_arrow_has_unit_length() {
+    let vertices = ArrowBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let mut min = f32::MAX;
+    let mut max = f32::MIN;
+    for vertex in &vertices {
+        let pos = Vector3::<f32>::from(vertex.position);
+        min = min.min(pos.y);
+        max = max.max(pos.y);
+        let radius = Vector2::<f32>::new(pos.x, pos.z).magnitude();
+        assert!(radius <= 0.1 + 0.0001);
+    }
+    assert_eq!(min, 0.0);
+    assert_eq!(max, 1.0);
+}
+
+#[test]
+pub fn ensure_default_arrow_is_watertight() {
+    use std::collections::HashMap;
+    let vertices = ArrowBuilder::new()
+        .segments(7)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let key = |vertex: &Vertex| vertex.position.map(f32::to_bits);
+    let mut edges = HashMap::new();
+    for chunk in vertices.chunks(3) {
+        for index in 0..3 {
+            let (a, b) = (key(&chunk[index]), key(&chunk[(index + 1) % 3]));
+            *edges
+                .entry(if a < b { (a, b) } else { (b, a) })
+                .or_insert(0) += 1;
+        }
+    }
+    for count in edges.values() {
+        assert_eq!(*count, 2);
+    }
+}
+
+#[test]
+pub fn ensure_default_arrow_has_ccw_triangles() {
+    let vertices = ArrowBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let eyepos = v0 + Vector3::<f32>::from(chunk[0].normal);
+        let e0 = v1 - v0;
+        let e1 = v2 - v0;
+        let n = e0.cross(e1);
+        assert!(n.dot(v0 - eyepos) <= 0.0);
+        assert!(n.dot(v1 - eyepos) <= 0.0);
+        assert!(n.dot(v2 - eyepos) <= 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_arrow_pointing_spans_both_points() {
+    let from = Point3::<f32>::new(1.0, 2.0, 3.0);
+    let to = Point3::<f32>::new(-2.0, 4.0, 1.0);
+    let vertices = ArrowBuilder::new()
+        .pointing(from, to)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let axis = (to - from).normalize();
+    for vertex in &vertices {
+        let pos = Point3::<f32>::from(vertex.position);
+        let along = (pos - from).dot(axis);
+        assert!(along >= -0.0001 && along <= (to - from).magnitude() + 0.0001);
+        assert!(((pos - from) - axis * along).magnitude() <= 0.1 + 0.0001);
+    }
+    assert_ulps_eq!(
+        Point3::<f32>::from(vertices[0].position),
+        from,
+        epsilon = 0.0001
+    );
+    assert_ulps_eq!(
+        Point3::<f32>::from(vertices[17].position),
+        to,
+        epsilon = 0.0001
+    );
+}
+
+#[test]
+pub fn ensure_arrow_pointing_down_is_flipped() {
+    let vertices = ArrowBuilder::new()
+        .pointing(Point3::<f32>::origin(), Point3::<f32>::new(0.0, -2.0, 0.0))
+        .build_vertices()
+        .expect("Failed to build vertices");
+    assert_ulps_eq!(
+        Vector3::<f32>::from(vertices[17].position),
+        Vector3::<f32>::new(0.0, -2.0, 0.0),
+        epsilon = 0.0001
+    );
+}
+
+#[test]
+pub fn ensure_arrow_with_invalid_parameters_fails_to_build() {
+    match ArrowBuilder::new().segments(2).build_vertices() {
+        Err(ShapeCreationError::NotEnoughDivisionsInU) => (),
+        _ => panic!("Expected NotEnoughDivisionsInU"),
+    }
+    match ArrowBuilder::new().shaft_radius(0.2).build_vertices() {
+        Err(ShapeCreationError::InvalidRadii) => (),
+        _ => panic!("Expected InvalidRadii"),
+    }
+    match ArrowBuilder::new().head_length(1.0).build_vertices() {
+        Err(ShapeCreationError::InvalidDimensions) => (),
+        _ => panic!("Expected InvalidDimensions"),
+    }
+}
+
+#[test]
+pub fn ensure_arrow_applies_late_pipeline() {
+    use crate::options::WithTexcoords;
+    use crate::uv_mapping::UvMapping;
+
+    crate::options::ensure_late_pipeline(
+        || {
+            ArrowBuilder::new()
+                .uv_mapping(UvMapping::Box)
+                .double_sided()
+        },
+        ArrowBuilder::build_vertices,
+    );
+}
+
+#[test]
+pub fn ensure_arrow_with_invalid_resolution_fails_to_build() {
+    crate::resolution::ensure_min_resolution(
+        ArrowBuilder::new,
+        ArrowBuilder::build_vertices,
+        ShapeCreationError::NotEnoughDivisionsInU,
+        None,
+    );
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_arrow() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = ArrowBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
@@ -4,14 +4,45 @@ extern crate cgmath;
 extern crate glium;
 
 use self::cgmath::*;
+use crate::arrow::ArrowBuilder;
 use crate::errors::ShapeCreationError;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{HasOptions, HasVertices, ShapeOptions, WithColors, WithTransform};
 use crate::vertex::Vertex;
 
-/// A set of orthogonal `Axes` lines.
+use std::f32;
+use std::ops::Range;
+
+/// A set of orthogonal `Axes` lines, or arrows.
 ///
 /// This object is constructed using a `AxesBuilder` object.
 pub struct Axes {
     vertices: glium::vertex::VertexBufferAny,
+    primitives: glium::index::PrimitiveType,
+}
+
+impl Axes {
+    /// Returns the range of vertices that define the x-axis.
+    pub fn x_range(&self) -> Range<usize> {
+        self.axis_range(0)
+    }
+
+    /// Returns the range of vertices that define the y-axis.
+    pub fn y_range(&self) -> Range<usize> {
+        self.axis_range(1)
+    }
+
+    /// Returns the range of vertices that define the z-axis.
+    pub fn z_range(&self) -> Range<usize> {
+        self.axis_range(2)
+    }
+
+    /// Returns the range of vertices that define the given axis, as each axis
+    /// occupies an equal, contiguous range of vertices.
+    fn axis_range(&self, axis: usize) -> Range<usize> {
+        let count = self.vertices.len() / 3;
+        (axis * count)..((axis + 1) * count)
+    }
 }
 
 /// Allows an `Axes` object to be passed as a source of vertices.
@@ -22,10 +53,10 @@ impl<'a> From<&'a Axes> for glium::vertex::VerticesSource<'a> {
 }
 
 /// Allows an `Axes` object to be passed as a source of indices.
-impl<'a> Into<glium::index::IndicesSource<'a>> for &'a Axes {
-    fn into(self) -> glium::index::IndicesSource<'a> {
+impl<'a> From<&'a Axes> for glium::index::IndicesSource<'a> {
+    fn from(axes: &'a Axes) -> glium::index::IndicesSource<'a> {
         glium::index::IndicesSource::NoIndices {
-            primitives: glium::index::PrimitiveType::LinesList,
+            primitives: axes.primitives,
         }
     }
 }
@@ -34,21 +65,38 @@ impl<'a> Into<glium::index::IndicesSource<'a>> for &'a Axes {
 ///
 /// By default, each orthogonal axis line is 1 unit in length, with the
 /// centre point located at the origin. This can be overriden using the
-/// transformation methods on this object.
+/// size and transformation methods on this object.
 ///
 /// The resultant geometry is constructed to suit OpenGL defaults - assuming
 /// a right-handed coordinate system. Vertex normals define the normalised
 /// direction of their respective axis line. Vertex texture coordinates encode
 /// end point in the U coordinate (a value of 0 or 1), and the axis number in
 /// the V coordinate (a value of 0, 1, or 2).
+///
+/// Alternatively, each axis may be built as a solid arrow (as built by an
+/// `ArrowBuilder`), in which case the axes are emitted as a triangle list.
+/// Front-facing polygons are defined in counter-clock-wise order, and vertex
+/// normals are those of the arrow surface. Vertex texture coordinates encode
+/// the proportion of the distance along the axis in the U coordinate, and the
+/// axis number in the V coordinate.
+///
+/// In either case, the x, y and z axes occupy consecutive, equally sized
+/// ranges of vertices, which are also returned by the `x_range`, `y_range`
+/// and `z_range` methods of the resultant `Axes` object.
 pub struct AxesBuilder {
     matrix: cgmath::Matrix4<f32>,
+    options: ShapeOptions,
+    size: f32,
+    arrows: bool,
 }
 
 impl Default for AxesBuilder {
     fn default() -> AxesBuilder {
         AxesBuilder {
             matrix: cgmath::Matrix4::<f32>::identity(),
+            options: ShapeOptions::default(),
+            size: 1.0,
+            arrows: false,
         }
     }
 }
@@ -59,74 +107,17 @@ impl AxesBuilder {
         Default::default()
     }
 
-    /// Apply a scaling transformation to the shape.
-    ///
-    /// The `scale`, `translate`, and `rotate` functions accumulate, and are
-    /// not commutative. The transformation functions are intended to provide
-    /// flexibility in model-space. For per-instance world-space transformations,
-    /// one should prefer to share as few shapes as possible across multiple
-    /// instances, and instead rely on uniform constants in the shader and/or
-    /// instanced drawing.
-    pub fn scale(mut self, x: f32, y: f32, z: f32) -> Self {
-        self.matrix = cgmath::Matrix4::from_nonuniform_scale(x, y, z) * self.matrix;
-        self
-    }
-
-    /// Apply a translation transformation to the shape.
-    ///
-    /// The `scale`, `translate`, and `rotate` functions accumulate, and are
-    /// not commutative. The transformation functions are intended to provide
-    /// flexibility in model-space. For per-instance world-space transformations,
-    /// one should prefer to share as few shapes as possible across multiple
-    /// instances, and instead rely on uniform constants in the shader and/or
-    /// instanced drawing.
-    pub fn translate(mut self, x: f32, y: f32, z: f32) -> Self {
-        self.matrix = cgmath::Matrix4::from_translation([x, y, z].into()) * self.matrix;
-        self
-    }
-
-    /// Apply a rotation transformation to the shape about the x-axis.
-    ///
-    /// The `scale`, `translate`, and `rotate` functions accumulate, and are
-    /// not commutative. The transformation functions are intended to provide
-    /// flexibility in model-space. For per-instance world-space transformations,
-    /// one should prefer to share as few shapes as possible across multiple
-    /// instances, and instead rely on uniform constants in the shader and/or
-    /// instanced drawing.
-    pub fn rotate_x(mut self, radians: f32) -> Self {
-        self.matrix = cgmath::Matrix4::<f32>::from(cgmath::Matrix3::<f32>::from_angle_x(
-            cgmath::Rad::<f32>(radians),
-        )) * self.matrix;
-        self
-    }
-
-    /// Apply a rotation transformation to the shape about the y-axis.
-    ///
-    /// The `scale`, `translate`, and `rotate` functions accumulate, and are
-    /// not commutative. The transformation functions are intended to provide
-    /// flexibility in model-space. For per-instance world-space transformations,
-    /// one should prefer to share as few shapes as possible across multiple
-    /// instances, and instead rely on uniform constants in the shader and/or
-    /// instanced drawing.
-    pub fn rotate_y(mut self, radians: f32) -> Self {
-        self.matrix = cgmath::Matrix4::<f32>::from(cgmath::Matrix3::<f32>::from_angle_y(
-            cgmath::Rad::<f32>(radians),
-        )) * self.matrix;
+    /// Specify the length of each axis. By default, each axis is 1 unit in
+    /// length.
+    pub fn size(mut self, size: f32) -> Self {
+        self.size = size;
         self
     }
 
-    /// Apply a rotation transformation to the shape about the z-axis.
-    ///
-    /// The `scale`, `translate`, and `rotate` functions accumulate, and are
-    /// not commutative. The transformation functions are intended to provide
-    /// flexibility in model-space. For per-instance world-space transformations,
-    /// one should prefer to share as few shapes as possible across multiple
-    /// instances, and instead rely on uniform constants in the shader and/or
-    /// instanced drawing.
-    pub fn rotate_z(mut self, radians: f32) -> Self {
-        self.matrix = cgmath::Matrix4::<f32>::from(cgmath::Matrix3::<f32>::from_angle_z(
-            cgmath::Rad::<f32>(radians),
-        )) * self.matrix;
+    /// Specify whether each axis is built as a solid arrow rather than a line.
+    /// By default, the axes are built as lines.
+    pub fn arrows(mut self, enabled: bool) -> Self {
+        self.arrows = enabled;
         self
     }
 
@@ -135,12 +126,7 @@ impl AxesBuilder {
     where
         F: glium::backend::Facade,
     {
-        let vertices =
-            glium::vertex::VertexBuffer::<Vertex>::new(display, &self.build_vertices()?)?;
-
-        Ok(Axes {
-            vertices: glium::vertex::VertexBufferAny::from(vertices),
-        })
+        Ok(self.shape(self.buffer(display)?))
     }
 
     /// Build the axes vertices and return them in a vector.
@@ -148,17 +134,19 @@ impl AxesBuilder {
     /// Useful if you wish to do other things with the vertices besides constructing
     /// a `Axes` object (e.g. unit testing, further processing, etc).
     pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
-        // Compute the normal transformation matrix.
-        let normal_matrix = Matrix3::<f32>::from_cols(
-            self.matrix.x.truncate(),
-            self.matrix.y.truncate(),
-            self.matrix.z.truncate(),
-        )
-        .invert()
-        .unwrap_or_else(Matrix3::<f32>::identity)
-        .transpose();
-
-        // Build the vertices.
+        self.vertices_with::<Selected>()
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<M>::new(self.matrix);
+
+        // Build the vertices, either as arrows, or as lines.
+        if self.arrows {
+            return self.arrow_vertices::<M>();
+        }
         let num_axes = 3;
         let verts_per_axis = 2;
         let mut vertices = Vec::<Vertex>::with_capacity(verts_per_axis * num_axes);
@@ -167,10 +155,9 @@ impl AxesBuilder {
             for vert in 0..verts_per_axis {
                 let mut normal = Vector3::<f32>::new(0.0, 0.0, 0.0);
                 normal[axis] = 1.0;
-                let position = (normal * (vert as f32)).extend(1.0);
                 vertices.push(Vertex {
-                    position: Point3::<f32>::from_homogeneous(self.matrix * position).into(),
-                    normal: (normal_matrix * normal).normalize().into(),
+                    position: transform.point(normal * (vert as f32) * self.size),
+                    normal: transform.normal(normal),
                     texcoord: [vert as f32, axis as f32],
                 });
             }
@@ -178,8 +165,74 @@ impl AxesBuilder {
 
         Ok(vertices)
     }
+
+    /// Build the vertices of each axis as an arrow, by rotating an arrow along
+    /// the y-axis onto each axis in turn.
+    fn arrow_vertices<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        let arrow = ArrowBuilder::new().build_vertices()?;
+        let rotations = [
+            Matrix3::<f32>::from_angle_z(Rad(-f32::consts::FRAC_PI_2)),
+            Matrix3::<f32>::identity(),
+            Matrix3::<f32>::from_angle_x(Rad(f32::consts::FRAC_PI_2)),
+        ];
+        let mut vertices = Vec::<Vertex>::with_capacity(arrow.len() * rotations.len());
+        for (axis, rotation) in rotations.iter().enumerate() {
+            let transform = Transformation::<M>::new(
+                self.matrix * Matrix4::from_scale(self.size) * Matrix4::from(*rotation),
+            );
+            for vertex in &arrow {
+                vertices.push(Vertex {
+                    position: transform.point(vertex.position),
+                    normal: transform.normal(vertex.normal),
+                    texcoord: [vertex.position[1], axis as f32],
+                });
+            }
+        }
+        Ok(vertices)
+    }
+}
+
+impl HasOptions for AxesBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
 }
 
+impl HasVertices for AxesBuilder {
+    type Shape = Axes;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> Axes {
+        Axes {
+            vertices,
+            primitives: if self.arrows {
+                glium::index::PrimitiveType::TrianglesList
+            } else {
+                glium::index::PrimitiveType::LinesList
+            },
+        }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for AxesBuilder {}
+
+impl WithColors for AxesBuilder {}
+
 #[test]
 pub fn ensure_default_axes_has_unit_dimensions() {
     let vertices = AxesBuilder::new()
@@ -270,3 +323,86 @@ pub fn ensure_axes_uvs_are_in_correct_range() {
     assert!(min == Vector2::<f32>::zero());
     assert!(max == Vector2::<f32>::new(1.0, 2.0));
 }
+
+#[test]
+pub fn ensure_axes_size_scales_axis_length() {
+    let vertices = AxesBuilder::new()
+        .size(2.5)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(2) {
+        let dir = Vector3::<f32>::from(chunk[1].position) - Vector3::<f32>::from(chunk[0].position);
+        assert!(abs_diff_eq!(dir.magnitude(), 2.5));
+    }
+}
+
+#[test]
+pub fn ensure_axes_arrow_tips_lie_at_unit_positions() {
+    let vertices = AxesBuilder::new()
+        .arrows(true)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for (axis, range) in vertices.chunks(vertices.len() / 3).enumerate() {
+        let mut expected = Vector3::<f32>::zero();
+        expected[axis] = 1.0;
+        let tip = range.iter().map(|v| Vector3::<f32>::from(v.position)).fold(
+            Vector3::<f32>::zero(),
+            |tip, p| {
+                if p[axis] > tip[axis] {
+                    p
+                } else {
+                    tip
+                }
+            },
+        );
+        assert_ulps_eq!(tip, expected, epsilon = 0.0001);
+        for vertex in range {
+            assert_eq!(vertex.texcoord[1], axis as f32);
+            assert!(vertex.position[axis] >= -0.0001 && vertex.position[axis] <= 1.0001);
+            let offset = Vector3::<f32>::from(vertex.position) - expected * vertex.position[axis];
+            assert!(offset.magnitude() <= 0.1001);
+        }
+    }
+}
+
+#[test]
+pub fn ensure_axes_arrows_have_ccw_triangles() {
+    let vertices = AxesBuilder::new()
+        .arrows(true)
+        .size(2.0)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let eyepos = v0 + Vector3::<f32>::from(chunk[0].normal);
+        let e0 = v1 - v0;
+        let e1 = v2 - v0;
+        let n = e0.cross(e1);
+        assert!(n.dot(v0 - eyepos) <= 0.0);
+        assert!(n.dot(v1 - eyepos) <= 0.0);
+        assert!(n.dot(v2 - eyepos) <= 0.0);
+    }
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_axes() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = AxesBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
+
+#[test]
+pub fn ensure_colored_axes_is_painted() {
+    crate::options::ensure_colors(AxesBuilder::new);
+}
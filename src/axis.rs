@@ -0,0 +1,61 @@
+//! A module containing the axis enumeration used to orient axial shapes.
+
+extern crate cgmath;
+
+use self::cgmath::*;
+
+/// One of the three coordinate axes.
+///
+/// Axial shapes (e.g. cylinders and cones) are aligned to the y-axis by
+/// default, and may be aligned to another axis instead. The shape is rotated
+/// by exactly a quarter turn onto its axis before any other transformation is
+/// applied, such that the y-axis maps to the positive end of the chosen axis
+/// (with the x-axis rotated onto the negative y-axis for `Axis::X`, and the
+/// z-axis rotated onto the negative y-axis for `Axis::Z`).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Axis {
+    /// The x-axis.
+    X,
+
+    /// The y-axis.
+    Y,
+
+    /// The z-axis.
+    Z,
+}
+
+impl Axis {
+    /// Returns the rotation from the y-axis onto this axis. The rotation is
+    /// built directly from its elements, such that it is exact.
+    pub(crate) fn matrix(self) -> Matrix4<f32> {
+        match self {
+            Axis::X => Matrix4::<f32>::new(
+                0.0, -1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0,
+            ),
+            Axis::Y => Matrix4::<f32>::identity(),
+            Axis::Z => Matrix4::<f32>::new(
+                1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, -1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0,
+            ),
+        }
+    }
+
+    /// Returns the index of the axis within a vector.
+    pub(crate) fn index(self) -> usize {
+        match self {
+            Axis::X => 0,
+            Axis::Y => 1,
+            Axis::Z => 2,
+        }
+    }
+}
+
+#[test]
+pub fn ensure_axis_rotations_map_y_onto_axis() {
+    let y = Vector4::<f32>::unit_y();
+    assert_eq!(Axis::X.matrix() * y, Vector4::unit_x());
+    assert_eq!(Axis::Y.matrix() * y, Vector4::unit_y());
+    assert_eq!(Axis::Z.matrix() * y, Vector4::unit_z());
+    for axis in [Axis::X, Axis::Z].iter() {
+        assert_eq!(axis.matrix().determinant(), 1.0);
+    }
+}
@@ -0,0 +1,401 @@
+//! A module for constructing billboard quad shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::errors::ShapeCreationError;
+use crate::facet::negate_normals;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{
+    BuildAs, HasOptions, HasVertices, ShapeOptions, WithColors, WithOrientation, WithShading,
+    WithTangents, WithTexcoords, WithTransform,
+};
+use crate::shading::Shading;
+use crate::tangent::with_strip_tangents;
+use crate::uv_mapping::transform_texcoords;
+use crate::vertex::{Vertex, VertexTangent};
+
+/// A polygonal billboard quad.
+///
+/// This object is constructed using a `BillboardQuadBuilder` object.
+pub struct BillboardQuad {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows a `BillboardQuad` object to be passed as a source of vertices.
+impl<'a> From<&'a BillboardQuad> for glium::vertex::VerticesSource<'a> {
+    fn from(quad: &'a BillboardQuad) -> glium::vertex::VerticesSource<'a> {
+        (&quad.vertices).into()
+    }
+}
+
+/// Allows a `BillboardQuad` object to be passed as a source of indices.
+impl<'a> From<&'a BillboardQuad> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a BillboardQuad) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::TriangleStrip,
+        }
+    }
+}
+
+/// The point of a `BillboardQuad` that is placed at the origin.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Anchor {
+    /// The top-left corner of the quad.
+    TopLeft,
+    /// The centre of the top edge of the quad.
+    TopCenter,
+    /// The top-right corner of the quad.
+    TopRight,
+    /// The centre of the left edge of the quad.
+    CenterLeft,
+    /// The centre of the quad.
+    Center,
+    /// The centre of the right edge of the quad.
+    CenterRight,
+    /// The bottom-left corner of the quad.
+    BottomLeft,
+    /// The centre of the bottom edge of the quad.
+    BottomCenter,
+    /// The bottom-right corner of the quad.
+    BottomRight,
+}
+
+impl Anchor {
+    /// Returns the position of the anchor as a proportion of the width and
+    /// height of the quad, from its bottom-left corner.
+    fn offset(self) -> Vector2<f32> {
+        match self {
+            Anchor::TopLeft => Vector2::new(0.0, 1.0),
+            Anchor::TopCenter => Vector2::new(0.5, 1.0),
+            Anchor::TopRight => Vector2::new(1.0, 1.0),
+            Anchor::CenterLeft => Vector2::new(0.0, 0.5),
+            Anchor::Center => Vector2::new(0.5, 0.5),
+            Anchor::CenterRight => Vector2::new(1.0, 0.5),
+            Anchor::BottomLeft => Vector2::new(0.0, 0.0),
+            Anchor::BottomCenter => Vector2::new(0.5, 0.0),
+            Anchor::BottomRight => Vector2::new(1.0, 0.0),
+        }
+    }
+}
+
+/// Responsible for building and returning a `BillboardQuad` object.
+///
+/// By default, the resultant polygon will be a unit-square in the xy-plane,
+/// facing the positive z-axis, with its centre at the origin. The point of the
+/// quad that is placed at the origin can be changed using an `Anchor`, such
+/// that sprites can pivot about their base or a corner without baking the
+/// pivot into per-instance transformations. The default size and anchor can
+/// be overridden using the size and anchor methods, and the default position
+/// and alignment can be overridden using the transformation methods on this
+/// object.
+///
+/// The resultant geometry is emitted as a triangle strip, and is constructed
+/// to suit OpenGL defaults - assuming a right-handed coordinate system,
+/// front-facing polygons are defined in counter-clock-wise order. Vertex
+/// normals point in the direction of the face. Vertex texture coordinates
+/// define a planar-projection on the face, with the u coordinate increasing
+/// along the positive x-axis and the v coordinate increasing along the
+/// positive y-axis.
+pub struct BillboardQuadBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    width: f32,
+    height: f32,
+    anchor: Anchor,
+    options: ShapeOptions,
+}
+
+impl Default for BillboardQuadBuilder {
+    fn default() -> BillboardQuadBuilder {
+        BillboardQuadBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            width: 1.0,
+            height: 1.0,
+            anchor: Anchor::Center,
+            options: ShapeOptions::default(),
+        }
+    }
+}
+
+impl BillboardQuadBuilder {
+    /// Create a new `BillboardQuadBuilder` object.
+    pub fn new() -> BillboardQuadBuilder {
+        Default::default()
+    }
+
+    /// Specify the point of the quad that is placed at the origin. By default,
+    /// the centre of the quad is placed at the origin.
+    pub fn anchor(mut self, anchor: Anchor) -> Self {
+        self.anchor = anchor;
+        self
+    }
+
+    /// Specify the width and height of the quad. By default, the quad is a
+    /// unit-square. The width and height must be greater than zero to build a
+    /// valid quad.
+    pub fn size(mut self, width: f32, height: f32) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    /// Specify the size of the quad in pixels, such that a sprite of the given
+    /// dimensions covers `pixels_per_unit` pixels for each unit of length. The
+    /// dimensions and the number of pixels per unit must be greater than zero
+    /// to build a valid quad.
+    pub fn pixel_size(self, width: u32, height: u32, pixels_per_unit: f32) -> Self {
+        self.size(
+            width as f32 / pixels_per_unit,
+            height as f32 / pixels_per_unit,
+        )
+    }
+
+    /// Build a new `BillboardQuad` object.
+    pub fn build<F>(self, display: &F) -> Result<BillboardQuad, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the quad vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// a `BillboardQuad` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.vertices_with::<Selected>()
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.options.check_shading(Self::SHADINGS)?;
+
+        // Ensure the quad has a valid size.
+        let valid = |x: f32| x > 0.0 && x.is_finite();
+        if !valid(self.width) || !valid(self.height) {
+            return Err(ShapeCreationError::InvalidDimensions);
+        }
+
+        // The texture coordinate projections may differ between the triangles
+        // that share a vertex of the strip.
+        self.options.check_uv_mapping()?;
+
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<M>::new(self.matrix);
+        let normal = transform.normal(Vector3::<f32>::unit_z());
+
+        // Build the vertices, offset such that the anchor lies at the origin.
+        let offset = self.anchor.offset();
+        let mut vertices = Vec::<Vertex>::with_capacity(self.num_vertices());
+        for vert in 0..self.num_vertices() {
+            let (u, v) = ((vert % 2) as f32, (vert / 2) as f32);
+            let position = Vector3::<f32>::new(
+                (u - offset.x) * self.width,
+                (v - offset.y) * self.height,
+                0.0,
+            );
+            vertices.push(Vertex {
+                position: transform.point(position),
+                normal,
+                texcoord: [u, v],
+            });
+        }
+
+        transform_texcoords(&self.options.uv_matrix, &mut vertices);
+        Ok(vertices)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    pub fn num_vertices(&self) -> usize {
+        4
+    }
+}
+
+impl HasOptions for BillboardQuadBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for BillboardQuadBuilder {
+    type Shape = BillboardQuad;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> BillboardQuad {
+        BillboardQuad { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+
+    /// The billboard is built as a triangle strip, so the options are applied
+    /// to it as a strip, rather than as a list of triangles.
+    fn finish(&self, vertices: &mut Vec<Vertex>) -> Result<(), ShapeCreationError> {
+        // Convert the strip into the convention, and reverse its winding when
+        // required.
+        self.options.orient_strip(vertices);
+        if self.options.flip_normals {
+            negate_normals(vertices);
+        }
+        Ok(())
+    }
+}
+
+impl WithTransform for BillboardQuadBuilder {}
+
+impl BuildAs for BillboardQuadBuilder {}
+
+impl WithTangents for BillboardQuadBuilder {
+    /// The tangents are accumulated over the triangles of the strip, rather
+    /// than over a list of triangles.
+    fn build_vertices_with_tangents(&self) -> Result<Vec<VertexTangent>, ShapeCreationError> {
+        Ok(with_strip_tangents(&self.build_vertices()?))
+    }
+}
+
+impl WithOrientation for BillboardQuadBuilder {}
+
+impl WithTexcoords for BillboardQuadBuilder {}
+
+impl WithColors for BillboardQuadBuilder {}
+
+impl WithShading for BillboardQuadBuilder {
+    /// By default, the billboard is flat shaded. Both modes are supported, and
+    /// build the same normals, since the billboard is planar.
+    const SHADINGS: &'static [Shading] = &[Shading::Flat, Shading::Smooth];
+}
+
+#[test]
+pub fn ensure_billboard_quad_places_anchor_at_origin() {
+    let anchors = [
+        Anchor::TopLeft,
+        Anchor::TopCenter,
+        Anchor::TopRight,
+        Anchor::CenterLeft,
+        Anchor::Center,
+        Anchor::CenterRight,
+        Anchor::BottomLeft,
+        Anchor::BottomCenter,
+        Anchor::BottomRight,
+    ];
+    for &anchor in anchors.iter() {
+        let vertices = BillboardQuadBuilder::new()
+            .size(3.0, 2.0)
+            .anchor(anchor)
+            .build_vertices()
+            .expect("Failed to build vertices");
+        let (min, max) = (
+            Vector3::<f32>::from(vertices[0].position),
+            Vector3::<f32>::from(vertices[3].position),
+        );
+        assert_eq!(max - min, Vector3::<f32>::new(3.0, 2.0, 0.0));
+        let offset = anchor.offset();
+        let origin = min + Vector3::<f32>::new(offset.x * 3.0, offset.y * 2.0, 0.0);
+        assert_eq!(origin, Vector3::<f32>::zero());
+    }
+}
+
+#[test]
+pub fn ensure_billboard_quad_anchors_lie_exactly_on_corners() {
+    let vertices = BillboardQuadBuilder::new()
+        .anchor(Anchor::BottomCenter)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    assert_eq!(vertices[0].position, [-0.5, 0.0, 0.0]);
+    assert_eq!(vertices[3].position, [0.5, 1.0, 0.0]);
+    let vertices = BillboardQuadBuilder::new()
+        .anchor(Anchor::TopLeft)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    assert_eq!(vertices[2].position, [0.0, 0.0, 0.0]);
+}
+
+#[test]
+pub fn ensure_billboard_quad_pixel_size_divides_by_pixels_per_unit() {
+    let vertices = BillboardQuadBuilder::new()
+        .anchor(Anchor::BottomLeft)
+        .pixel_size(64, 32, 16.0)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    assert_eq!(vertices[3].position, [4.0, 2.0, 0.0]);
+}
+
+#[test]
+pub fn ensure_default_billboard_quad_has_ccw_triangles() {
+    let vertices = BillboardQuadBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let tris = [[0, 1, 2], [2, 1, 3]];
+    for tri in tris.iter() {
+        let v0 = Vector3::<f32>::from(vertices[tri[0]].position);
+        let v1 = Vector3::<f32>::from(vertices[tri[1]].position);
+        let v2 = Vector3::<f32>::from(vertices[tri[2]].position);
+        let eyepos = v0 + Vector3::<f32>::from(vertices[tri[0]].normal);
+        let e0 = v1 - v0;
+        let e1 = v2 - v0;
+        let n = e0.cross(e1);
+        assert!(n.dot(v0 - eyepos) <= 0.0);
+        assert!(n.dot(v1 - eyepos) <= 0.0);
+        assert!(n.dot(v2 - eyepos) <= 0.0);
+    }
+    for vertex in &vertices {
+        assert_eq!(vertex.normal, [0.0, 0.0, 1.0]);
+        assert_eq!(
+            vertex.texcoord,
+            [vertex.position[0] + 0.5, vertex.position[1] + 0.5]
+        );
+    }
+}
+
+#[test]
+pub fn ensure_billboard_quad_with_invalid_size_fails_to_build() {
+    match BillboardQuadBuilder::new().size(0.0, 1.0).build_vertices() {
+        Err(ShapeCreationError::InvalidDimensions) => (),
+        _ => panic!("Expected InvalidDimensions"),
+    }
+    match BillboardQuadBuilder::new()
+        .pixel_size(16, 16, 0.0)
+        .build_vertices()
+    {
+        Err(ShapeCreationError::InvalidDimensions) => (),
+        _ => panic!("Expected InvalidDimensions"),
+    }
+}
+
+#[test]
+pub fn ensure_billboard_quad_applies_late_pipeline() {
+    crate::options::ensure_late_strip_pipeline(
+        BillboardQuadBuilder::new,
+        BillboardQuadBuilder::build_vertices,
+    );
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_billboard_quad() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = BillboardQuadBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
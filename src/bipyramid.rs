@@ -0,0 +1,309 @@
+//! A module for constructing bipyramid shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::errors::ShapeCreationError;
+use crate::facet::push_polygon;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{
+    BuildAs, HasOptions, HasVertices, ShapeOptions, WithBackFaces, WithColors, WithLightmap,
+    WithModifiers, WithOrientation, WithShading, WithTangents, WithTexcoords, WithTransform,
+};
+use crate::shading::Shading;
+use crate::vertex::Vertex;
+
+use std::f32;
+
+/// A polygonal `Bipyramid` object.
+///
+/// This object is constructed using a `BipyramidBuilder` object.
+pub struct Bipyramid {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows a `Bipyramid` object to be passed as a source of vertices.
+impl<'a> From<&'a Bipyramid> for glium::vertex::VerticesSource<'a> {
+    fn from(bipyramid: &'a Bipyramid) -> glium::vertex::VerticesSource<'a> {
+        (&bipyramid.vertices).into()
+    }
+}
+
+/// Allows a `Bipyramid` object to be passed as a source of indices.
+impl<'a> From<&'a Bipyramid> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a Bipyramid) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::TrianglesList,
+        }
+    }
+}
+
+/// Responsible for building and returning a `Bipyramid` object.
+///
+/// The bipyramid is constructed as two pyramids joined base-to-base, such as
+/// the diamond markers commonly used in editors. By default, the bipyramid has
+/// a square base, and is of unit height with its apexes located at
+/// `(0, 0.5, 0)` and `(0, -0.5, 0)`. The vertices of the shared base lie on a
+/// circle of radius 0.5 about the y-axis, the first of which lies on the
+/// positive x-axis, such that the default bipyramid is a regular octahedron.
+/// This can be overriden using the transformation methods on this object.
+///
+/// The resultant geometry is constructed to suit OpenGL defaults - assuming
+/// a right-handed coordinate system, front-facing polygons are defined in
+/// counter-clock-wise order. Vertex normals point in the direction of their
+/// respective face (such that the shape appears faceted when lit). Vertex
+/// texture coordinates define a planar-projection on each face.
+pub struct BipyramidBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    sides: usize,
+    options: ShapeOptions,
+}
+
+impl Default for BipyramidBuilder {
+    fn default() -> Self {
+        BipyramidBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            sides: 4,
+            options: ShapeOptions::default(),
+        }
+    }
+}
+
+impl BipyramidBuilder {
+    /// Create a new `BipyramidBuilder` object.
+    pub fn new() -> BipyramidBuilder {
+        Default::default()
+    }
+
+    /// Specify the number of sides of the shared base of the bipyramid. By
+    /// default, the builder will use a square base with 4 sides. At least 3
+    /// sides are required to build a valid bipyramid.
+    pub fn sides(mut self, sides: usize) -> Self {
+        self.sides = sides;
+        self
+    }
+
+    /// Build a new `Bipyramid` object.
+    pub fn build<F>(self, display: &F) -> Result<Bipyramid, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the shape vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// a `Bipyramid` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        let vertices = self.vertices_with::<Selected>()?;
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.options.check_shading(Self::SHADINGS)?;
+
+        // Ensure there are enough sides to produce valid bipyramid geometry.
+        if self.sides < 3 {
+            return Err(ShapeCreationError::NotEnoughDivisionsInU);
+        }
+
+        // Build the base vertices.
+        let angle = 2.0 * f32::consts::PI / self.sides as f32;
+        let base = (0..self.sides)
+            .map(|x| {
+                let a = x as f32 * angle;
+                Vector3::<f32>::new(a.cos() * 0.5, 0.0, -a.sin() * 0.5)
+            })
+            .collect::<Vec<Vector3<f32>>>();
+        let top = Vector3::<f32>::new(0.0, 0.5, 0.0);
+        let bottom = Vector3::<f32>::new(0.0, -0.5, 0.0);
+
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<M>::new(self.matrix);
+
+        // Emit the upper faces, followed by the lower faces.
+        let mut vertices = Vec::<Vertex>::with_capacity(self.num_vertices());
+        for side in 0..self.sides {
+            let next = (side + 1) % self.sides;
+            let face = [base[side], base[next], top];
+            push_polygon(&mut vertices, &transform, &face);
+        }
+        for side in 0..self.sides {
+            let next = (side + 1) % self.sides;
+            let face = [base[next], base[side], bottom];
+            push_polygon(&mut vertices, &transform, &face);
+        }
+
+        self.options.map_texcoords(&mut vertices);
+        Ok(vertices)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    pub fn num_vertices(&self) -> usize {
+        self.sides * 2 * 3 * self.options.copies()
+    }
+}
+
+impl HasOptions for BipyramidBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for BipyramidBuilder {
+    type Shape = Bipyramid;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> Bipyramid {
+        Bipyramid { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for BipyramidBuilder {}
+
+impl BuildAs for BipyramidBuilder {}
+
+impl WithTangents for BipyramidBuilder {}
+
+impl WithLightmap for BipyramidBuilder {}
+
+impl WithOrientation for BipyramidBuilder {}
+
+impl WithBackFaces for BipyramidBuilder {}
+
+impl WithModifiers for BipyramidBuilder {}
+
+impl WithTexcoords for BipyramidBuilder {}
+
+impl WithColors for BipyramidBuilder {}
+
+impl WithShading for BipyramidBuilder {
+    /// Only flat shading is supported by the bipyramid.
+    const SHADINGS: &'static [Shading] = &[Shading::Flat];
+}
+
+#[test]
+pub fn ensure_default_bipyramid_has_apexes_at_unit_height() {
+    let vertices = BipyramidBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let mut apexes = [0, 0];
+    for vertex in &vertices {
+        let pos = Vector3::<f32>::from(vertex.position);
+        if pos.y > 0.0 {
+            assert_ulps_eq!(pos, Vector3::<f32>::new(0.0, 0.5, 0.0));
+            apexes[0] += 1;
+        } else if pos.y < 0.0 {
+            assert_ulps_eq!(pos, Vector3::<f32>::new(0.0, -0.5, 0.0));
+            apexes[1] += 1;
+        } else {
+            assert_ulps_eq!(pos.magnitude(), 0.5);
+        }
+    }
+    assert_eq!(apexes, [4, 4]);
+}
+
+#[test]
+pub fn ensure_default_bipyramid_has_outward_facing_normals() {
+    let vertices = BipyramidBuilder::new()
+        .sides(5)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let centroid = (Vector3::<f32>::from(chunk[0].position)
+            + Vector3::<f32>::from(chunk[1].position)
+            + Vector3::<f32>::from(chunk[2].position))
+            / 3.0;
+        assert!(Vector3::<f32>::from(chunk[0].normal).dot(centroid) > 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_default_bipyramid_has_uvs_in_unit_range() {
+    let vertices = BipyramidBuilder::new()
+        .sides(7)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        assert!(vertex.texcoord[0] >= -0.0001 && vertex.texcoord[0] <= 1.0001);
+        assert!(vertex.texcoord[1] >= -0.0001 && vertex.texcoord[1] <= 1.0001);
+    }
+}
+
+#[test]
+pub fn ensure_default_bipyramid_has_ccw_triangles() {
+    let vertices = BipyramidBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let eyepos = v0 + Vector3::<f32>::from(chunk[0].normal);
+        let e0 = v1 - v0;
+        let e1 = v2 - v0;
+        let n = e0.cross(e1);
+        assert!(n.dot(v0 - eyepos) <= 0.0);
+        assert!(n.dot(v1 - eyepos) <= 0.0);
+        assert!(n.dot(v2 - eyepos) <= 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_bipyramid_with_too_few_sides_fails_to_build() {
+    match BipyramidBuilder::new().sides(2).build_vertices() {
+        Err(ShapeCreationError::NotEnoughDivisionsInU) => (),
+        _ => panic!("Expected NotEnoughDivisionsInU"),
+    }
+}
+
+#[test]
+pub fn ensure_bipyramid_applies_late_pipeline() {
+    use crate::options::WithTexcoords;
+    use crate::uv_mapping::UvMapping;
+
+    crate::options::ensure_late_pipeline(
+        || {
+            BipyramidBuilder::new()
+                .uv_mapping(UvMapping::Box)
+                .double_sided()
+        },
+        BipyramidBuilder::build_vertices,
+    );
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_bipyramid() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = BipyramidBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
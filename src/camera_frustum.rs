@@ -0,0 +1,488 @@
+//! A module for constructing camera frustum shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::errors::ShapeCreationError;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{HasOptions, HasVertices, ShapeOptions, WithColors, WithTransform};
+use crate::vertex::Vertex;
+
+use std::f32;
+
+/// A `CameraFrustum` object, made of lines along its edges, or of triangles
+/// across its near and far planes.
+///
+/// This object is constructed using a `CameraFrustumBuilder` object.
+pub struct CameraFrustum {
+    vertices: glium::vertex::VertexBufferAny,
+    primitives: glium::index::PrimitiveType,
+}
+
+/// Allows a `CameraFrustum` object to be passed as a source of vertices.
+impl<'a> From<&'a CameraFrustum> for glium::vertex::VerticesSource<'a> {
+    fn from(frustum: &'a CameraFrustum) -> glium::vertex::VerticesSource<'a> {
+        (&frustum.vertices).into()
+    }
+}
+
+/// Allows a `CameraFrustum` object to be passed as a source of indices.
+impl<'a> From<&'a CameraFrustum> for glium::index::IndicesSource<'a> {
+    fn from(frustum: &'a CameraFrustum) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: frustum.primitives,
+        }
+    }
+}
+
+/// The projection from which the frustum is built.
+enum Projection {
+    Perspective {
+        fovy: f32,
+        aspect: f32,
+        near: f32,
+        far: f32,
+    },
+    InverseViewProjection(Matrix4<f32>),
+}
+
+/// Responsible for building and returning a `CameraFrustum` object.
+///
+/// The frustum is the region of space that is visible through a projection,
+/// and is found by unprojecting the corners of the normalised device
+/// coordinate cube. The projection may be given either as the inverse of a
+/// view-projection matrix (in which case the frustum is built in world-space),
+/// or as the parameters of a perspective projection (in which case the
+/// frustum is built in view-space, with the camera at the origin looking along
+/// the negative z-axis). By default, the frustum is that of a perspective
+/// projection with a vertical field of view of 60 degrees, a square aspect
+/// ratio, and near and far planes at distances of 0.1 and 1. This can be
+/// overriden using the projection and transformation methods on this object.
+///
+/// Projections with an infinite far plane are supported, in which case the far
+/// corners of the frustum are clamped to lie at the maximum distance from their
+/// respective near corners. By default, the frustum is emitted as lines along
+/// its 12 edges - the 4 edges of the near plane, followed by the 4 edges of
+/// the far plane, followed by the 4 edges that join them. Vertex normals store
+/// the direction of their respective edge, and vertex texture coordinates map
+/// the proportion of the length along each edge to the u coordinate. The
+/// frustum may instead be emitted as triangles across its near and far planes,
+/// which are double-sided such that they may be drawn translucently from
+/// either side. To draw both, build one of each. Vertex normals then point in
+/// the direction of their respective face, and vertex texture coordinates map
+/// each plane to the unit square as it appears through the projection.
+pub struct CameraFrustumBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    options: ShapeOptions,
+    projection: Projection,
+    max_distance: f32,
+    planes: bool,
+}
+
+impl Default for CameraFrustumBuilder {
+    fn default() -> Self {
+        CameraFrustumBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            options: ShapeOptions::default(),
+            projection: Projection::Perspective {
+                fovy: f32::consts::FRAC_PI_3,
+                aspect: 1.0,
+                near: 0.1,
+                far: 1.0,
+            },
+            max_distance: 100.0,
+            planes: false,
+        }
+    }
+}
+
+impl CameraFrustumBuilder {
+    /// Create a new `CameraFrustumBuilder` object.
+    pub fn new() -> CameraFrustumBuilder {
+        Default::default()
+    }
+
+    /// Specify the projection as a perspective projection, with the given
+    /// vertical field of view in radians, aspect ratio (width over height),
+    /// and distances to the near and far planes. The far distance may be
+    /// infinite. The field of view must lie between 0 and a half-turn, the
+    /// aspect ratio and near distance must be greater than zero, and the far
+    /// distance must be greater than the near distance, to build a valid
+    /// frustum.
+    pub fn perspective(mut self, fovy: f32, aspect: f32, near: f32, far: f32) -> Self {
+        self.projection = Projection::Perspective {
+            fovy,
+            aspect,
+            near,
+            far,
+        };
+        self
+    }
+
+    /// Specify the projection as the inverse of a view-projection matrix. The
+    /// matrix must map the normalised device coordinate cube to a finite near
+    /// plane to build a valid frustum.
+    pub fn inverse_view_projection(mut self, matrix: Matrix4<f32>) -> Self {
+        self.projection = Projection::InverseViewProjection(matrix);
+        self
+    }
+
+    /// Specify the maximum distance between the near and far corners of the
+    /// frustum, to which the far plane of an infinite projection is clamped.
+    /// By default, the maximum distance is 100. The maximum distance must be
+    /// greater than zero to build a valid frustum.
+    pub fn max_distance(mut self, distance: f32) -> Self {
+        self.max_distance = distance;
+        self
+    }
+
+    /// Specify whether to emit triangles across the near and far planes,
+    /// rather than lines along the edges of the frustum. By default, the
+    /// edges are emitted.
+    pub fn planes(mut self, planes: bool) -> Self {
+        self.planes = planes;
+        self
+    }
+
+    /// Build a new `CameraFrustum` object.
+    pub fn build<F>(self, display: &F) -> Result<CameraFrustum, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the frustum vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// a `CameraFrustum` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.vertices_with::<Selected>()
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        let corners = self.corners()?;
+
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<M>::new(self.matrix);
+
+        let mut vertices = Vec::<Vertex>::with_capacity(self.num_vertices());
+        let mut push = |corner: usize, normal: Vector3<f32>, texcoord: [f32; 2]| {
+            vertices.push(Vertex {
+                position: transform.point(corners[corner]),
+                normal: transform.normal(normal),
+                texcoord,
+            });
+        };
+
+        if self.planes {
+            // Emit the near and far planes, each followed by its reverse side.
+            let quad = [0, 1, 2, 0, 2, 3];
+            let texcoords = [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]];
+            for &plane in [0, 4].iter() {
+                let [a, b, c] = [corners[plane], corners[plane + 1], corners[plane + 2]];
+                let normal = (b - a).cross(c - a);
+                for &side in [1.0, -1.0].iter() {
+                    for k in 0..quad.len() {
+                        let corner = quad[if side > 0.0 { k } else { quad.len() - 1 - k }];
+                        push(plane + corner, normal * side, texcoords[corner]);
+                    }
+                }
+            }
+        } else {
+            // Emit the edges of the near and far planes, followed by the edges
+            // that join them.
+            let edges = (0..4)
+                .map(|k| (k, (k + 1) % 4))
+                .chain((0..4).map(|k| (k + 4, (k + 1) % 4 + 4)))
+                .chain((0..4).map(|k| (k, k + 4)));
+            for (start, end) in edges {
+                let direction = corners[end] - corners[start];
+                push(start, direction, [0.0, 0.0]);
+                push(end, direction, [1.0, 0.0]);
+            }
+        }
+
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    pub fn num_vertices(&self) -> usize {
+        // Both the 12 edges, and the two double-sided planes, take 24 vertices.
+        24
+    }
+
+    /// Compute the corners of the frustum, in the order bottom-left,
+    /// bottom-right, top-right and top-left (as seen through the projection)
+    /// of the near plane, followed by the same corners of the far plane.
+    fn corners(&self) -> Result<[Vector3<f32>; 8], ShapeCreationError> {
+        if self.max_distance <= 0.0 || !self.max_distance.is_finite() {
+            return Err(ShapeCreationError::InvalidDimensions);
+        }
+
+        // Find the inverse of the projection. The far plane of an infinite
+        // perspective projection is treated as the limit of a finite one.
+        let inverse = match self.projection {
+            Projection::InverseViewProjection(matrix) => matrix,
+            Projection::Perspective {
+                fovy,
+                aspect,
+                near,
+                far,
+            } => {
+                if fovy <= 0.0
+                    || fovy >= f32::consts::PI
+                    || aspect <= 0.0
+                    || !aspect.is_finite()
+                    || near <= 0.0
+                    || !near.is_finite()
+                    || far <= near
+                {
+                    return Err(ShapeCreationError::InvalidDimensions);
+                }
+                let projection = if far.is_finite() {
+                    cgmath::perspective(Rad(fovy), aspect, near, far)
+                } else {
+                    let f = 1.0 / (fovy * 0.5).tan();
+                    Matrix4::<f32>::new(
+                        f / aspect,
+                        0.0,
+                        0.0,
+                        0.0,
+                        0.0,
+                        f,
+                        0.0,
+                        0.0,
+                        0.0,
+                        0.0,
+                        -1.0,
+                        -1.0,
+                        0.0,
+                        0.0,
+                        -2.0 * near,
+                        0.0,
+                    )
+                };
+                projection
+                    .invert()
+                    .ok_or(ShapeCreationError::InvalidDimensions)?
+            }
+        };
+
+        // Unproject each pair of near and far corners. Where the far corner
+        // lies at (or beyond) infinity, or further than the maximum distance
+        // from the near corner, it is clamped to the maximum distance along
+        // the direction of the ray between them.
+        let mut corners = [Vector3::<f32>::zero(); 8];
+        let ndc = [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)];
+        for (index, &(x, y)) in ndc.iter().enumerate() {
+            let near = inverse * Vector4::<f32>::new(x, y, -1.0, 1.0);
+            let far = inverse * Vector4::<f32>::new(x, y, 1.0, 1.0);
+            if near.w <= 0.0 || !near.w.is_finite() {
+                return Err(ShapeCreationError::InvalidDimensions);
+            }
+            let near = near.truncate() / near.w;
+            let direction = far.truncate() - near * far.w;
+            let far = if far.w > direction.magnitude() * 1e-6 {
+                Some(far.truncate() / far.w)
+            } else {
+                None
+            };
+            corners[index] = near;
+            corners[index + 4] = match far {
+                Some(far) if (far - near).magnitude() <= self.max_distance => far,
+                _ => near + direction.normalize() * self.max_distance,
+            };
+        }
+
+        let finite = |c: &Vector3<f32>| c.x.is_finite() && c.y.is_finite() && c.z.is_finite();
+        if !corners.iter().all(finite) {
+            return Err(ShapeCreationError::InvalidDimensions);
+        }
+        Ok(corners)
+    }
+}
+
+impl HasOptions for CameraFrustumBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for CameraFrustumBuilder {
+    type Shape = CameraFrustum;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> CameraFrustum {
+        CameraFrustum {
+            vertices,
+            primitives: if self.planes {
+                glium::index::PrimitiveType::TrianglesList
+            } else {
+                glium::index::PrimitiveType::LinesList
+            },
+        }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for CameraFrustumBuilder {}
+
+impl WithColors for CameraFrustumBuilder {}
+
+#[test]
+pub fn ensure_default_camera_frustum_has_expected_corners() {
+    let vertices = CameraFrustumBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    assert_eq!(vertices.len(), 24);
+    let tangent = (f32::consts::FRAC_PI_3 * 0.5).tan();
+    for vertex in &vertices {
+        let [x, y, z] = vertex.position;
+        assert!(ulps_eq!(z, -0.1, epsilon = 0.0001) || ulps_eq!(z, -1.0, epsilon = 0.0001));
+        assert!(abs_diff_eq!(x.abs(), -z * tangent, epsilon = 0.0001));
+        assert!(abs_diff_eq!(y.abs(), -z * tangent, epsilon = 0.0001));
+    }
+    for pair in vertices.chunks(2) {
+        let direction = Vector3::<f32>::from(pair[1].position) - Vector3::from(pair[0].position);
+        for vertex in pair {
+            assert_ulps_eq!(
+                Vector3::from(vertex.normal),
+                direction.normalize(),
+                epsilon = 0.0001
+            );
+        }
+    }
+}
+
+#[test]
+pub fn ensure_camera_frustum_unprojects_view_projection() {
+    let view = Matrix4::<f32>::look_at(
+        Point3::new(1.0, 2.0, 3.0),
+        Point3::new(0.0, 0.0, 0.0),
+        Vector3::unit_y(),
+    );
+    let view_projection = cgmath::perspective(Deg(45.0), 1.5, 0.5, 20.0) * view;
+    let vertices = CameraFrustumBuilder::new()
+        .inverse_view_projection(view_projection.invert().unwrap())
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        let clip = view_projection * Vector3::<f32>::from(vertex.position).extend(1.0);
+        let ndc = clip.truncate() / clip.w;
+        assert!(abs_diff_eq!(ndc.x.abs(), 1.0, epsilon = 0.001));
+        assert!(abs_diff_eq!(ndc.y.abs(), 1.0, epsilon = 0.001));
+        assert!(abs_diff_eq!(ndc.z.abs(), 1.0, epsilon = 0.001));
+    }
+}
+
+#[test]
+pub fn ensure_infinite_camera_frustum_is_clamped() {
+    let builders = [
+        CameraFrustumBuilder::new().perspective(1.0, 2.0, 0.1, f32::INFINITY),
+        CameraFrustumBuilder::new().inverse_view_projection(
+            Matrix4::<f32>::new(
+                1.0, 0.0, 0.0, 0.0, 0.0, 2.0, 0.0, 0.0, 0.0, 0.0, -1.0, -1.0, 0.0, 0.0, -0.2, 0.0,
+            )
+            .invert()
+            .unwrap(),
+        ),
+    ];
+    for builder in builders.iter() {
+        let corners = builder.corners().expect("Failed to build corners");
+        for index in 0..4 {
+            let (near, far) = (corners[index], corners[index + 4]);
+            assert!(abs_diff_eq!(near.z, -0.1, epsilon = 0.0001));
+            assert!(abs_diff_eq!(
+                (far - near).magnitude(),
+                100.0,
+                epsilon = 0.01
+            ));
+
+            // The far corner lies along the ray from the eye through the near
+            // corner.
+            assert_ulps_eq!(far.normalize(), near.normalize(), epsilon = 0.0001);
+        }
+        let vertices = builder.build_vertices().expect("Failed to build vertices");
+        for vertex in &vertices {
+            assert!(vertex.position.iter().all(|x| x.is_finite()));
+            assert!(vertex.normal.iter().all(|x| x.is_finite()));
+        }
+    }
+}
+
+#[test]
+pub fn ensure_camera_frustum_planes_have_ccw_triangles() {
+    let builder = CameraFrustumBuilder::new().planes(true);
+    let vertices = builder.build_vertices().expect("Failed to build vertices");
+    assert_eq!(vertices.len(), builder.num_vertices());
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let eyepos = v0 + Vector3::<f32>::from(chunk[0].normal);
+        let e0 = v1 - v0;
+        let e1 = v2 - v0;
+        let n = e0.cross(e1);
+        assert!(n.dot(v0 - eyepos) < 0.0);
+        assert!(n.dot(v1 - eyepos) < 0.0);
+        assert!(n.dot(v2 - eyepos) < 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_camera_frustum_with_invalid_parameters_fails_to_build() {
+    let builders = [
+        CameraFrustumBuilder::new().perspective(0.0, 1.0, 0.1, 1.0),
+        CameraFrustumBuilder::new().perspective(1.0, 0.0, 0.1, 1.0),
+        CameraFrustumBuilder::new().perspective(1.0, 1.0, 0.0, 1.0),
+        CameraFrustumBuilder::new().perspective(1.0, 1.0, 0.5, 0.5),
+        CameraFrustumBuilder::new().max_distance(0.0),
+        CameraFrustumBuilder::new().inverse_view_projection(Matrix4::zero()),
+    ];
+    for builder in builders.iter() {
+        match builder.build_vertices() {
+            Err(ShapeCreationError::InvalidDimensions) => (),
+            _ => panic!("Expected InvalidDimensions"),
+        }
+    }
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_camera_frustum() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = CameraFrustumBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
+
+#[test]
+pub fn ensure_colored_camera_frustum_is_painted() {
+    crate::options::ensure_colors(CameraFrustumBuilder::new);
+}
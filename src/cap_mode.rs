@@ -0,0 +1,31 @@
+//! A module containing the cap modes shared by shapes with open ends.
+
+/// The ends of a shape that are closed with flat caps.
+///
+/// Each builder documents which of its ends are its start and its end. A
+/// shape with a single open end (e.g. the base of a cone) has no cap at its
+/// end, and is left open there by every mode.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum CapMode {
+    /// Both the start and the end of the shape are closed with caps.
+    Both,
+    /// Only the start of the shape is closed with a cap.
+    Start,
+    /// Only the end of the shape is closed with a cap.
+    End,
+    /// Neither end of the shape is closed with a cap, such that the shape is
+    /// open at both of its ends.
+    Neither,
+}
+
+impl CapMode {
+    /// Returns whether the start of the shape is closed with a cap.
+    pub(crate) fn caps_start(self) -> bool {
+        self == CapMode::Both || self == CapMode::Start
+    }
+
+    /// Returns whether the end of the shape is closed with a cap.
+    pub(crate) fn caps_end(self) -> bool {
+        self == CapMode::Both || self == CapMode::End
+    }
+}
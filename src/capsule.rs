@@ -0,0 +1,514 @@
+//! A module for constructing capsule shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::axis::Axis;
+use crate::errors::ShapeCreationError;
+use crate::facet::flatten_normals;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{
+    BuildAs, HasOptions, HasVertices, ShapeOptions, WithBackFaces, WithColors, WithLightmap,
+    WithModifiers, WithOrientation, WithShading, WithTangents, WithTexcoords, WithTransform,
+};
+use crate::resolution::{Resolution, WithResolution};
+use crate::shading::Shading;
+use crate::vertex::Vertex;
+
+use std::f32;
+
+/// A polygonal `Capsule` object.
+///
+/// This object is constructed using a `CapsuleBuilder` object.
+pub struct Capsule {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows a `Capsule` object to be passed as a source of vertices.
+impl<'a> From<&'a Capsule> for glium::vertex::VerticesSource<'a> {
+    fn from(capsule: &'a Capsule) -> glium::vertex::VerticesSource<'a> {
+        (&capsule.vertices).into()
+    }
+}
+
+/// Allows a `Capsule` object to be passed as a source of indices.
+impl<'a> From<&'a Capsule> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a Capsule) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::TrianglesList,
+        }
+    }
+}
+
+/// Responsible for building and returning a `Capsule` object.
+///
+/// A capsule is a cylinder capped with a hemisphere at each end. By default,
+/// the capsule is aligned to the y-axis with a radius of 0.25 and a cylinder
+/// half-height of 0.25, such that it has unit height overall, with its
+/// centre-of-mass located at the origin. This can be overriden using the
+/// dimension and transformation methods on this object.
+///
+/// The resultant geometry is constructed to suit OpenGL defaults - assuming
+/// a right-handed coordinate system, front-facing polygons are defined in
+/// counter-clock-wise order. Vertex normals point away from the axis of the
+/// capsule (such that the shape appears smooth when lit, including across the
+/// junction between the cylinder and each hemisphere). Vertex texture
+/// coordinates wrap around the capsule in the u coordinate, and increase with
+/// the distance travelled along the surface from the bottom pole to the top
+/// pole in the v coordinate. Vertices along the seam are duplicated such that
+/// the seam has both a 0 and a 1 u coordinate.
+pub struct CapsuleBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    axis: Axis,
+    radius: f32,
+    half_height: f32,
+    segments: usize,
+    rings: usize,
+    options: ShapeOptions,
+}
+
+impl Default for CapsuleBuilder {
+    fn default() -> Self {
+        CapsuleBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            axis: Axis::Y,
+            radius: 0.25,
+            half_height: 0.25,
+            segments: 24,
+            rings: 6,
+            options: ShapeOptions {
+                shading: Shading::Smooth,
+                ..ShapeOptions::default()
+            },
+        }
+    }
+}
+
+impl CapsuleBuilder {
+    /// Create a new `CapsuleBuilder` object.
+    pub fn new() -> CapsuleBuilder {
+        Default::default()
+    }
+
+    /// Specify the radius of the capsule. By default, this is 0.25.
+    pub fn radius(mut self, radius: f32) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    /// Specify half of the height of the cylindrical section of the capsule
+    /// (e.g. the distance from the origin to the centre of each hemisphere).
+    /// By default, this is 0.25.
+    pub fn half_height(mut self, half_height: f32) -> Self {
+        self.half_height = half_height;
+        self
+    }
+
+    /// Specify the number of radial segments around the capsule. By default,
+    /// the builder will use 24 segments. At least 3 segments are required to
+    /// build a valid capsule.
+    pub fn segments(mut self, segments: usize) -> Self {
+        self.segments = segments;
+        self
+    }
+
+    /// Specify the number of rings in each hemisphere. By default, the builder
+    /// will use 6 rings. At least 1 ring is required to build a valid capsule.
+    pub fn rings(mut self, rings: usize) -> Self {
+        self.rings = rings;
+        self
+    }
+
+    /// Specify the axis along which the capsule is aligned. By default, the
+    /// capsule is aligned to the y-axis. The capsule is rotated onto its axis
+    /// before any other transformation is applied.
+    pub fn axis(mut self, axis: Axis) -> Self {
+        self.axis = axis;
+        self
+    }
+
+    /// Build a new `Capsule` object.
+    pub fn build<F>(self, display: &F) -> Result<Capsule, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the shape vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// a `Capsule` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        let vertices = self.vertices_with::<Selected>()?;
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.options.check_shading(Self::SHADINGS)?;
+
+        // Ensure there are enough segments and rings to produce valid capsule
+        // geometry.
+        if self.segments < 3 {
+            return Err(ShapeCreationError::NotEnoughDivisionsInU);
+        }
+
+        if self.rings < 1 {
+            return Err(ShapeCreationError::NotEnoughDivisionsInV);
+        }
+
+        // Build the profile of the capsule from the bottom pole to the top pole.
+        // Each entry holds the (cos, sin) of the latitude of the ring, and the
+        // height of the hemisphere centre that the ring belongs to. The two
+        // equatorial rings are shared by the hemispheres and the cylinder, such
+        // that their positions match exactly.
+        let ring_angle = f32::consts::FRAC_PI_2 / self.rings as f32;
+        let latitude = |ring: usize| -> [f32; 2] {
+            match ring {
+                0 => [1.0, 0.0],
+                r if r == self.rings => [0.0, 1.0],
+                r => {
+                    let a = r as f32 * ring_angle;
+                    [a.cos(), a.sin()]
+                }
+            }
+        };
+        let profile = (0..=self.rings)
+            .rev()
+            .map(|r| {
+                let [c, s] = latitude(r);
+                ([c, -s], -self.half_height)
+            })
+            .chain((0..=self.rings).map(|r| (latitude(r), self.half_height)))
+            .collect::<Vec<([f32; 2], f32)>>();
+
+        // Compute the texture v coordinate of each ring from its distance along
+        // the surface of the capsule.
+        let total_length = (f32::consts::PI * self.radius) + (2.0 * self.half_height);
+        let v_tab = (0..profile.len())
+            .map(|ring| {
+                let hemisphere_rings = if ring <= self.rings { ring } else { ring - 1 };
+                let mut length = hemisphere_rings as f32 * ring_angle * self.radius;
+                if ring > self.rings {
+                    length += 2.0 * self.half_height;
+                }
+                length / total_length
+            })
+            .collect::<Vec<f32>>();
+
+        // Build a lookup table of (cos, sin) pairs around the capsule.
+        let angle = 2.0 * f32::consts::PI / self.segments as f32;
+        let u_tab = (0..=self.segments)
+            .map(|x| {
+                let a = ((x % self.segments) as f32) * angle;
+                [a.cos(), a.sin()]
+            })
+            .collect::<Vec<[f32; 2]>>();
+
+        // Compute the transformation matrix, which rotates the shape onto its
+        // axis before any other transformation, and the normal transformation
+        // matrix.
+        let matrix = self.matrix * self.axis.matrix();
+        let transform = Transformation::<M>::new(matrix);
+
+        // Build vertex array. Each band between two consecutive rings is built
+        // from a quad per segment, except for the bands that touch a pole,
+        // which are built from a single triangle per segment. Pole vertices
+        // take the u coordinate of the middle of their segment.
+        let mut vertices = Vec::<Vertex>::with_capacity(self.num_vertices());
+        let quad = [(0, 0), (1, 0), (1, 1), (0, 0), (1, 1), (0, 1)];
+        let last_band = profile.len() - 2;
+
+        for band in 0..=last_band {
+            let corners = if band == 0 {
+                &quad[3..6]
+            } else if band == last_band {
+                &quad[0..3]
+            } else {
+                &quad[..]
+            };
+            for seg in 0..self.segments {
+                for &(du, dv) in corners.iter() {
+                    let ring = band + dv;
+                    let ([cl, sl], centre) = profile[ring];
+                    let [ca, sa] = u_tab[seg + du];
+                    let normal = Vector3::<f32>::new(cl * ca, sl, -cl * sa);
+                    let pos = Vector3::<f32>::new(0.0, centre, 0.0) + (normal * self.radius);
+                    let u = if ring == 0 || ring == profile.len() - 1 {
+                        (seg as f32 + 0.5) / self.segments as f32
+                    } else {
+                        (seg + du) as f32 / self.segments as f32
+                    };
+                    vertices.push(Vertex {
+                        position: transform.point(pos),
+                        normal: transform.normal(normal),
+                        texcoord: [u, v_tab[ring]],
+                    });
+                }
+            }
+        }
+
+        if self.options.shading == Shading::Flat {
+            flatten_normals(&mut vertices);
+        }
+
+        self.options.map_texcoords(&mut vertices);
+        Ok(vertices)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    pub fn num_vertices(&self) -> usize {
+        self.segments * self.rings * 12 * self.options.copies()
+    }
+}
+
+impl HasOptions for CapsuleBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for CapsuleBuilder {
+    type Shape = Capsule;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> Capsule {
+        Capsule { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for CapsuleBuilder {}
+
+impl BuildAs for CapsuleBuilder {}
+
+impl WithTangents for CapsuleBuilder {}
+
+impl WithLightmap for CapsuleBuilder {}
+
+impl WithOrientation for CapsuleBuilder {}
+
+impl WithBackFaces for CapsuleBuilder {}
+
+impl WithModifiers for CapsuleBuilder {}
+
+impl WithTexcoords for CapsuleBuilder {}
+
+impl WithColors for CapsuleBuilder {}
+
+impl WithShading for CapsuleBuilder {
+    /// By default, the capsule is smooth shaded. When flat shaded, each vertex
+    /// normal points in the direction of its respective triangle instead.
+    const SHADINGS: &'static [Shading] = &[Shading::Flat, Shading::Smooth];
+}
+
+impl WithResolution for CapsuleBuilder {
+    /// At least 3 segments around the capsule, and 1 ring in each hemisphere.
+    const MIN_RESOLUTION: Resolution = Resolution {
+        around: 3,
+        along: 1,
+    };
+
+    /// Specify the number of segments around the capsule, and the number of
+    /// rings in each hemisphere.
+    fn resolution(mut self, resolution: Resolution) -> Self {
+        self.segments = resolution.around as usize;
+        self.rings = resolution.along as usize;
+        self
+    }
+}
+
+#[cfg(test)]
+fn closest_point_on_capsule_axis(pos: Vector3<f32>, half_height: f32) -> Vector3<f32> {
+    Vector3::<f32>::new(0.0, pos.y.max(-half_height).min(half_height), 0.0)
+}
+
+#[test]
+pub fn ensure_capsule_vertices_lie_at_radius_from_axis() {
+    let vertices = CapsuleBuilder::new()
+        .radius(0.5)
+        .half_height(1.0)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        let pos = Vector3::<f32>::from(vertex.position);
+        let axis = closest_point_on_capsule_axis(pos, 1.0);
+        assert!(abs_diff_eq!(
+            (pos - axis).magnitude(),
+            0.5,
+            epsilon = 0.0001
+        ));
+    }
+}
+
+#[test]
+pub fn ensure_capsule_has_smooth_outward_normals() {
+    let vertices = CapsuleBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        let pos = Vector3::<f32>::from(vertex.position);
+        let axis = closest_point_on_capsule_axis(pos, 0.25);
+        let expected = (pos - axis).normalize();
+        assert_ulps_eq!(
+            Vector3::<f32>::from(vertex.normal),
+            expected,
+            epsilon = 0.0001
+        );
+    }
+}
+
+#[test]
+pub fn ensure_default_capsule_has_centroid_at_origin() {
+    let vertices = CapsuleBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let mut sum = Vector3::<f32>::zero();
+    for vertex in &vertices {
+        sum += Vector3::<f32>::from(vertex.position);
+    }
+    assert_ulps_eq!(sum, Vector3::<f32>::zero(), epsilon = 0.0001);
+}
+
+#[test]
+pub fn ensure_capsule_uvs_increase_with_height() {
+    let vertices = CapsuleBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        assert!(vertex.texcoord[0] >= 0.0 && vertex.texcoord[0] <= 1.0);
+        assert!(vertex.texcoord[1] >= 0.0 && vertex.texcoord[1] <= 1.0);
+    }
+    for a in &vertices {
+        for b in &vertices {
+            if a.position[1] < b.position[1] - 0.0001 {
+                assert!(a.texcoord[1] < b.texcoord[1]);
+            }
+        }
+    }
+}
+
+#[test]
+pub fn ensure_default_capsule_has_ccw_triangles() {
+    let vertices = CapsuleBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let eyepos = v0 + Vector3::<f32>::from(chunk[0].normal);
+        let e0 = v1 - v0;
+        let e1 = v2 - v0;
+        let n = e0.cross(e1);
+        assert!(n.dot(v0 - eyepos) <= 0.0);
+        assert!(n.dot(v1 - eyepos) <= 0.0);
+        assert!(n.dot(v2 - eyepos) <= 0.0);
+        assert!(n.magnitude() > 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_capsule_with_too_few_divisions_fails_to_build() {
+    match CapsuleBuilder::new().segments(2).build_vertices() {
+        Err(ShapeCreationError::NotEnoughDivisionsInU) => (),
+        _ => panic!("Expected NotEnoughDivisionsInU"),
+    }
+    match CapsuleBuilder::new().rings(0).build_vertices() {
+        Err(ShapeCreationError::NotEnoughDivisionsInV) => (),
+        _ => panic!("Expected NotEnoughDivisionsInV"),
+    }
+}
+
+#[test]
+pub fn ensure_capsule_with_invalid_resolution_fails_to_build() {
+    crate::resolution::ensure_min_resolution(
+        CapsuleBuilder::new,
+        CapsuleBuilder::build_vertices,
+        ShapeCreationError::NotEnoughDivisionsInU,
+        Some(ShapeCreationError::NotEnoughDivisionsInV),
+    );
+}
+
+#[test]
+pub fn ensure_capsule_uvs_do_not_wrap_across_seam() {
+    let vertices = CapsuleBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let us = chunk.iter().map(|v| v.texcoord[0]);
+        let min = us.clone().fold(f32::MAX, f32::min);
+        let max = us.fold(f32::MIN, f32::max);
+        assert!(max - min <= 0.5);
+    }
+}
+
+#[test]
+pub fn ensure_capsule_can_be_aligned_to_z_axis() {
+    use crate::axis::Axis;
+    let base = CapsuleBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let vertices = CapsuleBuilder::new()
+        .axis(Axis::Z)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for (a, b) in vertices.iter().zip(base.iter()) {
+        let ([x, y, z], [nx, ny, nz]) = (b.position, b.normal);
+        assert_eq!(a.position, [x, -z, y]);
+        assert_ulps_eq!(
+            Vector3::from(a.normal),
+            Vector3::new(nx, -nz, ny),
+            epsilon = 0.000001
+        );
+    }
+}
+
+#[test]
+pub fn ensure_capsule_applies_late_pipeline() {
+    use crate::options::WithTexcoords;
+    use crate::uv_mapping::UvMapping;
+
+    crate::options::ensure_late_pipeline(
+        || {
+            CapsuleBuilder::new()
+                .uv_mapping(UvMapping::Box)
+                .double_sided()
+        },
+        CapsuleBuilder::build_vertices,
+    );
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_capsule() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = CapsuleBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
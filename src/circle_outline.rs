@@ -0,0 +1,266 @@
+//! A module for constructing circle outline shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::errors::ShapeCreationError;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{HasOptions, HasVertices, ShapeOptions, WithColors, WithTransform};
+use crate::resolution::{Resolution, WithResolution};
+use crate::vertex::Vertex;
+
+use std::f32;
+
+/// A `CircleOutline` line strip.
+///
+/// This object is constructed using a `CircleOutlineBuilder` object.
+pub struct CircleOutline {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows a `CircleOutline` object to be passed as a source of vertices.
+impl<'a> From<&'a CircleOutline> for glium::vertex::VerticesSource<'a> {
+    fn from(circle: &'a CircleOutline) -> glium::vertex::VerticesSource<'a> {
+        (&circle.vertices).into()
+    }
+}
+
+/// Allows a `CircleOutline` object to be passed as a source of indices.
+impl<'a> From<&'a CircleOutline> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a CircleOutline) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::LineStrip,
+        }
+    }
+}
+
+/// Responsible for building and returning a `CircleOutline` object.
+///
+/// By default, the circle is defined as a unit-diameter circle in the
+/// xy-plane, with its centre located at the origin. This can be overriden
+/// using the transformation methods on this object.
+///
+/// The resultant geometry is constructed to suit OpenGL defaults - assuming
+/// a right-handed coordinate system. The circle is emitted as a line strip,
+/// which starts on the positive x-axis and proceeds counter-clock-wise when
+/// viewed from the positive z-axis, and whose last vertex closes the loop by
+/// repeating the first. Vertex normals point radially outward from the centre
+/// of the circle. Vertex texture coordinates map the angle around the circle
+/// to the u coordinate (from 0 at the first vertex to 1 at the last), and the
+/// v coordinate is always 0.
+pub struct CircleOutlineBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    options: ShapeOptions,
+    segments: usize,
+}
+
+impl Default for CircleOutlineBuilder {
+    fn default() -> CircleOutlineBuilder {
+        CircleOutlineBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            options: ShapeOptions::default(),
+            segments: 32,
+        }
+    }
+}
+
+impl CircleOutlineBuilder {
+    /// Create a new `CircleOutlineBuilder` object.
+    pub fn new() -> CircleOutlineBuilder {
+        Default::default()
+    }
+
+    /// Specify the number of segments around the circle. By default, the
+    /// builder will use 32 segments. At least 3 segments are required to build
+    /// a valid circle.
+    pub fn segments(mut self, segments: usize) -> Self {
+        self.segments = segments;
+        self
+    }
+
+    /// Build a new `CircleOutline` object.
+    pub fn build<F>(self, display: &F) -> Result<CircleOutline, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the circle vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// a `CircleOutline` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.vertices_with::<Selected>()
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        // Ensure there are enough segments to produce a valid circle.
+        if self.segments < 3 {
+            return Err(ShapeCreationError::NotEnoughDivisionsInU);
+        }
+
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<M>::new(self.matrix);
+
+        // Build the vertices. The last vertex wraps around to the first such
+        // that the loop closes exactly.
+        let angle = 2.0 * f32::consts::PI / self.segments as f32;
+        let mut vertices = Vec::<Vertex>::with_capacity(self.num_vertices());
+        for x in 0..=self.segments {
+            let a = ((x % self.segments) as f32) * angle;
+            let normal = Vector3::<f32>::new(a.cos(), a.sin(), 0.0);
+            vertices.push(Vertex {
+                position: transform.point(normal * 0.5),
+                normal: transform.normal(normal),
+                texcoord: [x as f32 / self.segments as f32, 0.0],
+            });
+        }
+
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    pub fn num_vertices(&self) -> usize {
+        self.segments + 1
+    }
+}
+
+impl HasOptions for CircleOutlineBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for CircleOutlineBuilder {
+    type Shape = CircleOutline;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> CircleOutline {
+        CircleOutline { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for CircleOutlineBuilder {}
+
+impl WithColors for CircleOutlineBuilder {}
+
+impl WithResolution for CircleOutlineBuilder {
+    /// At least 3 segments around the circle. The outline has no surface to
+    /// segment along, such that any `along` count is accepted.
+    const MIN_RESOLUTION: Resolution = Resolution {
+        around: 3,
+        along: 0,
+    };
+
+    /// Specify the number of segments around the circle. The `along` count is
+    /// ignored.
+    fn resolution(mut self, resolution: Resolution) -> Self {
+        self.segments = resolution.around as usize;
+        self
+    }
+}
+
+#[test]
+pub fn ensure_default_circle_outline_has_unit_diameter() {
+    let vertices = CircleOutlineBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        let pos = Vector3::<f32>::from(vertex.position);
+        assert!(abs_diff_eq!(pos.magnitude(), 0.5, epsilon = 0.0001));
+        assert_eq!(pos.z, 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_circle_outline_forms_closed_ccw_loop() {
+    let vertices = CircleOutlineBuilder::new()
+        .segments(7)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    assert_eq!(vertices.len(), 8);
+    assert_eq!(vertices[0].position, vertices[7].position);
+    assert_eq!(vertices[0].position, [0.5, 0.0, 0.0]);
+    for pair in vertices.windows(2) {
+        let p0 = Vector3::<f32>::from(pair[0].position);
+        let p1 = Vector3::<f32>::from(pair[1].position);
+        assert!(p0.cross(p1).z > 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_circle_outline_has_radial_normals_and_angular_uvs() {
+    let vertices = CircleOutlineBuilder::new()
+        .segments(12)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for (index, vertex) in vertices.iter().enumerate() {
+        let pos = Vector3::<f32>::from(vertex.position);
+        assert_ulps_eq!(
+            Vector3::<f32>::from(vertex.normal),
+            pos * 2.0,
+            epsilon = 0.0001
+        );
+        assert_ulps_eq!(vertex.texcoord[0], index as f32 / 12.0);
+        assert_eq!(vertex.texcoord[1], 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_circle_outline_with_too_few_segments_fails_to_build() {
+    match CircleOutlineBuilder::new().segments(2).build_vertices() {
+        Err(ShapeCreationError::NotEnoughDivisionsInU) => (),
+        _ => panic!("Expected NotEnoughDivisionsInU"),
+    }
+}
+
+#[test]
+pub fn ensure_circle_outline_with_invalid_resolution_fails_to_build() {
+    crate::resolution::ensure_min_resolution(
+        CircleOutlineBuilder::new,
+        CircleOutlineBuilder::build_vertices,
+        ShapeCreationError::NotEnoughDivisionsInU,
+        None,
+    );
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_circle_outline() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = CircleOutlineBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
+
+#[test]
+pub fn ensure_colored_circle_outline_is_painted() {
+    crate::options::ensure_colors(CircleOutlineBuilder::new);
+}
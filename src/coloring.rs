@@ -0,0 +1,136 @@
+//! A crate-private module for coloring the vertices of shapes.
+
+extern crate cgmath;
+
+use self::cgmath::*;
+use crate::axis::Axis;
+use crate::vertex::{Vertex, VertexColored};
+
+/// The way in which the vertices of a shape are colored.
+#[derive(Copy, Clone, Debug)]
+pub(crate) enum Colors {
+    /// Every vertex takes the same color.
+    Constant([f32; 4]),
+    /// Each vertex takes a color interpolated from its coordinate along an
+    /// axis prior to transformation, remapped from the extent of the shape
+    /// along the axis.
+    Gradient {
+        axis: Axis,
+        min: [f32; 4],
+        max: [f32; 4],
+    },
+    /// Each vertex takes a color from its normal, after all transformations.
+    Normals,
+}
+
+impl Colors {
+    /// Returns a copy of a list of vertices, which have been transformed by the
+    /// given matrix but not yet had the late options of the shape applied, in
+    /// which the first texture coordinate of each vertex holds its coordinate
+    /// along the axis of a gradient prior to transformation. The late options
+    /// leave the texture coordinates as they are, such that once they have
+    /// been applied to the copy, each of its vertices carries the coordinate of
+    /// the vertex from which it was derived, however it was moved or copied.
+    pub(crate) fn trace(self, vertices: &[Vertex], matrix: Matrix4<f32>) -> Vec<Vertex> {
+        match self {
+            Colors::Gradient { axis, .. } => {
+                let inverse = matrix.invert().unwrap_or_else(Matrix4::<f32>::identity);
+                vertices
+                    .iter()
+                    .map(|vertex| {
+                        let position = Vector3::<f32>::from(vertex.position).extend(1.0);
+                        Vertex {
+                            texcoord: [(inverse * position)[axis.index()], 0.0],
+                            ..*vertex
+                        }
+                    })
+                    .collect()
+            }
+            _ => vertices.to_vec(),
+        }
+    }
+
+    /// Attach the colors to a list of vertices, given the traced copy of the
+    /// vertices from which the coordinates of a gradient are taken.
+    pub(crate) fn paint(self, vertices: &[Vertex], traced: &[Vertex]) -> Vec<VertexColored> {
+        let colors = match self {
+            Colors::Constant(color) => vec![color; vertices.len()],
+            Colors::Normals => vertices
+                .iter()
+                .map(|vertex| normal_color(vertex.normal))
+                .collect(),
+            Colors::Gradient { min, max, .. } => {
+                let coordinates = traced
+                    .iter()
+                    .map(|vertex| vertex.texcoord[0])
+                    .collect::<Vec<f32>>();
+                let lo = coordinates.iter().cloned().fold(f32::INFINITY, f32::min);
+                let hi = coordinates
+                    .iter()
+                    .cloned()
+                    .fold(f32::NEG_INFINITY, f32::max);
+                coordinates
+                    .iter()
+                    .map(|&coordinate| {
+                        if hi > lo {
+                            lerp(min, max, (coordinate - lo) / (hi - lo))
+                        } else {
+                            min
+                        }
+                    })
+                    .collect()
+            }
+        };
+        vertices
+            .iter()
+            .zip(colors)
+            .map(|(vertex, color)| VertexColored {
+                position: vertex.position,
+                normal: vertex.normal,
+                texcoord: vertex.texcoord,
+                color,
+            })
+            .collect()
+    }
+}
+
+/// Returns the color that visualises a normal, whose red, green, and blue
+/// components are mapped from the unit range of the x, y, and z components of
+/// the normal respectively, with an alpha of 1.
+pub(crate) fn normal_color(normal: [f32; 3]) -> [f32; 4] {
+    [
+        normal[0] * 0.5 + 0.5,
+        normal[1] * 0.5 + 0.5,
+        normal[2] * 0.5 + 0.5,
+        1.0,
+    ]
+}
+
+/// Linearly interpolate between two colors, such that the colors are returned
+/// exactly at either end of the unit range.
+pub(crate) fn lerp(a: [f32; 4], b: [f32; 4], t: f32) -> [f32; 4] {
+    let mut color = [0.0; 4];
+    for (index, component) in color.iter_mut().enumerate() {
+        *component = a[index] * (1.0 - t) + b[index] * t;
+    }
+    color
+}
+
+#[test]
+pub fn ensure_gradient_over_zero_extent_takes_min_color() {
+    let vertex = Vertex {
+        position: [1.0, 2.0, 3.0],
+        normal: [0.0, 1.0, 0.0],
+        texcoord: [0.0, 0.0],
+    };
+    let colors = Colors::Gradient {
+        axis: Axis::Y,
+        min: [1.0, 0.0, 0.0, 1.0],
+        max: [0.0, 0.0, 1.0, 1.0],
+    };
+    let traced = colors.trace(&[vertex; 3], Matrix4::<f32>::identity());
+    let vertices = colors.paint(&[vertex; 3], &traced);
+    for vertex in &vertices {
+        assert_eq!(vertex.color, [1.0, 0.0, 0.0, 1.0]);
+    }
+}
@@ -0,0 +1,655 @@
+//! A module for constructing cone shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::axis::Axis;
+use crate::errors::ShapeCreationError;
+use crate::facet::flatten_normals;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{
+    BuildAs, HasOptions, HasVertices, ShapeOptions, WithBackFaces, WithCaps, WithColors,
+    WithLightmap, WithModifiers, WithOrientation, WithShading, WithTangents, WithTexcoords,
+    WithTransform,
+};
+use crate::resolution::{Resolution, WithResolution};
+use crate::shading::Shading;
+use crate::vertex::Vertex;
+
+use std::f32;
+
+/// A polygonal `Cone` object.
+///
+/// This object is constructed using a `ConeBuilder` object.
+pub struct Cone {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows a `Cone` object to be passed as a source of vertices.
+impl<'a> From<&'a Cone> for glium::vertex::VerticesSource<'a> {
+    fn from(cone: &'a Cone) -> glium::vertex::VerticesSource<'a> {
+        (&cone.vertices).into()
+    }
+}
+
+/// Allows a `Cone` object to be passed as a source of indices.
+impl<'a> From<&'a Cone> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a Cone) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::TrianglesList,
+        }
+    }
+}
+
+/// Responsible for building and returning a `Cone` object.
+///
+/// By default, the cone is defined with unit height and a base of unit diameter,
+/// aligned to the y-axis, with its apex located at `(0, 0.5, 0)` and its base
+/// centred at `(0, -0.5, 0)`. This can be overriden using the transformation
+/// methods on this object.
+///
+/// The resultant geometry is constructed to suit OpenGL defaults - assuming
+/// a right-handed coordinate system, front-facing polygons are defined in
+/// counter-clock-wise order. Vertex normals on the side of the cone are
+/// perpendicular to the slope of the cone (such that the side appears smooth
+/// when lit). The apex is duplicated for each radial segment, with each copy
+/// taking the normal at the middle of its segment. Normals on the base point
+/// along the negative y-axis. Vertex texture coordinates wrap around the side
+/// of the cone, and define a planar-projection on the base. The base may be
+/// left open. Vertices along the seam of the side are duplicated such that
+/// the seam has both a 0 and a 1 u coordinate.
+///
+/// The cone may sweep only part of a turn about its axis, in which case it is
+/// closed by a flat triangular face on each of its cut planes, with normals
+/// perpendicular to the plane. Texture coordinates on the cut faces map the
+/// distance from the axis (relative to the radius of the base) to the u
+/// coordinate, and the height to the v coordinate.
+pub struct ConeBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    axis: Axis,
+    segments: usize,
+    stacks: usize,
+    start_angle: f32,
+    sweep_angle: f32,
+    options: ShapeOptions,
+}
+
+impl Default for ConeBuilder {
+    fn default() -> Self {
+        ConeBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            axis: Axis::Y,
+            segments: 24,
+            stacks: 1,
+            start_angle: 0.0,
+            sweep_angle: 2.0 * f32::consts::PI,
+            options: ShapeOptions {
+                shading: Shading::Smooth,
+                ..ShapeOptions::default()
+            },
+        }
+    }
+}
+
+impl ConeBuilder {
+    /// Create a new `ConeBuilder` object.
+    pub fn new() -> ConeBuilder {
+        Default::default()
+    }
+
+    /// Specify the number of radial segments around the cone. By default,
+    /// the builder will use 24 segments. At least 3 segments are required to
+    /// build a valid cone.
+    pub fn segments(mut self, segments: usize) -> Self {
+        self.segments = segments;
+        self
+    }
+
+    /// Specify the number of stacks along the height of the cone. By default,
+    /// the builder will use 1 stack. At least 1 stack is required to build a
+    /// valid cone.
+    pub fn stacks(mut self, stacks: usize) -> Self {
+        self.stacks = stacks;
+        self
+    }
+
+    /// Specify the angle at which the cone starts, in radians measured
+    /// counter-clock-wise about the y-axis from the positive x-axis. By
+    /// default, the start angle is 0.
+    pub fn start_angle(mut self, radians: f32) -> Self {
+        self.start_angle = radians;
+        self
+    }
+
+    /// Specify the angle swept by the cone about its axis, in radians. By
+    /// default, the sweep angle is a full turn. The sweep angle must be
+    /// greater than zero, and no greater than a full turn, to build a valid
+    /// cone.
+    pub fn sweep_angle(mut self, radians: f32) -> Self {
+        self.sweep_angle = radians;
+        self
+    }
+
+    /// Specify the axis along which the cone is aligned. By default, the
+    /// cone is aligned to the y-axis. The cone is rotated onto its axis
+    /// before any other transformation is applied.
+    pub fn axis(mut self, axis: Axis) -> Self {
+        self.axis = axis;
+        self
+    }
+
+    /// Build a new `Cone` object.
+    pub fn build<F>(self, display: &F) -> Result<Cone, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the shape vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// a `Cone` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        let vertices = self.vertices_with::<Selected>()?;
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.options.check_shading(Self::SHADINGS)?;
+
+        // Ensure there are enough segments and stacks to produce valid cone
+        // geometry.
+        if self.segments < 3 {
+            return Err(ShapeCreationError::NotEnoughDivisionsInU);
+        }
+
+        if self.stacks < 1 {
+            return Err(ShapeCreationError::NotEnoughDivisionsInV);
+        }
+
+        let full = self.is_full_turn();
+        if self.sweep_angle <= 0.0 || !(full || self.sweep_angle < 2.0 * f32::consts::PI) {
+            return Err(ShapeCreationError::InvalidDimensions);
+        }
+
+        // The angle of each segment boundary around the cone. A full turn
+        // reuses the first angle at the end, such that the seam closes
+        // seamlessly.
+        let angle = self.sweep_angle / self.segments as f32;
+        let angle_at = |x: usize| {
+            let x = if full { x % self.segments } else { x };
+            self.start_angle + (x as f32) * angle
+        };
+
+        // The side normal at a given angle around the cone. For a cone of
+        // radius 0.5 and height 1, the normal rises by 0.5 for every unit
+        // it extends outward.
+        let side_normal = |a: f32| Vector3::<f32>::new(a.cos(), 0.5, -a.sin());
+        let rim = |a: f32| Vector3::<f32>::new(a.cos() * 0.5, -0.5, -a.sin() * 0.5);
+        let ring = |a: f32, stack: usize| {
+            let v = stack as f32 / self.stacks as f32;
+            let radius = (1.0 - v) * 0.5;
+            Vector3::<f32>::new(a.cos() * radius, v - 0.5, -a.sin() * radius)
+        };
+        let apex = Vector3::<f32>::new(0.0, 0.5, 0.0);
+        let centre = Vector3::<f32>::new(0.0, -0.5, 0.0);
+        let down = Vector3::<f32>::new(0.0, -1.0, 0.0);
+
+        // Compute the transformation matrix, which rotates the shape onto its
+        // axis before any other transformation, and the normal transformation
+        // matrix.
+        let matrix = self.matrix * self.axis.matrix();
+        let transform = Transformation::<M>::new(matrix);
+
+        let mut vertices = Vec::<Vertex>::with_capacity(self.num_vertices());
+        let mut emit = |position: Vector3<f32>, normal: Vector3<f32>, texcoord: [f32; 2]| {
+            vertices.push(Vertex {
+                position: transform.point(position),
+                normal: transform.normal(normal),
+                texcoord,
+            });
+        };
+
+        // Emit the side faces. Angles increase counter-clock-wise when viewed
+        // from above, such that the u coordinate increases from left to right
+        // when viewed from outside. Each stack is built from a quad per
+        // segment, except for the stack that touches the apex, which is built
+        // from a single triangle per segment.
+        let quad = [(0, 0), (1, 0), (1, 1), (0, 0), (1, 1), (0, 1)];
+        for stack in 0..self.stacks {
+            let corners = if stack == self.stacks - 1 {
+                &quad[0..2]
+            } else {
+                &quad[..]
+            };
+            for seg in 0..self.segments {
+                let a = |du: usize| angle_at(seg + du);
+                let u = |du: usize| (seg + du) as f32 / self.segments as f32;
+                for &(du, dv) in corners.iter() {
+                    let v = (stack + dv) as f32 / self.stacks as f32;
+                    emit(ring(a(du), stack + dv), side_normal(a(du)), [u(du), v]);
+                }
+                if stack == self.stacks - 1 {
+                    emit(
+                        apex,
+                        side_normal(self.start_angle + (seg as f32 + 0.5) * angle),
+                        [(u(0) + u(1)) * 0.5, 1.0],
+                    );
+                }
+            }
+        }
+
+        // Emit the base as a triangle fan, wound counter-clock-wise when viewed
+        // from below, unless it is disabled.
+        if self.options.caps.caps_start() {
+            for seg in 0..self.segments {
+                let (a0, a1) = (angle_at(seg), angle_at(seg + 1));
+                emit(centre, down, [0.5, 0.5]);
+                for &a in [a1, a0].iter() {
+                    let pos = rim(a);
+                    emit(pos, down, [pos.x + 0.5, pos.z + 0.5]);
+                }
+            }
+        }
+
+        // Emit the cut faces, unless the cone sweeps a full turn. Each is
+        // built like a single segment of the side, between the axis and the
+        // slope. The face at the end of the sweep is mirrored, such that both
+        // faces are wound counter-clock-wise when viewed from outside.
+        if !full {
+            let (a, b) = (angle_at(0), angle_at(self.segments));
+            let start = Vector3::<f32>::new(a.sin(), 0.0, a.cos());
+            let end = Vector3::<f32>::new(-b.sin(), 0.0, -b.cos());
+            for &(a, normal, flip) in [(a, start, false), (b, end, true)].iter() {
+                for stack in 0..self.stacks {
+                    let corners = if stack == self.stacks - 1 {
+                        &quad[0..3]
+                    } else {
+                        &quad[..]
+                    };
+                    for &(r, dv) in corners.iter() {
+                        let r = if flip { 1 - r } else { r };
+                        let v = (stack + dv) as f32 / self.stacks as f32;
+                        let position = if stack + dv == self.stacks {
+                            apex
+                        } else if r == 0 {
+                            Vector3::<f32>::new(0.0, v - 0.5, 0.0)
+                        } else {
+                            ring(a, stack + dv)
+                        };
+                        emit(position, normal, [r as f32 * (1.0 - v), v]);
+                    }
+                }
+            }
+        }
+
+        if self.options.shading == Shading::Flat {
+            flatten_normals(&mut vertices);
+        }
+
+        self.options.map_texcoords(&mut vertices);
+        Ok(vertices)
+    }
+
+    /// Returns true if the cone sweeps a full turn, such that it has no cut
+    /// faces.
+    fn is_full_turn(&self) -> bool {
+        ulps_eq!(self.sweep_angle, 2.0 * f32::consts::PI)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    pub fn num_vertices(&self) -> usize {
+        let cuts = if self.is_full_turn() {
+            0
+        } else {
+            2 * ((self.stacks - 1) * 6 + 3)
+        };
+        let cap = if self.options.caps.caps_start() {
+            self.segments * 3
+        } else {
+            0
+        };
+        ((self.segments * (self.stacks - 1) * 6) + (self.segments * 3) + cap + cuts)
+            * self.options.copies()
+    }
+}
+
+impl HasOptions for ConeBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for ConeBuilder {
+    type Shape = Cone;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> Cone {
+        Cone { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for ConeBuilder {}
+
+impl BuildAs for ConeBuilder {}
+
+impl WithTangents for ConeBuilder {}
+
+impl WithLightmap for ConeBuilder {}
+
+impl WithOrientation for ConeBuilder {}
+
+impl WithBackFaces for ConeBuilder {}
+
+impl WithModifiers for ConeBuilder {}
+
+/// The start of the cone is its base, and its end is its apex, which has no
+/// cap.
+impl WithCaps for ConeBuilder {}
+
+impl WithTexcoords for ConeBuilder {}
+
+impl WithColors for ConeBuilder {}
+
+impl WithShading for ConeBuilder {
+    /// By default, the cone is smooth shaded. When flat shaded, each vertex
+    /// normal points in the direction of its respective triangle instead.
+    const SHADINGS: &'static [Shading] = &[Shading::Flat, Shading::Smooth];
+}
+
+impl WithResolution for ConeBuilder {
+    /// At least 3 segments around the cone, and 1 stack along its height.
+    const MIN_RESOLUTION: Resolution = Resolution {
+        around: 3,
+        along: 1,
+    };
+
+    /// Specify the number of segments around the cone, and the number of
+    /// stacks along its height.
+    fn resolution(mut self, resolution: Resolution) -> Self {
+        self.segments = resolution.around as usize;
+        self.stacks = resolution.along as usize;
+        self
+    }
+}
+
+#[test]
+pub fn ensure_default_cone_has_unit_dimensions() {
+    let vertices = ConeBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        let pos = Vector3::<f32>::from(vertex.position);
+        assert!(pos.y >= -0.5 && pos.y <= 0.5);
+        let radius = Vector2::<f32>::new(pos.x, pos.z).magnitude();
+        assert!(radius <= (0.5 - pos.y) * 0.5 + 0.0001);
+    }
+}
+
+#[test]
+pub fn ensure_default_cone_has_outward_facing_normals() {
+    let vertices = ConeBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        let position = Vector3::<f32>::from(vertex.position);
+        let normal = Vector3::<f32>::from(vertex.normal);
+        assert!(normal.dot(position) > 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_default_cone_has_smooth_side_normals() {
+    let builder = ConeBuilder::new();
+    let vertices = builder.build_vertices().expect("Failed to build vertices");
+    let apex = Vector3::<f32>::new(0.0, 0.5, 0.0);
+    for vertex in vertices.iter().take(builder.segments * 3) {
+        // Each side normal must be perpendicular to the slope line that passes
+        // through its vertex.
+        let normal = Vector3::<f32>::from(vertex.normal);
+        let a = (-normal.z).atan2(normal.x);
+        let rim = Vector3::<f32>::new(a.cos() * 0.5, -0.5, -a.sin() * 0.5);
+        assert!(abs_diff_eq!(normal.dot(apex - rim), 0.0, epsilon = 0.0001));
+        assert!(abs_diff_eq!(normal.magnitude(), 1.0, epsilon = 0.0001));
+    }
+}
+
+#[test]
+pub fn ensure_default_cone_has_uvs_in_unit_range() {
+    let vertices = ConeBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        assert!(vertex.texcoord[0] >= 0.0);
+        assert!(vertex.texcoord[1] >= 0.0);
+        assert!(vertex.texcoord[0] <= 1.0);
+        assert!(vertex.texcoord[1] <= 1.0);
+    }
+}
+
+#[test]
+pub fn ensure_default_cone_has_ccw_triangles() {
+    let vertices = ConeBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let eyepos = v0 + Vector3::<f32>::from(chunk[0].normal);
+        let e0 = v1 - v0;
+        let e1 = v2 - v0;
+        let n = e0.cross(e1);
+        assert!(n.dot(v0 - eyepos) <= 0.0);
+        assert!(n.dot(v1 - eyepos) <= 0.0);
+        assert!(n.dot(v2 - eyepos) <= 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_cone_with_too_few_segments_fails_to_build() {
+    let result = ConeBuilder::new().segments(2).build_vertices();
+    match result {
+        Err(ShapeCreationError::NotEnoughDivisionsInU) => (),
+        _ => panic!("Expected NotEnoughDivisionsInU"),
+    }
+}
+
+#[test]
+pub fn ensure_cone_apex_normals_bisect_their_segment() {
+    let builder = ConeBuilder::new().segments(5);
+    let vertices = builder.build_vertices().expect("Failed to build vertices");
+    for chunk in vertices.chunks(3).take(builder.segments) {
+        let horizontal = |n: [f32; 3]| Vector2::<f32>::new(n[0], n[2]).normalize();
+        let bisector = (horizontal(chunk[0].normal) + horizontal(chunk[1].normal)).normalize();
+        assert_ulps_eq!(horizontal(chunk[2].normal), bisector, epsilon = 0.0001);
+    }
+}
+
+#[test]
+pub fn ensure_cone_stacks_lie_on_the_slope() {
+    let builder = ConeBuilder::new().segments(6).stacks(3);
+    let vertices = builder.build_vertices().expect("Failed to build vertices");
+    assert_eq!(vertices.len(), builder.num_vertices());
+    for vertex in vertices.iter().take(6 * 2 * 6 + 6 * 3) {
+        let pos = Vector3::<f32>::from(vertex.position);
+        let radius = Vector2::<f32>::new(pos.x, pos.z).magnitude();
+        assert!(abs_diff_eq!(radius, (0.5 - pos.y) * 0.5, epsilon = 0.0001));
+        assert_ulps_eq!(vertex.texcoord[1], pos.y + 0.5);
+    }
+}
+
+#[test]
+pub fn ensure_cone_with_invalid_resolution_fails_to_build() {
+    crate::resolution::ensure_min_resolution(
+        ConeBuilder::new,
+        ConeBuilder::build_vertices,
+        ShapeCreationError::NotEnoughDivisionsInU,
+        Some(ShapeCreationError::NotEnoughDivisionsInV),
+    );
+}
+
+#[test]
+pub fn ensure_partial_cone_is_watertight() {
+    use std::collections::HashMap;
+    let vertices = ConeBuilder::new()
+        .segments(9)
+        .stacks(3)
+        .start_angle(-0.5)
+        .sweep_angle(f32::consts::PI)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let mut edges = HashMap::<([u32; 3], [u32; 3]), usize>::new();
+    for chunk in vertices.chunks(3) {
+        for k in 0..3 {
+            let a = chunk[k].position.map(f32::to_bits);
+            let b = chunk[(k + 1) % 3].position.map(f32::to_bits);
+            *edges
+                .entry(if a < b { (a, b) } else { (b, a) })
+                .or_insert(0) += 1;
+        }
+    }
+    assert!(edges.values().all(|&count| count == 2));
+}
+
+#[test]
+pub fn ensure_partial_cone_cut_faces_have_planar_normals() {
+    let (start, sweep) = (0.25, 4.0);
+    let builder = ConeBuilder::new()
+        .segments(7)
+        .stacks(2)
+        .start_angle(start)
+        .sweep_angle(sweep);
+    let vertices = builder.build_vertices().expect("Failed to build vertices");
+    assert_eq!(vertices.len(), builder.num_vertices());
+    let cuts = &vertices[7 * 6 + 7 * 3 + 7 * 3..];
+    assert_eq!(cuts.len(), 2 * 9);
+    for (chunk, a) in cuts.chunks(9).zip([start, start + sweep].iter()) {
+        let radial = Vector3::<f32>::new(a.cos(), 0.0, -a.sin());
+        let normal = if *a == start {
+            radial.cross(Vector3::unit_y())
+        } else {
+            Vector3::unit_y().cross(radial)
+        };
+        for tri in chunk.chunks(3) {
+            let [v0, v1, v2] = [0, 1, 2].map(|k| Vector3::<f32>::from(tri[k].position));
+            assert!((v1 - v0).cross(v2 - v0).dot(normal) > 0.0);
+        }
+        for vertex in chunk {
+            let pos = Vector3::<f32>::from(vertex.position);
+            assert!(abs_diff_eq!(pos.dot(normal), 0.0, epsilon = 0.0001));
+            assert_ulps_eq!(Vector3::from(vertex.normal), normal, epsilon = 0.0001);
+        }
+    }
+}
+
+#[test]
+pub fn ensure_cone_with_invalid_sweep_fails_to_build() {
+    for &sweep in [0.0, -1.0, 7.0, f32::NAN].iter() {
+        match ConeBuilder::new().sweep_angle(sweep).build_vertices() {
+            Err(ShapeCreationError::InvalidDimensions) => (),
+            _ => panic!("Expected InvalidDimensions"),
+        }
+    }
+}
+
+#[test]
+pub fn ensure_cone_base_cap_can_be_disabled() {
+    use crate::cap_mode::CapMode;
+    let builder = ConeBuilder::new().segments(5);
+    assert_eq!(builder.num_vertices(), 5 * 3 + 5 * 3);
+    let builder = builder.caps(CapMode::Neither);
+    assert_eq!(builder.num_vertices(), 5 * 3);
+    let vertices = builder.build_vertices().expect("Failed to build vertices");
+    assert_eq!(vertices.len(), builder.num_vertices());
+    for vertex in &vertices {
+        assert!(vertex.normal[1] > 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_cone_uvs_do_not_wrap_across_seam() {
+    let vertices = ConeBuilder::new()
+        .stacks(2)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let us = chunk.iter().map(|v| v.texcoord[0]);
+        let min = us.clone().fold(f32::MAX, f32::min);
+        let max = us.fold(f32::MIN, f32::max);
+        assert!(max - min <= 0.5);
+    }
+}
+
+#[test]
+pub fn ensure_cone_can_be_aligned_to_z_axis() {
+    use crate::axis::Axis;
+    let base = ConeBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let vertices = ConeBuilder::new()
+        .axis(Axis::Z)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for (a, b) in vertices.iter().zip(base.iter()) {
+        let ([x, y, z], [nx, ny, nz]) = (b.position, b.normal);
+        assert_eq!(a.position, [x, -z, y]);
+        assert_ulps_eq!(
+            Vector3::from(a.normal),
+            Vector3::new(nx, -nz, ny),
+            epsilon = 0.000001
+        );
+    }
+}
+
+#[test]
+pub fn ensure_cone_applies_late_pipeline() {
+    use crate::options::WithTexcoords;
+    use crate::uv_mapping::UvMapping;
+
+    crate::options::ensure_late_pipeline(
+        || ConeBuilder::new().uv_mapping(UvMapping::Box).double_sided(),
+        ConeBuilder::build_vertices,
+    );
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_cone() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = ConeBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
+
+#[test]
+pub fn ensure_cone_supports_its_shadings() {
+    crate::options::ensure_shadings(ConeBuilder::new, ConeBuilder::build_vertices);
+}
@@ -0,0 +1,111 @@
+//! A module containing the coordinate conventions shared by all shapes.
+
+extern crate cgmath;
+
+use self::cgmath::*;
+use crate::facet::reverse_winding;
+use crate::vertex::Vertex;
+
+/// The coordinate convention in which the vertices of a shape are output.
+///
+/// Shapes are generated in the Y-up right-handed convention of OpenGL, and are
+/// converted into the chosen convention after all of their transformations
+/// have been applied, such that the transformations are always given in the
+/// Y-up right-handed convention. The x-axis is shared by all conventions. The
+/// Z-up conventions map the y-axis onto the z-axis, and the left-handed
+/// conventions mirror the remaining axis, which also reverses the winding of
+/// every triangle. The front faces are therefore wound counter-clock-wise in
+/// the right-handed conventions and clock-wise in the left-handed conventions
+/// (as viewed from the front), and the normals point outwards in all of them.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Convention {
+    /// The y-axis points up, and the z-axis points towards the viewer.
+    YUpRightHanded,
+
+    /// The z-axis points up, and the y-axis points away from the viewer.
+    ZUpRightHanded,
+
+    /// The y-axis points up, and the z-axis points away from the viewer.
+    YUpLeftHanded,
+
+    /// The z-axis points up, and the y-axis points towards the viewer.
+    ZUpLeftHanded,
+}
+
+impl Convention {
+    /// Returns the change of basis from the Y-up right-handed convention into
+    /// this convention. The matrix is built directly from its elements, such
+    /// that it is exact.
+    pub(crate) fn matrix(self) -> Matrix4<f32> {
+        match self {
+            Convention::YUpRightHanded => Matrix4::<f32>::identity(),
+            Convention::ZUpRightHanded => Matrix4::<f32>::new(
+                1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, -1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0,
+            ),
+            Convention::YUpLeftHanded => Matrix4::<f32>::new(
+                1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, -1.0, 0.0, 0.0, 0.0, 0.0, 1.0,
+            ),
+            Convention::ZUpLeftHanded => Matrix4::<f32>::new(
+                1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0,
+            ),
+        }
+    }
+
+    /// Returns whether the convention is left-handed, such that converting
+    /// into it reverses the winding of the triangles.
+    pub(crate) fn is_left_handed(self) -> bool {
+        match self {
+            Convention::YUpRightHanded | Convention::ZUpRightHanded => false,
+            Convention::YUpLeftHanded | Convention::ZUpLeftHanded => true,
+        }
+    }
+
+    /// Convert the positions and normals of a list of triangles into this
+    /// convention, reversing the winding of the triangles if the convention is
+    /// left-handed.
+    pub(crate) fn apply(self, vertices: &mut [Vertex]) {
+        self.convert(vertices);
+        if self.is_left_handed() {
+            reverse_winding(vertices);
+        }
+    }
+
+    /// Convert the positions and normals of a list of vertices into this
+    /// convention, leaving their order unchanged.
+    pub(crate) fn convert(self, vertices: &mut [Vertex]) {
+        if self == Convention::YUpRightHanded {
+            return;
+        }
+        let matrix = self.matrix();
+        for vertex in vertices.iter_mut() {
+            let position = Vector3::<f32>::from(vertex.position).extend(1.0);
+            let normal = Vector3::<f32>::from(vertex.normal).extend(0.0);
+            vertex.position = (matrix * position).truncate().into();
+            vertex.normal = (matrix * normal).truncate().into();
+        }
+    }
+}
+
+#[test]
+pub fn ensure_conventions_map_y_onto_up_axis() {
+    let y = Vector4::<f32>::unit_y();
+    assert_eq!(Convention::YUpRightHanded.matrix() * y, Vector4::unit_y());
+    assert_eq!(Convention::ZUpRightHanded.matrix() * y, Vector4::unit_z());
+    assert_eq!(Convention::YUpLeftHanded.matrix() * y, Vector4::unit_y());
+    assert_eq!(Convention::ZUpLeftHanded.matrix() * y, Vector4::unit_z());
+    for &convention in [
+        Convention::YUpRightHanded,
+        Convention::ZUpRightHanded,
+        Convention::YUpLeftHanded,
+        Convention::ZUpLeftHanded,
+    ]
+    .iter()
+    {
+        let handedness = if convention.is_left_handed() {
+            -1.0
+        } else {
+            1.0
+        };
+        assert_eq!(convention.matrix().determinant(), handedness);
+    }
+}
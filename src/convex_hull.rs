@@ -0,0 +1,572 @@
+//! A module for constructing convex hull shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::errors::ShapeCreationError;
+use crate::facet::push_polygon;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{
+    BuildAs, HasOptions, HasVertices, ShapeOptions, WithBackFaces, WithColors, WithLightmap,
+    WithModifiers, WithOrientation, WithShading, WithTangents, WithTexcoords, WithTransform,
+};
+use crate::shading::Shading;
+use crate::vertex::Vertex;
+
+use std::collections::HashMap;
+
+/// A polygonal `ConvexHull` object.
+///
+/// This object is constructed using a `ConvexHullBuilder` object.
+pub struct ConvexHull {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows a `ConvexHull` object to be passed as a source of vertices.
+impl<'a> From<&'a ConvexHull> for glium::vertex::VerticesSource<'a> {
+    fn from(hull: &'a ConvexHull) -> glium::vertex::VerticesSource<'a> {
+        (&hull.vertices).into()
+    }
+}
+
+/// Allows a `ConvexHull` object to be passed as a source of indices.
+impl<'a> From<&'a ConvexHull> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a ConvexHull) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::TrianglesList,
+        }
+    }
+}
+
+/// Responsible for building and returning a `ConvexHull` object.
+///
+/// The convex hull is the smallest convex solid that contains every point of a
+/// point cloud, and is computed using the quickhull algorithm. By default, the
+/// point cloud contains the corners of a unit cube with its centre located at
+/// the origin. This can be overriden using the transformation methods on this
+/// object.
+///
+/// The hull is emitted as triangles whose corners are points of the point
+/// cloud. Points that lie within the hull (or on one of its faces) do not
+/// contribute to its geometry, and coplanar faces are not merged (e.g. each
+/// side of the default cube is emitted as two separate triangles). The
+/// resultant geometry is constructed to suit OpenGL defaults - assuming a
+/// right-handed coordinate system, front-facing polygons are defined in
+/// counter-clock-wise order. Vertex normals point in the direction of their
+/// respective face (such that the shape appears faceted when lit). Vertex
+/// texture coordinates define a planar-projection on each face.
+pub struct ConvexHullBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    points: Vec<Point3<f32>>,
+    options: ShapeOptions,
+}
+
+impl Default for ConvexHullBuilder {
+    fn default() -> Self {
+        ConvexHullBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            points: (0..8)
+                .map(|corner| {
+                    let coord = |bit: usize| if corner & bit != 0 { 0.5 } else { -0.5 };
+                    Point3::<f32>::new(coord(1), coord(2), coord(4))
+                })
+                .collect(),
+            options: ShapeOptions::default(),
+        }
+    }
+}
+
+impl ConvexHullBuilder {
+    /// Create a new `ConvexHullBuilder` object.
+    pub fn new() -> ConvexHullBuilder {
+        Default::default()
+    }
+
+    /// Specify the point cloud from which the hull is computed. By default, the
+    /// point cloud contains the corners of a unit cube. At least 4 points that
+    /// do not lie on the same plane are required to build a valid hull.
+    pub fn points(mut self, points: &[impl Into<Point3<f32>> + Copy]) -> Self {
+        self.points = points.iter().map(|&point| point.into()).collect();
+        self
+    }
+
+    /// Build a new `ConvexHull` object.
+    pub fn build<F>(self, display: &F) -> Result<ConvexHull, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the shape vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// a `ConvexHull` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        let vertices = self.vertices_with::<Selected>()?;
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.options.check_shading(Self::SHADINGS)?;
+
+        let points = self
+            .points
+            .iter()
+            .map(|p| p.to_vec())
+            .collect::<Vec<Vector3<f32>>>();
+        let triangles = quickhull(&points)?;
+
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<M>::new(self.matrix);
+
+        // Emit the faces of the hull.
+        let mut vertices = Vec::<Vertex>::with_capacity(triangles.len() * 3);
+        for triangle in &triangles {
+            let face = [
+                points[triangle[0]],
+                points[triangle[1]],
+                points[triangle[2]],
+            ];
+            push_polygon(&mut vertices, &transform, &face);
+        }
+
+        self.options.map_texcoords(&mut vertices);
+        Ok(vertices)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    ///
+    /// As this depends on the shape of the point cloud, the hull is computed in
+    /// order to count its faces. Returns zero if the hull cannot be built.
+    pub fn num_vertices(&self) -> usize {
+        let points = self
+            .points
+            .iter()
+            .map(|p| p.to_vec())
+            .collect::<Vec<Vector3<f32>>>();
+        quickhull(&points).map(|t| t.len() * 3).unwrap_or(0) * self.options.copies()
+    }
+}
+
+/// A triangular face of a hull under construction.
+struct Face {
+    corners: [usize; 3],
+    normal: Vector3<f32>,
+    offset: f32,
+    outside: Vec<usize>,
+    alive: bool,
+}
+
+impl Face {
+    fn new(points: &[Vector3<f32>], corners: [usize; 3]) -> Face {
+        let [a, b, c] = corners;
+        let normal = (points[b] - points[a])
+            .cross(points[c] - points[a])
+            .normalize();
+        Face {
+            corners,
+            normal,
+            offset: normal.dot(points[a]),
+            outside: Vec::new(),
+            alive: true,
+        }
+    }
+
+    /// Returns the signed distance of a point above the plane of the face.
+    fn distance(&self, point: Vector3<f32>) -> f32 {
+        self.normal.dot(point) - self.offset
+    }
+
+    /// Returns the directed edges of the face, in counter-clock-wise order.
+    fn edges(&self) -> [(usize, usize); 3] {
+        let [a, b, c] = self.corners;
+        [(a, b), (b, c), (c, a)]
+    }
+}
+
+/// Compute the convex hull of a point cloud, returning its faces as triangles
+/// that index the point cloud and are wound counter-clock-wise when viewed
+/// from outside the hull.
+fn quickhull(points: &[Vector3<f32>]) -> Result<Vec<[usize; 3]>, ShapeCreationError> {
+    if points.len() < 4 {
+        return Err(ShapeCreationError::NotEnoughPoints);
+    }
+
+    // Points within a small distance of a plane are considered to lie on it,
+    // relative to the size of the point cloud.
+    let mut min = points[0];
+    let mut max = points[0];
+    for p in points {
+        for axis in 0..3 {
+            min[axis] = min[axis].min(p[axis]);
+            max[axis] = max[axis].max(p[axis]);
+        }
+    }
+    let extent = (max - min).x.max((max - min).y).max((max - min).z);
+    if !extent.is_finite() {
+        return Err(ShapeCreationError::InvalidDimensions);
+    }
+    let epsilon = extent * 1e-5;
+
+    // Build the initial tetrahedron from the two most distant extreme points
+    // along the axes, the point furthest from the line between them, and the
+    // point furthest from the plane through all three.
+    let mut extremes = Vec::<usize>::with_capacity(6);
+    for axis in 0..3 {
+        let by_axis = |&a: &usize, &b: &usize| points[a][axis].total_cmp(&points[b][axis]);
+        extremes.push((0..points.len()).min_by(by_axis).unwrap());
+        extremes.push((0..points.len()).max_by(by_axis).unwrap());
+    }
+    let furthest = |measure: &dyn Fn(Vector3<f32>) -> f32| {
+        (0..points.len())
+            .map(|index| (index, measure(points[index])))
+            .fold(
+                (0, 0.0),
+                |best, next| if next.1 > best.1 { next } else { best },
+            )
+    };
+    let (mut a, mut b, mut span) = (0, 0, 0.0);
+    for &i in &extremes {
+        for &j in &extremes {
+            let distance = (points[j] - points[i]).magnitude();
+            if distance > span {
+                a = i;
+                b = j;
+                span = distance;
+            }
+        }
+    }
+    let direction = (points[b] - points[a]) / span.max(f32::MIN_POSITIVE);
+    let (c, distance) = furthest(&|p| (p - points[a]).cross(direction).magnitude());
+    if span <= epsilon || distance <= epsilon {
+        return Err(ShapeCreationError::NotEnoughPoints);
+    }
+    let base = Face::new(points, [a, b, c]);
+    let (d, distance) = furthest(&|p| base.distance(p).abs());
+    if distance <= epsilon {
+        return Err(ShapeCreationError::NotEnoughPoints);
+    }
+    let initial = if base.distance(points[d]) > 0.0 {
+        [[a, c, b], [a, b, d], [b, c, d], [c, a, d]]
+    } else {
+        [[a, b, c], [a, d, b], [b, d, c], [c, d, a]]
+    };
+
+    // Track the face on the left of each directed edge, such that the faces
+    // neighbouring a face may be found.
+    let mut faces = Vec::<Face>::new();
+    let mut edges = HashMap::<(usize, usize), usize>::new();
+    let add_face =
+        |faces: &mut Vec<Face>, edges: &mut HashMap<(usize, usize), usize>, corners: [usize; 3]| {
+            let face = Face::new(points, corners);
+            for &edge in face.edges().iter() {
+                edges.insert(edge, faces.len());
+            }
+            faces.push(face);
+            faces.len() - 1
+        };
+    for &corners in initial.iter() {
+        add_face(&mut faces, &mut edges, corners);
+    }
+
+    // Assign every remaining point to a face that it lies outside of.
+    let assign = |faces: &mut Vec<Face>, candidates: &[usize], point: usize| {
+        let face = candidates
+            .iter()
+            .find(|&&face| faces[face].distance(points[point]) > epsilon);
+        if let Some(&face) = face {
+            faces[face].outside.push(point);
+        }
+    };
+    let candidates = (0..faces.len()).collect::<Vec<usize>>();
+    for point in 0..points.len() {
+        if ![a, b, c, d].contains(&point) {
+            assign(&mut faces, &candidates, point);
+        }
+    }
+
+    // Repeatedly expand the hull to include the point that lies furthest
+    // outside of a face, replacing all of the faces it can see with a cone of
+    // faces from the horizon of the visible region to the point.
+    while let Some(start) =
+        (0..faces.len()).find(|&f| faces[f].alive && !faces[f].outside.is_empty())
+    {
+        let eye = *faces[start]
+            .outside
+            .iter()
+            .max_by(|&&i, &&j| {
+                let (di, dj) = (
+                    faces[start].distance(points[i]),
+                    faces[start].distance(points[j]),
+                );
+                di.total_cmp(&dj)
+            })
+            .unwrap();
+
+        // Find the connected region of faces visible from the point, and the
+        // directed edges along its horizon.
+        let mut visible = vec![start];
+        let mut horizon = Vec::<(usize, usize)>::new();
+        faces[start].alive = false;
+        let mut index = 0;
+        while index < visible.len() {
+            let face = visible[index];
+            for &(from, to) in faces[face].edges().iter() {
+                let neighbour = edges[&(to, from)];
+                if !faces[neighbour].alive {
+                    continue;
+                }
+                if faces[neighbour].distance(points[eye]) > epsilon {
+                    faces[neighbour].alive = false;
+                    visible.push(neighbour);
+                } else {
+                    horizon.push((from, to));
+                }
+            }
+            index += 1;
+        }
+
+        // Replace the visible faces, and reassign their outside points.
+        let mut orphans = Vec::<usize>::new();
+        for &face in &visible {
+            orphans.append(&mut faces[face].outside);
+            for &edge in faces[face].edges().iter() {
+                edges.remove(&edge);
+            }
+        }
+        let cone = horizon
+            .iter()
+            .map(|&(from, to)| add_face(&mut faces, &mut edges, [from, to, eye]))
+            .collect::<Vec<usize>>();
+        for point in orphans {
+            if point != eye {
+                assign(&mut faces, &cone, point);
+            }
+        }
+    }
+
+    Ok(faces
+        .iter()
+        .filter(|face| face.alive)
+        .map(|face| face.corners)
+        .collect())
+}
+
+impl HasOptions for ConvexHullBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for ConvexHullBuilder {
+    type Shape = ConvexHull;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> ConvexHull {
+        ConvexHull { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for ConvexHullBuilder {}
+
+impl BuildAs for ConvexHullBuilder {}
+
+impl WithTangents for ConvexHullBuilder {}
+
+impl WithLightmap for ConvexHullBuilder {}
+
+impl WithOrientation for ConvexHullBuilder {}
+
+impl WithBackFaces for ConvexHullBuilder {}
+
+impl WithModifiers for ConvexHullBuilder {}
+
+impl WithTexcoords for ConvexHullBuilder {}
+
+impl WithColors for ConvexHullBuilder {}
+
+impl WithShading for ConvexHullBuilder {
+    /// Only flat shading is supported by the convex hull.
+    const SHADINGS: &'static [Shading] = &[Shading::Flat];
+}
+
+#[cfg(test)]
+fn sphere_cloud(count: usize) -> Vec<Point3<f32>> {
+    // Scatter points within a unit sphere using a simple linear congruential
+    // generator, keeping some on its surface.
+    let mut state = 12345u32;
+    let mut random = move || {
+        state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+        (state >> 8) as f32 / (1 << 24) as f32 * 2.0 - 1.0
+    };
+    (0..count)
+        .map(|index| {
+            let p = Vector3::<f32>::new(random(), random(), random()).normalize();
+            let radius = if index % 3 == 0 { 1.0 } else { random().abs() };
+            Point3::from_vec(p * radius)
+        })
+        .collect()
+}
+
+#[test]
+pub fn ensure_default_convex_hull_is_unit_cube() {
+    let vertices = ConvexHullBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    assert_eq!(vertices.len(), 12 * 3);
+    for vertex in &vertices {
+        let position = Vector3::<f32>::from(vertex.position);
+        let normal = Vector3::<f32>::from(vertex.normal);
+        for axis in 0..3 {
+            assert!(abs_diff_eq!(position[axis].abs(), 0.5));
+        }
+        assert!(abs_diff_eq!(normal.dot(position), 0.5, epsilon = 0.0001));
+    }
+}
+
+#[test]
+pub fn ensure_convex_hull_contains_all_points() {
+    let mut points = sphere_cloud(200);
+    points.extend(ConvexHullBuilder::new().points.iter());
+    let vertices = ConvexHullBuilder::new()
+        .points(&points)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let normal = Vector3::<f32>::from(chunk[0].normal);
+        for p in &points {
+            assert!(normal.dot(p.to_vec() - v0) <= 0.0001);
+        }
+    }
+}
+
+#[test]
+pub fn ensure_convex_hull_is_watertight() {
+    let points = sphere_cloud(100);
+    let vertices = ConvexHullBuilder::new()
+        .points(&points)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let mut edges = HashMap::<([u32; 3], [u32; 3]), usize>::new();
+    for chunk in vertices.chunks(3) {
+        for k in 0..3 {
+            let a = chunk[k].position.map(f32::to_bits);
+            let b = chunk[(k + 1) % 3].position.map(f32::to_bits);
+            *edges.entry((a, b)).or_insert(0) += 1;
+        }
+    }
+
+    // Each directed edge is used once, and is reversed by its neighbour.
+    for (&(a, b), &count) in &edges {
+        assert_eq!(count, 1);
+        assert_eq!(edges.get(&(b, a)), Some(&1));
+    }
+}
+
+#[test]
+pub fn ensure_convex_hull_has_ccw_triangles() {
+    let vertices = ConvexHullBuilder::new()
+        .points(&sphere_cloud(50))
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let eyepos = v0 + Vector3::<f32>::from(chunk[0].normal);
+        let e0 = v1 - v0;
+        let e1 = v2 - v0;
+        let n = e0.cross(e1);
+        assert!(n.dot(v0 - eyepos) <= 0.0);
+        assert!(n.dot(v1 - eyepos) <= 0.0);
+        assert!(n.dot(v2 - eyepos) <= 0.0);
+        assert!(Vector3::<f32>::from(chunk[0].normal).dot(v0) > 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_convex_hull_has_uvs_in_unit_range() {
+    let vertices = ConvexHullBuilder::new()
+        .points(&sphere_cloud(50))
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        assert!(vertex.texcoord[0] >= -0.0001 && vertex.texcoord[0] <= 1.0001);
+        assert!(vertex.texcoord[1] >= -0.0001 && vertex.texcoord[1] <= 1.0001);
+    }
+}
+
+#[test]
+pub fn ensure_degenerate_convex_hull_fails_to_build() {
+    let flat = (0..10)
+        .map(|x| Point3::<f32>::new(x as f32, (x * x) as f32, 0.0))
+        .collect::<Vec<Point3<f32>>>();
+    let line = (0..10)
+        .map(|x| Point3::<f32>::new(x as f32, x as f32, x as f32))
+        .collect::<Vec<Point3<f32>>>();
+    for points in [
+        &flat[..],
+        &line[..],
+        &flat[..3],
+        &[Point3::new(1.0, 2.0, 3.0); 5],
+    ]
+    .iter()
+    {
+        match ConvexHullBuilder::new().points(points).build_vertices() {
+            Err(ShapeCreationError::NotEnoughPoints) => (),
+            _ => panic!("Expected NotEnoughPoints"),
+        }
+    }
+}
+
+#[test]
+pub fn ensure_convex_hull_applies_late_pipeline() {
+    use crate::options::WithTexcoords;
+    use crate::uv_mapping::UvMapping;
+
+    crate::options::ensure_late_pipeline(
+        || {
+            ConvexHullBuilder::new()
+                .uv_mapping(UvMapping::Box)
+                .double_sided()
+        },
+        ConvexHullBuilder::build_vertices,
+    );
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_convex_hull() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = ConvexHullBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
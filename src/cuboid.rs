@@ -4,14 +4,39 @@ extern crate cgmath;
 extern crate glium;
 
 use self::cgmath::*;
+use crate::aabb_wire::{AabbWire, AabbWireBuilder};
+use crate::coloring::{lerp, normal_color, Colors};
 use crate::errors::ShapeCreationError;
-use crate::vertex::Vertex;
+use crate::lightmap::with_indexed_lightmap_uvs;
+use crate::math::{Math, Selected, Transformation};
+use crate::modifier::Modifier;
+use crate::options::{
+    BuildAs, HasOptions, HasVertices, ShapeOptions, WithBackFaces, WithColors, WithLightmap,
+    WithModifiers, WithOrientation, WithShading, WithTangents, WithTexcoords, WithTransform,
+};
+pub use crate::shading::Shading;
+use crate::tangent::with_indexed_tangents;
+use crate::uv_mapping::transform_texcoord;
+use crate::vertex::{Vertex, VertexColored, VertexLightmapped, VertexTangent};
+use crate::winding::Winding;
+use std::ops::Range;
 
 /// A polygonal `Cuboid` object.
 ///
 /// This object is constructed using a `CuboidBuilder` object.
 pub struct Cuboid {
     vertices: glium::vertex::VertexBufferAny,
+    face_ranges: [Range<usize>; 6],
+}
+
+impl Cuboid {
+    /// Returns the range of vertices that define the given face, such that
+    /// individual faces may be drawn with separate draw calls (e.g. with a
+    /// different material per face). The range of a face that was omitted
+    /// from the cuboid is empty.
+    pub fn face_range(&self, face: Face) -> Range<usize> {
+        self.face_ranges[face as usize].clone()
+    }
 }
 
 /// Allows a `Cuboid` object to be passed as a source of vertices.
@@ -22,14 +47,54 @@ impl<'a> From<&'a Cuboid> for glium::vertex::VerticesSource<'a> {
 }
 
 /// Allows a `Cuboid` object to be passed as a source of indices.
-impl<'a> Into<glium::index::IndicesSource<'a>> for &'a Cuboid {
-    fn into(self) -> glium::index::IndicesSource<'a> {
+impl<'a> From<&'a Cuboid> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a Cuboid) -> glium::index::IndicesSource<'a> {
         glium::index::IndicesSource::NoIndices {
             primitives: glium::index::PrimitiveType::TrianglesList,
         }
     }
 }
 
+/// An indexed polygonal `Cuboid` object.
+///
+/// This object is constructed using the `CuboidBuilder::build_indexed` method.
+pub struct IndexedCuboid {
+    vertices: glium::vertex::VertexBufferAny,
+    indices: glium::index::IndexBuffer<u16>,
+    face_ranges: [Range<usize>; 6],
+}
+
+impl IndexedCuboid {
+    /// Returns the range of indices that define the given face. The range of a
+    /// face that was omitted from the cuboid is empty.
+    pub fn face_range(&self, face: Face) -> Range<usize> {
+        self.face_ranges[face as usize].clone()
+    }
+
+    /// Returns the slice of the index buffer that defines the given face, such
+    /// that individual faces may be drawn with separate draw calls (e.g. with
+    /// a different material per face).
+    pub fn face_indices(&self, face: Face) -> glium::index::IndexBufferSlice<'_, u16> {
+        self.indices
+            .slice(self.face_range(face))
+            .expect("Face range lies within the index buffer")
+    }
+}
+
+/// Allows an `IndexedCuboid` object to be passed as a source of vertices.
+impl<'a> From<&'a IndexedCuboid> for glium::vertex::VerticesSource<'a> {
+    fn from(cuboid: &'a IndexedCuboid) -> glium::vertex::VerticesSource<'a> {
+        (&cuboid.vertices).into()
+    }
+}
+
+/// Allows an `IndexedCuboid` object to be passed as a source of indices.
+impl<'a> From<&'a IndexedCuboid> for glium::index::IndicesSource<'a> {
+    fn from(cuboid: &'a IndexedCuboid) -> glium::index::IndicesSource<'a> {
+        (&cuboid.indices).into()
+    }
+}
+
 /// Responsible for building and returning a `Cuboid` object.
 ///
 /// By default, the cuboid is defined as a unit-cube with its centre-of-mass
@@ -40,15 +105,201 @@ impl<'a> Into<glium::index::IndicesSource<'a>> for &'a Cuboid {
 /// a right-handed coordinate system, front-facing polygons are defined in
 /// counter-clock-wise order. Vertex normals point in the direction of their
 /// respective face (such that the shape appears faceted when lit). Vertex
-/// texture coordinates define a planar-projection on each face.
+/// texture coordinates define a planar-projection on each face, and the faces
+/// may be unwrapped into a cross to share a single texture. Alternatively,
+/// the cuboid may be smooth shaded, in which case each vertex normal points in
+/// the direction of its respective corner from the centre of the cuboid.
+///
+/// The edges and corners of the cuboid may be bevelled with flat chamfers,
+/// each of whose texture coordinates map to the unit square.
+///
+/// Each face may be subdivided into a grid of quads, in which case the texture
+/// coordinates are interpolated across the face, and the faces that meet at an
+/// edge of the cuboid share identical vertex positions along that edge.
+///
+/// Unlike the `invert` function, flipping the normals of the cuboid leaves the
+/// winding of its triangles as it is, and clock-wise winding leaves the normals
+/// of an inverted cuboid pointing inwards. A jittered cuboid leaves no cracks
+/// between its faces, and each of its triangles is built from its own corners
+/// when indexed. Unless smoothly shaded, the faces of the cuboid do not share
+/// normals, such that they separate at the edges when offset along their
+/// normals. The wireframe of the cuboid ignores its radial arrays.
 pub struct CuboidBuilder {
     matrix: cgmath::Matrix4<f32>,
+    subdivisions: [usize; 3],
+    uv_layout: UvLayout,
+    faces: FaceMask,
+    half_extents: Vector3<f32>,
+    chamfer: f32,
+    inverted: bool,
+    face_colors: Option<FaceColors>,
+    uv_tiling: Option<f32>,
+    uv_orientation: UvOrientation,
+    diagonal: Diagonal,
+    options: ShapeOptions,
+}
+
+/// The way in which the texture coordinates of a `Cuboid` are laid out.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum UvLayout {
+    /// Each face maps to the whole of the unit square, such that a texture is
+    /// repeated on every face.
+    PerFace,
+    /// The faces are unwrapped into a cross within the unit square, which is
+    /// divided into a grid of four columns and three rows. The -Z face is at
+    /// the centre of the cross, in the second column of the middle row, with
+    /// the +Y face above it and the -Y face below it. The +X face is to its
+    /// left, and the -X and +Z faces follow to its right.
+    Cross,
+}
+
+/// The orientation of the texture coordinates on each face of a `Cuboid`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum UvOrientation {
+    /// The orientation used by earlier versions of this library, in which the
+    /// texture on some faces appears rotated or mirrored relative to others.
+    Original,
+    /// The u axis increases to the right and the v axis increases upwards when
+    /// viewing each face from outside the cuboid with the y-axis pointing up.
+    /// The +Y face takes the negative z-axis as up, and the -Y face takes the
+    /// positive z-axis as up, as though the cuboid were tipped towards or away
+    /// from the viewer.
+    Upright,
+}
+
+/// The diagonal along which each quad of a `Cuboid` is split into a pair of
+/// triangles, in the frame of its texture coordinates (with the u axis
+/// increasing to the right, and the v axis increasing upwards).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Diagonal {
+    /// Each quad is split from its top-left corner to its bottom-right corner.
+    TopLeftToBottomRight,
+    /// Each quad is split from its bottom-left corner to its top-right corner.
+    BottomLeftToTopRight,
+    /// The quads of each face alternate between the two diagonals in a
+    /// checkerboard pattern, starting from a top-left to bottom-right split
+    /// at the first corner of the face.
+    Alternating,
+}
+
+/// A face of a `Cuboid`.
+///
+/// The faces are built in the order in which they are declared, which is
+/// guaranteed to remain stable.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Face {
+    /// The face whose normal points along the negative x axis.
+    NegX,
+    /// The face whose normal points along the positive x axis.
+    PosX,
+    /// The face whose normal points along the negative y axis.
+    NegY,
+    /// The face whose normal points along the positive y axis.
+    PosY,
+    /// The face whose normal points along the negative z axis.
+    NegZ,
+    /// The face whose normal points along the positive z axis.
+    PosZ,
+}
+
+/// Allows a `Face` to be used as the set of that face alone.
+impl From<Face> for FaceMask {
+    fn from(face: Face) -> FaceMask {
+        FaceMask(1 << (face as usize))
+    }
+}
+
+/// A set of the faces of a `Cuboid`.
+///
+/// Masks may be combined using the bitwise operators, such that
+/// `FaceMask::ALL & !FaceMask::NEG_Y` is the set of every face besides the
+/// bottom face.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct FaceMask(u8);
+
+impl FaceMask {
+    /// The face whose normal points along the negative x axis.
+    pub const NEG_X: FaceMask = FaceMask(1 << 0);
+    /// The face whose normal points along the positive x axis.
+    pub const POS_X: FaceMask = FaceMask(1 << 1);
+    /// The face whose normal points along the negative y axis.
+    pub const NEG_Y: FaceMask = FaceMask(1 << 2);
+    /// The face whose normal points along the positive y axis.
+    pub const POS_Y: FaceMask = FaceMask(1 << 3);
+    /// The face whose normal points along the negative z axis.
+    pub const NEG_Z: FaceMask = FaceMask(1 << 4);
+    /// The face whose normal points along the positive z axis.
+    pub const POS_Z: FaceMask = FaceMask(1 << 5);
+    /// The set of no faces.
+    pub const NONE: FaceMask = FaceMask(0);
+    /// The set of all six faces.
+    pub const ALL: FaceMask = FaceMask(0b11_1111);
+
+    /// Returns whether every face in `other` is also in this set.
+    pub fn contains(self, other: FaceMask) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns whether this set contains no faces.
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns whether this set contains a side, where the sides are numbered
+    /// in the order -X, +X, -Y, +Y, -Z, +Z.
+    fn contains_side(self, side: usize) -> bool {
+        self.0 & (1 << side) != 0
+    }
+}
+
+impl std::ops::BitOr for FaceMask {
+    type Output = FaceMask;
+    fn bitor(self, other: FaceMask) -> FaceMask {
+        FaceMask(self.0 | other.0)
+    }
+}
+
+impl std::ops::BitOrAssign for FaceMask {
+    fn bitor_assign(&mut self, other: FaceMask) {
+        self.0 |= other.0;
+    }
+}
+
+impl std::ops::BitAnd for FaceMask {
+    type Output = FaceMask;
+    fn bitand(self, other: FaceMask) -> FaceMask {
+        FaceMask(self.0 & other.0)
+    }
+}
+
+impl std::ops::BitAndAssign for FaceMask {
+    fn bitand_assign(&mut self, other: FaceMask) {
+        self.0 &= other.0;
+    }
+}
+
+impl std::ops::Not for FaceMask {
+    type Output = FaceMask;
+    fn not(self) -> FaceMask {
+        FaceMask(!self.0 & FaceMask::ALL.0)
+    }
 }
 
 impl Default for CuboidBuilder {
     fn default() -> Self {
         CuboidBuilder {
             matrix: cgmath::Matrix4::<f32>::identity(),
+            subdivisions: [1, 1, 1],
+            uv_layout: UvLayout::PerFace,
+            faces: FaceMask::ALL,
+            half_extents: Vector3::<f32>::from_value(0.5),
+            chamfer: 0.0,
+            inverted: false,
+            face_colors: None,
+            uv_tiling: None,
+            uv_orientation: UvOrientation::Original,
+            diagonal: Diagonal::TopLeftToBottomRight,
+            options: ShapeOptions::default(),
         }
     }
 }
@@ -59,74 +310,143 @@ impl CuboidBuilder {
         Default::default()
     }
 
-    /// Apply a scaling transformation to the shape.
+    /// Create a new `CuboidBuilder` object, whose cuboid spans the axis-aligned
+    /// box with the given opposite corners.
     ///
-    /// The `scale`, `translate`, and `rotate` functions accumulate, and are
-    /// not commutative. The transformation functions are intended to provide
-    /// flexibility in model-space. For per-instance world-space transformations,
-    /// one should prefer to share as few shapes as possible across multiple
-    /// instances, and instead rely on uniform constants in the shader and/or
-    /// instanced drawing.
-    pub fn scale(mut self, x: f32, y: f32, z: f32) -> Self {
-        self.matrix = cgmath::Matrix4::from_nonuniform_scale(x, y, z) * self.matrix;
-        self
+    /// The components of the corners may be given in any order, such that
+    /// `min` need not be less than `max`, and may be equal along an axis to
+    /// build a flat box. Subsequent transformations are applied on top of the
+    /// box.
+    pub fn from_corners(min: impl Into<Point3<f32>>, max: impl Into<Point3<f32>>) -> CuboidBuilder {
+        let (min, max) = (min.into(), max.into());
+        let lower = Point3::<f32>::new(min.x.min(max.x), min.y.min(max.y), min.z.min(max.z));
+        let upper = Point3::<f32>::new(min.x.max(max.x), min.y.max(max.y), min.z.max(max.z));
+        CuboidBuilder::with_extents(lower.midpoint(upper), (upper - lower) / 2.0)
     }
 
-    /// Apply a translation transformation to the shape.
+    /// Create a new `CuboidBuilder` object, whose cuboid is centred on `center`
+    /// and extends by `half_extents` in either direction along each axis.
     ///
-    /// The `scale`, `translate`, and `rotate` functions accumulate, and are
-    /// not commutative. The transformation functions are intended to provide
-    /// flexibility in model-space. For per-instance world-space transformations,
-    /// one should prefer to share as few shapes as possible across multiple
-    /// instances, and instead rely on uniform constants in the shader and/or
-    /// instanced drawing.
-    pub fn translate(mut self, x: f32, y: f32, z: f32) -> Self {
-        self.matrix = cgmath::Matrix4::from_translation([x, y, z].into()) * self.matrix;
+    /// Each of the half-extents must not be negative to build a valid cuboid,
+    /// and a half-extent of zero builds a flat box. Subsequent transformations are applied on top of the box.
+    pub fn with_extents(
+        center: impl Into<Point3<f32>>,
+        half_extents: impl Into<Vector3<f32>>,
+    ) -> CuboidBuilder {
+        let center = center.into();
+        CuboidBuilder {
+            half_extents: half_extents.into(),
+            ..CuboidBuilder::new()
+        }
+        .translate(center.x, center.y, center.z)
+    }
+
+    /// Specify the way in which the texture coordinates are laid out. By
+    /// default, each face maps to the whole of the unit square.
+    pub fn uv_layout(mut self, uv_layout: UvLayout) -> Self {
+        self.uv_layout = uv_layout;
         self
     }
 
-    /// Apply a rotation transformation to the shape about the x-axis.
-    ///
-    /// The `scale`, `translate`, and `rotate` functions accumulate, and are
-    /// not commutative. The transformation functions are intended to provide
-    /// flexibility in model-space. For per-instance world-space transformations,
-    /// one should prefer to share as few shapes as possible across multiple
-    /// instances, and instead rely on uniform constants in the shader and/or
-    /// instanced drawing.
-    pub fn rotate_x(mut self, radians: f32) -> Self {
-        self.matrix = cgmath::Matrix4::<f32>::from(cgmath::Matrix3::<f32>::from_angle_x(
-            cgmath::Rad::<f32>(radians),
-        )) * self.matrix;
+    /// Specify the set of faces to build. The faces are always built in the
+    /// order -X, +X, -Y, +Y, -Z, +Z, omitting any face that is not in the set.
+    /// By default, all six faces are built. An empty set builds no vertices.
+    pub fn faces(mut self, faces: FaceMask) -> Self {
+        self.faces = faces;
         self
     }
 
-    /// Apply a rotation transformation to the shape about the y-axis.
-    ///
-    /// The `scale`, `translate`, and `rotate` functions accumulate, and are
-    /// not commutative. The transformation functions are intended to provide
-    /// flexibility in model-space. For per-instance world-space transformations,
-    /// one should prefer to share as few shapes as possible across multiple
-    /// instances, and instead rely on uniform constants in the shader and/or
-    /// instanced drawing.
-    pub fn rotate_y(mut self, radians: f32) -> Self {
-        self.matrix = cgmath::Matrix4::<f32>::from(cgmath::Matrix3::<f32>::from_angle_y(
-            cgmath::Rad::<f32>(radians),
-        )) * self.matrix;
+    /// Specify the width by which each edge and corner of the cuboid is
+    /// bevelled with a flat chamfer. Each side is inset by the width from its
+    /// edges, such that the edges become thin rectangles and the corners
+    /// become small triangles. By default, the cuboid is not chamfered. The
+    /// width must be non-negative and less than half the smallest extent of
+    /// the cuboid to build a valid cuboid.
+    pub fn chamfer(mut self, width: f32) -> Self {
+        self.chamfer = width;
         self
     }
 
-    /// Apply a rotation transformation to the shape about the z-axis.
-    ///
-    /// The `scale`, `translate`, and `rotate` functions accumulate, and are
-    /// not commutative. The transformation functions are intended to provide
-    /// flexibility in model-space. For per-instance world-space transformations,
-    /// one should prefer to share as few shapes as possible across multiple
-    /// instances, and instead rely on uniform constants in the shader and/or
-    /// instanced drawing.
-    pub fn rotate_z(mut self, radians: f32) -> Self {
-        self.matrix = cgmath::Matrix4::<f32>::from(cgmath::Matrix3::<f32>::from_angle_z(
-            cgmath::Rad::<f32>(radians),
-        )) * self.matrix;
+    /// Turn the cuboid inside out, such that its faces are front-facing when
+    /// viewed from inside the cuboid. The winding of every triangle is
+    /// reversed and the normals point inwards, whilst the positions and
+    /// texture coordinates are unchanged.
+    pub fn invert(mut self) -> Self {
+        self.inverted = true;
+        self
+    }
+
+    /// Specify the orientation of the texture coordinates on each face. By
+    /// default, the original orientation is used, such that existing textures
+    /// continue to map as before.
+    pub fn uv_orientation(mut self, uv_orientation: UvOrientation) -> Self {
+        self.uv_orientation = uv_orientation;
+        self
+    }
+
+    /// Specify the diagonal along which each quad is split into a pair of
+    /// triangles. By default, each quad is split from its top-left corner to
+    /// its bottom-right corner.
+    pub fn diagonal(mut self, diagonal: Diagonal) -> Self {
+        self.diagonal = diagonal;
+        self
+    }
+
+    /// Specify that the texture coordinates of each face are scaled by the
+    /// dimensions of the face once transformed, such that a texture repeats
+    /// `texels_per_unit` times per unit of length in either direction,
+    /// regardless of how the cuboid is stretched. For example, a 4x1 face
+    /// spans 0..4 by 0..1 with one repetition per unit. The dimensions include
+    /// the effect of all transformations applied to the builder. By default,
+    /// each face maps to the unit square, and tiling ignores the UV layout.
+    pub fn uv_tiling_per_unit(mut self, texels_per_unit: f32) -> Self {
+        self.uv_tiling = Some(texels_per_unit);
+        self
+    }
+
+    /// Specify the RGBA color of each face, in the order -X, +X, -Y, +Y, -Z,
+    /// +Z. By default, the faces are not colored, and the cuboid is built from
+    /// `Vertex` objects. Once colored, the cuboid is built from `VertexColored`
+    /// objects, whose color is constant across each face.
+    pub fn face_colors(mut self, colors: [[f32; 4]; 6]) -> Self {
+        self.options.colors = None;
+        self.face_colors = Some(FaceColors::Faces(colors));
+        self
+    }
+
+    /// Color each vertex by its position prior to transformation, such that
+    /// the red, green, and blue components increase from 0 to 1 across the
+    /// cuboid along the x, y, and z axes respectively, with an alpha of 1.
+    /// This is intended for debugging, as the orientation of the cuboid and
+    /// the interpolation of its colors are immediately visible. Once colored,
+    /// the cuboid is built from `VertexColored` objects.
+    pub fn corner_colors(mut self) -> Self {
+        self.options.colors = None;
+        self.face_colors = Some(FaceColors::Corners);
+        self
+    }
+
+    /// Color the faces with a preset intended for debugging, such that the -X,
+    /// +X, -Y, +Y, -Z, and +Z faces are red, cyan, green, magenta, blue, and
+    /// yellow respectively. Opposite faces take complementary colors.
+    pub fn face_colors_rainbow(self) -> Self {
+        self.face_colors([
+            [1.0, 0.0, 0.0, 1.0],
+            [0.0, 1.0, 1.0, 1.0],
+            [0.0, 1.0, 0.0, 1.0],
+            [1.0, 0.0, 1.0, 1.0],
+            [0.0, 0.0, 1.0, 1.0],
+            [1.0, 1.0, 0.0, 1.0],
+        ])
+    }
+
+    /// Specify the number of quads along the x, y, and z axes of the cuboid,
+    /// such that each face is subdivided into a grid (e.g. the +X face is
+    /// subdivided using the y and z counts). By default, each face is a single
+    /// quad. At least one quad along each axis is required to build a valid
+    /// cuboid.
+    pub fn subdivisions(mut self, x: usize, y: usize, z: usize) -> Self {
+        self.subdivisions = [x, y, z];
         self
     }
 
@@ -135,10 +455,99 @@ impl CuboidBuilder {
     where
         F: glium::backend::Facade,
     {
-        let vertices = &self.build_vertices()?;
-        let vbuffer = glium::vertex::VertexBuffer::<Vertex>::new(display, vertices)?;
-        Ok(Cuboid {
-            vertices: glium::vertex::VertexBufferAny::from(vbuffer),
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build a new `IndexedCuboid` object.
+    ///
+    /// The indexed cuboid shares the four corners of each face amongst its two
+    /// triangles, such that it is defined by 24 vertices and 36 indices rather
+    /// than 36 vertices. It is otherwise identical to the `Cuboid` object.
+    pub fn build_indexed<F>(self, display: &F) -> Result<IndexedCuboid, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        let (vertices, indices) = if self.options.lightmap_gutter.is_some() {
+            let (vertices, indices) = self.build_vertices_lightmapped_indexed()?;
+            let vbuffer =
+                glium::vertex::VertexBuffer::<VertexLightmapped>::new(display, &vertices)?;
+            (vbuffer.into(), indices)
+        } else if self.options.colors.is_some() || self.face_colors.is_some() {
+            let (vertices, indices) = self.build_vertices_colored_indexed()?;
+            let vbuffer = glium::vertex::VertexBuffer::<VertexColored>::new(display, &vertices)?;
+            (vbuffer.into(), indices)
+        } else {
+            let (vertices, indices) = self.build_vertices_indexed()?;
+            let vbuffer = glium::vertex::VertexBuffer::<Vertex>::new(display, &vertices)?;
+            (vbuffer.into(), indices)
+        };
+        let ibuffer = glium::index::IndexBuffer::<u16>::new(
+            display,
+            glium::index::PrimitiveType::TrianglesList,
+            &indices,
+        )?;
+        Ok(IndexedCuboid {
+            vertices,
+            indices: ibuffer,
+            face_ranges: self.face_ranges(),
+        })
+    }
+
+    /// Build a new `IndexedCuboid` object with a tangent at each vertex, for
+    /// normal mapping. Its vertices are of the `VertexTangent` type, and do not
+    /// carry the colors of the cuboid.
+    pub fn build_indexed_with_tangents<F>(
+        self,
+        display: &F,
+    ) -> Result<IndexedCuboid, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        let (vertices, indices) = self.build_vertices_with_tangents_indexed()?;
+        let vbuffer = glium::vertex::VertexBuffer::<VertexTangent>::new(display, &vertices)?;
+        let ibuffer = glium::index::IndexBuffer::<u16>::new(
+            display,
+            glium::index::PrimitiveType::TrianglesList,
+            &indices,
+        )?;
+        Ok(IndexedCuboid {
+            vertices: vbuffer.into(),
+            indices: ibuffer,
+            face_ranges: self.face_ranges(),
+        })
+    }
+
+    /// Build a new `AabbWire` object along the 12 edges of the cuboid.
+    ///
+    /// The wireframe follows the transformations applied to the builder, such
+    /// that it may be drawn over the cuboid, but ignores the chamfers and
+    /// subdivisions of its faces. Its vertices are laid out as described for
+    /// the `AabbWireBuilder` object.
+    pub fn build_wireframe<F>(self, display: &F) -> Result<AabbWire, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        self.validate()?;
+        self.wireframe().build(display)
+    }
+
+    /// Build the wireframe vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// an `AabbWire` object (e.g. unit testing, further processing, etc).
+    pub fn build_wireframe_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.validate()?;
+        self.wireframe().build_vertices()
+    }
+
+    /// Build the shape vertices and indices and return them in a pair of
+    /// vectors.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// an `IndexedCuboid` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices_indexed(&self) -> Result<(Vec<Vertex>, Vec<u16>), ShapeCreationError> {
+        self.emit_indexed::<Selected, _, _>(|placement, piece, corner, back| {
+            self.vertex(placement, piece, corner, back)
         })
     }
 
@@ -147,152 +556,2636 @@ impl CuboidBuilder {
     /// Useful if you wish to do other things with the vertices besides constructing
     /// a `Cuboid` object (e.g. unit testing, further processing, etc).
     pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
-        // Define lookup-tables used during construction of the cuboid geometry
-        let index_lut = [
-            0, 4, 1, 5, // -X
-            6, 2, 7, 3, // +X
-            0, 2, 4, 6, // -Y
-            5, 7, 1, 3, // +Y
-            2, 0, 3, 1, // -Z
-            4, 6, 5, 7, // +Z
-        ];
-        let poly_lut = [0, 1, 2, 2, 1, 3];
-        let num_sides = 6;
-        let verts_per_side = 6;
-
-        // Compute the normal transformation matrix.
-        let normal_matrix = Matrix3::<f32>::from_cols(
-            self.matrix.x.truncate(),
-            self.matrix.y.truncate(),
-            self.matrix.z.truncate(),
+        self.vertices::<Selected>()
+    }
+
+    /// Build the shape vertices, transformed by the given math.
+    fn vertices<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.options.check_shading(Self::SHADINGS)?;
+
+        self.emit::<M, _, _>(
+            |placement, piece, corner, back| self.vertex(placement, piece, corner, back),
+            |vertex| &mut vertex.texcoord,
         )
-        .invert()
-        .unwrap_or_else(Matrix3::<f32>::identity)
-        .transpose();
-
-        // Generate cuboid vertices.
-        let mut vertices = Vec::<Vertex>::with_capacity(verts_per_side * num_sides);
-
-        for side in 0..num_sides {
-            // Compute side normal.
-            let mut normal = Vector3::<f32>::new(0.0, 0.0, 0.0);
-            normal[side / 2] = (((side % 2) * 2) as f32) - 1.0;
-
-            // Build side vertices.
-            for vert in 0..verts_per_side {
-                let coord = index_lut[poly_lut[vert] + (side * 4)];
-                let vpos = Vector4::<f32>::new(
-                    (((coord & 2) - 1) as f32) * 0.5,
-                    (((coord & 1) * 2 - 1) as f32) * 0.5,
-                    ((((coord >> 1) & 2) - 1) as f32) * 0.5,
-                    1.0,
-                );
-                vertices.push(Vertex {
-                    position: Point3::<f32>::from_homogeneous(self.matrix * vpos).into(),
-                    normal: (normal_matrix * normal).normalize().into(),
-                    texcoord: [(poly_lut[vert] % 2) as f32, (poly_lut[vert] / 2) as f32],
-                });
+    }
+
+    /// Build the shape vertices with their colors and indices and return them
+    /// in a pair of vectors. If the cuboid has not been colored, every vertex
+    /// is white.
+    pub fn build_vertices_colored_indexed(
+        &self,
+    ) -> Result<(Vec<VertexColored>, Vec<u16>), ShapeCreationError> {
+        self.emit_indexed::<Selected, _, _>(|placement, piece, corner, back| {
+            self.vertex_colored(placement, piece, corner, back)
+        })
+    }
+
+    /// Build the shape vertices with their lightmap texture coordinates and
+    /// indices and return them in a pair of vectors. The vertices are shared
+    /// within each face, such that each takes the lightmap texture coordinates
+    /// of its own face.
+    pub fn build_vertices_lightmapped_indexed(
+        &self,
+    ) -> Result<(Vec<VertexLightmapped>, Vec<u16>), ShapeCreationError> {
+        let (vertices, indices) = self.build_vertices_indexed()?;
+        let gutter = self.options.lightmap_gutter.unwrap_or(0.0);
+        Ok((
+            with_indexed_lightmap_uvs(&vertices, &indices, gutter)?,
+            indices,
+        ))
+    }
+
+    /// Build the shape vertices with their tangents and indices and return them
+    /// in a pair of vectors.
+    pub fn build_vertices_with_tangents_indexed(
+        &self,
+    ) -> Result<(Vec<VertexTangent>, Vec<u16>), ShapeCreationError> {
+        let (vertices, indices) = self.build_vertices_indexed()?;
+        Ok((with_indexed_tangents(&vertices, &indices), indices))
+    }
+
+    /// Returns the total number of vertices that will be generated by the
+    /// `build_vertices` method, which is also the number of indices generated
+    /// by the `build_vertices_indexed` method.
+    pub fn num_vertices(&self) -> usize {
+        self.pieces()
+            .iter()
+            .map(|piece| piece.indices.len())
+            .sum::<usize>()
+            * self.options.copies()
+    }
+
+    /// Returns the range of vertices generated by the `build_vertices` method
+    /// that define the given face, which is also the range of indices
+    /// generated by the `build_vertices_indexed` method.
+    ///
+    /// The faces are built first, in the order in which they are declared by
+    /// `Face`, followed by any chamfers, and then by the back sides of the
+    /// faces and chamfers when the cuboid is double-sided. The range of a face
+    /// that is omitted from the cuboid is empty. The ranges cover the first
+    /// copy made by any arrays, and the faces of each subsequent copy follow
+    /// in the same order.
+    pub fn face_range(&self, face: Face) -> Range<usize> {
+        self.face_ranges()[face as usize].clone()
+    }
+
+    /// Returns the ranges of vertices that define each face.
+    fn face_ranges(&self) -> [Range<usize>; 6] {
+        let pieces = self.pieces();
+        let mut start = 0;
+        [0, 1, 2, 3, 4, 5].map(|side| {
+            let count = pieces
+                .iter()
+                .find(|piece| piece.side == Some(side))
+                .map_or(0, |piece| piece.indices.len());
+            start += count;
+            (start - count)..start
+        })
+    }
+
+    /// Returns the total number of vertices that will be generated by the
+    /// `build_vertices_indexed` method.
+    pub fn num_vertices_indexed(&self) -> usize {
+        self.pieces()
+            .iter()
+            .map(|piece| piece.corners.len())
+            .sum::<usize>()
+            * self.options.copies()
+    }
+
+    /// Returns whether each side of the faces that is built is the back side,
+    /// in the order in which the sides are built.
+    fn sides(&self) -> &'static [bool] {
+        if self.options.double_sided {
+            &[false, true]
+        } else {
+            &[false]
+        }
+    }
+
+    /// Returns the indices of a triangle of a piece, in reversed winding when
+    /// building either the back side of the faces or clock-wise front faces,
+    /// and once more when the copy is reflected (by a mirror, or by converting
+    /// into a left-handed convention).
+    fn triangle_winding<M: Math>(
+        &self,
+        placement: &Placement<M>,
+        triangle: &[usize],
+        back: bool,
+    ) -> [usize; 3] {
+        if back ^ (self.options.winding == Winding::Cw) ^ placement.reflected {
+            [triangle[0], triangle[2], triangle[1]]
+        } else {
+            [triangle[0], triangle[1], triangle[2]]
+        }
+    }
+
+    /// Returns a builder for the wireframe of the edges of the cuboid.
+    fn wireframe(&self) -> AabbWireBuilder {
+        AabbWireBuilder::new()
+            .corners(
+                Point3::from_vec(-self.half_extents),
+                Point3::from_vec(self.half_extents),
+            )
+            .transform(self.options.convention.matrix() * self.matrix)
+    }
+
+    /// Generate the vertices of the triangles of each piece of the cuboid.
+    ///
+    /// When a texture coordinate projection is given, it is computed from the
+    /// front sides of the faces prior to any jitter, and the back side of each
+    /// triangle takes the texture coordinates of its front side.
+    fn emit<M: Math, V, F>(
+        &self,
+        vertex: F,
+        texcoord: fn(&mut V) -> &mut [f32; 2],
+    ) -> Result<Vec<V>, ShapeCreationError>
+    where
+        F: Fn(&Placement<M>, &Piece, Corner, bool) -> V,
+    {
+        self.validate()?;
+
+        let pieces = self.pieces();
+        let texcoords = self.options.uv_mapping.map(|uv_mapping| {
+            let matrix = M::mat4(self.matrix);
+            let positions = self
+                .surface(false)
+                .iter()
+                .flat_map(|piece| {
+                    piece.indices.iter().map(move |index| {
+                        let position = piece.corners[*index].0.into();
+                        Vector3::from(M::transform_point(&matrix, position))
+                    })
+                })
+                .collect::<Vec<Vector3<f32>>>();
+            uv_mapping.texcoords(&positions)
+        });
+
+        let mut vertices = Vec::<V>::with_capacity(self.num_vertices());
+        for placement in self.placements::<M>()?.iter() {
+            for &back in self.sides() {
+                let mut start = 0;
+                for piece in pieces.iter() {
+                    for triangle in piece.indices.chunks(3) {
+                        for slot in self.triangle_winding(placement, &[0, 1, 2], back) {
+                            let corner = piece.corners[triangle[slot]];
+                            let mut v = vertex(placement, piece, corner, back);
+                            if let Some(texcoords) = &texcoords {
+                                *texcoord(&mut v) = transform_texcoord(
+                                    &self.options.uv_matrix,
+                                    texcoords[start + slot],
+                                );
+                            }
+                            vertices.push(v);
+                        }
+                        start += 3;
+                    }
+                }
             }
         }
 
+        assert!(vertices.len() == self.num_vertices());
         Ok(vertices)
     }
-}
 
-#[test]
-pub fn ensure_default_cuboid_has_unit_dimensions() {
-    let vertices = CuboidBuilder::new()
-        .build_vertices()
-        .expect("Failed to build vertices");
-    for vertex in &vertices {
-        assert!(abs_diff_eq!(vertex.position[0].abs(), 0.5));
-        assert!(abs_diff_eq!(vertex.position[1].abs(), 0.5));
-        assert!(abs_diff_eq!(vertex.position[2].abs(), 0.5));
+    /// Generate the corners of each piece of the cuboid, and the indices of
+    /// the triangles that form it.
+    fn emit_indexed<M: Math, V, F>(
+        &self,
+        vertex: F,
+    ) -> Result<(Vec<V>, Vec<u16>), ShapeCreationError>
+    where
+        F: Fn(&Placement<M>, &Piece, Corner, bool) -> V,
+    {
+        self.validate()?;
+
+        // The texture coordinate projections may differ between the triangles
+        // that share a corner.
+        self.options.check_uv_mapping()?;
+
+        // Ensure that every vertex may be addressed by a 16-bit index.
+        if self.num_vertices_indexed() > (u16::MAX as usize) + 1 {
+            return Err(ShapeCreationError::TooManySubdivisions);
+        }
+
+        let mut vertices = Vec::<V>::with_capacity(self.num_vertices_indexed());
+        let mut indices = Vec::<u16>::with_capacity(self.num_vertices());
+        let pieces = self.pieces();
+        for placement in self.placements::<M>()?.iter() {
+            for &back in self.sides() {
+                for piece in pieces.iter() {
+                    let base = vertices.len();
+                    for corner in piece.corners.iter() {
+                        vertices.push(vertex(placement, piece, *corner, back));
+                    }
+                    for triangle in piece.indices.chunks(3) {
+                        let winding = self.triangle_winding(placement, triangle, back);
+                        indices.extend(winding.iter().map(|index| (base + index) as u16));
+                    }
+                }
+            }
+        }
+
+        assert!(vertices.len() == self.num_vertices_indexed());
+        assert!(indices.len() == self.num_vertices());
+        Ok((vertices, indices))
     }
-}
 
-#[test]
-pub fn ensure_default_cuboid_has_centroid_at_origin() {
-    let vertices = CuboidBuilder::new()
-        .build_vertices()
-        .expect("Failed to build vertices");
-    let mut sum = Vector3::<f32>::zero();
-    for vertex in &vertices {
-        sum += Vector3::<f32>::from(vertex.position);
+    /// Ensure the builder parameters produce a valid cuboid.
+    fn validate(&self) -> Result<(), ShapeCreationError> {
+        if self.subdivisions.contains(&0) {
+            return Err(ShapeCreationError::InvalidDimensions);
+        }
+        if !(0..3).all(|axis| self.half_extents[axis] >= 0.0 && self.half_extents[axis].is_finite())
+        {
+            return Err(ShapeCreationError::InvalidDimensions);
+        }
+        let smallest = self
+            .half_extents
+            .x
+            .min(self.half_extents.y)
+            .min(self.half_extents.z);
+        if !(self.chamfer == 0.0 || self.chamfer > 0.0 && self.chamfer < smallest) {
+            return Err(ShapeCreationError::InvalidDimensions);
+        }
+        Ok(())
     }
-    assert_eq!(sum, Vector3::<f32>::zero());
-}
 
-#[test]
-pub fn ensure_default_cuboid_has_outward_facing_normals() {
-    let vertices = CuboidBuilder::new()
-        .scale(2.0, 2.0, 2.0)
-        .build_vertices()
-        .expect("Failed to build vertices");
-    for vertex in &vertices {
-        let position = Vector3::<f32>::from(vertex.position);
-        let normal = Vector3::<f32>::from(vertex.normal);
-        let outside = position + normal;
-        assert!(outside.x.abs() >= position.x.abs());
-        assert!(outside.y.abs() >= position.y.abs());
-        assert!(outside.z.abs() >= position.z.abs());
+    /// Returns the untransformed pieces of the surface of the cuboid, in the
+    /// order in which they are built.
+    ///
+    /// The sides are built first, in the order -X, +X, -Y, +Y, -Z, +Z, followed
+    /// by the chamfers of the edges and corners where they are bevelled. The
+    /// coordinate along each axis is computed from its index counted from the
+    /// negative end of that axis, such that the pieces that meet at an edge
+    /// compute bitwise identical positions along it.
+    fn pieces(&self) -> Vec<Piece> {
+        self.surface(true)
     }
-}
 
-#[test]
-pub fn ensure_default_cuboid_has_uvs_in_unit_range() {
-    use std::f32;
-    let vertices = CuboidBuilder::new()
-        .build_vertices()
-        .expect("Failed to build vertices");
-    let mut min = Vector2::<f32>::new(f32::MAX, f32::MAX);
-    let mut max = -min;
-    for vertex in &vertices {
-        min.x = f32::min(min.x, vertex.texcoord[0]);
-        min.y = f32::min(min.y, vertex.texcoord[1]);
-        max.x = f32::max(max.x, vertex.texcoord[0]);
-        max.y = f32::max(max.y, vertex.texcoord[1]);
+    /// Returns the untransformed pieces of the surface of the cuboid, which
+    /// are displaced by the jitter of the cuboid if requested. The pieces that
+    /// are not displaced are not split into their triangles, but their
+    /// triangles are built in the same order.
+    fn surface(&self, displaced: bool) -> Vec<Piece> {
+        let displace = |pieces: Vec<Piece>| {
+            if displaced {
+                self.displace(pieces)
+            } else {
+                pieces
+            }
+        };
+        let min = -self.half_extents;
+        let max = self.half_extents;
+        let chamfer = self.chamfer;
+        let coordinate = |axis: usize, index: usize| {
+            let count = self.subdivisions[axis];
+            let lo = min[axis] + chamfer;
+            let hi = max[axis] - chamfer;
+            if index == count {
+                hi
+            } else {
+                lo + (hi - lo) * (index as f32) / (count as f32)
+            }
+        };
+        let sign = |side: usize| (((side % 2) * 2) as f32) - 1.0;
+        let bound = |side: usize| {
+            if side % 2 != 0 {
+                max[side / 2]
+            } else {
+                min[side / 2]
+            }
+        };
+        let end = |side: usize| (side % 2) * self.subdivisions[side / 2];
+        let mut pieces = Vec::new();
+
+        // Build the sides as grids of quads.
+        for side in (0..6).filter(|side| self.faces.contains_side(*side)) {
+            // Find the axes along which the u and v directions of the side run,
+            // and whether they run towards the positive end of those axes.
+            let quad = face_quad(side, min, max);
+            let unit = face_quad(side, -Vector3::from_value(1.0), Vector3::from_value(1.0));
+            let find = |corner: Vector3<f32>| {
+                let axis = (0..3)
+                    .find(|axis| corner[*axis] != unit[0].0[*axis])
+                    .unwrap();
+                (axis, corner[axis] > unit[0].0[axis])
+            };
+            let (u_axis, u_forward) = find(unit[1].0);
+            let (v_axis, v_forward) = find(unit[2].0);
+            let nu = self.subdivisions[u_axis];
+            let nv = self.subdivisions[v_axis];
+
+            let mut corners = Vec::with_capacity((nu + 1) * (nv + 1));
+            for j in 0..=nv {
+                for i in 0..=nu {
+                    let mut index = [0; 3];
+                    index[u_axis] = if u_forward { i } else { nu - i };
+                    index[v_axis] = if v_forward { j } else { nv - j };
+                    let mut position = quad[0].0;
+                    position[u_axis] = coordinate(u_axis, index[u_axis]);
+                    position[v_axis] = coordinate(v_axis, index[v_axis]);
+                    let texcoord = match self.uv_orientation {
+                        UvOrientation::Original => [i as f32 / nu as f32, j as f32 / nv as f32],
+                        UvOrientation::Upright => {
+                            // The axes along which the u and v directions of
+                            // each side run, and whether they run towards the
+                            // positive end of those axes.
+                            let upright = [
+                                [(2, true), (1, true)],
+                                [(2, false), (1, true)],
+                                [(0, true), (2, true)],
+                                [(0, true), (2, false)],
+                                [(0, false), (1, true)],
+                                [(0, true), (1, true)],
+                            ];
+                            let fraction = |(axis, forward): (usize, bool)| {
+                                let count = self.subdivisions[axis];
+                                let index = if forward {
+                                    index[axis]
+                                } else {
+                                    count - index[axis]
+                                };
+                                index as f32 / count as f32
+                            };
+                            [fraction(upright[side][0]), fraction(upright[side][1])]
+                        }
+                    };
+                    corners.push((position, texcoord));
+                }
+            }
+            pieces.push(Piece {
+                normal: face_normal(side),
+                side: Some(side),
+                indices: grid_indices(nu, nv, &corners, self.diagonal),
+                corners,
+            });
+        }
+        if chamfer == 0.0 {
+            return self.tile(self.orient(displace(pieces)));
+        }
+
+        // Build the chamfer of each edge between a pair of sides, as a strip
+        // of quads that runs along the edge.
+        for axis in 0..3 {
+            let (b, c) = ((axis + 1) % 3, (axis + 2) % 3);
+            for side_b in (b * 2)..(b * 2 + 2) {
+                for side_c in (c * 2)..(c * 2 + 2) {
+                    if !self.faces.contains_side(side_b) || !self.faces.contains_side(side_c) {
+                        continue;
+                    }
+                    // The first row lies on the edge of one side, and the
+                    // second on the edge of the other, ordered such that the
+                    // triangles wind counter-clockwise.
+                    let mut rows = [(side_b, side_c), (side_c, side_b)];
+                    if sign(side_b) * sign(side_c) > 0.0 {
+                        rows.swap(0, 1);
+                    }
+                    let count = self.subdivisions[axis];
+                    let mut corners = Vec::with_capacity((count + 1) * 2);
+                    for (j, (on, across)) in rows.iter().enumerate() {
+                        for i in 0..=count {
+                            let mut position = Vector3::<f32>::zero();
+                            position[axis] = coordinate(axis, i);
+                            position[on / 2] = bound(*on);
+                            position[across / 2] = coordinate(across / 2, end(*across));
+                            corners.push((position, [i as f32 / count as f32, j as f32]));
+                        }
+                    }
+                    pieces.push(Piece {
+                        normal: (face_normal(side_b) + face_normal(side_c)).normalize(),
+                        side: None,
+                        indices: grid_indices(count, 1, &corners, self.diagonal),
+                        corners,
+                    });
+                }
+            }
+        }
+
+        // Build the chamfer of each corner between three sides, as a single
+        // triangle.
+        for corner in 0..8 {
+            let sides = [corner & 1, 2 + ((corner >> 1) & 1), 4 + ((corner >> 2) & 1)];
+            if !sides.iter().all(|side| self.faces.contains_side(*side)) {
+                continue;
+            }
+            let mut corners = Vec::with_capacity(3);
+            for (on, texcoord) in sides
+                .iter()
+                .zip([[0.0, 0.0], [1.0, 0.0], [0.5, 1.0]].iter())
+            {
+                let mut position = Vector3::<f32>::zero();
+                for side in sides.iter() {
+                    position[side / 2] = if side == on {
+                        bound(*side)
+                    } else {
+                        coordinate(side / 2, end(*side))
+                    };
+                }
+                corners.push((position, *texcoord));
+            }
+            // Each reflection of the positive corner reverses its winding.
+            if sides.iter().filter(|side| *side % 2 == 0).count() % 2 != 0 {
+                corners.swap(1, 2);
+            }
+            pieces.push(Piece {
+                normal: sides
+                    .iter()
+                    .map(|side| face_normal(*side))
+                    .sum::<Vector3<f32>>()
+                    .normalize(),
+                side: None,
+                corners,
+                indices: vec![0, 1, 2],
+            });
+        }
+        self.tile(self.orient(displace(pieces)))
     }
-    assert!(min == Vector2::<f32>::zero());
-    assert!(max == Vector2::<f32>::from_value(1.0));
-}
 
-#[test]
-pub fn ensure_default_cuboid_has_ccw_triangles() {
-    let vertices = CuboidBuilder::new()
-        .build_vertices()
-        .expect("Failed to build vertices");
-    for chunk in vertices.chunks(3) {
-        let v0 = Vector3::<f32>::from(chunk[0].position);
-        let v1 = Vector3::<f32>::from(chunk[1].position);
-        let v2 = Vector3::<f32>::from(chunk[2].position);
-        let eyepos = v0 + Vector3::<f32>::from(chunk[0].normal);
-        let e0 = v1 - v0;
-        let e1 = v2 - v0;
-        let n = e0.cross(e1);
-        assert!(n.dot(v0 - eyepos) <= 0.0);
-        assert!(n.dot(v1 - eyepos) <= 0.0);
-        assert!(n.dot(v2 - eyepos) <= 0.0);
+    /// Scale the texture coordinates of each piece by its transformed
+    /// dimensions if the cuboid tiles its texture coordinates.
+    fn tile(&self, mut pieces: Vec<Piece>) -> Vec<Piece> {
+        let scale = match self.uv_tiling {
+            Some(scale) => scale,
+            None => return pieces,
+        };
+        let transform =
+            |position: Vector3<f32>| self.matrix.transform_point(Point3::from_vec(position));
+        for piece in pieces.iter_mut() {
+            // Find the transformed directions in which the texture coordinates
+            // increase, from the edge that leaves the first corner of the
+            // piece, and the corner furthest from that edge in texture space.
+            let (p0, t0) = piece.corners[0];
+            let (p1, t1) = piece.corners[1];
+            let (u0, v0) = (t1[0] - t0[0], t1[1] - t0[1]);
+            let area = |t: [f32; 2]| (u0 * (t[1] - t0[1]) - (t[0] - t0[0]) * v0).abs();
+            let (p2, t2) = *piece
+                .corners
+                .iter()
+                .max_by(|a, b| area(a.1).total_cmp(&area(b.1)))
+                .unwrap();
+            let (d0, d1) = (transform(p1) - transform(p0), transform(p2) - transform(p0));
+            let (u1, v1) = (t2[0] - t0[0], t2[1] - t0[1]);
+            let det = u0 * v1 - u1 * v0;
+            let u_direction = (d0 * v1 - d1 * v0) / det;
+            let v_direction = (d1 * u0 - d0 * u1) / det;
+            let factor = [
+                u_direction.magnitude() * scale,
+                v_direction.magnitude() * scale,
+            ];
+            for corner in piece.corners.iter_mut() {
+                corner.1 = [corner.1[0] * factor[0], corner.1[1] * factor[1]];
+            }
+        }
+        pieces
     }
-}
 
-#[test]
-pub fn ensure_default_cuboid_has_faceted_normals() {
-    let vertices = CuboidBuilder::new()
-        .build_vertices()
-        .expect("Failed to build vertices");
-    for chunk in vertices.chunks(3) {
-        let v0 = Vector3::<f32>::from(chunk[0].position);
+    /// Split each piece into its triangles, and displace their corners, if
+    /// the cuboid is jittered. Each triangle takes the normal of its displaced
+    /// corners.
+    fn displace(&self, pieces: Vec<Piece>) -> Vec<Piece> {
+        let jitter = match self.options.jitter {
+            Some(jitter) if self.is_jittered() => jitter,
+            _ => return pieces,
+        };
+        let mut triangles = Vec::with_capacity(pieces.len());
+        for piece in pieces.iter() {
+            for triangle in piece.indices.chunks(3) {
+                let corners = triangle
+                    .iter()
+                    .map(|index| {
+                        let (position, texcoord) = piece.corners[*index];
+                        (position + jitter.offset(position), texcoord)
+                    })
+                    .collect::<Vec<Corner>>();
+                let normal = (corners[1].0 - corners[0].0).cross(corners[2].0 - corners[0].0);
+                triangles.push(Piece {
+                    normal: if normal.magnitude2() > 0.0 {
+                        normal.normalize()
+                    } else {
+                        piece.normal
+                    },
+                    side: piece.side,
+                    corners,
+                    indices: vec![0, 1, 2],
+                });
+            }
+        }
+        triangles
+    }
+
+    /// Returns whether the cuboid is displaced by a jitter.
+    fn is_jittered(&self) -> bool {
+        self.options
+            .jitter
+            .is_some_and(|jitter| jitter.amplitude != 0.0)
+    }
+
+    /// Reverse the winding of the triangles of each piece if the cuboid is
+    /// inverted.
+    fn orient(&self, mut pieces: Vec<Piece>) -> Vec<Piece> {
+        if self.inverted {
+            for piece in pieces.iter_mut() {
+                for triangle in piece.indices.chunks_mut(3) {
+                    triangle.swap(1, 2);
+                }
+            }
+        }
+        pieces
+    }
+
+    /// Compute the transformation of each copy of the cuboid, including the
+    /// conversion into the coordinate convention of the cuboid.
+    fn placements<M: Math>(&self) -> Result<Vec<Placement<M>>, ShapeCreationError> {
+        let copies = Modifier::matrices(&self.options.modifiers)?;
+        let matrix = M::mat4(self.matrix);
+        let normal_matrix = M::normal_matrix(matrix);
+
+        // The copies are rigid transformations, and the conversion is a
+        // rotation or reflection, such that their normal transformation
+        // matrices are their own linear parts, and they are reflections when
+        // their linear parts are.
+        let convention = M::mat4(self.options.convention.matrix());
+        Ok(copies
+            .into_iter()
+            .map(|copy| {
+                let copy = M::mat4(copy);
+                Placement {
+                    matrix: M::mul(M::mul(convention, copy), matrix),
+                    normal_matrix: M::mul3(
+                        M::mul3(M::linear(convention), M::linear(copy)),
+                        normal_matrix,
+                    ),
+                    reflected: (M::determinant(M::linear(copy)) < 0.0)
+                        ^ self.options.convention.is_left_handed(),
+                }
+            })
+            .collect())
+    }
+
+    /// Transform an untransformed corner of a piece of the cuboid into a vertex,
+    /// whose normal is flipped on the back side of the faces.
+    fn vertex<M: Math>(
+        &self,
+        placement: &Placement<M>,
+        piece: &Piece,
+        corner: Corner,
+        back: bool,
+    ) -> Vertex {
+        let (position, texcoord) = corner;
+        let normal = match self.options.shading {
+            Shading::Smooth if !self.is_jittered() => position.normalize(),
+            _ => piece.normal,
+        };
+        let normal = if self.inverted ^ back {
+            -normal
+        } else {
+            normal
+        };
+        let texcoord = match (self.uv_layout, piece.side, self.uv_tiling) {
+            (UvLayout::Cross, Some(side), None) => {
+                // The column and row of each side within the cross, which are
+                // counted from the bottom-left of the unit square.
+                let cells = [(2, 1), (0, 1), (1, 0), (1, 2), (1, 1), (3, 1)];
+                let (column, row) = cells[side];
+                [
+                    (column as f32 + texcoord[0]) / 4.0,
+                    (row as f32 + texcoord[1]) / 3.0,
+                ]
+            }
+            _ => texcoord,
+        };
+        let mut position = Point3::from(M::transform_point(&placement.matrix, position.into()));
+        let normal = Vector3::from(M::transform_normal(&placement.normal_matrix, normal.into()));
+        if self.options.normal_offset != 0.0 {
+            position += normal * self.options.normal_offset;
+        }
+        Vertex {
+            position: position.into(),
+            normal: if self.options.flip_normals {
+                -normal
+            } else {
+                normal
+            }
+            .into(),
+            texcoord: transform_texcoord(&self.options.uv_matrix, texcoord),
+        }
+    }
+
+    /// Transform an untransformed corner of a piece of the cuboid into a vertex
+    /// that carries its color.
+    ///
+    /// When the faces are colored, the color of a chamfer is the average of the
+    /// colors of the sides that meet at it.
+    fn vertex_colored<M: Math>(
+        &self,
+        placement: &Placement<M>,
+        piece: &Piece,
+        corner: Corner,
+        back: bool,
+    ) -> VertexColored {
+        let vertex = self.vertex(placement, piece, corner, back);
+        // The shared colors are cleared by the colors of the faces and corners,
+        // such that whichever was given last is used.
+        let color = match (self.options.colors, self.face_colors) {
+            (Some(Colors::Constant(color)), _) => color,
+            (Some(Colors::Gradient { axis, min, max }), _) => {
+                let axis = axis.index();
+                let extent = self.half_extents[axis];
+                if extent == 0.0 {
+                    min
+                } else {
+                    lerp(min, max, (corner.0[axis] + extent) / (extent * 2.0))
+                }
+            }
+            (None, Some(FaceColors::Faces(colors))) => {
+                let mut color = Vector4::<f32>::zero();
+                let mut count = 0.0;
+                for axis in 0..3 {
+                    if piece.normal[axis] != 0.0 {
+                        let side = axis * 2 + if piece.normal[axis] > 0.0 { 1 } else { 0 };
+                        color += Vector4::<f32>::from(colors[side]);
+                        count += 1.0;
+                    }
+                }
+                (color / count).into()
+            }
+            (None, Some(FaceColors::Corners)) => {
+                let position = corner.0;
+                let remap = |axis: usize| {
+                    let extent = self.half_extents[axis];
+                    if extent == 0.0 {
+                        0.0
+                    } else {
+                        (position[axis] / extent + 1.0) * 0.5
+                    }
+                };
+                [remap(0), remap(1), remap(2), 1.0]
+            }
+            (Some(Colors::Normals), _) => normal_color(vertex.normal),
+            (None, None) => [1.0; 4],
+        };
+        VertexColored {
+            position: vertex.position,
+            normal: vertex.normal,
+            texcoord: vertex.texcoord,
+            color,
+        }
+    }
+}
+
+/// The ways in which only the vertices of a `Cuboid` are colored.
+#[derive(Copy, Clone)]
+enum FaceColors {
+    /// Each face takes a constant color.
+    Faces([[f32; 4]; 6]),
+    /// Each vertex takes a color from its position.
+    Corners,
+}
+
+/// The transformation of a copy of a `Cuboid`, its normal transformation
+/// matrix, and whether the copy is reflected.
+struct Placement<M: Math> {
+    matrix: M::Mat4,
+    normal_matrix: M::Mat3,
+    reflected: bool,
+}
+
+/// A flat piece of the surface of a `Cuboid`, prior to transformation.
+struct Piece {
+    /// The outward normal of the piece.
+    normal: Vector3<f32>,
+    /// The side of the cuboid that the piece lies on, or `None` for a chamfer.
+    side: Option<usize>,
+    /// The corners of the piece.
+    corners: Vec<Corner>,
+    /// The indices of the corners that form the triangles of the piece.
+    indices: Vec<usize>,
+}
+
+/// Returns the indices of the triangles that form a grid of `nu` by `nv`
+/// quads, whose corners are ordered by row, with each quad split along the
+/// given diagonal of its texture coordinates.
+fn grid_indices(nu: usize, nv: usize, corners: &[Corner], diagonal: Diagonal) -> Vec<usize> {
+    let mut indices = Vec::with_capacity(nu * nv * QUAD_INDICES.len());
+    for j in 0..nv {
+        for i in 0..nu {
+            let quad = [
+                j * (nu + 1) + i,
+                j * (nu + 1) + i + 1,
+                (j + 1) * (nu + 1) + i,
+                (j + 1) * (nu + 1) + i + 1,
+            ];
+            let top_left_to_bottom_right = match diagonal {
+                Diagonal::TopLeftToBottomRight => true,
+                Diagonal::BottomLeftToTopRight => false,
+                Diagonal::Alternating => (i + j) % 2 == 0,
+            };
+
+            // The second and third corners of the quad are split by the
+            // top-left to bottom-right diagonal unless the texture coordinates
+            // of the quad are mirrored, in which case the first and fourth
+            // corners are.
+            let (a, b) = (corners[quad[1]].1, corners[quad[2]].1);
+            let mirrored = (a[0] - b[0]) * (a[1] - b[1]) > 0.0;
+            let pattern = if top_left_to_bottom_right != mirrored {
+                QUAD_INDICES
+            } else {
+                ALTERNATE_QUAD_INDICES
+            };
+            indices.extend(pattern.iter().map(|index| quad[*index]));
+        }
+    }
+    indices
+}
+
+/// Emit a single side of the axis-aligned box bounded by `min` and `max` as a
+/// pair of triangles.
+///
+/// The sides are numbered in the order -X, +X, -Y, +Y, -Z, +Z. Each vertex takes
+/// the normal of its side, and texture coordinates map the side to the unit
+/// square. The box may be flat along the axis of the side, such that this may
+/// also be used to emit axis-aligned rectangles.
+pub(crate) fn push_face<M: Math>(
+    vertices: &mut Vec<Vertex>,
+    transform: &Transformation<M>,
+    side: usize,
+    min: Vector3<f32>,
+    max: Vector3<f32>,
+) {
+    let normal = transform.normal(face_normal(side));
+    for (position, texcoord) in face_corners(side, min, max).iter() {
+        vertices.push(Vertex {
+            position: transform.point(*position),
+            normal,
+            texcoord: *texcoord,
+        });
+    }
+}
+
+/// Returns the normal of a side of an axis-aligned box, where the sides are
+/// numbered in the order -X, +X, -Y, +Y, -Z, +Z.
+pub(crate) fn face_normal(side: usize) -> Vector3<f32> {
+    let mut normal = Vector3::<f32>::new(0.0, 0.0, 0.0);
+    normal[side / 2] = (((side % 2) * 2) as f32) - 1.0;
+    normal
+}
+
+/// The untransformed position and texture coordinates of a corner of a side.
+type Corner = (Vector3<f32>, [f32; 2]);
+
+/// The order in which the four corners returned by `face_quad` form the pair
+/// of triangles of a side.
+pub(crate) const QUAD_INDICES: [usize; 6] = [0, 1, 2, 2, 1, 3];
+
+/// The order in which the four corners returned by `face_quad` form the pair
+/// of triangles of a side, when split along its other diagonal.
+const ALTERNATE_QUAD_INDICES: [usize; 6] = [0, 1, 3, 0, 3, 2];
+
+/// Returns the untransformed positions and texture coordinates of the six
+/// corners of the pair of triangles that form a side of the axis-aligned box
+/// bounded by `min` and `max`.
+pub(crate) fn face_corners(
+    side: usize,
+    min: Vector3<f32>,
+    max: Vector3<f32>,
+) -> [(Vector3<f32>, [f32; 2]); 6] {
+    let quad = face_quad(side, min, max);
+    let mut corners = [(Vector3::<f32>::zero(), [0.0; 2]); 6];
+    for (corner, index) in corners.iter_mut().zip(QUAD_INDICES.iter()) {
+        *corner = quad[*index];
+    }
+    corners
+}
+
+/// Returns the untransformed positions and texture coordinates of the four
+/// unique corners of a side of the axis-aligned box bounded by `min` and `max`.
+pub(crate) fn face_quad(
+    side: usize,
+    min: Vector3<f32>,
+    max: Vector3<f32>,
+) -> [(Vector3<f32>, [f32; 2]); 4] {
+    // Define lookup-table used during construction of the cuboid geometry
+    let index_lut = [
+        0, 4, 1, 5, // -X
+        6, 2, 7, 3, // +X
+        0, 2, 4, 6, // -Y
+        5, 7, 1, 3, // +Y
+        2, 0, 3, 1, // -Z
+        4, 6, 5, 7, // +Z
+    ];
+
+    // Build side corners.
+    let select = |bit: usize, axis: usize| if bit != 0 { max[axis] } else { min[axis] };
+    let mut corners = [(Vector3::<f32>::zero(), [0.0; 2]); 4];
+    for (vert, corner) in corners.iter_mut().enumerate() {
+        let coord = index_lut[vert + (side * 4)];
+        *corner = (
+            Vector3::<f32>::new(
+                select(coord & 2, 0),
+                select(coord & 1, 1),
+                select(coord & 4, 2),
+            ),
+            [(vert % 2) as f32, (vert / 2) as f32],
+        );
+    }
+    corners
+}
+
+impl HasOptions for CuboidBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for CuboidBuilder {
+    type Shape = Cuboid;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> Cuboid {
+        Cuboid {
+            vertices,
+            face_ranges: self.face_ranges(),
+        }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.vertices::<M>()
+    }
+
+    /// The options are applied to each piece as the cuboid is emitted.
+    fn finish(&self, _vertices: &mut Vec<Vertex>) -> Result<(), ShapeCreationError> {
+        Ok(())
+    }
+
+    /// The cuboid is also colored once its faces or corners have been colored.
+    fn is_colored(&self) -> bool {
+        self.options.colors.is_some() || self.face_colors.is_some()
+    }
+}
+
+impl WithTransform for CuboidBuilder {}
+
+impl BuildAs for CuboidBuilder {}
+
+impl WithTangents for CuboidBuilder {}
+
+impl WithLightmap for CuboidBuilder {}
+
+impl WithOrientation for CuboidBuilder {}
+
+impl WithBackFaces for CuboidBuilder {}
+
+impl WithModifiers for CuboidBuilder {}
+
+impl WithTexcoords for CuboidBuilder {}
+
+impl WithColors for CuboidBuilder {
+    /// The vertices are colored from the corners of the pieces of the cuboid
+    /// from which they are built, along with the colors of the faces and
+    /// corners of the cuboid.
+    fn build_vertices_colored(&self) -> Result<Vec<VertexColored>, ShapeCreationError> {
+        self.emit::<Selected, _, _>(
+            |placement, piece, corner, back| self.vertex_colored(placement, piece, corner, back),
+            |vertex| &mut vertex.texcoord,
+        )
+    }
+}
+
+impl WithShading for CuboidBuilder {
+    /// By default, the cuboid is flat shaded. Both modes are supported, and
+    /// each vertex normal points in the direction of its respective corner when
+    /// smooth shaded.
+    const SHADINGS: &'static [Shading] = &[Shading::Flat, Shading::Smooth];
+}
+
+#[test]
+pub fn ensure_default_cuboid_has_unit_dimensions() {
+    let vertices = CuboidBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        assert!(abs_diff_eq!(vertex.position[0].abs(), 0.5));
+        assert!(abs_diff_eq!(vertex.position[1].abs(), 0.5));
+        assert!(abs_diff_eq!(vertex.position[2].abs(), 0.5));
+    }
+}
+
+#[test]
+pub fn ensure_default_cuboid_has_centroid_at_origin() {
+    let vertices = CuboidBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let mut sum = Vector3::<f32>::zero();
+    for vertex in &vertices {
+        sum += Vector3::<f32>::from(vertex.position);
+    }
+    assert_eq!(sum, Vector3::<f32>::zero());
+}
+
+#[test]
+pub fn ensure_default_cuboid_has_outward_facing_normals() {
+    let vertices = CuboidBuilder::new()
+        .scale(2.0, 2.0, 2.0)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        let position = Vector3::<f32>::from(vertex.position);
+        let normal = Vector3::<f32>::from(vertex.normal);
+        let outside = position + normal;
+        assert!(outside.x.abs() >= position.x.abs());
+        assert!(outside.y.abs() >= position.y.abs());
+        assert!(outside.z.abs() >= position.z.abs());
+    }
+}
+
+#[test]
+pub fn ensure_default_cuboid_has_uvs_in_unit_range() {
+    use std::f32;
+    let vertices = CuboidBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let mut min = Vector2::<f32>::new(f32::MAX, f32::MAX);
+    let mut max = -min;
+    for vertex in &vertices {
+        min.x = f32::min(min.x, vertex.texcoord[0]);
+        min.y = f32::min(min.y, vertex.texcoord[1]);
+        max.x = f32::max(max.x, vertex.texcoord[0]);
+        max.y = f32::max(max.y, vertex.texcoord[1]);
+    }
+    assert!(min == Vector2::<f32>::zero());
+    assert!(max == Vector2::<f32>::from_value(1.0));
+}
+
+#[test]
+pub fn ensure_default_cuboid_has_ccw_triangles() {
+    let vertices = CuboidBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
         let v1 = Vector3::<f32>::from(chunk[1].position);
         let v2 = Vector3::<f32>::from(chunk[2].position);
+        let eyepos = v0 + Vector3::<f32>::from(chunk[0].normal);
+        let e0 = v1 - v0;
+        let e1 = v2 - v0;
+        let n = e0.cross(e1);
+        assert!(n.dot(v0 - eyepos) <= 0.0);
+        assert!(n.dot(v1 - eyepos) <= 0.0);
+        assert!(n.dot(v2 - eyepos) <= 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_cuboid_has_faceted_normals_only_when_flat_shaded() {
+    for &shading in [Shading::Flat, Shading::Smooth].iter() {
+        let vertices = CuboidBuilder::new()
+            .shading(shading)
+            .build_vertices()
+            .expect("Failed to build vertices");
+        for chunk in vertices.chunks(3) {
+            let v0 = Vector3::<f32>::from(chunk[0].position);
+            let v1 = Vector3::<f32>::from(chunk[1].position);
+            let v2 = Vector3::<f32>::from(chunk[2].position);
+            let e0 = v1 - v0;
+            let e1 = v2 - v0;
+            let n = e0.cross(e1).normalize();
+            for vertex in chunk {
+                let normal = Vector3::<f32>::from(vertex.normal);
+                match shading {
+                    Shading::Flat => assert_ulps_eq!(n, normal),
+                    Shading::Smooth => {
+                        assert_ulps_eq!(n.dot(normal), 1.0 / 3.0f32.sqrt(), epsilon = 0.0001)
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+pub fn ensure_smooth_cuboid_has_corner_normals() {
+    let vertices = CuboidBuilder::new()
+        .shading(Shading::Smooth)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    assert_eq!(vertices.len(), 36);
+    for chunk in vertices.chunks(3) {
         let n0 = Vector3::<f32>::from(chunk[0].normal);
         let n1 = Vector3::<f32>::from(chunk[1].normal);
         let n2 = Vector3::<f32>::from(chunk[2].normal);
-        let e0 = v1 - v0;
-        let e1 = v2 - v0;
-        let n = e0.cross(e1).normalize();
-        assert_ulps_eq!(n, n0);
-        assert_ulps_eq!(n, n1);
-        assert_ulps_eq!(n, n2);
+        assert!(n0 != n1 || n0 != n2);
+        for vertex in chunk {
+            let direction = Vector3::<f32>::from(vertex.position).normalize();
+            assert_ulps_eq!(direction, Vector3::<f32>::from(vertex.normal));
+        }
+    }
+}
+
+#[test]
+pub fn ensure_indexed_cuboid_shares_face_corners() {
+    let (vertices, indices) = CuboidBuilder::new()
+        .build_vertices_indexed()
+        .expect("Failed to build vertices");
+    assert_eq!(vertices.len(), 24);
+    assert_eq!(indices.len(), 36);
+    assert!(indices
+        .iter()
+        .all(|index| (*index as usize) < vertices.len()));
+}
+
+#[test]
+pub fn ensure_indexed_cuboid_matches_cuboid() {
+    for shading in [Shading::Flat, Shading::Smooth].iter() {
+        let builder = CuboidBuilder::new()
+            .shading(*shading)
+            .scale(1.0, 2.0, 3.0)
+            .rotate_y(0.5);
+        let expected = builder.build_vertices().expect("Failed to build vertices");
+        let (vertices, indices) = builder
+            .build_vertices_indexed()
+            .expect("Failed to build vertices");
+        assert_eq!(indices.len(), expected.len());
+        for (index, vertex) in indices.iter().zip(expected.iter()) {
+            let actual = &vertices[*index as usize];
+            assert_eq!(actual.position, vertex.position);
+            assert_eq!(actual.normal, vertex.normal);
+            assert_eq!(actual.texcoord, vertex.texcoord);
+        }
+    }
+}
+
+#[test]
+pub fn ensure_default_subdivisions_reproduce_face_corners() {
+    let vertices = CuboidBuilder::new()
+        .subdivisions(1, 1, 1)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let min = Vector3::<f32>::from_value(-0.5);
+    let max = Vector3::<f32>::from_value(0.5);
+    let mut expected = Vec::<Vertex>::new();
+    for side in 0..6 {
+        push_face(
+            &mut expected,
+            &Transformation::<Selected>::new(Matrix4::<f32>::identity()),
+            side,
+            min,
+            max,
+        );
+    }
+    assert_eq!(vertices.len(), expected.len());
+    for (vertex, expected) in vertices.iter().zip(expected.iter()) {
+        assert_eq!(vertex.position, expected.position);
+        assert_eq!(vertex.normal, expected.normal);
+        assert_eq!(vertex.texcoord, expected.texcoord);
+    }
+}
+
+#[test]
+pub fn ensure_subdivided_cuboid_has_consistent_edges() {
+    use std::collections::HashMap;
+    let builder = CuboidBuilder::new()
+        .subdivisions(3, 5, 7)
+        .scale(1.3, 0.7, 2.9);
+    let vertices = builder.build_vertices().expect("Failed to build vertices");
+    assert_eq!(vertices.len(), builder.num_vertices());
+    assert_eq!(vertices.len(), (3 * 5 + 5 * 7 + 7 * 3) * 12);
+
+    // Every edge of every triangle must be matched by an opposing edge with
+    // bitwise identical end-points, either within its own face or across an
+    // edge of the cuboid.
+    let key = |v: &Vertex| v.position.map(f32::to_bits);
+    let mut edges = HashMap::new();
+    for chunk in vertices.chunks(3) {
+        for k in 0..3 {
+            let edge = (key(&chunk[k]), key(&chunk[(k + 1) % 3]));
+            *edges.entry(edge).or_insert(0) += 1;
+        }
+    }
+    for ((a, b), count) in edges.iter() {
+        assert_eq!(*count, 1);
+        assert_eq!(edges.get(&(*b, *a)), Some(&1));
+    }
+}
+
+#[test]
+pub fn ensure_subdivided_cuboid_interpolates_uvs() {
+    let (vertices, indices) = CuboidBuilder::new()
+        .subdivisions(2, 4, 1)
+        .build_vertices_indexed()
+        .expect("Failed to build vertices");
+    assert_eq!(vertices.len(), (3 * 5 + 5 * 2 + 2 * 3) * 2);
+    assert_eq!(indices.len(), (2 * 4 + 4 + 2) * 12);
+    for vertex in &vertices {
+        let normal = Vector3::<f32>::from(vertex.normal);
+        let position = Vector3::<f32>::from(vertex.position);
+        assert_ulps_eq!(position.dot(normal), 0.5);
+        for texcoord in vertex.texcoord.iter() {
+            assert!(*texcoord >= 0.0 && *texcoord <= 1.0);
+        }
+    }
+    // The +X face follows the -X face, and is subdivided into a single quad
+    // along the z axis and four quads along the y axis.
+    let side = &vertices[(5 * 2)..(5 * 2) * 2];
+    assert!(side.iter().all(|vertex| vertex.normal == [1.0, 0.0, 0.0]));
+    assert!(side.iter().any(|vertex| vertex.texcoord == [1.0, 0.25]));
+    assert!(side.iter().all(|vertex| vertex.texcoord != [0.5, 0.25]));
+}
+
+#[test]
+pub fn ensure_cuboid_with_no_subdivisions_fails_to_build() {
+    match CuboidBuilder::new().subdivisions(1, 0, 1).build_vertices() {
+        Err(ShapeCreationError::InvalidDimensions) => (),
+        _ => panic!("Expected InvalidDimensions"),
+    }
+    match CuboidBuilder::new()
+        .subdivisions(200, 200, 200)
+        .build_vertices_indexed()
+    {
+        Err(ShapeCreationError::TooManySubdivisions) => (),
+        _ => panic!("Expected TooManySubdivisions"),
+    }
+}
+
+#[test]
+pub fn ensure_cross_uv_layout_places_faces_in_cross() {
+    let vertices = CuboidBuilder::new()
+        .uv_layout(UvLayout::Cross)
+        .subdivisions(2, 2, 2)
+        .build_vertices()
+        .expect("Failed to build vertices");
+
+    // The expected bounds of the texture coordinates of each face, given the
+    // normal of the face.
+    let expected = [
+        ([0.0, 0.0, -1.0], [0.25, 1.0 / 3.0], [0.5, 2.0 / 3.0]),
+        ([0.0, 1.0, 0.0], [0.25, 2.0 / 3.0], [0.5, 1.0]),
+        ([0.0, -1.0, 0.0], [0.25, 0.0], [0.5, 1.0 / 3.0]),
+        ([1.0, 0.0, 0.0], [0.0, 1.0 / 3.0], [0.25, 2.0 / 3.0]),
+        ([-1.0, 0.0, 0.0], [0.5, 1.0 / 3.0], [0.75, 2.0 / 3.0]),
+        ([0.0, 0.0, 1.0], [0.75, 1.0 / 3.0], [1.0, 2.0 / 3.0]),
+    ];
+    for (normal, min, max) in expected.iter() {
+        let face = vertices
+            .iter()
+            .filter(|vertex| vertex.normal == *normal)
+            .collect::<Vec<&Vertex>>();
+        assert_eq!(face.len(), 24);
+        for axis in 0..2 {
+            let lo = face.iter().map(|v| v.texcoord[axis]).fold(1.0, f32::min);
+            let hi = face.iter().map(|v| v.texcoord[axis]).fold(0.0, f32::max);
+            assert_ulps_eq!(lo, min[axis]);
+            assert_ulps_eq!(hi, max[axis]);
+        }
+    }
+    for vertex in &vertices {
+        for texcoord in vertex.texcoord.iter() {
+            assert!(*texcoord >= 0.0 && *texcoord <= 1.0);
+        }
+    }
+}
+
+#[test]
+pub fn ensure_cuboid_face_mask_omits_faces() {
+    let all = CuboidBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let faces = FaceMask::ALL & !(FaceMask::NEG_X | FaceMask::POS_Y);
+    assert!(!faces.contains(FaceMask::NEG_X));
+    assert!(faces.contains(FaceMask::POS_X | FaceMask::NEG_Y));
+    let builder = CuboidBuilder::new().faces(faces);
+    let vertices = builder.build_vertices().expect("Failed to build vertices");
+    assert_eq!(vertices.len(), 24);
+    assert_eq!(vertices.len(), builder.num_vertices());
+
+    // The remaining faces are built in the same order as the full cuboid.
+    let expected = all[6..12]
+        .iter()
+        .chain(all[12..18].iter())
+        .chain(all[24..].iter());
+    for (vertex, expected) in vertices.iter().zip(expected) {
+        assert_eq!(vertex.position, expected.position);
+        assert_eq!(vertex.normal, expected.normal);
+        assert_eq!(vertex.texcoord, expected.texcoord);
     }
+
+    let (vertices, indices) = builder
+        .subdivisions(2, 1, 1)
+        .build_vertices_indexed()
+        .expect("Failed to build vertices");
+    assert_eq!(vertices.len(), 4 + 6 + 6 + 6);
+    assert_eq!(indices.len(), (1 + 2 + 2 + 2) * 6);
+}
+
+#[test]
+pub fn ensure_cuboid_with_empty_face_mask_has_no_vertices() {
+    let builder = CuboidBuilder::new().faces(FaceMask::NONE);
+    assert!(FaceMask::NONE.is_empty());
+    assert_eq!(builder.num_vertices(), 0);
+    let vertices = builder.build_vertices().expect("Failed to build vertices");
+    assert!(vertices.is_empty());
+    let (vertices, indices) = builder
+        .build_vertices_indexed()
+        .expect("Failed to build vertices");
+    assert!(vertices.is_empty());
+    assert!(indices.is_empty());
+}
+
+#[test]
+pub fn ensure_cuboid_from_corners_spans_corners() {
+    use std::f32;
+    let bounds = |vertices: &[Vertex]| {
+        let mut min = Vector3::<f32>::from_value(f32::MAX);
+        let mut max = Vector3::<f32>::from_value(f32::MIN);
+        for vertex in vertices {
+            for axis in 0..3 {
+                min[axis] = min[axis].min(vertex.position[axis]);
+                max[axis] = max[axis].max(vertex.position[axis]);
+            }
+        }
+        (min, max)
+    };
+    let vertices = CuboidBuilder::from_corners(
+        Point3::<f32>::new(3.0, -4.0, 0.25),
+        Point3::<f32>::new(-1.0, 2.0, 1.5),
+    )
+    .build_vertices()
+    .expect("Failed to build vertices");
+    let (min, max) = bounds(&vertices);
+    assert_ulps_eq!(min, Vector3::<f32>::new(-1.0, -4.0, 0.25));
+    assert_ulps_eq!(max, Vector3::<f32>::new(3.0, 2.0, 1.5));
+
+    // Subsequent transformations apply on top of the box.
+    let vertices = CuboidBuilder::from_corners(
+        Point3::<f32>::new(0.0, 0.0, 0.0),
+        Point3::<f32>::new(1.0, 2.0, 3.0),
+    )
+    .translate(1.0, 0.0, 0.0)
+    .rotate_y(std::f32::consts::PI)
+    .build_vertices()
+    .expect("Failed to build vertices");
+    let (min, max) = bounds(&vertices);
+    assert!(abs_diff_eq!(
+        min,
+        Vector3::<f32>::new(-2.0, 0.0, -3.0),
+        epsilon = 0.0001
+    ));
+    assert!(abs_diff_eq!(
+        max,
+        Vector3::<f32>::new(-1.0, 2.0, 0.0),
+        epsilon = 0.0001
+    ));
+}
+
+#[test]
+pub fn ensure_cuboid_with_extents_spans_extents() {
+    let center = Point3::<f32>::new(1.5, -2.0, 0.3);
+    let half_extents = Vector3::<f32>::new(0.25, 3.0, 0.7);
+    let vertices = CuboidBuilder::with_extents(center, half_extents)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let min = center - half_extents;
+    let max = center + half_extents;
+    for vertex in &vertices {
+        for axis in 0..3 {
+            let position = vertex.position[axis];
+            assert!(position == min[axis] || position == max[axis]);
+        }
+    }
+    for axis in 0..3 {
+        assert!(vertices
+            .iter()
+            .any(|vertex| vertex.position[axis] == min[axis]));
+        assert!(vertices
+            .iter()
+            .any(|vertex| vertex.position[axis] == max[axis]));
+    }
+}
+
+#[test]
+pub fn ensure_cuboid_with_invalid_extents_fails_to_build() {
+    for half_extents in [
+        Vector3::<f32>::new(-0.5, 1.0, 1.0),
+        Vector3::<f32>::new(1.0, -1.0, 1.0),
+        Vector3::<f32>::new(1.0, 1.0, f32::NAN),
+    ]
+    .iter()
+    {
+        match CuboidBuilder::with_extents(Point3::<f32>::origin(), *half_extents).build_vertices() {
+            Err(ShapeCreationError::InvalidDimensions) => (),
+            _ => panic!("Expected InvalidDimensions"),
+        }
+    }
+}
+
+#[test]
+pub fn ensure_zero_chamfer_reproduces_cuboid() {
+    let expected = CuboidBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let vertices = CuboidBuilder::new()
+        .chamfer(0.0)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    assert_eq!(vertices.len(), expected.len());
+    for (vertex, expected) in vertices.iter().zip(expected.iter()) {
+        assert_eq!(vertex.position, expected.position);
+        assert_eq!(vertex.normal, expected.normal);
+        assert_eq!(vertex.texcoord, expected.texcoord);
+    }
+}
+
+#[test]
+pub fn ensure_chamfered_cuboid_is_watertight_and_faceted() {
+    use std::collections::HashMap;
+    for subdivisions in [(1, 1, 1), (2, 3, 1)].iter() {
+        let builder = CuboidBuilder::with_extents(
+            Point3::<f32>::origin(),
+            Vector3::<f32>::new(0.5, 0.75, 1.0),
+        )
+        .subdivisions(subdivisions.0, subdivisions.1, subdivisions.2)
+        .chamfer(0.2);
+        let vertices = builder.build_vertices().expect("Failed to build vertices");
+        assert_eq!(vertices.len(), builder.num_vertices());
+
+        let key = |v: &Vertex| v.position.map(f32::to_bits);
+        let mut edges = HashMap::new();
+        for chunk in vertices.chunks(3) {
+            let v0 = Vector3::<f32>::from(chunk[0].position);
+            let v1 = Vector3::<f32>::from(chunk[1].position);
+            let v2 = Vector3::<f32>::from(chunk[2].position);
+            let n = (v1 - v0).cross(v2 - v0).normalize();
+            for vertex in chunk {
+                assert_ulps_eq!(n, Vector3::<f32>::from(vertex.normal), epsilon = 0.0001);
+                assert!(n.dot(Vector3::<f32>::from(vertex.position)) > 0.0);
+            }
+            for k in 0..3 {
+                let edge = (key(&chunk[k]), key(&chunk[(k + 1) % 3]));
+                *edges.entry(edge).or_insert(0) += 1;
+            }
+        }
+        for ((a, b), count) in edges.iter() {
+            assert_eq!(*count, 1);
+            assert_eq!(edges.get(&(*b, *a)), Some(&1));
+        }
+    }
+}
+
+#[test]
+pub fn ensure_chamfered_cuboid_has_chamfer_pieces() {
+    let vertices = CuboidBuilder::new()
+        .chamfer(0.1)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    assert_eq!(vertices.len(), 36 + 12 * 6 + 8 * 3);
+    let sqrt2 = 0.5f32.sqrt();
+    let sqrt3 = (1.0f32 / 3.0).sqrt();
+    assert!(vertices[36..108].iter().all(|v| v
+        .normal
+        .iter()
+        .filter(|n| abs_diff_eq!(n.abs(), sqrt2))
+        .count()
+        == 2));
+    assert!(vertices[108..]
+        .iter()
+        .all(|v| v.normal.iter().all(|n| abs_diff_eq!(n.abs(), sqrt3))));
+    for vertex in &vertices[..36] {
+        let inset = vertex
+            .position
+            .iter()
+            .filter(|p| abs_diff_eq!(p.abs(), 0.4));
+        assert_eq!(inset.count(), 2);
+    }
+}
+
+#[test]
+pub fn ensure_cuboid_with_invalid_chamfer_fails_to_build() {
+    let builder =
+        CuboidBuilder::with_extents(Point3::<f32>::origin(), Vector3::<f32>::new(1.0, 0.25, 1.0));
+    assert!(builder.chamfer(0.24).build_vertices().is_ok());
+    for width in [0.6, -0.1].iter() {
+        match CuboidBuilder::new().chamfer(*width).build_vertices() {
+            Err(ShapeCreationError::InvalidDimensions) => (),
+            _ => panic!("Expected InvalidDimensions"),
+        }
+    }
+    match CuboidBuilder::with_extents(Point3::<f32>::origin(), Vector3::<f32>::new(1.0, 0.25, 1.0))
+        .chamfer(0.26)
+        .build_vertices()
+    {
+        Err(ShapeCreationError::InvalidDimensions) => (),
+        _ => panic!("Expected InvalidDimensions"),
+    }
+}
+
+#[test]
+pub fn ensure_cuboid_fails_to_build_with_chamfer_equal_to_half_extent() {
+    match CuboidBuilder::with_extents(Point3::<f32>::origin(), Vector3::<f32>::new(1.0, 0.25, 1.0))
+        .chamfer(0.25)
+        .build_vertices()
+    {
+        Err(ShapeCreationError::InvalidDimensions) => (),
+        _ => panic!("Expected InvalidDimensions"),
+    }
+}
+
+#[test]
+pub fn ensure_chamfer_of_cuboid_from_corners_is_measured_in_its_extents() {
+    let builder = || {
+        CuboidBuilder::from_corners(
+            Point3::<f32>::new(0.0, 0.0, 0.0),
+            Point3::<f32>::new(4.0, 1.0, 2.0),
+        )
+    };
+    let vertices = builder()
+        .chamfer(0.4)
+        .build_vertices()
+        .expect("Failed to build vertices");
+
+    // The side facing the positive x-axis is inset by the same width along
+    // both of its axes, rather than by a width scaled with the box.
+    let side = vertices
+        .iter()
+        .filter(|vertex| vertex.normal == [1.0, 0.0, 0.0])
+        .collect::<Vec<_>>();
+    assert!(!side.is_empty());
+    for vertex in side {
+        assert!(abs_diff_eq!(vertex.position[0], 4.0));
+        let (y, z) = (vertex.position[1], vertex.position[2]);
+        assert!((0.4 - 0.0001..=0.6 + 0.0001).contains(&y));
+        assert!((0.4 - 0.0001..=1.6 + 0.0001).contains(&z));
+    }
+
+    // The chamfer is limited by the smallest half-extent of the box.
+    match builder().chamfer(0.5).build_vertices() {
+        Err(ShapeCreationError::InvalidDimensions) => (),
+        _ => panic!("Expected InvalidDimensions"),
+    }
+}
+
+#[test]
+pub fn ensure_flat_cuboid_from_corners_transforms_its_normals() {
+    use std::f32;
+    let vertices = CuboidBuilder::from_corners(
+        Point3::<f32>::new(0.0, 1.0, 0.0),
+        Point3::<f32>::new(2.0, 1.0, 3.0),
+    )
+    .rotate_z(f32::consts::FRAC_PI_2)
+    .build_vertices()
+    .expect("Failed to build vertices");
+    assert_eq!(vertices.len(), 36);
+    for vertex in &vertices {
+        assert!(abs_diff_eq!(vertex.position[0], -1.0, epsilon = 0.0001));
+        let normal = Vector3::<f32>::from(vertex.normal);
+        assert!(abs_diff_eq!(normal.magnitude(), 1.0, epsilon = 0.0001));
+    }
+
+    // The sides that faced along the y-axis are rotated onto the x-axis.
+    for direction in [-1.0f32, 1.0].iter() {
+        assert_eq!(
+            vertices
+                .iter()
+                .filter(|vertex| abs_diff_eq!(
+                    Vector3::<f32>::from(vertex.normal),
+                    Vector3::<f32>::new(-direction, 0.0, 0.0),
+                    epsilon = 0.0001
+                ))
+                .count(),
+            6
+        );
+    }
+}
+
+#[test]
+pub fn ensure_inverted_cuboid_has_ccw_triangles_from_inside() {
+    let builder = CuboidBuilder::new().scale(2.0, 2.0, 2.0);
+    let expected = builder.build_vertices().expect("Failed to build vertices");
+    let vertices = builder
+        .invert()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let eyepos = v0 + Vector3::<f32>::from(chunk[0].normal);
+        assert!(eyepos.x.abs() <= 1.0 && eyepos.y.abs() <= 1.0 && eyepos.z.abs() <= 1.0);
+        let e0 = v1 - v0;
+        let e1 = v2 - v0;
+        let n = e0.cross(e1);
+        assert!(n.dot(v0 - eyepos) <= 0.0);
+        assert!(n.dot(v1 - eyepos) <= 0.0);
+        assert!(n.dot(v2 - eyepos) <= 0.0);
+        assert!(n.dot(v0) < 0.0);
+    }
+
+    // Each triangle retains the positions and texture coordinates of the
+    // regular cuboid, with its second and third vertices swapped.
+    for (chunk, expected) in vertices.chunks(3).zip(expected.chunks(3)) {
+        for (vertex, expected) in chunk.iter().zip([0, 2, 1].iter().map(|i| &expected[*i])) {
+            assert_eq!(vertex.position, expected.position);
+            assert_eq!(vertex.texcoord, expected.texcoord);
+            assert_eq!(vertex.normal, expected.normal.map(|n| -n));
+        }
+    }
+}
+
+#[test]
+pub fn ensure_face_colors_are_constant_per_face() {
+    let builder = CuboidBuilder::new().face_colors_rainbow().rotate_x(0.3);
+    let expected = builder.build_vertices().expect("Failed to build vertices");
+    let vertices = builder
+        .build_vertices_colored()
+        .expect("Failed to build vertices");
+    assert_eq!(vertices.len(), 36);
+    let colors = [
+        [1.0, 0.0, 0.0, 1.0],
+        [0.0, 1.0, 1.0, 1.0],
+        [0.0, 1.0, 0.0, 1.0],
+        [1.0, 0.0, 1.0, 1.0],
+        [0.0, 0.0, 1.0, 1.0],
+        [1.0, 1.0, 0.0, 1.0],
+    ];
+    for (side, face) in vertices.chunks(6).enumerate() {
+        for vertex in face {
+            assert_eq!(vertex.color, colors[side]);
+        }
+    }
+    for (vertex, expected) in vertices.iter().zip(expected.iter()) {
+        assert_eq!(vertex.position, expected.position);
+        assert_eq!(vertex.normal, expected.normal);
+        assert_eq!(vertex.texcoord, expected.texcoord);
+    }
+}
+
+#[test]
+pub fn ensure_chamfer_colors_blend_face_colors() {
+    let mut colors = [[0.0, 0.0, 0.0, 1.0]; 6];
+    colors[1] = [1.0, 0.0, 0.0, 1.0];
+    colors[3] = [0.0, 1.0, 0.0, 1.0];
+    colors[5] = [0.0, 0.0, 1.0, 1.0];
+    let (vertices, indices) = CuboidBuilder::new()
+        .face_colors(colors)
+        .chamfer(0.1)
+        .build_vertices_colored_indexed()
+        .expect("Failed to build vertices");
+    assert_eq!(indices.len(), 36 + 12 * 6 + 8 * 3);
+    // The last chamfer is the corner between the +X, +Y, and +Z faces.
+    let corner = &vertices[vertices.len() - 3..];
+    for vertex in corner {
+        assert!(vertex.normal.iter().all(|n| *n > 0.0));
+        assert_ulps_eq!(vertex.color[0], 1.0 / 3.0);
+        assert_ulps_eq!(vertex.color[1], 1.0 / 3.0);
+        assert_ulps_eq!(vertex.color[2], 1.0 / 3.0);
+        assert_ulps_eq!(vertex.color[3], 1.0);
+    }
+}
+
+#[test]
+pub fn ensure_uv_tiling_follows_transformed_dimensions() {
+    for scale in [1.0, 2.5].iter() {
+        let vertices = CuboidBuilder::new()
+            .scale(4.0, 1.0, 2.0)
+            .rotate_y(0.7)
+            .translate(3.0, 0.0, 1.0)
+            .uv_tiling_per_unit(*scale)
+            .subdivisions(2, 3, 1)
+            .build_vertices()
+            .expect("Failed to build vertices");
+
+        // The expected dimensions of each pair of opposite faces.
+        let expected = [(1.0, 2.0), (2.0, 4.0), (1.0, 4.0)];
+        for (side, (lo, hi)) in (0..6).zip(expected.iter().flat_map(|e| vec![e, e])) {
+            let normal = crate::cuboid::face_normal(side);
+            let face = vertices
+                .iter()
+                .filter(|vertex| {
+                    let n = Vector3::<f32>::from(vertex.normal);
+                    let n = Matrix3::<f32>::from_angle_y(Rad(-0.7)) * n;
+                    abs_diff_eq!(n, normal, epsilon = 0.0001)
+                })
+                .collect::<Vec<&Vertex>>();
+            assert!(!face.is_empty());
+            let mut extents = [0.0f32; 2];
+            for vertex in face.iter() {
+                extents[0] = extents[0].max(vertex.texcoord[0]);
+                extents[1] = extents[1].max(vertex.texcoord[1]);
+                assert!(vertex.texcoord[0] >= 0.0 && vertex.texcoord[1] >= 0.0);
+            }
+            extents.sort_by(f32::total_cmp);
+            assert!(abs_diff_eq!(extents[0], lo * scale, epsilon = 0.0001));
+            assert!(abs_diff_eq!(extents[1], hi * scale, epsilon = 0.0001));
+        }
+    }
+}
+
+#[test]
+pub fn ensure_uv_tiling_scales_chamfers() {
+    let vertices = CuboidBuilder::new()
+        .chamfer(0.1)
+        .uv_tiling_per_unit(1.0)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    // Each side is inset by the chamfer, and spans 0.8 units.
+    for vertex in &vertices[..36] {
+        for texcoord in vertex.texcoord.iter() {
+            assert!(abs_diff_eq!(*texcoord, 0.0) || abs_diff_eq!(*texcoord, 0.8));
+        }
+    }
+    // Each edge chamfer spans 0.8 units along the edge, and is 0.1 * sqrt(2)
+    // units wide.
+    for vertex in &vertices[36..108] {
+        let u = vertex.texcoord[0];
+        let v = vertex.texcoord[1];
+        assert!(abs_diff_eq!(u, 0.0) || abs_diff_eq!(u, 0.8, epsilon = 0.0001));
+        assert!(abs_diff_eq!(v, 0.0) || abs_diff_eq!(v, 0.02f32.sqrt(), epsilon = 0.0001));
+    }
+    // Each corner chamfer is an equilateral triangle whose sides are
+    // 0.1 * sqrt(2) units long.
+    for vertex in &vertices[108..] {
+        let u = vertex.texcoord[0];
+        let v = vertex.texcoord[1];
+        let side = 0.02f32.sqrt();
+        assert!([0.0, 0.5, 1.0]
+            .iter()
+            .any(|k| abs_diff_eq!(u, k * side, epsilon = 0.0001)));
+        assert!([0.0, 1.0].iter().any(|k| abs_diff_eq!(
+            v,
+            k * side * 0.75f32.sqrt(),
+            epsilon = 0.0001
+        )));
+    }
+}
+
+#[test]
+pub fn ensure_cuboid_wireframe_follows_cuboid_edges() {
+    use std::collections::HashSet;
+    let builder = CuboidBuilder::with_extents(
+        Point3::<f32>::new(1.0, 2.0, 3.0),
+        Vector3::<f32>::new(0.5, 1.0, 2.0),
+    )
+    .rotate_z(0.4)
+    .scale(1.0, 3.0, 1.0)
+    .chamfer(0.0);
+    let lines = builder
+        .build_wireframe_vertices()
+        .expect("Failed to build vertices");
+    assert_eq!(lines.len(), 24);
+
+    // Every end-point of each line is a corner of the cuboid, and every
+    // corner of the cuboid is the end-point of three lines.
+    let key = |position: [f32; 3]| position.map(|p| (p * 1000.0).round() as i32);
+    let vertices = builder.build_vertices().expect("Failed to build vertices");
+    let corners = vertices
+        .iter()
+        .map(|vertex| key(vertex.position))
+        .collect::<HashSet<[i32; 3]>>();
+    assert_eq!(corners.len(), 8);
+    for corner in corners.iter() {
+        let count = lines
+            .iter()
+            .filter(|vertex| key(vertex.position) == *corner)
+            .count();
+        assert_eq!(count, 3);
+    }
+    for pair in lines.chunks(2) {
+        let edge = Vector3::<f32>::from(pair[1].position) - Vector3::<f32>::from(pair[0].position);
+        assert_ulps_eq!(
+            edge.normalize(),
+            Vector3::<f32>::from(pair[0].normal),
+            epsilon = 0.0001
+        );
+    }
+}
+
+#[test]
+pub fn ensure_upright_uvs_are_consistent_across_faces() {
+    let vertices = CuboidBuilder::new()
+        .uv_orientation(UvOrientation::Upright)
+        .build_vertices()
+        .expect("Failed to build vertices");
+
+    // The texture coordinates of the bottom-left and top-right corners of each
+    // face, when viewed from outside the cuboid.
+    let expected = [
+        ([-0.5, -0.5, -0.5], [-0.5, 0.5, 0.5]),
+        ([0.5, -0.5, 0.5], [0.5, 0.5, -0.5]),
+        ([-0.5, -0.5, -0.5], [0.5, -0.5, 0.5]),
+        ([-0.5, 0.5, 0.5], [0.5, 0.5, -0.5]),
+        ([0.5, -0.5, -0.5], [-0.5, 0.5, -0.5]),
+        ([-0.5, -0.5, 0.5], [0.5, 0.5, 0.5]),
+    ];
+    for (face, (bottom_left, top_right)) in vertices.chunks(6).zip(expected.iter()) {
+        let find = |position: &[f32; 3]| {
+            face.iter()
+                .find(|vertex| vertex.position == *position)
+                .expect("Missing corner")
+                .texcoord
+        };
+        assert_eq!(find(bottom_left), [0.0, 0.0]);
+        assert_eq!(find(top_right), [1.0, 1.0]);
+
+        // The u and v directions, and the normal, form a right-handed frame.
+        let normal = Vector3::<f32>::from(face[0].normal);
+        let origin = Vector3::<f32>::from(*bottom_left);
+        let corner = Vector3::<f32>::from(*top_right);
+        let u = face
+            .iter()
+            .find(|vertex| vertex.texcoord == [1.0, 0.0])
+            .map(|vertex| Vector3::<f32>::from(vertex.position) - origin)
+            .expect("Missing corner");
+        let v = corner - origin - u;
+        assert_ulps_eq!(u.cross(v), normal);
+    }
+}
+
+#[test]
+pub fn ensure_upright_uvs_follow_subdivisions() {
+    let original = CuboidBuilder::new()
+        .subdivisions(2, 3, 4)
+        .chamfer(0.1)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let upright = CuboidBuilder::new()
+        .subdivisions(2, 3, 4)
+        .chamfer(0.1)
+        .uv_orientation(UvOrientation::Upright)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    assert_eq!(original.len(), upright.len());
+    for (a, b) in original.iter().zip(upright.iter()) {
+        assert_eq!(a.position, b.position);
+        assert_eq!(a.normal, b.normal);
+    }
+
+    // The -Z face increases in u towards the negative x-axis, in steps of
+    // a half, and in v towards the positive y-axis, in steps of a third.
+    for vertex in upright
+        .iter()
+        .filter(|vertex| vertex.normal == [0.0, 0.0, -1.0])
+    {
+        let u = (0.4 - vertex.position[0]) / 0.8;
+        let v = (vertex.position[1] + 0.4) / 0.8;
+        assert!(abs_diff_eq!(vertex.texcoord[0], u, epsilon = 0.0001));
+        assert!(abs_diff_eq!(vertex.texcoord[1], v, epsilon = 0.0001));
+        assert!(
+            abs_diff_eq!((u * 2.0).fract(), 0.0, epsilon = 0.0001)
+                || abs_diff_eq!((u * 2.0).fract(), 1.0, epsilon = 0.0001)
+        );
+    }
+    assert!(upright
+        .iter()
+        .any(|vertex| vertex.texcoord == [0.5, 1.0 / 3.0]));
+}
+
+#[test]
+pub fn ensure_cuboid_face_ranges_follow_face_order() {
+    let builder = CuboidBuilder::new().subdivisions(1, 2, 3).chamfer(0.1);
+    let vertices = builder.build_vertices().expect("Failed to build vertices");
+    let faces = [
+        (Face::NegX, [-1.0, 0.0, 0.0], 6),
+        (Face::PosX, [1.0, 0.0, 0.0], 6),
+        (Face::NegY, [0.0, -1.0, 0.0], 3),
+        (Face::PosY, [0.0, 1.0, 0.0], 3),
+        (Face::NegZ, [0.0, 0.0, -1.0], 2),
+        (Face::PosZ, [0.0, 0.0, 1.0], 2),
+    ];
+    let mut end = 0;
+    for (face, normal, quads) in faces.iter() {
+        let range = builder.face_range(*face);
+        assert_eq!(range.start, end);
+        assert_eq!(range.len(), quads * 6);
+        assert!(vertices[range.clone()].iter().all(|v| v.normal == *normal));
+        end = range.end;
+    }
+    // The chamfers follow the faces.
+    assert!(vertices[end..]
+        .iter()
+        .all(|v| v.normal.iter().filter(|n| **n != 0.0).count() > 1));
+}
+
+#[test]
+pub fn ensure_omitted_cuboid_faces_have_empty_ranges() {
+    let builder = CuboidBuilder::new().faces(FaceMask::ALL & !FaceMask::from(Face::NegY));
+    assert_eq!(builder.face_range(Face::PosX), 6..12);
+    assert_eq!(builder.face_range(Face::NegY), 12..12);
+    assert_eq!(builder.face_range(Face::PosY), 12..18);
+    assert_eq!(builder.face_range(Face::PosZ), 24..30);
+    assert_eq!(FaceMask::from(Face::NegY), FaceMask::NEG_Y);
+    assert_eq!(FaceMask::from(Face::PosZ), FaceMask::POS_Z);
+}
+
+#[test]
+pub fn ensure_cuboid_quads_split_along_diagonal() {
+    // Returns the texture coordinates of the pair of vertices that are shared
+    // by the triangles of each quad.
+    let shared_edges = |builder: CuboidBuilder| {
+        let vertices = builder.build_vertices().expect("Failed to build vertices");
+        vertices
+            .chunks(6)
+            .map(|quad| {
+                let shared = quad[..3]
+                    .iter()
+                    .filter(|a| quad[3..].iter().any(|b| a.position == b.position))
+                    .map(|vertex| vertex.texcoord)
+                    .collect::<Vec<[f32; 2]>>();
+                assert_eq!(shared.len(), 2);
+                (shared[0], shared[1])
+            })
+            .collect::<Vec<([f32; 2], [f32; 2])>>()
+    };
+    let is_top_left = |(a, b): ([f32; 2], [f32; 2])| (a[0] - b[0]) * (a[1] - b[1]) < 0.0;
+
+    let default = shared_edges(CuboidBuilder::new());
+    assert!(default.iter().all(|edge| is_top_left(*edge)));
+    for orientation in [UvOrientation::Original, UvOrientation::Upright].iter() {
+        let builder = || CuboidBuilder::new().uv_orientation(*orientation);
+        let edges = shared_edges(builder().diagonal(Diagonal::TopLeftToBottomRight));
+        assert!(edges.iter().all(|edge| is_top_left(*edge)));
+        let edges = shared_edges(builder().diagonal(Diagonal::BottomLeftToTopRight));
+        assert!(edges.iter().all(|edge| !is_top_left(*edge)));
+    }
+
+    // Each face is split into 2x2 quads, whose diagonals alternate.
+    let edges = shared_edges(
+        CuboidBuilder::new()
+            .subdivisions(2, 2, 2)
+            .diagonal(Diagonal::Alternating),
+    );
+    for face in edges.chunks(4) {
+        let pattern = face
+            .iter()
+            .map(|edge| is_top_left(*edge))
+            .collect::<Vec<bool>>();
+        assert_eq!(pattern, vec![true, false, false, true]);
+    }
+}
+
+#[test]
+pub fn ensure_cuboid_diagonals_preserve_winding() {
+    for diagonal in [Diagonal::BottomLeftToTopRight, Diagonal::Alternating].iter() {
+        let vertices = CuboidBuilder::new()
+            .subdivisions(3, 2, 1)
+            .chamfer(0.1)
+            .diagonal(*diagonal)
+            .build_vertices()
+            .expect("Failed to build vertices");
+        for chunk in vertices.chunks(3) {
+            let v0 = Vector3::<f32>::from(chunk[0].position);
+            let v1 = Vector3::<f32>::from(chunk[1].position);
+            let v2 = Vector3::<f32>::from(chunk[2].position);
+            let n = (v1 - v0).cross(v2 - v0).normalize();
+            assert_ulps_eq!(n, Vector3::<f32>::from(chunk[0].normal), epsilon = 0.0001);
+        }
+    }
+}
+
+#[test]
+pub fn ensure_corner_colors_form_rgb_cube() {
+    use std::collections::HashSet;
+    let vertices = CuboidBuilder::new()
+        .corner_colors()
+        .rotate_y(1.0)
+        .translate(2.0, 0.0, 0.0)
+        .build_vertices_colored()
+        .expect("Failed to build vertices");
+    let colors = vertices
+        .iter()
+        .map(|vertex| vertex.color.map(f32::to_bits))
+        .collect::<HashSet<[u32; 4]>>();
+    let mut expected = HashSet::new();
+    for corner in 0..8 {
+        let bit = |mask: usize| if corner & mask != 0 { 1.0f32 } else { 0.0 };
+        expected.insert([bit(1), bit(2), bit(4), 1.0].map(f32::to_bits));
+    }
+    assert_eq!(colors, expected);
+
+    // Each vertex takes the color of its corner prior to transformation.
+    let matrix =
+        Matrix4::from_translation(Vector3::new(2.0, 0.0, 0.0)) * Matrix4::from_angle_y(Rad(1.0));
+    let inverse = matrix.invert().expect("Expected invertible matrix");
+    for vertex in &vertices {
+        let position = inverse.transform_point(Point3::from(vertex.position));
+        for axis in 0..3 {
+            let expected = position[axis] + 0.5;
+            assert!(abs_diff_eq!(vertex.color[axis], expected, epsilon = 0.0001));
+        }
+    }
+}
+
+#[test]
+pub fn ensure_double_sided_cuboid_appends_reversed_twins() {
+    let builder = CuboidBuilder::new().chamfer(0.1).rotate_y(0.5);
+    let front = builder.build_vertices().expect("Failed to build vertices");
+    let builder = builder.double_sided();
+    let vertices = builder.build_vertices().expect("Failed to build vertices");
+    assert_eq!(vertices.len(), front.len() * 2);
+    assert_eq!(vertices.len(), builder.num_vertices());
+    let (original, back) = vertices.split_at(front.len());
+    for (a, b) in front.iter().zip(original.iter()) {
+        assert_eq!(a.position, b.position);
+        assert_eq!(a.normal, b.normal);
+    }
+    for (f, b) in front.chunks(3).zip(back.chunks(3)) {
+        for (fv, bv) in f.iter().zip([&b[0], &b[2], &b[1]].iter()) {
+            assert_eq!(fv.position, bv.position);
+            assert_eq!(fv.texcoord, bv.texcoord);
+            assert_eq!(
+                Vector3::<f32>::from(fv.normal),
+                -Vector3::<f32>::from(bv.normal)
+            );
+        }
+    }
+
+    // The indexed cuboid indexes a flipped copy of its corners.
+    let (corners, indices) = builder
+        .build_vertices_indexed()
+        .expect("Failed to build vertices");
+    assert_eq!(corners.len(), builder.num_vertices_indexed());
+    assert_eq!(indices.len(), vertices.len());
+    for (index, vertex) in indices.iter().zip(vertices.iter()) {
+        let actual = &corners[*index as usize];
+        assert_eq!(actual.position, vertex.position);
+        assert_eq!(actual.normal, vertex.normal);
+        assert_eq!(actual.texcoord, vertex.texcoord);
+    }
+}
+
+#[test]
+pub fn ensure_box_uv_mapping_maps_each_face_to_unit_square() {
+    use crate::options::WithTexcoords;
+    use crate::uv_mapping::UvMapping;
+
+    let builder = CuboidBuilder::new()
+        .scale(2.0, 3.0, 4.0)
+        .uv_mapping(UvMapping::Box)
+        .double_sided();
+    let vertices = builder.build_vertices().expect("Failed to build vertices");
+    let (front, back) = vertices.split_at(vertices.len() / 2);
+    for face in front.chunks(6) {
+        let mut texcoords = face
+            .iter()
+            .map(|v| [v.texcoord[0].to_bits(), v.texcoord[1].to_bits()])
+            .collect::<Vec<[u32; 2]>>();
+        texcoords.sort();
+        texcoords.dedup();
+        let expected = [[0.0f32, 0.0], [0.0, 1.0], [1.0, 0.0], [1.0, 1.0]]
+            .iter()
+            .map(|t| [t[0].to_bits(), t[1].to_bits()])
+            .collect::<Vec<[u32; 2]>>();
+        assert_eq!(texcoords, expected);
+    }
+    for (f, b) in front.chunks(3).zip(back.chunks(3)) {
+        assert_eq!(f[0].texcoord, b[0].texcoord);
+        assert_eq!(f[1].texcoord, b[2].texcoord);
+        assert_eq!(f[2].texcoord, b[1].texcoord);
+    }
+    match builder.build_vertices_indexed() {
+        Err(ShapeCreationError::UnsupportedUvMapping) => (),
+        _ => panic!("Expected UnsupportedUvMapping"),
+    }
+}
+
+#[test]
+pub fn ensure_uv_transform_maps_cuboid_into_sub_rectangle() {
+    use crate::options::WithTexcoords;
+
+    let vertices = CuboidBuilder::new()
+        .uv_scale(0.5, 0.5)
+        .uv_offset(0.25, 0.5)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let (corners, _) = CuboidBuilder::new()
+        .uv_scale(0.5, 0.5)
+        .uv_offset(0.25, 0.5)
+        .build_vertices_indexed()
+        .expect("Failed to build vertices");
+    for vertex in vertices.iter().chain(corners.iter()) {
+        let [u, v] = vertex.texcoord;
+        assert!((0.25..=0.75).contains(&u));
+        assert!((0.5..=1.0).contains(&v));
+    }
+    // Every face maps to the whole of the sub-rectangle.
+    assert!(vertices.iter().all(|vertex| {
+        [0.25, 0.75].contains(&vertex.texcoord[0]) && [0.5, 1.0].contains(&vertex.texcoord[1])
+    }));
+}
+
+#[test]
+pub fn ensure_flipped_cuboid_face_has_mirrored_corner_uvs() {
+    use crate::options::WithTexcoords;
+
+    let builder = CuboidBuilder::new()
+        .scale(2.0, 2.0, 2.0)
+        .uv_tiling_per_unit(2.0);
+    let range = builder.face_range(Face::PosZ);
+    let original = builder.build_vertices().expect("Failed to build vertices");
+    let flipped = builder
+        .flip_u()
+        .flip_v()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let mut corners = Vec::new();
+    for (a, b) in original[range.clone()].iter().zip(flipped[range].iter()) {
+        assert_eq!(b.texcoord, [1.0 - a.texcoord[0], 1.0 - a.texcoord[1]]);
+        corners.push([b.texcoord[0].to_bits(), b.texcoord[1].to_bits()]);
+    }
+    corners.sort();
+    corners.dedup();
+    // The tiling maps the face from 0 to 4 along each axis before flipping.
+    let mut expected = [[-3.0f32, -3.0], [-3.0, 1.0], [1.0, -3.0], [1.0, 1.0]]
+        .iter()
+        .map(|t| [t[0].to_bits(), t[1].to_bits()])
+        .collect::<Vec<[u32; 2]>>();
+    expected.sort();
+    assert_eq!(corners, expected);
+}
+
+#[test]
+pub fn ensure_flipped_normals_are_independent_of_inversion() {
+    let original = CuboidBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let flipped = CuboidBuilder::new()
+        .flip_normals()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let inverted = CuboidBuilder::new()
+        .invert()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let both = CuboidBuilder::new()
+        .invert()
+        .flip_normals()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for (a, b) in original.iter().zip(flipped.iter()) {
+        assert_eq!(a.position, b.position);
+        assert_eq!(a.texcoord, b.texcoord);
+        assert_eq!(Vector3::from(a.normal), -Vector3::from(b.normal));
+    }
+
+    // Flipping the normals of an inverted cuboid keeps its reversed winding,
+    // but points its normals outwards again.
+    for (a, b) in inverted.iter().zip(both.iter()) {
+        assert_eq!(a.position, b.position);
+        assert_eq!(Vector3::from(a.normal), -Vector3::from(b.normal));
+        assert!(Vector3::<f32>::from(b.normal).dot(Vector3::from(b.position)) > 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_flipped_indexed_cuboid_negates_normals_only() {
+    let (original, original_indices) = CuboidBuilder::new()
+        .build_vertices_indexed()
+        .expect("Failed to build vertices");
+    let (flipped, flipped_indices) = CuboidBuilder::new()
+        .flip_normals()
+        .build_vertices_indexed()
+        .expect("Failed to build vertices");
+    assert_eq!(original_indices, flipped_indices);
+    assert_eq!(original.len(), flipped.len());
+    for (a, b) in original.iter().zip(flipped.iter()) {
+        assert_eq!(a.position, b.position);
+        assert_eq!(a.texcoord, b.texcoord);
+        assert_eq!(Vector3::from(a.normal), -Vector3::from(b.normal));
+    }
+}
+
+#[test]
+pub fn ensure_cw_cuboid_mirrors_ccw_triangles() {
+    let expected = CuboidBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let vertices = CuboidBuilder::new()
+        .winding(Winding::Cw)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    assert_eq!(vertices.len(), expected.len());
+    for (chunk, expected) in vertices.chunks(3).zip(expected.chunks(3)) {
+        assert_eq!(chunk[0].position, expected[0].position);
+        assert_eq!(chunk[0].normal, expected[0].normal);
+        assert_eq!(chunk[0].texcoord, expected[0].texcoord);
+        assert_eq!(chunk[1].position, expected[2].position);
+        assert_eq!(chunk[1].normal, expected[2].normal);
+        assert_eq!(chunk[1].texcoord, expected[2].texcoord);
+        assert_eq!(chunk[2].position, expected[1].position);
+        assert_eq!(chunk[2].normal, expected[1].normal);
+        assert_eq!(chunk[2].texcoord, expected[1].texcoord);
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let eyepos = v0 + Vector3::<f32>::from(chunk[0].normal);
+        let e0 = v1 - v0;
+        let e1 = v2 - v0;
+        let n = e0.cross(e1);
+        assert!(n.dot(v0 - eyepos) >= 0.0);
+        assert!(n.dot(v1 - eyepos) >= 0.0);
+        assert!(n.dot(v2 - eyepos) >= 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_cuboid_has_outward_front_faces_under_each_convention() {
+    use crate::convention::Convention;
+
+    let conventions = [
+        (
+            Convention::YUpRightHanded,
+            Vector3::<f32>::new(0.0, 2.0, 0.0),
+        ),
+        (
+            Convention::ZUpRightHanded,
+            Vector3::<f32>::new(0.0, 0.0, 2.0),
+        ),
+        (
+            Convention::YUpLeftHanded,
+            Vector3::<f32>::new(0.0, 2.0, 0.0),
+        ),
+        (
+            Convention::ZUpLeftHanded,
+            Vector3::<f32>::new(0.0, 0.0, 2.0),
+        ),
+    ];
+    for &(convention, centre) in conventions.iter() {
+        let vertices = CuboidBuilder::new()
+            .translate(0.0, 2.0, 0.0)
+            .convention(convention)
+            .build_vertices()
+            .expect("Failed to build vertices");
+        for chunk in vertices.chunks(3) {
+            let v0 = Vector3::<f32>::from(chunk[0].position);
+            let v1 = Vector3::<f32>::from(chunk[1].position);
+            let v2 = Vector3::<f32>::from(chunk[2].position);
+            let normal = Vector3::<f32>::from(chunk[0].normal);
+            assert!(normal.dot(v0 - centre) > 0.0);
+
+            // Under the rules of each convention, the cross product of the edges
+            // points out of the front face, which is wound counter-clock-wise
+            // in the right-handed conventions and clock-wise in the left-handed
+            // conventions when viewed from outside.
+            let n = (v1 - v0).cross(v2 - v0);
+            assert!(n.dot(normal) > 0.0);
+        }
+    }
+}
+
+#[test]
+pub fn ensure_constant_color_covers_chamfers() {
+    let vertices = CuboidBuilder::new()
+        .color(0.1, 0.2, 0.3, 0.4)
+        .chamfer(0.1)
+        .build_vertices_colored()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        assert_eq!(vertex.color, [0.1, 0.2, 0.3, 0.4]);
+    }
+}
+
+#[test]
+pub fn ensure_gradient_colors_extreme_faces_with_endpoint_colors() {
+    use crate::axis::Axis;
+
+    let (min, max) = ([1.0, 0.0, 0.0, 1.0], [0.0, 0.0, 1.0, 0.5]);
+    let builder = CuboidBuilder::new()
+        .scale(3.0, 2.0, 1.0)
+        .rotate_z(0.5)
+        .color_gradient(Axis::X, min, max);
+    let vertices = builder
+        .build_vertices_colored()
+        .expect("Failed to build vertices");
+    for vertex in &vertices[builder.face_range(Face::NegX)] {
+        assert_eq!(vertex.color, min);
+    }
+    for vertex in &vertices[builder.face_range(Face::PosX)] {
+        assert_eq!(vertex.color, max);
+    }
+}
+
+#[test]
+pub fn ensure_normal_colors_follow_transformed_normals() {
+    let vertices = CuboidBuilder::new()
+        .scale(1.0, 4.0, 1.0)
+        .rotate_y(0.75)
+        .chamfer(0.1)
+        .debug_color_from_normals()
+        .build_vertices_colored()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        for axis in 0..3 {
+            assert_eq!(vertex.color[axis], vertex.normal[axis] * 0.5 + 0.5);
+        }
+        assert_eq!(vertex.color[3], 1.0);
+    }
+}
+
+#[test]
+pub fn ensure_radial_array_of_cuboids_is_centred_on_its_axis() {
+    use crate::axis::Axis;
+
+    let builder = CuboidBuilder::new()
+        .scale(0.5, 1.0, 2.0)
+        .rotate_x(0.3)
+        .translate(0.0, 1.0, 0.5)
+        .radial_array(5, Axis::Y, 3.0);
+    let vertices = builder.build_vertices().expect("Failed to build vertices");
+    assert_eq!(vertices.len(), builder.num_vertices());
+    assert_eq!(vertices.len(), 36 * 5);
+    let mut centroid = Vector3::<f32>::zero();
+    for vertex in &vertices {
+        centroid += Vector3::<f32>::from(vertex.position);
+    }
+    centroid /= vertices.len() as f32;
+    assert_ulps_eq!(centroid.x, 0.0, epsilon = 0.0001);
+    assert_ulps_eq!(centroid.z, 0.0, epsilon = 0.0001);
+
+    // The normals of each copy point away from the centre of the copy.
+    for copy in vertices.chunks(36) {
+        let mut centre = Vector3::<f32>::zero();
+        for vertex in copy {
+            centre += Vector3::<f32>::from(vertex.position);
+        }
+        centre /= copy.len() as f32;
+        for vertex in copy {
+            let position = Vector3::<f32>::from(vertex.position);
+            assert!(Vector3::<f32>::from(vertex.normal).dot(position - centre) > 0.0);
+        }
+    }
+}
+
+#[test]
+pub fn ensure_empty_radial_array_fails_to_build() {
+    use crate::axis::Axis;
+
+    match CuboidBuilder::new()
+        .radial_array(0, Axis::Y, 1.0)
+        .build_vertices()
+    {
+        Err(ShapeCreationError::InvalidDimensions) => (),
+        _ => panic!("Expected InvalidDimensions"),
+    }
+}
+
+#[test]
+pub fn ensure_linear_array_translates_each_copy_by_offset() {
+    let builder = CuboidBuilder::new().rotate_y(0.25);
+    let base = builder.build_vertices().expect("Failed to build vertices");
+    let offset = Vector3::<f32>::new(3.0, 0.0, -1.0);
+    let builder = builder.linear_array(4, offset);
+    let vertices = builder.build_vertices().expect("Failed to build vertices");
+    assert_eq!(vertices.len(), 4 * base.len());
+    assert_eq!(vertices.len(), builder.num_vertices());
+    let last = &vertices[3 * base.len()..];
+    for (index, vertex) in base.iter().enumerate() {
+        assert_eq!(vertices[index].position, vertex.position);
+        let expected = Vector3::<f32>::from(vertex.position) + offset * 3.0;
+        assert_ulps_eq!(Vector3::<f32>::from(last[index].position), expected);
+        assert_eq!(last[index].normal, vertex.normal);
+    }
+}
+
+#[test]
+pub fn ensure_mirrored_cuboid_halves_are_front_facing() {
+    use crate::mirror_plane::MirrorPlane;
+
+    let builder = CuboidBuilder::new()
+        .translate(2.0, 0.0, 0.0)
+        .mirror(MirrorPlane::Yz);
+    for (index, vertices) in [
+        builder.build_vertices().expect("Failed to build vertices"),
+        builder
+            .build_vertices_indexed()
+            .map(|(vertices, indices)| indices.iter().map(|&i| vertices[i as usize]).collect())
+            .expect("Failed to build vertices"),
+    ]
+    .iter()
+    .enumerate()
+    {
+        assert_eq!(vertices.len(), 72, "build {}", index);
+        for (copy, centre) in vertices.chunks(36).zip([2.0, -2.0].iter()) {
+            let centre = Vector3::<f32>::new(*centre, 0.0, 0.0);
+            for chunk in copy.chunks(3) {
+                let v0 = Vector3::<f32>::from(chunk[0].position);
+                let v1 = Vector3::<f32>::from(chunk[1].position);
+                let v2 = Vector3::<f32>::from(chunk[2].position);
+                let normal = Vector3::<f32>::from(chunk[0].normal);
+                assert!((v1 - v0).cross(v2 - v0).dot(normal) > 0.0);
+                assert!(normal.dot(v0 - centre) > 0.0);
+            }
+        }
+    }
+}
+
+#[test]
+pub fn ensure_jittered_cuboid_is_deterministic() {
+    let build = |seed: u32| {
+        CuboidBuilder::new()
+            .jitter(0.1, seed)
+            .build_vertices()
+            .expect("Failed to build vertices")
+    };
+    let (first, second, other) = (build(3), build(3), build(4));
+    for (a, b) in first.iter().zip(second.iter()) {
+        assert_eq!(a.position, b.position);
+        assert_eq!(a.normal, b.normal);
+    }
+    assert!(first
+        .iter()
+        .zip(other.iter())
+        .any(|(a, b)| a.position != b.position));
+}
+
+#[test]
+pub fn ensure_zero_jitter_leaves_cuboid_as_it_is() {
+    let builder = CuboidBuilder::new()
+        .shading(Shading::Smooth)
+        .subdivisions(2, 3, 4);
+    let expected = builder
+        .build_vertices_indexed()
+        .expect("Failed to build vertices");
+    let (vertices, indices) = builder
+        .jitter(0.0, 1)
+        .build_vertices_indexed()
+        .expect("Failed to build vertices");
+    assert_eq!(indices, expected.1);
+    for (vertex, expected) in vertices.iter().zip(expected.0.iter()) {
+        assert_eq!(vertex.position, expected.position);
+        assert_eq!(vertex.normal, expected.normal);
+        assert_eq!(vertex.texcoord, expected.texcoord);
+    }
+}
+
+#[test]
+pub fn ensure_jittered_subdivided_cuboid_has_no_cracks() {
+    let builder = CuboidBuilder::new().subdivisions(3, 2, 4).chamfer(0.1);
+    let base = builder.build_vertices().expect("Failed to build vertices");
+    let vertices = builder
+        .jitter(0.05, 11)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    assert_eq!(vertices.len(), base.len());
+
+    // Every vertex that shares a position with another before displacement
+    // is displaced to the same position.
+    let mut displaced = std::collections::HashMap::new();
+    for (vertex, base) in vertices.iter().zip(base.iter()) {
+        let key = base
+            .position
+            .map(|x| if x == 0.0 { 0 } else { x.to_bits() });
+        assert_ne!(vertex.position, base.position);
+        let position = *displaced.entry(key).or_insert(vertex.position);
+        assert_eq!(vertex.position, position);
+    }
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let normal = (v1 - v0).cross(v2 - v0).normalize();
+        for vertex in chunk {
+            assert_ulps_eq!(
+                Vector3::<f32>::from(vertex.normal),
+                normal,
+                epsilon = 0.0001
+            );
+        }
+    }
+}
+
+#[test]
+pub fn ensure_offset_flat_cuboid_separates_at_edges() {
+    let builder = CuboidBuilder::new();
+    let base = builder.build_vertices().expect("Failed to build vertices");
+    let vertices = builder
+        .offset_along_normals(0.1)
+        .build_vertices()
+        .expect("Failed to build vertices");
+
+    // Each face moves out along its own normal, such that the corners shared
+    // between faces are torn apart.
+    let mut offset = std::collections::HashMap::new();
+    let mut separated = false;
+    for (vertex, base) in vertices.iter().zip(base.iter()) {
+        assert_eq!(vertex.normal, base.normal);
+        let expected = Vector3::<f32>::from(base.position) + Vector3::from(base.normal) * 0.1;
+        assert_ulps_eq!(Vector3::<f32>::from(vertex.position), expected);
+        let key = base.position.map(f32::to_bits);
+        separated |= *offset.entry(key).or_insert(vertex.position) != vertex.position;
+    }
+    assert!(separated);
+}
+
+#[test]
+pub fn ensure_negative_offset_shrinks_smooth_cuboid_towards_centre() {
+    let builder = CuboidBuilder::new().shading(Shading::Smooth).flip_normals();
+    let base = builder.build_vertices().expect("Failed to build vertices");
+    let vertices = builder
+        .offset_along_normals(-0.1)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for (vertex, base) in vertices.iter().zip(base.iter()) {
+        // The offset follows the outward normals, whether or not flipped.
+        assert_eq!(vertex.normal, base.normal);
+        let base = Vector3::<f32>::from(base.position);
+        let expected = base - base.normalize() * 0.1;
+        assert_ulps_eq!(Vector3::<f32>::from(vertex.position), expected);
+    }
+}
+
+#[test]
+pub fn ensure_cuboid_tangents_follow_texture_coordinates() {
+    let builder = CuboidBuilder::new().scale(2.0, 3.0, 4.0);
+    let vertices = builder
+        .build_vertices_with_tangents()
+        .expect("Failed to build vertices");
+    let (indexed, indices) = builder
+        .build_vertices_with_tangents_indexed()
+        .expect("Failed to build vertices");
+    let triangles = vertices.chunks(3).map(|triangle| triangle.to_vec()).chain(
+        indices
+            .chunks(3)
+            .map(|triangle| triangle.iter().map(|&i| indexed[i as usize]).collect()),
+    );
+    for triangle in triangles {
+        // The directions in which the texture coordinates increase over the
+        // face of the triangle.
+        let p = |i: usize| Vector3::<f32>::from(triangle[i].position);
+        let (e1, e2) = (p(1) - p(0), p(2) - p(0));
+        let t = |i: usize, axis: usize| triangle[i].texcoord[axis] - triangle[0].texcoord[axis];
+        let r = t(1, 0) * t(2, 1) - t(2, 0) * t(1, 1);
+        let du = (e1 * t(2, 1) - e2 * t(1, 1)) / r;
+        let dv = (e2 * t(1, 0) - e1 * t(2, 0)) / r;
+        for vertex in triangle.iter() {
+            let normal = Vector3::<f32>::from(vertex.normal);
+            let tangent = Vector4::<f32>::from(vertex.tangent);
+            let bitangent = normal.cross(tangent.truncate()) * tangent.w;
+            assert_ulps_eq!(tangent.truncate().magnitude(), 1.0);
+            assert_ulps_eq!(tangent.truncate().dot(normal), 0.0, epsilon = 0.0001);
+            assert_ulps_eq!(tangent.truncate(), du.normalize(), epsilon = 0.0001);
+            assert_ulps_eq!(bitangent, dv.normalize(), epsilon = 0.0001);
+        }
+    }
+}
+
+#[cfg(test)]
+fn assert_lightmap_faces_are_disjoint(triangles: &[[VertexLightmapped; 3]], gutter: f32) {
+    // Gather the bounding rectangle of the lightmap texture coordinates of
+    // each face, as identified by its face normal.
+    let mut faces = std::collections::BTreeMap::new();
+    for triangle in triangles {
+        let p = |i: usize| Vector3::<f32>::from(triangle[i].position);
+        let normal = (p(1) - p(0)).cross(p(2) - p(0)).normalize();
+        let key = [0, 1, 2].map(|axis| (normal[axis] * 1000.0).round() as i32);
+        let rect = faces.entry(key).or_insert(([1.0f32; 2], [0.0f32; 2]));
+        for vertex in triangle {
+            for axis in 0..2 {
+                let t = vertex.texcoord1[axis];
+                assert!((0.0..=1.0).contains(&t));
+                rect.0[axis] = rect.0[axis].min(t);
+                rect.1[axis] = rect.1[axis].max(t);
+            }
+        }
+    }
+    let rects = faces.values().collect::<Vec<_>>();
+    for (i, a) in rects.iter().enumerate() {
+        for b in rects[i + 1..].iter() {
+            let apart = (0..2).any(|axis| {
+                a.1[axis] + gutter * 0.99 <= b.0[axis] || b.1[axis] + gutter * 0.99 <= a.0[axis]
+            });
+            assert!(apart, "Lightmap faces {:?} and {:?} overlap", a, b);
+        }
+    }
+}
+
+#[test]
+pub fn ensure_lightmapped_cuboid_faces_do_not_overlap() {
+    let builder = CuboidBuilder::new()
+        .scale(1.0, 2.0, 3.0)
+        .with_lightmap_uvs(0.02);
+    let base = builder.build_vertices().expect("Failed to build vertices");
+    let vertices = builder
+        .build_vertices_lightmapped()
+        .expect("Failed to build vertices");
+    for (vertex, base) in vertices.iter().zip(base.iter()) {
+        assert_eq!(vertex.texcoord, base.texcoord);
+    }
+    let triangles = vertices
+        .chunks(3)
+        .map(|t| [t[0], t[1], t[2]])
+        .collect::<Vec<_>>();
+    assert_lightmap_faces_are_disjoint(&triangles, 0.02);
+
+    let (vertices, indices) = builder
+        .build_vertices_lightmapped_indexed()
+        .expect("Failed to build vertices");
+    let triangles = indices
+        .chunks(3)
+        .map(|t| [0, 1, 2].map(|i| vertices[t[i] as usize]))
+        .collect::<Vec<_>>();
+    assert_lightmap_faces_are_disjoint(&triangles, 0.02);
+}
+
+#[test]
+pub fn ensure_lightmapped_chamfered_cuboid_faces_do_not_overlap() {
+    let vertices = CuboidBuilder::new()
+        .subdivisions(2, 3, 4)
+        .chamfer(0.1)
+        .with_lightmap_uvs(0.01)
+        .build_vertices_lightmapped()
+        .expect("Failed to build vertices");
+    let triangles = vertices
+        .chunks(3)
+        .map(|t| [t[0], t[1], t[2]])
+        .collect::<Vec<_>>();
+    assert_lightmap_faces_are_disjoint(&triangles, 0.01);
+}
+
+#[test]
+pub fn ensure_lightmap_gutter_of_half_is_invalid() {
+    match CuboidBuilder::new()
+        .with_lightmap_uvs(0.5)
+        .build_vertices_lightmapped()
+    {
+        Err(ShapeCreationError::InvalidDimensions) => (),
+        _ => panic!("Expected InvalidDimensions"),
+    }
+}
+
+#[cfg(feature = "mint")]
+#[test]
+pub fn ensure_cuboid_corners_may_be_given_as_mint_points() {
+    let expected =
+        CuboidBuilder::from_corners(Point3::new(-1.0, 0.0, 2.0), Point3::new(3.0, 1.0, 4.0))
+            .build_vertices()
+            .expect("Failed to build vertices");
+    let vertices = CuboidBuilder::from_corners(
+        mint::Point3 {
+            x: -1.0,
+            y: 0.0,
+            z: 2.0,
+        },
+        mint::Point3 {
+            x: 3.0,
+            y: 1.0,
+            z: 4.0,
+        },
+    )
+    .build_vertices()
+    .expect("Failed to build vertices");
+    for (vertex, expected) in vertices.iter().zip(expected.iter()) {
+        assert_eq!(vertex.position, expected.position);
+        assert_eq!(vertex.normal, expected.normal);
+    }
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_cuboid() {
+    use crate::convention::Convention;
+    use crate::math::{Cgmath, Glam};
+    use crate::mirror_plane::MirrorPlane;
+    use crate::options::{WithModifiers, WithOrientation, WithTexcoords};
+    use crate::uv_mapping::UvMapping;
+
+    let builder = CuboidBuilder::from_corners(
+        mint::Point3::from(glam::Vec3::new(-1.0, 0.0, 2.0)),
+        mint::Point3::from(glam::Vec3::new(3.0, 1.0, 4.0)),
+    )
+    .uv_mapping(UvMapping::Box)
+    .scale(1.5, 0.75, 2.0)
+    .rotate(glam::Quat::from_rotation_y(0.7))
+    .rotate_x(0.3)
+    .translate(0.25, -0.5, 1.0)
+    .linear_array(2, mint::Vector3::from(glam::Vec3::new(0.5, 6.0, 0.0)))
+    .mirror(MirrorPlane::Yz)
+    .convention(Convention::ZUpLeftHanded);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
+
+#[test]
+pub fn ensure_cuboid_applies_late_pipeline() {
+    use crate::options::WithTexcoords;
+    use crate::uv_mapping::UvMapping;
+
+    crate::options::ensure_late_pipeline(
+        || {
+            CuboidBuilder::new()
+                .uv_mapping(UvMapping::Box)
+                .double_sided()
+        },
+        CuboidBuilder::build_vertices,
+    );
 }
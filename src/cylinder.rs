@@ -0,0 +1,684 @@
+//! A module for constructing cylinder shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::axis::Axis;
+use crate::errors::ShapeCreationError;
+use crate::facet::flatten_normals;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{
+    BuildAs, HasOptions, HasVertices, ShapeOptions, WithBackFaces, WithCaps, WithColors,
+    WithLightmap, WithModifiers, WithOrientation, WithShading, WithTangents, WithTexcoords,
+    WithTransform,
+};
+use crate::resolution::{Resolution, WithResolution};
+use crate::shading::Shading;
+use crate::vertex::Vertex;
+
+use std::f32;
+
+/// A polygonal `Cylinder` object.
+///
+/// This object is constructed using a `CylinderBuilder` object.
+pub struct Cylinder {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows a `Cylinder` object to be passed as a source of vertices.
+impl<'a> From<&'a Cylinder> for glium::vertex::VerticesSource<'a> {
+    fn from(cylinder: &'a Cylinder) -> glium::vertex::VerticesSource<'a> {
+        (&cylinder.vertices).into()
+    }
+}
+
+/// Allows a `Cylinder` object to be passed as a source of indices.
+impl<'a> From<&'a Cylinder> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a Cylinder) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::TrianglesList,
+        }
+    }
+}
+
+/// Responsible for building and returning a `Cylinder` object.
+///
+/// By default, the cylinder is defined as a capped cylinder of unit height and
+/// unit diameter, aligned to the y-axis, with its centre-of-mass located at
+/// the origin. This can be overriden using the transformation methods on this
+/// object.
+///
+/// The resultant geometry is constructed to suit OpenGL defaults - assuming
+/// a right-handed coordinate system, front-facing polygons are defined in
+/// counter-clock-wise order. Vertex normals on the side of the cylinder point
+/// radially outward (such that the side appears smooth when lit), whereas the
+/// normals of each cap point along the axis (such that the caps appear faceted
+/// against the side). Vertex texture coordinates define a cylindrical-projection
+/// on the side, and a planar-projection on each cap. Vertices along the seam
+/// of the side are duplicated such that the seam has both a 0 and a 1 u
+/// coordinate.
+///
+/// The cylinder may sweep only part of a turn about its axis, in which case
+/// it is closed by a flat face on each of its cut planes, with normals
+/// perpendicular to the plane. Texture coordinates on the cut faces map the
+/// distance from the axis to the u coordinate, and the height to the v
+/// coordinate.
+pub struct CylinderBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    axis: Axis,
+    segments: usize,
+    stacks: usize,
+    start_angle: f32,
+    sweep_angle: f32,
+    options: ShapeOptions,
+}
+
+impl Default for CylinderBuilder {
+    fn default() -> Self {
+        CylinderBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            axis: Axis::Y,
+            segments: 24,
+            stacks: 1,
+            start_angle: 0.0,
+            sweep_angle: 2.0 * f32::consts::PI,
+            options: ShapeOptions {
+                shading: Shading::Smooth,
+                ..ShapeOptions::default()
+            },
+        }
+    }
+}
+
+impl CylinderBuilder {
+    /// Create a new `CylinderBuilder` object.
+    pub fn new() -> CylinderBuilder {
+        Default::default()
+    }
+
+    /// Specify the number of radial segments around the cylinder. By default,
+    /// the builder will use 24 segments. At least 3 segments are required to
+    /// build a valid cylinder.
+    pub fn segments(mut self, segments: usize) -> Self {
+        self.segments = segments;
+        self
+    }
+
+    /// Specify the number of stacks along the height of the cylinder. By
+    /// default, the builder will use 1 stack. At least 1 stack is required to
+    /// build a valid cylinder.
+    pub fn stacks(mut self, stacks: usize) -> Self {
+        self.stacks = stacks;
+        self
+    }
+
+    /// Specify the angle at which the cylinder starts, in radians measured
+    /// counter-clock-wise about the y-axis from the positive x-axis. By
+    /// default, the start angle is 0.
+    pub fn start_angle(mut self, radians: f32) -> Self {
+        self.start_angle = radians;
+        self
+    }
+
+    /// Specify the angle swept by the cylinder about its axis, in radians. By
+    /// default, the sweep angle is a full turn. The sweep angle must be
+    /// greater than zero, and no greater than a full turn, to build a valid
+    /// cylinder.
+    pub fn sweep_angle(mut self, radians: f32) -> Self {
+        self.sweep_angle = radians;
+        self
+    }
+
+    /// Specify the axis along which the cylinder is aligned. By default, the
+    /// cylinder is aligned to the y-axis. The cylinder is rotated onto its axis
+    /// before any other transformation is applied.
+    pub fn axis(mut self, axis: Axis) -> Self {
+        self.axis = axis;
+        self
+    }
+
+    /// Build a new `Cylinder` object.
+    pub fn build<F>(self, display: &F) -> Result<Cylinder, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the shape vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// a `Cylinder` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        let vertices = self.vertices_with::<Selected>()?;
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.options.check_shading(Self::SHADINGS)?;
+
+        // Ensure there are enough segments and stacks to produce valid
+        // cylinder geometry.
+        if self.segments < 3 {
+            return Err(ShapeCreationError::NotEnoughDivisionsInU);
+        }
+
+        if self.stacks < 1 {
+            return Err(ShapeCreationError::NotEnoughDivisionsInV);
+        }
+
+        let full = self.is_full_turn();
+        if self.sweep_angle <= 0.0 || !(full || self.sweep_angle < 2.0 * f32::consts::PI) {
+            return Err(ShapeCreationError::InvalidDimensions);
+        }
+
+        // Build a lookup table of (cos, sin) pairs around the cylinder. A full
+        // turn reuses the first pair at the end of the table, such that the
+        // seam closes seamlessly.
+        let angle = self.sweep_angle / self.segments as f32;
+        let ring = (0..=self.segments)
+            .map(|x| {
+                let x = if full { x % self.segments } else { x };
+                let a = self.start_angle + (x as f32) * angle;
+                [a.cos(), a.sin()]
+            })
+            .collect::<Vec<[f32; 2]>>();
+
+        // Compute the transformation matrix, which rotates the shape onto its
+        // axis before any other transformation, and the normal transformation
+        // matrix.
+        let matrix = self.matrix * self.axis.matrix();
+        let transform = Transformation::<M>::new(matrix);
+
+        let mut vertices = Vec::<Vertex>::with_capacity(self.num_vertices());
+        let mut emit = |position: Vector3<f32>, normal: Vector3<f32>, texcoord: [f32; 2]| {
+            vertices.push(Vertex {
+                position: transform.point(position),
+                normal: transform.normal(normal),
+                texcoord,
+            });
+        };
+
+        // Emit the side faces. Angles increase counter-clock-wise when viewed
+        // from above, such that the u coordinate increases from left to right
+        // when viewed from outside. Each stack is built from a quad per
+        // segment, from the bottom of the cylinder to the top.
+        let quad = [(0, 0), (1, 0), (1, 1), (0, 0), (1, 1), (0, 1)];
+        for stack in 0..self.stacks {
+            for seg in 0..self.segments {
+                for &(du, dv) in quad.iter() {
+                    let [c, s] = ring[seg + du];
+                    let v = (stack + dv) as f32 / self.stacks as f32;
+                    emit(
+                        Vector3::<f32>::new(c * 0.5, v - 0.5, -s * 0.5),
+                        Vector3::<f32>::new(c, 0.0, -s),
+                        [(seg + du) as f32 / self.segments as f32, v],
+                    );
+                }
+            }
+        }
+
+        // Emit the top and bottom caps as triangle fans about each cap centre,
+        // skipping any cap that is disabled.
+        let caps = [
+            (0.5, self.options.caps.caps_end(), [0, 1]),
+            (-0.5, self.options.caps.caps_start(), [1, 0]),
+        ];
+        for &(y, _, order) in caps.iter().filter(|cap| cap.1) {
+            let normal = Vector3::<f32>::new(0.0, y * 2.0, 0.0);
+            for seg in 0..self.segments {
+                let indices = [seg + order[0], seg + order[1]];
+                emit(Vector3::<f32>::new(0.0, y, 0.0), normal, [0.5, 0.5]);
+                for &index in indices.iter() {
+                    let [c, s] = ring[index];
+                    let (x, z) = (c * 0.5, -s * 0.5);
+                    emit(
+                        Vector3::<f32>::new(x, y, z),
+                        normal,
+                        [x + 0.5, 0.5 - (z * y * 2.0)],
+                    );
+                }
+            }
+        }
+
+        // Emit the cut faces, unless the cylinder sweeps a full turn. Each is
+        // built from a quad per stack, between the axis and the side. The
+        // face at the end of the sweep is mirrored, such that both faces are
+        // wound counter-clock-wise when viewed from outside.
+        if !full {
+            let [c, s] = ring[0];
+            let start = Vector3::<f32>::new(s, 0.0, c);
+            let [c, s] = ring[self.segments];
+            let end = Vector3::<f32>::new(-s, 0.0, -c);
+            for &(index, normal, flip) in [(0, start, false), (self.segments, end, true)].iter() {
+                let [c, s] = ring[index];
+                for stack in 0..self.stacks {
+                    for &(r, dv) in quad.iter() {
+                        let r = if flip { 1 - r } else { r };
+                        let v = (stack + dv) as f32 / self.stacks as f32;
+                        let position = if r == 0 {
+                            Vector3::<f32>::new(0.0, v - 0.5, 0.0)
+                        } else {
+                            Vector3::<f32>::new(c * 0.5, v - 0.5, -s * 0.5)
+                        };
+                        emit(position, normal, [r as f32, v]);
+                    }
+                }
+            }
+        }
+
+        if self.options.shading == Shading::Flat {
+            flatten_normals(&mut vertices);
+        }
+
+        self.options.map_texcoords(&mut vertices);
+        Ok(vertices)
+    }
+
+    /// Returns true if the cylinder sweeps a full turn, such that it has no
+    /// cut faces.
+    fn is_full_turn(&self) -> bool {
+        ulps_eq!(self.sweep_angle, 2.0 * f32::consts::PI)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    pub fn num_vertices(&self) -> usize {
+        let cuts = if self.is_full_turn() {
+            0
+        } else {
+            2 * self.stacks * 6
+        };
+        let caps = [self.options.caps.caps_end(), self.options.caps.caps_start()]
+            .iter()
+            .filter(|&&cap| cap)
+            .count();
+        ((self.segments * self.stacks * 6) + (self.segments * 3 * caps) + cuts)
+            * self.options.copies()
+    }
+}
+
+impl HasOptions for CylinderBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for CylinderBuilder {
+    type Shape = Cylinder;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> Cylinder {
+        Cylinder { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for CylinderBuilder {}
+
+impl BuildAs for CylinderBuilder {}
+
+impl WithTangents for CylinderBuilder {}
+
+impl WithLightmap for CylinderBuilder {}
+
+impl WithOrientation for CylinderBuilder {}
+
+impl WithBackFaces for CylinderBuilder {}
+
+impl WithModifiers for CylinderBuilder {}
+
+/// The start of the cylinder is its bottom, and its end is its top.
+impl WithCaps for CylinderBuilder {}
+
+impl WithTexcoords for CylinderBuilder {}
+
+impl WithColors for CylinderBuilder {}
+
+impl WithShading for CylinderBuilder {
+    /// By default, the cylinder is smooth shaded. When flat shaded, each vertex
+    /// normal points in the direction of its respective triangle instead.
+    const SHADINGS: &'static [Shading] = &[Shading::Flat, Shading::Smooth];
+}
+
+impl WithResolution for CylinderBuilder {
+    /// At least 3 segments around the cylinder, and 1 stack along its height.
+    const MIN_RESOLUTION: Resolution = Resolution {
+        around: 3,
+        along: 1,
+    };
+
+    /// Specify the number of segments around the cylinder, and the number of
+    /// stacks along its height.
+    fn resolution(mut self, resolution: Resolution) -> Self {
+        self.segments = resolution.around as usize;
+        self.stacks = resolution.along as usize;
+        self
+    }
+}
+
+#[test]
+pub fn ensure_default_cylinder_has_unit_dimensions() {
+    let vertices = CylinderBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        let pos = Vector3::<f32>::from(vertex.position);
+        assert!(abs_diff_eq!(pos.y.abs(), 0.5));
+        assert!(Vector2::<f32>::new(pos.x, pos.z).magnitude() <= 0.5 + 0.0001);
+    }
+}
+
+#[test]
+pub fn ensure_default_cylinder_has_centroid_at_origin() {
+    let vertices = CylinderBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let mut sum = Vector3::<f32>::zero();
+    for vertex in &vertices {
+        sum += Vector3::<f32>::from(vertex.position);
+    }
+    assert_ulps_eq!(sum, Vector3::<f32>::zero(), epsilon = 0.0001);
+}
+
+#[test]
+pub fn ensure_default_cylinder_has_outward_facing_normals() {
+    let vertices = CylinderBuilder::new()
+        .scale(2.0, 2.0, 2.0)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        let position = Vector3::<f32>::from(vertex.position);
+        let normal = Vector3::<f32>::from(vertex.normal);
+        let outside = position + normal;
+        assert!(outside.x.abs() >= position.x.abs());
+        assert!(outside.y.abs() >= position.y.abs());
+        assert!(outside.z.abs() >= position.z.abs());
+    }
+}
+
+#[test]
+pub fn ensure_default_cylinder_has_uvs_in_unit_range() {
+    let vertices = CylinderBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        assert!(vertex.texcoord[0] >= 0.0);
+        assert!(vertex.texcoord[1] >= 0.0);
+        assert!(vertex.texcoord[0] <= 1.0);
+        assert!(vertex.texcoord[1] <= 1.0);
+    }
+}
+
+#[test]
+pub fn ensure_default_cylinder_has_ccw_triangles() {
+    let vertices = CylinderBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let eyepos = v0 + Vector3::<f32>::from(chunk[0].normal);
+        let e0 = v1 - v0;
+        let e1 = v2 - v0;
+        let n = e0.cross(e1);
+        assert!(n.dot(v0 - eyepos) <= 0.0);
+        assert!(n.dot(v1 - eyepos) <= 0.0);
+        assert!(n.dot(v2 - eyepos) <= 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_cylinder_side_normals_are_radial() {
+    let vertices = CylinderBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in vertices.iter().take(CylinderBuilder::new().segments * 6) {
+        let pos = Vector3::<f32>::from(vertex.position);
+        let radial = Vector3::<f32>::new(pos.x, 0.0, pos.z).normalize();
+        assert_ulps_eq!(
+            Vector3::<f32>::from(vertex.normal),
+            radial,
+            epsilon = 0.0001
+        );
+    }
+}
+
+#[test]
+pub fn ensure_cylinder_with_too_few_segments_fails_to_build() {
+    let result = CylinderBuilder::new().segments(2).build_vertices();
+    match result {
+        Err(ShapeCreationError::NotEnoughDivisionsInU) => (),
+        _ => panic!("Expected NotEnoughDivisionsInU"),
+    }
+}
+
+#[test]
+pub fn ensure_cylinder_stacks_split_side_evenly() {
+    let builder = CylinderBuilder::new().segments(4).stacks(4);
+    let vertices = builder.build_vertices().expect("Failed to build vertices");
+    assert_eq!(vertices.len(), builder.num_vertices());
+    for vertex in vertices.iter().take(4 * 4 * 6) {
+        let y = vertex.position[1];
+        assert_ulps_eq!((y + 0.5) * 4.0, ((y + 0.5) * 4.0).round());
+        assert_ulps_eq!(vertex.texcoord[1], y + 0.5);
+    }
+}
+
+#[test]
+pub fn ensure_cylinder_resolution_matches_segments_and_stacks() {
+    let from_resolution = CylinderBuilder::new()
+        .resolution(Resolution::new(5, 3))
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let from_setters = CylinderBuilder::new()
+        .segments(5)
+        .stacks(3)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    assert_eq!(from_resolution.len(), from_setters.len());
+    for (a, b) in from_resolution.iter().zip(from_setters.iter()) {
+        assert_eq!(a.position, b.position);
+        assert_eq!(a.texcoord, b.texcoord);
+    }
+    crate::resolution::ensure_min_resolution(
+        CylinderBuilder::new,
+        CylinderBuilder::build_vertices,
+        ShapeCreationError::NotEnoughDivisionsInU,
+        Some(ShapeCreationError::NotEnoughDivisionsInV),
+    );
+}
+
+#[test]
+pub fn ensure_partial_cylinder_is_watertight() {
+    use std::collections::HashMap;
+    let vertices = CylinderBuilder::new()
+        .segments(9)
+        .stacks(2)
+        .start_angle(0.5)
+        .sweep_angle(1.5 * f32::consts::PI)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let mut edges = HashMap::<([u32; 3], [u32; 3]), usize>::new();
+    for chunk in vertices.chunks(3) {
+        for k in 0..3 {
+            let a = chunk[k].position.map(f32::to_bits);
+            let b = chunk[(k + 1) % 3].position.map(f32::to_bits);
+            *edges
+                .entry(if a < b { (a, b) } else { (b, a) })
+                .or_insert(0) += 1;
+        }
+    }
+    assert!(edges.values().all(|&count| count == 2));
+}
+
+#[test]
+pub fn ensure_partial_cylinder_cut_faces_have_planar_normals() {
+    let (start, sweep) = (0.25, 2.0);
+    let builder = CylinderBuilder::new()
+        .segments(7)
+        .start_angle(start)
+        .sweep_angle(sweep);
+    let vertices = builder.build_vertices().expect("Failed to build vertices");
+    assert_eq!(vertices.len(), builder.num_vertices());
+    let cuts = &vertices[7 * 6 + 7 * 3 * 2..];
+    assert_eq!(cuts.len(), 2 * 6);
+    for (chunk, a) in cuts.chunks(6).zip([start, start + sweep].iter()) {
+        let radial = Vector3::<f32>::new(a.cos(), 0.0, -a.sin());
+        let normal = if *a == start {
+            radial.cross(Vector3::unit_y())
+        } else {
+            Vector3::unit_y().cross(radial)
+        };
+        for tri in chunk.chunks(3) {
+            let [v0, v1, v2] = [0, 1, 2].map(|k| Vector3::<f32>::from(tri[k].position));
+            assert!((v1 - v0).cross(v2 - v0).dot(normal) > 0.0);
+        }
+        for vertex in chunk {
+            let pos = Vector3::<f32>::from(vertex.position);
+            assert!(abs_diff_eq!(pos.dot(normal), 0.0, epsilon = 0.0001));
+            assert_ulps_eq!(Vector3::from(vertex.normal), normal, epsilon = 0.0001);
+        }
+    }
+}
+
+#[test]
+pub fn ensure_full_sweep_cylinder_has_no_cut_faces() {
+    let builder = CylinderBuilder::new()
+        .start_angle(1.0)
+        .sweep_angle(2.0 * f32::consts::PI);
+    assert_eq!(
+        builder.num_vertices(),
+        CylinderBuilder::new().num_vertices()
+    );
+    let vertices = builder.build_vertices().expect("Failed to build vertices");
+    assert_eq!(vertices.len(), builder.num_vertices());
+}
+
+#[test]
+pub fn ensure_cylinder_with_invalid_sweep_fails_to_build() {
+    for &sweep in [0.0, -1.0, 7.0, f32::NAN].iter() {
+        match CylinderBuilder::new().sweep_angle(sweep).build_vertices() {
+            Err(ShapeCreationError::InvalidDimensions) => (),
+            _ => panic!("Expected InvalidDimensions"),
+        }
+    }
+}
+
+#[test]
+pub fn ensure_cylinder_caps_can_be_disabled() {
+    use crate::cap_mode::CapMode;
+    let builder = CylinderBuilder::new().segments(5);
+    assert_eq!(builder.num_vertices(), 5 * 6 + 5 * 3 * 2);
+    let builder = builder.caps(CapMode::Start);
+    assert_eq!(builder.num_vertices(), 5 * 6 + 5 * 3);
+    let vertices = builder.build_vertices().expect("Failed to build vertices");
+    assert_eq!(vertices.len(), builder.num_vertices());
+    for vertex in &vertices[5 * 6..] {
+        assert_eq!(vertex.position[1], -0.5);
+        assert_eq!(vertex.normal, [0.0, -1.0, 0.0]);
+    }
+    let vertices = CylinderBuilder::new()
+        .segments(5)
+        .caps(CapMode::Neither)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    assert_eq!(vertices.len(), 5 * 6);
+}
+
+#[test]
+pub fn ensure_cylinder_uvs_do_not_wrap_across_seam() {
+    let vertices = CylinderBuilder::new()
+        .stacks(2)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let us = chunk.iter().map(|v| v.texcoord[0]);
+        let min = us.clone().fold(f32::MAX, f32::min);
+        let max = us.fold(f32::MIN, f32::max);
+        assert!(max - min <= 0.5);
+    }
+}
+
+#[test]
+pub fn ensure_cylinder_axes_are_exact_quarter_turns() {
+    use crate::axis::Axis;
+    let base = CylinderBuilder::new()
+        .segments(7)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let rotate = |axis: Axis, [x, y, z]: [f32; 3]| match axis {
+        Axis::X => [y, -x, z],
+        Axis::Y => [x, y, z],
+        Axis::Z => [x, -z, y],
+    };
+    for &axis in [Axis::X, Axis::Y, Axis::Z].iter() {
+        let vertices = CylinderBuilder::new()
+            .segments(7)
+            .translate(0.0, 0.0, 1.0)
+            .axis(axis)
+            .build_vertices()
+            .expect("Failed to build vertices");
+        assert_eq!(vertices.len(), base.len());
+        for (a, b) in vertices.iter().zip(base.iter()) {
+            // The axis is applied before the translation.
+            let [x, y, z] = rotate(axis, b.position);
+            assert_eq!(a.position, [x, y, z + 1.0]);
+            assert_ulps_eq!(
+                Vector3::from(a.normal),
+                Vector3::from(rotate(axis, b.normal)),
+                epsilon = 0.000001
+            );
+            assert_eq!(a.texcoord, b.texcoord);
+        }
+    }
+}
+
+#[test]
+pub fn ensure_cylinder_applies_late_pipeline() {
+    use crate::options::WithTexcoords;
+    use crate::uv_mapping::UvMapping;
+
+    crate::options::ensure_late_pipeline(
+        || {
+            CylinderBuilder::new()
+                .uv_mapping(UvMapping::Box)
+                .double_sided()
+        },
+        CylinderBuilder::build_vertices,
+    );
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_cylinder() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = CylinderBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
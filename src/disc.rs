@@ -0,0 +1,384 @@
+//! A module for constructing disc shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::errors::ShapeCreationError;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{
+    BuildAs, HasOptions, HasVertices, ShapeOptions, WithBackFaces, WithColors, WithLightmap,
+    WithModifiers, WithOrientation, WithShading, WithTangents, WithTexcoords, WithTransform,
+};
+use crate::resolution::{Resolution, WithResolution};
+use crate::shading::Shading;
+use crate::vertex::Vertex;
+
+use std::f32;
+
+/// A polygonal `Disc` object.
+///
+/// This object is constructed using a `DiscBuilder` object.
+pub struct Disc {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows a `Disc` object to be passed as a source of vertices.
+impl<'a> From<&'a Disc> for glium::vertex::VerticesSource<'a> {
+    fn from(disc: &'a Disc) -> glium::vertex::VerticesSource<'a> {
+        (&disc.vertices).into()
+    }
+}
+
+/// Allows a `Disc` object to be passed as a source of indices.
+impl<'a> From<&'a Disc> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a Disc) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::TrianglesList,
+        }
+    }
+}
+
+/// Responsible for building and returning a `Disc` object.
+///
+/// By default, the disc is defined as a filled circle of unit diameter in the
+/// xy-plane, facing the positive z-axis, with its centre located at the origin.
+/// This can be overriden using the transformation methods on this object.
+///
+/// The resultant geometry is constructed to suit OpenGL defaults - assuming
+/// a right-handed coordinate system, front-facing polygons are defined in
+/// counter-clock-wise order. The disc is triangulated as a fan of triangles
+/// about its centre. Vertex normals point in the direction of the disc. Vertex
+/// texture coordinates define a planar-projection on the disc, with the centre
+/// of the disc at `(0.5, 0.5)` and the rim touching the edges of the unit square.
+pub struct DiscBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    segments: usize,
+    options: ShapeOptions,
+}
+
+impl Default for DiscBuilder {
+    fn default() -> Self {
+        DiscBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            segments: 24,
+            options: ShapeOptions::default(),
+        }
+    }
+}
+
+impl DiscBuilder {
+    /// Create a new `DiscBuilder` object.
+    pub fn new() -> DiscBuilder {
+        Default::default()
+    }
+
+    /// Specify the number of segments around the disc. By default, the builder
+    /// will use 24 segments. At least 3 segments are required to build a valid
+    /// disc.
+    pub fn segments(mut self, segments: usize) -> Self {
+        self.segments = segments;
+        self
+    }
+
+    /// Build a new `Disc` object.
+    pub fn build<F>(self, display: &F) -> Result<Disc, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the shape vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// a `Disc` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        let vertices = self.vertices_with::<Selected>()?;
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.options.check_shading(Self::SHADINGS)?;
+
+        // Ensure there are enough segments to produce valid disc geometry.
+        if self.segments < 3 {
+            return Err(ShapeCreationError::NotEnoughDivisionsInU);
+        }
+
+        // Build a lookup table of points around the rim of the disc.
+        let angle = 2.0 * f32::consts::PI / self.segments as f32;
+        let rim = (0..=self.segments)
+            .map(|x| {
+                let a = ((x % self.segments) as f32) * angle;
+                Vector2::<f32>::new(a.cos() * 0.5, a.sin() * 0.5)
+            })
+            .collect::<Vec<Vector2<f32>>>();
+
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<M>::new(self.matrix);
+        let normal = transform.normal(Vector3::<f32>::new(0.0, 0.0, 1.0));
+
+        // Build the triangle fan.
+        let mut vertices = Vec::<Vertex>::with_capacity(self.num_vertices());
+        for seg in 0..self.segments {
+            for &point in [Vector2::<f32>::zero(), rim[seg], rim[seg + 1]].iter() {
+                let position = Vector3::<f32>::new(point.x, point.y, 0.0);
+                vertices.push(Vertex {
+                    position: transform.point(position),
+                    normal,
+                    texcoord: [point.x + 0.5, point.y + 0.5],
+                });
+            }
+        }
+
+        self.options.map_texcoords(&mut vertices);
+        Ok(vertices)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    pub fn num_vertices(&self) -> usize {
+        self.segments * 3 * self.options.copies()
+    }
+}
+
+impl HasOptions for DiscBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for DiscBuilder {
+    type Shape = Disc;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> Disc {
+        Disc { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for DiscBuilder {}
+
+impl BuildAs for DiscBuilder {}
+
+impl WithTangents for DiscBuilder {}
+
+impl WithLightmap for DiscBuilder {}
+
+impl WithOrientation for DiscBuilder {}
+
+impl WithBackFaces for DiscBuilder {}
+
+impl WithModifiers for DiscBuilder {}
+
+impl WithTexcoords for DiscBuilder {}
+
+impl WithColors for DiscBuilder {}
+
+impl WithShading for DiscBuilder {
+    /// By default, the disc is flat shaded. Both modes are supported, and build
+    /// the same normals, since the disc is planar.
+    const SHADINGS: &'static [Shading] = &[Shading::Flat, Shading::Smooth];
+}
+
+impl WithResolution for DiscBuilder {
+    /// At least 3 segments around the disc. The disc is a single fan of
+    /// triangles without segments along its radius, such that any `along`
+    /// count is accepted.
+    const MIN_RESOLUTION: Resolution = Resolution {
+        around: 3,
+        along: 0,
+    };
+
+    /// Specify the number of segments around the disc. The `along` count is
+    /// ignored.
+    fn resolution(mut self, resolution: Resolution) -> Self {
+        self.segments = resolution.around as usize;
+        self
+    }
+}
+
+#[test]
+pub fn ensure_default_disc_has_unit_diameter() {
+    let vertices = DiscBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        assert_eq!(Vector3::<f32>::from(chunk[0].position), Vector3::zero());
+        for vertex in &chunk[1..] {
+            let pos = Vector3::<f32>::from(vertex.position);
+            assert!(abs_diff_eq!(pos.magnitude(), 0.5, epsilon = 0.0001));
+            assert!(abs_diff_eq!(pos.z, 0.0));
+        }
+    }
+}
+
+#[test]
+pub fn ensure_default_disc_has_centroid_at_origin() {
+    let vertices = DiscBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let mut sum = Vector3::<f32>::zero();
+    for vertex in &vertices {
+        sum += Vector3::<f32>::from(vertex.position);
+    }
+    assert_ulps_eq!(sum, Vector3::<f32>::zero(), epsilon = 0.0001);
+}
+
+#[test]
+pub fn ensure_default_disc_has_ccw_triangles() {
+    let vertices = DiscBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let eyepos = v0 + Vector3::<f32>::from(chunk[0].normal);
+        let e0 = v1 - v0;
+        let e1 = v2 - v0;
+        let n = e0.cross(e1);
+        assert!(n.dot(v0 - eyepos) <= 0.0);
+        assert!(n.dot(v1 - eyepos) <= 0.0);
+        assert!(n.dot(v2 - eyepos) <= 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_default_disc_has_forward_facing_normals() {
+    let vertices = DiscBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        assert_eq!(vertex.normal, [0.0, 0.0, 1.0]);
+    }
+}
+
+#[test]
+pub fn ensure_disc_uvs_touch_unit_square() {
+    use std::f32;
+    let vertices = DiscBuilder::new()
+        .segments(4)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let mut min = Vector2::<f32>::new(f32::MAX, f32::MAX);
+    let mut max = -min;
+    for vertex in &vertices {
+        min.x = f32::min(min.x, vertex.texcoord[0]);
+        min.y = f32::min(min.y, vertex.texcoord[1]);
+        max.x = f32::max(max.x, vertex.texcoord[0]);
+        max.y = f32::max(max.y, vertex.texcoord[1]);
+    }
+    assert_ulps_eq!(min, Vector2::<f32>::zero(), epsilon = 0.0001);
+    assert_ulps_eq!(max, Vector2::<f32>::from_value(1.0), epsilon = 0.0001);
+    assert_eq!(vertices[0].texcoord, [0.5, 0.5]);
+}
+
+#[test]
+pub fn ensure_disc_with_too_few_segments_fails_to_build() {
+    match DiscBuilder::new().segments(2).build_vertices() {
+        Err(ShapeCreationError::NotEnoughDivisionsInU) => (),
+        _ => panic!("Expected NotEnoughDivisionsInU"),
+    }
+}
+
+#[test]
+pub fn ensure_radial_array_of_discs_repeats_each_vertex() {
+    use crate::axis::Axis;
+
+    let original = DiscBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let builder = DiscBuilder::new().radial_array(5, Axis::Y, 2.0);
+    let vertices = builder.build_vertices().expect("Failed to build vertices");
+    assert_eq!(vertices.len(), builder.num_vertices());
+    assert_eq!(vertices.len(), original.len() * 5);
+
+    // Each copy is pushed out along the z-axis and rotated about the y-axis.
+    for (index, copy) in vertices.chunks(original.len()).enumerate() {
+        let angle = 2.0 * f32::consts::PI * (index as f32) / 5.0;
+        let rotation = Matrix3::<f32>::from_angle_y(Rad(angle));
+        for (vertex, base) in copy.iter().zip(original.iter()) {
+            let position = Vector3::<f32>::from(base.position) + Vector3::new(0.0, 0.0, 2.0);
+            assert_ulps_eq!(
+                Vector3::<f32>::from(vertex.position),
+                rotation * position,
+                epsilon = 0.0001
+            );
+            assert_ulps_eq!(
+                Vector3::<f32>::from(vertex.normal),
+                rotation * Vector3::<f32>::from(base.normal),
+                epsilon = 0.0001
+            );
+            assert_eq!(vertex.texcoord, base.texcoord);
+        }
+    }
+}
+
+#[test]
+pub fn ensure_empty_radial_array_of_discs_fails_to_build() {
+    use crate::axis::Axis;
+
+    match DiscBuilder::new()
+        .radial_array(0, Axis::Y, 1.0)
+        .build_vertices()
+    {
+        Err(ShapeCreationError::InvalidDimensions) => (),
+        _ => panic!("Expected InvalidDimensions"),
+    }
+}
+
+#[test]
+pub fn ensure_disc_applies_late_pipeline() {
+    use crate::options::WithTexcoords;
+    use crate::uv_mapping::UvMapping;
+
+    crate::options::ensure_late_pipeline(
+        || DiscBuilder::new().uv_mapping(UvMapping::Box).double_sided(),
+        DiscBuilder::build_vertices,
+    );
+}
+
+#[test]
+pub fn ensure_disc_with_invalid_resolution_fails_to_build() {
+    crate::resolution::ensure_min_resolution(
+        DiscBuilder::new,
+        DiscBuilder::build_vertices,
+        ShapeCreationError::NotEnoughDivisionsInU,
+        None,
+    );
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_disc() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = DiscBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
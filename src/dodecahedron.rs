@@ -0,0 +1,358 @@
+//! A module for constructing dodecahedron shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::errors::ShapeCreationError;
+use crate::facet::push_polygon;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{
+    BuildAs, HasOptions, HasVertices, ShapeOptions, WithBackFaces, WithColors, WithLightmap,
+    WithModifiers, WithOrientation, WithShading, WithTangents, WithTexcoords, WithTransform,
+};
+use crate::shading::Shading;
+use crate::vertex::Vertex;
+
+/// A polygonal `Dodecahedron` object.
+///
+/// This object is constructed using a `DodecahedronBuilder` object.
+pub struct Dodecahedron {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows a `Dodecahedron` object to be passed as a source of vertices.
+impl<'a> From<&'a Dodecahedron> for glium::vertex::VerticesSource<'a> {
+    fn from(dodecahedron: &'a Dodecahedron) -> glium::vertex::VerticesSource<'a> {
+        (&dodecahedron.vertices).into()
+    }
+}
+
+/// Allows a `Dodecahedron` object to be passed as a source of indices.
+impl<'a> From<&'a Dodecahedron> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a Dodecahedron) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::TrianglesList,
+        }
+    }
+}
+
+/// Responsible for building and returning a `Dodecahedron` object.
+///
+/// By default, the dodecahedron is defined as a regular dodecahedron inscribed
+/// in a unit-diameter sphere (e.g. each vertex lies at a distance of 0.5 from
+/// the origin), with its centre-of-mass located at the origin. This can be
+/// overriden using the transformation methods on this object. Each pentagonal
+/// face is triangulated as a fan of three triangles.
+///
+/// The resultant geometry is constructed to suit OpenGL defaults - assuming
+/// a right-handed coordinate system, front-facing polygons are defined in
+/// counter-clock-wise order. Vertex normals point in the direction of their
+/// respective face (such that the shape appears faceted when lit). Vertex
+/// texture coordinates define a planar-projection on each face.
+pub struct DodecahedronBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    options: ShapeOptions,
+}
+
+impl Default for DodecahedronBuilder {
+    fn default() -> Self {
+        DodecahedronBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            options: ShapeOptions::default(),
+        }
+    }
+}
+
+impl DodecahedronBuilder {
+    /// Create a new `DodecahedronBuilder` object.
+    pub fn new() -> DodecahedronBuilder {
+        Default::default()
+    }
+
+    /// Build a new `Dodecahedron` object.
+    pub fn build<F>(self, display: &F) -> Result<Dodecahedron, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the shape vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// a `Dodecahedron` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        let vertices = self.vertices_with::<Selected>()?;
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.options.check_shading(Self::SHADINGS)?;
+
+        // Define the vertices of the dodecahedron, made up of the corners of a
+        // cube and the corners of three mutually orthogonal golden rectangles.
+        let phi = (1.0 + 5.0f32.sqrt()) * 0.5;
+        let s = 0.5 / 3.0f32.sqrt();
+        let (a, b) = (s / phi, s * phi);
+        let points = [
+            Vector3::<f32>::new(-s, -s, -s),
+            Vector3::<f32>::new(-s, -s, s),
+            Vector3::<f32>::new(-s, s, -s),
+            Vector3::<f32>::new(-s, s, s),
+            Vector3::<f32>::new(s, -s, -s),
+            Vector3::<f32>::new(s, -s, s),
+            Vector3::<f32>::new(s, s, -s),
+            Vector3::<f32>::new(s, s, s),
+            Vector3::<f32>::new(0.0, -a, -b),
+            Vector3::<f32>::new(-a, -b, 0.0),
+            Vector3::<f32>::new(-b, 0.0, -a),
+            Vector3::<f32>::new(0.0, -a, b),
+            Vector3::<f32>::new(-a, b, 0.0),
+            Vector3::<f32>::new(b, 0.0, -a),
+            Vector3::<f32>::new(0.0, a, -b),
+            Vector3::<f32>::new(a, -b, 0.0),
+            Vector3::<f32>::new(-b, 0.0, a),
+            Vector3::<f32>::new(0.0, a, b),
+            Vector3::<f32>::new(a, b, 0.0),
+            Vector3::<f32>::new(b, 0.0, a),
+        ];
+
+        // Define the pentagonal faces of the dodecahedron, wound
+        // counter-clock-wise when viewed from outside.
+        let faces = [
+            [16, 10, 0, 9, 1],
+            [14, 8, 0, 10, 2],
+            [15, 9, 0, 8, 4],
+            [3, 16, 1, 11, 17],
+            [5, 11, 1, 9, 15],
+            [3, 12, 2, 10, 16],
+            [6, 14, 2, 12, 18],
+            [18, 12, 3, 17, 7],
+            [5, 15, 4, 13, 19],
+            [6, 13, 4, 8, 14],
+            [17, 11, 5, 19, 7],
+            [19, 13, 6, 18, 7],
+        ];
+
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<M>::new(self.matrix);
+
+        // Emit each face.
+        let mut vertices = Vec::<Vertex>::with_capacity(self.num_vertices());
+        for face in faces.iter() {
+            let polygon = face
+                .iter()
+                .map(|&i| points[i])
+                .collect::<Vec<Vector3<f32>>>();
+            push_polygon(&mut vertices, &transform, &polygon);
+        }
+
+        self.options.map_texcoords(&mut vertices);
+        Ok(vertices)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    pub fn num_vertices(&self) -> usize {
+        12 * 3 * 3 * self.options.copies()
+    }
+}
+
+impl HasOptions for DodecahedronBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for DodecahedronBuilder {
+    type Shape = Dodecahedron;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> Dodecahedron {
+        Dodecahedron { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for DodecahedronBuilder {}
+
+impl BuildAs for DodecahedronBuilder {}
+
+impl WithTangents for DodecahedronBuilder {}
+
+impl WithLightmap for DodecahedronBuilder {}
+
+impl WithOrientation for DodecahedronBuilder {}
+
+impl WithBackFaces for DodecahedronBuilder {}
+
+impl WithModifiers for DodecahedronBuilder {}
+
+impl WithTexcoords for DodecahedronBuilder {}
+
+impl WithColors for DodecahedronBuilder {}
+
+impl WithShading for DodecahedronBuilder {
+    /// Only flat shading is supported by the dodecahedron.
+    const SHADINGS: &'static [Shading] = &[Shading::Flat];
+}
+
+#[test]
+pub fn ensure_default_dodecahedron_has_centroid_at_origin() {
+    let vertices = DodecahedronBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    // The pentagons are fanned about their first point, so weight each
+    // triangle centroid by its area rather than summing raw vertices.
+    let mut sum = Vector3::<f32>::zero();
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let area = (v1 - v0).cross(v2 - v0).magnitude() * 0.5;
+        sum += (v0 + v1 + v2) * (area / 3.0);
+    }
+    assert_ulps_eq!(sum, Vector3::<f32>::zero(), epsilon = 0.0001);
+}
+
+#[test]
+pub fn ensure_default_dodecahedron_has_unit_outward_face_normals() {
+    let vertices = DodecahedronBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let centroid = (Vector3::<f32>::from(chunk[0].position)
+            + Vector3::<f32>::from(chunk[1].position)
+            + Vector3::<f32>::from(chunk[2].position))
+            / 3.0;
+        for vertex in chunk {
+            let normal = Vector3::<f32>::from(vertex.normal);
+            assert!(abs_diff_eq!(normal.magnitude(), 1.0, epsilon = 0.0001));
+            assert!(normal.dot(centroid) > 0.0);
+        }
+    }
+}
+
+#[test]
+pub fn ensure_default_dodecahedron_has_ccw_triangles() {
+    let vertices = DodecahedronBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let eyepos = v0 + Vector3::<f32>::from(chunk[0].normal);
+        let e0 = v1 - v0;
+        let e1 = v2 - v0;
+        let n = e0.cross(e1);
+        assert!(n.dot(v0 - eyepos) <= 0.0);
+        assert!(n.dot(v1 - eyepos) <= 0.0);
+        assert!(n.dot(v2 - eyepos) <= 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_default_dodecahedron_has_faceted_normals() {
+    let vertices = DodecahedronBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let n = (v1 - v0).cross(v2 - v0).normalize();
+        for vertex in chunk {
+            assert_ulps_eq!(n, Vector3::<f32>::from(vertex.normal), epsilon = 0.0001);
+        }
+    }
+}
+
+#[test]
+pub fn ensure_default_dodecahedron_has_uvs_in_unit_range() {
+    let vertices = DodecahedronBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        assert!(vertex.texcoord[0] >= -0.0001 && vertex.texcoord[0] <= 1.0001);
+        assert!(vertex.texcoord[1] >= -0.0001 && vertex.texcoord[1] <= 1.0001);
+    }
+}
+
+#[test]
+pub fn ensure_default_dodecahedron_is_inscribed_in_unit_diameter_sphere() {
+    let vertices = DodecahedronBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    assert_eq!(vertices.len(), 108);
+    for vertex in &vertices {
+        let pos = Vector3::<f32>::from(vertex.position);
+        assert!(abs_diff_eq!(pos.magnitude(), 0.5, epsilon = 0.0001));
+    }
+}
+
+#[test]
+pub fn ensure_default_dodecahedron_has_twelve_distinct_face_normals() {
+    let vertices = DodecahedronBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let mut normals = Vec::<Vector3<f32>>::new();
+    for vertex in &vertices {
+        let normal = Vector3::<f32>::from(vertex.normal);
+        if !normals
+            .iter()
+            .any(|n| abs_diff_eq!(*n, normal, epsilon = 0.0001))
+        {
+            normals.push(normal);
+        }
+    }
+    assert_eq!(normals.len(), 12);
+}
+
+#[test]
+pub fn ensure_dodecahedron_applies_late_pipeline() {
+    use crate::options::WithTexcoords;
+    use crate::uv_mapping::UvMapping;
+
+    crate::options::ensure_late_pipeline(
+        || {
+            DodecahedronBuilder::new()
+                .uv_mapping(UvMapping::Box)
+                .double_sided()
+        },
+        DodecahedronBuilder::build_vertices,
+    );
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_dodecahedron() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = DodecahedronBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
@@ -0,0 +1,533 @@
+//! A module for constructing elbow (bent pipe) shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::errors::ShapeCreationError;
+use crate::facet::flatten_normals;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{
+    BuildAs, HasOptions, HasVertices, ShapeOptions, WithBackFaces, WithCaps, WithColors,
+    WithLightmap, WithModifiers, WithOrientation, WithShading, WithTangents, WithTexcoords,
+    WithTransform,
+};
+use crate::resolution::{Resolution, WithResolution};
+use crate::shading::Shading;
+use crate::vertex::Vertex;
+
+use std::f32;
+
+/// A polygonal `Elbow` object.
+///
+/// This object is constructed using an `ElbowBuilder` object.
+pub struct Elbow {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows an `Elbow` object to be passed as a source of vertices.
+impl<'a> From<&'a Elbow> for glium::vertex::VerticesSource<'a> {
+    fn from(elbow: &'a Elbow) -> glium::vertex::VerticesSource<'a> {
+        (&elbow.vertices).into()
+    }
+}
+
+/// Allows an `Elbow` object to be passed as a source of indices.
+impl<'a> From<&'a Elbow> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a Elbow) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::TrianglesList,
+        }
+    }
+}
+
+/// Responsible for building and returning an `Elbow` object.
+///
+/// An elbow is a pipe bent along a circular arc, such that it forms part of a
+/// torus. By default, the arc lies in the xz-plane, centred at the origin with
+/// a bend radius (the distance from the origin to the centre of the pipe) of
+/// 0.375 and a pipe radius of 0.125, such that the elbow is a quarter of the
+/// default `Torus`. The arc starts on the positive x-axis, and bends through a
+/// quarter turn counter-clock-wise when viewed from above, such that it ends
+/// on the negative z-axis. This can be overriden using the radius, angle and
+/// transformation methods on this object.
+///
+/// Each end of the elbow lies in the plane that contains the y-axis and the
+/// end of the arc (the start lying exactly on the plane at `z = 0`, and the
+/// ends of bends through multiples of a quarter turn lying exactly on their
+/// respective axis-aligned planes). As such, an elbow that is rotated about
+/// the y-axis by the bend angle of another butts against it without cracks.
+///
+/// The resultant geometry is constructed to suit OpenGL defaults - assuming
+/// a right-handed coordinate system, front-facing polygons are defined in
+/// counter-clock-wise order. Vertex normals on the pipe point outward from the
+/// centre-line of the pipe (such that the pipe appears smooth when lit),
+/// whereas the normals of each cap point along the arc (such that the caps
+/// appear faceted against the pipe). Vertex texture coordinates map the
+/// proportion of the bend angle to the u coordinate, and the angle around the
+/// pipe (increasing upwards from the outside of the bend) to the v coordinate.
+/// Caps define a planar-projection.
+pub struct ElbowBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    bend_radius: f32,
+    pipe_radius: f32,
+    bend_angle: f32,
+    bend_segments: usize,
+    pipe_segments: usize,
+    options: ShapeOptions,
+}
+
+impl Default for ElbowBuilder {
+    fn default() -> Self {
+        ElbowBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            bend_radius: 0.375,
+            pipe_radius: 0.125,
+            bend_angle: f32::consts::FRAC_PI_2,
+            bend_segments: 8,
+            pipe_segments: 12,
+            options: ShapeOptions {
+                shading: Shading::Smooth,
+                ..ShapeOptions::default()
+            },
+        }
+    }
+}
+
+impl ElbowBuilder {
+    /// Create a new `ElbowBuilder` object.
+    pub fn new() -> ElbowBuilder {
+        Default::default()
+    }
+
+    /// Specify the distance from the centre of the bend to the centre-line of
+    /// the pipe. By default, the bend radius is 0.375. The bend radius must be
+    /// greater than the pipe radius to build a valid elbow.
+    pub fn bend_radius(mut self, radius: f32) -> Self {
+        self.bend_radius = radius;
+        self
+    }
+
+    /// Specify the radius of the pipe. By default, the pipe radius is 0.125.
+    /// The pipe radius must be greater than zero to build a valid elbow.
+    pub fn pipe_radius(mut self, radius: f32) -> Self {
+        self.pipe_radius = radius;
+        self
+    }
+
+    /// Specify the angle (in radians) through which the pipe bends. By
+    /// default, the bend angle is a quarter turn. The bend angle must be
+    /// greater than zero, and must not exceed a full turn, to build a valid
+    /// elbow.
+    pub fn bend_angle(mut self, radians: f32) -> Self {
+        self.bend_angle = radians;
+        self
+    }
+
+    /// Specify the number of segments along the bend. By default, the builder
+    /// will use 8 segments. At least 1 segment is required to build a valid
+    /// elbow.
+    pub fn bend_segments(mut self, segments: usize) -> Self {
+        self.bend_segments = segments;
+        self
+    }
+
+    /// Specify the number of segments around the pipe. By default, the
+    /// builder will use 12 segments. At least 3 segments are required to
+    /// build a valid elbow.
+    pub fn pipe_segments(mut self, segments: usize) -> Self {
+        self.pipe_segments = segments;
+        self
+    }
+
+    /// Build a new `Elbow` object.
+    pub fn build<F>(self, display: &F) -> Result<Elbow, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the shape vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// an `Elbow` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        let vertices = self.vertices_with::<Selected>()?;
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.options.check_shading(Self::SHADINGS)?;
+
+        // Ensure the parameters produce valid elbow geometry.
+        if self.bend_segments < 1 {
+            return Err(ShapeCreationError::NotEnoughDivisionsInU);
+        }
+        if self.pipe_segments < 3 {
+            return Err(ShapeCreationError::NotEnoughDivisionsInV);
+        }
+        if self.pipe_radius <= 0.0
+            || self.bend_radius <= self.pipe_radius
+            || !self.bend_radius.is_finite()
+        {
+            return Err(ShapeCreationError::InvalidRadii);
+        }
+        if self.bend_angle <= 0.0 || self.bend_angle > 2.0 * f32::consts::PI {
+            return Err(ShapeCreationError::InvalidDimensions);
+        }
+
+        // Build lookup tables of (cos, sin) pairs along the bend and around
+        // the pipe. Values that are almost zero are snapped to zero, such that
+        // the ends of bends through multiples of a quarter turn lie exactly on
+        // their axis-aligned planes. The last entry of the table around the
+        // pipe wraps around to the first such that seam positions match.
+        let snap = |x: f32| if x.abs() < 1e-6 { 0.0 } else { x };
+        let u_tab = (0..=self.bend_segments)
+            .map(|x| {
+                let a = self.bend_angle * x as f32 / self.bend_segments as f32;
+                [snap(a.cos()), snap(a.sin())]
+            })
+            .collect::<Vec<[f32; 2]>>();
+        let angle = 2.0 * f32::consts::PI / self.pipe_segments as f32;
+        let v_tab = (0..=self.pipe_segments)
+            .map(|x| {
+                let a = ((x % self.pipe_segments) as f32) * angle;
+                [snap(a.cos()), snap(a.sin())]
+            })
+            .collect::<Vec<[f32; 2]>>();
+
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<M>::new(self.matrix);
+
+        let mut vertices = Vec::<Vertex>::with_capacity(self.num_vertices());
+        let mut push = |position: Vector3<f32>, normal: Vector3<f32>, texcoord: [f32; 2]| {
+            vertices.push(Vertex {
+                position: transform.point(position),
+                normal: transform.normal(normal),
+                texcoord,
+            });
+        };
+        let position = |[cu, su]: [f32; 2], [cv, sv]: [f32; 2]| {
+            let distance = self.bend_radius + cv * self.pipe_radius;
+            Vector3::<f32>::new(cu * distance, sv * self.pipe_radius, -su * distance)
+        };
+
+        // Emit the pipe. The bend angle increases counter-clock-wise when
+        // viewed from above, and the angle around the pipe increases upwards
+        // from the outside of the bend.
+        let quad = [(0, 0), (1, 0), (1, 1), (0, 0), (1, 1), (0, 1)];
+        for u in 0..self.bend_segments {
+            for v in 0..self.pipe_segments {
+                for &(du, dv) in quad.iter() {
+                    let ([cu, su], [cv, sv]) = (u_tab[u + du], v_tab[v + dv]);
+                    push(
+                        position([cu, su], [cv, sv]),
+                        Vector3::<f32>::new(cv * cu, sv, -cv * su),
+                        [
+                            (u + du) as f32 / self.bend_segments as f32,
+                            (v + dv) as f32 / self.pipe_segments as f32,
+                        ],
+                    );
+                }
+            }
+        }
+
+        // Emit the caps at the start and end of the bend as triangle fans
+        // about the centre-line of the pipe, skipping any cap that is disabled.
+        let last = self.bend_segments;
+        let caps = [
+            (0, -1.0, [0, 1], self.options.caps.caps_start()),
+            (last, 1.0, [1, 0], self.options.caps.caps_end()),
+        ];
+        for &(u, sign, order, _) in caps.iter().filter(|cap| cap.3) {
+            let [cu, su] = u_tab[u];
+            let normal = Vector3::<f32>::new(-su, 0.0, -cu) * sign;
+            let centre = Vector3::<f32>::new(cu, 0.0, -su) * self.bend_radius;
+            for v in 0..self.pipe_segments {
+                push(centre, normal, [0.5, 0.5]);
+                for &index in [v + order[0], v + order[1]].iter() {
+                    let [cv, sv] = v_tab[index];
+                    push(
+                        position([cu, su], [cv, sv]),
+                        normal,
+                        [0.5 - cv * sign * 0.5, 0.5 + sv * 0.5],
+                    );
+                }
+            }
+        }
+
+        if self.options.shading == Shading::Flat {
+            flatten_normals(&mut vertices);
+        }
+
+        self.options.map_texcoords(&mut vertices);
+        Ok(vertices)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    pub fn num_vertices(&self) -> usize {
+        let caps = [self.options.caps.caps_start(), self.options.caps.caps_end()]
+            .iter()
+            .filter(|&&cap| cap)
+            .count();
+        ((self.bend_segments * self.pipe_segments * 6) + (self.pipe_segments * 3 * caps))
+            * self.options.copies()
+    }
+}
+
+impl HasOptions for ElbowBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for ElbowBuilder {
+    type Shape = Elbow;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> Elbow {
+        Elbow { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for ElbowBuilder {}
+
+impl BuildAs for ElbowBuilder {}
+
+impl WithTangents for ElbowBuilder {}
+
+impl WithLightmap for ElbowBuilder {}
+
+impl WithOrientation for ElbowBuilder {}
+
+impl WithBackFaces for ElbowBuilder {}
+
+impl WithModifiers for ElbowBuilder {}
+
+/// The start of the elbow is at the start of its bend, and its end is at the
+/// end of its bend.
+impl WithCaps for ElbowBuilder {}
+
+impl WithTexcoords for ElbowBuilder {}
+
+impl WithColors for ElbowBuilder {}
+
+impl WithShading for ElbowBuilder {
+    /// By default, the elbow is smooth shaded. When flat shaded, each vertex
+    /// normal points in the direction of its respective triangle instead.
+    const SHADINGS: &'static [Shading] = &[Shading::Flat, Shading::Smooth];
+}
+
+impl WithResolution for ElbowBuilder {
+    /// At least 3 segments around the pipe, and 1 segment along the bend.
+    const MIN_RESOLUTION: Resolution = Resolution {
+        around: 3,
+        along: 1,
+    };
+
+    /// Specify the number of segments around the pipe of the elbow, and the
+    /// number of segments along its bend.
+    fn resolution(mut self, resolution: Resolution) -> Self {
+        self.pipe_segments = resolution.around as usize;
+        self.bend_segments = resolution.along as usize;
+        self
+    }
+}
+
+#[test]
+pub fn ensure_default_elbow_ends_lie_exactly_on_axis_planes() {
+    use crate::cap_mode::CapMode;
+    let vertices = ElbowBuilder::new()
+        .caps(CapMode::Neither)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        let [x, y, z] = vertex.position;
+        if vertex.texcoord[0] == 0.0 {
+            assert_eq!(z, 0.0);
+            assert!(x > 0.0);
+        } else if vertex.texcoord[0] == 1.0 {
+            assert_eq!(x, 0.0);
+            assert!(z < 0.0);
+        }
+
+        // Every vertex lies on the surface of the default torus.
+        let centre = Vector3::<f32>::new(x, 0.0, z).normalize() * 0.375;
+        let offset = Vector3::<f32>::new(x, y, z) - centre;
+        assert!(abs_diff_eq!(offset.magnitude(), 0.125, epsilon = 0.0001));
+        assert_ulps_eq!(
+            offset.normalize(),
+            Vector3::from(vertex.normal),
+            epsilon = 0.0001
+        );
+    }
+}
+
+#[test]
+pub fn ensure_consecutive_elbows_butt_together() {
+    use crate::cap_mode::CapMode;
+    let angle = 1.0f32;
+    let first = ElbowBuilder::new()
+        .bend_angle(angle)
+        .caps(CapMode::Neither)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let second = ElbowBuilder::new()
+        .bend_angle(angle)
+        .caps(CapMode::Neither)
+        .rotate_y(angle)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let start = second
+        .iter()
+        .filter(|v| v.texcoord[0] == 0.0)
+        .collect::<Vec<&Vertex>>();
+    let mut count = 0;
+    for a in first.iter().filter(|v| v.texcoord[0] == 1.0) {
+        let b = start
+            .iter()
+            .find(|b| b.texcoord[1] == a.texcoord[1])
+            .expect("Missing vertex at the start of the second elbow");
+        assert_ulps_eq!(
+            Vector3::from(a.position),
+            Vector3::from(b.position),
+            epsilon = 0.000_001
+        );
+        count += 1;
+    }
+    assert_eq!(count, 12 * 3);
+}
+
+#[test]
+pub fn ensure_capped_elbow_is_watertight_with_ccw_triangles() {
+    let vertices = ElbowBuilder::new()
+        .bend_angle(2.5)
+        .bend_segments(5)
+        .pipe_segments(7)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let mut edges = std::collections::HashMap::<([u32; 3], [u32; 3]), usize>::new();
+    for chunk in vertices.chunks(3) {
+        for index in 0..3 {
+            let a = chunk[index].position.map(f32::to_bits);
+            let b = chunk[(index + 1) % 3].position.map(f32::to_bits);
+            *edges.entry((a.min(b), a.max(b))).or_insert(0) += 1;
+        }
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let eyepos = v0 + Vector3::<f32>::from(chunk[0].normal);
+        let n = (v1 - v0).cross(v2 - v0);
+        assert!(n.dot(v0 - eyepos) <= 0.0);
+        assert!(n.dot(v1 - eyepos) <= 0.0);
+        assert!(n.dot(v2 - eyepos) <= 0.0);
+    }
+    for count in edges.values() {
+        assert_eq!(*count, 2);
+    }
+}
+
+#[test]
+pub fn ensure_elbow_with_invalid_parameters_fails_to_build() {
+    match ElbowBuilder::new().bend_radius(0.1).build_vertices() {
+        Err(ShapeCreationError::InvalidRadii) => (),
+        _ => panic!("Expected InvalidRadii"),
+    }
+    match ElbowBuilder::new().bend_angle(0.0).build_vertices() {
+        Err(ShapeCreationError::InvalidDimensions) => (),
+        _ => panic!("Expected InvalidDimensions"),
+    }
+    match ElbowBuilder::new().bend_segments(0).build_vertices() {
+        Err(ShapeCreationError::NotEnoughDivisionsInU) => (),
+        _ => panic!("Expected NotEnoughDivisionsInU"),
+    }
+    match ElbowBuilder::new().pipe_segments(2).build_vertices() {
+        Err(ShapeCreationError::NotEnoughDivisionsInV) => (),
+        _ => panic!("Expected NotEnoughDivisionsInV"),
+    }
+}
+
+#[test]
+pub fn ensure_elbow_applies_late_pipeline() {
+    use crate::options::WithTexcoords;
+    use crate::uv_mapping::UvMapping;
+
+    crate::options::ensure_late_pipeline(
+        || {
+            ElbowBuilder::new()
+                .uv_mapping(UvMapping::Box)
+                .double_sided()
+        },
+        ElbowBuilder::build_vertices,
+    );
+}
+
+#[test]
+pub fn ensure_elbow_with_invalid_resolution_fails_to_build() {
+    crate::resolution::ensure_min_resolution(
+        ElbowBuilder::new,
+        ElbowBuilder::build_vertices,
+        ShapeCreationError::NotEnoughDivisionsInV,
+        Some(ShapeCreationError::NotEnoughDivisionsInU),
+    );
+}
+
+#[test]
+pub fn ensure_elbow_caps_can_be_disabled_separately() {
+    use crate::cap_mode::CapMode;
+    let builder = ElbowBuilder::new().bend_segments(4).pipe_segments(5);
+    let side = 4 * 5 * 6;
+    assert_eq!(builder.num_vertices(), side + 5 * 3 * 2);
+    let vertices = builder.build_vertices().expect("Failed to build vertices");
+    for (caps, cap) in [(CapMode::Start, 0), (CapMode::End, 1)].iter() {
+        let builder = ElbowBuilder::new()
+            .bend_segments(4)
+            .pipe_segments(5)
+            .caps(*caps);
+        assert_eq!(builder.num_vertices(), side + 5 * 3);
+        let partial = builder.build_vertices().expect("Failed to build vertices");
+        assert_eq!(partial.len(), builder.num_vertices());
+
+        // The remaining cap is the one at the enabled end of the bend.
+        let start = side + cap * 5 * 3;
+        for (vertex, expected) in partial[side..].iter().zip(vertices[start..].iter()) {
+            assert_eq!(vertex.position, expected.position);
+            assert_eq!(vertex.normal, expected.normal);
+        }
+    }
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_elbow() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = ElbowBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
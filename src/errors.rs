@@ -11,6 +11,9 @@ pub enum ShapeCreationError {
     /// The shape failed to build because vertex buffer could not be created.
     VertexBufferCreationError(glium::vertex::BufferCreationError),
 
+    /// The shape failed to build because index buffer could not be created.
+    IndexBufferCreationError(glium::index::BufferCreationError),
+
     /// The shape failed to build because the number of divisions in the u axis
     /// is too small.
     NotEnoughDivisionsInU,
@@ -18,20 +21,55 @@ pub enum ShapeCreationError {
     /// The shape failed to build because the number of divisions in the v axis
     /// is too small.
     NotEnoughDivisionsInV,
+
+    /// The shape failed to build because its radii are invalid (e.g. an inner
+    /// radius that is not smaller than its outer radius).
+    InvalidRadii,
+
+    /// The shape failed to build because the requested number of subdivisions
+    /// would produce an unreasonably large amount of geometry.
+    TooManySubdivisions,
+
+    /// The shape failed to build because its dimensions are invalid (e.g. an
+    /// extent that is not greater than zero, or a number of samples that does
+    /// not match the dimensions of a grid).
+    InvalidDimensions,
+
+    /// The shape failed to build because too few points were provided to
+    /// define it (e.g. a path with fewer than two points).
+    NotEnoughPoints,
+
+    /// The shape failed to build because two consecutive points that define it
+    /// are coincident.
+    DuplicatePoints,
+
+    /// The shape failed to build because the polygon that defines it is not
+    /// simple (e.g. its edges intersect, or it encloses no area).
+    InvalidPolygon,
+
+    /// The shape failed to build because its winding numbers are invalid (e.g.
+    /// a torus knot whose winding numbers are zero or not coprime).
+    InvalidWindingNumbers,
+
+    /// The shape failed to build because its exponents are invalid (e.g. a
+    /// superellipsoid exponent that is not greater than zero).
+    InvalidExponents,
+
+    /// The shape failed to build because it does not support the requested
+    /// shading mode (e.g. a smooth shaded tetrahedron).
+    UnsupportedShading,
+
+    /// The shape failed to build because it does not support the requested
+    /// texture coordinate projection (e.g. an indexed cuboid with a spherical
+    /// projection).
+    UnsupportedUvMapping,
 }
 
 impl std::error::Error for ShapeCreationError {
-    fn description(&self) -> &str {
-        match &self {
-            ShapeCreationError::VertexBufferCreationError(ref err) => err.description(),
-            ShapeCreationError::NotEnoughDivisionsInU => "Not enough divisions in the u axis",
-            ShapeCreationError::NotEnoughDivisionsInV => "Not enough divisions in the v axis",
-        }
-    }
-
-    fn cause(&self) -> Option<&dyn Error> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
         match &self {
             ShapeCreationError::VertexBufferCreationError(ref error) => Some(error),
+            ShapeCreationError::IndexBufferCreationError(ref error) => Some(error),
             _ => None,
         }
     }
@@ -43,8 +81,33 @@ impl From<glium::vertex::BufferCreationError> for ShapeCreationError {
     }
 }
 
+impl From<glium::index::BufferCreationError> for ShapeCreationError {
+    fn from(error: glium::index::BufferCreationError) -> Self {
+        ShapeCreationError::IndexBufferCreationError(error)
+    }
+}
+
 impl core::fmt::Display for ShapeCreationError {
     fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
-        write!(fmt, "{}", self.description())
+        match &self {
+            ShapeCreationError::VertexBufferCreationError(ref err) => write!(fmt, "{}", err),
+            ShapeCreationError::IndexBufferCreationError(ref err) => write!(fmt, "{}", err),
+            ShapeCreationError::NotEnoughDivisionsInU => {
+                write!(fmt, "Not enough divisions in the u axis")
+            }
+            ShapeCreationError::NotEnoughDivisionsInV => {
+                write!(fmt, "Not enough divisions in the v axis")
+            }
+            ShapeCreationError::InvalidRadii => write!(fmt, "Invalid radii"),
+            ShapeCreationError::TooManySubdivisions => write!(fmt, "Too many subdivisions"),
+            ShapeCreationError::InvalidDimensions => write!(fmt, "Invalid dimensions"),
+            ShapeCreationError::NotEnoughPoints => write!(fmt, "Not enough points"),
+            ShapeCreationError::DuplicatePoints => write!(fmt, "Duplicate consecutive points"),
+            ShapeCreationError::InvalidPolygon => write!(fmt, "Invalid polygon"),
+            ShapeCreationError::InvalidWindingNumbers => write!(fmt, "Invalid winding numbers"),
+            ShapeCreationError::InvalidExponents => write!(fmt, "Invalid exponents"),
+            ShapeCreationError::UnsupportedShading => write!(fmt, "Unsupported shading"),
+            ShapeCreationError::UnsupportedUvMapping => write!(fmt, "Unsupported UV mapping"),
+        }
     }
 }
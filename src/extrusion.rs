@@ -0,0 +1,422 @@
+//! A module for constructing extruded polygon shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::errors::ShapeCreationError;
+use crate::facet::{push_polygon, push_triangulated_polygon, signed_area, triangulate};
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{
+    BuildAs, HasOptions, HasVertices, ShapeOptions, WithBackFaces, WithColors, WithLightmap,
+    WithModifiers, WithOrientation, WithShading, WithTangents, WithTexcoords, WithTransform,
+};
+use crate::shading::Shading;
+use crate::vertex::Vertex;
+
+/// A polygonal `Extrusion` object.
+///
+/// This object is constructed using an `ExtrusionBuilder` object.
+pub struct Extrusion {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows an `Extrusion` object to be passed as a source of vertices.
+impl<'a> From<&'a Extrusion> for glium::vertex::VerticesSource<'a> {
+    fn from(extrusion: &'a Extrusion) -> glium::vertex::VerticesSource<'a> {
+        (&extrusion.vertices).into()
+    }
+}
+
+/// Allows an `Extrusion` object to be passed as a source of indices.
+impl<'a> From<&'a Extrusion> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a Extrusion) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::TrianglesList,
+        }
+    }
+}
+
+/// Responsible for building and returning an `Extrusion` object.
+///
+/// The extrusion is constructed by extruding a closed 2D polygon in the xy
+/// plane along the positive z-axis, from `z = 0` to `z = depth`. By default,
+/// the polygon is a unit square centred at the origin, and the depth is 1.
+/// This can be overriden using the transformation methods on this object.
+///
+/// The polygon may be concave, and wound in either direction, but must be
+/// simple (e.g. its edges must not intersect). The front cap (facing the
+/// positive z-axis) and back cap (facing the negative z-axis) are triangulated
+/// by ear-clipping.
+///
+/// The resultant geometry is constructed to suit OpenGL defaults - assuming
+/// a right-handed coordinate system, front-facing polygons are defined in
+/// counter-clock-wise order. Vertex normals point in the direction of their
+/// respective face (such that the shape appears faceted when lit). Vertex
+/// texture coordinates define a planar-projection on each face.
+pub struct ExtrusionBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    polygon: Vec<Point2<f32>>,
+    depth: f32,
+    options: ShapeOptions,
+}
+
+impl Default for ExtrusionBuilder {
+    fn default() -> Self {
+        ExtrusionBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            polygon: vec![
+                Point2::<f32>::new(-0.5, -0.5),
+                Point2::<f32>::new(0.5, -0.5),
+                Point2::<f32>::new(0.5, 0.5),
+                Point2::<f32>::new(-0.5, 0.5),
+            ],
+            depth: 1.0,
+            options: ShapeOptions::default(),
+        }
+    }
+}
+
+impl ExtrusionBuilder {
+    /// Create a new `ExtrusionBuilder` object.
+    pub fn new() -> ExtrusionBuilder {
+        Default::default()
+    }
+
+    /// Specify the closed polygon in the xy plane that is extruded. The last
+    /// point is implicitly connected to the first. At least three points are
+    /// required to build a valid extrusion, and consecutive points must not be
+    /// coincident.
+    pub fn polygon(mut self, points: &[impl Into<Point2<f32>> + Copy]) -> Self {
+        self.polygon = points.iter().map(|&point| point.into()).collect();
+        self
+    }
+
+    /// Specify the distance that the polygon is extruded along the z-axis. By
+    /// default, the depth is 1. The depth must be greater than zero.
+    pub fn depth(mut self, depth: f32) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    /// Build a new `Extrusion` object.
+    pub fn build<F>(self, display: &F) -> Result<Extrusion, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the shape vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// an `Extrusion` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        let vertices = self.vertices_with::<Selected>()?;
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.options.check_shading(Self::SHADINGS)?;
+
+        // Ensure the polygon and depth describe a valid extrusion.
+        let count = self.polygon.len();
+        if count < 3 {
+            return Err(ShapeCreationError::NotEnoughPoints);
+        }
+        if (0..count).any(|i| ulps_eq!(self.polygon[i], self.polygon[(i + 1) % count])) {
+            return Err(ShapeCreationError::DuplicatePoints);
+        }
+        if self.depth <= 0.0 {
+            return Err(ShapeCreationError::InvalidDimensions);
+        }
+
+        // Ensure the polygon is wound counter-clock-wise, and triangulate it.
+        let mut polygon = self
+            .polygon
+            .iter()
+            .map(|p| p.to_vec())
+            .collect::<Vec<Vector2<f32>>>();
+        if signed_area(&polygon) < 0.0 {
+            polygon.reverse();
+        }
+        let triangles = triangulate(&polygon)?;
+
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<M>::new(self.matrix);
+
+        // Emit the side walls.
+        let mut vertices = Vec::<Vertex>::with_capacity(self.num_vertices());
+        for index in 0..count {
+            let (a, b) = (polygon[index], polygon[(index + 1) % count]);
+            let wall = [
+                a.extend(0.0),
+                b.extend(0.0),
+                b.extend(self.depth),
+                a.extend(self.depth),
+            ];
+            push_polygon(&mut vertices, &transform, &wall);
+        }
+
+        // Emit the front and back caps, reversing the points and triangle
+        // winding of the back, such that it faces the negative z-axis.
+        let front = polygon
+            .iter()
+            .map(|p| p.extend(self.depth))
+            .collect::<Vec<Vector3<f32>>>();
+        push_triangulated_polygon(&mut vertices, &transform, &front, &triangles);
+        let back = polygon
+            .iter()
+            .rev()
+            .map(|p| p.extend(0.0))
+            .collect::<Vec<Vector3<f32>>>();
+        let reversed = triangles
+            .iter()
+            .map(|t| [count - 1 - t[0], count - 1 - t[2], count - 1 - t[1]])
+            .collect::<Vec<[usize; 3]>>();
+        push_triangulated_polygon(&mut vertices, &transform, &back, &reversed);
+
+        self.options.map_texcoords(&mut vertices);
+        Ok(vertices)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    pub fn num_vertices(&self) -> usize {
+        let count = self.polygon.len();
+        ((count * 6) + (count.saturating_sub(2) * 3 * 2)) * self.options.copies()
+    }
+}
+
+impl HasOptions for ExtrusionBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for ExtrusionBuilder {
+    type Shape = Extrusion;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> Extrusion {
+        Extrusion { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for ExtrusionBuilder {}
+
+impl BuildAs for ExtrusionBuilder {}
+
+impl WithTangents for ExtrusionBuilder {}
+
+impl WithLightmap for ExtrusionBuilder {}
+
+impl WithOrientation for ExtrusionBuilder {}
+
+impl WithBackFaces for ExtrusionBuilder {}
+
+impl WithModifiers for ExtrusionBuilder {}
+
+impl WithTexcoords for ExtrusionBuilder {}
+
+impl WithColors for ExtrusionBuilder {}
+
+impl WithShading for ExtrusionBuilder {
+    /// Only flat shading is supported by the extrusion.
+    const SHADINGS: &'static [Shading] = &[Shading::Flat];
+}
+
+#[cfg(test)]
+fn l_shape() -> Vec<Point2<f32>> {
+    vec![
+        Point2::<f32>::new(0.0, 0.0),
+        Point2::<f32>::new(2.0, 0.0),
+        Point2::<f32>::new(2.0, 1.0),
+        Point2::<f32>::new(1.0, 1.0),
+        Point2::<f32>::new(1.0, 2.0),
+        Point2::<f32>::new(0.0, 2.0),
+    ]
+}
+
+#[test]
+pub fn ensure_default_extrusion_has_unit_dimensions() {
+    let vertices = ExtrusionBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        let pos = Vector3::<f32>::from(vertex.position);
+        assert!(abs_diff_eq!(pos.x.abs(), 0.5));
+        assert!(abs_diff_eq!(pos.y.abs(), 0.5));
+        assert!(abs_diff_eq!(pos.z, 0.0) || abs_diff_eq!(pos.z, 1.0));
+    }
+}
+
+#[test]
+pub fn ensure_concave_extrusion_encloses_polygon_area() {
+    let vertices = ExtrusionBuilder::new()
+        .polygon(&l_shape())
+        .depth(0.5)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let mut front = 0.0;
+    let mut back = 0.0;
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let area = (v1 - v0).cross(v2 - v0).z * 0.5;
+        if chunk[0].normal[2] > 0.5 {
+            front += area;
+        } else if chunk[0].normal[2] < -0.5 {
+            back += area;
+        }
+    }
+    assert_ulps_eq!(front, 3.0);
+    assert_ulps_eq!(back, -3.0);
+}
+
+#[test]
+pub fn ensure_clockwise_polygon_extrudes_with_outward_normals() {
+    let mut reversed = l_shape();
+    reversed.reverse();
+    let a = ExtrusionBuilder::new()
+        .polygon(&l_shape())
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let b = ExtrusionBuilder::new()
+        .polygon(&reversed)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    assert_eq!(a.len(), b.len());
+    for chunk in b.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let centroid = (v0 + v1 + v2) / 3.0;
+        let normal = Vector3::<f32>::from(chunk[0].normal);
+        let centre = Vector3::<f32>::new(0.75, 0.75, 0.5);
+        if normal.z.abs() < 0.5 {
+            // Walls of the L-shape face away from the corner it wraps around.
+            let outside = centroid + normal * 0.01;
+            let inside = centroid - normal * 0.01;
+            let contains = |p: Vector3<f32>| {
+                p.x > 0.0 && p.y > 0.0 && p.x < 2.0 && p.y < 2.0 && (p.x < 1.0 || p.y < 1.0)
+            };
+            assert!(!contains(outside) && contains(inside));
+        } else {
+            assert!(normal.dot(centroid - centre) > 0.0);
+        }
+    }
+}
+
+#[test]
+pub fn ensure_default_extrusion_has_ccw_triangles() {
+    let vertices = ExtrusionBuilder::new()
+        .polygon(&l_shape())
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let eyepos = v0 + Vector3::<f32>::from(chunk[0].normal);
+        let e0 = v1 - v0;
+        let e1 = v2 - v0;
+        let n = e0.cross(e1);
+        assert!(n.dot(v0 - eyepos) <= 0.0);
+        assert!(n.dot(v1 - eyepos) <= 0.0);
+        assert!(n.dot(v2 - eyepos) <= 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_default_extrusion_has_uvs_in_unit_range() {
+    let vertices = ExtrusionBuilder::new()
+        .polygon(&l_shape())
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        assert!(vertex.texcoord[0] >= -0.0001 && vertex.texcoord[0] <= 1.0001);
+        assert!(vertex.texcoord[1] >= -0.0001 && vertex.texcoord[1] <= 1.0001);
+    }
+}
+
+#[test]
+pub fn ensure_extrusion_with_invalid_polygon_fails_to_build() {
+    let bowtie = [
+        Point2::<f32>::new(0.0, 0.0),
+        Point2::<f32>::new(1.0, 1.0),
+        Point2::<f32>::new(1.0, 0.0),
+        Point2::<f32>::new(0.0, 1.0),
+    ];
+    match ExtrusionBuilder::new().polygon(&bowtie).build_vertices() {
+        Err(ShapeCreationError::InvalidPolygon) => (),
+        _ => panic!("Expected InvalidPolygon"),
+    }
+    match ExtrusionBuilder::new()
+        .polygon(&bowtie[..2])
+        .build_vertices()
+    {
+        Err(ShapeCreationError::NotEnoughPoints) => (),
+        _ => panic!("Expected NotEnoughPoints"),
+    }
+    match ExtrusionBuilder::new()
+        .polygon(&[bowtie[0], bowtie[1], bowtie[2], bowtie[0]])
+        .build_vertices()
+    {
+        Err(ShapeCreationError::DuplicatePoints) => (),
+        _ => panic!("Expected DuplicatePoints"),
+    }
+    match ExtrusionBuilder::new().depth(0.0).build_vertices() {
+        Err(ShapeCreationError::InvalidDimensions) => (),
+        _ => panic!("Expected InvalidDimensions"),
+    }
+}
+
+#[test]
+pub fn ensure_extrusion_applies_late_pipeline() {
+    use crate::options::WithTexcoords;
+    use crate::uv_mapping::UvMapping;
+
+    crate::options::ensure_late_pipeline(
+        || {
+            ExtrusionBuilder::new()
+                .uv_mapping(UvMapping::Box)
+                .double_sided()
+        },
+        ExtrusionBuilder::build_vertices,
+    );
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_extrusion() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = ExtrusionBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
@@ -0,0 +1,318 @@
+//! A crate-private module for emitting flat, faceted polygons.
+
+extern crate cgmath;
+
+use self::cgmath::*;
+use crate::errors::ShapeCreationError;
+use crate::math::{Math, Transformation};
+use crate::vertex::Vertex;
+
+/// Emit a convex, planar polygon as a fan of triangles about its first point.
+///
+/// The polygon is expected to be wound counter-clock-wise when viewed from the
+/// front. Each vertex takes the face normal of the polygon, and texture
+/// coordinates define a planar-projection on the face, uniformly scaled and
+/// centred to fit the unit square. The u axis increases to the right and the
+/// v axis increases upwards when viewing the face from the front with the
+/// y-axis pointing up (faces pointing along the y-axis take the negative z-axis
+/// as up when facing upwards, and the positive z-axis when facing downwards).
+pub(crate) fn push_polygon<M: Math>(
+    vertices: &mut Vec<Vertex>,
+    transform: &Transformation<M>,
+    polygon: &[Vector3<f32>],
+) {
+    let triangles = (1..polygon.len() - 1)
+        .map(|index| [0, index, index + 1])
+        .collect::<Vec<[usize; 3]>>();
+    push_triangulated_polygon(vertices, transform, polygon, &triangles);
+}
+
+/// Emit a planar polygon as the given triangles, each of which indexes the
+/// points of the polygon.
+///
+/// The normals and texture coordinates are defined in the same way as for
+/// `push_polygon`, such that this may be used for concave polygons that have
+/// been triangulated with `triangulate`.
+pub(crate) fn push_triangulated_polygon<M: Math>(
+    vertices: &mut Vec<Vertex>,
+    transform: &Transformation<M>,
+    polygon: &[Vector3<f32>],
+    triangles: &[[usize; 3]],
+) {
+    // Compute the face normal using Newell's method, which is robust against
+    // nearly collinear points.
+    let mut normal = Vector3::<f32>::zero();
+    for (index, a) in polygon.iter().enumerate() {
+        let b = polygon[(index + 1) % polygon.len()];
+        normal.x += (a.y - b.y) * (a.z + b.z);
+        normal.y += (a.z - b.z) * (a.x + b.x);
+        normal.z += (a.x - b.x) * (a.y + b.y);
+    }
+    let normal = normal.normalize();
+
+    // Build the planar-projection basis for the face.
+    let up = if normal.y.abs() > 0.999 {
+        Vector3::<f32>::new(0.0, 0.0, -normal.y.signum())
+    } else {
+        Vector3::<f32>::unit_y()
+    };
+    let v_axis = (up - normal * normal.dot(up)).normalize();
+    let u_axis = v_axis.cross(normal);
+
+    // Project the polygon onto the face, and fit it to the unit square.
+    let projected = polygon
+        .iter()
+        .map(|p| Vector2::<f32>::new(p.dot(u_axis), p.dot(v_axis)))
+        .collect::<Vec<Vector2<f32>>>();
+    let mut min = projected[0];
+    let mut max = projected[0];
+    for p in &projected {
+        min.x = min.x.min(p.x);
+        min.y = min.y.min(p.y);
+        max.x = max.x.max(p.x);
+        max.y = max.y.max(p.y);
+    }
+    let extent = (max.x - min.x).max(max.y - min.y);
+    let offset = (Vector2::<f32>::from_value(extent) - (max - min)) * 0.5;
+    let texcoord = |p: Vector2<f32>| -> [f32; 2] {
+        let t = (p - min + offset) / extent;
+        [t.x, t.y]
+    };
+
+    let normal = transform.normal(normal);
+    for triangle in triangles {
+        for &corner in triangle.iter() {
+            vertices.push(Vertex {
+                position: transform.point(polygon[corner]),
+                normal,
+                texcoord: texcoord(projected[corner]),
+            });
+        }
+    }
+}
+
+/// Returns twice the signed area of the triangle `(a, b, c)`, which is positive
+/// when the triangle is wound counter-clock-wise.
+fn cross(a: Vector2<f32>, b: Vector2<f32>, c: Vector2<f32>) -> f32 {
+    (b - a).perp_dot(c - a)
+}
+
+/// Returns true if the closed segments `(a, b)` and `(c, d)` touch or cross.
+fn segments_intersect(a: Vector2<f32>, b: Vector2<f32>, c: Vector2<f32>, d: Vector2<f32>) -> bool {
+    let within = |p: Vector2<f32>, q: Vector2<f32>, r: Vector2<f32>| {
+        r.x >= p.x.min(q.x) && r.x <= p.x.max(q.x) && r.y >= p.y.min(q.y) && r.y <= p.y.max(q.y)
+    };
+    let (d1, d2) = (cross(c, d, a), cross(c, d, b));
+    let (d3, d4) = (cross(a, b, c), cross(a, b, d));
+    if d1 * d2 < 0.0 && d3 * d4 < 0.0 {
+        return true;
+    }
+    (d1 == 0.0 && within(c, d, a))
+        || (d2 == 0.0 && within(c, d, b))
+        || (d3 == 0.0 && within(a, b, c))
+        || (d4 == 0.0 && within(a, b, d))
+}
+
+/// Returns the signed area of a closed polygon, which is positive when the
+/// polygon is wound counter-clock-wise.
+pub(crate) fn signed_area(polygon: &[Vector2<f32>]) -> f32 {
+    let mut area = 0.0;
+    for (index, a) in polygon.iter().enumerate() {
+        area += a.perp_dot(polygon[(index + 1) % polygon.len()]);
+    }
+    area * 0.5
+}
+
+/// Triangulate a simple, closed, counter-clock-wise polygon by ear-clipping.
+///
+/// Concave polygons are supported, but polygons whose edges intersect (or
+/// that enclose no area) are rejected. A polygon of `n` points always results
+/// in `n - 2` triangles, each of which indexes the points of the polygon and is
+/// wound counter-clock-wise. Collinear points may result in triangles with no
+/// area.
+pub(crate) fn triangulate(polygon: &[Vector2<f32>]) -> Result<Vec<[usize; 3]>, ShapeCreationError> {
+    let count = polygon.len();
+    if count < 3 {
+        return Err(ShapeCreationError::NotEnoughPoints);
+    }
+    if signed_area(polygon) <= 0.0 {
+        return Err(ShapeCreationError::InvalidPolygon);
+    }
+
+    // Ensure that no pair of non-adjacent edges touch or cross.
+    for i in 0..count {
+        for j in (i + 2)..count {
+            if i == 0 && j == count - 1 {
+                continue;
+            }
+            let (a, b) = (polygon[i], polygon[(i + 1) % count]);
+            let (c, d) = (polygon[j], polygon[(j + 1) % count]);
+            if segments_intersect(a, b, c, d) {
+                return Err(ShapeCreationError::InvalidPolygon);
+            }
+        }
+    }
+
+    // Repeatedly clip the first ear found from the remaining polygon. A convex
+    // corner is an ear if no other remaining point lies within its triangle.
+    // If no ear remains (which can only happen due to collinear points), a
+    // collinear corner is clipped as a triangle of zero area instead.
+    let mut remaining = (0..count).collect::<Vec<usize>>();
+    let mut triangles = Vec::<[usize; 3]>::with_capacity(count - 2);
+    while remaining.len() > 3 {
+        let n = remaining.len();
+        let corner = |i: usize| {
+            let (a, b, c) = (
+                remaining[(i + n - 1) % n],
+                remaining[i],
+                remaining[(i + 1) % n],
+            );
+            [a, b, c]
+        };
+        let is_ear = |i: usize| {
+            let [a, b, c] = corner(i);
+            let (pa, pb, pc) = (polygon[a], polygon[b], polygon[c]);
+            cross(pa, pb, pc) > 0.0
+                && remaining.iter().all(|&p| {
+                    p == a
+                        || p == b
+                        || p == c
+                        || cross(pa, pb, polygon[p]) < 0.0
+                        || cross(pb, pc, polygon[p]) < 0.0
+                        || cross(pc, pa, polygon[p]) < 0.0
+                })
+        };
+        let is_collinear = |i: usize| {
+            let [a, b, c] = corner(i);
+            cross(polygon[a], polygon[b], polygon[c]) == 0.0
+        };
+        let clip = (0..n)
+            .find(|&i| is_ear(i))
+            .or_else(|| (0..n).find(|&i| is_collinear(i)))
+            .ok_or(ShapeCreationError::InvalidPolygon)?;
+        triangles.push(corner(clip));
+        remaining.remove(clip);
+    }
+    triangles.push([remaining[0], remaining[1], remaining[2]]);
+    Ok(triangles)
+}
+
+/// Point the normal of every vertex in the list of triangles in the direction
+/// of its triangle, such that the shape appears faceted when lit. The normals
+/// of degenerate triangles are left as they are.
+pub(crate) fn flatten_normals(vertices: &mut [Vertex]) {
+    for triangle in vertices.chunks_mut(3) {
+        let [v0, v1, v2] = [0, 1, 2].map(|index| Vector3::<f32>::from(triangle[index].position));
+        let normal = (v1 - v0).cross(v2 - v0);
+        if normal.magnitude2() > 0.0 {
+            let normal = normal.normalize();
+            for vertex in triangle.iter_mut() {
+                vertex.normal = normal.into();
+            }
+        }
+    }
+}
+
+/// Negate the normal of every vertex, leaving the positions, texture
+/// coordinates, and winding of the triangles unchanged.
+pub(crate) fn negate_normals(vertices: &mut [Vertex]) {
+    for vertex in vertices.iter_mut() {
+        vertex.normal = (-Vector3::<f32>::from(vertex.normal)).into();
+    }
+}
+
+/// Move every vertex along its normal by the given distance, which may be
+/// negative. The normals themselves are left unchanged, and a distance of
+/// zero leaves the vertices as they are.
+pub(crate) fn offset_along_normals(distance: f32, vertices: &mut [Vertex]) {
+    if distance == 0.0 {
+        return;
+    }
+    for vertex in vertices.iter_mut() {
+        let normal = Vector3::<f32>::from(vertex.normal);
+        vertex.position = (Vector3::<f32>::from(vertex.position) + normal * distance).into();
+    }
+}
+
+/// Reverse the winding of every triangle, by swapping its last two vertices,
+/// leaving the normals and texture coordinates of the vertices unchanged.
+pub(crate) fn reverse_winding(vertices: &mut [Vertex]) {
+    for triangle in vertices.chunks_mut(3) {
+        triangle.swap(1, 2);
+    }
+}
+
+/// Append a back face for every triangle in the list, which shares the
+/// positions and texture coordinates of its front face but has reversed
+/// winding and flipped normals. The front faces keep their vertex indices.
+pub(crate) fn push_back_faces(vertices: &mut Vec<Vertex>) {
+    let flip = |vertex: &Vertex| Vertex {
+        normal: (-Vector3::<f32>::from(vertex.normal)).into(),
+        ..*vertex
+    };
+    vertices.reserve(vertices.len());
+    for index in (0..vertices.len()).step_by(3) {
+        let back = [
+            flip(&vertices[index]),
+            flip(&vertices[index + 2]),
+            flip(&vertices[index + 1]),
+        ];
+        vertices.extend_from_slice(&back);
+    }
+}
+
+#[test]
+pub fn ensure_triangulated_concave_polygon_covers_its_area() {
+    let polygon = [
+        Vector2::<f32>::new(0.0, 0.0),
+        Vector2::<f32>::new(2.0, 0.0),
+        Vector2::<f32>::new(2.0, 1.0),
+        Vector2::<f32>::new(1.0, 1.0),
+        Vector2::<f32>::new(1.0, 2.0),
+        Vector2::<f32>::new(0.0, 2.0),
+    ];
+    let triangles = triangulate(&polygon).expect("Failed to triangulate polygon");
+    assert_eq!(triangles.len(), polygon.len() - 2);
+    let mut area = 0.0;
+    for t in &triangles {
+        let triangle_area = cross(polygon[t[0]], polygon[t[1]], polygon[t[2]]) * 0.5;
+        assert!(triangle_area > 0.0);
+        area += triangle_area;
+    }
+    assert_ulps_eq!(area, 3.0);
+}
+
+#[test]
+pub fn ensure_triangulated_polygon_with_collinear_points_has_no_overlaps() {
+    let polygon = [
+        Vector2::<f32>::new(0.0, 0.0),
+        Vector2::<f32>::new(1.0, 0.0),
+        Vector2::<f32>::new(2.0, 0.0),
+        Vector2::<f32>::new(2.0, 2.0),
+        Vector2::<f32>::new(0.0, 2.0),
+    ];
+    let triangles = triangulate(&polygon).expect("Failed to triangulate polygon");
+    assert_eq!(triangles.len(), polygon.len() - 2);
+    let mut area = 0.0;
+    for t in &triangles {
+        let triangle_area = cross(polygon[t[0]], polygon[t[1]], polygon[t[2]]) * 0.5;
+        assert!(triangle_area >= 0.0);
+        area += triangle_area;
+    }
+    assert_ulps_eq!(area, 4.0);
+}
+
+#[test]
+pub fn ensure_self_intersecting_polygon_fails_to_triangulate() {
+    let polygon = [
+        Vector2::<f32>::new(0.0, 0.0),
+        Vector2::<f32>::new(2.0, 0.0),
+        Vector2::<f32>::new(2.0, 2.0),
+        Vector2::<f32>::new(1.0, -1.0),
+        Vector2::<f32>::new(0.0, 2.0),
+    ];
+    match triangulate(&polygon) {
+        Err(ShapeCreationError::InvalidPolygon) => (),
+        _ => panic!("Expected InvalidPolygon"),
+    }
+}
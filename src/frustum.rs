@@ -0,0 +1,609 @@
+//! A module for constructing conical frustum shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::axis::Axis;
+use crate::errors::ShapeCreationError;
+use crate::facet::flatten_normals;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{
+    BuildAs, HasOptions, HasVertices, ShapeOptions, WithBackFaces, WithCaps, WithColors,
+    WithLightmap, WithModifiers, WithOrientation, WithShading, WithTangents, WithTexcoords,
+    WithTransform,
+};
+use crate::resolution::{Resolution, WithResolution};
+use crate::shading::Shading;
+use crate::vertex::Vertex;
+
+use std::f32;
+
+/// A polygonal `Frustum` object.
+///
+/// This object is constructed using a `FrustumBuilder` object.
+pub struct Frustum {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows a `Frustum` object to be passed as a source of vertices.
+impl<'a> From<&'a Frustum> for glium::vertex::VerticesSource<'a> {
+    fn from(frustum: &'a Frustum) -> glium::vertex::VerticesSource<'a> {
+        (&frustum.vertices).into()
+    }
+}
+
+/// Allows a `Frustum` object to be passed as a source of indices.
+impl<'a> From<&'a Frustum> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a Frustum) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::TrianglesList,
+        }
+    }
+}
+
+/// Responsible for building and returning a `Frustum` object.
+///
+/// By default, the frustum is defined as a capped conical frustum of unit
+/// height, aligned to the y-axis, with a bottom radius of 0.5, a top radius of
+/// 0.25, and its vertical centre located at the origin. This can be overriden
+/// using the transformation methods on this object.
+///
+/// When the top and bottom radii are equal, the frustum is identical to the
+/// geometry produced by a `CylinderBuilder`. When either radius is zero, the
+/// frustum degenerates into a cone, with the apex duplicated for each segment
+/// (as with the `ConeBuilder`), and the cap at the apex is omitted.
+///
+/// The resultant geometry is constructed to suit OpenGL defaults - assuming
+/// a right-handed coordinate system, front-facing polygons are defined in
+/// counter-clock-wise order. Vertex normals on the side of the frustum are
+/// perpendicular to the slanted surface (such that the side appears smooth when
+/// lit), whereas the normals of each cap point along the axis (such that the
+/// caps appear faceted against the side). Vertex texture coordinates define a
+/// cylindrical-projection on the side, and a planar-projection on each cap.
+pub struct FrustumBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    axis: Axis,
+    bottom_radius: f32,
+    top_radius: f32,
+    segments: usize,
+    stacks: usize,
+    options: ShapeOptions,
+}
+
+impl Default for FrustumBuilder {
+    fn default() -> Self {
+        FrustumBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            axis: Axis::Y,
+            bottom_radius: 0.5,
+            top_radius: 0.25,
+            segments: 24,
+            stacks: 1,
+            options: ShapeOptions {
+                shading: Shading::Smooth,
+                ..ShapeOptions::default()
+            },
+        }
+    }
+}
+
+impl FrustumBuilder {
+    /// Create a new `FrustumBuilder` object.
+    pub fn new() -> FrustumBuilder {
+        Default::default()
+    }
+
+    /// Specify the radius of the bottom of the frustum. By default, the bottom
+    /// radius is 0.5. The radius must not be negative, and at least one of the
+    /// top and bottom radii must be greater than zero.
+    pub fn bottom_radius(mut self, radius: f32) -> Self {
+        self.bottom_radius = radius;
+        self
+    }
+
+    /// Specify the radius of the top of the frustum. By default, the top
+    /// radius is 0.25. The radius must not be negative, and at least one of the
+    /// top and bottom radii must be greater than zero.
+    pub fn top_radius(mut self, radius: f32) -> Self {
+        self.top_radius = radius;
+        self
+    }
+
+    /// Specify the number of radial segments around the frustum. By default,
+    /// the builder will use 24 segments. At least 3 segments are required to
+    /// build a valid frustum.
+    pub fn segments(mut self, segments: usize) -> Self {
+        self.segments = segments;
+        self
+    }
+
+    /// Specify the number of stacks along the height of the frustum. By
+    /// default, the builder will use 1 stack. At least 1 stack is required to
+    /// build a valid frustum.
+    pub fn stacks(mut self, stacks: usize) -> Self {
+        self.stacks = stacks;
+        self
+    }
+
+    /// Specify the axis along which the frustum is aligned. By default, the
+    /// frustum is aligned to the y-axis. The frustum is rotated onto its axis
+    /// before any other transformation is applied.
+    pub fn axis(mut self, axis: Axis) -> Self {
+        self.axis = axis;
+        self
+    }
+
+    /// Build a new `Frustum` object.
+    pub fn build<F>(self, display: &F) -> Result<Frustum, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the shape vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// a `Frustum` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        let vertices = self.vertices_with::<Selected>()?;
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.options.check_shading(Self::SHADINGS)?;
+
+        // Ensure there are enough segments and stacks to produce valid
+        // frustum geometry.
+        if self.segments < 3 {
+            return Err(ShapeCreationError::NotEnoughDivisionsInU);
+        }
+
+        if self.stacks < 1 {
+            return Err(ShapeCreationError::NotEnoughDivisionsInV);
+        }
+
+        // Ensure the radii describe a frustum with a non-zero volume.
+        let (rb, rt) = (self.bottom_radius, self.top_radius);
+        if rb < 0.0 || rt < 0.0 || (rb == 0.0 && rt == 0.0) {
+            return Err(ShapeCreationError::InvalidRadii);
+        }
+
+        // Build a lookup table of (cos, sin) pairs around the frustum.
+        let angle = 2.0 * f32::consts::PI / self.segments as f32;
+        let ring = (0..=self.segments)
+            .map(|x| {
+                let a = ((x % self.segments) as f32) * angle;
+                [a.cos(), a.sin()]
+            })
+            .collect::<Vec<[f32; 2]>>();
+
+        // The side normal for a given (cos, sin) pair. For a frustum of unit
+        // height, the normal rises by the difference in radii for every unit
+        // it extends outward.
+        let side_normal = |[c, s]: [f32; 2]| Vector3::<f32>::new(c, rb - rt, -s);
+        let rim = |index: usize, y: f32, r: f32| {
+            let [c, s] = ring[index];
+            Vector3::<f32>::new(c * r, y, -s * r)
+        };
+        let u = |index: usize| index as f32 / self.segments as f32;
+
+        // Compute the transformation matrix, which rotates the shape onto its
+        // axis before any other transformation, and the normal transformation
+        // matrix.
+        let matrix = self.matrix * self.axis.matrix();
+        let transform = Transformation::<M>::new(matrix);
+
+        let mut vertices = Vec::<Vertex>::with_capacity(self.num_vertices());
+        let mut emit = |position: Vector3<f32>, normal: Vector3<f32>, texcoord: [f32; 2]| {
+            vertices.push(Vertex {
+                position: transform.point(position),
+                normal: transform.normal(normal),
+                texcoord,
+            });
+        };
+
+        // Emit the side faces. Angles increase counter-clock-wise when viewed
+        // from above, such that the u coordinate increases from left to right
+        // when viewed from outside. Each stack is built from a quad per
+        // segment, with its radius interpolated between the bottom and top
+        // radii. Where a radius is zero, each segment of the stack that touches
+        // it is reduced to a single triangle, with the apex taking the normal
+        // and u coordinate of the middle of the segment.
+        let level = |stack: usize| {
+            let v = stack as f32 / self.stacks as f32;
+            let r = if stack == 0 {
+                rb
+            } else if stack == self.stacks {
+                rt
+            } else {
+                rb + (rt - rb) * v
+            };
+            (v - 0.5, r, v)
+        };
+        let quad = [(0, 0), (1, 0), (1, 1), (0, 0), (1, 1), (0, 1)];
+        for stack in 0..self.stacks {
+            let (y0, r0, v0) = level(stack);
+            let (y1, r1, v1) = level(stack + 1);
+            for seg in 0..self.segments {
+                let mid = (seg as f32 + 0.5) * angle;
+                let (mid_normal, mid_u) = (
+                    side_normal([mid.cos(), mid.sin()]),
+                    (u(seg) + u(seg + 1)) * 0.5,
+                );
+                if r1 == 0.0 {
+                    emit(rim(seg, y0, r0), side_normal(ring[seg]), [u(seg), v0]);
+                    emit(
+                        rim(seg + 1, y0, r0),
+                        side_normal(ring[seg + 1]),
+                        [u(seg + 1), v0],
+                    );
+                    emit(Vector3::<f32>::new(0.0, y1, 0.0), mid_normal, [mid_u, v1]);
+                } else if r0 == 0.0 {
+                    emit(Vector3::<f32>::new(0.0, y0, 0.0), mid_normal, [mid_u, v0]);
+                    emit(
+                        rim(seg + 1, y1, r1),
+                        side_normal(ring[seg + 1]),
+                        [u(seg + 1), v1],
+                    );
+                    emit(rim(seg, y1, r1), side_normal(ring[seg]), [u(seg), v1]);
+                } else {
+                    for &(du, dv) in quad.iter() {
+                        let (y, r, v) = if dv == 0 { (y0, r0, v0) } else { (y1, r1, v1) };
+                        emit(
+                            rim(seg + du, y, r),
+                            side_normal(ring[seg + du]),
+                            [u(seg + du), v],
+                        );
+                    }
+                }
+            }
+        }
+
+        // Emit the top and bottom caps as triangle fans about each cap centre,
+        // skipping any cap that is disabled or has collapsed to a point.
+        let caps = [
+            (0.5, rt, self.options.caps.caps_end(), [0, 1]),
+            (-0.5, rb, self.options.caps.caps_start(), [1, 0]),
+        ];
+        for &(y, r, enabled, order) in caps.iter() {
+            if !enabled || r == 0.0 {
+                continue;
+            }
+            let normal = Vector3::<f32>::new(0.0, y * 2.0, 0.0);
+            for seg in 0..self.segments {
+                let indices = [seg + order[0], seg + order[1]];
+                emit(Vector3::<f32>::new(0.0, y, 0.0), normal, [0.5, 0.5]);
+                for &index in indices.iter() {
+                    let pos = rim(index, y, r);
+                    emit(
+                        pos,
+                        normal,
+                        [pos.x / (r * 2.0) + 0.5, 0.5 - (pos.z * y * 2.0) / (r * 2.0)],
+                    );
+                }
+            }
+        }
+
+        if self.options.shading == Shading::Flat {
+            flatten_normals(&mut vertices);
+        }
+
+        self.options.map_texcoords(&mut vertices);
+        Ok(vertices)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    pub fn num_vertices(&self) -> usize {
+        let (rb, rt) = (self.bottom_radius, self.top_radius);
+        let apex = if rb == 0.0 || rt == 0.0 { 1 } else { 0 };
+        let side = self.stacks.saturating_sub(apex) * 6 + apex * 3;
+        let caps = [
+            (rt, self.options.caps.caps_end()),
+            (rb, self.options.caps.caps_start()),
+        ]
+        .iter()
+        .filter(|&&(r, enabled)| enabled && r != 0.0)
+        .count();
+        ((self.segments * side) + (self.segments * 3 * caps)) * self.options.copies()
+    }
+}
+
+impl HasOptions for FrustumBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for FrustumBuilder {
+    type Shape = Frustum;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> Frustum {
+        Frustum { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for FrustumBuilder {}
+
+impl BuildAs for FrustumBuilder {}
+
+impl WithTangents for FrustumBuilder {}
+
+impl WithLightmap for FrustumBuilder {}
+
+impl WithOrientation for FrustumBuilder {}
+
+impl WithBackFaces for FrustumBuilder {}
+
+impl WithModifiers for FrustumBuilder {}
+
+/// The start of the frustum is its bottom, and its end is its top.
+impl WithCaps for FrustumBuilder {}
+
+impl WithTexcoords for FrustumBuilder {}
+
+impl WithColors for FrustumBuilder {}
+
+impl WithShading for FrustumBuilder {
+    /// By default, the frustum is smooth shaded. When flat shaded, each vertex
+    /// normal points in the direction of its respective triangle instead.
+    const SHADINGS: &'static [Shading] = &[Shading::Flat, Shading::Smooth];
+}
+
+impl WithResolution for FrustumBuilder {
+    /// At least 3 segments around the frustum, and 1 stack along its height.
+    const MIN_RESOLUTION: Resolution = Resolution {
+        around: 3,
+        along: 1,
+    };
+
+    /// Specify the number of segments around the frustum, and the number of
+    /// stacks along its height.
+    fn resolution(mut self, resolution: Resolution) -> Self {
+        self.segments = resolution.around as usize;
+        self.stacks = resolution.along as usize;
+        self
+    }
+}
+
+#[test]
+pub fn ensure_default_frustum_has_unit_dimensions() {
+    let vertices = FrustumBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        let pos = Vector3::<f32>::from(vertex.position);
+        assert!(abs_diff_eq!(pos.y.abs(), 0.5));
+        let radius = Vector2::<f32>::new(pos.x, pos.z).magnitude();
+        assert!(radius <= 0.375 - pos.y * 0.25 + 0.0001);
+    }
+}
+
+#[test]
+pub fn ensure_frustum_with_equal_radii_matches_cylinder() {
+    use crate::cylinder::CylinderBuilder;
+    let frustum = FrustumBuilder::new()
+        .bottom_radius(0.5)
+        .top_radius(0.5)
+        .segments(7)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let cylinder = CylinderBuilder::new()
+        .segments(7)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    assert_eq!(frustum.len(), cylinder.len());
+    for (a, b) in frustum.iter().zip(cylinder.iter()) {
+        assert_eq!(a.position, b.position);
+        assert_eq!(a.normal, b.normal);
+        assert_eq!(a.texcoord, b.texcoord);
+    }
+}
+
+#[test]
+pub fn ensure_frustum_with_zero_top_radius_matches_cone() {
+    use crate::cone::ConeBuilder;
+    let frustum = FrustumBuilder::new()
+        .top_radius(0.0)
+        .segments(7)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let cone = ConeBuilder::new()
+        .segments(7)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    assert_eq!(frustum.len(), cone.len());
+    for (a, b) in frustum.iter().zip(cone.iter()) {
+        assert_ulps_eq!(
+            Vector3::<f32>::from(a.position),
+            Vector3::<f32>::from(b.position),
+            epsilon = 0.0001
+        );
+        assert_ulps_eq!(
+            Vector3::<f32>::from(a.normal),
+            Vector3::<f32>::from(b.normal),
+            epsilon = 0.0001
+        );
+        assert_ulps_eq!(
+            Vector2::<f32>::from(a.texcoord),
+            Vector2::<f32>::from(b.texcoord),
+            epsilon = 0.0001
+        );
+    }
+}
+
+#[test]
+pub fn ensure_frustum_side_normals_are_perpendicular_to_slant() {
+    let builder = FrustumBuilder::new().bottom_radius(0.4).top_radius(0.1);
+    let vertices = builder.build_vertices().expect("Failed to build vertices");
+    for vertex in &vertices[..builder.segments * 6] {
+        let pos = Vector3::<f32>::from(vertex.position);
+        let radial = Vector2::<f32>::new(pos.x, pos.z).normalize();
+        let slant = Vector3::<f32>::new(radial.x * -0.3, 1.0, radial.y * -0.3);
+        let normal = Vector3::<f32>::from(vertex.normal);
+        assert!(abs_diff_eq!(normal.dot(slant), 0.0, epsilon = 0.0001));
+        assert!(abs_diff_eq!(normal.magnitude(), 1.0, epsilon = 0.0001));
+    }
+}
+
+#[test]
+pub fn ensure_frustum_has_ccw_triangles() {
+    for &(rb, rt) in [(0.5, 0.25), (0.25, 0.5), (0.5, 0.0), (0.0, 0.5)].iter() {
+        let vertices = FrustumBuilder::new()
+            .bottom_radius(rb)
+            .top_radius(rt)
+            .build_vertices()
+            .expect("Failed to build vertices");
+        for chunk in vertices.chunks(3) {
+            let v0 = Vector3::<f32>::from(chunk[0].position);
+            let v1 = Vector3::<f32>::from(chunk[1].position);
+            let v2 = Vector3::<f32>::from(chunk[2].position);
+            let eyepos = v0 + Vector3::<f32>::from(chunk[0].normal);
+            let e0 = v1 - v0;
+            let e1 = v2 - v0;
+            let n = e0.cross(e1);
+            assert!(n.dot(v0 - eyepos) <= 0.0);
+            assert!(n.dot(v1 - eyepos) <= 0.0);
+            assert!(n.dot(v2 - eyepos) <= 0.0);
+        }
+    }
+}
+
+#[test]
+pub fn ensure_frustum_caps_can_be_disabled() {
+    use crate::cap_mode::CapMode;
+    let builder = FrustumBuilder::new().segments(5);
+    assert_eq!(builder.num_vertices(), 5 * 6 + 5 * 3 * 2);
+    let builder = builder.caps(CapMode::Start);
+    assert_eq!(builder.num_vertices(), 5 * 6 + 5 * 3);
+    let vertices = builder
+        .caps(CapMode::Neither)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    assert_eq!(vertices.len(), 5 * 6);
+}
+
+#[test]
+pub fn ensure_frustum_with_invalid_radii_fails_to_build() {
+    for &(rb, rt) in [(0.0, 0.0), (-0.5, 0.5), (0.5, -0.5)].iter() {
+        match FrustumBuilder::new()
+            .bottom_radius(rb)
+            .top_radius(rt)
+            .build_vertices()
+        {
+            Err(ShapeCreationError::InvalidRadii) => (),
+            _ => panic!("Expected InvalidRadii"),
+        }
+    }
+}
+
+#[test]
+pub fn ensure_frustum_with_too_few_segments_fails_to_build() {
+    match FrustumBuilder::new().segments(2).build_vertices() {
+        Err(ShapeCreationError::NotEnoughDivisionsInU) => (),
+        _ => panic!("Expected NotEnoughDivisionsInU"),
+    }
+}
+
+#[test]
+pub fn ensure_frustum_can_be_aligned_to_z_axis() {
+    use crate::axis::Axis;
+    let base = FrustumBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let vertices = FrustumBuilder::new()
+        .axis(Axis::Z)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for (a, b) in vertices.iter().zip(base.iter()) {
+        let ([x, y, z], [nx, ny, nz]) = (b.position, b.normal);
+        assert_eq!(a.position, [x, -z, y]);
+        assert_ulps_eq!(
+            Vector3::from(a.normal),
+            Vector3::new(nx, -nz, ny),
+            epsilon = 0.000001
+        );
+    }
+}
+
+#[test]
+pub fn ensure_frustum_applies_late_pipeline() {
+    use crate::options::WithTexcoords;
+    use crate::uv_mapping::UvMapping;
+
+    crate::options::ensure_late_pipeline(
+        || {
+            FrustumBuilder::new()
+                .uv_mapping(UvMapping::Box)
+                .double_sided()
+        },
+        FrustumBuilder::build_vertices,
+    );
+}
+
+#[test]
+pub fn ensure_frustum_with_invalid_resolution_fails_to_build() {
+    crate::resolution::ensure_min_resolution(
+        FrustumBuilder::new,
+        FrustumBuilder::build_vertices,
+        ShapeCreationError::NotEnoughDivisionsInU,
+        Some(ShapeCreationError::NotEnoughDivisionsInV),
+    );
+}
+
+#[test]
+pub fn ensure_frustum_stacks_lie_on_the_slope() {
+    for &(bottom, top) in [(0.5, 0.25), (0.5, 0.0), (0.0, 0.5)].iter() {
+        let builder = FrustumBuilder::new()
+            .bottom_radius(bottom)
+            .top_radius(top)
+            .segments(6)
+            .stacks(3);
+        let vertices = builder.build_vertices().expect("Failed to build vertices");
+        assert_eq!(vertices.len(), builder.num_vertices());
+        let side = if bottom == 0.0 || top == 0.0 { 15 } else { 18 };
+        for vertex in vertices.iter().take(6 * side) {
+            let pos = Vector3::<f32>::from(vertex.position);
+            let radius = Vector2::<f32>::new(pos.x, pos.z).magnitude();
+            let expected = bottom + (top - bottom) * (pos.y + 0.5);
+            assert!(abs_diff_eq!(radius, expected, epsilon = 0.0001));
+            assert_ulps_eq!(vertex.texcoord[1], pos.y + 0.5);
+        }
+    }
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_frustum() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = FrustumBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
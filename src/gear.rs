@@ -0,0 +1,457 @@
+//! A module for constructing gear shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::errors::ShapeCreationError;
+use crate::facet::{flatten_normals, push_polygon};
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{
+    BuildAs, HasOptions, HasVertices, ShapeOptions, WithBackFaces, WithColors, WithLightmap,
+    WithModifiers, WithOrientation, WithShading, WithTangents, WithTexcoords, WithTransform,
+};
+use crate::shading::Shading;
+use crate::vertex::Vertex;
+
+use std::f32;
+
+/// A polygonal `Gear` object.
+///
+/// This object is constructed using a `GearBuilder` object.
+pub struct Gear {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows a `Gear` object to be passed as a source of vertices.
+impl<'a> From<&'a Gear> for glium::vertex::VerticesSource<'a> {
+    fn from(gear: &'a Gear) -> glium::vertex::VerticesSource<'a> {
+        (&gear.vertices).into()
+    }
+}
+
+/// Allows a `Gear` object to be passed as a source of indices.
+impl<'a> From<&'a Gear> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a Gear) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::TrianglesList,
+        }
+    }
+}
+
+/// Responsible for building and returning a `Gear` object.
+///
+/// The gear is constructed by extruding a toothed profile about a central
+/// hole, where each tooth is simplified to a trapezoid that rises from the root
+/// radius to the tip radius. By default, the gear is defined as a flat wheel
+/// in the xy-plane, facing the positive z-axis, with 12 teeth, a hole radius of
+/// 0.1, a root radius of 0.4, a tip radius of 0.5, and a thickness of 0.2,
+/// with its centre located at the origin and its first tooth centred on the
+/// positive x-axis. This can be overriden using the transformation methods on
+/// this object.
+///
+/// The resultant geometry is constructed to suit OpenGL defaults - assuming
+/// a right-handed coordinate system, front-facing polygons are defined in
+/// counter-clock-wise order. Vertex normals on the front and back faces point
+/// along the z-axis, and vertex normals on the outer walls point in the
+/// direction of their respective face (such that the tooth flanks appear
+/// faceted when lit). Vertex normals on the wall of the hole point towards the
+/// z-axis (such that the hole appears smooth when lit). Vertex texture
+/// coordinates define a planar-projection on the front and back faces, scaled
+/// to fit the tip radius to the unit square, and on each outer wall. On the
+/// wall of the hole, the angle around the hole maps to the u coordinate, and
+/// the depth through the gear maps to the v coordinate.
+pub struct GearBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    teeth: usize,
+    hole_radius: f32,
+    root_radius: f32,
+    tip_radius: f32,
+    thickness: f32,
+    options: ShapeOptions,
+}
+
+impl Default for GearBuilder {
+    fn default() -> Self {
+        GearBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            teeth: 12,
+            hole_radius: 0.1,
+            root_radius: 0.4,
+            tip_radius: 0.5,
+            thickness: 0.2,
+            options: ShapeOptions {
+                shading: Shading::Smooth,
+                ..ShapeOptions::default()
+            },
+        }
+    }
+}
+
+impl GearBuilder {
+    /// Create a new `GearBuilder` object.
+    pub fn new() -> GearBuilder {
+        Default::default()
+    }
+
+    /// Specify the number of teeth around the gear. By default, the gear has
+    /// 12 teeth. At least 3 teeth are required to build a valid gear.
+    pub fn teeth(mut self, teeth: usize) -> Self {
+        self.teeth = teeth;
+        self
+    }
+
+    /// Specify the radius of the hole through the centre of the gear. By
+    /// default, the radius is 0.1. The radius must be greater than zero, and
+    /// less than the root radius, to build a valid gear.
+    pub fn hole_radius(mut self, radius: f32) -> Self {
+        self.hole_radius = radius;
+        self
+    }
+
+    /// Specify the radius at the root of the teeth. By default, the radius is
+    /// 0.4. The radius must be less than the tip radius to build a valid gear.
+    pub fn root_radius(mut self, radius: f32) -> Self {
+        self.root_radius = radius;
+        self
+    }
+
+    /// Specify the radius at the tip of the teeth. By default, the radius is
+    /// 0.5.
+    pub fn tip_radius(mut self, radius: f32) -> Self {
+        self.tip_radius = radius;
+        self
+    }
+
+    /// Specify the thickness of the gear along the z-axis. By default, the
+    /// thickness is 0.2. The thickness must be greater than zero to build a
+    /// valid gear.
+    pub fn thickness(mut self, thickness: f32) -> Self {
+        self.thickness = thickness;
+        self
+    }
+
+    /// Build a new `Gear` object.
+    pub fn build<F>(self, display: &F) -> Result<Gear, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the shape vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// a `Gear` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        let vertices = self.vertices_with::<Selected>()?;
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.options.check_shading(Self::SHADINGS)?;
+
+        // Ensure the parameters produce valid gear geometry.
+        if self.teeth < 3 {
+            return Err(ShapeCreationError::NotEnoughDivisionsInU);
+        }
+        if self.hole_radius <= 0.0
+            || self.hole_radius >= self.root_radius
+            || self.root_radius >= self.tip_radius
+        {
+            return Err(ShapeCreationError::InvalidRadii);
+        }
+        if self.thickness <= 0.0 {
+            return Err(ShapeCreationError::InvalidDimensions);
+        }
+
+        // Build lookup tables of the points around the profile of the teeth,
+        // and the points around the hole at the same angles. Each tooth spans
+        // half of its pitch at the root and a quarter of its pitch at the tip,
+        // and is centred on its pitch angle. The last entry of each table
+        // wraps around to the first such that seam positions match.
+        let pitch = 2.0 * f32::consts::PI / self.teeth as f32;
+        let tooth = [
+            (-0.75, self.root_radius),
+            (-0.25, self.root_radius),
+            (-0.125, self.tip_radius),
+            (0.125, self.tip_radius),
+        ];
+        let count = self.teeth * tooth.len();
+        let angles = (0..=count)
+            .map(|x| {
+                let (offset, radius) = tooth[x % tooth.len()];
+                let a = ((x / tooth.len()) % self.teeth) as f32 * pitch + offset * pitch;
+                (Vector2::<f32>::new(a.cos(), a.sin()), radius)
+            })
+            .collect::<Vec<(Vector2<f32>, f32)>>();
+        let profile = angles
+            .iter()
+            .map(|&(dir, radius)| dir * radius)
+            .collect::<Vec<Vector2<f32>>>();
+        let hole = angles
+            .iter()
+            .map(|&(dir, _)| dir * self.hole_radius)
+            .collect::<Vec<Vector2<f32>>>();
+        let depth = [-0.5 * self.thickness, 0.5 * self.thickness];
+
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<M>::new(self.matrix);
+
+        let mut vertices = Vec::<Vertex>::with_capacity(self.num_vertices());
+        {
+            let mut emit = |position: Vector3<f32>, normal: Vector3<f32>, texcoord: [f32; 2]| {
+                vertices.push(Vertex {
+                    position: transform.point(position),
+                    normal: transform.normal(normal),
+                    texcoord,
+                });
+            };
+
+            // Emit the front and back faces as quads between the hole and the
+            // profile, in the same order as the `Annulus`. The back face reverses
+            // the winding, and mirrors the u coordinate.
+            let quad = [(0, 0), (0, 1), (1, 1), (0, 0), (1, 1), (1, 0)];
+            let scale = 0.5 / self.tip_radius;
+            for &(side, sign) in [(1, 1.0), (0, -1.0)].iter() {
+                for x in 0..count {
+                    for k in 0..quad.len() {
+                        let (du, dv) = quad[if side == 1 { k } else { quad.len() - 1 - k }];
+                        let point = [&hole, &profile][dv][x + du];
+                        emit(
+                            point.extend(depth[side]),
+                            Vector3::<f32>::new(0.0, 0.0, sign),
+                            [0.5 + point.x * scale * sign, 0.5 + point.y * scale],
+                        );
+                    }
+                }
+            }
+
+            // Emit the wall of the hole, whose angle increases to the left when
+            // viewed from inside the hole.
+            for x in 0..count {
+                for &(dv, du) in quad.iter() {
+                    let (dir, _) = angles[x + 1 - du];
+                    emit(
+                        hole[x + 1 - du].extend(depth[dv]),
+                        -dir.extend(0.0),
+                        [(count - x - 1 + du) as f32 / count as f32, dv as f32],
+                    );
+                }
+            }
+        }
+
+        // Emit the outer walls of the teeth as faceted quads.
+        for x in 0..count {
+            let face = [
+                profile[x].extend(depth[0]),
+                profile[x + 1].extend(depth[0]),
+                profile[x + 1].extend(depth[1]),
+                profile[x].extend(depth[1]),
+            ];
+            push_polygon(&mut vertices, &transform, &face);
+        }
+
+        if self.options.shading == Shading::Flat {
+            flatten_normals(&mut vertices);
+        }
+
+        self.options.map_texcoords(&mut vertices);
+        Ok(vertices)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    pub fn num_vertices(&self) -> usize {
+        self.teeth * 4 * 6 * 4 * self.options.copies()
+    }
+}
+
+impl HasOptions for GearBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for GearBuilder {
+    type Shape = Gear;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> Gear {
+        Gear { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for GearBuilder {}
+
+impl BuildAs for GearBuilder {}
+
+impl WithTangents for GearBuilder {}
+
+impl WithLightmap for GearBuilder {}
+
+impl WithOrientation for GearBuilder {}
+
+impl WithBackFaces for GearBuilder {}
+
+impl WithModifiers for GearBuilder {}
+
+impl WithTexcoords for GearBuilder {}
+
+impl WithColors for GearBuilder {}
+
+impl WithShading for GearBuilder {
+    /// By default, the gear is smooth shaded. When flat shaded, each vertex
+    /// normal points in the direction of its respective triangle instead.
+    const SHADINGS: &'static [Shading] = &[Shading::Flat, Shading::Smooth];
+}
+
+#[test]
+pub fn ensure_default_gear_is_watertight() {
+    use std::collections::HashMap;
+    let vertices = GearBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let key = |v: &Vertex| Vector3::<f32>::from(v.position).map(f32::to_bits);
+    let mut edges = HashMap::<_, usize>::new();
+    for chunk in vertices.chunks(3) {
+        for i in 0..3 {
+            let (a, b) = (key(&chunk[i]), key(&chunk[(i + 1) % 3]));
+            let edge = if (a.x, a.y, a.z) < (b.x, b.y, b.z) {
+                (a, b)
+            } else {
+                (b, a)
+            };
+            *edges.entry(edge).or_insert(0) += 1;
+        }
+    }
+    for count in edges.values() {
+        assert_eq!(*count, 2);
+    }
+}
+
+#[test]
+pub fn ensure_default_gear_has_ccw_triangles() {
+    let vertices = GearBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let eyepos = v0 + Vector3::<f32>::from(chunk[0].normal);
+        let e0 = v1 - v0;
+        let e1 = v2 - v0;
+        let n = e0.cross(e1);
+        assert!(n.dot(v0 - eyepos) <= 0.0);
+        assert!(n.dot(v1 - eyepos) <= 0.0);
+        assert!(n.dot(v2 - eyepos) <= 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_gear_triangle_count_scales_with_teeth() {
+    let triangles = |teeth: usize| {
+        let builder = GearBuilder::new().teeth(teeth);
+        let vertices = builder.build_vertices().expect("Failed to build vertices");
+        assert_eq!(vertices.len(), builder.num_vertices());
+        vertices.len() / 3
+    };
+    let base = triangles(3);
+    for teeth in 4..20 {
+        assert_eq!(triangles(teeth) * 3, base * teeth);
+    }
+}
+
+#[test]
+pub fn ensure_default_gear_has_faceted_tooth_flanks() {
+    let builder = GearBuilder::new();
+    let vertices = builder.build_vertices().expect("Failed to build vertices");
+    let walls = &vertices[builder.num_vertices() * 3 / 4..];
+    for chunk in walls.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let n = (v1 - v0).cross(v2 - v0).normalize();
+        assert!(abs_diff_eq!(n.z, 0.0, epsilon = 0.0001));
+        for vertex in chunk {
+            assert_ulps_eq!(n, Vector3::<f32>::from(vertex.normal), epsilon = 0.0001);
+        }
+    }
+    for vertex in &vertices {
+        let radius = Vector2::<f32>::new(vertex.position[0], vertex.position[1]).magnitude();
+        assert!(radius > 0.0999 && radius < 0.5001);
+        assert!(abs_diff_eq!(
+            vertex.position[2].abs(),
+            0.1,
+            epsilon = 0.0001
+        ));
+    }
+}
+
+#[test]
+pub fn ensure_gear_with_invalid_parameters_fails_to_build() {
+    match GearBuilder::new().teeth(2).build_vertices() {
+        Err(ShapeCreationError::NotEnoughDivisionsInU) => (),
+        _ => panic!("Expected NotEnoughDivisionsInU"),
+    }
+    for &(hole, root, tip) in [(0.0, 0.4, 0.5), (0.4, 0.4, 0.5), (0.1, 0.5, 0.5)].iter() {
+        match GearBuilder::new()
+            .hole_radius(hole)
+            .root_radius(root)
+            .tip_radius(tip)
+            .build_vertices()
+        {
+            Err(ShapeCreationError::InvalidRadii) => (),
+            _ => panic!("Expected InvalidRadii"),
+        }
+    }
+    match GearBuilder::new().thickness(0.0).build_vertices() {
+        Err(ShapeCreationError::InvalidDimensions) => (),
+        _ => panic!("Expected InvalidDimensions"),
+    }
+}
+
+#[test]
+pub fn ensure_gear_applies_late_pipeline() {
+    use crate::options::WithTexcoords;
+    use crate::uv_mapping::UvMapping;
+
+    crate::options::ensure_late_pipeline(
+        || GearBuilder::new().uv_mapping(UvMapping::Box).double_sided(),
+        GearBuilder::build_vertices,
+    );
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_gear() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = GearBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
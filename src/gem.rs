@@ -0,0 +1,367 @@
+//! A module for constructing gem shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::errors::ShapeCreationError;
+use crate::facet::push_polygon;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{
+    BuildAs, HasOptions, HasVertices, ShapeOptions, WithBackFaces, WithColors, WithLightmap,
+    WithModifiers, WithOrientation, WithShading, WithTangents, WithTexcoords, WithTransform,
+};
+use crate::shading::Shading;
+use crate::vertex::Vertex;
+
+use std::f32;
+
+/// A polygonal `Gem` object.
+///
+/// This object is constructed using a `GemBuilder` object.
+pub struct Gem {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows a `Gem` object to be passed as a source of vertices.
+impl<'a> From<&'a Gem> for glium::vertex::VerticesSource<'a> {
+    fn from(gem: &'a Gem) -> glium::vertex::VerticesSource<'a> {
+        (&gem.vertices).into()
+    }
+}
+
+/// Allows a `Gem` object to be passed as a source of indices.
+impl<'a> From<&'a Gem> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a Gem) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::TrianglesList,
+        }
+    }
+}
+
+/// Responsible for building and returning a `Gem` object.
+///
+/// The gem is constructed in the style of a brilliant cut, with a polygonal
+/// girdle that separates a crown above from a pavilion below. The crown rises
+/// to a flat table, which is rotated by half a step relative to the girdle,
+/// and is joined to the girdle by alternating bezel and star facets. The
+/// pavilion is a pyramid that descends from the girdle to a point (the culet).
+///
+/// By default, the girdle is an octagon whose vertices lie on a circle of
+/// radius 0.5 in the xz-plane, the first of which lies on the positive x-axis.
+/// The table is half the width of the girdle, and lies 0.15 above it, and the
+/// culet lies 0.45 below it. This can be overriden using the dimension and
+/// transformation methods on this object.
+///
+/// The resultant geometry is constructed to suit OpenGL defaults - assuming
+/// a right-handed coordinate system, front-facing polygons are defined in
+/// counter-clock-wise order. Vertex normals point in the direction of their
+/// respective facet (such that the shape appears faceted when lit). Vertex
+/// texture coordinates define a planar-projection on each facet.
+pub struct GemBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    sides: usize,
+    table: f32,
+    crown_height: f32,
+    pavilion_depth: f32,
+    options: ShapeOptions,
+}
+
+impl Default for GemBuilder {
+    fn default() -> Self {
+        GemBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            sides: 8,
+            table: 0.5,
+            crown_height: 0.15,
+            pavilion_depth: 0.45,
+            options: ShapeOptions::default(),
+        }
+    }
+}
+
+impl GemBuilder {
+    /// Create a new `GemBuilder` object.
+    pub fn new() -> GemBuilder {
+        Default::default()
+    }
+
+    /// Specify the number of sides of the girdle (and of the table). By
+    /// default, the builder will use an octagonal girdle with 8 sides. At
+    /// least 3 sides are required to build a valid gem.
+    pub fn sides(mut self, sides: usize) -> Self {
+        self.sides = sides;
+        self
+    }
+
+    /// Specify the size of the table, as a proportion of the size of the
+    /// girdle. By default, the table ratio is 0.5. The table ratio must lie
+    /// between 0 and 1 (exclusive) to build a valid gem.
+    pub fn table(mut self, ratio: f32) -> Self {
+        self.table = ratio;
+        self
+    }
+
+    /// Specify the height of the table above the girdle. By default, the
+    /// crown height is 0.15. The crown height must be greater than zero to
+    /// build a valid gem.
+    pub fn crown_height(mut self, height: f32) -> Self {
+        self.crown_height = height;
+        self
+    }
+
+    /// Specify the depth of the culet below the girdle. By default, the
+    /// pavilion depth is 0.45. The pavilion depth must be greater than zero
+    /// to build a valid gem.
+    pub fn pavilion_depth(mut self, depth: f32) -> Self {
+        self.pavilion_depth = depth;
+        self
+    }
+
+    /// Build a new `Gem` object.
+    pub fn build<F>(self, display: &F) -> Result<Gem, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the shape vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// a `Gem` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        let vertices = self.vertices_with::<Selected>()?;
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.options.check_shading(Self::SHADINGS)?;
+
+        // Ensure the parameters produce valid gem geometry.
+        if self.sides < 3 {
+            return Err(ShapeCreationError::NotEnoughDivisionsInU);
+        }
+        if !(self.table > 0.0 && self.table < 1.0)
+            || self.crown_height <= 0.0
+            || !self.crown_height.is_finite()
+            || self.pavilion_depth <= 0.0
+            || !self.pavilion_depth.is_finite()
+        {
+            return Err(ShapeCreationError::InvalidDimensions);
+        }
+
+        // Build the girdle and the table, with the table rotated by half a
+        // step, followed by the culet.
+        let n = self.sides;
+        let angle = 2.0 * f32::consts::PI / n as f32;
+        let ring = |offset: f32, radius: f32, y: f32| {
+            (0..n)
+                .map(|x| {
+                    let a = (x as f32 + offset) * angle;
+                    Vector3::<f32>::new(a.cos() * radius, y, -a.sin() * radius)
+                })
+                .collect::<Vec<Vector3<f32>>>()
+        };
+        let girdle = ring(0.0, 0.5, 0.0);
+        let table = ring(0.5, 0.5 * self.table, self.crown_height);
+        let culet = Vector3::<f32>::new(0.0, -self.pavilion_depth, 0.0);
+
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<M>::new(self.matrix);
+
+        // Emit the table, followed by the bezel and star facets of the crown,
+        // followed by the facets of the pavilion.
+        let mut vertices = Vec::<Vertex>::with_capacity(self.num_vertices());
+        push_polygon(&mut vertices, &transform, &table);
+        for side in 0..n {
+            let next = (side + 1) % n;
+            let facets = [
+                [girdle[side], girdle[next], table[side]],
+                [table[side], girdle[next], table[next]],
+            ];
+            for facet in facets.iter() {
+                push_polygon(&mut vertices, &transform, facet);
+            }
+        }
+        for side in 0..n {
+            let next = (side + 1) % n;
+            let facet = [girdle[next], girdle[side], culet];
+            push_polygon(&mut vertices, &transform, &facet);
+        }
+
+        self.options.map_texcoords(&mut vertices);
+        Ok(vertices)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    pub fn num_vertices(&self) -> usize {
+        ((self.sides - 2) + (self.sides * 2) + self.sides) * 3 * self.options.copies()
+    }
+}
+
+impl HasOptions for GemBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for GemBuilder {
+    type Shape = Gem;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> Gem {
+        Gem { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for GemBuilder {}
+
+impl BuildAs for GemBuilder {}
+
+impl WithTangents for GemBuilder {}
+
+impl WithLightmap for GemBuilder {}
+
+impl WithOrientation for GemBuilder {}
+
+impl WithBackFaces for GemBuilder {}
+
+impl WithModifiers for GemBuilder {}
+
+impl WithTexcoords for GemBuilder {}
+
+impl WithColors for GemBuilder {}
+
+impl WithShading for GemBuilder {
+    /// Only flat shading is supported by the gem.
+    const SHADINGS: &'static [Shading] = &[Shading::Flat];
+}
+
+#[test]
+pub fn ensure_gem_is_watertight() {
+    for sides in 3..12 {
+        let vertices = GemBuilder::new()
+            .sides(sides)
+            .build_vertices()
+            .expect("Failed to build vertices");
+        let mut edges = std::collections::HashMap::<([u32; 3], [u32; 3]), usize>::new();
+        for chunk in vertices.chunks(3) {
+            for index in 0..3 {
+                let a = chunk[index].position.map(f32::to_bits);
+                let b = chunk[(index + 1) % 3].position.map(f32::to_bits);
+                *edges.entry((a.min(b), a.max(b))).or_insert(0) += 1;
+            }
+        }
+        for count in edges.values() {
+            assert_eq!(*count, 2);
+        }
+    }
+}
+
+#[test]
+pub fn ensure_gem_has_outward_faceted_normals() {
+    let vertices = GemBuilder::new()
+        .sides(12)
+        .table(0.6)
+        .crown_height(0.2)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let n = (v1 - v0).cross(v2 - v0).normalize();
+        for vertex in chunk {
+            assert_ulps_eq!(n, Vector3::<f32>::from(vertex.normal), epsilon = 0.0001);
+        }
+        assert!(n.dot(v0 + v1 + v2) > 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_default_gem_has_expected_dimensions() {
+    let vertices = GemBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        let [x, y, z] = vertex.position;
+        let radius = Vector2::<f32>::new(x, z).magnitude();
+        if y > 0.0 {
+            assert_eq!(y, 0.15);
+            assert_ulps_eq!(radius, 0.25);
+        } else if y < 0.0 {
+            assert_eq!(y, -0.45);
+            assert_eq!(radius, 0.0);
+        } else {
+            assert_ulps_eq!(radius, 0.5);
+        }
+        assert!(vertex.texcoord[0] >= -0.0001 && vertex.texcoord[0] <= 1.0001);
+        assert!(vertex.texcoord[1] >= -0.0001 && vertex.texcoord[1] <= 1.0001);
+    }
+}
+
+#[test]
+pub fn ensure_gem_with_invalid_parameters_fails_to_build() {
+    match GemBuilder::new().sides(2).build_vertices() {
+        Err(ShapeCreationError::NotEnoughDivisionsInU) => (),
+        _ => panic!("Expected NotEnoughDivisionsInU"),
+    }
+    let builders = [
+        GemBuilder::new().table(0.0),
+        GemBuilder::new().table(1.0),
+        GemBuilder::new().crown_height(0.0),
+        GemBuilder::new().pavilion_depth(-1.0),
+    ];
+    for builder in builders.iter() {
+        match builder.build_vertices() {
+            Err(ShapeCreationError::InvalidDimensions) => (),
+            _ => panic!("Expected InvalidDimensions"),
+        }
+    }
+}
+
+#[test]
+pub fn ensure_gem_applies_late_pipeline() {
+    use crate::options::WithTexcoords;
+    use crate::uv_mapping::UvMapping;
+
+    crate::options::ensure_late_pipeline(
+        || GemBuilder::new().uv_mapping(UvMapping::Box).double_sided(),
+        GemBuilder::build_vertices,
+    );
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_gem() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = GemBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
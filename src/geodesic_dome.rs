@@ -0,0 +1,520 @@
+//! A module for constructing geodesic dome shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::errors::ShapeCreationError;
+use crate::facet::flatten_normals;
+use crate::icosphere::{spherical_texcoords, subdivided_icosahedron, MAX_ICOSPHERE_SUBDIVISIONS};
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{
+    BuildAs, HasOptions, HasVertices, ShapeOptions, WithBackFaces, WithCaps, WithColors,
+    WithLightmap, WithModifiers, WithOrientation, WithShading, WithTangents, WithTexcoords,
+    WithTransform,
+};
+use crate::shading::Shading;
+use crate::vertex::Vertex;
+
+use std::collections::HashMap;
+use std::f32;
+
+/// A polygonal `GeodesicDome` object.
+///
+/// This object is constructed using a `GeodesicDomeBuilder` object.
+pub struct GeodesicDome {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows a `GeodesicDome` object to be passed as a source of vertices.
+impl<'a> From<&'a GeodesicDome> for glium::vertex::VerticesSource<'a> {
+    fn from(dome: &'a GeodesicDome) -> glium::vertex::VerticesSource<'a> {
+        (&dome.vertices).into()
+    }
+}
+
+/// Allows a `GeodesicDome` object to be passed as a source of indices.
+impl<'a> From<&'a GeodesicDome> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a GeodesicDome) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::TrianglesList,
+        }
+    }
+}
+
+/// A list of triangles, each of which is a triple of points.
+type Triangles = Vec<[Vector3<f32>; 3]>;
+
+/// The length of an edge of an icosahedron inscribed in the unit-sphere.
+const ICOSAHEDRON_EDGE: f32 = 1.051_462_2;
+
+/// The proportion of the length of an edge within which vertices are snapped
+/// onto the rim of the dome.
+const SNAP_DISTANCE: f32 = 0.3;
+
+/// Responsible for building and returning a `GeodesicDome` object.
+///
+/// A geodesic dome is built by subdividing an icosphere (see `IcoSphereBuilder`),
+/// and clipping it at a latitude, keeping the part above the clipping plane. By
+/// default, the dome is built from a unit-sphere (e.g. a radius of 1) with its
+/// centre located at the origin, subdivided twice, and clipped at the equator,
+/// such that the rim of the dome lies on the xz-plane. The dome is closed with
+/// a flat base cap. This can be overriden using the transformation methods on
+/// this object.
+///
+/// The rim of the dome lies exactly on the clipping plane. Vertices that lie
+/// close to the clipping plane are first snapped onto the rim (along their
+/// meridian), such that clipping does not produce slivers, and the remaining
+/// edges that cross the clipping plane are split where they meet the rim.
+///
+/// The resultant geometry is constructed to suit OpenGL defaults - assuming
+/// a right-handed coordinate system, front-facing polygons are defined in
+/// counter-clock-wise order. Vertex normals on the dome are equal to the
+/// normalised vertex positions (such that the dome appears smooth when lit),
+/// whereas the normals of the base cap point down the y-axis. Vertex texture
+/// coordinates define a spherical-projection on the dome, in the same way as
+/// an `IcoSphere`, and a planar-projection on the base cap.
+pub struct GeodesicDomeBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    subdivisions: usize,
+    latitude: f32,
+    options: ShapeOptions,
+}
+
+impl Default for GeodesicDomeBuilder {
+    fn default() -> Self {
+        GeodesicDomeBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            subdivisions: 2,
+            latitude: 0.0,
+            options: ShapeOptions {
+                shading: Shading::Smooth,
+                ..ShapeOptions::default()
+            },
+        }
+    }
+}
+
+impl GeodesicDomeBuilder {
+    /// Create a new `GeodesicDomeBuilder` object.
+    pub fn new() -> GeodesicDomeBuilder {
+        Default::default()
+    }
+
+    /// Specify the number of times to subdivide each face of the icosahedron
+    /// before clipping. By default, the builder will use 2 subdivisions. At
+    /// most `MAX_ICOSPHERE_SUBDIVISIONS` subdivisions are supported.
+    pub fn subdivisions(mut self, subdivisions: usize) -> Self {
+        self.subdivisions = subdivisions;
+        self
+    }
+
+    /// Specify the latitude (in radians) at which the dome is clipped, where
+    /// zero is the equator, and positive latitudes are closer to the pole at
+    /// `(0, 1, 0)`. By default, the dome is clipped at the equator. The
+    /// latitude must lie strictly between the poles to build a valid dome.
+    pub fn latitude(mut self, radians: f32) -> Self {
+        self.latitude = radians;
+        self
+    }
+
+    /// Build a new `GeodesicDome` object.
+    pub fn build<F>(self, display: &F) -> Result<GeodesicDome, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the shape vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// a `GeodesicDome` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        let vertices = self.vertices_with::<Selected>()?;
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.options.check_shading(Self::SHADINGS)?;
+
+        let (dome, cap) = self.triangles()?;
+
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<M>::new(self.matrix);
+
+        // Emit the dome, followed by the base cap.
+        let mut vertices = Vec::<Vertex>::with_capacity((dome.len() + cap.len()) * 3);
+        let mut push = |point: Vector3<f32>, normal: Vector3<f32>, texcoord: [f32; 2]| {
+            vertices.push(Vertex {
+                position: transform.point(point),
+                normal: transform.normal(normal),
+                texcoord,
+            });
+        };
+        for triangle in &dome {
+            let texcoords = spherical_texcoords(triangle);
+            for (point, texcoord) in triangle.iter().zip(texcoords.iter()) {
+                push(*point, *point, *texcoord);
+            }
+        }
+        let rim_radius = self.latitude.cos();
+        for triangle in &cap {
+            for point in triangle.iter() {
+                let texcoord = [
+                    0.5 + point.x / (2.0 * rim_radius),
+                    0.5 + point.z / (2.0 * rim_radius),
+                ];
+                push(*point, -Vector3::<f32>::unit_y(), texcoord);
+            }
+        }
+
+        if self.options.shading == Shading::Flat {
+            flatten_normals(&mut vertices);
+        }
+
+        self.options.map_texcoords(&mut vertices);
+        Ok(vertices)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    ///
+    /// As the number of triangles that are clipped depends on the latitude,
+    /// this requires the dome to be clipped, and returns 0 if the dome would
+    /// fail to build.
+    pub fn num_vertices(&self) -> usize {
+        self.triangles()
+            .map(|(dome, cap)| (dome.len() + cap.len()) * 3)
+            .unwrap_or(0)
+            * self.options.copies()
+    }
+
+    /// Clip the icosphere, returning the triangles of the dome, followed by
+    /// the triangles of the base cap (if enabled).
+    fn triangles(&self) -> Result<(Triangles, Triangles), ShapeCreationError> {
+        // Ensure we don't accidentally allocate an enormous amount of geometry,
+        // and that the rim lies between the poles.
+        if self.subdivisions > MAX_ICOSPHERE_SUBDIVISIONS {
+            return Err(ShapeCreationError::TooManySubdivisions);
+        }
+        if !self.latitude.is_finite() || self.latitude.abs() >= f32::consts::FRAC_PI_2 {
+            return Err(ShapeCreationError::InvalidDimensions);
+        }
+        let height = self.latitude.sin();
+        let rim_radius = self.latitude.cos();
+        let rim_angle = f32::consts::FRAC_PI_2 - self.latitude;
+
+        // Project a point onto the rim, along its meridian.
+        let rim = |p: Vector3<f32>| {
+            let radius = (p.x * p.x + p.z * p.z).sqrt();
+            if radius > 0.0 {
+                Vector3::<f32>::new(p.x / radius * rim_radius, height, p.z / radius * rim_radius)
+            } else {
+                p
+            }
+        };
+
+        // Snap the vertices that lie close to the rim onto it. The snapping
+        // distance is measured along the sphere, in proportion to the length
+        // of the edges at this level of subdivision.
+        let threshold = SNAP_DISTANCE * ICOSAHEDRON_EDGE / (1 << self.subdivisions) as f32;
+        let snap = |p: Vector3<f32>| {
+            if (p.y.clamp(-1.0, 1.0).acos() - rim_angle).abs() < threshold {
+                rim(p)
+            } else {
+                p
+            }
+        };
+
+        // Split the edges that cross the rim where they meet it. The split is
+        // computed from the lower point of the edge, such that it is identical
+        // for both triangles that share the edge.
+        let split = |p: Vector3<f32>, q: Vector3<f32>| {
+            let (lower, upper) = if p.y < q.y { (p, q) } else { (q, p) };
+            let t = (height - lower.y) / (upper.y - lower.y);
+            rim(lower + (upper - lower) * t)
+        };
+        let side = |p: Vector3<f32>| {
+            if p.y > height {
+                1
+            } else if p.y < height {
+                -1
+            } else {
+                0
+            }
+        };
+
+        // Clip each triangle, keeping the part above the rim. This leaves
+        // either a triangle, or a quadrilateral that is split along its
+        // shorter diagonal.
+        let mut dome = Triangles::new();
+        for triangle in subdivided_icosahedron(self.subdivisions) {
+            let triangle = triangle.map(snap);
+            if triangle.iter().all(|&p| side(p) <= 0) {
+                continue;
+            }
+            let mut polygon = Vec::<Vector3<f32>>::with_capacity(4);
+            for index in 0..3 {
+                let (p, q) = (triangle[index], triangle[(index + 1) % 3]);
+                if side(p) >= 0 {
+                    polygon.push(p);
+                }
+                if side(p) * side(q) < 0 {
+                    polygon.push(split(p, q));
+                }
+            }
+            if polygon.len() == 3 {
+                dome.push([polygon[0], polygon[1], polygon[2]]);
+            } else if (polygon[2] - polygon[0]).magnitude2()
+                <= (polygon[3] - polygon[1]).magnitude2()
+            {
+                dome.push([polygon[0], polygon[1], polygon[2]]);
+                dome.push([polygon[0], polygon[2], polygon[3]]);
+            } else {
+                dome.push([polygon[0], polygon[1], polygon[3]]);
+                dome.push([polygon[1], polygon[2], polygon[3]]);
+            }
+        }
+
+        // Close the rim with a fan of triangles about its centre, one for each
+        // edge of the dome that is not shared with another triangle.
+        let mut cap = Triangles::new();
+        if self.options.caps.caps_start() {
+            let key = |p: Vector3<f32>| [p.x.to_bits(), p.y.to_bits(), p.z.to_bits()];
+            let mut edges = HashMap::<([u32; 3], [u32; 3]), usize>::new();
+            for triangle in &dome {
+                for index in 0..3 {
+                    let (a, b) = (key(triangle[index]), key(triangle[(index + 1) % 3]));
+                    *edges.entry((a.min(b), a.max(b))).or_insert(0) += 1;
+                }
+            }
+            let centre = Vector3::<f32>::new(0.0, height, 0.0);
+            for triangle in &dome {
+                for index in 0..3 {
+                    let (p, q) = (triangle[index], triangle[(index + 1) % 3]);
+                    let (a, b) = (key(p), key(q));
+                    if edges[&(a.min(b), a.max(b))] == 1 {
+                        cap.push([centre, q, p]);
+                    }
+                }
+            }
+        }
+        Ok((dome, cap))
+    }
+}
+
+impl HasOptions for GeodesicDomeBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for GeodesicDomeBuilder {
+    type Shape = GeodesicDome;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> GeodesicDome {
+        GeodesicDome { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for GeodesicDomeBuilder {}
+
+impl BuildAs for GeodesicDomeBuilder {}
+
+impl WithTangents for GeodesicDomeBuilder {}
+
+impl WithLightmap for GeodesicDomeBuilder {}
+
+impl WithOrientation for GeodesicDomeBuilder {}
+
+impl WithBackFaces for GeodesicDomeBuilder {}
+
+impl WithModifiers for GeodesicDomeBuilder {}
+
+/// The start of the dome is its rim, and its end is its top, which has no cap.
+impl WithCaps for GeodesicDomeBuilder {}
+
+impl WithTexcoords for GeodesicDomeBuilder {}
+
+impl WithColors for GeodesicDomeBuilder {}
+
+impl WithShading for GeodesicDomeBuilder {
+    /// By default, the geodesic dome is smooth shaded. When flat shaded, each
+    /// vertex normal points in the direction of its respective triangle
+    /// instead.
+    const SHADINGS: &'static [Shading] = &[Shading::Flat, Shading::Smooth];
+}
+
+#[cfg(test)]
+fn edge_counts(vertices: &[Vertex]) -> HashMap<([u32; 3], [u32; 3]), usize> {
+    let mut edges = HashMap::<([u32; 3], [u32; 3]), usize>::new();
+    for triangle in vertices.chunks(3) {
+        for index in 0..3 {
+            let a = triangle[index].position.map(f32::to_bits);
+            let b = triangle[(index + 1) % 3].position.map(f32::to_bits);
+            *edges.entry((a.min(b), a.max(b))).or_insert(0) += 1;
+        }
+    }
+    edges
+}
+
+#[test]
+pub fn ensure_geodesic_dome_lies_above_exact_rim() {
+    use crate::cap_mode::CapMode;
+    for &latitude in [-0.7f32, -0.2, 0.0, 0.3, 1.2].iter() {
+        for subdivisions in 0..5 {
+            let builder = GeodesicDomeBuilder::new()
+                .subdivisions(subdivisions)
+                .latitude(latitude)
+                .caps(CapMode::Neither);
+            let vertices = builder.build_vertices().expect("Failed to build vertices");
+            assert_eq!(vertices.len(), builder.num_vertices());
+            let height = latitude.sin();
+            for vertex in &vertices {
+                let pos = Vector3::<f32>::from(vertex.position);
+                assert!(pos.y >= height);
+                assert!(abs_diff_eq!(pos.magnitude(), 1.0, epsilon = 0.0001));
+                assert_ulps_eq!(Vector3::<f32>::from(vertex.normal), pos, epsilon = 0.0001);
+            }
+
+            // Every open edge of the dome lies on the rim.
+            for ((a, b), count) in edge_counts(&vertices) {
+                if count == 1 {
+                    assert_eq!(f32::from_bits(a[1]), height);
+                    assert_eq!(f32::from_bits(b[1]), height);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+pub fn ensure_geodesic_dome_with_base_cap_is_watertight() {
+    for &latitude in [-0.7f32, 0.0, 0.3, 1.2].iter() {
+        for subdivisions in 0..5 {
+            let vertices = GeodesicDomeBuilder::new()
+                .subdivisions(subdivisions)
+                .latitude(latitude)
+                .build_vertices()
+                .expect("Failed to build vertices");
+            for count in edge_counts(&vertices).values() {
+                assert_eq!(*count, 2);
+            }
+        }
+    }
+}
+
+#[test]
+pub fn ensure_geodesic_dome_has_no_slivers() {
+    use crate::cap_mode::CapMode;
+    // Snapping vertices onto the rim ensures that clipping never produces
+    // triangles that are much thinner than those of the icosphere.
+    for step in -13..=13 {
+        let latitude = step as f32 * 0.1;
+        for subdivisions in 0..5 {
+            let vertices = GeodesicDomeBuilder::new()
+                .subdivisions(subdivisions)
+                .latitude(latitude)
+                .caps(CapMode::Neither)
+                .build_vertices()
+                .expect("Failed to build vertices");
+            for chunk in vertices.chunks(3) {
+                let p = chunk
+                    .iter()
+                    .map(|v| Vector3::<f32>::from(v.position))
+                    .collect::<Vec<_>>();
+                for index in 0..3 {
+                    let e0 = p[(index + 1) % 3] - p[index];
+                    let e1 = p[(index + 2) % 3] - p[index];
+                    assert!(e0.angle(e1) > Deg(10.0).into());
+                }
+            }
+        }
+    }
+}
+
+#[test]
+pub fn ensure_geodesic_dome_has_outward_ccw_triangles() {
+    let vertices = GeodesicDomeBuilder::new()
+        .latitude(0.3)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let eyepos = v0 + Vector3::<f32>::from(chunk[0].normal);
+        let n = (v1 - v0).cross(v2 - v0);
+        assert!(n.dot(v0 - eyepos) < 0.0);
+        assert!(n.dot(v1 - eyepos) < 0.0);
+        assert!(n.dot(v2 - eyepos) < 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_geodesic_dome_with_invalid_parameters_fails_to_build() {
+    match GeodesicDomeBuilder::new()
+        .latitude(f32::consts::FRAC_PI_2)
+        .build_vertices()
+    {
+        Err(ShapeCreationError::InvalidDimensions) => (),
+        _ => panic!("Expected InvalidDimensions"),
+    }
+    match GeodesicDomeBuilder::new()
+        .subdivisions(MAX_ICOSPHERE_SUBDIVISIONS + 1)
+        .build_vertices()
+    {
+        Err(ShapeCreationError::TooManySubdivisions) => (),
+        _ => panic!("Expected TooManySubdivisions"),
+    }
+}
+
+#[test]
+pub fn ensure_geodesic_dome_applies_late_pipeline() {
+    use crate::options::WithTexcoords;
+    use crate::uv_mapping::UvMapping;
+
+    crate::options::ensure_late_pipeline(
+        || {
+            GeodesicDomeBuilder::new()
+                .uv_mapping(UvMapping::Box)
+                .double_sided()
+        },
+        GeodesicDomeBuilder::build_vertices,
+    );
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_geodesic_dome() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = GeodesicDomeBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
@@ -0,0 +1,382 @@
+//! A module for constructing grid line shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::errors::ShapeCreationError;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{HasOptions, HasVertices, ShapeOptions, WithColors, WithTransform};
+use crate::vertex::Vertex;
+
+/// A set of `GridLines` lines forming a ground grid.
+///
+/// This object is constructed using a `GridLinesBuilder` object.
+pub struct GridLines {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows a `GridLines` object to be passed as a source of vertices.
+impl<'a> From<&'a GridLines> for glium::vertex::VerticesSource<'a> {
+    fn from(grid: &'a GridLines) -> glium::vertex::VerticesSource<'a> {
+        (&grid.vertices).into()
+    }
+}
+
+/// Allows a `GridLines` object to be passed as a source of indices.
+impl<'a> From<&'a GridLines> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a GridLines) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::LinesList,
+        }
+    }
+}
+
+/// Responsible for building and returning a `GridLines` object.
+///
+/// By default, the grid is defined as a unit-square in the xz-plane divided
+/// into 10x10 cells, with a spacing of 0.1 between lines, and with its centre
+/// located at the origin. This can be overriden using the cell, spacing and
+/// transformation methods on this object.
+///
+/// The two centre lines of the grid (along the x-axis and z-axis) may be
+/// excluded from the grid using the `axes` method, and built as a separate
+/// `GridLines` object using the `build_axes` method, such that they may be
+/// drawn in a different colour. The centre lines span the whole grid, and are
+/// present even when the grid has an odd number of cells along an axis.
+///
+/// The resultant geometry is constructed to suit OpenGL defaults - assuming
+/// a right-handed coordinate system. Each line is defined by a pair of
+/// vertices, and lines parallel to the z-axis are emitted before lines
+/// parallel to the x-axis. Vertex normals point along the positive y-axis.
+/// Vertex texture coordinates are measured in cells, with the u coordinate
+/// increasing along the positive x-axis and the v coordinate increasing along
+/// the negative z-axis, such that cell boundaries lie on whole numbers.
+pub struct GridLinesBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    options: ShapeOptions,
+    cells_x: usize,
+    cells_z: usize,
+    spacing: f32,
+    axes: bool,
+}
+
+impl Default for GridLinesBuilder {
+    fn default() -> GridLinesBuilder {
+        GridLinesBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            options: ShapeOptions::default(),
+            cells_x: 10,
+            cells_z: 10,
+            spacing: 0.1,
+            axes: true,
+        }
+    }
+}
+
+impl GridLinesBuilder {
+    /// Create a new `GridLinesBuilder` object.
+    pub fn new() -> GridLinesBuilder {
+        Default::default()
+    }
+
+    /// Specify the number of cells along the x-axis. By default, the grid has
+    /// 10 cells along each axis. At least 1 cell is required to build a valid
+    /// grid.
+    pub fn cells_x(mut self, cells: usize) -> Self {
+        self.cells_x = cells;
+        self
+    }
+
+    /// Specify the number of cells along the z-axis. By default, the grid has
+    /// 10 cells along each axis. At least 1 cell is required to build a valid
+    /// grid.
+    pub fn cells_z(mut self, cells: usize) -> Self {
+        self.cells_z = cells;
+        self
+    }
+
+    /// Specify the spacing between neighbouring grid lines. By default, the
+    /// spacing is 0.1. The spacing must be greater than zero to build a valid
+    /// grid.
+    pub fn spacing(mut self, spacing: f32) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    /// Specify whether the grid includes any lines that lie along the centre
+    /// axes. By default, the centre lines are included. Disable them to draw
+    /// the centre lines separately using the `build_axes` method.
+    pub fn axes(mut self, enabled: bool) -> Self {
+        self.axes = enabled;
+        self
+    }
+
+    /// Build a new `GridLines` object.
+    pub fn build<F>(self, display: &F) -> Result<GridLines, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build a new `GridLines` object containing only the two centre lines of
+    /// the grid, along the x-axis and z-axis.
+    pub fn build_axes<F>(self, display: &F) -> Result<GridLines, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        let vertices =
+            glium::vertex::VertexBuffer::<Vertex>::new(display, &self.build_axes_vertices()?)?;
+
+        Ok(GridLines {
+            vertices: glium::vertex::VertexBufferAny::from(vertices),
+        })
+    }
+
+    /// Build the grid vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// a `GridLines` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.vertices_with::<Selected>()
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.validate()?;
+
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<M>::new(self.matrix);
+
+        let (cx, cz) = (self.cells_x as f32, self.cells_z as f32);
+        let mut vertices = Vec::<Vertex>::with_capacity(self.num_vertices());
+        for x in 0..=self.cells_x {
+            if self.axes || x * 2 != self.cells_x {
+                self.push_line(&mut vertices, &transform, [x as f32, 0.0], [x as f32, cz]);
+            }
+        }
+        for z in 0..=self.cells_z {
+            if self.axes || z * 2 != self.cells_z {
+                self.push_line(&mut vertices, &transform, [0.0, z as f32], [cx, z as f32]);
+            }
+        }
+
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Build the vertices of the two centre lines of the grid and return them
+    /// in a vector. The line parallel to the z-axis is emitted first.
+    pub fn build_axes_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.validate()?;
+
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<Selected>::new(self.matrix);
+
+        let (cx, cz) = (self.cells_x as f32, self.cells_z as f32);
+        let mut vertices = Vec::<Vertex>::with_capacity(4);
+        self.push_line(&mut vertices, &transform, [cx * 0.5, 0.0], [cx * 0.5, cz]);
+        self.push_line(&mut vertices, &transform, [0.0, cz * 0.5], [cx, cz * 0.5]);
+        Ok(vertices)
+    }
+
+    /// Returns the total number of vertices that will be generated by the
+    /// `build_vertices` method.
+    pub fn num_vertices(&self) -> usize {
+        let lines = |cells: usize| (0..=cells).filter(|&x| self.axes || x * 2 != cells).count();
+        (lines(self.cells_x) + lines(self.cells_z)) * 2
+    }
+
+    /// Ensure the parameters produce a valid grid.
+    fn validate(&self) -> Result<(), ShapeCreationError> {
+        if self.cells_x < 1 {
+            return Err(ShapeCreationError::NotEnoughDivisionsInU);
+        }
+        if self.cells_z < 1 {
+            return Err(ShapeCreationError::NotEnoughDivisionsInV);
+        }
+        if self.spacing <= 0.0 {
+            return Err(ShapeCreationError::InvalidDimensions);
+        }
+        Ok(())
+    }
+
+    /// Emit a single line between the given texture coordinates, which are
+    /// measured in cells.
+    fn push_line<M: Math>(
+        &self,
+        vertices: &mut Vec<Vertex>,
+        transform: &Transformation<M>,
+        start: [f32; 2],
+        end: [f32; 2],
+    ) {
+        let normal = transform.normal(Vector3::<f32>::unit_y());
+
+        let (cx, cz) = (self.cells_x as f32, self.cells_z as f32);
+        for &texcoord in [start, end].iter() {
+            let position = Vector3::<f32>::new(
+                (texcoord[0] - cx * 0.5) * self.spacing,
+                0.0,
+                (cz * 0.5 - texcoord[1]) * self.spacing,
+            );
+            vertices.push(Vertex {
+                position: transform.point(position),
+                normal,
+                texcoord,
+            });
+        }
+    }
+}
+
+impl HasOptions for GridLinesBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for GridLinesBuilder {
+    type Shape = GridLines;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> GridLines {
+        GridLines { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for GridLinesBuilder {}
+
+impl WithColors for GridLinesBuilder {}
+
+#[test]
+pub fn ensure_default_grid_lines_have_unit_dimensions() {
+    let vertices = GridLinesBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    assert_eq!(vertices.len(), 11 * 2 * 2);
+    for vertex in &vertices {
+        let pos = Vector3::<f32>::from(vertex.position);
+        assert!(pos.x.abs() <= 0.5001 && pos.z.abs() <= 0.5001);
+        assert_eq!(pos.y, 0.0);
+        assert_eq!(
+            Vector3::<f32>::from(vertex.normal),
+            Vector3::<f32>::unit_y()
+        );
+    }
+}
+
+#[test]
+pub fn ensure_grid_lines_are_axis_aligned() {
+    let vertices = GridLinesBuilder::new()
+        .cells_x(3)
+        .cells_z(5)
+        .spacing(0.5)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let (along_z, along_x) = vertices.split_at(4 * 2);
+    for chunk in along_z.chunks(2) {
+        assert_eq!(chunk[0].position[0], chunk[1].position[0]);
+        assert!(abs_diff_eq!(
+            chunk[0].position[2] - chunk[1].position[2],
+            2.5
+        ));
+    }
+    for chunk in along_x.chunks(2) {
+        assert_eq!(chunk[0].position[2], chunk[1].position[2]);
+        assert!(abs_diff_eq!(
+            chunk[1].position[0] - chunk[0].position[0],
+            1.5
+        ));
+    }
+}
+
+#[test]
+pub fn ensure_grid_lines_texcoords_are_measured_in_cells() {
+    let vertices = GridLinesBuilder::new()
+        .cells_x(4)
+        .cells_z(2)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        let uv = vertex.texcoord;
+        assert_eq!(uv[0].fract(), 0.0);
+        assert_eq!(uv[1].fract(), 0.0);
+        assert!(uv[0] >= 0.0 && uv[0] <= 4.0);
+        assert!(uv[1] >= 0.0 && uv[1] <= 2.0);
+        assert!(abs_diff_eq!(vertex.position[0], (uv[0] - 2.0) * 0.1));
+        assert!(abs_diff_eq!(vertex.position[2], (1.0 - uv[1]) * 0.1));
+    }
+}
+
+#[test]
+pub fn ensure_grid_lines_can_exclude_centre_axes() {
+    let builder = GridLinesBuilder::new().cells_x(4).cells_z(3).axes(false);
+    let vertices = builder.build_vertices().expect("Failed to build vertices");
+    assert_eq!(vertices.len(), (4 + 4) * 2);
+    assert_eq!(vertices.len(), builder.num_vertices());
+    for chunk in vertices.chunks(2) {
+        assert!(chunk[0].position[0] != 0.0 || chunk[1].position[0] != 0.0);
+    }
+
+    let axes = builder
+        .build_axes_vertices()
+        .expect("Failed to build vertices");
+    assert_eq!(axes.len(), 4);
+    assert_eq!(axes[0].position, [0.0, 0.0, 0.15]);
+    assert_eq!(axes[1].position, [0.0, 0.0, -0.15]);
+    assert_eq!(axes[2].position, [-0.2, 0.0, 0.0]);
+    assert_eq!(axes[3].position, [0.2, 0.0, 0.0]);
+}
+
+#[test]
+pub fn ensure_grid_lines_with_invalid_parameters_fail_to_build() {
+    match GridLinesBuilder::new().cells_x(0).build_vertices() {
+        Err(ShapeCreationError::NotEnoughDivisionsInU) => (),
+        _ => panic!("Expected NotEnoughDivisionsInU"),
+    }
+    match GridLinesBuilder::new().cells_z(0).build_axes_vertices() {
+        Err(ShapeCreationError::NotEnoughDivisionsInV) => (),
+        _ => panic!("Expected NotEnoughDivisionsInV"),
+    }
+    match GridLinesBuilder::new().spacing(0.0).build_vertices() {
+        Err(ShapeCreationError::InvalidDimensions) => (),
+        _ => panic!("Expected InvalidDimensions"),
+    }
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_grid_lines() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = GridLinesBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
+
+#[test]
+pub fn ensure_colored_grid_lines_is_painted() {
+    crate::options::ensure_colors(GridLinesBuilder::new);
+}
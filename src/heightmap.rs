@@ -0,0 +1,382 @@
+//! A module for constructing heightmap (terrain) shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::errors::ShapeCreationError;
+use crate::facet::flatten_normals;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{
+    BuildAs, HasOptions, HasVertices, ShapeOptions, WithBackFaces, WithColors, WithLightmap,
+    WithModifiers, WithOrientation, WithShading, WithTangents, WithTexcoords, WithTransform,
+};
+use crate::shading::Shading;
+use crate::vertex::Vertex;
+
+/// A polygonal `Heightmap` object.
+///
+/// This object is constructed using a `HeightmapBuilder` object.
+pub struct Heightmap {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows a `Heightmap` object to be passed as a source of vertices.
+impl<'a> From<&'a Heightmap> for glium::vertex::VerticesSource<'a> {
+    fn from(heightmap: &'a Heightmap) -> glium::vertex::VerticesSource<'a> {
+        (&heightmap.vertices).into()
+    }
+}
+
+/// Allows a `Heightmap` object to be passed as a source of indices.
+impl<'a> From<&'a Heightmap> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a Heightmap) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::TrianglesList,
+        }
+    }
+}
+
+/// Responsible for building and returning a `Heightmap` object.
+///
+/// The heightmap is constructed as a grid of height samples spanning a unit
+/// square in the xz-plane, with its centre located at the origin. Each sample
+/// defines the y coordinate of its grid point. By default, the heightmap is a
+/// flat 2x2 grid of samples, such that it matches a unit `Plane`. This can be
+/// overriden using the transformation methods on this object (e.g. to scale
+/// the terrain after generation).
+///
+/// The resultant geometry is constructed to suit OpenGL defaults - assuming
+/// a right-handed coordinate system, front-facing polygons are defined in
+/// counter-clock-wise order when viewed from above. Vertex normals are computed
+/// from the central differences of neighbouring samples (such that the terrain
+/// appears smooth when lit). Vertex texture coordinates define a
+/// planar-projection across the whole grid, with the u coordinate increasing
+/// along the positive x-axis and the v coordinate increasing along the negative
+/// z-axis.
+pub struct HeightmapBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    width: usize,
+    depth: usize,
+    heights: Vec<f32>,
+    options: ShapeOptions,
+}
+
+impl Default for HeightmapBuilder {
+    fn default() -> Self {
+        HeightmapBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            width: 2,
+            depth: 2,
+            heights: vec![0.0; 4],
+            options: ShapeOptions {
+                shading: Shading::Smooth,
+                ..ShapeOptions::default()
+            },
+        }
+    }
+}
+
+impl HeightmapBuilder {
+    /// Create a new `HeightmapBuilder` object.
+    pub fn new() -> HeightmapBuilder {
+        Default::default()
+    }
+
+    /// Specify the grid of height samples, with `width` samples along the x
+    /// axis and `depth` samples along the z axis. Samples are stored in rows of
+    /// `width` samples, where the first sample of the first row lies at the
+    /// minimum x and maximum z coordinate of the grid (such that it has
+    /// texture coordinates of zero). At least 2 samples are required along each
+    /// axis, and the number of samples must equal `width * depth`.
+    pub fn heights(mut self, width: usize, depth: usize, heights: &[f32]) -> Self {
+        self.width = width;
+        self.depth = depth;
+        self.heights = heights.to_vec();
+        self
+    }
+
+    /// Build a new `Heightmap` object.
+    pub fn build<F>(self, display: &F) -> Result<Heightmap, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the shape vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// a `Heightmap` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        let vertices = self.vertices_with::<Selected>()?;
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.options.check_shading(Self::SHADINGS)?;
+
+        // Ensure there are enough samples to produce valid heightmap geometry.
+        if self.width < 2 {
+            return Err(ShapeCreationError::NotEnoughDivisionsInU);
+        }
+        if self.depth < 2 {
+            return Err(ShapeCreationError::NotEnoughDivisionsInV);
+        }
+        if self.heights.len() != self.width * self.depth {
+            return Err(ShapeCreationError::InvalidDimensions);
+        }
+
+        // Compute the position of each sample.
+        let (cells_x, cells_z) = (self.width - 1, self.depth - 1);
+        let uv = |x: usize, z: usize| [x as f32 / cells_x as f32, z as f32 / cells_z as f32];
+        let position = |x: usize, z: usize| {
+            let [u, v] = uv(x, z);
+            Vector3::<f32>::new(u - 0.5, self.heights[z * self.width + x], 0.5 - v)
+        };
+
+        // Compute the normal at each sample from the central differences of
+        // its neighbours, falling back to one-sided differences at the edges.
+        let normal = |x: usize, z: usize| {
+            let (x0, x1) = (x.saturating_sub(1), (x + 1).min(cells_x));
+            let (z0, z1) = (z.saturating_sub(1), (z + 1).min(cells_z));
+            let dx = position(x1, z) - position(x0, z);
+            let dz = position(x, z1) - position(x, z0);
+            Vector3::<f32>::new(-dx.y / dx.x, 1.0, -dz.y / dz.z)
+        };
+
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<M>::new(self.matrix);
+
+        // Build the vertices.
+        let mut vertices = Vec::<Vertex>::with_capacity(self.num_vertices());
+        let quad = [(0, 0), (1, 0), (1, 1), (0, 0), (1, 1), (0, 1)];
+
+        for z in 0..cells_z {
+            for x in 0..cells_x {
+                for &(dx, dz) in quad.iter() {
+                    let (x, z) = (x + dx, z + dz);
+                    vertices.push(Vertex {
+                        position: transform.point(position(x, z)),
+                        normal: transform.normal(normal(x, z)),
+                        texcoord: uv(x, z),
+                    });
+                }
+            }
+        }
+
+        if self.options.shading == Shading::Flat {
+            flatten_normals(&mut vertices);
+        }
+
+        self.options.map_texcoords(&mut vertices);
+        Ok(vertices)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    pub fn num_vertices(&self) -> usize {
+        self.width.saturating_sub(1) * self.depth.saturating_sub(1) * 6 * self.options.copies()
+    }
+}
+
+impl HasOptions for HeightmapBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for HeightmapBuilder {
+    type Shape = Heightmap;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> Heightmap {
+        Heightmap { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for HeightmapBuilder {}
+
+impl BuildAs for HeightmapBuilder {}
+
+impl WithTangents for HeightmapBuilder {}
+
+impl WithLightmap for HeightmapBuilder {}
+
+impl WithOrientation for HeightmapBuilder {}
+
+impl WithBackFaces for HeightmapBuilder {}
+
+impl WithModifiers for HeightmapBuilder {}
+
+impl WithTexcoords for HeightmapBuilder {}
+
+impl WithColors for HeightmapBuilder {}
+
+impl WithShading for HeightmapBuilder {
+    /// By default, the heightmap is smooth shaded. When flat shaded, each
+    /// vertex normal points in the direction of its respective triangle
+    /// instead.
+    const SHADINGS: &'static [Shading] = &[Shading::Flat, Shading::Smooth];
+}
+
+#[test]
+pub fn ensure_default_heightmap_matches_plane() {
+    use crate::plane::PlaneBuilder;
+    let heightmap = HeightmapBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let plane = PlaneBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    assert_eq!(heightmap.len(), plane.len());
+    for (a, b) in heightmap.iter().zip(plane.iter()) {
+        assert_eq!(a.position, b.position);
+        assert_eq!(a.normal, b.normal);
+        assert_eq!(a.texcoord, b.texcoord);
+    }
+}
+
+#[test]
+pub fn ensure_heightmap_vertices_take_sample_heights() {
+    let heights = [0.0, 1.0, 2.0, 3.0, 4.0, 5.0];
+    let vertices = HeightmapBuilder::new()
+        .heights(3, 2, &heights)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    assert_eq!(vertices.len(), 2 * 6);
+    for vertex in &vertices {
+        let x = (vertex.texcoord[0] * 2.0).round() as usize;
+        let z = vertex.texcoord[1].round() as usize;
+        assert_eq!(vertex.position[1], heights[z * 3 + x]);
+        assert!(abs_diff_eq!(vertex.position[0], vertex.texcoord[0] - 0.5));
+        assert!(abs_diff_eq!(vertex.position[2], 0.5 - vertex.texcoord[1]));
+    }
+}
+
+#[test]
+pub fn ensure_sloped_heightmap_has_uniform_normals() {
+    let heights = (0..16).map(|i| (i % 4) as f32 / 3.0).collect::<Vec<f32>>();
+    let vertices = HeightmapBuilder::new()
+        .heights(4, 4, &heights)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let expected = Vector3::<f32>::new(-1.0, 1.0, 0.0).normalize();
+    for vertex in &vertices {
+        assert_ulps_eq!(
+            Vector3::<f32>::from(vertex.normal),
+            expected,
+            epsilon = 0.0001
+        );
+    }
+}
+
+#[test]
+pub fn ensure_heightmap_has_ccw_triangles() {
+    let heights = (0..25)
+        .map(|i| ((i * 7) % 5) as f32 * 0.05)
+        .collect::<Vec<f32>>();
+    let vertices = HeightmapBuilder::new()
+        .heights(5, 5, &heights)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let n = (v1 - v0).cross(v2 - v0);
+        assert!(n.y > 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_heightmap_uvs_span_unit_range() {
+    use std::f32;
+    let vertices = HeightmapBuilder::new()
+        .heights(3, 4, &[0.0; 12])
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let mut min = Vector2::<f32>::new(f32::MAX, f32::MAX);
+    let mut max = -min;
+    for vertex in &vertices {
+        min.x = f32::min(min.x, vertex.texcoord[0]);
+        min.y = f32::min(min.y, vertex.texcoord[1]);
+        max.x = f32::max(max.x, vertex.texcoord[0]);
+        max.y = f32::max(max.y, vertex.texcoord[1]);
+    }
+    assert!(min == Vector2::<f32>::zero());
+    assert!(max == Vector2::<f32>::from_value(1.0));
+}
+
+#[test]
+pub fn ensure_heightmap_with_invalid_samples_fails_to_build() {
+    match HeightmapBuilder::new()
+        .heights(3, 3, &[0.0; 8])
+        .build_vertices()
+    {
+        Err(ShapeCreationError::InvalidDimensions) => (),
+        _ => panic!("Expected InvalidDimensions"),
+    }
+    match HeightmapBuilder::new()
+        .heights(1, 3, &[0.0; 3])
+        .build_vertices()
+    {
+        Err(ShapeCreationError::NotEnoughDivisionsInU) => (),
+        _ => panic!("Expected NotEnoughDivisionsInU"),
+    }
+    match HeightmapBuilder::new()
+        .heights(3, 1, &[0.0; 3])
+        .build_vertices()
+    {
+        Err(ShapeCreationError::NotEnoughDivisionsInV) => (),
+        _ => panic!("Expected NotEnoughDivisionsInV"),
+    }
+}
+
+#[test]
+pub fn ensure_heightmap_applies_late_pipeline() {
+    use crate::options::WithTexcoords;
+    use crate::uv_mapping::UvMapping;
+
+    crate::options::ensure_late_pipeline(
+        || {
+            HeightmapBuilder::new()
+                .uv_mapping(UvMapping::Box)
+                .double_sided()
+        },
+        HeightmapBuilder::build_vertices,
+    );
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_heightmap() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = HeightmapBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
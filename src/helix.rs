@@ -0,0 +1,440 @@
+//! A module for constructing helix shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::errors::ShapeCreationError;
+use crate::facet::flatten_normals;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{
+    BuildAs, HasOptions, HasVertices, ShapeOptions, WithBackFaces, WithColors, WithLightmap,
+    WithModifiers, WithOrientation, WithShading, WithTangents, WithTexcoords, WithTransform,
+};
+use crate::resolution::{Resolution, WithResolution};
+use crate::shading::Shading;
+use crate::sweep::Sweep;
+use crate::vertex::Vertex;
+
+use std::f32;
+
+/// A polygonal `Helix` object.
+///
+/// This object is constructed using a `HelixBuilder` object.
+pub struct Helix {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows a `Helix` object to be passed as a source of vertices.
+impl<'a> From<&'a Helix> for glium::vertex::VerticesSource<'a> {
+    fn from(helix: &'a Helix) -> glium::vertex::VerticesSource<'a> {
+        (&helix.vertices).into()
+    }
+}
+
+/// Allows a `Helix` object to be passed as a source of indices.
+impl<'a> From<&'a Helix> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a Helix) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::TrianglesList,
+        }
+    }
+}
+
+/// Responsible for building and returning a `Helix` object.
+///
+/// The helix is constructed by sweeping a circular cross-section along a
+/// helical path that winds counter-clock-wise about the y-axis (when viewed
+/// from above), rising from its start to its end. By default, the helix makes
+/// 3 turns with a coil radius (the distance from the y-axis to the centre of
+/// the tube) of 0.375 and a tube radius of 0.05, and its path rises from
+/// `y = -0.5` to `y = 0.5` such that it is centred on the origin. Both ends of
+/// the tube are closed with flat caps. This can be overriden using the
+/// transformation methods on this object.
+///
+/// The resultant geometry is constructed to suit OpenGL defaults - assuming
+/// a right-handed coordinate system, front-facing polygons are defined in
+/// counter-clock-wise order. Vertex normals on the side of the tube point
+/// radially outward from the centre of the tube (such that the side appears
+/// smooth when lit), whereas the normals of each cap point along the path
+/// (such that the caps appear faceted against the side). Vertex texture
+/// coordinates map the angle around the tube to the u coordinate, and the
+/// proportion of the arc-length along the path to the v coordinate. Caps
+/// define a planar-projection.
+pub struct HelixBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    coil_radius: f32,
+    tube_radius: f32,
+    turns: f32,
+    height: f32,
+    radial_segments: usize,
+    tube_segments: usize,
+    options: ShapeOptions,
+}
+
+impl Default for HelixBuilder {
+    fn default() -> Self {
+        HelixBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            coil_radius: 0.375,
+            tube_radius: 0.05,
+            turns: 3.0,
+            height: 1.0,
+            radial_segments: 32,
+            tube_segments: 12,
+            options: ShapeOptions {
+                shading: Shading::Smooth,
+                ..ShapeOptions::default()
+            },
+        }
+    }
+}
+
+impl HelixBuilder {
+    /// Create a new `HelixBuilder` object.
+    pub fn new() -> HelixBuilder {
+        Default::default()
+    }
+
+    /// Specify the radius of the coil (the distance from the y-axis to the
+    /// centre of the tube). By default, the radius is 0.375. The radius must be
+    /// greater than zero to build a valid helix.
+    pub fn coil_radius(mut self, radius: f32) -> Self {
+        self.coil_radius = radius;
+        self
+    }
+
+    /// Specify the radius of the tube. By default, the radius is 0.05. The
+    /// radius must be greater than zero to build a valid helix.
+    pub fn tube_radius(mut self, radius: f32) -> Self {
+        self.tube_radius = radius;
+        self
+    }
+
+    /// Specify the number of turns the helix makes about the y-axis, which
+    /// need not be a whole number. By default, the helix makes 3 turns. The
+    /// number of turns must be greater than zero to build a valid helix.
+    pub fn turns(mut self, turns: f32) -> Self {
+        self.turns = turns;
+        self
+    }
+
+    /// Specify the height that the helix rises from its start to its end. By
+    /// default, the height is 1. The height must not be negative.
+    pub fn height(mut self, height: f32) -> Self {
+        self.height = height;
+        self
+    }
+
+    /// Specify the number of segments along the path for each turn of the
+    /// helix. By default, the builder will use 32 segments per turn. At least
+    /// 3 segments are required to build a valid helix.
+    pub fn radial_segments(mut self, segments: usize) -> Self {
+        self.radial_segments = segments;
+        self
+    }
+
+    /// Specify the number of radial segments around the tube. By default,
+    /// the builder will use 12 segments. At least 3 segments are required to
+    /// build a valid helix.
+    pub fn tube_segments(mut self, segments: usize) -> Self {
+        self.tube_segments = segments;
+        self
+    }
+
+    /// Build a new `Helix` object.
+    pub fn build<F>(self, display: &F) -> Result<Helix, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the shape vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// a `Helix` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        let vertices = self.vertices_with::<Selected>()?;
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.options.check_shading(Self::SHADINGS)?;
+
+        // Ensure the helix is large enough to produce valid geometry.
+        if self.coil_radius <= 0.0 || self.tube_radius <= 0.0 {
+            return Err(ShapeCreationError::InvalidRadii);
+        }
+        if self.turns <= 0.0 || self.height < 0.0 {
+            return Err(ShapeCreationError::InvalidDimensions);
+        }
+        if self.radial_segments < 3 {
+            return Err(ShapeCreationError::NotEnoughDivisionsInV);
+        }
+
+        let path = self.path();
+        let sweep = self.sweep(&path);
+        sweep.validate()?;
+
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<M>::new(self.matrix);
+
+        let mut vertices = Vec::<Vertex>::with_capacity(self.num_vertices());
+        sweep.push(&mut vertices, &transform);
+
+        if self.options.shading == Shading::Flat {
+            flatten_normals(&mut vertices);
+        }
+
+        self.options.map_texcoords(&mut vertices);
+        Ok(vertices)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    pub fn num_vertices(&self) -> usize {
+        ((self.num_path_segments() * self.tube_segments * 6) + (self.tube_segments * 6))
+            * self.options.copies()
+    }
+
+    /// Returns the number of segments along the whole path of the helix.
+    fn num_path_segments(&self) -> usize {
+        ((self.turns * self.radial_segments as f32).ceil() as usize).max(1)
+    }
+
+    /// Returns the points along the path of the helix.
+    fn path(&self) -> Vec<Point3<f32>> {
+        let count = self.num_path_segments();
+        let sweep = 2.0 * f32::consts::PI * self.turns;
+        (0..=count)
+            .map(|x| {
+                let t = x as f32 / count as f32;
+                let (s, c) = (t * sweep).sin_cos();
+                Point3::<f32>::new(
+                    c * self.coil_radius,
+                    (t - 0.5) * self.height,
+                    -s * self.coil_radius,
+                )
+            })
+            .collect()
+    }
+
+    /// Returns a description of the circular cross-section swept along the path.
+    fn sweep<'a>(&self, path: &'a [Point3<f32>]) -> Sweep<'a> {
+        Sweep {
+            path,
+            closed: false,
+            radius: self.tube_radius,
+            segments: self.tube_segments,
+            caps: [true, true],
+        }
+    }
+}
+
+impl HasOptions for HelixBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for HelixBuilder {
+    type Shape = Helix;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> Helix {
+        Helix { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for HelixBuilder {}
+
+impl BuildAs for HelixBuilder {}
+
+impl WithTangents for HelixBuilder {}
+
+impl WithLightmap for HelixBuilder {}
+
+impl WithOrientation for HelixBuilder {}
+
+impl WithBackFaces for HelixBuilder {}
+
+impl WithModifiers for HelixBuilder {}
+
+impl WithTexcoords for HelixBuilder {}
+
+impl WithColors for HelixBuilder {}
+
+impl WithShading for HelixBuilder {
+    /// By default, the helix is smooth shaded. When flat shaded, each vertex
+    /// normal points in the direction of its respective triangle instead.
+    const SHADINGS: &'static [Shading] = &[Shading::Flat, Shading::Smooth];
+}
+
+impl WithResolution for HelixBuilder {
+    /// At least 3 segments around the tube, and 3 segments along the path for
+    /// each turn.
+    const MIN_RESOLUTION: Resolution = Resolution {
+        around: 3,
+        along: 3,
+    };
+
+    /// Specify the number of segments around the tube of the helix, and the
+    /// number of segments along its path for each turn.
+    fn resolution(mut self, resolution: Resolution) -> Self {
+        self.tube_segments = resolution.around as usize;
+        self.radial_segments = resolution.along as usize;
+        self
+    }
+}
+
+#[test]
+pub fn ensure_default_helix_is_watertight() {
+    use std::collections::HashMap;
+    let vertices = HelixBuilder::new()
+        .turns(1.5)
+        .tube_segments(6)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let key = |v: &Vertex| Vector3::<f32>::from(v.position).map(f32::to_bits);
+    let mut edges = HashMap::<_, usize>::new();
+    for chunk in vertices.chunks(3) {
+        for i in 0..3 {
+            let (a, b) = (key(&chunk[i]), key(&chunk[(i + 1) % 3]));
+            let edge = if (a.x, a.y, a.z) < (b.x, b.y, b.z) {
+                (a, b)
+            } else {
+                (b, a)
+            };
+            *edges.entry(edge).or_insert(0) += 1;
+        }
+    }
+    for count in edges.values() {
+        assert_eq!(*count, 2);
+    }
+}
+
+#[test]
+pub fn ensure_default_helix_has_ccw_triangles() {
+    let vertices = HelixBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let eyepos = v0 + Vector3::<f32>::from(chunk[0].normal);
+        let e0 = v1 - v0;
+        let e1 = v2 - v0;
+        let n = e0.cross(e1);
+        assert!(n.dot(v0 - eyepos) <= 0.0);
+        assert!(n.dot(v1 - eyepos) <= 0.0);
+        assert!(n.dot(v2 - eyepos) <= 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_default_helix_is_centred_vertically() {
+    let vertices = HelixBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let (min, max) = vertices
+        .iter()
+        .map(|v| v.position[1])
+        .fold((f32::MAX, f32::MIN), |(lo, hi), y| (lo.min(y), hi.max(y)));
+    assert!(abs_diff_eq!(min, -max, epsilon = 0.0001));
+    assert!(max > 0.5 && max < 0.55);
+    for vertex in &vertices {
+        let radius = Vector2::<f32>::new(vertex.position[0], vertex.position[2]).magnitude();
+        assert!(radius > 0.3249 && radius < 0.4251);
+    }
+}
+
+#[test]
+pub fn ensure_helix_has_expected_vertex_count() {
+    let builder = HelixBuilder::new()
+        .turns(2.5)
+        .radial_segments(10)
+        .tube_segments(5);
+    let vertices = builder.build_vertices().expect("Failed to build vertices");
+    assert_eq!(vertices.len(), (25 * 5 * 6) + (5 * 6));
+    assert_eq!(vertices.len(), builder.num_vertices());
+}
+
+#[test]
+pub fn ensure_helix_with_invalid_parameters_fails_to_build() {
+    match HelixBuilder::new().turns(0.0).build_vertices() {
+        Err(ShapeCreationError::InvalidDimensions) => (),
+        _ => panic!("Expected InvalidDimensions"),
+    }
+    match HelixBuilder::new().tube_radius(0.0).build_vertices() {
+        Err(ShapeCreationError::InvalidRadii) => (),
+        _ => panic!("Expected InvalidRadii"),
+    }
+    match HelixBuilder::new().radial_segments(2).build_vertices() {
+        Err(ShapeCreationError::NotEnoughDivisionsInV) => (),
+        _ => panic!("Expected NotEnoughDivisionsInV"),
+    }
+    match HelixBuilder::new().tube_segments(2).build_vertices() {
+        Err(ShapeCreationError::NotEnoughDivisionsInU) => (),
+        _ => panic!("Expected NotEnoughDivisionsInU"),
+    }
+}
+
+#[test]
+pub fn ensure_helix_applies_late_pipeline() {
+    use crate::options::WithTexcoords;
+    use crate::uv_mapping::UvMapping;
+
+    crate::options::ensure_late_pipeline(
+        || {
+            HelixBuilder::new()
+                .uv_mapping(UvMapping::Box)
+                .double_sided()
+        },
+        HelixBuilder::build_vertices,
+    );
+}
+
+#[test]
+pub fn ensure_helix_with_invalid_resolution_fails_to_build() {
+    crate::resolution::ensure_min_resolution(
+        HelixBuilder::new,
+        HelixBuilder::build_vertices,
+        ShapeCreationError::NotEnoughDivisionsInU,
+        Some(ShapeCreationError::NotEnoughDivisionsInV),
+    );
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_helix() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = HelixBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
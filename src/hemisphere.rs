@@ -0,0 +1,452 @@
+//! A module for constructing hemisphere (dome) shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::errors::ShapeCreationError;
+use crate::facet::flatten_normals;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{
+    BuildAs, HasOptions, HasVertices, ShapeOptions, WithBackFaces, WithCaps, WithColors,
+    WithLightmap, WithModifiers, WithOrientation, WithShading, WithTangents, WithTexcoords,
+    WithTransform,
+};
+use crate::resolution::{Resolution, WithResolution};
+use crate::shading::Shading;
+use crate::vertex::Vertex;
+
+use std::f32;
+
+/// A polygonal `Hemisphere` object.
+///
+/// This object is constructed using a `HemisphereBuilder` object.
+pub struct Hemisphere {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows a `Hemisphere` object to be passed as a source of vertices.
+impl<'a> From<&'a Hemisphere> for glium::vertex::VerticesSource<'a> {
+    fn from(hemisphere: &'a Hemisphere) -> glium::vertex::VerticesSource<'a> {
+        (&hemisphere.vertices).into()
+    }
+}
+
+/// Allows a `Hemisphere` object to be passed as a source of indices.
+impl<'a> From<&'a Hemisphere> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a Hemisphere) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::TrianglesList,
+        }
+    }
+}
+
+/// Responsible for building and returning a `Hemisphere` object.
+///
+/// By default, the hemisphere is defined as the top half of a unit-sphere
+/// (e.g. a radius of 1), closed with a flat base cap. The centre of the base
+/// is located at the origin, and the vertices around the edge of the base lie
+/// exactly on the xz-plane, such that the dome sits flush on a plane at `y = 0`.
+/// This can be overriden using the transformation methods on this object.
+///
+/// The resultant geometry is constructed to suit OpenGL defaults - assuming
+/// a right-handed coordinate system, front-facing polygons are defined in
+/// counter-clock-wise order. Vertex normals on the dome point radially outward
+/// (such that the dome appears smooth when lit), whereas the normals of the base
+/// cap point down the y-axis. Vertex texture coordinates define a
+/// spherical-projection on the dome, with the v coordinate increasing from 0 at
+/// the base to 1 at the pole, and a planar-projection on the base cap.
+pub struct HemisphereBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    segments: usize,
+    rings: usize,
+    options: ShapeOptions,
+}
+
+impl Default for HemisphereBuilder {
+    fn default() -> Self {
+        HemisphereBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            segments: 24,
+            rings: 6,
+            options: ShapeOptions {
+                shading: Shading::Smooth,
+                ..ShapeOptions::default()
+            },
+        }
+    }
+}
+
+impl HemisphereBuilder {
+    /// Create a new `HemisphereBuilder` object.
+    pub fn new() -> HemisphereBuilder {
+        Default::default()
+    }
+
+    /// Specify the number of radial segments around the hemisphere. By
+    /// default, the builder will use 24 segments. At least 3 segments are
+    /// required to build a valid hemisphere.
+    pub fn segments(mut self, segments: usize) -> Self {
+        self.segments = segments;
+        self
+    }
+
+    /// Specify the number of rings between the base and the pole of the
+    /// hemisphere. By default, the builder will use 6 rings. At least 1 ring
+    /// is required to build a valid hemisphere.
+    pub fn rings(mut self, rings: usize) -> Self {
+        self.rings = rings;
+        self
+    }
+
+    /// Build a new `Hemisphere` object.
+    pub fn build<F>(self, display: &F) -> Result<Hemisphere, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the shape vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// a `Hemisphere` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        let vertices = self.vertices_with::<Selected>()?;
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.options.check_shading(Self::SHADINGS)?;
+
+        // Ensure there are enough segments and rings to produce valid
+        // hemisphere geometry.
+        if self.segments < 3 {
+            return Err(ShapeCreationError::NotEnoughDivisionsInU);
+        }
+        if self.rings < 1 {
+            return Err(ShapeCreationError::NotEnoughDivisionsInV);
+        }
+
+        // Build lookup tables of (cos, sin) pairs around the hemisphere, and
+        // of (radius, height) pairs from the pole down to the base. The base
+        // ring is exact, such that it lies flush on the xz-plane.
+        let angle = 2.0 * f32::consts::PI / self.segments as f32;
+        let ring = (0..=self.segments)
+            .map(|x| {
+                let a = ((x % self.segments) as f32) * angle;
+                [a.cos(), a.sin()]
+            })
+            .collect::<Vec<[f32; 2]>>();
+        let profile = (0..=self.rings)
+            .map(|y| {
+                if y == self.rings {
+                    [1.0, 0.0]
+                } else {
+                    let a = f32::consts::FRAC_PI_2 * (y as f32 / self.rings as f32);
+                    [a.sin(), a.cos()]
+                }
+            })
+            .collect::<Vec<[f32; 2]>>();
+        let point = |[c, s]: [f32; 2], y: usize| {
+            let [r, h] = profile[y];
+            Vector3::<f32>::new(c * r, h, -s * r)
+        };
+        let u = |index: usize| index as f32 / self.segments as f32;
+        let v = |y: usize| 1.0 - y as f32 / self.rings as f32;
+
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<M>::new(self.matrix);
+
+        let mut vertices = Vec::<Vertex>::with_capacity(self.num_vertices());
+        let mut emit = |position: Vector3<f32>, normal: Vector3<f32>, texcoord: [f32; 2]| {
+            vertices.push(Vertex {
+                position: transform.point(position),
+                normal: transform.normal(normal),
+                texcoord,
+            });
+        };
+
+        // Emit the dome, from the pole down to the base. Angles increase
+        // counter-clock-wise when viewed from above, such that the u coordinate
+        // increases from left to right when viewed from outside. The band
+        // around the pole is reduced to a single triangle per segment, with the
+        // pole taking the u coordinate of the middle of the segment.
+        for y in 0..self.rings {
+            for seg in 0..self.segments {
+                let corners: &[(usize, usize)] = if y == 0 {
+                    &[(seg, 1), (seg + 1, 1)]
+                } else {
+                    &[
+                        (seg, y + 1),
+                        (seg + 1, y + 1),
+                        (seg + 1, y),
+                        (seg, y + 1),
+                        (seg + 1, y),
+                        (seg, y),
+                    ]
+                };
+                for &(index, y) in corners.iter() {
+                    let position = point(ring[index], y);
+                    emit(position, position, [u(index), v(y)]);
+                }
+                if y == 0 {
+                    let pole = Vector3::<f32>::new(0.0, 1.0, 0.0);
+                    emit(pole, pole, [(u(seg) + u(seg + 1)) * 0.5, 1.0]);
+                }
+            }
+        }
+
+        // Emit the base cap as a triangle fan, wound counter-clock-wise when
+        // viewed from below.
+        if self.options.caps.caps_start() {
+            let down = Vector3::<f32>::new(0.0, -1.0, 0.0);
+            for seg in 0..self.segments {
+                emit(Vector3::<f32>::zero(), down, [0.5, 0.5]);
+                for &index in [seg + 1, seg].iter() {
+                    let pos = point(ring[index], self.rings);
+                    emit(pos, down, [pos.x * 0.5 + 0.5, pos.z * 0.5 + 0.5]);
+                }
+            }
+        }
+
+        if self.options.shading == Shading::Flat {
+            flatten_normals(&mut vertices);
+        }
+
+        self.options.map_texcoords(&mut vertices);
+        Ok(vertices)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    pub fn num_vertices(&self) -> usize {
+        let cap = if self.options.caps.caps_start() {
+            self.segments * 3
+        } else {
+            0
+        };
+        ((self.segments * 3) + (self.segments * 6 * self.rings.saturating_sub(1)) + cap)
+            * self.options.copies()
+    }
+}
+
+impl HasOptions for HemisphereBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for HemisphereBuilder {
+    type Shape = Hemisphere;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> Hemisphere {
+        Hemisphere { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for HemisphereBuilder {}
+
+impl BuildAs for HemisphereBuilder {}
+
+impl WithTangents for HemisphereBuilder {}
+
+impl WithLightmap for HemisphereBuilder {}
+
+impl WithOrientation for HemisphereBuilder {}
+
+impl WithBackFaces for HemisphereBuilder {}
+
+impl WithModifiers for HemisphereBuilder {}
+
+/// The start of the hemisphere is its base, and its end is its pole, which
+/// has no cap.
+impl WithCaps for HemisphereBuilder {}
+
+impl WithTexcoords for HemisphereBuilder {}
+
+impl WithColors for HemisphereBuilder {}
+
+impl WithShading for HemisphereBuilder {
+    /// By default, the hemisphere is smooth shaded. When flat shaded, each
+    /// vertex normal points in the direction of its respective triangle
+    /// instead.
+    const SHADINGS: &'static [Shading] = &[Shading::Flat, Shading::Smooth];
+}
+
+impl WithResolution for HemisphereBuilder {
+    /// At least 3 segments around the hemisphere, and 1 ring between its base
+    /// and its pole.
+    const MIN_RESOLUTION: Resolution = Resolution {
+        around: 3,
+        along: 1,
+    };
+
+    /// Specify the number of segments around the hemisphere, and the number of
+    /// rings between its base and its pole.
+    fn resolution(mut self, resolution: Resolution) -> Self {
+        self.segments = resolution.around as usize;
+        self.rings = resolution.along as usize;
+        self
+    }
+}
+
+#[test]
+pub fn ensure_default_hemisphere_has_unit_radius() {
+    let vertices = HemisphereBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        let pos = Vector3::<f32>::from(vertex.position);
+        assert!(pos.y >= 0.0);
+        if vertex.normal[1] > -0.5 {
+            assert!(abs_diff_eq!(pos.magnitude(), 1.0, epsilon = 0.0001));
+        }
+    }
+}
+
+#[test]
+pub fn ensure_hemisphere_base_lies_exactly_on_plane() {
+    let builder = HemisphereBuilder::new().segments(7).rings(5);
+    let vertices = builder.build_vertices().expect("Failed to build vertices");
+    let mut base = 0;
+    for vertex in &vertices {
+        if vertex.position[1] < 0.0001 {
+            assert_eq!(vertex.position[1], 0.0);
+            base += 1;
+        }
+    }
+    // Each dome segment contributes 3 base vertices, and each cap segment 3.
+    assert_eq!(base, 7 * 3 + 7 * 3);
+}
+
+#[test]
+pub fn ensure_hemisphere_has_smooth_dome_and_flat_cap() {
+    let vertices = HemisphereBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let (dome, cap) = vertices.split_at(vertices.len() - 24 * 3);
+    for vertex in dome {
+        assert_ulps_eq!(
+            Vector3::<f32>::from(vertex.normal),
+            Vector3::<f32>::from(vertex.position),
+            epsilon = 0.0001
+        );
+    }
+    for vertex in cap {
+        assert_eq!(vertex.normal, [0.0, -1.0, 0.0]);
+    }
+}
+
+#[test]
+pub fn ensure_default_hemisphere_has_ccw_triangles() {
+    let vertices = HemisphereBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let eyepos = v0 + Vector3::<f32>::from(chunk[0].normal);
+        let e0 = v1 - v0;
+        let e1 = v2 - v0;
+        let n = e0.cross(e1);
+        assert!(n.dot(v0 - eyepos) <= 0.0);
+        assert!(n.dot(v1 - eyepos) <= 0.0);
+        assert!(n.dot(v2 - eyepos) <= 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_default_hemisphere_has_uvs_in_unit_range() {
+    let vertices = HemisphereBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        assert!(vertex.texcoord[0] >= 0.0 && vertex.texcoord[0] <= 1.0);
+        assert!(vertex.texcoord[1] >= 0.0 && vertex.texcoord[1] <= 1.0);
+    }
+}
+
+#[test]
+pub fn ensure_hemisphere_without_base_cap_is_open() {
+    use crate::cap_mode::CapMode;
+    let builder = HemisphereBuilder::new()
+        .segments(5)
+        .rings(3)
+        .caps(CapMode::Neither);
+    let vertices = builder.build_vertices().expect("Failed to build vertices");
+    assert_eq!(vertices.len(), 5 * 3 + 5 * 6 * 2);
+    assert!(vertices.iter().all(|vertex| vertex.normal[1] >= 0.0));
+}
+
+#[test]
+pub fn ensure_hemisphere_with_too_few_divisions_fails_to_build() {
+    match HemisphereBuilder::new().segments(2).build_vertices() {
+        Err(ShapeCreationError::NotEnoughDivisionsInU) => (),
+        _ => panic!("Expected NotEnoughDivisionsInU"),
+    }
+    match HemisphereBuilder::new().rings(0).build_vertices() {
+        Err(ShapeCreationError::NotEnoughDivisionsInV) => (),
+        _ => panic!("Expected NotEnoughDivisionsInV"),
+    }
+}
+
+#[test]
+pub fn ensure_hemisphere_applies_late_pipeline() {
+    use crate::options::WithTexcoords;
+    use crate::uv_mapping::UvMapping;
+
+    crate::options::ensure_late_pipeline(
+        || {
+            HemisphereBuilder::new()
+                .uv_mapping(UvMapping::Box)
+                .double_sided()
+        },
+        HemisphereBuilder::build_vertices,
+    );
+}
+
+#[test]
+pub fn ensure_hemisphere_with_invalid_resolution_fails_to_build() {
+    crate::resolution::ensure_min_resolution(
+        HemisphereBuilder::new,
+        HemisphereBuilder::build_vertices,
+        ShapeCreationError::NotEnoughDivisionsInU,
+        Some(ShapeCreationError::NotEnoughDivisionsInV),
+    );
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_hemisphere() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = HemisphereBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
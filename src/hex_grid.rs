@@ -0,0 +1,567 @@
+//! A module for constructing hexagonal grid shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::errors::ShapeCreationError;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{
+    BuildAs, HasOptions, HasVertices, ShapeOptions, WithBackFaces, WithColors, WithLightmap,
+    WithModifiers, WithOrientation, WithShading, WithTangents, WithTexcoords, WithTransform,
+};
+use crate::shading::Shading;
+use crate::vertex::Vertex;
+
+use std::ops::Range;
+
+/// A `HexGrid` object, made of hexagonal cells.
+///
+/// This object is constructed using a `HexGridBuilder` object.
+pub struct HexGrid {
+    vertices: glium::vertex::VertexBufferAny,
+    columns: usize,
+    rows: usize,
+    copies: usize,
+}
+
+impl HexGrid {
+    /// Returns the range of vertices that define the front faces of the cell
+    /// at the given column and row, such that individual cells may be drawn
+    /// (or skipped) with separate draw calls. When the grid is double-sided or
+    /// repeated by its modifiers, the range lies within the first copy of the
+    /// grid, and the ranges of every copy are returned by `cell_ranges`.
+    ///
+    /// Panics if the cell lies outside of the grid.
+    pub fn cell_range(&self, column: usize, row: usize) -> Range<usize> {
+        self.cell_ranges(column, row).next().unwrap()
+    }
+
+    /// Returns the ranges of vertices that define the cell at the given column
+    /// and row in each copy of the grid, in the order in which the copies are
+    /// built. The front faces of the grid come first, followed by its back
+    /// faces when it is double-sided, and the copies made by its modifiers
+    /// repeat both in turn.
+    ///
+    /// Panics if the cell lies outside of the grid.
+    pub fn cell_ranges(&self, column: usize, row: usize) -> impl Iterator<Item = Range<usize>> {
+        cell_ranges(self.columns, self.rows, self.copies, column, row)
+    }
+}
+
+/// Returns the ranges of vertices that define a cell in each copy of a grid of
+/// the given size, whose copies each hold every cell of the grid in turn.
+fn cell_ranges(
+    columns: usize,
+    rows: usize,
+    copies: usize,
+    column: usize,
+    row: usize,
+) -> impl Iterator<Item = Range<usize>> {
+    assert!(column < columns && row < rows);
+    let stride = columns * rows * VERTICES_PER_CELL;
+    let start = (row * columns + column) * VERTICES_PER_CELL;
+    (0..copies).map(move |copy| {
+        let start = copy * stride + start;
+        start..(start + VERTICES_PER_CELL)
+    })
+}
+
+/// Allows a `HexGrid` object to be passed as a source of vertices.
+impl<'a> From<&'a HexGrid> for glium::vertex::VerticesSource<'a> {
+    fn from(grid: &'a HexGrid) -> glium::vertex::VerticesSource<'a> {
+        (&grid.vertices).into()
+    }
+}
+
+/// Allows a `HexGrid` object to be passed as a source of indices.
+impl<'a> From<&'a HexGrid> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a HexGrid) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::TrianglesList,
+        }
+    }
+}
+
+/// The orientation of the cells of a `HexGrid`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum HexOrientation {
+    /// Each cell has a corner pointing along the negative z-axis, and cells
+    /// in odd rows are shifted along the positive x-axis by half a cell.
+    PointyTop,
+    /// Each cell has an edge facing the negative z-axis, and cells in odd
+    /// columns are shifted along the negative z-axis by half a cell.
+    FlatTop,
+}
+
+/// The number of vertices in each cell, which is a fan of six triangles.
+const VERTICES_PER_CELL: usize = 6 * 3;
+
+/// Responsible for building and returning a `HexGrid` object.
+///
+/// The grid is constructed as a plane of hexagonal cells in the xz-plane,
+/// facing the positive y-axis, with its centre located at the origin. Columns
+/// of cells run along the positive x-axis, and rows of cells run along the
+/// negative z-axis. By default, the grid is a single pointy-topped cell with a
+/// size (the distance from the centre of a cell to each of its corners) of
+/// 0.5. This can be overriden using the grid and transformation methods on
+/// this object.
+///
+/// Each cell is emitted as a fan of six triangles about its centre, and cells
+/// are emitted row by row, such that each cell occupies a contiguous range of
+/// vertices, which is also returned by the `cell_range` and `cell_ranges`
+/// methods of the resultant `HexGrid` object. Neighbouring cells share identical vertex
+/// positions along their shared edges.
+///
+/// The resultant geometry is constructed to suit OpenGL defaults - assuming
+/// a right-handed coordinate system, front-facing polygons are defined in
+/// counter-clock-wise order. Vertex normals point in the direction of the
+/// grid. Vertex texture coordinates define a planar-projection on each cell,
+/// uniformly scaled and centred to fit the unit square, with the u coordinate
+/// increasing along the positive x-axis and the v coordinate increasing along
+/// the negative z-axis.
+pub struct HexGridBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    columns: usize,
+    rows: usize,
+    cell_size: f32,
+    orientation: HexOrientation,
+    options: ShapeOptions,
+}
+
+impl Default for HexGridBuilder {
+    fn default() -> Self {
+        HexGridBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            columns: 1,
+            rows: 1,
+            cell_size: 0.5,
+            orientation: HexOrientation::PointyTop,
+            options: ShapeOptions::default(),
+        }
+    }
+}
+
+impl HexGridBuilder {
+    /// Create a new `HexGridBuilder` object.
+    pub fn new() -> HexGridBuilder {
+        Default::default()
+    }
+
+    /// Specify the number of columns of cells along the x-axis. By default,
+    /// the builder will use 1 column. At least 1 column is required to build a
+    /// valid grid.
+    pub fn columns(mut self, columns: usize) -> Self {
+        self.columns = columns;
+        self
+    }
+
+    /// Specify the number of rows of cells along the z-axis. By default, the
+    /// builder will use 1 row. At least 1 row is required to build a valid
+    /// grid.
+    pub fn rows(mut self, rows: usize) -> Self {
+        self.rows = rows;
+        self
+    }
+
+    /// Specify the distance from the centre of each cell to each of its
+    /// corners. By default, the cell size is 0.5. The cell size must be
+    /// greater than zero to build a valid grid.
+    pub fn cell_size(mut self, size: f32) -> Self {
+        self.cell_size = size;
+        self
+    }
+
+    /// Specify the orientation of the cells. By default, the cells are
+    /// pointy-topped.
+    pub fn orientation(mut self, orientation: HexOrientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    /// Build a new `HexGrid` object.
+    pub fn build<F>(self, display: &F) -> Result<HexGrid, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the grid vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// a `HexGrid` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        let vertices = self.vertices_with::<Selected>()?;
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.options.check_shading(Self::SHADINGS)?;
+
+        // Ensure the parameters produce a valid grid.
+        if self.columns < 1 {
+            return Err(ShapeCreationError::NotEnoughDivisionsInU);
+        }
+        if self.rows < 1 {
+            return Err(ShapeCreationError::NotEnoughDivisionsInV);
+        }
+        if self.cell_size <= 0.0 || !self.cell_size.is_finite() {
+            return Err(ShapeCreationError::InvalidDimensions);
+        }
+
+        // Cells are laid out on an integer lattice, whose units along the x
+        // and z axes depend upon the orientation, such that neighbouring cells
+        // compute their shared corners from identical lattice coordinates.
+        // Corners are listed counter-clock-wise when viewed from above.
+        let half_width = 0.75f32.sqrt() * self.cell_size;
+        let half_size = 0.5 * self.cell_size;
+        let pointy = self.orientation == HexOrientation::PointyTop;
+        let (units, corners) = if pointy {
+            (
+                (half_width, half_size),
+                [(1, -1), (0, -2), (-1, -1), (-1, 1), (0, 2), (1, 1)],
+            )
+        } else {
+            (
+                (half_size, half_width),
+                [(2, 0), (1, -1), (-1, -1), (-2, 0), (-1, 1), (1, 1)],
+            )
+        };
+        let centre = |column: i64, row: i64| {
+            if pointy {
+                (2 * column + (row & 1), -3 * row)
+            } else {
+                (3 * column, -(2 * row + (column & 1)))
+            }
+        };
+
+        // Find the extent of the lattice, such that the grid may be centred.
+        let cells = (0..self.rows as i64)
+            .flat_map(|row| (0..self.columns as i64).map(move |column| centre(column, row)))
+            .collect::<Vec<(i64, i64)>>();
+        let mut min = (i64::MAX, i64::MAX);
+        let mut max = (i64::MIN, i64::MIN);
+        for &(x, z) in &cells {
+            for &(dx, dz) in corners.iter() {
+                min = (min.0.min(x + dx), min.1.min(z + dz));
+                max = (max.0.max(x + dx), max.1.max(z + dz));
+            }
+        }
+        let mid = ((min.0 + max.0) as f32 * 0.5, (min.1 + max.1) as f32 * 0.5);
+        let position = |(x, z): (i64, i64)| {
+            Vector3::<f32>::new(
+                (x as f32 - mid.0) * units.0,
+                0.0,
+                (z as f32 - mid.1) * units.1,
+            )
+        };
+
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<M>::new(self.matrix);
+        let normal = transform.normal(Vector3::<f32>::unit_y());
+
+        // Emit each cell as a fan of triangles about its centre.
+        let mut vertices = Vec::<Vertex>::with_capacity(self.num_vertices());
+        let scale = 0.5 / self.cell_size;
+        let offsets = std::iter::once((0, 0))
+            .chain(corners.iter().cloned())
+            .collect::<Vec<(i64, i64)>>();
+        for &(x, z) in &cells {
+            for side in 0..6 {
+                for &corner in [0, side + 1, (side + 1) % 6 + 1].iter() {
+                    let (dx, dz) = offsets[corner];
+                    let texcoord = [
+                        0.5 + dx as f32 * units.0 * scale,
+                        0.5 - dz as f32 * units.1 * scale,
+                    ];
+                    vertices.push(Vertex {
+                        position: transform.point(position((x + dx, z + dz))),
+                        normal,
+                        texcoord,
+                    });
+                }
+            }
+        }
+
+        self.options.map_texcoords(&mut vertices);
+        Ok(vertices)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    pub fn num_vertices(&self) -> usize {
+        self.columns * self.rows * VERTICES_PER_CELL * self.options.copies()
+    }
+}
+
+impl HasOptions for HexGridBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for HexGridBuilder {
+    type Shape = HexGrid;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> HexGrid {
+        HexGrid {
+            vertices,
+            columns: self.columns,
+            rows: self.rows,
+            copies: self.options.copies(),
+        }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for HexGridBuilder {}
+
+impl BuildAs for HexGridBuilder {}
+
+impl WithTangents for HexGridBuilder {}
+
+impl WithLightmap for HexGridBuilder {}
+
+impl WithOrientation for HexGridBuilder {}
+
+impl WithBackFaces for HexGridBuilder {}
+
+impl WithModifiers for HexGridBuilder {}
+
+impl WithTexcoords for HexGridBuilder {}
+
+impl WithColors for HexGridBuilder {}
+
+impl WithShading for HexGridBuilder {
+    /// By default, the hex grid is flat shaded. Both modes are supported, and
+    /// build the same normals, since the hex grid is planar.
+    const SHADINGS: &'static [Shading] = &[Shading::Flat, Shading::Smooth];
+}
+
+#[cfg(test)]
+fn cell_centre(vertices: &[Vertex], columns: usize, column: usize, row: usize) -> Vector3<f32> {
+    Vector3::from(vertices[(row * columns + column) * VERTICES_PER_CELL].position)
+}
+
+#[test]
+pub fn ensure_hex_grid_cells_are_laid_out_by_orientation() {
+    let s = 0.25f32;
+    let width = 3.0f32.sqrt() * s;
+    for &(orientation, column_step, row_step) in [
+        (
+            HexOrientation::PointyTop,
+            Vector3::new(width, 0.0, 0.0),
+            Vector3::new(width * 0.5, 0.0, -1.5 * s),
+        ),
+        (
+            HexOrientation::FlatTop,
+            Vector3::new(1.5 * s, 0.0, -width * 0.5),
+            Vector3::new(0.0, 0.0, -width),
+        ),
+    ]
+    .iter()
+    {
+        let vertices = HexGridBuilder::new()
+            .columns(3)
+            .rows(4)
+            .cell_size(s)
+            .orientation(orientation)
+            .build_vertices()
+            .expect("Failed to build vertices");
+        assert_eq!(vertices.len(), 3 * 4 * VERTICES_PER_CELL);
+        let origin = cell_centre(&vertices, 3, 0, 0);
+        assert_ulps_eq!(
+            cell_centre(&vertices, 3, 1, 0) - origin,
+            column_step,
+            epsilon = 0.0001
+        );
+        assert_ulps_eq!(
+            cell_centre(&vertices, 3, 0, 1) - origin,
+            row_step,
+            epsilon = 0.0001
+        );
+
+        // The grid is centred at the origin.
+        let mut min = Vector3::from_value(f32::MAX);
+        let mut max = Vector3::from_value(f32::MIN);
+        for vertex in &vertices {
+            let pos = Vector3::from(vertex.position);
+            min = Vector3::new(min.x.min(pos.x), min.y.min(pos.y), min.z.min(pos.z));
+            max = Vector3::new(max.x.max(pos.x), max.y.max(pos.y), max.z.max(pos.z));
+            assert_eq!(vertex.normal, [0.0, 1.0, 0.0]);
+        }
+        assert_ulps_eq!(min + max, Vector3::zero(), epsilon = 0.0001);
+    }
+}
+
+#[test]
+pub fn ensure_hex_grid_cells_share_exact_edges() {
+    for &orientation in [HexOrientation::PointyTop, HexOrientation::FlatTop].iter() {
+        let (columns, rows) = (4, 3);
+        let vertices = HexGridBuilder::new()
+            .columns(columns)
+            .rows(rows)
+            .orientation(orientation)
+            .build_vertices()
+            .expect("Failed to build vertices");
+
+        // Count the outer edges of each cell (those that do not touch its
+        // centre), which are shared exactly by neighbouring cells.
+        let mut edges = std::collections::HashMap::<([u32; 3], [u32; 3]), usize>::new();
+        for chunk in vertices.chunks(3) {
+            let a = chunk[1].position.map(f32::to_bits);
+            let b = chunk[2].position.map(f32::to_bits);
+            *edges.entry((a.min(b), a.max(b))).or_insert(0) += 1;
+        }
+        let shared = edges.values().filter(|&&count| count == 2).count();
+        assert!(edges.values().all(|&count| count <= 2));
+
+        // Neighbouring cells have centres that are one cell-width apart.
+        let mut neighbours = 0;
+        for a in 0..(columns * rows) {
+            for b in (a + 1)..(columns * rows) {
+                let pa = cell_centre(&vertices, columns, a % columns, a / columns);
+                let pb = cell_centre(&vertices, columns, b % columns, b / columns);
+                if (pa - pb).magnitude() < 0.9 {
+                    neighbours += 1;
+                }
+            }
+        }
+        assert_eq!(shared, neighbours);
+    }
+}
+
+#[test]
+pub fn ensure_hex_grid_cells_have_unit_uvs_and_ccw_triangles() {
+    let vertices = HexGridBuilder::new()
+        .columns(2)
+        .rows(2)
+        .cell_size(2.0)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for cell in vertices.chunks(VERTICES_PER_CELL) {
+        assert_eq!(cell[0].texcoord, [0.5, 0.5]);
+        for vertex in cell {
+            assert!(vertex.texcoord[0] >= 0.0 && vertex.texcoord[0] <= 1.0);
+            assert!(vertex.texcoord[1] >= 0.0 && vertex.texcoord[1] <= 1.0);
+        }
+        for chunk in cell.chunks(3) {
+            let v0 = Vector3::<f32>::from(chunk[0].position);
+            let v1 = Vector3::<f32>::from(chunk[1].position);
+            let v2 = Vector3::<f32>::from(chunk[2].position);
+            assert!((v1 - v0).cross(v2 - v0).y > 0.0);
+        }
+    }
+}
+
+#[test]
+pub fn ensure_hex_grid_with_invalid_parameters_fails_to_build() {
+    match HexGridBuilder::new().columns(0).build_vertices() {
+        Err(ShapeCreationError::NotEnoughDivisionsInU) => (),
+        _ => panic!("Expected NotEnoughDivisionsInU"),
+    }
+    match HexGridBuilder::new().rows(0).build_vertices() {
+        Err(ShapeCreationError::NotEnoughDivisionsInV) => (),
+        _ => panic!("Expected NotEnoughDivisionsInV"),
+    }
+    match HexGridBuilder::new().cell_size(0.0).build_vertices() {
+        Err(ShapeCreationError::InvalidDimensions) => (),
+        _ => panic!("Expected InvalidDimensions"),
+    }
+}
+
+#[test]
+pub fn ensure_hex_grid_applies_late_pipeline() {
+    use crate::options::WithTexcoords;
+    use crate::uv_mapping::UvMapping;
+
+    crate::options::ensure_late_pipeline(
+        || {
+            HexGridBuilder::new()
+                .uv_mapping(UvMapping::Box)
+                .double_sided()
+        },
+        HexGridBuilder::build_vertices,
+    );
+}
+
+#[test]
+pub fn ensure_double_sided_hex_grid_cells_span_each_copy() {
+    let builder = HexGridBuilder::new()
+        .columns(3)
+        .rows(2)
+        .double_sided()
+        .linear_array(2, [0.0, 1.0, 0.0]);
+    let front = HexGridBuilder::new()
+        .columns(3)
+        .rows(2)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let vertices = builder.build_vertices().expect("Failed to build vertices");
+    let copies = builder.options.copies();
+    assert_eq!(copies, 4);
+    for row in 0..2 {
+        for column in 0..3 {
+            let cell = cell_ranges(3, 2, 1, column, row).next().unwrap();
+            let ranges = cell_ranges(3, 2, copies, column, row).collect::<Vec<_>>();
+            assert_eq!(ranges.len(), copies);
+            assert_eq!(ranges[0], cell);
+            for (copy, range) in ranges.into_iter().enumerate() {
+                let back = copy % 2 == 1;
+                let offset = (copy / 2) as f32;
+                let mut positions = vertices[range.clone()]
+                    .iter()
+                    .map(|vertex| {
+                        let sign = if back { -1.0 } else { 1.0 };
+                        assert_eq!(vertex.normal, [0.0, sign, 0.0]);
+                        vertex.position.map(f32::to_bits)
+                    })
+                    .collect::<Vec<_>>();
+                let mut expected = front[cell.clone()]
+                    .iter()
+                    .map(|vertex| {
+                        let [x, y, z] = vertex.position;
+                        [x, y + offset, z].map(f32::to_bits)
+                    })
+                    .collect::<Vec<_>>();
+                positions.sort();
+                expected.sort();
+                assert_eq!(positions, expected);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_hex_grid() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = HexGridBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
@@ -0,0 +1,350 @@
+//! A module for constructing icosahedron shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::errors::ShapeCreationError;
+use crate::facet::push_polygon;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{
+    BuildAs, HasOptions, HasVertices, ShapeOptions, WithBackFaces, WithColors, WithLightmap,
+    WithModifiers, WithOrientation, WithShading, WithTangents, WithTexcoords, WithTransform,
+};
+use crate::shading::Shading;
+use crate::vertex::Vertex;
+
+/// A polygonal `Icosahedron` object.
+///
+/// This object is constructed using an `IcosahedronBuilder` object.
+pub struct Icosahedron {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows an `Icosahedron` object to be passed as a source of vertices.
+impl<'a> From<&'a Icosahedron> for glium::vertex::VerticesSource<'a> {
+    fn from(icosahedron: &'a Icosahedron) -> glium::vertex::VerticesSource<'a> {
+        (&icosahedron.vertices).into()
+    }
+}
+
+/// Allows an `Icosahedron` object to be passed as a source of indices.
+impl<'a> From<&'a Icosahedron> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a Icosahedron) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::TrianglesList,
+        }
+    }
+}
+
+/// Responsible for building and returning an `Icosahedron` object.
+///
+/// By default, the icosahedron is defined as a regular icosahedron inscribed
+/// in a unit-diameter sphere (e.g. each vertex lies at a distance of 0.5 from
+/// the origin), with its centre-of-mass located at the origin. This can be
+/// overriden using the transformation methods on this object.
+///
+/// The resultant geometry is constructed to suit OpenGL defaults - assuming
+/// a right-handed coordinate system, front-facing polygons are defined in
+/// counter-clock-wise order. Vertex normals point in the direction of their
+/// respective face (such that the shape appears faceted when lit). Vertex
+/// texture coordinates define a planar-projection on each face.
+pub struct IcosahedronBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    options: ShapeOptions,
+}
+
+impl Default for IcosahedronBuilder {
+    fn default() -> Self {
+        IcosahedronBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            options: ShapeOptions::default(),
+        }
+    }
+}
+
+impl IcosahedronBuilder {
+    /// Create a new `IcosahedronBuilder` object.
+    pub fn new() -> IcosahedronBuilder {
+        Default::default()
+    }
+
+    /// Build a new `Icosahedron` object.
+    pub fn build<F>(self, display: &F) -> Result<Icosahedron, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the shape vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// an `Icosahedron` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        let vertices = self.vertices_with::<Selected>()?;
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.options.check_shading(Self::SHADINGS)?;
+
+        // Define the vertices of the icosahedron, which lie at the corners of
+        // three mutually orthogonal golden rectangles. The coordinates are
+        // derived from the golden ratio once, such that the resultant shape is
+        // exactly symmetric about each axis.
+        let phi = (1.0 + 5.0f32.sqrt()) * 0.5;
+        let s = 0.5 / (1.0 + (phi * phi)).sqrt();
+        let t = phi * s;
+        let points = [
+            Vector3::<f32>::new(0.0, -s, -t),
+            Vector3::<f32>::new(-s, -t, 0.0),
+            Vector3::<f32>::new(-t, 0.0, -s),
+            Vector3::<f32>::new(0.0, -s, t),
+            Vector3::<f32>::new(-s, t, 0.0),
+            Vector3::<f32>::new(t, 0.0, -s),
+            Vector3::<f32>::new(0.0, s, -t),
+            Vector3::<f32>::new(s, -t, 0.0),
+            Vector3::<f32>::new(-t, 0.0, s),
+            Vector3::<f32>::new(0.0, s, t),
+            Vector3::<f32>::new(s, t, 0.0),
+            Vector3::<f32>::new(t, 0.0, s),
+        ];
+
+        // Define the faces of the icosahedron, wound counter-clock-wise when
+        // viewed from outside.
+        let faces = [
+            [2, 0, 1],
+            [1, 0, 7],
+            [6, 0, 2],
+            [5, 0, 6],
+            [7, 0, 5],
+            [2, 1, 8],
+            [3, 1, 7],
+            [8, 1, 3],
+            [6, 2, 4],
+            [4, 2, 8],
+            [11, 3, 7],
+            [8, 3, 9],
+            [9, 3, 11],
+            [6, 4, 10],
+            [9, 4, 8],
+            [10, 4, 9],
+            [10, 5, 6],
+            [7, 5, 11],
+            [11, 5, 10],
+            [10, 9, 11],
+        ];
+
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<M>::new(self.matrix);
+
+        // Emit each face.
+        let mut vertices = Vec::<Vertex>::with_capacity(self.num_vertices());
+        for face in faces.iter() {
+            let polygon = face
+                .iter()
+                .map(|&i| points[i])
+                .collect::<Vec<Vector3<f32>>>();
+            push_polygon(&mut vertices, &transform, &polygon);
+        }
+
+        self.options.map_texcoords(&mut vertices);
+        Ok(vertices)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    pub fn num_vertices(&self) -> usize {
+        20 * 3 * self.options.copies()
+    }
+}
+
+impl HasOptions for IcosahedronBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for IcosahedronBuilder {
+    type Shape = Icosahedron;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> Icosahedron {
+        Icosahedron { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for IcosahedronBuilder {}
+
+impl BuildAs for IcosahedronBuilder {}
+
+impl WithTangents for IcosahedronBuilder {}
+
+impl WithLightmap for IcosahedronBuilder {}
+
+impl WithOrientation for IcosahedronBuilder {}
+
+impl WithBackFaces for IcosahedronBuilder {}
+
+impl WithModifiers for IcosahedronBuilder {}
+
+impl WithTexcoords for IcosahedronBuilder {}
+
+impl WithColors for IcosahedronBuilder {}
+
+impl WithShading for IcosahedronBuilder {
+    /// Only flat shading is supported by the icosahedron.
+    const SHADINGS: &'static [Shading] = &[Shading::Flat];
+}
+
+#[test]
+pub fn ensure_default_icosahedron_has_centroid_at_origin() {
+    let vertices = IcosahedronBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let mut sum = Vector3::<f32>::zero();
+    for vertex in &vertices {
+        sum += Vector3::<f32>::from(vertex.position);
+    }
+    assert_ulps_eq!(sum, Vector3::<f32>::zero(), epsilon = 0.0001);
+}
+
+#[test]
+pub fn ensure_default_icosahedron_has_unit_outward_face_normals() {
+    let vertices = IcosahedronBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let centroid = (Vector3::<f32>::from(chunk[0].position)
+            + Vector3::<f32>::from(chunk[1].position)
+            + Vector3::<f32>::from(chunk[2].position))
+            / 3.0;
+        for vertex in chunk {
+            let normal = Vector3::<f32>::from(vertex.normal);
+            assert!(abs_diff_eq!(normal.magnitude(), 1.0, epsilon = 0.0001));
+            assert!(normal.dot(centroid) > 0.0);
+        }
+    }
+}
+
+#[test]
+pub fn ensure_default_icosahedron_has_ccw_triangles() {
+    let vertices = IcosahedronBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let eyepos = v0 + Vector3::<f32>::from(chunk[0].normal);
+        let e0 = v1 - v0;
+        let e1 = v2 - v0;
+        let n = e0.cross(e1);
+        assert!(n.dot(v0 - eyepos) <= 0.0);
+        assert!(n.dot(v1 - eyepos) <= 0.0);
+        assert!(n.dot(v2 - eyepos) <= 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_default_icosahedron_has_faceted_normals() {
+    let vertices = IcosahedronBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let n = (v1 - v0).cross(v2 - v0).normalize();
+        for vertex in chunk {
+            assert_ulps_eq!(n, Vector3::<f32>::from(vertex.normal), epsilon = 0.0001);
+        }
+    }
+}
+
+#[test]
+pub fn ensure_default_icosahedron_has_uvs_in_unit_range() {
+    let vertices = IcosahedronBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        assert!(vertex.texcoord[0] >= -0.0001 && vertex.texcoord[0] <= 1.0001);
+        assert!(vertex.texcoord[1] >= -0.0001 && vertex.texcoord[1] <= 1.0001);
+    }
+}
+
+#[test]
+pub fn ensure_default_icosahedron_is_inscribed_in_unit_diameter_sphere() {
+    let vertices = IcosahedronBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    assert_eq!(vertices.len(), 60);
+    for vertex in &vertices {
+        let pos = Vector3::<f32>::from(vertex.position);
+        assert!(abs_diff_eq!(pos.magnitude(), 0.5, epsilon = 0.0001));
+    }
+}
+
+#[test]
+pub fn ensure_icosahedron_is_symmetric_about_each_axis() {
+    let vertices = IcosahedronBuilder::new()
+        .scale(3.0, 5.0, 7.0)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        for axis in 0..3 {
+            let mut mirrored = vertex.position;
+            mirrored[axis] = -mirrored[axis];
+            assert!(vertices.iter().any(|v| v.position == mirrored));
+        }
+    }
+}
+
+#[test]
+pub fn ensure_icosahedron_applies_late_pipeline() {
+    use crate::options::WithTexcoords;
+    use crate::uv_mapping::UvMapping;
+
+    crate::options::ensure_late_pipeline(
+        || {
+            IcosahedronBuilder::new()
+                .uv_mapping(UvMapping::Box)
+                .double_sided()
+        },
+        IcosahedronBuilder::build_vertices,
+    );
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_icosahedron() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = IcosahedronBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
@@ -0,0 +1,456 @@
+//! A module for constructing icosphere shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::errors::ShapeCreationError;
+use crate::facet::flatten_normals;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{
+    BuildAs, HasOptions, HasVertices, ShapeOptions, WithBackFaces, WithColors, WithLightmap,
+    WithModifiers, WithOrientation, WithShading, WithTangents, WithTexcoords, WithTransform,
+};
+use crate::shading::Shading;
+use crate::vertex::Vertex;
+
+use std::f32;
+
+/// A polygonal `IcoSphere` object.
+///
+/// This object is constructed using an `IcoSphereBuilder` object.
+pub struct IcoSphere {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows an `IcoSphere` object to be passed as a source of vertices.
+impl<'a> From<&'a IcoSphere> for glium::vertex::VerticesSource<'a> {
+    fn from(sphere: &'a IcoSphere) -> glium::vertex::VerticesSource<'a> {
+        (&sphere.vertices).into()
+    }
+}
+
+/// Allows an `IcoSphere` object to be passed as a source of indices.
+impl<'a> From<&'a IcoSphere> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a IcoSphere) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::TrianglesList,
+        }
+    }
+}
+
+/// The maximum number of subdivisions supported by an `IcoSphereBuilder`.
+pub const MAX_ICOSPHERE_SUBDIVISIONS: usize = 7;
+
+/// Responsible for building and returning an `IcoSphere` object.
+///
+/// An icosphere is built by repeatedly subdividing the faces of an icosahedron,
+/// and re-projecting the resultant vertices onto the sphere, which distributes
+/// triangles much more evenly than a UV sphere. By default, the icosphere is
+/// defined as a unit-sphere (e.g. a radius of 1) with its centre-of-mass located
+/// at the origin, and with a vertex located at each pole. This can be overriden
+/// using the transformation methods on this object.
+///
+/// The resultant geometry is constructed to suit OpenGL defaults - assuming
+/// a right-handed coordinate system, front-facing polygons are defined in
+/// counter-clock-wise order. Vertex normals are equal to the normalised vertex
+/// positions (such that the shape appears smooth when lit). Vertex texture
+/// coordinates define a spherical-projection on the object. Triangles that
+/// cross the seam of the projection take a u coordinate greater than 1 on the
+/// far side of the seam (effectively duplicating the seam), such that textures
+/// sampled with a repeating wrap mode do not smear across the seam.
+pub struct IcoSphereBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    subdivisions: usize,
+    options: ShapeOptions,
+}
+
+impl Default for IcoSphereBuilder {
+    fn default() -> Self {
+        IcoSphereBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            subdivisions: 2,
+            options: ShapeOptions {
+                shading: Shading::Smooth,
+                ..ShapeOptions::default()
+            },
+        }
+    }
+}
+
+impl IcoSphereBuilder {
+    /// Create a new `IcoSphereBuilder` object.
+    pub fn new() -> IcoSphereBuilder {
+        Default::default()
+    }
+
+    /// Specify the number of times to subdivide each face of the icosahedron.
+    /// Each subdivision splits every triangle into four. A value of 0 yields
+    /// the raw icosahedron. By default, the builder will use 2 subdivisions.
+    /// At most `MAX_ICOSPHERE_SUBDIVISIONS` subdivisions are supported.
+    pub fn subdivisions(mut self, subdivisions: usize) -> Self {
+        self.subdivisions = subdivisions;
+        self
+    }
+
+    /// Build a new `IcoSphere` object.
+    pub fn build<F>(self, display: &F) -> Result<IcoSphere, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the shape vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// an `IcoSphere` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        let vertices = self.vertices_with::<Selected>()?;
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.options.check_shading(Self::SHADINGS)?;
+
+        // Ensure we don't accidentally allocate an enormous amount of geometry.
+        if self.subdivisions > MAX_ICOSPHERE_SUBDIVISIONS {
+            return Err(ShapeCreationError::TooManySubdivisions);
+        }
+
+        let triangles = subdivided_icosahedron(self.subdivisions);
+
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<M>::new(self.matrix);
+
+        // Emit the vertices.
+        let mut vertices = Vec::<Vertex>::with_capacity(self.num_vertices());
+        for triangle in &triangles {
+            let texcoords = spherical_texcoords(triangle);
+            for (point, texcoord) in triangle.iter().zip(texcoords.iter()) {
+                vertices.push(Vertex {
+                    position: transform.point(*point),
+                    normal: transform.normal(*point),
+                    texcoord: *texcoord,
+                });
+            }
+        }
+
+        if self.options.shading == Shading::Flat {
+            flatten_normals(&mut vertices);
+        }
+
+        self.options.map_texcoords(&mut vertices);
+        Ok(vertices)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    pub fn num_vertices(&self) -> usize {
+        20 * 3 * 4usize.pow(self.subdivisions as u32) * self.options.copies()
+    }
+}
+
+/// Returns the triangles of an icosahedron whose faces have been subdivided
+/// the given number of times, with each vertex lying on the unit-sphere.
+pub(crate) fn subdivided_icosahedron(subdivisions: usize) -> Vec<[Vector3<f32>; 3]> {
+    // Build the icosahedron, with a vertex at each pole and two rings of
+    // five vertices in between, the lower ring being rotated by half a step.
+    let ring_y = 1.0 / 5.0f32.sqrt();
+    let ring_radius = 2.0 * ring_y;
+    let ring_point = |step: f32, y: f32| {
+        let a = step * 2.0 * f32::consts::PI / 5.0;
+        Vector3::<f32>::new(a.cos() * ring_radius, y, -a.sin() * ring_radius)
+    };
+    let mut points = vec![Vector3::<f32>::unit_y()];
+    points.extend((0..5).map(|k| ring_point(k as f32, ring_y)));
+    points.extend((0..5).map(|k| ring_point(k as f32 + 0.5, -ring_y)));
+    points.push(-Vector3::<f32>::unit_y());
+
+    let mut triangles =
+        Vec::<[Vector3<f32>; 3]>::with_capacity(20 * 4usize.pow(subdivisions as u32));
+    for k in 0..5 {
+        let (upper0, upper1) = (1 + k, 1 + ((k + 1) % 5));
+        let (lower0, lower1) = (6 + k, 6 + ((k + 1) % 5));
+        triangles.push([points[0], points[upper0], points[upper1]]);
+        triangles.push([points[upper0], points[lower0], points[upper1]]);
+        triangles.push([points[lower0], points[lower1], points[upper1]]);
+        triangles.push([points[11], points[lower1], points[lower0]]);
+    }
+
+    // Subdivide each triangle into four, re-projecting the new vertices onto
+    // the sphere. Midpoints are computed identically for both triangles that
+    // share an edge, such that the resultant sphere is free of cracks.
+    for _ in 0..subdivisions {
+        triangles = triangles
+            .iter()
+            .flat_map(|&[a, b, c]| {
+                let ab = (a + b).normalize();
+                let bc = (b + c).normalize();
+                let ca = (c + a).normalize();
+                vec![[a, ab, ca], [ab, b, bc], [ca, bc, c], [ab, bc, ca]]
+            })
+            .collect();
+    }
+    triangles
+}
+
+/// Returns the spherical-projection texture coordinates of a triangle on the
+/// unit-sphere. Triangles that cross the seam of the projection take a u
+/// coordinate greater than 1 on the far side of the seam.
+pub(crate) fn spherical_texcoords(triangle: &[Vector3<f32>; 3]) -> [[f32; 2]; 3] {
+    let mut texcoords = [[0.0f32; 2]; 3];
+    for (texcoord, point) in texcoords.iter_mut().zip(triangle.iter()) {
+        let mut u = (-point.z).atan2(point.x) / (2.0 * f32::consts::PI);
+        if u < 0.0 {
+            u += 1.0;
+        }
+        *texcoord = [u, point.y.clamp(-1.0, 1.0).acos() / f32::consts::PI];
+    }
+
+    // Push triangles that cross the seam onto its far side.
+    let max_u = texcoords.iter().map(|t| t[0]).fold(0.0, f32::max);
+    for texcoord in texcoords.iter_mut() {
+        if max_u - texcoord[0] > 0.5 {
+            texcoord[0] += 1.0;
+        }
+    }
+
+    // The u coordinate of a pole is undefined, so take the average of
+    // the other two vertices in the triangle.
+    for index in 0..3 {
+        let point = triangle[index];
+        if point.x == 0.0 && point.z == 0.0 {
+            let others = [texcoords[(index + 1) % 3][0], texcoords[(index + 2) % 3][0]];
+            texcoords[index][0] = (others[0] + others[1]) * 0.5;
+        }
+    }
+    texcoords
+}
+
+impl HasOptions for IcoSphereBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for IcoSphereBuilder {
+    type Shape = IcoSphere;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> IcoSphere {
+        IcoSphere { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for IcoSphereBuilder {}
+
+impl BuildAs for IcoSphereBuilder {}
+
+impl WithTangents for IcoSphereBuilder {}
+
+impl WithLightmap for IcoSphereBuilder {}
+
+impl WithOrientation for IcoSphereBuilder {}
+
+impl WithBackFaces for IcoSphereBuilder {}
+
+impl WithModifiers for IcoSphereBuilder {}
+
+impl WithTexcoords for IcoSphereBuilder {}
+
+impl WithColors for IcoSphereBuilder {}
+
+impl WithShading for IcoSphereBuilder {
+    /// By default, the icosphere is smooth shaded. When flat shaded, each
+    /// vertex normal points in the direction of its respective triangle
+    /// instead.
+    const SHADINGS: &'static [Shading] = &[Shading::Flat, Shading::Smooth];
+}
+
+#[test]
+pub fn ensure_icosphere_is_unit_sphere() {
+    for subdivisions in 0..4 {
+        let vertices = IcoSphereBuilder::new()
+            .subdivisions(subdivisions)
+            .build_vertices()
+            .expect("Failed to build vertices");
+        assert_eq!(vertices.len(), 60 * 4usize.pow(subdivisions as u32));
+        for vertex in &vertices {
+            let pos = Vector3::<f32>::from(vertex.position);
+            assert!(abs_diff_eq!(pos.magnitude(), 1.0, epsilon = 0.0001));
+        }
+    }
+}
+
+#[test]
+pub fn ensure_icosphere_normals_equal_positions() {
+    let vertices = IcoSphereBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        let pos = Vector3::<f32>::from(vertex.position);
+        assert_ulps_eq!(Vector3::<f32>::from(vertex.normal), pos, epsilon = 0.0001);
+    }
+}
+
+#[test]
+pub fn ensure_default_icosphere_has_centroid_at_origin() {
+    let vertices = IcoSphereBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let mut sum = Vector3::<f32>::zero();
+    for vertex in &vertices {
+        sum += Vector3::<f32>::from(vertex.position);
+    }
+    assert_ulps_eq!(sum, Vector3::<f32>::zero(), epsilon = 0.001);
+}
+
+#[test]
+pub fn ensure_default_icosphere_has_ccw_triangles() {
+    let vertices = IcoSphereBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let eyepos = v0 + Vector3::<f32>::from(chunk[0].normal);
+        let e0 = v1 - v0;
+        let e1 = v2 - v0;
+        let n = e0.cross(e1);
+        assert!(n.dot(v0 - eyepos) <= 0.0);
+        assert!(n.dot(v1 - eyepos) <= 0.0);
+        assert!(n.dot(v2 - eyepos) <= 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_icosphere_uvs_do_not_wrap_across_seam() {
+    let vertices = IcoSphereBuilder::new()
+        .subdivisions(3)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let us = chunk.iter().map(|v| v.texcoord[0]);
+        let min = us.clone().fold(f32::MAX, f32::min);
+        let max = us.fold(f32::MIN, f32::max);
+        assert!(max - min <= 0.5);
+        assert!(min >= 0.0 && max < 1.5);
+        for vertex in chunk {
+            assert!(vertex.texcoord[1] >= 0.0 && vertex.texcoord[1] <= 1.0);
+        }
+    }
+}
+
+#[test]
+pub fn ensure_icosphere_with_too_many_subdivisions_fails_to_build() {
+    match IcoSphereBuilder::new()
+        .subdivisions(MAX_ICOSPHERE_SUBDIVISIONS + 1)
+        .build_vertices()
+    {
+        Err(ShapeCreationError::TooManySubdivisions) => (),
+        _ => panic!("Expected TooManySubdivisions"),
+    }
+}
+
+#[test]
+pub fn ensure_jittered_icosphere_is_deterministic() {
+    let build = |seed: u32| {
+        IcoSphereBuilder::new()
+            .jitter(0.1, seed)
+            .build_vertices()
+            .expect("Failed to build vertices")
+    };
+    let (first, second, other) = (build(3), build(3), build(4));
+    for (a, b) in first.iter().zip(second.iter()) {
+        assert_eq!(a.position, b.position);
+        assert_eq!(a.normal, b.normal);
+    }
+    assert!(first
+        .iter()
+        .zip(other.iter())
+        .any(|(a, b)| a.position != b.position));
+}
+
+#[test]
+pub fn ensure_jittered_icosphere_is_watertight() {
+    // Every edge of a closed surface is shared by exactly one other triangle,
+    // which runs along it in the opposite direction.
+    let edges = |vertices: &[Vertex]| {
+        let key = |vertex: &Vertex| {
+            vertex
+                .position
+                .map(|x| if x == 0.0 { 0 } else { x.to_bits() })
+        };
+        let mut edges = std::collections::HashMap::new();
+        for chunk in vertices.chunks(3) {
+            for i in 0..3 {
+                let edge = (key(&chunk[i]), key(&chunk[(i + 1) % 3]));
+                *edges.entry(edge).or_insert(0) += 1;
+            }
+        }
+        edges
+    };
+    let builder = IcoSphereBuilder::new().subdivisions(2);
+    let base = builder.build_vertices().expect("Failed to build vertices");
+    let vertices = builder
+        .jitter(0.05, 11)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertices in [base, vertices].iter() {
+        let edges = edges(vertices);
+        for (&(a, b), &count) in edges.iter() {
+            assert_eq!(count, 1);
+            assert_eq!(edges.get(&(b, a)), Some(&1));
+        }
+    }
+}
+
+#[test]
+pub fn ensure_icosphere_applies_late_pipeline() {
+    use crate::options::WithTexcoords;
+    use crate::uv_mapping::UvMapping;
+
+    crate::options::ensure_late_pipeline(
+        || {
+            IcoSphereBuilder::new()
+                .uv_mapping(UvMapping::Box)
+                .double_sided()
+        },
+        IcoSphereBuilder::build_vertices,
+    );
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_icosphere() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = IcoSphereBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
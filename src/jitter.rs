@@ -0,0 +1,84 @@
+//! A crate-private module for displacing the vertices of shapes by noise.
+
+extern crate cgmath;
+
+use self::cgmath::*;
+use crate::facet::flatten_normals;
+use crate::vertex::Vertex;
+
+/// Describes a deterministic pseudo-random displacement of the vertices of a
+/// shape.
+///
+/// The displacement of each vertex is hashed from the bits of its position
+/// prior to transformation and the seed, using integer arithmetic alone, such
+/// that the same seed displaces the same shape identically on every platform,
+/// and vertices that share a position are displaced together.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Jitter {
+    pub(crate) amplitude: f32,
+    pub(crate) seed: u32,
+}
+
+impl Jitter {
+    /// Returns the displacement of an untransformed position, each component
+    /// of which lies within the amplitude of the jitter.
+    pub(crate) fn offset(self, position: Vector3<f32>) -> Vector3<f32> {
+        // Treat both zeroes as the same coordinate, such that positions that
+        // compare equal are displaced together.
+        let bits = |x: f32| if x == 0.0 { 0 } else { x.to_bits() };
+        let mut h = hash(self.seed);
+        for axis in 0..3 {
+            h = hash(h ^ bits(position[axis]));
+        }
+        let mut offset = Vector3::<f32>::zero();
+        for axis in 0..3 {
+            h = hash(h ^ axis as u32);
+            let unit = (h >> 8) as f32 / (1u32 << 24) as f32;
+            offset[axis] = (unit * 2.0 - 1.0) * self.amplitude;
+        }
+        offset
+    }
+
+    /// Displace a list of triangles, which have been transformed by the given
+    /// matrix, and point their normals in the direction of their displaced
+    /// triangles. The displacement is computed and applied prior to the
+    /// transformation, such that it scales with the shape. A jitter with an
+    /// amplitude of zero leaves the triangles as they are.
+    pub(crate) fn apply(self, matrix: &Matrix4<f32>, vertices: &mut [Vertex]) {
+        if self.amplitude == 0.0 {
+            return;
+        }
+        let inverse = matrix.invert().unwrap_or_else(Matrix4::<f32>::identity);
+        for vertex in vertices.iter_mut() {
+            let position = Vector3::<f32>::from(vertex.position).extend(1.0);
+            let untransformed = (inverse * position).truncate();
+            let offset = (matrix * self.offset(untransformed).extend(0.0)).truncate();
+            vertex.position = (position.truncate() + offset).into();
+        }
+        flatten_normals(vertices);
+    }
+}
+
+/// Mix the bits of an integer, such that similar integers hash to unrelated
+/// values.
+fn hash(mut h: u32) -> u32 {
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x7feb_352d);
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x846c_a68b);
+    h ^= h >> 16;
+    h
+}
+
+#[test]
+pub fn ensure_jitter_offset_is_deterministic() {
+    let jitter = Jitter {
+        amplitude: 0.5,
+        seed: 7,
+    };
+    let position = Vector3::<f32>::new(1.0, -2.0, 0.0);
+    let offset = jitter.offset(position);
+    assert_eq!(offset, jitter.offset(Vector3::new(1.0, -2.0, -0.0)));
+    assert!((0..3).all(|axis| offset[axis].abs() <= 0.5));
+    assert_ne!(offset, Jitter { seed: 8, ..jitter }.offset(position));
+}
@@ -0,0 +1,510 @@
+//! A module for constructing lathed shapes (surfaces of revolution).
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::errors::ShapeCreationError;
+use crate::facet::flatten_normals;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{
+    BuildAs, HasOptions, HasVertices, ShapeOptions, WithBackFaces, WithColors, WithLightmap,
+    WithModifiers, WithOrientation, WithShading, WithTangents, WithTexcoords, WithTransform,
+};
+use crate::resolution::{Resolution, WithResolution};
+use crate::shading::Shading;
+use crate::vertex::Vertex;
+
+use std::f32;
+
+/// A polygonal `Lathe` object.
+///
+/// This object is constructed using a `LatheBuilder` object.
+pub struct Lathe {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows a `Lathe` object to be passed as a source of vertices.
+impl<'a> From<&'a Lathe> for glium::vertex::VerticesSource<'a> {
+    fn from(lathe: &'a Lathe) -> glium::vertex::VerticesSource<'a> {
+        (&lathe.vertices).into()
+    }
+}
+
+/// Allows a `Lathe` object to be passed as a source of indices.
+impl<'a> From<&'a Lathe> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a Lathe) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::TrianglesList,
+        }
+    }
+}
+
+/// Responsible for building and returning a `Lathe` object.
+///
+/// The lathe is constructed by revolving a 2D profile around the y-axis, where
+/// the x coordinate of each profile point defines its radius, and the y
+/// coordinate defines its height. By default, the profile is a single vertical
+/// line of unit length at a radius of 0.5, such that the default lathe matches
+/// the side of a unit cylinder. This can be overriden using the transformation
+/// methods on this object.
+///
+/// The front of the surface lies to the right of the profile when travelling
+/// along it (with the x-axis pointing right and the y-axis pointing up), such
+/// that a profile running from bottom to top faces outward. Where the profile
+/// touches the y-axis, the surface collapses into a fan of triangles about a
+/// single pole point, which is duplicated for each segment.
+///
+/// The resultant geometry is constructed to suit OpenGL defaults - assuming
+/// a right-handed coordinate system, front-facing polygons are defined in
+/// counter-clock-wise order. Vertex normals are computed from the neighbouring
+/// segments of the profile (such that the surface appears smooth when lit).
+/// Vertex texture coordinates define a cylindrical-projection, where the v
+/// coordinate is the proportion of the arc-length along the profile.
+pub struct LatheBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    profile: Vec<Point2<f32>>,
+    segments: usize,
+    options: ShapeOptions,
+}
+
+impl Default for LatheBuilder {
+    fn default() -> Self {
+        LatheBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            profile: vec![Point2::<f32>::new(0.5, -0.5), Point2::<f32>::new(0.5, 0.5)],
+            segments: 24,
+            options: ShapeOptions {
+                shading: Shading::Smooth,
+                ..ShapeOptions::default()
+            },
+        }
+    }
+}
+
+impl LatheBuilder {
+    /// Create a new `LatheBuilder` object.
+    pub fn new() -> LatheBuilder {
+        Default::default()
+    }
+
+    /// Specify the profile that is revolved around the y-axis. At least two
+    /// points are required to build a valid lathe, consecutive points must not
+    /// be coincident, and no point may have a negative x coordinate.
+    pub fn profile(mut self, points: &[impl Into<Point2<f32>> + Copy]) -> Self {
+        self.profile = points.iter().map(|&point| point.into()).collect();
+        self
+    }
+
+    /// Specify the number of radial segments around the lathe. By default,
+    /// the builder will use 24 segments. At least 3 segments are required to
+    /// build a valid lathe.
+    pub fn segments(mut self, segments: usize) -> Self {
+        self.segments = segments;
+        self
+    }
+
+    /// Build a new `Lathe` object.
+    pub fn build<F>(self, display: &F) -> Result<Lathe, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the shape vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// a `Lathe` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        let vertices = self.vertices_with::<Selected>()?;
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.options.check_shading(Self::SHADINGS)?;
+
+        // Ensure there are enough segments to produce valid lathe geometry.
+        if self.segments < 3 {
+            return Err(ShapeCreationError::NotEnoughDivisionsInU);
+        }
+
+        // Ensure the profile describes a valid surface.
+        if self.profile.len() < 2 {
+            return Err(ShapeCreationError::NotEnoughPoints);
+        }
+        if self.profile.windows(2).any(|w| ulps_eq!(w[0], w[1])) {
+            return Err(ShapeCreationError::DuplicatePoints);
+        }
+        if self.profile.iter().any(|p| p.x < 0.0) {
+            return Err(ShapeCreationError::InvalidRadii);
+        }
+
+        // Compute the normal of each profile segment, and the smooth normal at
+        // each profile point as the average of its neighbouring segments.
+        let last = self.profile.len() - 1;
+        let segment_normals = self
+            .profile
+            .windows(2)
+            .map(|w| {
+                let d = (w[1] - w[0]).normalize();
+                Vector2::<f32>::new(d.y, -d.x)
+            })
+            .collect::<Vec<Vector2<f32>>>();
+        let normals = (0..=last)
+            .map(|i| {
+                let sum = segment_normals[i.saturating_sub(1)] + segment_normals[i.min(last - 1)];
+                if sum.magnitude2() > 0.000_001 {
+                    sum.normalize()
+                } else {
+                    segment_normals[i.min(last - 1)]
+                }
+            })
+            .collect::<Vec<Vector2<f32>>>();
+
+        // Compute the proportion of the arc-length at each profile point.
+        let mut arc = vec![0.0];
+        for w in self.profile.windows(2) {
+            let length = arc[arc.len() - 1] + (w[1] - w[0]).magnitude();
+            arc.push(length);
+        }
+        let total = arc[last];
+
+        // Build a lookup table of (cos, sin) pairs around the lathe.
+        let angle = 2.0 * f32::consts::PI / self.segments as f32;
+        let ring = (0..=self.segments)
+            .map(|x| {
+                let a = ((x % self.segments) as f32) * angle;
+                [a.cos(), a.sin()]
+            })
+            .collect::<Vec<[f32; 2]>>();
+        let revolve =
+            |v: Vector2<f32>, [c, s]: [f32; 2]| Vector3::<f32>::new(v.x * c, v.y, -v.x * s);
+        let u = |index: usize| index as f32 / self.segments as f32;
+
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<M>::new(self.matrix);
+
+        let mut vertices = Vec::<Vertex>::with_capacity(self.num_vertices());
+        let mut emit = |position: Vector3<f32>, normal: Vector3<f32>, texcoord: [f32; 2]| {
+            vertices.push(Vertex {
+                position: transform.point(position),
+                normal: transform.normal(normal),
+                texcoord,
+            });
+        };
+
+        // Emit each band of the surface between consecutive profile points.
+        // Angles increase counter-clock-wise when viewed from above, such that
+        // the u coordinate increases from left to right when viewed from the
+        // front. Where a profile point lies on the axis, each segment of the
+        // band is reduced to a single triangle, with the pole taking the normal
+        // and u coordinate of the middle of the segment.
+        for point in 0..last {
+            let next = point + 1;
+            for seg in 0..self.segments {
+                let mid = (seg as f32 + 0.5) * angle;
+                let corner = |index: usize, point: usize| {
+                    (
+                        revolve(self.profile[point].to_vec(), ring[index]),
+                        revolve(normals[point], ring[index]),
+                        [u(index), arc[point] / total],
+                    )
+                };
+                let pole = |point: usize| {
+                    (
+                        revolve(self.profile[point].to_vec(), [mid.cos(), mid.sin()]),
+                        revolve(normals[point], [mid.cos(), mid.sin()]),
+                        [(u(seg) + u(seg + 1)) * 0.5, arc[point] / total],
+                    )
+                };
+                let corners = match (self.profile[point].x == 0.0, self.profile[next].x == 0.0) {
+                    (true, true) => vec![],
+                    (true, false) => vec![pole(point), corner(seg + 1, next), corner(seg, next)],
+                    (false, true) => vec![corner(seg, point), corner(seg + 1, point), pole(next)],
+                    (false, false) => vec![
+                        corner(seg, point),
+                        corner(seg + 1, point),
+                        corner(seg + 1, next),
+                        corner(seg, point),
+                        corner(seg + 1, next),
+                        corner(seg, next),
+                    ],
+                };
+                for (position, normal, texcoord) in corners {
+                    emit(position, normal, texcoord);
+                }
+            }
+        }
+
+        if self.options.shading == Shading::Flat {
+            flatten_normals(&mut vertices);
+        }
+
+        self.options.map_texcoords(&mut vertices);
+        Ok(vertices)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    pub fn num_vertices(&self) -> usize {
+        self.profile
+            .windows(2)
+            .map(|w| match (w[0].x == 0.0, w[1].x == 0.0) {
+                (true, true) => 0,
+                (false, false) => self.segments * 6,
+                _ => self.segments * 3,
+            })
+            .sum::<usize>()
+            * self.options.copies()
+    }
+}
+
+impl HasOptions for LatheBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for LatheBuilder {
+    type Shape = Lathe;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> Lathe {
+        Lathe { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for LatheBuilder {}
+
+impl BuildAs for LatheBuilder {}
+
+impl WithTangents for LatheBuilder {}
+
+impl WithLightmap for LatheBuilder {}
+
+impl WithOrientation for LatheBuilder {}
+
+impl WithBackFaces for LatheBuilder {}
+
+impl WithModifiers for LatheBuilder {}
+
+impl WithTexcoords for LatheBuilder {}
+
+impl WithColors for LatheBuilder {}
+
+impl WithShading for LatheBuilder {
+    /// By default, the lathe is smooth shaded. When flat shaded, each vertex
+    /// normal points in the direction of its respective triangle instead.
+    const SHADINGS: &'static [Shading] = &[Shading::Flat, Shading::Smooth];
+}
+
+impl WithResolution for LatheBuilder {
+    /// At least 3 segments around the lathe. The lathe has no segments along its
+    /// profile, which runs through its points, such that any `along` count is
+    /// accepted.
+    const MIN_RESOLUTION: Resolution = Resolution {
+        around: 3,
+        along: 0,
+    };
+
+    /// Specify the number of segments around the lathe. The `along` count is
+    /// ignored.
+    fn resolution(mut self, resolution: Resolution) -> Self {
+        self.segments = resolution.around as usize;
+        self
+    }
+}
+
+#[cfg(test)]
+fn semicircle_profile(points: usize) -> Vec<Point2<f32>> {
+    (0..=points)
+        .map(|i| {
+            let a = f32::consts::PI * (i as f32 / points as f32);
+            Point2::<f32>::new(a.sin() * 0.5, -a.cos() * 0.5)
+        })
+        .map(|p| Point2::<f32>::new(if p.x.abs() < 0.0001 { 0.0 } else { p.x }, p.y))
+        .collect()
+}
+
+#[test]
+pub fn ensure_default_lathe_matches_cylinder_sides() {
+    use crate::cylinder::CylinderBuilder;
+    let lathe = LatheBuilder::new()
+        .segments(7)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let cylinder = CylinderBuilder::new()
+        .segments(7)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    assert_eq!(lathe.len(), 7 * 6);
+    for (a, b) in lathe.iter().zip(cylinder.iter()) {
+        assert_ulps_eq!(
+            Vector3::<f32>::from(a.position),
+            Vector3::<f32>::from(b.position),
+            epsilon = 0.0001
+        );
+        assert_ulps_eq!(
+            Vector3::<f32>::from(a.normal),
+            Vector3::<f32>::from(b.normal),
+            epsilon = 0.0001
+        );
+        assert_ulps_eq!(
+            Vector2::<f32>::from(a.texcoord),
+            Vector2::<f32>::from(b.texcoord),
+            epsilon = 0.0001
+        );
+    }
+}
+
+#[test]
+pub fn ensure_lathe_collapses_profile_ends_on_axis_into_pole_fans() {
+    let builder = LatheBuilder::new()
+        .profile(&semicircle_profile(6))
+        .segments(8);
+    let vertices = builder.build_vertices().expect("Failed to build vertices");
+    assert_eq!(vertices.len(), (8 * 3 * 2) + (8 * 6 * 4));
+    assert_eq!(vertices.len(), builder.num_vertices());
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        assert!((v1 - v0).cross(v2 - v0).magnitude() > 0.0001);
+    }
+}
+
+#[test]
+pub fn ensure_lathe_has_smooth_normals() {
+    let vertices = LatheBuilder::new()
+        .profile(&semicircle_profile(12))
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        // Away from the poles, the smooth normals of a semicircular profile
+        // point directly away from the centre of the circle.
+        let pos = Vector3::<f32>::from(vertex.position);
+        if pos.y.abs() < 0.45 {
+            let normal = Vector3::<f32>::from(vertex.normal);
+            assert_ulps_eq!(normal, pos.normalize(), epsilon = 0.0001);
+        }
+    }
+}
+
+#[test]
+pub fn ensure_lathe_has_ccw_triangles() {
+    let vertices = LatheBuilder::new()
+        .profile(&[
+            Point2::<f32>::new(0.0, -0.5),
+            Point2::<f32>::new(0.3, -0.5),
+            Point2::<f32>::new(0.4, -0.2),
+            Point2::<f32>::new(0.15, 0.3),
+            Point2::<f32>::new(0.2, 0.5),
+        ])
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let eyepos = v0 + Vector3::<f32>::from(chunk[0].normal);
+        let e0 = v1 - v0;
+        let e1 = v2 - v0;
+        let n = e0.cross(e1);
+        assert!(n.dot(v0 - eyepos) <= 0.0);
+        assert!(n.dot(v1 - eyepos) <= 0.0);
+        assert!(n.dot(v2 - eyepos) <= 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_lathe_has_uvs_in_unit_range() {
+    let vertices = LatheBuilder::new()
+        .profile(&semicircle_profile(6))
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        assert!(vertex.texcoord[0] >= 0.0 && vertex.texcoord[0] <= 1.0);
+        assert!(vertex.texcoord[1] >= 0.0 && vertex.texcoord[1] <= 1.0);
+    }
+}
+
+#[test]
+pub fn ensure_lathe_with_invalid_parameters_fails_to_build() {
+    match LatheBuilder::new()
+        .profile(&[Point2::<f32>::new(0.5, 0.0)])
+        .build_vertices()
+    {
+        Err(ShapeCreationError::NotEnoughPoints) => (),
+        _ => panic!("Expected NotEnoughPoints"),
+    }
+    match LatheBuilder::new().segments(2).build_vertices() {
+        Err(ShapeCreationError::NotEnoughDivisionsInU) => (),
+        _ => panic!("Expected NotEnoughDivisionsInU"),
+    }
+    match LatheBuilder::new()
+        .profile(&[Point2::<f32>::new(-0.5, 0.0), Point2::<f32>::new(0.5, 1.0)])
+        .build_vertices()
+    {
+        Err(ShapeCreationError::InvalidRadii) => (),
+        _ => panic!("Expected InvalidRadii"),
+    }
+}
+
+#[test]
+pub fn ensure_lathe_applies_late_pipeline() {
+    use crate::options::WithTexcoords;
+    use crate::uv_mapping::UvMapping;
+
+    crate::options::ensure_late_pipeline(
+        || {
+            LatheBuilder::new()
+                .uv_mapping(UvMapping::Box)
+                .double_sided()
+        },
+        LatheBuilder::build_vertices,
+    );
+}
+
+#[test]
+pub fn ensure_lathe_with_invalid_resolution_fails_to_build() {
+    crate::resolution::ensure_min_resolution(
+        LatheBuilder::new,
+        LatheBuilder::build_vertices,
+        ShapeCreationError::NotEnoughDivisionsInU,
+        None,
+    );
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_lathe() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = LatheBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
@@ -3,12 +3,15 @@
 //! # Building a shape
 //!
 //! Each shape is constructed using a builder object. The builder objects allow us
-//! to customise the resultant shape.
+//! to customise the resultant shape. The builders are transformed through the
+//! `options::WithTransform` trait, which must be in scope to do so.
 //!
 //! In the following example, we use a `CuboidBuilder` to construct a 2x3x4 `Cuboid`
 //! with its base at the origin:
 //!
 //! ```ignore
+//! use glium_shapes::options::WithTransform;
+//!
 //! let cuboid = glium_shapes::cuboid::CuboidBuilder::new()
 //!              .translate(0.0, 0.5, 0.0)
 //!              .scale(2.0, 3.0, 4.0)
@@ -27,15 +30,125 @@
 //! ```
 //!
 //! The shader program, uniform buffers, and draw parameters are not provided by this library.
+//!
+//! # Shared options
+//!
+//! The options that most builders share, such as double-sided faces, flipped
+//! normals, winding orders, coordinate conventions, jitter, and arrays, are
+//! provided by the traits of the `options` module, which must be in scope to
+//! set them:
+//!
+//! ```ignore
+//! use glium_shapes::options::{WithBackFaces, WithOrientation};
+//!
+//! let plane = glium_shapes::plane::PlaneBuilder::new()
+//!              .double_sided()
+//!              .flip_normals()
+//!              .build(display)
+//!              .expect("Failed to build plane shape");
+//! ```
+//!
+//! # Interoperability
+//!
+//! The builder functions that take points and vectors accept any type that
+//! converts into the corresponding `cgmath` type, such as arrays of their
+//! components. With the `mint` feature enabled, they also accept the types of
+//! the `mint` crate, to which most math libraries convert, and the `Vertex`
+//! structure converts to and from a tuple of its `mint` position, normal, and
+//! texture coordinate. With the `mint` feature, each of these may also be read
+//! and set on its own, as a `mint` point or vector.
+//!
+//! The `rotate` functions accept any unit quaternion that implements
+//! `rotation::IntoQuaternion`, which includes the quaternions of `mint` with
+//! the `mint` feature.
+//!
+//! With the `glam` feature enabled, which also enables the `mint` feature, the
+//! shapes are transformed by `glam` rather than by `cgmath`, to the same bits.
+//! The points and vectors of `glam` are given to the builder functions through
+//! their `mint` types (e.g. `mint::Point3::from(vec)`), the `IntoQuaternion`
+//! trait is implemented for `glam::Quat`, and the `Vertex` structure converts
+//! to and from a tuple of its `glam` position, normal, and texture coordinate.
 
 extern crate approx;
 
 #[macro_use]
 extern crate glium;
 
+pub mod aabb_wire;
+pub mod annulus;
+pub mod antiprism;
+pub mod arc_sector;
+pub mod arrow;
 pub mod axes;
+pub mod axis;
+pub mod billboard_quad;
+pub mod bipyramid;
+pub mod camera_frustum;
+pub mod cap_mode;
+pub mod capsule;
+pub mod circle_outline;
+pub mod cone;
+pub mod convention;
+pub mod convex_hull;
 pub mod cuboid;
+pub mod cylinder;
+pub mod disc;
+pub mod dodecahedron;
+pub mod elbow;
 pub mod errors;
+pub mod extrusion;
+pub mod frustum;
+pub mod gear;
+pub mod gem;
+pub mod geodesic_dome;
+pub mod grid_lines;
+pub mod heightmap;
+pub mod helix;
+pub mod hemisphere;
+pub mod hex_grid;
+pub mod icosahedron;
+pub mod icosphere;
+pub mod lathe;
+pub mod mirror_plane;
+pub mod mobius_strip;
+pub mod normals_visualizer;
+pub mod octahedron;
+pub mod open_box;
+pub mod options;
+pub mod parametric_surface;
+pub mod plane;
+pub mod polyline;
+pub mod prism;
+pub mod pyramid;
 pub mod quad;
+pub mod quad_sphere;
+pub mod resolution;
+pub mod rotation;
+pub mod rounded_cuboid;
+pub mod rounded_rect;
+pub mod shading;
+pub mod skybox;
 pub mod sphere;
+pub mod stairs;
+pub mod star_prism;
+pub mod superellipsoid;
+pub mod teapot;
+pub mod tetrahedron;
+pub mod torus;
+pub mod torus_knot;
+pub mod truncated_pyramid;
+pub mod tube;
+pub mod uv_mapping;
 pub mod vertex;
+pub mod voxel_chunk;
+pub mod wedge;
+pub mod winding;
+
+mod coloring;
+mod facet;
+mod jitter;
+mod lightmap;
+mod math;
+mod modifier;
+mod sweep;
+mod tangent;
@@ -0,0 +1,228 @@
+//! A crate-private module for computing a second set of texture coordinates
+//! for the lightmaps of shapes.
+
+extern crate cgmath;
+
+use self::cgmath::*;
+use crate::errors::ShapeCreationError;
+use crate::vertex::{Vertex, VertexLightmapped};
+
+/// Attach lightmap texture coordinates to each vertex of a list of triangles.
+pub(crate) fn with_lightmap_uvs(
+    vertices: &[Vertex],
+    gutter: f32,
+) -> Result<Vec<VertexLightmapped>, ShapeCreationError> {
+    let triangles = (0..vertices.len() / 3)
+        .map(|index| [index * 3, index * 3 + 1, index * 3 + 2])
+        .collect::<Vec<[usize; 3]>>();
+    lightmap(vertices, &triangles, gutter)
+}
+
+/// Attach lightmap texture coordinates to each vertex of a list of indexed
+/// triangles. A vertex that is shared by triangles of different charts takes
+/// the coordinates of the last of them.
+pub(crate) fn with_indexed_lightmap_uvs(
+    vertices: &[Vertex],
+    indices: &[u16],
+    gutter: f32,
+) -> Result<Vec<VertexLightmapped>, ShapeCreationError> {
+    let triangles = indices
+        .chunks(3)
+        .map(|triangle| {
+            [
+                triangle[0] as usize,
+                triangle[1] as usize,
+                triangle[2] as usize,
+            ]
+        })
+        .collect::<Vec<[usize; 3]>>();
+    lightmap(vertices, &triangles, gutter)
+}
+
+/// A run of consecutive, coplanar triangles that is laid out as a single
+/// rectangle of the lightmap.
+struct Chart {
+    normal: Vector3<f32>,
+    distance: f32,
+    axes: (Vector3<f32>, Vector3<f32>),
+    min: Vector2<f32>,
+    max: Vector2<f32>,
+    triangles: Vec<[usize; 3]>,
+}
+
+/// Compute the lightmap texture coordinates of the vertices from the given
+/// triangles.
+///
+/// Each run of consecutive triangles that lie in the same plane (e.g. the face
+/// of a cuboid) forms a chart, which is projected onto its plane without
+/// distortion. The bounding rectangles of the charts are then packed into
+/// rows of the unit square, tallest first, all scaled by the same factor such
+/// that the lightmap has a uniform texel density. The rectangles are kept
+/// apart from each other, and from the edges of the unit square, by the given
+/// gutter.
+fn lightmap(
+    vertices: &[Vertex],
+    triangles: &[[usize; 3]],
+    gutter: f32,
+) -> Result<Vec<VertexLightmapped>, ShapeCreationError> {
+    if !(0.0..0.5).contains(&gutter) {
+        return Err(ShapeCreationError::InvalidDimensions);
+    }
+
+    // The tolerance within which triangles are coplanar, relative to the size
+    // of the shape.
+    let position = |index: usize| Vector3::<f32>::from(vertices[index].position);
+    let mut lo = Vector3::<f32>::from_value(f32::INFINITY);
+    let mut hi = Vector3::<f32>::from_value(f32::NEG_INFINITY);
+    for vertex in vertices {
+        for axis in 0..3 {
+            lo[axis] = lo[axis].min(vertex.position[axis]);
+            hi[axis] = hi[axis].max(vertex.position[axis]);
+        }
+    }
+    let size = (0..3).map(|axis| hi[axis] - lo[axis]).fold(0.0, f32::max);
+    let tolerance = size.max(f32::MIN_POSITIVE) * 1.0e-5;
+
+    // Gather the triangles into charts.
+    let mut charts = Vec::<Chart>::new();
+    for &triangle in triangles {
+        let [p0, p1, p2] = triangle.map(position);
+        let normal = (p1 - p0).cross(p2 - p0);
+        let extends = |chart: &Chart| {
+            normal.magnitude2() == 0.0
+                || (chart.normal.dot(normal.normalize()) > 1.0 - 1.0e-5
+                    && (chart.normal.dot(p0) - chart.distance).abs() <= tolerance)
+        };
+        match charts.last_mut() {
+            Some(chart) if extends(chart) => chart.triangles.push(triangle),
+            _ => {
+                let normal = if normal.magnitude2() > 0.0 {
+                    normal.normalize()
+                } else {
+                    Vector3::unit_z()
+                };
+                let u = if normal.x.abs() < 0.9 {
+                    Vector3::unit_x()
+                } else {
+                    Vector3::unit_y()
+                };
+                let u = (u - normal * normal.dot(u)).normalize();
+                charts.push(Chart {
+                    normal,
+                    distance: normal.dot(p0),
+                    axes: (u, normal.cross(u)),
+                    min: Vector2::from_value(f32::INFINITY),
+                    max: Vector2::from_value(f32::NEG_INFINITY),
+                    triangles: vec![triangle],
+                });
+            }
+        }
+    }
+    let project = |chart: &Chart, index: usize| {
+        let p = position(index);
+        Vector2::<f32>::new(chart.axes.0.dot(p), chart.axes.1.dot(p))
+    };
+    for chart in charts.iter_mut() {
+        let (mut min, mut max) = (chart.min, chart.max);
+        for &triangle in chart.triangles.iter() {
+            for &vertex in triangle.iter() {
+                let p = project(chart, vertex);
+                min = Vector2::new(min.x.min(p.x), min.y.min(p.y));
+                max = Vector2::new(max.x.max(p.x), max.y.max(p.y));
+            }
+        }
+        chart.min = min;
+        chart.max = max;
+    }
+
+    // Find the largest scale, to within a few percent, at which the charts
+    // can be packed.
+    let sizes = charts
+        .iter()
+        .map(|chart| chart.max - chart.min)
+        .collect::<Vec<Vector2<f32>>>();
+    let area = sizes.iter().map(|size| size.x * size.y).sum::<f32>();
+    let longest = sizes
+        .iter()
+        .map(|size| size.x.max(size.y))
+        .fold(0.0, f32::max);
+    let mut scale = if longest > 0.0 {
+        let fit = (1.0 - gutter * 2.0) / longest;
+        if area > 0.0 {
+            fit.min(area.sqrt().recip())
+        } else {
+            fit
+        }
+    } else {
+        1.0
+    };
+    let mut offsets = None;
+    for _ in 0..1000 {
+        offsets = pack(&sizes, scale, gutter);
+        if offsets.is_some() {
+            break;
+        }
+        scale *= 0.95;
+    }
+    let offsets = offsets.ok_or(ShapeCreationError::InvalidDimensions)?;
+
+    let mut texcoords = vec![[0.0; 2]; vertices.len()];
+    for (chart, offset) in charts.iter().zip(offsets) {
+        for &triangle in chart.triangles.iter() {
+            for &vertex in triangle.iter() {
+                let p = (project(chart, vertex) - chart.min) * scale;
+                texcoords[vertex] = [offset.x + p.x, offset.y + p.y];
+            }
+        }
+    }
+    Ok(vertices
+        .iter()
+        .zip(texcoords)
+        .map(|(vertex, texcoord1)| VertexLightmapped {
+            position: vertex.position,
+            normal: vertex.normal,
+            texcoord: vertex.texcoord,
+            texcoord1,
+        })
+        .collect())
+}
+
+/// Pack rectangles of the given sizes, scaled by the given factor, into rows
+/// of the unit square, separated by the given gutter. Returns the offset of
+/// each rectangle, or `None` if the rectangles do not fit.
+fn pack(sizes: &[Vector2<f32>], scale: f32, gutter: f32) -> Option<Vec<Vector2<f32>>> {
+    let mut order = (0..sizes.len()).collect::<Vec<usize>>();
+    order.sort_by(|&a, &b| sizes[b].y.partial_cmp(&sizes[a].y).unwrap());
+
+    let mut offsets = vec![Vector2::<f32>::zero(); sizes.len()];
+    let (mut x, mut y, mut height) = (gutter, gutter, 0.0f32);
+    for index in order {
+        let size = sizes[index] * scale;
+        if x + size.x + gutter > 1.0 {
+            x = gutter;
+            y += height + gutter;
+            height = 0.0;
+            if x + size.x + gutter > 1.0 {
+                return None;
+            }
+        }
+        offsets[index] = Vector2::new(x, y);
+        x += size.x + gutter;
+        height = height.max(size.y);
+    }
+    if y + height + gutter > 1.0 {
+        None
+    } else {
+        Some(offsets)
+    }
+}
+
+#[test]
+pub fn ensure_packing_fails_when_gutters_do_not_fit() {
+    let sizes = vec![Vector2::<f32>::new(1.0, 1.0); 4];
+    assert!(pack(&sizes, 0.25, 0.2).is_none());
+    let offsets = pack(&sizes, 0.25, 0.05).expect("Failed to pack rectangles");
+    assert_ulps_eq!(offsets[0], Vector2::new(0.05, 0.05));
+    assert_ulps_eq!(offsets[2], Vector2::new(0.65, 0.05));
+    assert_ulps_eq!(offsets[3], Vector2::new(0.05, 0.35));
+}
@@ -0,0 +1,324 @@
+//! A crate-private module containing the matrix and quaternion math with which
+//! the vertices of shapes are transformed, implemented by `cgmath` and, with
+//! the `glam` feature, by `glam`.
+//!
+//! The transformations of the builders are accumulated as `cgmath` matrices,
+//! which are handed to the implementation selected by the features once the
+//! vertices are built. Both implementations evaluate the same formulas in the
+//! same order, such that they build identical vertices.
+
+extern crate cgmath;
+
+use self::cgmath::*;
+#[cfg(all(test, feature = "glam"))]
+use crate::errors::ShapeCreationError;
+#[cfg(all(test, feature = "glam"))]
+use crate::vertex::Vertex;
+
+/// The matrix and quaternion math with which the vertices of shapes are
+/// transformed. The trait is public within this private module, such that it
+/// may bound the methods of the sealed build traits.
+pub trait Math {
+    /// A 4x4 matrix, which transforms positions.
+    type Mat4: Copy;
+    /// A 3x3 matrix, which transforms normals.
+    type Mat3: Copy;
+    /// A unit quaternion, which rotates.
+    type Quat: Copy;
+
+    /// Returns a matrix with the elements of a `cgmath` matrix.
+    fn mat4(matrix: Matrix4<f32>) -> Self::Mat4;
+
+    /// Returns a `cgmath` matrix with the elements of a matrix.
+    fn to_matrix4(matrix: Self::Mat4) -> Matrix4<f32>;
+
+    /// Returns a quaternion with the components of a `cgmath` quaternion.
+    fn quat(quaternion: Quaternion<f32>) -> Self::Quat;
+
+    /// Returns the rotation matrix of a quaternion.
+    fn rotation(quaternion: Self::Quat) -> Self::Mat4;
+
+    /// Returns the product of two matrices, which applies `b` before `a`.
+    fn mul(a: Self::Mat4, b: Self::Mat4) -> Self::Mat4;
+
+    /// Returns the product of two normal matrices, which applies `b` before
+    /// `a`.
+    fn mul3(a: Self::Mat3, b: Self::Mat3) -> Self::Mat3;
+
+    /// Returns the upper-left 3x3 part of a matrix, which is its linear part
+    /// when the matrix is affine.
+    fn linear(matrix: Self::Mat4) -> Self::Mat3;
+
+    /// Returns the normal matrix of a matrix, the inverse transpose of its
+    /// linear part, or the identity if the linear part is singular.
+    fn normal_matrix(matrix: Self::Mat4) -> Self::Mat3;
+
+    /// Returns the determinant of a normal matrix.
+    fn determinant(matrix: Self::Mat3) -> f32;
+
+    /// Transform a position by a matrix, dividing by its homogeneous
+    /// coordinate.
+    fn transform_point(matrix: &Self::Mat4, position: [f32; 3]) -> [f32; 3];
+
+    /// Transform a normal by a normal matrix, and normalize it.
+    fn transform_normal(matrix: &Self::Mat3, normal: [f32; 3]) -> [f32; 3];
+}
+
+/// The math of `cgmath`, which is only compared against that of `glam` when
+/// the `glam` feature is enabled.
+#[cfg_attr(feature = "glam", allow(dead_code))]
+pub(crate) struct Cgmath;
+
+impl Math for Cgmath {
+    type Mat4 = Matrix4<f32>;
+    type Mat3 = Matrix3<f32>;
+    type Quat = Quaternion<f32>;
+
+    fn mat4(matrix: Matrix4<f32>) -> Matrix4<f32> {
+        matrix
+    }
+
+    fn to_matrix4(matrix: Matrix4<f32>) -> Matrix4<f32> {
+        matrix
+    }
+
+    fn quat(quaternion: Quaternion<f32>) -> Quaternion<f32> {
+        quaternion
+    }
+
+    fn rotation(quaternion: Quaternion<f32>) -> Matrix4<f32> {
+        Matrix4::from(quaternion)
+    }
+
+    fn mul(a: Matrix4<f32>, b: Matrix4<f32>) -> Matrix4<f32> {
+        a * b
+    }
+
+    fn mul3(a: Matrix3<f32>, b: Matrix3<f32>) -> Matrix3<f32> {
+        a * b
+    }
+
+    fn linear(matrix: Matrix4<f32>) -> Matrix3<f32> {
+        Matrix3::from_cols(
+            matrix.x.truncate(),
+            matrix.y.truncate(),
+            matrix.z.truncate(),
+        )
+    }
+
+    fn normal_matrix(matrix: Matrix4<f32>) -> Matrix3<f32> {
+        Cgmath::linear(matrix)
+            .invert()
+            .unwrap_or_else(Matrix3::identity)
+            .transpose()
+    }
+
+    fn determinant(matrix: Matrix3<f32>) -> f32 {
+        matrix.determinant()
+    }
+
+    fn transform_point(matrix: &Matrix4<f32>, position: [f32; 3]) -> [f32; 3] {
+        let position = Vector3::from(position).extend(1.0);
+        Point3::from_homogeneous(matrix * position).into()
+    }
+
+    fn transform_normal(matrix: &Matrix3<f32>, normal: [f32; 3]) -> [f32; 3] {
+        (matrix * Vector3::from(normal)).normalize().into()
+    }
+}
+
+/// The math of `glam`, which evaluates the formulas of `cgmath` wherever
+/// `glam` evaluates them differently.
+#[cfg(feature = "glam")]
+pub(crate) struct Glam;
+
+#[cfg(feature = "glam")]
+impl Math for Glam {
+    type Mat4 = glam::Mat4;
+    type Mat3 = glam::Mat3;
+    type Quat = glam::Quat;
+
+    fn mat4(matrix: Matrix4<f32>) -> glam::Mat4 {
+        glam::Mat4::from_cols_array_2d(&matrix.into())
+    }
+
+    fn to_matrix4(matrix: glam::Mat4) -> Matrix4<f32> {
+        matrix.to_cols_array_2d().into()
+    }
+
+    fn quat(quaternion: Quaternion<f32>) -> glam::Quat {
+        let v = quaternion.v;
+        glam::Quat::from_xyzw(v.x, v.y, v.z, quaternion.s)
+    }
+
+    fn rotation(quaternion: glam::Quat) -> glam::Mat4 {
+        let (x2, y2, z2) = (
+            quaternion.x + quaternion.x,
+            quaternion.y + quaternion.y,
+            quaternion.z + quaternion.z,
+        );
+        let (xx2, xy2, xz2) = (x2 * quaternion.x, x2 * quaternion.y, x2 * quaternion.z);
+        let (yy2, yz2, zz2) = (y2 * quaternion.y, y2 * quaternion.z, z2 * quaternion.z);
+        let (sy2, sz2, sx2) = (y2 * quaternion.w, z2 * quaternion.w, x2 * quaternion.w);
+        glam::Mat4::from_cols(
+            glam::Vec4::new(1.0 - yy2 - zz2, xy2 + sz2, xz2 - sy2, 0.0),
+            glam::Vec4::new(xy2 - sz2, 1.0 - xx2 - zz2, yz2 + sx2, 0.0),
+            glam::Vec4::new(xz2 + sy2, yz2 - sx2, 1.0 - xx2 - yy2, 0.0),
+            glam::Vec4::W,
+        )
+    }
+
+    fn mul(a: glam::Mat4, b: glam::Mat4) -> glam::Mat4 {
+        a * b
+    }
+
+    fn mul3(a: glam::Mat3, b: glam::Mat3) -> glam::Mat3 {
+        a * b
+    }
+
+    fn linear(matrix: glam::Mat4) -> glam::Mat3 {
+        glam::Mat3::from_mat4(matrix)
+    }
+
+    fn normal_matrix(matrix: glam::Mat4) -> glam::Mat3 {
+        let linear = Glam::linear(matrix);
+        let det = Glam::determinant(linear);
+        if det == 0.0 {
+            return glam::Mat3::IDENTITY;
+        }
+        let (x, y, z) = (linear.x_axis, linear.y_axis, linear.z_axis);
+        glam::Mat3::from_cols(y.cross(z) / det, z.cross(x) / det, x.cross(y) / det)
+    }
+
+    fn determinant(matrix: glam::Mat3) -> f32 {
+        let (x, y, z) = (matrix.x_axis, matrix.y_axis, matrix.z_axis);
+        x.x * (y.y * z.z - z.y * y.z) - y.x * (x.y * z.z - z.y * x.z)
+            + z.x * (x.y * y.z - y.y * x.z)
+    }
+
+    fn transform_point(matrix: &glam::Mat4, position: [f32; 3]) -> [f32; 3] {
+        let position = *matrix * glam::Vec3::from(position).extend(1.0);
+        (position.truncate() * (1.0 / position.w)).into()
+    }
+
+    fn transform_normal(matrix: &glam::Mat3, normal: [f32; 3]) -> [f32; 3] {
+        (*matrix * glam::Vec3::from(normal)).normalize().into()
+    }
+}
+
+/// The transformation of the positions and normals of a shape, evaluated by
+/// the given math.
+pub(crate) struct Transformation<M: Math> {
+    matrix: M::Mat4,
+    linear: M::Mat3,
+    normal_matrix: M::Mat3,
+}
+
+impl<M: Math> Transformation<M> {
+    /// Returns the transformation of a `cgmath` matrix, whose normals are
+    /// transformed by its normal matrix, and whose directions are transformed
+    /// by its linear part.
+    pub(crate) fn new(matrix: Matrix4<f32>) -> Self {
+        let matrix = M::mat4(matrix);
+        Transformation {
+            matrix,
+            linear: M::linear(matrix),
+            normal_matrix: M::normal_matrix(matrix),
+        }
+    }
+
+    /// Transform a position.
+    pub(crate) fn point(&self, position: impl Into<[f32; 3]>) -> [f32; 3] {
+        M::transform_point(&self.matrix, position.into())
+    }
+
+    /// Transform a normal, and normalize it.
+    pub(crate) fn normal(&self, normal: impl Into<[f32; 3]>) -> [f32; 3] {
+        M::transform_normal(&self.normal_matrix, normal.into())
+    }
+
+    /// Transform a direction, such as that of a line, and normalize it. A
+    /// direction that the transformation collapses to zero is left as it is.
+    pub(crate) fn direction(&self, direction: impl Into<[f32; 3]>) -> [f32; 3] {
+        let direction = direction.into();
+        let transformed = M::transform_normal(&self.linear, direction);
+        if transformed.iter().all(|component| component.is_finite()) {
+            transformed
+        } else {
+            direction
+        }
+    }
+}
+
+/// The math with which the shapes are transformed, which is that of `glam`
+/// with the `glam` feature, and that of `cgmath` otherwise.
+#[cfg(not(feature = "glam"))]
+pub(crate) type Selected = Cgmath;
+
+/// The math with which the shapes are transformed, which is that of `glam`
+/// with the `glam` feature, and that of `cgmath` otherwise.
+#[cfg(feature = "glam")]
+pub(crate) type Selected = Glam;
+
+/// Ensure that a builder builds identical vertices with the math of `glam` as
+/// with the math of `cgmath`, down to the bits of every component.
+#[cfg(all(test, feature = "glam"))]
+pub(crate) fn ensure_glam_matches_cgmath(
+    expected: Result<Vec<Vertex>, ShapeCreationError>,
+    vertices: Result<Vec<Vertex>, ShapeCreationError>,
+) {
+    let expected = expected.expect("Failed to build vertices");
+    let vertices = vertices.expect("Failed to build vertices");
+    assert_eq!(vertices.len(), expected.len());
+    for (vertex, expected) in vertices.iter().zip(expected.iter()) {
+        assert_eq!(
+            vertex.position.map(f32::to_bits),
+            expected.position.map(f32::to_bits)
+        );
+        assert_eq!(
+            vertex.normal.map(f32::to_bits),
+            expected.normal.map(f32::to_bits)
+        );
+        assert_eq!(
+            vertex.texcoord.map(f32::to_bits),
+            expected.texcoord.map(f32::to_bits)
+        );
+    }
+}
+
+#[test]
+pub fn ensure_normal_matrix_of_singular_matrix_is_identity() {
+    let matrix = Selected::mat4(Matrix4::from_nonuniform_scale(2.0, 0.0, 1.0));
+    let normal = Selected::transform_normal(&Selected::normal_matrix(matrix), [0.0, 0.0, 2.0]);
+    assert_eq!(normal, [0.0, 0.0, 1.0]);
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_evaluates_the_formulas_of_cgmath() {
+    let bits = |values: [f32; 3]| values.map(f32::to_bits);
+    let rotation = Quaternion::from_axis_angle(Vector3::new(1.0, 2.0, 3.0).normalize(), Rad(0.7));
+    let matrix = Matrix4::from_translation(Vector3::new(0.5, -1.5, 3.0))
+        * Matrix4::from(rotation)
+        * Matrix4::from_nonuniform_scale(1.5, -0.25, 3.0);
+    let cgmath = Cgmath::mul(
+        Cgmath::rotation(Cgmath::quat(rotation)),
+        Cgmath::mat4(matrix),
+    );
+    let glam = Glam::mul(Glam::rotation(Glam::quat(rotation)), Glam::mat4(matrix));
+    let (cgmath_normal, glam_normal) = (Cgmath::normal_matrix(cgmath), Glam::normal_matrix(glam));
+    assert_eq!(
+        Cgmath::determinant(cgmath_normal).to_bits(),
+        Glam::determinant(glam_normal).to_bits()
+    );
+    for value in [[0.0, 1.0, 0.0], [1.0, -2.0, 3.5], [-0.3, 0.7, 0.1]] {
+        assert_eq!(
+            bits(Cgmath::transform_point(&cgmath, value)),
+            bits(Glam::transform_point(&glam, value))
+        );
+        assert_eq!(
+            bits(Cgmath::transform_normal(&cgmath_normal, value)),
+            bits(Glam::transform_normal(&glam_normal, value))
+        );
+    }
+}
@@ -0,0 +1,52 @@
+//! A module containing the planes across which shapes may be mirrored.
+
+extern crate cgmath;
+
+use self::cgmath::*;
+
+/// One of the three coordinate planes through the origin.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum MirrorPlane {
+    /// The plane spanned by the y-axis and the z-axis, across which the
+    /// x-axis is reflected.
+    Yz,
+
+    /// The plane spanned by the x-axis and the z-axis, across which the
+    /// y-axis is reflected.
+    Xz,
+
+    /// The plane spanned by the x-axis and the y-axis, across which the
+    /// z-axis is reflected.
+    Xy,
+}
+
+impl MirrorPlane {
+    /// Returns the reflection across this plane. The reflection is built
+    /// directly from its elements, such that it is exact.
+    pub(crate) fn matrix(self) -> Matrix4<f32> {
+        let mut matrix = Matrix4::<f32>::identity();
+        match self {
+            MirrorPlane::Yz => matrix.x.x = -1.0,
+            MirrorPlane::Xz => matrix.y.y = -1.0,
+            MirrorPlane::Xy => matrix.z.z = -1.0,
+        }
+        matrix
+    }
+}
+
+#[test]
+pub fn ensure_mirror_planes_reflect_their_normal_axis() {
+    let point = Vector4::<f32>::new(1.0, 2.0, 3.0, 1.0);
+    assert_eq!(
+        MirrorPlane::Yz.matrix() * point,
+        Vector4::new(-1.0, 2.0, 3.0, 1.0)
+    );
+    assert_eq!(
+        MirrorPlane::Xz.matrix() * point,
+        Vector4::new(1.0, -2.0, 3.0, 1.0)
+    );
+    assert_eq!(
+        MirrorPlane::Xy.matrix() * point,
+        Vector4::new(1.0, 2.0, -3.0, 1.0)
+    );
+}
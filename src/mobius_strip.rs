@@ -0,0 +1,429 @@
+//! A module for constructing Möbius strip shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::errors::ShapeCreationError;
+use crate::facet::flatten_normals;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{
+    BuildAs, HasOptions, HasVertices, ShapeOptions, WithColors, WithLightmap, WithModifiers,
+    WithOrientation, WithShading, WithTangents, WithTexcoords, WithTransform,
+};
+use crate::resolution::{Resolution, WithResolution};
+use crate::shading::Shading;
+use crate::vertex::Vertex;
+
+use std::f32;
+
+/// A polygonal `MobiusStrip` object.
+///
+/// This object is constructed using a `MobiusStripBuilder` object.
+pub struct MobiusStrip {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows a `MobiusStrip` object to be passed as a source of vertices.
+impl<'a> From<&'a MobiusStrip> for glium::vertex::VerticesSource<'a> {
+    fn from(strip: &'a MobiusStrip) -> glium::vertex::VerticesSource<'a> {
+        (&strip.vertices).into()
+    }
+}
+
+/// Allows a `MobiusStrip` object to be passed as a source of indices.
+impl<'a> From<&'a MobiusStrip> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a MobiusStrip) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::TrianglesList,
+        }
+    }
+}
+
+/// Responsible for building and returning a `MobiusStrip` object.
+///
+/// The Möbius strip is constructed by sweeping a straight band around a circle
+/// about the y-axis, rotating the band by half a turn about the circle over
+/// the course of a full revolution. By default, the circle has a radius of
+/// 0.375, and the band has a width of 0.25 and lies flat in the xz-plane where
+/// it crosses the positive x-axis. This can be overriden using the
+/// transformation methods on this object.
+///
+/// Because the surface of a Möbius strip has only one side, the geometry is
+/// emitted double-sided, such that it renders correctly with back-face culling
+/// enabled. The resultant geometry is constructed to suit OpenGL defaults -
+/// assuming a right-handed coordinate system, front-facing polygons are
+/// defined in counter-clock-wise order. Each side is emitted with its own
+/// vertex normals, which follow the local surface (such that the strip appears
+/// smooth when lit). Vertex texture coordinates map the proportion of the
+/// length around the band to the u coordinate, and the proportion of the width
+/// across the band to the v coordinate.
+pub struct MobiusStripBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    radius: f32,
+    width: f32,
+    length_segments: usize,
+    width_segments: usize,
+    options: ShapeOptions,
+}
+
+impl Default for MobiusStripBuilder {
+    fn default() -> Self {
+        MobiusStripBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            radius: 0.375,
+            width: 0.25,
+            length_segments: 64,
+            width_segments: 4,
+            options: ShapeOptions {
+                shading: Shading::Smooth,
+                ..ShapeOptions::default()
+            },
+        }
+    }
+}
+
+impl MobiusStripBuilder {
+    /// Create a new `MobiusStripBuilder` object.
+    pub fn new() -> MobiusStripBuilder {
+        Default::default()
+    }
+
+    /// Specify the radius of the circle about which the band is swept. By
+    /// default, the radius is 0.375. The radius must be greater than zero to
+    /// build a valid Möbius strip.
+    pub fn radius(mut self, radius: f32) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    /// Specify the width of the band. By default, the width is 0.25. The width
+    /// must be greater than zero, and less than twice the radius, to build a
+    /// valid Möbius strip.
+    pub fn width(mut self, width: f32) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Specify the number of segments along the length of the band. By
+    /// default, the builder will use 64 segments. At least 3 segments are
+    /// required to build a valid Möbius strip.
+    pub fn length_segments(mut self, segments: usize) -> Self {
+        self.length_segments = segments;
+        self
+    }
+
+    /// Specify the number of segments across the width of the band. By
+    /// default, the builder will use 4 segments. At least 1 segment is
+    /// required to build a valid Möbius strip.
+    pub fn width_segments(mut self, segments: usize) -> Self {
+        self.width_segments = segments;
+        self
+    }
+
+    /// Build a new `MobiusStrip` object.
+    pub fn build<F>(self, display: &F) -> Result<MobiusStrip, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the shape vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// a `MobiusStrip` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        let vertices = self.vertices_with::<Selected>()?;
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.options.check_shading(Self::SHADINGS)?;
+
+        // Ensure there are enough segments to produce valid strip geometry.
+        if self.length_segments < 3 {
+            return Err(ShapeCreationError::NotEnoughDivisionsInU);
+        }
+        if self.width_segments < 1 {
+            return Err(ShapeCreationError::NotEnoughDivisionsInV);
+        }
+
+        // Ensure the band does not pass through the y-axis.
+        if self.radius <= 0.0 {
+            return Err(ShapeCreationError::InvalidRadii);
+        }
+        if self.width <= 0.0 || self.width >= self.radius * 2.0 {
+            return Err(ShapeCreationError::InvalidDimensions);
+        }
+
+        // Compute the position and surface normal at the given length and
+        // width indices. The band direction runs from the outer edge to the
+        // inner edge where the band crosses the positive x-axis, and after a
+        // full revolution, the band meets its start with its width reversed.
+        // The seam therefore reuses the positions at the start of the band,
+        // such that the strip is continuous.
+        let point = |i: usize, j: usize| {
+            let (i, j, sign) = if i == self.length_segments {
+                (0, self.width_segments - j, -1.0)
+            } else {
+                (i, j, 1.0)
+            };
+            let theta = 2.0 * f32::consts::PI * i as f32 / self.length_segments as f32;
+            let w = (j as f32 / self.width_segments as f32 - 0.5) * self.width;
+            let (s, c) = theta.sin_cos();
+            let (s2, c2) = (theta * 0.5).sin_cos();
+            let radial = Vector3::<f32>::new(c, 0.0, -s);
+            let tangent = Vector3::<f32>::new(-s, 0.0, -c);
+            let band = -(radial * c2 + Vector3::<f32>::unit_y() * s2);
+            let band_derivative =
+                -(tangent * c2 - radial * (s2 * 0.5) + Vector3::<f32>::unit_y() * (c2 * 0.5));
+            let position = radial * self.radius + band * w;
+            let normal = (tangent * self.radius + band_derivative * w).cross(band);
+            (position, normal.normalize() * sign)
+        };
+
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<M>::new(self.matrix);
+
+        // Build vertex array. The front side is emitted first, followed by the
+        // back side, which reverses the winding and normals of the front.
+        let mut vertices = Vec::<Vertex>::with_capacity(self.num_vertices());
+        let quad = [(0, 0), (1, 0), (1, 1), (0, 0), (1, 1), (0, 1)];
+        for &(side, sign) in [(0, 1.0), (1, -1.0)].iter() {
+            for i in 0..self.length_segments {
+                for j in 0..self.width_segments {
+                    for k in 0..quad.len() {
+                        let (di, dj) = quad[if side == 0 { k } else { quad.len() - 1 - k }];
+                        let (position, normal) = point(i + di, j + dj);
+                        vertices.push(Vertex {
+                            position: transform.point(position),
+                            normal: transform.normal(normal * sign),
+                            texcoord: [
+                                (i + di) as f32 / self.length_segments as f32,
+                                (j + dj) as f32 / self.width_segments as f32,
+                            ],
+                        });
+                    }
+                }
+            }
+        }
+
+        if self.options.shading == Shading::Flat {
+            flatten_normals(&mut vertices);
+        }
+        self.options.map_texcoords(&mut vertices);
+        Ok(vertices)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    pub fn num_vertices(&self) -> usize {
+        self.length_segments * self.width_segments * 6 * 2 * self.options.copies()
+    }
+}
+
+impl HasOptions for MobiusStripBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for MobiusStripBuilder {
+    type Shape = MobiusStrip;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> MobiusStrip {
+        MobiusStrip { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for MobiusStripBuilder {}
+
+impl BuildAs for MobiusStripBuilder {}
+
+impl WithTangents for MobiusStripBuilder {}
+
+impl WithLightmap for MobiusStripBuilder {}
+
+impl WithOrientation for MobiusStripBuilder {}
+
+impl WithModifiers for MobiusStripBuilder {}
+
+impl WithTexcoords for MobiusStripBuilder {}
+
+impl WithColors for MobiusStripBuilder {}
+
+impl WithShading for MobiusStripBuilder {
+    /// By default, the Möbius strip is smooth shaded. When flat shaded, each
+    /// vertex normal points in the direction of its respective triangle
+    /// instead.
+    const SHADINGS: &'static [Shading] = &[Shading::Flat, Shading::Smooth];
+}
+
+impl WithResolution for MobiusStripBuilder {
+    /// At least 3 segments along the length of the band, and 1 segment across
+    /// its width.
+    const MIN_RESOLUTION: Resolution = Resolution {
+        around: 3,
+        along: 1,
+    };
+
+    /// Specify the number of segments along the length of the band, and the
+    /// number of segments across its width.
+    fn resolution(mut self, resolution: Resolution) -> Self {
+        self.length_segments = resolution.around as usize;
+        self.width_segments = resolution.along as usize;
+        self
+    }
+}
+
+#[test]
+pub fn ensure_default_mobius_strip_has_ccw_triangles() {
+    let vertices = MobiusStripBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let eyepos = v0 + Vector3::<f32>::from(chunk[0].normal);
+        let e0 = v1 - v0;
+        let e1 = v2 - v0;
+        let n = e0.cross(e1);
+        assert!(n.dot(v0 - eyepos) <= 0.0);
+        assert!(n.dot(v1 - eyepos) <= 0.0);
+        assert!(n.dot(v2 - eyepos) <= 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_default_mobius_strip_is_double_sided() {
+    let vertices = MobiusStripBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let (front, back) = vertices.split_at(vertices.len() / 2);
+    for (f, b) in front.chunks(6).zip(back.chunks(6)) {
+        for (fv, bv) in f.iter().zip(b.iter().rev()) {
+            assert_eq!(fv.position, bv.position);
+            assert_ulps_eq!(
+                Vector3::<f32>::from(fv.normal),
+                -Vector3::<f32>::from(bv.normal),
+                epsilon = 0.0001
+            );
+        }
+    }
+}
+
+#[test]
+pub fn ensure_mobius_strip_normals_follow_surface() {
+    let vertices = MobiusStripBuilder::new()
+        .length_segments(256)
+        .width_segments(8)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let n = (v1 - v0).cross(v2 - v0).normalize();
+        for vertex in chunk {
+            assert!(n.dot(Vector3::<f32>::from(vertex.normal)) > 0.99);
+        }
+    }
+}
+
+#[test]
+pub fn ensure_mobius_strip_has_half_twist_at_seam() {
+    let builder = MobiusStripBuilder::new()
+        .length_segments(8)
+        .width_segments(2);
+    let vertices = builder.build_vertices().expect("Failed to build vertices");
+
+    // The last quad of each column across the front of the band ends at the
+    // seam, where the band meets its start with its width reversed.
+    let front = &vertices[..vertices.len() / 2];
+    let first = &front[..2 * 6];
+    let last = &front[front.len() - 2 * 6..];
+    for &(a, b) in [(0, 1), (1, 0)].iter() {
+        assert_eq!(first[a * 6].position, last[b * 6 + 2].position);
+        assert_eq!(first[a * 6 + 5].position, last[b * 6 + 1].position);
+    }
+}
+
+#[test]
+pub fn ensure_mobius_strip_with_invalid_parameters_fails_to_build() {
+    match MobiusStripBuilder::new()
+        .length_segments(2)
+        .build_vertices()
+    {
+        Err(ShapeCreationError::NotEnoughDivisionsInU) => (),
+        _ => panic!("Expected NotEnoughDivisionsInU"),
+    }
+    match MobiusStripBuilder::new().width_segments(0).build_vertices() {
+        Err(ShapeCreationError::NotEnoughDivisionsInV) => (),
+        _ => panic!("Expected NotEnoughDivisionsInV"),
+    }
+    match MobiusStripBuilder::new().radius(0.0).build_vertices() {
+        Err(ShapeCreationError::InvalidRadii) => (),
+        _ => panic!("Expected InvalidRadii"),
+    }
+    match MobiusStripBuilder::new().width(1.0).build_vertices() {
+        Err(ShapeCreationError::InvalidDimensions) => (),
+        _ => panic!("Expected InvalidDimensions"),
+    }
+}
+
+#[test]
+pub fn ensure_mobius_strip_applies_late_pipeline() {
+    use crate::options::WithTexcoords;
+    use crate::uv_mapping::UvMapping;
+
+    crate::options::ensure_late_pipeline(
+        || MobiusStripBuilder::new().uv_mapping(UvMapping::Box),
+        MobiusStripBuilder::build_vertices,
+    );
+}
+
+#[test]
+pub fn ensure_mobius_strip_with_invalid_resolution_fails_to_build() {
+    crate::resolution::ensure_min_resolution(
+        MobiusStripBuilder::new,
+        MobiusStripBuilder::build_vertices,
+        ShapeCreationError::NotEnoughDivisionsInU,
+        Some(ShapeCreationError::NotEnoughDivisionsInV),
+    );
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_mobius_strip() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = MobiusStripBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
@@ -0,0 +1,132 @@
+//! A crate-private module for repeating shapes once they have been built.
+
+extern crate cgmath;
+
+use self::cgmath::*;
+use crate::axis::Axis;
+use crate::errors::ShapeCreationError;
+use crate::facet::reverse_winding;
+use crate::math::{Selected, Transformation};
+use crate::mirror_plane::MirrorPlane;
+use crate::vertex::Vertex;
+
+use std::f32;
+
+/// Describes copies of a shape that are made once it has been transformed.
+///
+/// The modifiers of a builder accumulate in call order, such that each one
+/// repeats the copies made by the modifiers requested before it.
+#[derive(Debug, Copy, Clone)]
+pub(crate) enum Modifier {
+    /// Copies spaced evenly around an axis.
+    ///
+    /// The first copy is pushed out from the axis by the radius, along the
+    /// positive z-axis about the y-axis, the positive y-axis about the x-axis,
+    /// and the positive x-axis about the z-axis (matching the seams of the
+    /// texture coordinate projections). Each subsequent copy is rotated a
+    /// further `2π / count` radians counter-clock-wise about the axis, when
+    /// viewed from its positive end.
+    RadialArray {
+        count: usize,
+        axis: Axis,
+        radius: f32,
+    },
+    /// Copies spaced evenly along a line, the first of which is left in place.
+    LinearArray { count: usize, offset: Vector3<f32> },
+    /// The shape itself, followed by its reflection across a plane. The
+    /// winding of the reflected triangles is reversed, such that they remain
+    /// front-facing.
+    Mirror { plane: MirrorPlane },
+}
+
+impl Modifier {
+    /// Returns the number of copies of a shape that are built by a list of
+    /// modifiers, which is 1 without any.
+    pub(crate) fn copies(modifiers: &[Modifier]) -> usize {
+        modifiers
+            .iter()
+            .map(|modifier| match *modifier {
+                Modifier::RadialArray { count, .. } => count,
+                Modifier::LinearArray { count, .. } => count,
+                Modifier::Mirror { .. } => 2,
+            })
+            .product()
+    }
+
+    /// Returns the transformation of each copy made by a list of modifiers,
+    /// in the order in which the copies are built.
+    pub(crate) fn matrices(
+        modifiers: &[Modifier],
+    ) -> Result<Vec<Matrix4<f32>>, ShapeCreationError> {
+        let mut matrices = vec![Matrix4::<f32>::identity()];
+        for modifier in modifiers {
+            let copies = modifier.copy_matrices()?;
+            matrices = copies
+                .iter()
+                .flat_map(|copy| matrices.iter().map(move |matrix| copy * matrix))
+                .collect();
+        }
+        Ok(matrices)
+    }
+
+    /// Replace a list of triangles with the copies made by a list of
+    /// modifiers, transforming their normals with the normal matrix of each
+    /// copy, and reversing the winding of the copies that are reflected.
+    pub(crate) fn apply(
+        modifiers: &[Modifier],
+        vertices: &mut Vec<Vertex>,
+    ) -> Result<(), ShapeCreationError> {
+        if modifiers.is_empty() {
+            return Ok(());
+        }
+        let original = std::mem::take(vertices);
+        for matrix in Modifier::matrices(modifiers)? {
+            let transform = Transformation::<Selected>::new(matrix);
+            let start = vertices.len();
+            vertices.extend(original.iter().map(|vertex| Vertex {
+                position: transform.point(vertex.position),
+                normal: transform.normal(vertex.normal),
+                texcoord: vertex.texcoord,
+            }));
+            if matrix.determinant() < 0.0 {
+                reverse_winding(&mut vertices[start..]);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the transformation of each copy made by this modifier alone.
+    fn copy_matrices(self) -> Result<Vec<Matrix4<f32>>, ShapeCreationError> {
+        match self {
+            Modifier::RadialArray {
+                count,
+                axis,
+                radius,
+            } => {
+                if count == 0 || !radius.is_finite() {
+                    return Err(ShapeCreationError::InvalidDimensions);
+                }
+                let mut direction = Vector3::<f32>::zero();
+                direction[(axis.index() + 1) % 3] = radius;
+                let mut unit = Vector3::<f32>::zero();
+                unit[axis.index()] = 1.0;
+                Ok((0..count)
+                    .map(|copy| {
+                        let angle = 2.0 * f32::consts::PI * copy as f32 / count as f32;
+                        Matrix4::from_axis_angle(unit, Rad(angle))
+                            * Matrix4::from_translation(direction)
+                    })
+                    .collect())
+            }
+            Modifier::LinearArray { count, offset } => {
+                if count == 0 || !(0..3).all(|axis| offset[axis].is_finite()) {
+                    return Err(ShapeCreationError::InvalidDimensions);
+                }
+                Ok((0..count)
+                    .map(|copy| Matrix4::from_translation(offset * copy as f32))
+                    .collect())
+            }
+            Modifier::Mirror { plane } => Ok(vec![Matrix4::<f32>::identity(), plane.matrix()]),
+        }
+    }
+}
@@ -0,0 +1,347 @@
+//! A module for constructing shapes that visualise the normals of other shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::errors::ShapeCreationError;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{HasOptions, HasVertices, ShapeOptions, WithColors, WithTransform};
+use crate::vertex::Vertex;
+
+/// A `NormalsVisualizer` object, made of one line per normal.
+///
+/// This object is constructed using a `NormalsVisualizerBuilder` object.
+pub struct NormalsVisualizer {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows a `NormalsVisualizer` object to be passed as a source of vertices.
+impl<'a> From<&'a NormalsVisualizer> for glium::vertex::VerticesSource<'a> {
+    fn from(visualizer: &'a NormalsVisualizer) -> glium::vertex::VerticesSource<'a> {
+        (&visualizer.vertices).into()
+    }
+}
+
+/// Allows a `NormalsVisualizer` object to be passed as a source of indices.
+impl<'a> From<&'a NormalsVisualizer> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a NormalsVisualizer) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::LinesList,
+        }
+    }
+}
+
+/// Responsible for building and returning a `NormalsVisualizer` object.
+///
+/// The visualizer is constructed as a list of lines, one from the position of
+/// each given vertex to its position plus its normal scaled by the given
+/// length, such that it may be drawn alongside the shape those vertices were
+/// built for. Normals are not normalised first, so that normals of the wrong
+/// magnitude are visible. Alternatively, the given vertices may be treated as
+/// a list of triangles, in which case one line is emitted from the centroid of
+/// each triangle along its face normal, such that faceting may be checked. By
+/// default, a single upward normal is visualised at the origin, with a length
+/// of 0.1. This can be overriden using the transformation methods on this
+/// object, which should match any applied when drawing the original shape.
+///
+/// Vertex normals store the visualised normal (transformed as a surface normal,
+/// but retaining its magnitude), and vertex texture coordinates map the u
+/// coordinate from 0 at the base of each line to 1 at its tip, with the v
+/// coordinate always 0.
+pub struct NormalsVisualizerBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    options: ShapeOptions,
+    vertices: Vec<Vertex>,
+    length: f32,
+    faces: bool,
+}
+
+impl Default for NormalsVisualizerBuilder {
+    fn default() -> Self {
+        NormalsVisualizerBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            options: ShapeOptions::default(),
+            vertices: vec![Vertex {
+                position: [0.0, 0.0, 0.0],
+                normal: [0.0, 1.0, 0.0],
+                texcoord: [0.0, 0.0],
+            }],
+            length: 0.1,
+            faces: false,
+        }
+    }
+}
+
+impl NormalsVisualizerBuilder {
+    /// Create a new `NormalsVisualizerBuilder` object.
+    pub fn new() -> NormalsVisualizerBuilder {
+        Default::default()
+    }
+
+    /// Specify the vertices whose normals are visualised, such as those
+    /// returned from the `build_vertices` method of another builder. At least
+    /// one vertex is required to build a valid visualizer, and when
+    /// visualising face normals, the number of vertices must be a multiple of
+    /// three.
+    pub fn vertices(mut self, vertices: &[Vertex]) -> Self {
+        self.vertices = vertices.to_vec();
+        self
+    }
+
+    /// Specify the length of a unit normal when visualised. By default, the
+    /// length is 0.1. The length must be greater than zero to build a valid
+    /// visualizer.
+    pub fn length(mut self, length: f32) -> Self {
+        self.length = length;
+        self
+    }
+
+    /// Specify whether to visualise one face normal at the centroid of each
+    /// triangle, rather than the normal of each vertex. By default, vertex
+    /// normals are visualised.
+    pub fn faces(mut self, faces: bool) -> Self {
+        self.faces = faces;
+        self
+    }
+
+    /// Build a new `NormalsVisualizer` object.
+    pub fn build<F>(self, display: &F) -> Result<NormalsVisualizer, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the visualizer vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// a `NormalsVisualizer` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.vertices_with::<Selected>()
+    }
+
+    /// Build the line segment vertices, transformed by the given math, before
+    /// the options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        if self.vertices.is_empty() {
+            return Err(ShapeCreationError::NotEnoughPoints);
+        }
+        if self.length <= 0.0 || !self.length.is_finite() {
+            return Err(ShapeCreationError::InvalidDimensions);
+        }
+        if self.faces && self.vertices.len() % 3 != 0 {
+            return Err(ShapeCreationError::InvalidDimensions);
+        }
+
+        // Find the base and normal of each line.
+        let lines = if self.faces {
+            self.vertices
+                .chunks(3)
+                .map(|triangle| {
+                    let [a, b, c] = [0, 1, 2].map(|k| Vector3::from(triangle[k].position));
+                    let centroid = (a + b + c) / 3.0;
+
+                    // Fall back to the mean of the vertex normals for triangles
+                    // with no area.
+                    let normal = (b - a).cross(c - a);
+                    let normal = if normal.magnitude2() > 0.0 {
+                        normal.normalize()
+                    } else {
+                        let sum = triangle
+                            .iter()
+                            .fold(Vector3::zero(), |sum, v| sum + Vector3::from(v.normal));
+                        if sum.magnitude2() > 0.0 {
+                            sum.normalize()
+                        } else {
+                            sum
+                        }
+                    };
+                    (centroid, normal)
+                })
+                .collect::<Vec<(Vector3<f32>, Vector3<f32>)>>()
+        } else {
+            self.vertices
+                .iter()
+                .map(|v| (Vector3::from(v.position), Vector3::from(v.normal)))
+                .collect::<Vec<(Vector3<f32>, Vector3<f32>)>>()
+        };
+
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<M>::new(self.matrix);
+
+        // Build the vertices, transforming each normal as a surface normal but
+        // retaining its magnitude.
+        let mut vertices = Vec::<Vertex>::with_capacity(self.num_vertices());
+        for (base, normal) in lines {
+            let base = Point3::<f32>::from(transform.point(base));
+            let normal = if normal.magnitude2() > 0.0 {
+                Vector3::<f32>::from(transform.normal(normal)) * normal.magnitude()
+            } else {
+                normal
+            };
+            let tip = base + normal * self.length;
+            for &(position, u) in [(base, 0.0), (tip, 1.0)].iter() {
+                vertices.push(Vertex {
+                    position: position.into(),
+                    normal: normal.into(),
+                    texcoord: [u, 0.0],
+                });
+            }
+        }
+
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    pub fn num_vertices(&self) -> usize {
+        if self.faces {
+            self.vertices.len() / 3 * 2
+        } else {
+            self.vertices.len() * 2
+        }
+    }
+}
+
+impl HasOptions for NormalsVisualizerBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for NormalsVisualizerBuilder {
+    type Shape = NormalsVisualizer;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> NormalsVisualizer {
+        NormalsVisualizer { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for NormalsVisualizerBuilder {}
+
+impl WithColors for NormalsVisualizerBuilder {}
+
+#[test]
+pub fn ensure_vertex_normals_are_visualized() {
+    let mesh = crate::sphere::SphereBuilder::new()
+        .build_vertices()
+        .expect("Failed to build sphere vertices");
+    let builder = NormalsVisualizerBuilder::new().vertices(&mesh).length(0.25);
+    let vertices = builder.build_vertices().expect("Failed to build vertices");
+    assert_eq!(vertices.len(), mesh.len() * 2);
+    assert_eq!(vertices.len(), builder.num_vertices());
+    for (line, vertex) in vertices.chunks(2).zip(mesh.iter()) {
+        assert_eq!(line[0].position, vertex.position);
+        let tip = Vector3::from(vertex.position) + Vector3::from(vertex.normal) * 0.25;
+        assert_ulps_eq!(Vector3::from(line[1].position), tip);
+        assert_eq!(line[0].texcoord, [0.0, 0.0]);
+        assert_eq!(line[1].texcoord, [1.0, 0.0]);
+    }
+}
+
+#[test]
+pub fn ensure_face_normals_are_visualized_at_centroids() {
+    let mesh = crate::cuboid::CuboidBuilder::new()
+        .build_vertices()
+        .expect("Failed to build cuboid vertices");
+    let builder = NormalsVisualizerBuilder::new().vertices(&mesh).faces(true);
+    let vertices = builder.build_vertices().expect("Failed to build vertices");
+    assert_eq!(vertices.len(), mesh.len() / 3 * 2);
+    assert_eq!(vertices.len(), builder.num_vertices());
+    for (line, triangle) in vertices.chunks(2).zip(mesh.chunks(3)) {
+        let centroid = triangle
+            .iter()
+            .fold(Vector3::zero(), |sum, v| sum + Vector3::from(v.position))
+            / 3.0;
+        assert_ulps_eq!(Vector3::from(line[0].position), centroid);
+        assert_ulps_eq!(
+            Vector3::from(line[0].normal),
+            Vector3::from(triangle[0].normal)
+        );
+        let length = (Vector3::from(line[1].position) - centroid).magnitude();
+        assert_ulps_eq!(length, 0.1);
+    }
+}
+
+#[test]
+pub fn ensure_visualized_normals_follow_transforms() {
+    let vertices = NormalsVisualizerBuilder::new()
+        .vertices(&[Vertex {
+            position: [0.0, 0.0, 0.0],
+            normal: [0.0, 2.0, 0.0],
+            texcoord: [0.0, 0.0],
+        }])
+        .translate(1.0, 0.0, 0.0)
+        .rotate_z(std::f32::consts::FRAC_PI_2)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    assert_ulps_eq!(
+        Vector3::from(vertices[0].position),
+        Vector3::new(0.0, 1.0, 0.0)
+    );
+    assert_ulps_eq!(
+        Vector3::from(vertices[0].normal),
+        Vector3::new(-2.0, 0.0, 0.0)
+    );
+    assert_ulps_eq!(
+        Vector3::from(vertices[1].position),
+        Vector3::new(-0.2, 1.0, 0.0)
+    );
+}
+
+#[test]
+pub fn ensure_normals_visualizer_with_invalid_parameters_fails_to_build() {
+    match NormalsVisualizerBuilder::new()
+        .vertices(&[])
+        .build_vertices()
+    {
+        Err(ShapeCreationError::NotEnoughPoints) => (),
+        _ => panic!("Expected NotEnoughPoints"),
+    }
+    match NormalsVisualizerBuilder::new().length(0.0).build_vertices() {
+        Err(ShapeCreationError::InvalidDimensions) => (),
+        _ => panic!("Expected InvalidDimensions"),
+    }
+    match NormalsVisualizerBuilder::new().faces(true).build_vertices() {
+        Err(ShapeCreationError::InvalidDimensions) => (),
+        _ => panic!("Expected InvalidDimensions"),
+    }
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_normals_visualizer() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = NormalsVisualizerBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
+
+#[test]
+pub fn ensure_colored_normals_visualizer_is_painted() {
+    crate::options::ensure_colors(NormalsVisualizerBuilder::new);
+}
@@ -0,0 +1,314 @@
+//! A module for constructing octahedron shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::errors::ShapeCreationError;
+use crate::facet::push_polygon;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{
+    BuildAs, HasOptions, HasVertices, ShapeOptions, WithBackFaces, WithColors, WithLightmap,
+    WithModifiers, WithOrientation, WithShading, WithTangents, WithTexcoords, WithTransform,
+};
+use crate::shading::Shading;
+use crate::vertex::Vertex;
+
+/// A polygonal `Octahedron` object.
+///
+/// This object is constructed using an `OctahedronBuilder` object.
+pub struct Octahedron {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows an `Octahedron` object to be passed as a source of vertices.
+impl<'a> From<&'a Octahedron> for glium::vertex::VerticesSource<'a> {
+    fn from(octahedron: &'a Octahedron) -> glium::vertex::VerticesSource<'a> {
+        (&octahedron.vertices).into()
+    }
+}
+
+/// Allows an `Octahedron` object to be passed as a source of indices.
+impl<'a> From<&'a Octahedron> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a Octahedron) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::TrianglesList,
+        }
+    }
+}
+
+/// Responsible for building and returning an `Octahedron` object.
+///
+/// By default, the octahedron is defined as a regular octahedron with its
+/// vertices located on the coordinate axes at a distance of 0.5 from the origin
+/// (such that it has unit diameter), with its centre-of-mass located at the
+/// origin. This can be overriden using the transformation methods on this
+/// object.
+///
+/// The resultant geometry is constructed to suit OpenGL defaults - assuming
+/// a right-handed coordinate system, front-facing polygons are defined in
+/// counter-clock-wise order. Vertex normals point in the direction of their
+/// respective face (such that the shape appears faceted when lit). Vertex
+/// texture coordinates define a planar-projection on each face.
+pub struct OctahedronBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    options: ShapeOptions,
+}
+
+impl Default for OctahedronBuilder {
+    fn default() -> Self {
+        OctahedronBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            options: ShapeOptions::default(),
+        }
+    }
+}
+
+impl OctahedronBuilder {
+    /// Create a new `OctahedronBuilder` object.
+    pub fn new() -> OctahedronBuilder {
+        Default::default()
+    }
+
+    /// Build a new `Octahedron` object.
+    pub fn build<F>(self, display: &F) -> Result<Octahedron, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the shape vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// an `Octahedron` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        let vertices = self.vertices_with::<Selected>()?;
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.options.check_shading(Self::SHADINGS)?;
+
+        // Define the vertices of the octahedron, which lie on each axis.
+        let points = [
+            Vector3::<f32>::new(0.5, 0.0, 0.0),
+            Vector3::<f32>::new(-0.5, 0.0, 0.0),
+            Vector3::<f32>::new(0.0, 0.5, 0.0),
+            Vector3::<f32>::new(0.0, -0.5, 0.0),
+            Vector3::<f32>::new(0.0, 0.0, 0.5),
+            Vector3::<f32>::new(0.0, 0.0, -0.5),
+        ];
+
+        // Define the faces of the octahedron, wound counter-clock-wise when
+        // viewed from outside.
+        let faces = [
+            [4, 0, 2],
+            [2, 0, 5],
+            [3, 0, 4],
+            [5, 0, 3],
+            [2, 1, 4],
+            [5, 1, 2],
+            [4, 1, 3],
+            [3, 1, 5],
+        ];
+
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<M>::new(self.matrix);
+
+        // Emit each face.
+        let mut vertices = Vec::<Vertex>::with_capacity(self.num_vertices());
+        for face in faces.iter() {
+            let polygon = face
+                .iter()
+                .map(|&i| points[i])
+                .collect::<Vec<Vector3<f32>>>();
+            push_polygon(&mut vertices, &transform, &polygon);
+        }
+
+        self.options.map_texcoords(&mut vertices);
+        Ok(vertices)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    pub fn num_vertices(&self) -> usize {
+        8 * 3 * self.options.copies()
+    }
+}
+
+impl HasOptions for OctahedronBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for OctahedronBuilder {
+    type Shape = Octahedron;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> Octahedron {
+        Octahedron { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for OctahedronBuilder {}
+
+impl BuildAs for OctahedronBuilder {}
+
+impl WithTangents for OctahedronBuilder {}
+
+impl WithLightmap for OctahedronBuilder {}
+
+impl WithOrientation for OctahedronBuilder {}
+
+impl WithBackFaces for OctahedronBuilder {}
+
+impl WithModifiers for OctahedronBuilder {}
+
+impl WithTexcoords for OctahedronBuilder {}
+
+impl WithColors for OctahedronBuilder {}
+
+impl WithShading for OctahedronBuilder {
+    /// Only flat shading is supported by the octahedron.
+    const SHADINGS: &'static [Shading] = &[Shading::Flat];
+}
+
+#[test]
+pub fn ensure_default_octahedron_has_centroid_at_origin() {
+    let vertices = OctahedronBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let mut sum = Vector3::<f32>::zero();
+    for vertex in &vertices {
+        sum += Vector3::<f32>::from(vertex.position);
+    }
+    assert_ulps_eq!(sum, Vector3::<f32>::zero(), epsilon = 0.0001);
+}
+
+#[test]
+pub fn ensure_default_octahedron_has_unit_outward_face_normals() {
+    let vertices = OctahedronBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let centroid = (Vector3::<f32>::from(chunk[0].position)
+            + Vector3::<f32>::from(chunk[1].position)
+            + Vector3::<f32>::from(chunk[2].position))
+            / 3.0;
+        for vertex in chunk {
+            let normal = Vector3::<f32>::from(vertex.normal);
+            assert!(abs_diff_eq!(normal.magnitude(), 1.0, epsilon = 0.0001));
+            assert!(normal.dot(centroid) > 0.0);
+        }
+    }
+}
+
+#[test]
+pub fn ensure_default_octahedron_has_ccw_triangles() {
+    let vertices = OctahedronBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let eyepos = v0 + Vector3::<f32>::from(chunk[0].normal);
+        let e0 = v1 - v0;
+        let e1 = v2 - v0;
+        let n = e0.cross(e1);
+        assert!(n.dot(v0 - eyepos) <= 0.0);
+        assert!(n.dot(v1 - eyepos) <= 0.0);
+        assert!(n.dot(v2 - eyepos) <= 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_default_octahedron_has_faceted_normals() {
+    let vertices = OctahedronBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let n = (v1 - v0).cross(v2 - v0).normalize();
+        for vertex in chunk {
+            assert_ulps_eq!(n, Vector3::<f32>::from(vertex.normal), epsilon = 0.0001);
+        }
+    }
+}
+
+#[test]
+pub fn ensure_default_octahedron_has_uvs_in_unit_range() {
+    let vertices = OctahedronBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        assert!(vertex.texcoord[0] >= -0.0001 && vertex.texcoord[0] <= 1.0001);
+        assert!(vertex.texcoord[1] >= -0.0001 && vertex.texcoord[1] <= 1.0001);
+    }
+}
+
+#[test]
+pub fn ensure_default_octahedron_has_vertices_on_axes() {
+    let vertices = OctahedronBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    assert_eq!(vertices.len(), 24);
+    for vertex in &vertices {
+        let pos = Vector3::<f32>::from(vertex.position);
+        let mut components = [pos.x.abs(), pos.y.abs(), pos.z.abs()];
+        components.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(components, [0.0, 0.0, 0.5]);
+    }
+}
+
+#[test]
+pub fn ensure_octahedron_applies_late_pipeline() {
+    use crate::options::WithTexcoords;
+    use crate::uv_mapping::UvMapping;
+
+    crate::options::ensure_late_pipeline(
+        || {
+            OctahedronBuilder::new()
+                .uv_mapping(UvMapping::Box)
+                .double_sided()
+        },
+        OctahedronBuilder::build_vertices,
+    );
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_octahedron() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = OctahedronBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
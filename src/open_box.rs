@@ -0,0 +1,403 @@
+//! A module for constructing open box shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::cuboid::push_face;
+use crate::errors::ShapeCreationError;
+use crate::facet::push_polygon;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{
+    BuildAs, HasOptions, HasVertices, ShapeOptions, WithBackFaces, WithColors, WithLightmap,
+    WithModifiers, WithOrientation, WithShading, WithTangents, WithTexcoords, WithTransform,
+};
+use crate::shading::Shading;
+use crate::vertex::Vertex;
+
+/// A polygonal `OpenBox` object.
+///
+/// This object is constructed using an `OpenBoxBuilder` object.
+pub struct OpenBox {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows an `OpenBox` object to be passed as a source of vertices.
+impl<'a> From<&'a OpenBox> for glium::vertex::VerticesSource<'a> {
+    fn from(open_box: &'a OpenBox) -> glium::vertex::VerticesSource<'a> {
+        (&open_box.vertices).into()
+    }
+}
+
+/// Allows an `OpenBox` object to be passed as a source of indices.
+impl<'a> From<&'a OpenBox> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a OpenBox) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::TrianglesList,
+        }
+    }
+}
+
+/// Responsible for building and returning an `OpenBox` object.
+///
+/// By default, the open box is defined as a unit-cube with its centre located
+/// at the origin, whose floor and four walls are 0.1 thick, and which is open
+/// towards the positive y-axis. This can be overriden using the transformation
+/// methods on this object.
+///
+/// The box is emitted as a single solid - the exterior of the walls and floor,
+/// followed by the interior of the walls and floor, followed by the rim along
+/// the top of the walls. The rim is mitred at the corners of the box, such
+/// that every piece shares its edges with its neighbours and the mesh is
+/// watertight. The resultant geometry is constructed to suit OpenGL defaults -
+/// assuming a right-handed coordinate system, front-facing polygons are
+/// defined in counter-clock-wise order. Vertex normals point in the direction
+/// of their respective face (such that the shape appears faceted when lit).
+/// Vertex texture coordinates define a planar-projection on each face.
+pub struct OpenBoxBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    width: f32,
+    height: f32,
+    depth: f32,
+    thickness: f32,
+    options: ShapeOptions,
+}
+
+impl Default for OpenBoxBuilder {
+    fn default() -> Self {
+        OpenBoxBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            width: 1.0,
+            height: 1.0,
+            depth: 1.0,
+            thickness: 0.1,
+            options: ShapeOptions::default(),
+        }
+    }
+}
+
+impl OpenBoxBuilder {
+    /// Create a new `OpenBoxBuilder` object.
+    pub fn new() -> OpenBoxBuilder {
+        Default::default()
+    }
+
+    /// Specify the total width of the box along the x-axis. By default, the
+    /// width is 1. The width must be greater than twice the wall thickness to
+    /// build a valid box.
+    pub fn width(mut self, width: f32) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Specify the total height of the box along the y-axis, including the
+    /// floor. By default, the height is 1. The height must be greater than the
+    /// wall thickness to build a valid box.
+    pub fn height(mut self, height: f32) -> Self {
+        self.height = height;
+        self
+    }
+
+    /// Specify the total depth of the box along the z-axis. By default, the
+    /// depth is 1. The depth must be greater than twice the wall thickness to
+    /// build a valid box.
+    pub fn depth(mut self, depth: f32) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    /// Specify the thickness of the floor and walls of the box. By default, the
+    /// thickness is 0.1. The thickness must be greater than zero, and less
+    /// than half the width and depth of the box, to build a valid box.
+    pub fn thickness(mut self, thickness: f32) -> Self {
+        self.thickness = thickness;
+        self
+    }
+
+    /// Build a new `OpenBox` object.
+    pub fn build<F>(self, display: &F) -> Result<OpenBox, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the shape vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// an `OpenBox` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        let vertices = self.vertices_with::<Selected>()?;
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.options.check_shading(Self::SHADINGS)?;
+
+        // Ensure the dimensions produce a box with a cavity.
+        let valid = |x: f32| x.is_finite() && x > 0.0;
+        if !valid(self.width) || !valid(self.height) || !valid(self.depth) {
+            return Err(ShapeCreationError::InvalidDimensions);
+        }
+        let thickness = self.thickness;
+        if !valid(thickness)
+            || thickness * 2.0 >= self.width
+            || thickness * 2.0 >= self.depth
+            || thickness >= self.height
+        {
+            return Err(ShapeCreationError::InvalidDimensions);
+        }
+
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<M>::new(self.matrix);
+
+        // Compute the bounds of the exterior of the box, and of its cavity.
+        let outer_max = Vector3::<f32>::new(self.width, self.height, self.depth) * 0.5;
+        let outer_min = -outer_max;
+        let inner_min = outer_min + Vector3::<f32>::from_value(thickness);
+        let inner_max = Vector3::<f32>::new(
+            outer_max.x - thickness,
+            outer_max.y,
+            outer_max.z - thickness,
+        );
+        let mut vertices = Vec::<Vertex>::with_capacity(self.num_vertices());
+
+        // Emit the exterior of the walls and floor, omitting the open top.
+        for &side in [0, 1, 2, 4, 5].iter() {
+            push_face(&mut vertices, &transform, side, outer_min, outer_max);
+        }
+
+        // Emit the interior of the walls and floor, each of which faces the
+        // opposite side of the cavity. An interior face is emitted as the
+        // opposing side of a box that is flat along the axis of the face.
+        for &side in [0, 1, 2, 4, 5].iter() {
+            let axis = side / 2;
+            let (mut min, mut max) = (inner_min, inner_max);
+            if side % 2 == 0 {
+                max[axis] = min[axis];
+            } else {
+                min[axis] = max[axis];
+            }
+            push_face(&mut vertices, &transform, side ^ 1, min, max);
+        }
+
+        // Emit the rim between the top of the exterior and interior walls, as
+        // one trapezoid per wall. The corners of the rim are ordered counter-
+        // clock-wise when viewed from the positive y-axis.
+        let top = outer_max.y;
+        let corner = |min: Vector3<f32>, max: Vector3<f32>, index: usize| {
+            let (x, z) = [
+                (min.x, min.z),
+                (min.x, max.z),
+                (max.x, max.z),
+                (max.x, min.z),
+            ][index];
+            Vector3::<f32>::new(x, top, z)
+        };
+        for index in 0..4 {
+            let next = (index + 1) % 4;
+            push_polygon(
+                &mut vertices,
+                &transform,
+                &[
+                    corner(outer_min, outer_max, index),
+                    corner(outer_min, outer_max, next),
+                    corner(inner_min, inner_max, next),
+                    corner(inner_min, inner_max, index),
+                ],
+            );
+        }
+
+        self.options.map_texcoords(&mut vertices);
+        Ok(vertices)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    pub fn num_vertices(&self) -> usize {
+        (5 + 5 + 4) * 6 * self.options.copies()
+    }
+}
+
+impl HasOptions for OpenBoxBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for OpenBoxBuilder {
+    type Shape = OpenBox;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> OpenBox {
+        OpenBox { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for OpenBoxBuilder {}
+
+impl BuildAs for OpenBoxBuilder {}
+
+impl WithTangents for OpenBoxBuilder {}
+
+impl WithLightmap for OpenBoxBuilder {}
+
+impl WithOrientation for OpenBoxBuilder {}
+
+impl WithBackFaces for OpenBoxBuilder {}
+
+impl WithModifiers for OpenBoxBuilder {}
+
+impl WithTexcoords for OpenBoxBuilder {}
+
+impl WithColors for OpenBoxBuilder {}
+
+impl WithShading for OpenBoxBuilder {
+    /// Only flat shading is supported by the open box.
+    const SHADINGS: &'static [Shading] = &[Shading::Flat];
+}
+
+#[test]
+pub fn ensure_default_open_box_fits_unit_cube() {
+    let vertices = OpenBoxBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let mut min = Vector3::<f32>::from_value(1.0);
+    let mut max = Vector3::<f32>::from_value(-1.0);
+    for vertex in &vertices {
+        for axis in 0..3 {
+            min[axis] = min[axis].min(vertex.position[axis]);
+            max[axis] = max[axis].max(vertex.position[axis]);
+        }
+    }
+    assert_ulps_eq!(min, Vector3::<f32>::from_value(-0.5));
+    assert_ulps_eq!(max, Vector3::<f32>::from_value(0.5));
+}
+
+#[test]
+pub fn ensure_open_box_is_watertight() {
+    use std::collections::HashMap;
+    let vertices = OpenBoxBuilder::new()
+        .width(2.0)
+        .height(0.5)
+        .depth(1.5)
+        .thickness(0.2)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let mut edges = HashMap::<([u32; 3], [u32; 3]), usize>::new();
+    for chunk in vertices.chunks(3) {
+        for k in 0..3 {
+            let a = chunk[k].position.map(f32::to_bits);
+            let b = chunk[(k + 1) % 3].position.map(f32::to_bits);
+            *edges
+                .entry(if a < b { (a, b) } else { (b, a) })
+                .or_insert(0) += 1;
+        }
+    }
+    assert!(edges.values().all(|&count| count == 2));
+}
+
+#[test]
+pub fn ensure_default_open_box_has_outward_facing_normals() {
+    let vertices = OpenBoxBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let centroid = (Vector3::<f32>::from(chunk[0].position)
+            + Vector3::<f32>::from(chunk[1].position)
+            + Vector3::<f32>::from(chunk[2].position))
+            / 3.0;
+        let normal = Vector3::<f32>::from(chunk[0].normal);
+
+        // Each face is on the boundary of the walls or floor, such that
+        // stepping a short distance along its normal leaves the solid.
+        let outside = centroid + normal * 0.01;
+        let within_box = outside.x.abs() < 0.5 && outside.y.abs() < 0.5 && outside.z.abs() < 0.5;
+        let within_cavity = outside.x.abs() < 0.4 && outside.y > -0.4 && outside.z.abs() < 0.4;
+        assert!(!within_box || within_cavity);
+    }
+}
+
+#[test]
+pub fn ensure_default_open_box_has_ccw_triangles() {
+    let vertices = OpenBoxBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let eyepos = v0 + Vector3::<f32>::from(chunk[0].normal);
+        let e0 = v1 - v0;
+        let e1 = v2 - v0;
+        let n = e0.cross(e1);
+        assert!(n.dot(v0 - eyepos) < 0.0);
+        assert!(n.dot(v1 - eyepos) < 0.0);
+        assert!(n.dot(v2 - eyepos) < 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_open_box_with_invalid_thickness_fails_to_build() {
+    for builder in [
+        OpenBoxBuilder::new().thickness(0.0),
+        OpenBoxBuilder::new().thickness(0.5),
+        OpenBoxBuilder::new().depth(0.3).thickness(0.2),
+        OpenBoxBuilder::new().height(0.1),
+    ]
+    .iter()
+    {
+        match builder.build_vertices() {
+            Err(ShapeCreationError::InvalidDimensions) => (),
+            _ => panic!("Expected InvalidDimensions"),
+        }
+    }
+}
+
+#[test]
+pub fn ensure_open_box_applies_late_pipeline() {
+    use crate::options::WithTexcoords;
+    use crate::uv_mapping::UvMapping;
+
+    crate::options::ensure_late_pipeline(
+        || {
+            OpenBoxBuilder::new()
+                .uv_mapping(UvMapping::Box)
+                .double_sided()
+        },
+        OpenBoxBuilder::build_vertices,
+    );
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_open_box() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = OpenBoxBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
@@ -0,0 +1,802 @@
+//! A module containing the options shared by the builders of surface shapes,
+//! which are applied to the triangles of a shape once it has been built and
+//! transformed.
+//!
+//! The shapes are transformed through the functions of the `WithTransform`
+//! trait, and the options are set through the functions of the
+//! `WithOrientation`, `WithBackFaces`, `WithModifiers`, `WithCaps`,
+//! `WithTexcoords`, `WithShading`, and `WithColors` traits, which must be in
+//! scope to be called, as must the `BuildAs`, `WithTangents`, and
+//! `WithLightmap` traits to build a shape from a vertex type of your own, with
+//! tangents, or with lightmap texture coordinates. Each builder implements the
+//! traits whose options suit its shape.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::axis::Axis;
+use crate::cap_mode::CapMode;
+use crate::coloring::Colors;
+use crate::convention::Convention;
+use crate::errors::ShapeCreationError;
+use crate::facet::{negate_normals, offset_along_normals, push_back_faces, reverse_winding};
+use crate::jitter::Jitter;
+use crate::lightmap::with_lightmap_uvs;
+use crate::math::{Math, Selected};
+use crate::mirror_plane::MirrorPlane;
+use crate::modifier::Modifier;
+use crate::rotation::{rotation_matrix, IntoQuaternion};
+use crate::shading::Shading;
+use crate::tangent::with_tangents;
+use crate::uv_mapping::{transform_texcoords, UvMapping};
+use crate::vertex::{Vertex, VertexColored, VertexLightmapped, VertexTangent};
+use crate::winding::Winding;
+
+pub(crate) use self::sealed::{HasOptions, HasVertices, ShapeOptions};
+
+mod sealed {
+    use super::*;
+
+    /// The options shared by the builders, most of which are applied once the
+    /// shape of a builder has been built and transformed.
+    #[derive(Debug, Clone)]
+    pub struct ShapeOptions {
+        pub(crate) double_sided: bool,
+        pub(crate) flip_normals: bool,
+        pub(crate) winding: Winding,
+        pub(crate) convention: Convention,
+        pub(crate) jitter: Option<Jitter>,
+        pub(crate) normal_offset: f32,
+        pub(crate) modifiers: Vec<Modifier>,
+        pub(crate) caps: CapMode,
+        pub(crate) uv_mapping: Option<UvMapping>,
+        pub(crate) uv_matrix: Matrix3<f32>,
+        pub(crate) shading: Shading,
+        pub(crate) colors: Option<Colors>,
+        pub(crate) lightmap_gutter: Option<f32>,
+    }
+
+    /// Gives the option traits access to the options of a builder, such that
+    /// they may only be implemented within this crate.
+    pub trait HasOptions {
+        /// Returns the options of the builder.
+        fn options(&self) -> &ShapeOptions;
+
+        /// Returns the options of the builder, such that they may be set.
+        fn options_mut(&mut self) -> &mut ShapeOptions;
+    }
+
+    /// Gives the build traits access to the vertices of a builder, such that
+    /// they may only be implemented within this crate.
+    pub trait HasVertices: HasOptions {
+        /// The shape built by the builder.
+        type Shape;
+
+        /// Returns the shape built by the builder from the given vertices.
+        fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> Self::Shape;
+
+        /// Returns the transformation of the builder.
+        fn matrix(&self) -> Matrix4<f32>;
+
+        /// Returns the transformation of the builder, such that it may be set.
+        fn matrix_mut(&mut self) -> &mut Matrix4<f32>;
+
+        /// Build the vertices of the shape, transformed by the given math,
+        /// before the late options are applied.
+        fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError>;
+
+        /// Apply the late options to the vertices of the shape, once they have
+        /// been built by `surface_with`.
+        fn finish(&self, vertices: &mut Vec<Vertex>) -> Result<(), ShapeCreationError> {
+            self.options().apply(&self.matrix(), vertices)
+        }
+
+        /// Build the vertices of the shape, transformed by the given math, from
+        /// which the vertices of each build variant are derived.
+        fn vertices_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+            let mut vertices = self.surface_with::<M>()?;
+            self.finish(&mut vertices)?;
+            Ok(vertices)
+        }
+
+        /// Returns whether the shape has been colored.
+        fn is_colored(&self) -> bool {
+            self.options().colors.is_some()
+        }
+
+        /// Build the vertex buffer of the shape. Its vertices are of the
+        /// `VertexLightmapped` type if the shape has been lightmapped.
+        /// Otherwise, if the shape has been colored, its vertices are of the
+        /// `VertexColored` type, rather than the `Vertex` type.
+        fn buffer<F>(
+            &self,
+            display: &F,
+        ) -> Result<glium::vertex::VertexBufferAny, ShapeCreationError>
+        where
+            Self: WithColors,
+            F: glium::backend::Facade,
+        {
+            let vertices = if let Some(gutter) = self.options().lightmap_gutter {
+                let vertices = &with_lightmap_uvs(&self.vertices_with::<Selected>()?, gutter)?;
+                glium::vertex::VertexBuffer::<VertexLightmapped>::new(display, vertices)?.into()
+            } else if self.is_colored() {
+                let vertices = &self.build_vertices_colored()?;
+                glium::vertex::VertexBuffer::<VertexColored>::new(display, vertices)?.into()
+            } else {
+                let vertices = &self.vertices_with::<Selected>()?;
+                glium::vertex::VertexBuffer::<Vertex>::new(display, vertices)?.into()
+            };
+            Ok(vertices)
+        }
+    }
+}
+
+impl Default for ShapeOptions {
+    fn default() -> Self {
+        ShapeOptions {
+            double_sided: false,
+            flip_normals: false,
+            winding: Winding::Ccw,
+            convention: Convention::YUpRightHanded,
+            jitter: None,
+            normal_offset: 0.0,
+            modifiers: Vec::new(),
+            caps: CapMode::Both,
+            uv_mapping: None,
+            uv_matrix: Matrix3::identity(),
+            shading: Shading::Flat,
+            colors: None,
+            lightmap_gutter: None,
+        }
+    }
+}
+
+impl ShapeOptions {
+    /// Returns the number of triangles that are built for each triangle of the
+    /// shape, over both of its sides and all of its copies.
+    pub(crate) fn copies(&self) -> usize {
+        let sides = if self.double_sided { 2 } else { 1 };
+        sides * Modifier::copies(&self.modifiers)
+    }
+
+    /// Apply the options to a list of triangles, which have been transformed by
+    /// the given matrix. The back faces are appended first, followed by the
+    /// jitter, the offset along the normals, and the modifiers, after which the
+    /// triangles are converted into the convention, rewound, and flipped.
+    pub(crate) fn apply(
+        &self,
+        matrix: &Matrix4<f32>,
+        vertices: &mut Vec<Vertex>,
+    ) -> Result<(), ShapeCreationError> {
+        if self.double_sided {
+            push_back_faces(vertices);
+        }
+        if let Some(jitter) = self.jitter {
+            jitter.apply(matrix, vertices);
+        }
+        offset_along_normals(self.normal_offset, vertices);
+        Modifier::apply(&self.modifiers, vertices)?;
+        self.convention.apply(vertices);
+        if self.winding == Winding::Cw {
+            reverse_winding(vertices);
+        }
+        if self.flip_normals {
+            negate_normals(vertices);
+        }
+        Ok(())
+    }
+
+    /// Replace the texture coordinates of a list of triangles with those of the
+    /// projection, if one is given, and then transform them.
+    pub(crate) fn map_texcoords(&self, vertices: &mut [Vertex]) {
+        if let Some(uv_mapping) = self.uv_mapping {
+            uv_mapping.apply(vertices);
+        }
+        transform_texcoords(&self.uv_matrix, vertices);
+    }
+
+    /// Ensure that no projection is given to a shape whose triangles share
+    /// vertices, which the projection may map differently for each triangle.
+    pub(crate) fn check_uv_mapping(&self) -> Result<(), ShapeCreationError> {
+        match self.uv_mapping {
+            Some(_) => Err(ShapeCreationError::UnsupportedUvMapping),
+            None => Ok(()),
+        }
+    }
+
+    /// Ensure that the shading mode is one of those supported by a shape.
+    pub(crate) fn check_shading(&self, supported: &[Shading]) -> Result<(), ShapeCreationError> {
+        if supported.contains(&self.shading) {
+            Ok(())
+        } else {
+            Err(ShapeCreationError::UnsupportedShading)
+        }
+    }
+
+    /// Convert the vertices of a triangle strip into the convention, and
+    /// reverse the winding of both of its triangles when required by swapping
+    /// its middle vertices (which keeps the diagonal shared by the triangles).
+    /// The winding is reversed once more by a left-handed convention.
+    pub(crate) fn orient_strip(&self, vertices: &mut [Vertex]) {
+        self.convention.convert(vertices);
+        if (self.winding == Winding::Cw) ^ self.convention.is_left_handed() {
+            vertices.swap(1, 2);
+        }
+    }
+}
+
+/// Implemented by the builders of all shapes, allowing them to be transformed
+/// in the same way for all of them.
+///
+/// The `scale`, `translate`, and `rotate` functions accumulate, and are not
+/// commutative. The transformation functions are intended to provide
+/// flexibility in model-space. For per-instance world-space transformations,
+/// one should prefer to share as few shapes as possible across multiple
+/// instances, and instead rely on uniform constants in the shader and/or
+/// instanced drawing.
+pub trait WithTransform: HasVertices + Sized {
+    /// Apply a scaling transformation to the shape.
+    fn scale(mut self, x: f32, y: f32, z: f32) -> Self {
+        let matrix = Matrix4::from_nonuniform_scale(x, y, z) * self.matrix();
+        *self.matrix_mut() = matrix;
+        self
+    }
+
+    /// Apply a translation transformation to the shape.
+    fn translate(mut self, x: f32, y: f32, z: f32) -> Self {
+        let matrix = Matrix4::from_translation([x, y, z].into()) * self.matrix();
+        *self.matrix_mut() = matrix;
+        self
+    }
+
+    /// Apply a rotation transformation to the shape about the x-axis.
+    fn rotate_x(mut self, radians: f32) -> Self {
+        let rotation = Matrix3::<f32>::from_angle_x(Rad::<f32>(radians));
+        let matrix = Matrix4::<f32>::from(rotation) * self.matrix();
+        *self.matrix_mut() = matrix;
+        self
+    }
+
+    /// Apply a rotation transformation to the shape about the y-axis.
+    fn rotate_y(mut self, radians: f32) -> Self {
+        let rotation = Matrix3::<f32>::from_angle_y(Rad::<f32>(radians));
+        let matrix = Matrix4::<f32>::from(rotation) * self.matrix();
+        *self.matrix_mut() = matrix;
+        self
+    }
+
+    /// Apply a rotation transformation to the shape about the z-axis.
+    fn rotate_z(mut self, radians: f32) -> Self {
+        let rotation = Matrix3::<f32>::from_angle_z(Rad::<f32>(radians));
+        let matrix = Matrix4::<f32>::from(rotation) * self.matrix();
+        *self.matrix_mut() = matrix;
+        self
+    }
+
+    /// Apply a rotation transformation to the shape, given as a unit quaternion
+    /// of any type that implements `IntoQuaternion`.
+    fn rotate(mut self, rotation: impl IntoQuaternion) -> Self {
+        let matrix = rotation_matrix(rotation) * self.matrix();
+        *self.matrix_mut() = matrix;
+        self
+    }
+}
+
+/// Implemented by the builders of surface shapes, allowing the orientation of
+/// their triangles to be specified in the same way for all of them.
+pub trait WithOrientation: HasOptions + Sized {
+    /// Flip the normals of the shape, such that each points in the opposite
+    /// direction. The positions, texture coordinates, and winding of the
+    /// triangles are left as they are.
+    fn flip_normals(mut self) -> Self {
+        self.options_mut().flip_normals = true;
+        self
+    }
+
+    /// Specify the winding order of the front faces of the shape. By default,
+    /// the front faces are wound counter-clock-wise. Clock-wise winding
+    /// reverses the order of the vertices of every triangle, leaving the
+    /// normals pointing outwards.
+    fn winding(mut self, winding: Winding) -> Self {
+        self.options_mut().winding = winding;
+        self
+    }
+
+    /// Specify the coordinate convention in which the shape is output. By
+    /// default, the Y-up right-handed convention of OpenGL is used. The
+    /// conversion is applied after all of the transformations of the shape,
+    /// which are given in the Y-up right-handed convention.
+    fn convention(mut self, convention: Convention) -> Self {
+        self.options_mut().convention = convention;
+        self
+    }
+}
+
+/// Implemented by the builders of open surface shapes, allowing them to be
+/// made double-sided in the same way for all of them.
+pub trait WithBackFaces: HasOptions + Sized {
+    /// Make the shape double-sided. The triangles of the front faces are left
+    /// as they are, and are followed by a back face for each triangle, which
+    /// shares the positions and texture coordinates of the front face but has
+    /// reversed winding and flipped normals.
+    fn double_sided(mut self) -> Self {
+        self.options_mut().double_sided = true;
+        self
+    }
+}
+
+/// Implemented by the builders of shapes that are built as lists of
+/// triangles, allowing their triangles to be displaced and repeated in the
+/// same way for all of them.
+pub trait WithModifiers: HasOptions + Sized {
+    /// Displace every vertex of the shape by a pseudo-random offset, each
+    /// component of which lies within the given amplitude prior to the
+    /// transformations of the shape. The offset is hashed from the position of
+    /// the vertex and the seed, such that the same seed always builds the same
+    /// shape, and vertices that share a position move together. The normals
+    /// are then recomputed from the displaced triangles, such that the shape
+    /// appears faceted. An amplitude of zero leaves the shape as it is.
+    fn jitter(mut self, amplitude: f32, seed: u32) -> Self {
+        self.options_mut().jitter = Some(Jitter { amplitude, seed });
+        self
+    }
+
+    /// Move every vertex of the shape along its normal by the given distance,
+    /// after the transformations of the shape, such that a positive distance
+    /// inflates the shape and a negative distance shrinks it. The normals
+    /// themselves are left unchanged. Vertices that share a position but not a
+    /// normal separate, such that a faceted shape opens at its edges.
+    fn offset_along_normals(mut self, distance: f32) -> Self {
+        self.options_mut().normal_offset = distance;
+        self
+    }
+
+    /// Repeat the shape `count` times around the given axis, merging the copies
+    /// into a single shape. Each copy is pushed out from the axis by the given
+    /// radius, and the `i`th copy is then rotated by `2π * i / count` radians
+    /// about the axis, after all of the transformations of the shape have been
+    /// applied. The first copy is pushed out along the positive z-axis about
+    /// the y-axis, the positive y-axis about the x-axis, and the positive
+    /// x-axis about the z-axis.
+    ///
+    /// The positions and normals of each copy are rotated together, and its
+    /// texture coordinates are left as they are. The arrays accumulate in call
+    /// order, such that each array repeats the copies made by the arrays
+    /// requested before it. A count of zero fails to build with
+    /// `ShapeCreationError::InvalidDimensions`.
+    fn radial_array(mut self, count: usize, axis: Axis, radius: f32) -> Self {
+        self.options_mut().modifiers.push(Modifier::RadialArray {
+            count,
+            axis,
+            radius,
+        });
+        self
+    }
+
+    /// Repeat the shape `count` times along a line, merging the copies into a
+    /// single shape. The `i`th copy is translated by `i * offset`, after all of
+    /// the transformations of the shape have been applied, such that the first
+    /// copy is left in place.
+    ///
+    /// The arrays accumulate in call order, such that each array repeats the
+    /// copies made by the arrays requested before it. A count of zero fails to
+    /// build with `ShapeCreationError::InvalidDimensions`.
+    fn linear_array(mut self, count: usize, offset: impl Into<Vector3<f32>>) -> Self {
+        let offset = offset.into();
+        self.options_mut()
+            .modifiers
+            .push(Modifier::LinearArray { count, offset });
+        self
+    }
+
+    /// Append the reflection of the shape across the given plane to the shape,
+    /// after all of the transformations of the shape have been applied. The
+    /// winding of the reflected triangles is reversed and their normals are
+    /// reflected, such that both halves remain front-facing with outward
+    /// normals. Vertices that lie on the plane are duplicated rather than
+    /// shared between the halves.
+    ///
+    /// The mirror accumulates with the arrays in call order, such that it
+    /// reflects the copies made by the arrays requested before it.
+    fn mirror(mut self, plane: MirrorPlane) -> Self {
+        self.options_mut()
+            .modifiers
+            .push(Modifier::Mirror { plane });
+        self
+    }
+}
+
+/// Implemented by the builders of shapes with open ends, allowing the ends to
+/// be closed with caps in the same way for all of them. Each builder documents
+/// which of its ends are its start and its end.
+pub trait WithCaps: HasOptions + Sized {
+    /// Specify which ends of the shape are closed with flat caps. By default,
+    /// both ends are capped.
+    fn caps(mut self, caps: CapMode) -> Self {
+        self.options_mut().caps = caps;
+        self
+    }
+}
+
+/// Implemented by the builders of surface shapes, allowing their texture
+/// coordinates to be transformed in the same way for all of them.
+pub trait WithTexcoords: HasOptions + Sized {
+    /// Specify a projection from which the texture coordinates are computed, in
+    /// place of the native texture coordinates of the shape. By default, the
+    /// native texture coordinates are used. Shapes whose triangles share
+    /// vertices, such as triangle strips and indexed shapes, may map a shared
+    /// vertex differently for each of its triangles, so they fail to build
+    /// with `ShapeCreationError::UnsupportedUvMapping` if a projection is
+    /// given.
+    fn uv_mapping(mut self, uv_mapping: UvMapping) -> Self {
+        self.options_mut().uv_mapping = Some(uv_mapping);
+        self
+    }
+
+    /// Apply a translation to the texture coordinates of the shape.
+    ///
+    /// The `uv_offset`, `uv_scale`, and `uv_rotate` functions accumulate, and
+    /// are not commutative, in the same way as the spatial transformation
+    /// functions. The texture coordinate transformation is applied to every
+    /// texture coordinate of the shape, after any `uv_mapping` projection.
+    fn uv_offset(mut self, u: f32, v: f32) -> Self {
+        let translation = Matrix3::new(1.0, 0.0, 0.0, 0.0, 1.0, 0.0, u, v, 1.0);
+        let options = self.options_mut();
+        options.uv_matrix = translation * options.uv_matrix;
+        self
+    }
+
+    /// Apply a scaling to the texture coordinates of the shape.
+    ///
+    /// The `uv_offset`, `uv_scale`, and `uv_rotate` functions accumulate, and
+    /// are not commutative, in the same way as the spatial transformation
+    /// functions. The texture coordinate transformation is applied to every
+    /// texture coordinate of the shape, after any `uv_mapping` projection.
+    fn uv_scale(mut self, u: f32, v: f32) -> Self {
+        let scale = Matrix3::new(u, 0.0, 0.0, 0.0, v, 0.0, 0.0, 0.0, 1.0);
+        let options = self.options_mut();
+        options.uv_matrix = scale * options.uv_matrix;
+        self
+    }
+
+    /// Apply a counter-clock-wise rotation about the origin to the texture
+    /// coordinates of the shape.
+    ///
+    /// The `uv_offset`, `uv_scale`, and `uv_rotate` functions accumulate, and
+    /// are not commutative, in the same way as the spatial transformation
+    /// functions. The texture coordinate transformation is applied to every
+    /// texture coordinate of the shape, after any `uv_mapping` projection.
+    fn uv_rotate(mut self, radians: f32) -> Self {
+        let rotation = Matrix3::from_angle_z(Rad(radians));
+        let options = self.options_mut();
+        options.uv_matrix = rotation * options.uv_matrix;
+        self
+    }
+
+    /// Mirror the texture coordinates of the shape along the u axis, such that
+    /// each u coordinate becomes 1 - u.
+    ///
+    /// The flip accumulates with the `uv_offset`, `uv_scale`, and `uv_rotate`
+    /// functions, and applies to the texture coordinates as transformed by the
+    /// functions called before it.
+    fn flip_u(mut self) -> Self {
+        let flip = Matrix3::new(-1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0);
+        let options = self.options_mut();
+        options.uv_matrix = flip * options.uv_matrix;
+        self
+    }
+
+    /// Mirror the texture coordinates of the shape along the v axis, such that
+    /// each v coordinate becomes 1 - v. This matches images that are loaded
+    /// with their origin at the top-left corner.
+    ///
+    /// The flip accumulates with the `uv_offset`, `uv_scale`, and `uv_rotate`
+    /// functions, and applies to the texture coordinates as transformed by the
+    /// functions called before it.
+    fn flip_v(mut self) -> Self {
+        let flip = Matrix3::new(1.0, 0.0, 0.0, 0.0, -1.0, 0.0, 0.0, 1.0, 1.0);
+        let options = self.options_mut();
+        options.uv_matrix = flip * options.uv_matrix;
+        self
+    }
+}
+
+/// Ensure that a builder applies its options to the triangles of its shape in
+/// the order of the late pipeline, by comparing the vertices that it builds
+/// with every option set against those that it builds without them, to which
+/// the options are then applied by hand. The vertices must match to within
+/// rounding, since the cuboid combines the transformations of the options
+/// with its own.
+/// Implemented by the builders of surface shapes, allowing the way in which
+/// their vertex normals are computed to be specified in the same way for all
+/// of them.
+pub trait WithShading: HasOptions + Sized {
+    /// The shading modes supported by the shape, which fails to build with
+    /// `ShapeCreationError::UnsupportedShading` when given any other mode.
+    const SHADINGS: &'static [Shading];
+
+    /// Specify the way in which the vertex normals are computed. Each shape
+    /// documents its default mode alongside the modes that it supports.
+    fn shading(mut self, shading: Shading) -> Self {
+        self.options_mut().shading = shading;
+        self
+    }
+}
+
+/// Implemented by the builders of all shapes, allowing their vertices to be
+/// colored in the same way for all of them. Each coloring replaces any
+/// coloring given before it, and is replaced by any given after it.
+pub trait WithColors: HasVertices + Sized {
+    /// Specify a constant RGBA color for every vertex of the shape. By default,
+    /// the shape is not colored, and is built from `Vertex` objects. Once
+    /// colored, the shape is built from `VertexColored` objects.
+    fn color(mut self, r: f32, g: f32, b: f32, a: f32) -> Self {
+        self.options_mut().colors = Some(Colors::Constant([r, g, b, a]));
+        self
+    }
+
+    /// Color each vertex with a gradient along the given axis, linearly
+    /// interpolated between the given RGBA colors from the coordinate of the
+    /// vertex along the axis prior to transformation, such that the colors are
+    /// reached at the minimum and maximum extent of the shape along the axis.
+    /// If the shape has no extent along the axis, every vertex takes the
+    /// minimum color. Once colored, the shape is built from `VertexColored`
+    /// objects.
+    fn color_gradient(mut self, axis: Axis, min: [f32; 4], max: [f32; 4]) -> Self {
+        self.options_mut().colors = Some(Colors::Gradient { axis, min, max });
+        self
+    }
+
+    /// Color each vertex by its normal for debugging, such that the red, green,
+    /// and blue components are mapped from the x, y, and z components of the
+    /// normal as `normal * 0.5 + 0.5`, with an alpha of 1. The normals are
+    /// taken after all transformations have been applied, such that the colors
+    /// show the final normals of the shape. Once colored, the shape is built
+    /// from `VertexColored` objects.
+    fn debug_color_from_normals(mut self) -> Self {
+        self.options_mut().colors = Some(Colors::Normals);
+        self
+    }
+
+    /// Build the shape vertices with their colors and return them in a vector.
+    /// If the shape has not been colored, every vertex is white.
+    fn build_vertices_colored(&self) -> Result<Vec<VertexColored>, ShapeCreationError> {
+        let colors = self.options().colors.unwrap_or(Colors::Constant([1.0; 4]));
+        let mut vertices = self.surface_with::<Selected>()?;
+        let mut traced = colors.trace(&vertices, self.matrix());
+        self.finish(&mut vertices)?;
+        self.finish(&mut traced)?;
+        Ok(colors.paint(&vertices, &traced))
+    }
+}
+
+/// Implemented by the builders of surface shapes, allowing them to be built
+/// from a vertex type of your own in the same way for all of them.
+pub trait BuildAs: HasVertices + Sized {
+    /// Build a new shape object whose vertices are of a type of your own,
+    /// converted from each `Vertex` of the shape. The shape is not colored.
+    fn build_as<V, F>(self, display: &F) -> Result<Self::Shape, ShapeCreationError>
+    where
+        V: From<Vertex> + glium::Vertex + Send + 'static,
+        F: glium::backend::Facade,
+    {
+        let vertices = &self.build_vertices_as::<V>()?;
+        let vertices = glium::vertex::VertexBuffer::<V>::new(display, vertices)?.into();
+        Ok(self.shape(vertices))
+    }
+
+    /// Build the shape vertices and return them in a vector of a vertex type of
+    /// your own, converted from each `Vertex` of the shape.
+    fn build_vertices_as<V: From<Vertex>>(&self) -> Result<Vec<V>, ShapeCreationError> {
+        let vertices = self.vertices_with::<Selected>()?;
+        Ok(vertices.into_iter().map(V::from).collect())
+    }
+}
+
+/// Implemented by the builders of surface shapes, allowing them to be built
+/// with a tangent at each vertex in the same way for all of them.
+pub trait WithTangents: HasVertices + Sized {
+    /// Build a new shape object with a tangent at each vertex, for normal
+    /// mapping. Its vertices are of the `VertexTangent` type, and do not carry
+    /// the colors of the shape.
+    fn build_with_tangents<F>(self, display: &F) -> Result<Self::Shape, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        let vertices = &self.build_vertices_with_tangents()?;
+        let vertices = glium::vertex::VertexBuffer::<VertexTangent>::new(display, vertices)?.into();
+        Ok(self.shape(vertices))
+    }
+
+    /// Build the shape vertices with their tangents and return them in a vector.
+    /// The tangents are computed from the positions and texture coordinates of
+    /// the triangles, and are averaged over the triangles that meet at vertices
+    /// of the same position, normal, and texture coordinate.
+    fn build_vertices_with_tangents(&self) -> Result<Vec<VertexTangent>, ShapeCreationError> {
+        Ok(with_tangents(&self.vertices_with::<Selected>()?))
+    }
+}
+
+/// Implemented by the builders of surface shapes, allowing a second set of
+/// texture coordinates to be laid out for lightmapping in the same way for all
+/// of them.
+pub trait WithLightmap: HasVertices + Sized {
+    /// Give each vertex of the shape a second set of texture coordinates for
+    /// lightmapping, which lays out every face of the shape in its own
+    /// rectangle of the unit square, separated from the others and from the
+    /// edges of the square by the given gutter. Each run of coplanar triangles
+    /// forms a face, and the faces are laid out at the same scale. The native
+    /// texture coordinates of the shape are left as they are. Once
+    /// lightmapped, the shape is built from `VertexLightmapped` objects.
+    fn with_lightmap_uvs(mut self, gutter: f32) -> Self {
+        self.options_mut().lightmap_gutter = Some(gutter);
+        self
+    }
+
+    /// Build the shape vertices with their lightmap texture coordinates and
+    /// return them in a vector. If lightmap texture coordinates have not been
+    /// requested for the shape, the faces are laid out without a gutter.
+    fn build_vertices_lightmapped(&self) -> Result<Vec<VertexLightmapped>, ShapeCreationError> {
+        let gutter = self.options().lightmap_gutter.unwrap_or(0.0);
+        with_lightmap_uvs(&self.vertices_with::<Selected>()?, gutter)
+    }
+}
+
+/// Ensure that a builder is built with the positions, normals, and texture
+/// coordinates of its plain vertices once colored, each of which takes the
+/// color that it is given.
+#[cfg(test)]
+pub(crate) fn ensure_colors<B, N>(new: N)
+where
+    B: WithColors,
+    N: Fn() -> B,
+{
+    let vertices = new()
+        .vertices_with::<Selected>()
+        .expect("Failed to build vertices");
+    let colored = new()
+        .color(0.25, 0.5, 0.75, 1.0)
+        .build_vertices_colored()
+        .expect("Failed to build vertices");
+    assert_eq!(colored.len(), vertices.len());
+    for (colored, vertex) in colored.iter().zip(vertices.iter()) {
+        assert_eq!(colored.position, vertex.position);
+        assert_eq!(colored.normal, vertex.normal);
+        assert_eq!(colored.texcoord, vertex.texcoord);
+        assert_eq!(colored.color, [0.25, 0.5, 0.75, 1.0]);
+    }
+}
+
+/// Ensure that a builder builds with each of its shading modes, and fails to
+/// build with `ShapeCreationError::UnsupportedShading` with any other mode.
+#[cfg(test)]
+pub(crate) fn ensure_shadings<B, N, V, T>(new: N, build_vertices: V)
+where
+    B: WithShading,
+    N: Fn() -> B,
+    V: Fn(&B) -> Result<Vec<T>, ShapeCreationError>,
+{
+    for &shading in [Shading::Flat, Shading::Smooth].iter() {
+        match build_vertices(&new().shading(shading)) {
+            Ok(_) => assert!(B::SHADINGS.contains(&shading)),
+            Err(ShapeCreationError::UnsupportedShading) => {
+                assert!(!B::SHADINGS.contains(&shading))
+            }
+            Err(error) => panic!("Unexpected {:?}", error),
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) fn ensure_late_pipeline<B, N, V>(new: N, build_vertices: V)
+where
+    B: WithOrientation + WithModifiers,
+    N: Fn() -> B,
+    V: Fn(&B) -> Result<Vec<Vertex>, ShapeCreationError>,
+{
+    let builder = new()
+        .jitter(0.05, 7)
+        .offset_along_normals(0.01)
+        .radial_array(3, Axis::Y, 2.0)
+        .mirror(MirrorPlane::Yz)
+        .convention(Convention::ZUpRightHanded)
+        .winding(Winding::Cw)
+        .flip_normals();
+    let vertices = build_vertices(&builder).expect("Failed to build vertices");
+
+    let mut expected = build_vertices(&new()).expect("Failed to build vertices");
+    Jitter {
+        amplitude: 0.05,
+        seed: 7,
+    }
+    .apply(&Matrix4::identity(), &mut expected);
+    offset_along_normals(0.01, &mut expected);
+    let arrays = [
+        Modifier::RadialArray {
+            count: 3,
+            axis: Axis::Y,
+            radius: 2.0,
+        },
+        Modifier::Mirror {
+            plane: MirrorPlane::Yz,
+        },
+    ];
+    Modifier::apply(&arrays, &mut expected).expect("Failed to apply modifiers");
+    Convention::ZUpRightHanded.apply(&mut expected);
+    reverse_winding(&mut expected);
+    negate_normals(&mut expected);
+
+    assert_vertices_match(&vertices, &expected);
+}
+
+/// Ensure that a builder of a triangle strip applies its options to the strip
+/// in the order of the late pipeline, in the same way as
+/// `ensure_late_pipeline`.
+#[cfg(test)]
+pub(crate) fn ensure_late_strip_pipeline<B, N, V>(new: N, build_vertices: V)
+where
+    B: WithOrientation,
+    N: Fn() -> B,
+    V: Fn(&B) -> Result<Vec<Vertex>, ShapeCreationError>,
+{
+    let builder = new()
+        .convention(Convention::ZUpRightHanded)
+        .winding(Winding::Cw)
+        .flip_normals();
+    let vertices = build_vertices(&builder).expect("Failed to build vertices");
+
+    let mut expected = build_vertices(&new()).expect("Failed to build vertices");
+    Convention::ZUpRightHanded.convert(&mut expected);
+    expected.swap(1, 2);
+    negate_normals(&mut expected);
+    assert_vertices_match(&vertices, &expected);
+}
+
+/// Ensure that two lists of vertices match to within rounding.
+#[cfg(test)]
+fn assert_vertices_match(vertices: &[Vertex], expected: &[Vertex]) {
+    assert_eq!(vertices.len(), expected.len());
+    for (vertex, expected) in vertices.iter().zip(expected.iter()) {
+        assert_ulps_eq!(
+            Vector3::from(vertex.position),
+            Vector3::from(expected.position),
+            epsilon = 0.0001
+        );
+        assert_ulps_eq!(
+            Vector3::from(vertex.normal),
+            Vector3::from(expected.normal),
+            epsilon = 0.0001
+        );
+        assert_ulps_eq!(
+            Vector2::from(vertex.texcoord),
+            Vector2::from(expected.texcoord),
+            epsilon = 0.0001
+        );
+    }
+}
+
+#[test]
+pub fn ensure_default_options_leave_triangles_as_they_are() {
+    let vertex = |position: [f32; 3]| Vertex {
+        position,
+        normal: [0.0, 0.0, 1.0],
+        texcoord: [position[0], position[1]],
+    };
+    let triangle = vec![
+        vertex([0.0, 0.0, 0.0]),
+        vertex([1.0, 0.0, 0.0]),
+        vertex([0.0, 1.0, 0.0]),
+    ];
+    let options = ShapeOptions::default();
+    let mut vertices = triangle.clone();
+    options
+        .apply(&Matrix4::identity(), &mut vertices)
+        .expect("Failed to apply options");
+    assert_eq!(options.copies(), 1);
+    for (vertex, expected) in vertices.iter().zip(triangle.iter()) {
+        assert_eq!(vertex.position, expected.position);
+        assert_eq!(vertex.normal, expected.normal);
+        assert_eq!(vertex.texcoord, expected.texcoord);
+    }
+}
@@ -0,0 +1,476 @@
+//! A module for constructing parametric surface shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::errors::ShapeCreationError;
+use crate::facet::flatten_normals;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{
+    BuildAs, HasOptions, HasVertices, ShapeOptions, WithBackFaces, WithColors, WithLightmap,
+    WithModifiers, WithOrientation, WithShading, WithTangents, WithTexcoords, WithTransform,
+};
+use crate::shading::Shading;
+use crate::vertex::Vertex;
+
+use std::ops::Range;
+
+/// A polygonal `ParametricSurface` object.
+///
+/// This object is constructed using a `ParametricSurfaceBuilder` object.
+pub struct ParametricSurface {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows a `ParametricSurface` object to be passed as a source of vertices.
+impl<'a> From<&'a ParametricSurface> for glium::vertex::VerticesSource<'a> {
+    fn from(surface: &'a ParametricSurface) -> glium::vertex::VerticesSource<'a> {
+        (&surface.vertices).into()
+    }
+}
+
+/// Allows a `ParametricSurface` object to be passed as a source of indices.
+impl<'a> From<&'a ParametricSurface> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a ParametricSurface) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::TrianglesList,
+        }
+    }
+}
+
+/// Responsible for building and returning a `ParametricSurface` object.
+///
+/// The surface is constructed by sampling a function of two parameters, `u` and
+/// `v`, over a regular grid of their domain. By default, the function maps the
+/// domain `0..1` of both parameters to a unit square in the xy-plane, facing
+/// the positive z-axis, with its centre located at the origin. This can be
+/// overriden using the transformation methods on this object.
+///
+/// The surface may be wrapped in either parameter, in which case the function
+/// is expected to return to its starting point at the end of the domain of
+/// that parameter (e.g. the angle about the axis of a cylinder). The samples
+/// at the start of the domain are then reused at the end, such that the seam
+/// is closed exactly. The resultant geometry is constructed to suit OpenGL
+/// defaults - assuming a right-handed coordinate system, front-facing polygons
+/// are defined in counter-clock-wise order when the u axis runs to the right
+/// and the v axis runs upwards. Vertex normals are computed from the cross
+/// product of the tangents along u and v, as estimated from neighbouring
+/// samples (such that the surface appears smooth when lit). Where the tangents
+/// degenerate (e.g. at the pole of a sphere), they are estimated a short
+/// distance into the domain instead. Vertex texture coordinates store the
+/// parameters of each sample.
+pub struct ParametricSurfaceBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    function: Box<dyn Fn(f32, f32) -> Point3<f32>>,
+    u_domain: Range<f32>,
+    v_domain: Range<f32>,
+    u_segments: usize,
+    v_segments: usize,
+    wrap_u: bool,
+    wrap_v: bool,
+    options: ShapeOptions,
+}
+
+impl Default for ParametricSurfaceBuilder {
+    fn default() -> Self {
+        ParametricSurfaceBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            function: Box::new(|u, v| Point3::<f32>::new(u - 0.5, v - 0.5, 0.0)),
+            u_domain: 0.0..1.0,
+            v_domain: 0.0..1.0,
+            u_segments: 16,
+            v_segments: 16,
+            wrap_u: false,
+            wrap_v: false,
+            options: ShapeOptions {
+                shading: Shading::Smooth,
+                ..ShapeOptions::default()
+            },
+        }
+    }
+}
+
+impl ParametricSurfaceBuilder {
+    /// Create a new `ParametricSurfaceBuilder` object.
+    pub fn new() -> ParametricSurfaceBuilder {
+        Default::default()
+    }
+
+    /// Specify the function that maps the parameters `(u, v)` to a point on
+    /// the surface. By default, the function maps the unit square of the
+    /// parameters to a unit square in the xy-plane.
+    pub fn function<F>(mut self, function: F) -> Self
+    where
+        F: Fn(f32, f32) -> Point3<f32> + 'static,
+    {
+        self.function = Box::new(function);
+        self
+    }
+
+    /// Specify the domain over which each of the parameters are sampled. By
+    /// default, both parameters are sampled from 0 to 1. Each domain must
+    /// have a finite, non-zero extent to build a valid surface, but may run
+    /// in reverse.
+    pub fn domain(mut self, u: Range<f32>, v: Range<f32>) -> Self {
+        self.u_domain = u;
+        self.v_domain = v;
+        self
+    }
+
+    /// Specify the number of segments sampled along the u and v axes. By
+    /// default, the builder will use 16 segments along each axis. At least 1
+    /// segment along each axis is required to build a valid surface.
+    pub fn segments(mut self, u: usize, v: usize) -> Self {
+        self.u_segments = u;
+        self.v_segments = v;
+        self
+    }
+
+    /// Specify whether the surface wraps along the u axis, such that the end
+    /// of the domain of u meets its start. By default, the surface does not
+    /// wrap.
+    pub fn wrap_u(mut self, wrap: bool) -> Self {
+        self.wrap_u = wrap;
+        self
+    }
+
+    /// Specify whether the surface wraps along the v axis, such that the end
+    /// of the domain of v meets its start. By default, the surface does not
+    /// wrap.
+    pub fn wrap_v(mut self, wrap: bool) -> Self {
+        self.wrap_v = wrap;
+        self
+    }
+
+    /// Build a new `ParametricSurface` object.
+    pub fn build<F>(self, display: &F) -> Result<ParametricSurface, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the shape vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// a `ParametricSurface` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        let vertices = self.vertices_with::<Selected>()?;
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.options.check_shading(Self::SHADINGS)?;
+
+        // Ensure there are enough segments to produce valid surface geometry.
+        if self.u_segments < 1 {
+            return Err(ShapeCreationError::NotEnoughDivisionsInU);
+        }
+        if self.v_segments < 1 {
+            return Err(ShapeCreationError::NotEnoughDivisionsInV);
+        }
+        let valid = |d: &Range<f32>| d.start.is_finite() && d.end.is_finite() && d.start != d.end;
+        if !valid(&self.u_domain) || !valid(&self.v_domain) {
+            return Err(ShapeCreationError::InvalidDimensions);
+        }
+
+        // Sample the function over the grid, reusing the first samples at the
+        // end of a wrapped axis.
+        let (nu, nv) = (self.u_segments, self.v_segments);
+        let parameter = |domain: &Range<f32>, index: usize, count: usize| {
+            domain.start + (domain.end - domain.start) * (index as f32 / count as f32)
+        };
+        let u = |i: usize| parameter(&self.u_domain, i, nu);
+        let v = |j: usize| parameter(&self.v_domain, j, nv);
+        let mut samples = Vec::<Vector3<f32>>::with_capacity((nu + 1) * (nv + 1));
+        for i in 0..=nu {
+            for j in 0..=nv {
+                let i = if self.wrap_u && i == nu { 0 } else { i };
+                let j = if self.wrap_v && j == nv { 0 } else { j };
+                samples.push((self.function)(u(i), v(j)).to_vec());
+            }
+        }
+        let sample = |i: usize, j: usize| samples[i * (nv + 1) + j];
+
+        // Estimate the tangent along an axis from the neighbouring samples,
+        // using central differences in the interior (and across a wrapped
+        // seam), and one-sided differences at the edges.
+        let neighbours = |index: usize, count: usize, wrap: bool| {
+            if wrap {
+                ((index + count - 1) % count, (index + 1) % count)
+            } else {
+                (index.saturating_sub(1), (index + 1).min(count))
+            }
+        };
+        let normal_at = |i: usize, j: usize| {
+            let (i0, i1) = neighbours(i, nu, self.wrap_u);
+            let (j0, j1) = neighbours(j, nv, self.wrap_v);
+            let tu = sample(i1, j) - sample(i0, j);
+            let tv = sample(i, j1) - sample(i, j0);
+            let normal = tu.cross(tv);
+            let scale = tu.magnitude2().max(tv.magnitude2());
+            if normal.magnitude2() > scale * scale * 1e-8 {
+                return normal.normalize();
+            }
+
+            // The tangents degenerate, so estimate them from the function a
+            // quarter of a segment towards the centre of the domain.
+            let offset = |index: usize, count: usize| {
+                let t = index as f32 / count as f32;
+                t + (0.5 - t).signum() * 0.25 / count as f32
+            };
+            let (du, dv) = (offset(i, nu), offset(j, nv));
+            let (su, sv) = (0.125 / nu as f32, 0.125 / nv as f32);
+            let at = |a: f32, b: f32| {
+                let pu = self.u_domain.start + (self.u_domain.end - self.u_domain.start) * a;
+                let pv = self.v_domain.start + (self.v_domain.end - self.v_domain.start) * b;
+                (self.function)(pu, pv).to_vec()
+            };
+            let tu = at(du + su, dv) - at(du - su, dv);
+            let tv = at(du, dv + sv) - at(du, dv - sv);
+            let normal = tu.cross(tv);
+            if normal.magnitude2() > 0.0 {
+                normal.normalize()
+            } else {
+                Vector3::<f32>::unit_z()
+            }
+        };
+
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<M>::new(self.matrix);
+
+        // Build vertex array.
+        let mut vertices = Vec::<Vertex>::with_capacity(self.num_vertices());
+        for i in 0..nu {
+            for j in 0..nv {
+                for &(di, dj) in [(0, 0), (1, 0), (1, 1), (0, 0), (1, 1), (0, 1)].iter() {
+                    let (i, j) = (i + di, j + dj);
+                    vertices.push(Vertex {
+                        position: transform.point(sample(i, j)),
+                        normal: transform.normal(normal_at(i, j)),
+                        texcoord: [u(i), v(j)],
+                    });
+                }
+            }
+        }
+
+        if self.options.shading == Shading::Flat {
+            flatten_normals(&mut vertices);
+        }
+
+        self.options.map_texcoords(&mut vertices);
+        Ok(vertices)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    pub fn num_vertices(&self) -> usize {
+        self.u_segments * self.v_segments * 6 * self.options.copies()
+    }
+}
+
+impl HasOptions for ParametricSurfaceBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for ParametricSurfaceBuilder {
+    type Shape = ParametricSurface;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> ParametricSurface {
+        ParametricSurface { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for ParametricSurfaceBuilder {}
+
+impl BuildAs for ParametricSurfaceBuilder {}
+
+impl WithTangents for ParametricSurfaceBuilder {}
+
+impl WithLightmap for ParametricSurfaceBuilder {}
+
+impl WithOrientation for ParametricSurfaceBuilder {}
+
+impl WithBackFaces for ParametricSurfaceBuilder {}
+
+impl WithModifiers for ParametricSurfaceBuilder {}
+
+impl WithTexcoords for ParametricSurfaceBuilder {}
+
+impl WithColors for ParametricSurfaceBuilder {}
+
+impl WithShading for ParametricSurfaceBuilder {
+    /// By default, the parametric surface is smooth shaded. When flat shaded,
+    /// each vertex normal points in the direction of its respective triangle
+    /// instead.
+    const SHADINGS: &'static [Shading] = &[Shading::Flat, Shading::Smooth];
+}
+
+#[cfg(test)]
+fn torus(u: f32, v: f32) -> Point3<f32> {
+    let r = 0.375 + 0.125 * v.cos();
+    Point3::<f32>::new(r * u.cos(), 0.125 * v.sin(), -r * u.sin())
+}
+
+#[cfg(test)]
+fn sphere(u: f32, v: f32) -> Point3<f32> {
+    Point3::<f32>::new(v.sin() * u.sin(), -v.cos(), v.sin() * u.cos())
+}
+
+#[test]
+pub fn ensure_default_parametric_surface_is_unit_square() {
+    let vertices = ParametricSurfaceBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        let [x, y, z] = vertex.position;
+        assert!(x.abs() <= 0.5 && y.abs() <= 0.5 && z == 0.0);
+        assert_ulps_eq!(Vector3::<f32>::from(vertex.normal), Vector3::unit_z());
+        assert_ulps_eq!(vertex.texcoord[0], x + 0.5);
+        assert_ulps_eq!(vertex.texcoord[1], y + 0.5);
+    }
+}
+
+#[test]
+pub fn ensure_wrapped_parametric_surface_is_watertight() {
+    use std::collections::HashMap;
+    use std::f32::consts::PI;
+    let vertices = ParametricSurfaceBuilder::new()
+        .function(torus)
+        .domain(0.0..2.0 * PI, 0.0..2.0 * PI)
+        .segments(24, 12)
+        .wrap_u(true)
+        .wrap_v(true)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let mut edges = HashMap::<([u32; 3], [u32; 3]), usize>::new();
+    for chunk in vertices.chunks(3) {
+        for k in 0..3 {
+            let a = chunk[k].position.map(f32::to_bits);
+            let b = chunk[(k + 1) % 3].position.map(f32::to_bits);
+            *edges
+                .entry(if a < b { (a, b) } else { (b, a) })
+                .or_insert(0) += 1;
+        }
+    }
+    assert!(edges.values().all(|&count| count == 2));
+}
+
+#[test]
+pub fn ensure_parametric_surface_has_ccw_triangles() {
+    use std::f32::consts::PI;
+    let vertices = ParametricSurfaceBuilder::new()
+        .function(torus)
+        .domain(0.0..2.0 * PI, 0.0..2.0 * PI)
+        .wrap_u(true)
+        .wrap_v(true)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let eyepos = v0 + Vector3::<f32>::from(chunk[0].normal);
+        let e0 = v1 - v0;
+        let e1 = v2 - v0;
+        let n = e0.cross(e1);
+        assert!(n.dot(v0 - eyepos) <= 0.0);
+        assert!(n.dot(v1 - eyepos) <= 0.0);
+        assert!(n.dot(v2 - eyepos) <= 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_parametric_surface_normals_survive_degenerate_tangents() {
+    use std::f32::consts::PI;
+    let vertices = ParametricSurfaceBuilder::new()
+        .function(sphere)
+        .domain(0.0..2.0 * PI, 0.0..PI)
+        .segments(16, 8)
+        .wrap_u(true)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        let position = Vector3::<f32>::from(vertex.position);
+        let normal = Vector3::<f32>::from(vertex.normal);
+        assert_ulps_eq!(normal.magnitude(), 1.0, epsilon = 0.0001);
+        assert!(normal.dot(position) > 0.9);
+    }
+}
+
+#[test]
+pub fn ensure_parametric_surface_with_invalid_parameters_fails_to_build() {
+    match ParametricSurfaceBuilder::new()
+        .segments(0, 1)
+        .build_vertices()
+    {
+        Err(ShapeCreationError::NotEnoughDivisionsInU) => (),
+        _ => panic!("Expected NotEnoughDivisionsInU"),
+    }
+    match ParametricSurfaceBuilder::new()
+        .segments(1, 0)
+        .build_vertices()
+    {
+        Err(ShapeCreationError::NotEnoughDivisionsInV) => (),
+        _ => panic!("Expected NotEnoughDivisionsInV"),
+    }
+    match ParametricSurfaceBuilder::new()
+        .domain(1.0..1.0, 0.0..1.0)
+        .build_vertices()
+    {
+        Err(ShapeCreationError::InvalidDimensions) => (),
+        _ => panic!("Expected InvalidDimensions"),
+    }
+}
+
+#[test]
+pub fn ensure_parametric_surface_applies_late_pipeline() {
+    use crate::options::WithTexcoords;
+    use crate::uv_mapping::UvMapping;
+
+    crate::options::ensure_late_pipeline(
+        || {
+            ParametricSurfaceBuilder::new()
+                .uv_mapping(UvMapping::Box)
+                .double_sided()
+        },
+        ParametricSurfaceBuilder::build_vertices,
+    );
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_parametric_surface() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = ParametricSurfaceBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
@@ -0,0 +1,468 @@
+//! A module for constructing subdivided plane shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::errors::ShapeCreationError;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{
+    BuildAs, HasOptions, HasVertices, ShapeOptions, WithBackFaces, WithColors, WithLightmap,
+    WithModifiers, WithOrientation, WithShading, WithTangents, WithTexcoords, WithTransform,
+};
+use crate::shading::Shading;
+use crate::vertex::Vertex;
+
+/// A polygonal `Plane` object.
+///
+/// This object is constructed using a `PlaneBuilder` object.
+pub struct Plane {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows a `Plane` object to be passed as a source of vertices.
+impl<'a> From<&'a Plane> for glium::vertex::VerticesSource<'a> {
+    fn from(plane: &'a Plane) -> glium::vertex::VerticesSource<'a> {
+        (&plane.vertices).into()
+    }
+}
+
+/// Allows a `Plane` object to be passed as a source of indices.
+impl<'a> From<&'a Plane> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a Plane) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::TrianglesList,
+        }
+    }
+}
+
+/// Responsible for building and returning a `Plane` object.
+///
+/// By default, the plane is defined as a single unit-square in the xz-plane,
+/// facing the positive y-axis, with its centre located at the origin. The
+/// plane can be subdivided into a grid of cells, and the default position,
+/// size, and alignment can be overridden using the transformation methods on
+/// this object.
+///
+/// The resultant geometry is constructed to suit OpenGL defaults - assuming
+/// a right-handed coordinate system, front-facing polygons are defined in
+/// counter-clock-wise order. Vertex normals point in the direction of the
+/// plane. Vertex texture coordinates define a planar-projection across the
+/// whole plane, regardless of the number of subdivisions, with the u coordinate
+/// increasing along the positive x-axis and the v coordinate increasing along
+/// the negative z-axis. Neighbouring cells share identical vertex positions.
+pub struct PlaneBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    subdivisions_x: usize,
+    subdivisions_z: usize,
+    options: ShapeOptions,
+}
+
+impl Default for PlaneBuilder {
+    fn default() -> Self {
+        PlaneBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            subdivisions_x: 0,
+            subdivisions_z: 0,
+            options: ShapeOptions::default(),
+        }
+    }
+}
+
+impl PlaneBuilder {
+    /// Create a new `PlaneBuilder` object.
+    pub fn new() -> PlaneBuilder {
+        Default::default()
+    }
+
+    /// Specify the number of times to subdivide the plane along the x and z
+    /// axes. A plane with `x` and `z` subdivisions is built from a grid of
+    /// `(x + 1) * (z + 1)` cells. By default, the plane is not subdivided, and
+    /// consists of a single cell.
+    pub fn subdivisions(mut self, x: usize, z: usize) -> Self {
+        self.subdivisions_x = x;
+        self.subdivisions_z = z;
+        self
+    }
+
+    /// Build a new `Plane` object.
+    pub fn build<F>(self, display: &F) -> Result<Plane, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the shape vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// a `Plane` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        let vertices = self.vertices_with::<Selected>()?;
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.options.check_shading(Self::SHADINGS)?;
+
+        let (cells_x, cells_z) = (self.subdivisions_x + 1, self.subdivisions_z + 1);
+
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<M>::new(self.matrix);
+        let normal = transform.normal(Vector3::<f32>::new(0.0, 1.0, 0.0));
+
+        // Build the vertices. Positions are derived from the grid coordinates
+        // alone, such that neighbouring cells share identical positions.
+        let mut vertices = Vec::<Vertex>::with_capacity(self.num_vertices());
+        let quad = [(0, 0), (1, 0), (1, 1), (0, 0), (1, 1), (0, 1)];
+
+        for z in 0..cells_z {
+            for x in 0..cells_x {
+                for &(dx, dz) in quad.iter() {
+                    let u = (x + dx) as f32 / cells_x as f32;
+                    let v = (z + dz) as f32 / cells_z as f32;
+                    let position = Vector3::<f32>::new(u - 0.5, 0.0, 0.5 - v);
+                    vertices.push(Vertex {
+                        position: transform.point(position),
+                        normal,
+                        texcoord: [u, v],
+                    });
+                }
+            }
+        }
+
+        self.options.map_texcoords(&mut vertices);
+        Ok(vertices)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    pub fn num_vertices(&self) -> usize {
+        (self.subdivisions_x + 1) * (self.subdivisions_z + 1) * 6 * self.options.copies()
+    }
+}
+
+impl HasOptions for PlaneBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for PlaneBuilder {
+    type Shape = Plane;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> Plane {
+        Plane { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for PlaneBuilder {}
+
+impl BuildAs for PlaneBuilder {}
+
+impl WithTangents for PlaneBuilder {}
+
+impl WithLightmap for PlaneBuilder {}
+
+impl WithOrientation for PlaneBuilder {}
+
+impl WithBackFaces for PlaneBuilder {}
+
+impl WithModifiers for PlaneBuilder {}
+
+impl WithTexcoords for PlaneBuilder {}
+
+impl WithColors for PlaneBuilder {}
+
+impl WithShading for PlaneBuilder {
+    /// By default, the plane is flat shaded. Both modes are supported, and
+    /// build the same normals, since the plane is planar.
+    const SHADINGS: &'static [Shading] = &[Shading::Flat, Shading::Smooth];
+}
+
+#[test]
+pub fn ensure_default_plane_is_single_unit_quad() {
+    let vertices = PlaneBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    assert_eq!(vertices.len(), 6);
+    for vertex in &vertices {
+        assert!(abs_diff_eq!(vertex.position[0].abs(), 0.5));
+        assert!(abs_diff_eq!(vertex.position[1], 0.0));
+        assert!(abs_diff_eq!(vertex.position[2].abs(), 0.5));
+    }
+}
+
+#[test]
+pub fn ensure_subdivided_plane_has_centroid_at_origin() {
+    let vertices = PlaneBuilder::new()
+        .subdivisions(3, 4)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let mut sum = Vector3::<f32>::zero();
+    for vertex in &vertices {
+        sum += Vector3::<f32>::from(vertex.position);
+    }
+    assert_ulps_eq!(sum, Vector3::<f32>::zero(), epsilon = 0.0001);
+}
+
+#[test]
+pub fn ensure_plane_has_upward_facing_normals() {
+    let vertices = PlaneBuilder::new()
+        .subdivisions(2, 2)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        assert_eq!(vertex.normal, [0.0, 1.0, 0.0]);
+    }
+}
+
+#[test]
+pub fn ensure_subdivided_plane_uvs_span_unit_range() {
+    use std::f32;
+    let vertices = PlaneBuilder::new()
+        .subdivisions(4, 1)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let mut min = Vector2::<f32>::new(f32::MAX, f32::MAX);
+    let mut max = -min;
+    for vertex in &vertices {
+        min.x = f32::min(min.x, vertex.texcoord[0]);
+        min.y = f32::min(min.y, vertex.texcoord[1]);
+        max.x = f32::max(max.x, vertex.texcoord[0]);
+        max.y = f32::max(max.y, vertex.texcoord[1]);
+    }
+    assert!(min == Vector2::<f32>::zero());
+    assert!(max == Vector2::<f32>::from_value(1.0));
+}
+
+#[test]
+pub fn ensure_subdivided_plane_has_ccw_triangles() {
+    let vertices = PlaneBuilder::new()
+        .subdivisions(2, 3)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let eyepos = v0 + Vector3::<f32>::from(chunk[0].normal);
+        let e0 = v1 - v0;
+        let e1 = v2 - v0;
+        let n = e0.cross(e1);
+        assert!(n.dot(v0 - eyepos) <= 0.0);
+        assert!(n.dot(v1 - eyepos) <= 0.0);
+        assert!(n.dot(v2 - eyepos) <= 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_subdivided_plane_cells_share_identical_positions() {
+    let (x, z) = (3, 5);
+    let vertices = PlaneBuilder::new()
+        .subdivisions(x, z)
+        .scale(3.0, 1.0, 7.0)
+        .rotate_y(0.3)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let mut unique = vertices
+        .iter()
+        .map(|v| [v.position[0].to_bits(), v.position[2].to_bits()])
+        .collect::<Vec<[u32; 2]>>();
+    unique.sort();
+    unique.dedup();
+    assert_eq!(unique.len(), (x + 2) * (z + 2));
+}
+
+#[test]
+pub fn ensure_double_sided_plane_appends_reversed_twins() {
+    let front = PlaneBuilder::new()
+        .subdivisions(2, 3)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let builder = PlaneBuilder::new().subdivisions(2, 3).double_sided();
+    let vertices = builder.build_vertices().expect("Failed to build vertices");
+    assert_eq!(vertices.len(), front.len() * 2);
+    assert_eq!(vertices.len(), builder.num_vertices());
+    let (original, back) = vertices.split_at(front.len());
+    for (a, b) in front.iter().zip(original.iter()) {
+        assert_eq!(a.position, b.position);
+        assert_eq!(a.normal, b.normal);
+        assert_eq!(a.texcoord, b.texcoord);
+    }
+    for (f, b) in front.chunks(3).zip(back.chunks(3)) {
+        for (fv, bv) in f.iter().zip([&b[0], &b[2], &b[1]].iter()) {
+            assert_eq!(fv.position, bv.position);
+            assert_eq!(fv.texcoord, bv.texcoord);
+            assert_eq!(
+                Vector3::<f32>::from(fv.normal),
+                -Vector3::<f32>::from(bv.normal)
+            );
+        }
+    }
+}
+
+#[test]
+pub fn ensure_plane_uv_transforms_accumulate_in_call_order() {
+    use crate::options::WithTexcoords;
+
+    let offset_then_scale = PlaneBuilder::new()
+        .uv_offset(1.0, 0.0)
+        .uv_scale(2.0, 2.0)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let scale_then_offset = PlaneBuilder::new()
+        .uv_scale(2.0, 2.0)
+        .uv_offset(1.0, 0.0)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let rotated = PlaneBuilder::new()
+        .uv_rotate(std::f32::consts::FRAC_PI_2)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let original = PlaneBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for (i, vertex) in original.iter().enumerate() {
+        let [u, v] = vertex.texcoord;
+        assert_eq!(offset_then_scale[i].texcoord, [(u + 1.0) * 2.0, v * 2.0]);
+        assert_eq!(scale_then_offset[i].texcoord, [u * 2.0 + 1.0, v * 2.0]);
+        assert_ulps_eq!(rotated[i].texcoord[0], -v, epsilon = 0.0001);
+        assert_ulps_eq!(rotated[i].texcoord[1], u, epsilon = 0.0001);
+    }
+}
+
+#[test]
+pub fn ensure_gradient_plane_follows_untransformed_axis() {
+    use crate::axis::Axis;
+
+    let vertices = PlaneBuilder::new()
+        .rotate_z(1.0)
+        .translate(5.0, 0.0, 0.0)
+        .color_gradient(Axis::X, [0.0; 4], [1.0; 4])
+        .build_vertices_colored()
+        .expect("Failed to build vertices");
+    let expected = PlaneBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for (vertex, expected) in vertices.iter().zip(expected.iter()) {
+        let t = expected.position[0] + 0.5;
+        for component in vertex.color.iter() {
+            assert_ulps_eq!(*component, t, epsilon = 0.0001);
+        }
+    }
+}
+
+#[test]
+pub fn ensure_arrayed_gradient_plane_repeats_gradient_per_copy() {
+    use crate::axis::Axis;
+
+    let single = PlaneBuilder::new()
+        .rotate_z(1.0)
+        .color_gradient(Axis::X, [0.0; 4], [1.0; 4])
+        .build_vertices_colored()
+        .expect("Failed to build vertices");
+    let arrayed = PlaneBuilder::new()
+        .rotate_z(1.0)
+        .linear_array(3, [2.0, 0.0, 0.0])
+        .color_gradient(Axis::X, [0.0; 4], [1.0; 4])
+        .build_vertices_colored()
+        .expect("Failed to build vertices");
+    assert_eq!(arrayed.len(), single.len() * 3);
+    for copy in arrayed.chunks(single.len()) {
+        for (vertex, expected) in copy.iter().zip(single.iter()) {
+            assert_eq!(vertex.color, expected.color);
+        }
+    }
+}
+
+#[test]
+pub fn ensure_plane_arrays_accumulate_in_call_order() {
+    use crate::axis::Axis;
+
+    let offset = Vector3::<f32>::new(0.0, 1.0, 0.0);
+    let linear_first = PlaneBuilder::new()
+        .linear_array(2, offset)
+        .radial_array(2, Axis::Z, 4.0)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let radial_first = PlaneBuilder::new()
+        .radial_array(2, Axis::Z, 4.0)
+        .linear_array(2, offset)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    assert_eq!(linear_first.len(), 4 * 6);
+    assert_eq!(radial_first.len(), 4 * 6);
+
+    // The centres of the copies, in the order in which they are built.
+    let centres = |vertices: &[Vertex]| {
+        vertices
+            .chunks(6)
+            .map(|copy| {
+                copy.iter()
+                    .map(|vertex| Vector3::<f32>::from(vertex.position))
+                    .sum::<Vector3<f32>>()
+                    / 6.0
+            })
+            .collect::<Vec<Vector3<f32>>>()
+    };
+    let expected = [
+        [(4.0, 0.0), (4.0, 1.0), (-4.0, 0.0), (-4.0, -1.0)],
+        [(4.0, 0.0), (-4.0, 0.0), (4.0, 1.0), (-4.0, 1.0)],
+    ];
+    for (vertices, expected) in [linear_first, radial_first].iter().zip(expected.iter()) {
+        for (centre, &(x, y)) in centres(vertices).iter().zip(expected.iter()) {
+            assert_ulps_eq!(centre.x, x, epsilon = 0.0001);
+            assert_ulps_eq!(centre.y, y, epsilon = 0.0001);
+        }
+    }
+}
+
+#[test]
+pub fn ensure_plane_applies_late_pipeline() {
+    use crate::options::WithTexcoords;
+    use crate::uv_mapping::UvMapping;
+
+    crate::options::ensure_late_pipeline(
+        || {
+            PlaneBuilder::new()
+                .uv_mapping(UvMapping::Box)
+                .double_sided()
+        },
+        PlaneBuilder::build_vertices,
+    );
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_plane() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = PlaneBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
@@ -0,0 +1,287 @@
+//! A module for constructing polyline shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::errors::ShapeCreationError;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{HasOptions, HasVertices, ShapeOptions, WithColors, WithTransform};
+use crate::vertex::Vertex;
+
+/// A `Polyline` line strip.
+///
+/// This object is constructed using a `PolylineBuilder` object.
+pub struct Polyline {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows a `Polyline` object to be passed as a source of vertices.
+impl<'a> From<&'a Polyline> for glium::vertex::VerticesSource<'a> {
+    fn from(polyline: &'a Polyline) -> glium::vertex::VerticesSource<'a> {
+        (&polyline.vertices).into()
+    }
+}
+
+/// Allows a `Polyline` object to be passed as a source of indices.
+impl<'a> From<&'a Polyline> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a Polyline) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::LineStrip,
+        }
+    }
+}
+
+/// Responsible for building and returning a `Polyline` object.
+///
+/// The polyline is constructed as a line strip through a list of points. By
+/// default, the polyline is a single straight line of unit length from
+/// `(0, -0.5, 0)` to `(0, 0.5, 0)`. The polyline may be closed, in which case
+/// the line strip returns to its first point. This can be overriden using the
+/// transformation methods on this object.
+///
+/// Vertex normals store the direction of the segment that leaves each point
+/// (or that arrives at the last point of an open polyline), transformed as a
+/// direction rather than as a surface normal. Vertex texture coordinates map
+/// the proportion of the arc-length along the polyline to the u coordinate
+/// (from 0 at the first vertex to 1 at the last), such that dashed or fading
+/// lines may be drawn in a shader, and the v coordinate is always 0.
+pub struct PolylineBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    options: ShapeOptions,
+    points: Vec<Point3<f32>>,
+    closed: bool,
+}
+
+impl Default for PolylineBuilder {
+    fn default() -> PolylineBuilder {
+        PolylineBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            options: ShapeOptions::default(),
+            points: vec![
+                Point3::<f32>::new(0.0, -0.5, 0.0),
+                Point3::<f32>::new(0.0, 0.5, 0.0),
+            ],
+            closed: false,
+        }
+    }
+}
+
+impl PolylineBuilder {
+    /// Create a new `PolylineBuilder` object.
+    pub fn new() -> PolylineBuilder {
+        Default::default()
+    }
+
+    /// Specify the points of the polyline. At least two points are required
+    /// to build a valid polyline, and consecutive points must not be
+    /// coincident.
+    pub fn points(mut self, points: &[impl Into<Point3<f32>> + Copy]) -> Self {
+        self.points = points.iter().map(|&point| point.into()).collect();
+        self
+    }
+
+    /// Specify whether the polyline returns from its last point to its first.
+    /// By default, the polyline is open.
+    pub fn closed(mut self, closed: bool) -> Self {
+        self.closed = closed;
+        self
+    }
+
+    /// Build a new `Polyline` object.
+    pub fn build<F>(self, display: &F) -> Result<Polyline, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the polyline vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// a `Polyline` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.vertices_with::<Selected>()
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        // Ensure the points produce a valid polyline. When closed, the first
+        // point is repeated at the end of the line strip.
+        if self.points.len() < 2 {
+            return Err(ShapeCreationError::NotEnoughPoints);
+        }
+        let mut points = self.points.clone();
+        if self.closed {
+            points.push(points[0]);
+        }
+        if points.windows(2).any(|pair| ulps_eq!(pair[0], pair[1])) {
+            return Err(ShapeCreationError::DuplicatePoints);
+        }
+
+        // Compute the direction of each segment, and the arc-length at each
+        // point.
+        let directions = points
+            .windows(2)
+            .map(|pair| (pair[1] - pair[0]).normalize())
+            .collect::<Vec<Vector3<f32>>>();
+        let mut arc = vec![0.0];
+        for pair in points.windows(2) {
+            let length = arc[arc.len() - 1] + (pair[1] - pair[0]).magnitude();
+            arc.push(length);
+        }
+        let total = arc[arc.len() - 1];
+
+        // Compute the transformation of the positions and directions.
+        let transform = Transformation::<M>::new(self.matrix);
+
+        // Build the vertices.
+        let mut vertices = Vec::<Vertex>::with_capacity(self.num_vertices());
+        for (index, point) in points.iter().enumerate() {
+            let direction = directions[index.min(directions.len() - 1)];
+            vertices.push(Vertex {
+                position: transform.point(*point),
+                normal: transform.direction(direction),
+                texcoord: [arc[index] / total, 0.0],
+            });
+        }
+
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    pub fn num_vertices(&self) -> usize {
+        self.points.len() + if self.closed { 1 } else { 0 }
+    }
+}
+
+impl HasOptions for PolylineBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for PolylineBuilder {
+    type Shape = Polyline;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> Polyline {
+        Polyline { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for PolylineBuilder {}
+
+impl WithColors for PolylineBuilder {}
+
+#[test]
+pub fn ensure_polyline_texcoords_follow_arc_length() {
+    let vertices = PolylineBuilder::new()
+        .points(&[
+            Point3::<f32>::new(0.0, 0.0, 0.0),
+            Point3::<f32>::new(1.0, 0.0, 0.0),
+            Point3::<f32>::new(1.0, 3.0, 0.0),
+        ])
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let expected = [0.0, 0.25, 1.0];
+    for (vertex, u) in vertices.iter().zip(expected.iter()) {
+        assert_ulps_eq!(vertex.texcoord[0], *u);
+        assert_eq!(vertex.texcoord[1], 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_polyline_normals_store_segment_tangents() {
+    let vertices = PolylineBuilder::new()
+        .points(&[
+            Point3::<f32>::new(0.0, 0.0, 0.0),
+            Point3::<f32>::new(2.0, 0.0, 0.0),
+            Point3::<f32>::new(2.0, 0.0, -1.0),
+        ])
+        .scale(1.0, 1.0, 2.0)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    assert_eq!(vertices[0].normal, [1.0, 0.0, 0.0]);
+    assert_eq!(vertices[1].normal, [0.0, 0.0, -1.0]);
+    assert_eq!(vertices[2].normal, [0.0, 0.0, -1.0]);
+    assert_eq!(vertices[2].position, [2.0, 0.0, -2.0]);
+}
+
+#[test]
+pub fn ensure_closed_polyline_returns_to_first_point() {
+    let builder = PolylineBuilder::new()
+        .points(&[
+            Point3::<f32>::new(0.0, 0.0, 0.0),
+            Point3::<f32>::new(1.0, 0.0, 0.0),
+            Point3::<f32>::new(1.0, 1.0, 0.0),
+            Point3::<f32>::new(0.0, 1.0, 0.0),
+        ])
+        .closed(true);
+    let vertices = builder.build_vertices().expect("Failed to build vertices");
+    assert_eq!(vertices.len(), 5);
+    assert_eq!(vertices.len(), builder.num_vertices());
+    assert_eq!(vertices[4].position, vertices[0].position);
+    assert_eq!(vertices[3].normal, [0.0, -1.0, 0.0]);
+    assert_ulps_eq!(vertices[3].texcoord[0], 0.75);
+    assert_ulps_eq!(vertices[4].texcoord[0], 1.0);
+}
+
+#[test]
+pub fn ensure_polyline_with_invalid_points_fails_to_build() {
+    match PolylineBuilder::new()
+        .points(&[Point3::<f32>::new(0.0, 0.0, 0.0)])
+        .build_vertices()
+    {
+        Err(ShapeCreationError::NotEnoughPoints) => (),
+        _ => panic!("Expected NotEnoughPoints"),
+    }
+    match PolylineBuilder::new()
+        .points(&[
+            Point3::<f32>::new(0.0, 0.0, 0.0),
+            Point3::<f32>::new(0.0, 0.0, 0.0),
+        ])
+        .build_vertices()
+    {
+        Err(ShapeCreationError::DuplicatePoints) => (),
+        _ => panic!("Expected DuplicatePoints"),
+    }
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_polyline() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = PolylineBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
+
+#[test]
+pub fn ensure_colored_polyline_is_painted() {
+    crate::options::ensure_colors(PolylineBuilder::new);
+}
@@ -0,0 +1,350 @@
+//! A module for constructing prism shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::errors::ShapeCreationError;
+use crate::facet::push_polygon;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{
+    BuildAs, HasOptions, HasVertices, ShapeOptions, WithBackFaces, WithColors, WithLightmap,
+    WithModifiers, WithOrientation, WithShading, WithTangents, WithTexcoords, WithTransform,
+};
+use crate::shading::Shading;
+use crate::vertex::Vertex;
+
+use std::f32;
+
+/// A polygonal `Prism` object.
+///
+/// This object is constructed using a `PrismBuilder` object.
+pub struct Prism {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows a `Prism` object to be passed as a source of vertices.
+impl<'a> From<&'a Prism> for glium::vertex::VerticesSource<'a> {
+    fn from(prism: &'a Prism) -> glium::vertex::VerticesSource<'a> {
+        (&prism.vertices).into()
+    }
+}
+
+/// Allows a `Prism` object to be passed as a source of indices.
+impl<'a> From<&'a Prism> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a Prism) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::TrianglesList,
+        }
+    }
+}
+
+/// Responsible for building and returning a `Prism` object.
+///
+/// By default, the prism is defined as a regular hexagonal prism of unit height,
+/// extruded along the y-axis, with its cross-section inscribed in a
+/// unit-diameter circle and its centre-of-mass located at the origin. The first
+/// corner of the cross-section lies on the positive x-axis. This can be
+/// overriden using the transformation methods on this object. The top and
+/// bottom caps are triangulated as fans about their centre points.
+///
+/// The resultant geometry is constructed to suit OpenGL defaults - assuming
+/// a right-handed coordinate system, front-facing polygons are defined in
+/// counter-clock-wise order. Vertex normals point in the direction of their
+/// respective face (such that the shape appears faceted when lit). Vertex
+/// texture coordinates define a planar-projection on each face.
+pub struct PrismBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    sides: usize,
+    options: ShapeOptions,
+}
+
+impl Default for PrismBuilder {
+    fn default() -> Self {
+        PrismBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            sides: 6,
+            options: ShapeOptions::default(),
+        }
+    }
+}
+
+impl PrismBuilder {
+    /// Create a new `PrismBuilder` object.
+    pub fn new() -> PrismBuilder {
+        Default::default()
+    }
+
+    /// Specify the number of sides of the cross-section of the prism. By
+    /// default, the builder will produce a hexagonal prism with 6 sides. At
+    /// least 3 sides are required to build a valid prism.
+    pub fn sides(mut self, sides: usize) -> Self {
+        self.sides = sides;
+        self
+    }
+
+    /// Build a new `Prism` object.
+    pub fn build<F>(self, display: &F) -> Result<Prism, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the shape vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// a `Prism` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        let vertices = self.vertices_with::<Selected>()?;
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.options.check_shading(Self::SHADINGS)?;
+
+        // Ensure there are enough sides to produce valid prism geometry.
+        if self.sides < 3 {
+            return Err(ShapeCreationError::NotEnoughDivisionsInU);
+        }
+
+        // Build the cross-section corners.
+        let angle = 2.0 * f32::consts::PI / self.sides as f32;
+        let corners = (0..self.sides)
+            .map(|x| {
+                let a = x as f32 * angle;
+                Vector2::<f32>::new(a.cos() * 0.5, -a.sin() * 0.5)
+            })
+            .collect::<Vec<Vector2<f32>>>();
+        let bottom = |c: Vector2<f32>| Vector3::<f32>::new(c.x, -0.5, c.y);
+        let top = |c: Vector2<f32>| Vector3::<f32>::new(c.x, 0.5, c.y);
+
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<M>::new(self.matrix);
+
+        // Emit the sides.
+        let mut vertices = Vec::<Vertex>::with_capacity(self.num_vertices());
+        for side in 0..self.sides {
+            let (c0, c1) = (corners[side], corners[(side + 1) % self.sides]);
+            let face = [bottom(c0), bottom(c1), top(c1), top(c0)];
+            push_polygon(&mut vertices, &transform, &face);
+        }
+
+        // Emit the caps. Each cap is fanned about its centre point by leading
+        // with the centre and closing the loop on the first corner.
+        let mut cap = vec![Vector3::<f32>::new(0.0, 0.5, 0.0)];
+        cap.extend(corners.iter().chain(corners.first()).map(|&c| top(c)));
+        push_polygon(&mut vertices, &transform, &cap);
+        let mut cap = vec![Vector3::<f32>::new(0.0, -0.5, 0.0)];
+        cap.extend(
+            corners
+                .iter()
+                .chain(corners.first())
+                .rev()
+                .map(|&c| bottom(c)),
+        );
+        push_polygon(&mut vertices, &transform, &cap);
+
+        self.options.map_texcoords(&mut vertices);
+        Ok(vertices)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    pub fn num_vertices(&self) -> usize {
+        self.sides * 4 * 3 * self.options.copies()
+    }
+}
+
+impl HasOptions for PrismBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for PrismBuilder {
+    type Shape = Prism;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> Prism {
+        Prism { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for PrismBuilder {}
+
+impl BuildAs for PrismBuilder {}
+
+impl WithTangents for PrismBuilder {}
+
+impl WithLightmap for PrismBuilder {}
+
+impl WithOrientation for PrismBuilder {}
+
+impl WithBackFaces for PrismBuilder {}
+
+impl WithModifiers for PrismBuilder {}
+
+impl WithTexcoords for PrismBuilder {}
+
+impl WithColors for PrismBuilder {}
+
+impl WithShading for PrismBuilder {
+    /// Only flat shading is supported by the prism.
+    const SHADINGS: &'static [Shading] = &[Shading::Flat];
+}
+
+#[test]
+pub fn ensure_default_prism_has_unit_dimensions() {
+    let vertices = PrismBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        let pos = Vector3::<f32>::from(vertex.position);
+        assert!(abs_diff_eq!(pos.y.abs(), 0.5));
+        let radius = Vector2::<f32>::new(pos.x, pos.z).magnitude();
+        assert!(abs_diff_eq!(radius, 0.5, epsilon = 0.0001) || abs_diff_eq!(radius, 0.0));
+    }
+}
+
+#[test]
+pub fn ensure_default_prism_has_centroid_at_origin() {
+    let vertices = PrismBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let mut sum = Vector3::<f32>::zero();
+    for vertex in &vertices {
+        sum += Vector3::<f32>::from(vertex.position);
+    }
+    assert_ulps_eq!(sum, Vector3::<f32>::zero(), epsilon = 0.0001);
+}
+
+#[test]
+pub fn ensure_prism_has_four_triangles_per_side() {
+    for sides in 3..10 {
+        let vertices = PrismBuilder::new()
+            .sides(sides)
+            .build_vertices()
+            .expect("Failed to build vertices");
+        assert_eq!(vertices.len() / 3, 4 * sides);
+    }
+}
+
+#[test]
+pub fn ensure_default_prism_has_outward_facing_normals() {
+    let vertices = PrismBuilder::new()
+        .sides(5)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let centroid = (Vector3::<f32>::from(chunk[0].position)
+            + Vector3::<f32>::from(chunk[1].position)
+            + Vector3::<f32>::from(chunk[2].position))
+            / 3.0;
+        let normal = Vector3::<f32>::from(chunk[0].normal);
+        assert!(normal.dot(centroid) > 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_default_prism_has_uvs_in_unit_range() {
+    let vertices = PrismBuilder::new()
+        .sides(7)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        assert!(vertex.texcoord[0] >= -0.0001 && vertex.texcoord[0] <= 1.0001);
+        assert!(vertex.texcoord[1] >= -0.0001 && vertex.texcoord[1] <= 1.0001);
+    }
+}
+
+#[test]
+pub fn ensure_default_prism_has_ccw_triangles() {
+    let vertices = PrismBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let eyepos = v0 + Vector3::<f32>::from(chunk[0].normal);
+        let e0 = v1 - v0;
+        let e1 = v2 - v0;
+        let n = e0.cross(e1);
+        assert!(n.dot(v0 - eyepos) <= 0.0);
+        assert!(n.dot(v1 - eyepos) <= 0.0);
+        assert!(n.dot(v2 - eyepos) <= 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_default_prism_has_faceted_normals() {
+    let vertices = PrismBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let n = (v1 - v0).cross(v2 - v0).normalize();
+        for vertex in chunk {
+            assert_ulps_eq!(n, Vector3::<f32>::from(vertex.normal), epsilon = 0.0001);
+        }
+    }
+}
+
+#[test]
+pub fn ensure_prism_with_too_few_sides_fails_to_build() {
+    match PrismBuilder::new().sides(2).build_vertices() {
+        Err(ShapeCreationError::NotEnoughDivisionsInU) => (),
+        _ => panic!("Expected NotEnoughDivisionsInU"),
+    }
+}
+
+#[test]
+pub fn ensure_prism_applies_late_pipeline() {
+    use crate::options::WithTexcoords;
+    use crate::uv_mapping::UvMapping;
+
+    crate::options::ensure_late_pipeline(
+        || {
+            PrismBuilder::new()
+                .uv_mapping(UvMapping::Box)
+                .double_sided()
+        },
+        PrismBuilder::build_vertices,
+    );
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_prism() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = PrismBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
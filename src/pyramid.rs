@@ -0,0 +1,319 @@
+//! A module for constructing pyramid shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::errors::ShapeCreationError;
+use crate::facet::push_polygon;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{
+    BuildAs, HasOptions, HasVertices, ShapeOptions, WithBackFaces, WithColors, WithLightmap,
+    WithModifiers, WithOrientation, WithShading, WithTangents, WithTexcoords, WithTransform,
+};
+use crate::shading::Shading;
+use crate::vertex::Vertex;
+
+use std::f32;
+
+/// A polygonal `Pyramid` object.
+///
+/// This object is constructed using a `PyramidBuilder` object.
+pub struct Pyramid {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows a `Pyramid` object to be passed as a source of vertices.
+impl<'a> From<&'a Pyramid> for glium::vertex::VerticesSource<'a> {
+    fn from(pyramid: &'a Pyramid) -> glium::vertex::VerticesSource<'a> {
+        (&pyramid.vertices).into()
+    }
+}
+
+/// Allows a `Pyramid` object to be passed as a source of indices.
+impl<'a> From<&'a Pyramid> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a Pyramid) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::TrianglesList,
+        }
+    }
+}
+
+/// Responsible for building and returning a `Pyramid` object.
+///
+/// By default, the pyramid is defined as a square-based pyramid of unit height,
+/// with unit-length base edges aligned to the x and z axes, its apex located at
+/// `(0, 0.5, 0)`, and its base centred at `(0, -0.5, 0)`. The number of sides of
+/// the base can be changed, in which case the base vertices lie on the same
+/// circle as those of the square base. This can be overriden using the
+/// transformation methods on this object.
+///
+/// The resultant geometry is constructed to suit OpenGL defaults - assuming
+/// a right-handed coordinate system, front-facing polygons are defined in
+/// counter-clock-wise order. Vertex normals point in the direction of their
+/// respective face (such that the shape appears faceted when lit). Vertex
+/// texture coordinates define a planar-projection on each face.
+pub struct PyramidBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    base_segments: usize,
+    options: ShapeOptions,
+}
+
+impl Default for PyramidBuilder {
+    fn default() -> Self {
+        PyramidBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            base_segments: 4,
+            options: ShapeOptions::default(),
+        }
+    }
+}
+
+impl PyramidBuilder {
+    /// Create a new `PyramidBuilder` object.
+    pub fn new() -> PyramidBuilder {
+        Default::default()
+    }
+
+    /// Specify the number of sides of the base of the pyramid. By default, the
+    /// builder will use a square base with 4 sides. At least 3 sides are
+    /// required to build a valid pyramid.
+    pub fn base_segments(mut self, segments: usize) -> Self {
+        self.base_segments = segments;
+        self
+    }
+
+    /// Build a new `Pyramid` object.
+    pub fn build<F>(self, display: &F) -> Result<Pyramid, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the shape vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// a `Pyramid` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        let vertices = self.vertices_with::<Selected>()?;
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.options.check_shading(Self::SHADINGS)?;
+
+        // Ensure there are enough sides to produce valid pyramid geometry.
+        if self.base_segments < 3 {
+            return Err(ShapeCreationError::NotEnoughDivisionsInU);
+        }
+
+        // Build the base vertices, offset by half a step such that the edges of
+        // the square base are aligned to the x and z axes.
+        let radius = f32::consts::FRAC_1_SQRT_2;
+        let angle = 2.0 * f32::consts::PI / self.base_segments as f32;
+        let base = (0..self.base_segments)
+            .map(|x| {
+                let a = (x as f32 + 0.5) * angle;
+                Vector3::<f32>::new(a.cos() * radius, -0.5, -a.sin() * radius)
+            })
+            .collect::<Vec<Vector3<f32>>>();
+        let apex = Vector3::<f32>::new(0.0, 0.5, 0.0);
+
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<M>::new(self.matrix);
+
+        // Emit the sides, followed by the base.
+        let mut vertices = Vec::<Vertex>::with_capacity(self.num_vertices());
+        for side in 0..self.base_segments {
+            let next = (side + 1) % self.base_segments;
+            let face = [base[side], base[next], apex];
+            push_polygon(&mut vertices, &transform, &face);
+        }
+        let bottom = base.iter().rev().cloned().collect::<Vec<Vector3<f32>>>();
+        push_polygon(&mut vertices, &transform, &bottom);
+
+        self.options.map_texcoords(&mut vertices);
+        Ok(vertices)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    pub fn num_vertices(&self) -> usize {
+        ((self.base_segments * 3) + ((self.base_segments - 2) * 3)) * self.options.copies()
+    }
+}
+
+impl HasOptions for PyramidBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for PyramidBuilder {
+    type Shape = Pyramid;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> Pyramid {
+        Pyramid { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for PyramidBuilder {}
+
+impl BuildAs for PyramidBuilder {}
+
+impl WithTangents for PyramidBuilder {}
+
+impl WithLightmap for PyramidBuilder {}
+
+impl WithOrientation for PyramidBuilder {}
+
+impl WithBackFaces for PyramidBuilder {}
+
+impl WithModifiers for PyramidBuilder {}
+
+impl WithTexcoords for PyramidBuilder {}
+
+impl WithColors for PyramidBuilder {}
+
+impl WithShading for PyramidBuilder {
+    /// Only flat shading is supported by the pyramid.
+    const SHADINGS: &'static [Shading] = &[Shading::Flat];
+}
+
+#[test]
+pub fn ensure_default_pyramid_has_unit_dimensions() {
+    let vertices = PyramidBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        let pos = Vector3::<f32>::from(vertex.position);
+        if pos.y > 0.0 {
+            assert_ulps_eq!(pos, Vector3::<f32>::new(0.0, 0.5, 0.0));
+        } else {
+            assert!(abs_diff_eq!(pos.x.abs(), 0.5, epsilon = 0.0001));
+            assert!(abs_diff_eq!(pos.y, -0.5));
+            assert!(abs_diff_eq!(pos.z.abs(), 0.5, epsilon = 0.0001));
+        }
+    }
+}
+
+#[test]
+pub fn ensure_default_pyramid_has_outward_facing_normals() {
+    let vertices = PyramidBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let centroid = (Vector3::<f32>::from(chunk[0].position)
+            + Vector3::<f32>::from(chunk[1].position)
+            + Vector3::<f32>::from(chunk[2].position))
+            / 3.0;
+        let normal = Vector3::<f32>::from(chunk[0].normal);
+        assert!(normal.dot(centroid - Vector3::<f32>::new(0.0, -0.25, 0.0)) > 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_default_pyramid_has_uvs_in_unit_range() {
+    let vertices = PyramidBuilder::new()
+        .base_segments(7)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        assert!(vertex.texcoord[0] >= -0.0001 && vertex.texcoord[0] <= 1.0001);
+        assert!(vertex.texcoord[1] >= -0.0001 && vertex.texcoord[1] <= 1.0001);
+    }
+}
+
+#[test]
+pub fn ensure_default_pyramid_has_ccw_triangles() {
+    let vertices = PyramidBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let eyepos = v0 + Vector3::<f32>::from(chunk[0].normal);
+        let e0 = v1 - v0;
+        let e1 = v2 - v0;
+        let n = e0.cross(e1);
+        assert!(n.dot(v0 - eyepos) <= 0.0);
+        assert!(n.dot(v1 - eyepos) <= 0.0);
+        assert!(n.dot(v2 - eyepos) <= 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_default_pyramid_has_faceted_normals() {
+    let vertices = PyramidBuilder::new()
+        .base_segments(5)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let n = (v1 - v0).cross(v2 - v0).normalize();
+        for vertex in chunk {
+            assert_ulps_eq!(n, Vector3::<f32>::from(vertex.normal), epsilon = 0.0001);
+        }
+    }
+}
+
+#[test]
+pub fn ensure_pyramid_with_too_few_sides_fails_to_build() {
+    match PyramidBuilder::new().base_segments(2).build_vertices() {
+        Err(ShapeCreationError::NotEnoughDivisionsInU) => (),
+        _ => panic!("Expected NotEnoughDivisionsInU"),
+    }
+}
+
+#[test]
+pub fn ensure_pyramid_applies_late_pipeline() {
+    use crate::options::WithTexcoords;
+    use crate::uv_mapping::UvMapping;
+
+    crate::options::ensure_late_pipeline(
+        || {
+            PyramidBuilder::new()
+                .uv_mapping(UvMapping::Box)
+                .double_sided()
+        },
+        PyramidBuilder::build_vertices,
+    );
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_pyramid() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = PyramidBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
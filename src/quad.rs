@@ -5,7 +5,16 @@ extern crate glium;
 
 use self::cgmath::*;
 use crate::errors::ShapeCreationError;
-use crate::vertex::Vertex;
+use crate::facet::negate_normals;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{
+    BuildAs, HasOptions, HasVertices, ShapeOptions, WithBackFaces, WithColors, WithOrientation,
+    WithShading, WithTangents, WithTexcoords, WithTransform,
+};
+use crate::shading::Shading;
+use crate::tangent::with_strip_tangents;
+use crate::uv_mapping::transform_texcoords;
+use crate::vertex::{Vertex, VertexTangent};
 
 /// A polygonal quad.
 ///
@@ -22,8 +31,8 @@ impl<'a> From<&'a Quad> for glium::vertex::VerticesSource<'a> {
 }
 
 /// Allows a `Quad` object to be passed as a source of indices.
-impl<'a> Into<glium::index::IndicesSource<'a>> for &'a Quad {
-    fn into(self) -> glium::index::IndicesSource<'a> {
+impl<'a> From<&'a Quad> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a Quad) -> glium::index::IndicesSource<'a> {
         glium::index::IndicesSource::NoIndices {
             primitives: glium::index::PrimitiveType::TriangleStrip,
         }
@@ -44,14 +53,22 @@ impl<'a> Into<glium::index::IndicesSource<'a>> for &'a Quad {
 /// counter-clock-wise order. Vertex normals point in the direction of their
 /// respective face (such that the shape appears faceted when lit). Vertex
 /// texture coordinates define a planar-projection on the face.
+///
+/// The quad can optionally be made double-sided, in which case a reversed copy
+/// of the quad with flipped normals is appended to the triangle strip, such
+/// that the quad can be seen from both sides with back-face culling enabled.
+/// The back face is joined to the front face by a pair of degenerate
+/// triangles.
 pub struct QuadBuilder {
     matrix: cgmath::Matrix4<f32>,
+    options: ShapeOptions,
 }
 
 impl Default for QuadBuilder {
     fn default() -> QuadBuilder {
         QuadBuilder {
             matrix: cgmath::Matrix4::<f32>::identity(),
+            options: ShapeOptions::default(),
         }
     }
 }
@@ -62,88 +79,12 @@ impl QuadBuilder {
         Default::default()
     }
 
-    /// Apply a scaling transformation to the shape.
-    ///
-    /// The `scale`, `translate`, and `rotate` functions accumulate, and are
-    /// not commutative. The transformation functions are intended to provide
-    /// flexibility in model-space. For per-instance world-space transformations,
-    /// one should prefer to share as few shapes as possible across multiple
-    /// instances, and instead rely on uniform constants in the shader and/or
-    /// instanced drawing.
-    pub fn scale(mut self, x: f32, y: f32, z: f32) -> Self {
-        self.matrix = cgmath::Matrix4::from_nonuniform_scale(x, y, z) * self.matrix;
-        self
-    }
-
-    /// Apply a translation transformation to the shape.
-    ///
-    /// The `scale`, `translate`, and `rotate` functions accumulate, and are
-    /// not commutative. The transformation functions are intended to provide
-    /// flexibility in model-space. For per-instance world-space transformations,
-    /// one should prefer to share as few shapes as possible across multiple
-    /// instances, and instead rely on uniform constants in the shader and/or
-    /// instanced drawing.
-    pub fn translate(mut self, x: f32, y: f32, z: f32) -> Self {
-        self.matrix = cgmath::Matrix4::from_translation([x, y, z].into()) * self.matrix;
-        self
-    }
-
-    /// Apply a rotation transformation to the shape about the x-axis.
-    ///
-    /// The `scale`, `translate`, and `rotate` functions accumulate, and are
-    /// not commutative. The transformation functions are intended to provide
-    /// flexibility in model-space. For per-instance world-space transformations,
-    /// one should prefer to share as few shapes as possible across multiple
-    /// instances, and instead rely on uniform constants in the shader and/or
-    /// instanced drawing.
-    pub fn rotate_x(mut self, radians: f32) -> Self {
-        self.matrix = cgmath::Matrix4::<f32>::from(cgmath::Matrix3::<f32>::from_angle_x(
-            cgmath::Rad::<f32>(radians),
-        )) * self.matrix;
-        self
-    }
-
-    /// Apply a rotation transformation to the shape about the y-axis.
-    ///
-    /// The `scale`, `translate`, and `rotate` functions accumulate, and are
-    /// not commutative. The transformation functions are intended to provide
-    /// flexibility in model-space. For per-instance world-space transformations,
-    /// one should prefer to share as few shapes as possible across multiple
-    /// instances, and instead rely on uniform constants in the shader and/or
-    /// instanced drawing.
-    pub fn rotate_y(mut self, radians: f32) -> Self {
-        self.matrix = cgmath::Matrix4::<f32>::from(cgmath::Matrix3::<f32>::from_angle_y(
-            cgmath::Rad::<f32>(radians),
-        )) * self.matrix;
-        self
-    }
-
-    /// Apply a rotation transformation to the shape about the z-axis.
-    ///
-    /// The `scale`, `translate`, and `rotate` functions accumulate, and are
-    /// not commutative. The transformation functions are intended to provide
-    /// flexibility in model-space. For per-instance world-space transformations,
-    /// one should prefer to share as few shapes as possible across multiple
-    /// instances, and instead rely on uniform constants in the shader and/or
-    /// instanced drawing.
-    pub fn rotate_z(mut self, radians: f32) -> Self {
-        self.matrix = cgmath::Matrix4::<f32>::from(cgmath::Matrix3::<f32>::from_angle_z(
-            cgmath::Rad::<f32>(radians),
-        )) * self.matrix;
-        self
-    }
-
     /// Build a new `Quad` object.
     pub fn build<F>(self, display: &F) -> Result<Quad, ShapeCreationError>
     where
         F: glium::backend::Facade,
     {
-        let vertices =
-            glium::vertex::VertexBuffer::<Vertex>::new(display, &self.build_vertices()?)?;
-
-        Ok(Quad {
-            vertices: glium::vertex::VertexBufferAny::from(vertices),
-        })
+        Ok(self.shape(self.buffer(display)?))
     }
 
     /// Build the Quad vertices and return them in a vector.
@@ -151,33 +92,123 @@ impl QuadBuilder {
     /// Useful if you wish to do other things with the vertices besides constructing
     /// a `Quad` object (e.g. unit testing, further processing, etc).
     pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
-        // Compute the normal transformation matrix.
-        let normal_matrix = Matrix3::<f32>::from_cols(
-            self.matrix.x.truncate(),
-            self.matrix.y.truncate(),
-            self.matrix.z.truncate(),
-        )
-        .invert()
-        .unwrap_or_else(Matrix3::<f32>::identity)
-        .transpose();
+        self.vertices_with::<Selected>()
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.options.check_shading(Self::SHADINGS)?;
+
+        // The texture coordinate projections may differ between the triangles
+        // that share a vertex of the strip.
+        self.options.check_uv_mapping()?;
+
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<M>::new(self.matrix);
 
         // Build the vertices.
         let verts_per_quad = 4;
-        let mut vertices = Vec::<Vertex>::with_capacity(verts_per_quad);
+        let mut vertices = Vec::<Vertex>::with_capacity(verts_per_quad * 2 + 2);
         for vert in 0..verts_per_quad {
             let (u, v) = ((vert / 2) as f32, (vert % 2) as f32);
-            let position = Vector4::<f32>::new((u * 2.0) - 1.0, (v * 2.0) - 1.0, 0.0, 1.0);
+            let position = Vector3::<f32>::new((u * 2.0) - 1.0, (v * 2.0) - 1.0, 0.0);
             let normal = Vector3::<f32>::new(0.0, 0.0, -1.0);
             vertices.push(Vertex {
-                position: Point3::<f32>::from_homogeneous(self.matrix * position).into(),
-                normal: (normal_matrix * normal).normalize().into(),
+                position: transform.point(position),
+                normal: transform.normal(normal),
                 texcoord: [u, v],
             });
         }
+
+        transform_texcoords(&self.options.uv_matrix, &mut vertices);
         Ok(vertices)
     }
 }
 
+impl HasOptions for QuadBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for QuadBuilder {
+    type Shape = Quad;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> Quad {
+        Quad { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+
+    /// The quad is built as a triangle strip, so the options are applied to it
+    /// as a strip, rather than as a list of triangles.
+    fn finish(&self, vertices: &mut Vec<Vertex>) -> Result<(), ShapeCreationError> {
+        // Convert the strip into the convention, and reverse its winding when
+        // required.
+        self.options.orient_strip(vertices);
+
+        // Append the back face. The strip is extended with degenerate triangles
+        // that join the last vertex of the front face to the first vertex of the
+        // back face, leaving the back face with the same strip parity as the
+        // front face.
+        if self.options.double_sided {
+            let flip = |vertex: &Vertex| Vertex {
+                normal: (-Vector3::<f32>::from(vertex.normal)).into(),
+                ..*vertex
+            };
+            let back = [vertices[3], flip(&vertices[1]), flip(&vertices[1])];
+            let face = [flip(&vertices[0]), flip(&vertices[3]), flip(&vertices[2])];
+            vertices.extend_from_slice(&back);
+            vertices.extend_from_slice(&face);
+        }
+        if self.options.flip_normals {
+            negate_normals(vertices);
+        }
+        Ok(())
+    }
+}
+
+impl WithTransform for QuadBuilder {}
+
+impl BuildAs for QuadBuilder {}
+
+impl WithTangents for QuadBuilder {
+    /// The tangents are accumulated over the triangles of the strip, rather
+    /// than over a list of triangles.
+    fn build_vertices_with_tangents(&self) -> Result<Vec<VertexTangent>, ShapeCreationError> {
+        Ok(with_strip_tangents(&self.build_vertices()?))
+    }
+}
+
+impl WithOrientation for QuadBuilder {}
+
+impl WithBackFaces for QuadBuilder {}
+
+impl WithTexcoords for QuadBuilder {}
+
+impl WithColors for QuadBuilder {}
+
+impl WithShading for QuadBuilder {
+    /// By default, the quad is flat shaded. Both modes are supported, and build
+    /// the same normals, since the quad is planar.
+    const SHADINGS: &'static [Shading] = &[Shading::Flat, Shading::Smooth];
+}
+
 #[test]
 pub fn ensure_default_quad_has_edge_lengths_of_two() {
     use std::f32;
@@ -287,3 +318,189 @@ pub fn ensure_quad_uvs_are_in_correct_range() {
     assert!(min == Vector2::<f32>::zero());
     assert!(max == Vector2::<f32>::from_value(1.0));
 }
+
+#[test]
+pub fn ensure_double_sided_quad_preserves_front_face() {
+    let front = QuadBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let vertices = QuadBuilder::new()
+        .double_sided()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    assert_eq!(vertices.len(), 10);
+    for (a, b) in front.iter().zip(vertices.iter()) {
+        assert_eq!(a.position, b.position);
+        assert_eq!(a.normal, b.normal);
+        assert_eq!(a.texcoord, b.texcoord);
+    }
+}
+
+#[test]
+pub fn ensure_double_sided_quad_has_ccw_back_face() {
+    let vertices = QuadBuilder::new()
+        .double_sided()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let mut back_faces = 0;
+    for i in 0..vertices.len() - 2 {
+        // Decode the triangle strip, accounting for the alternating winding
+        // of each triangle, and skipping degenerate triangles.
+        let tri = if i % 2 == 0 {
+            [i, i + 1, i + 2]
+        } else {
+            [i + 1, i, i + 2]
+        };
+        let v0 = Vector3::<f32>::from(vertices[tri[0]].position);
+        let v1 = Vector3::<f32>::from(vertices[tri[1]].position);
+        let v2 = Vector3::<f32>::from(vertices[tri[2]].position);
+        let n = (v1 - v0).cross(v2 - v0);
+        if n.magnitude() == 0.0 {
+            continue;
+        }
+        for &index in tri.iter() {
+            assert!(n.dot(Vector3::<f32>::from(vertices[index].normal)) > 0.0);
+        }
+        if vertices[tri[0]].normal == [0.0, 0.0, 1.0] {
+            back_faces += 1;
+        }
+    }
+    assert_eq!(back_faces, 2);
+}
+
+#[test]
+pub fn ensure_flipped_quad_has_mirrored_corner_uvs() {
+    use crate::options::WithTexcoords;
+
+    let texcoords = |builder: QuadBuilder| {
+        builder
+            .build_vertices()
+            .expect("Failed to build vertices")
+            .iter()
+            .map(|vertex| vertex.texcoord)
+            .collect::<Vec<[f32; 2]>>()
+    };
+    assert_eq!(
+        texcoords(QuadBuilder::new().flip_v()),
+        vec![[0.0, 1.0], [0.0, 0.0], [1.0, 1.0], [1.0, 0.0]]
+    );
+    assert_eq!(
+        texcoords(QuadBuilder::new().flip_u()),
+        vec![[1.0, 0.0], [1.0, 1.0], [0.0, 0.0], [0.0, 1.0]]
+    );
+    assert_eq!(
+        texcoords(QuadBuilder::new().uv_scale(0.5, 0.5).flip_v()),
+        vec![[0.0, 1.0], [0.0, 0.5], [0.5, 1.0], [0.5, 0.5]]
+    );
+}
+
+#[test]
+pub fn ensure_flipped_quad_strip_negates_normals_only() {
+    let original = QuadBuilder::new()
+        .double_sided()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let flipped = QuadBuilder::new()
+        .double_sided()
+        .flip_normals()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    assert_eq!(original.len(), flipped.len());
+    for (a, b) in original.iter().zip(flipped.iter()) {
+        assert_eq!(a.position, b.position);
+        assert_eq!(a.texcoord, b.texcoord);
+        assert_eq!(Vector3::from(a.normal), -Vector3::from(b.normal));
+    }
+}
+
+#[test]
+pub fn ensure_cw_quad_has_cw_triangles() {
+    use crate::winding::Winding;
+
+    let vertices = QuadBuilder::new()
+        .winding(Winding::Cw)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let tris = [[0, 1, 2], [2, 1, 3]];
+    for tri in tris.iter() {
+        let v0 = Vector3::<f32>::from(vertices[tri[0]].position);
+        let v1 = Vector3::<f32>::from(vertices[tri[1]].position);
+        let v2 = Vector3::<f32>::from(vertices[tri[2]].position);
+        let eyepos = v0 + Vector3::<f32>::from(vertices[tri[0]].normal);
+        let e0 = v1 - v0;
+        let e1 = v2 - v0;
+        let n = e0.cross(e1);
+        assert!(n.dot(v0 - eyepos) >= 0.0);
+        assert!(n.dot(v1 - eyepos) >= 0.0);
+        assert!(n.dot(v2 - eyepos) >= 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_left_handed_quad_swaps_strip_vertices() {
+    use crate::convention::Convention;
+
+    let original = QuadBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let vertices = QuadBuilder::new()
+        .convention(Convention::YUpLeftHanded)
+        .build_vertices()
+        .expect("Failed to build vertices");
+
+    // The z-axis is mirrored, and the middle vertices of the strip are
+    // swapped to undo the reversal of its winding by the reflection.
+    let mirror = |v: [f32; 3]| [v[0], v[1], -v[2]];
+    for (index, &source) in [0, 2, 1, 3].iter().enumerate() {
+        assert_eq!(vertices[index].position, mirror(original[source].position));
+        assert_eq!(vertices[index].normal, mirror(original[source].normal));
+        assert_eq!(vertices[index].texcoord, original[source].texcoord);
+    }
+
+    // Each triangle of the strip faces the direction of its normals, such
+    // that it is wound clock-wise when viewed from the front in left-handed
+    // coordinates.
+    let tris = [[0, 1, 2], [2, 1, 3]];
+    for tri in tris.iter() {
+        let v0 = Vector3::<f32>::from(vertices[tri[0]].position);
+        let v1 = Vector3::<f32>::from(vertices[tri[1]].position);
+        let v2 = Vector3::<f32>::from(vertices[tri[2]].position);
+        let n = (v1 - v0).cross(v2 - v0);
+        assert!(n.dot(Vector3::<f32>::from(vertices[tri[0]].normal)) > 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_quad_applies_late_pipeline() {
+    crate::options::ensure_late_strip_pipeline(QuadBuilder::new, QuadBuilder::build_vertices);
+}
+
+#[test]
+pub fn ensure_quad_with_uv_mapping_fails_to_build() {
+    use crate::options::WithTexcoords;
+    use crate::uv_mapping::UvMapping;
+
+    match QuadBuilder::new()
+        .uv_mapping(UvMapping::Box)
+        .build_vertices()
+    {
+        Err(ShapeCreationError::UnsupportedUvMapping) => (),
+        _ => panic!("Expected UnsupportedUvMapping"),
+    }
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_quad() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = QuadBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
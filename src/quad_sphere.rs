@@ -0,0 +1,356 @@
+//! A module for constructing quad sphere shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::errors::ShapeCreationError;
+use crate::facet::flatten_normals;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{
+    BuildAs, HasOptions, HasVertices, ShapeOptions, WithBackFaces, WithColors, WithLightmap,
+    WithModifiers, WithOrientation, WithShading, WithTangents, WithTexcoords, WithTransform,
+};
+use crate::shading::Shading;
+use crate::vertex::Vertex;
+
+/// A polygonal `QuadSphere` object.
+///
+/// This object is constructed using a `QuadSphereBuilder` object.
+pub struct QuadSphere {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows a `QuadSphere` object to be passed as a source of vertices.
+impl<'a> From<&'a QuadSphere> for glium::vertex::VerticesSource<'a> {
+    fn from(sphere: &'a QuadSphere) -> glium::vertex::VerticesSource<'a> {
+        (&sphere.vertices).into()
+    }
+}
+
+/// Allows a `QuadSphere` object to be passed as a source of indices.
+impl<'a> From<&'a QuadSphere> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a QuadSphere) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::TrianglesList,
+        }
+    }
+}
+
+/// Responsible for building and returning a `QuadSphere` object.
+///
+/// The quad sphere is constructed by subdividing each side of a cube into a
+/// grid of quads, and projecting the corners of the quads onto a sphere. By
+/// default, the sphere is defined as a unit-sphere (e.g. a radius of 1) with
+/// its centre-of-mass located at the origin. This can be overriden using the
+/// transformation methods on this object.
+///
+/// The sides are emitted in the order -X, +X, -Y, +Y, -Z, +Z, such that each
+/// side occupies a contiguous range of `num_vertices_per_face` vertices, and
+/// the edges between neighbouring sides share exactly the same positions. The
+/// resultant geometry is constructed to suit OpenGL defaults - assuming a
+/// right-handed coordinate system, front-facing polygons are defined in
+/// counter-clock-wise order. Vertex normals point away from the centre of the
+/// sphere (such that the sphere appears smooth when lit). Vertex texture
+/// coordinates map each side to the unit square, with the v axis pointing up
+/// (or along the negative z-axis on the +Y side, and the positive z-axis on the
+/// -Y side) when viewed from outside.
+pub struct QuadSphereBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    subdivisions: usize,
+    options: ShapeOptions,
+}
+
+impl Default for QuadSphereBuilder {
+    fn default() -> Self {
+        QuadSphereBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            subdivisions: 8,
+            options: ShapeOptions {
+                shading: Shading::Smooth,
+                ..ShapeOptions::default()
+            },
+        }
+    }
+}
+
+impl QuadSphereBuilder {
+    /// Create a new `QuadSphereBuilder` object.
+    pub fn new() -> QuadSphereBuilder {
+        Default::default()
+    }
+
+    /// Specify the number of quads along each edge of each side of the cube.
+    /// By default, the builder will use 8 subdivisions. At least 1 subdivision
+    /// is required to build a valid quad sphere.
+    pub fn subdivisions(mut self, subdivisions: usize) -> Self {
+        self.subdivisions = subdivisions;
+        self
+    }
+
+    /// Build a new `QuadSphere` object.
+    pub fn build<F>(self, display: &F) -> Result<QuadSphere, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the shape vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// a `QuadSphere` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        let vertices = self.vertices_with::<Selected>()?;
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.options.check_shading(Self::SHADINGS)?;
+
+        // Ensure there are enough subdivisions to produce valid sphere geometry.
+        if self.subdivisions < 1 {
+            return Err(ShapeCreationError::NotEnoughDivisionsInU);
+        }
+
+        // Define the basis of each side, such that the u and v axes are wound
+        // counter-clock-wise about the normal.
+        let sides = [
+            (-Vector3::unit_x(), Vector3::unit_z(), Vector3::unit_y()),
+            (Vector3::unit_x(), -Vector3::unit_z(), Vector3::unit_y()),
+            (-Vector3::unit_y(), Vector3::unit_x(), Vector3::unit_z()),
+            (Vector3::unit_y(), Vector3::unit_x(), -Vector3::unit_z()),
+            (-Vector3::unit_z(), -Vector3::unit_x(), Vector3::unit_y()),
+            (Vector3::unit_z(), Vector3::unit_x(), Vector3::unit_y()),
+        ];
+
+        // Compute the cube coordinate at the given grid index. The coordinate
+        // is computed from an exact integer, such that it is exactly negated
+        // when the grid is traversed in reverse (as it is across some edges).
+        let n = self.subdivisions;
+        let coord = |index: usize| (2.0 * index as f32 - n as f32) / n as f32;
+
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<M>::new(self.matrix);
+
+        // Build vertex array.
+        let mut vertices = Vec::<Vertex>::with_capacity(self.num_vertices());
+        for &(normal, u_axis, v_axis) in sides.iter() {
+            for i in 0..n {
+                for j in 0..n {
+                    for &(di, dj) in [(0, 0), (1, 0), (1, 1), (0, 0), (1, 1), (0, 1)].iter() {
+                        let (i, j) = (i + di, j + dj);
+                        let point = (normal + u_axis * coord(i) + v_axis * coord(j)).normalize();
+                        vertices.push(Vertex {
+                            position: transform.point(point),
+                            normal: transform.normal(point),
+                            texcoord: [i as f32 / n as f32, j as f32 / n as f32],
+                        });
+                    }
+                }
+            }
+        }
+
+        if self.options.shading == Shading::Flat {
+            flatten_normals(&mut vertices);
+        }
+
+        self.options.map_texcoords(&mut vertices);
+        Ok(vertices)
+    }
+
+    /// Returns the number of vertices generated for each side of the cube.
+    pub fn num_vertices_per_face(&self) -> usize {
+        self.subdivisions * self.subdivisions * 6
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    pub fn num_vertices(&self) -> usize {
+        self.num_vertices_per_face() * 6 * self.options.copies()
+    }
+}
+
+impl HasOptions for QuadSphereBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for QuadSphereBuilder {
+    type Shape = QuadSphere;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> QuadSphere {
+        QuadSphere { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for QuadSphereBuilder {}
+
+impl BuildAs for QuadSphereBuilder {}
+
+impl WithTangents for QuadSphereBuilder {}
+
+impl WithLightmap for QuadSphereBuilder {}
+
+impl WithOrientation for QuadSphereBuilder {}
+
+impl WithBackFaces for QuadSphereBuilder {}
+
+impl WithModifiers for QuadSphereBuilder {}
+
+impl WithTexcoords for QuadSphereBuilder {}
+
+impl WithColors for QuadSphereBuilder {}
+
+impl WithShading for QuadSphereBuilder {
+    /// By default, the quad sphere is smooth shaded. When flat shaded, each
+    /// vertex normal points in the direction of its respective triangle
+    /// instead.
+    const SHADINGS: &'static [Shading] = &[Shading::Flat, Shading::Smooth];
+}
+
+#[test]
+pub fn ensure_default_quad_sphere_is_unit_sphere() {
+    let vertices = QuadSphereBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        let position = Vector3::<f32>::from(vertex.position);
+        assert_ulps_eq!(position.magnitude(), 1.0, epsilon = 0.0001);
+        assert_ulps_eq!(
+            Vector3::<f32>::from(vertex.normal),
+            position,
+            epsilon = 0.0001
+        );
+    }
+}
+
+#[test]
+pub fn ensure_quad_sphere_is_crack_free() {
+    use std::collections::HashMap;
+    for &subdivisions in [1, 3, 7].iter() {
+        let vertices = QuadSphereBuilder::new()
+            .subdivisions(subdivisions)
+            .build_vertices()
+            .expect("Failed to build vertices");
+        let mut edges = HashMap::<([u32; 3], [u32; 3]), usize>::new();
+        for chunk in vertices.chunks(3) {
+            for k in 0..3 {
+                let a = chunk[k].position.map(f32::to_bits);
+                let b = chunk[(k + 1) % 3].position.map(f32::to_bits);
+                *edges.entry((a, b)).or_insert(0) += 1;
+            }
+        }
+
+        // Each directed edge is used once, and is reversed by its neighbour.
+        for (&(a, b), &count) in &edges {
+            assert_eq!(count, 1);
+            assert_eq!(edges.get(&(b, a)), Some(&1));
+        }
+    }
+}
+
+#[test]
+pub fn ensure_quad_sphere_faces_each_span_unit_uvs() {
+    let builder = QuadSphereBuilder::new().subdivisions(4);
+    let vertices = builder.build_vertices().expect("Failed to build vertices");
+    let expected = [
+        -Vector3::unit_x(),
+        Vector3::unit_x(),
+        -Vector3::unit_y(),
+        Vector3::unit_y(),
+        -Vector3::unit_z(),
+        Vector3::unit_z(),
+    ];
+    for (face, chunk) in vertices.chunks(builder.num_vertices_per_face()).enumerate() {
+        let mut min = Vector2::<f32>::from_value(1.0);
+        let mut max = Vector2::<f32>::from_value(0.0);
+        for vertex in chunk {
+            let position = Vector3::<f32>::from(vertex.position);
+            assert!(position.dot(expected[face]) >= 0.5);
+            min.x = min.x.min(vertex.texcoord[0]);
+            min.y = min.y.min(vertex.texcoord[1]);
+            max.x = max.x.max(vertex.texcoord[0]);
+            max.y = max.y.max(vertex.texcoord[1]);
+        }
+        assert_eq!(min, Vector2::zero());
+        assert_eq!(max, Vector2::from_value(1.0));
+    }
+}
+
+#[test]
+pub fn ensure_default_quad_sphere_has_ccw_triangles() {
+    let vertices = QuadSphereBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let eyepos = v0 + Vector3::<f32>::from(chunk[0].normal);
+        let e0 = v1 - v0;
+        let e1 = v2 - v0;
+        let n = e0.cross(e1);
+        assert!(n.dot(v0 - eyepos) <= 0.0);
+        assert!(n.dot(v1 - eyepos) <= 0.0);
+        assert!(n.dot(v2 - eyepos) <= 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_quad_sphere_with_no_subdivisions_fails_to_build() {
+    match QuadSphereBuilder::new().subdivisions(0).build_vertices() {
+        Err(ShapeCreationError::NotEnoughDivisionsInU) => (),
+        _ => panic!("Expected NotEnoughDivisionsInU"),
+    }
+}
+
+#[test]
+pub fn ensure_quad_sphere_applies_late_pipeline() {
+    use crate::options::WithTexcoords;
+    use crate::uv_mapping::UvMapping;
+
+    crate::options::ensure_late_pipeline(
+        || {
+            QuadSphereBuilder::new()
+                .uv_mapping(UvMapping::Box)
+                .double_sided()
+        },
+        QuadSphereBuilder::build_vertices,
+    );
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_quad_sphere() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = QuadSphereBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
@@ -0,0 +1,103 @@
+//! A module containing the resolution structure shared by curved shapes.
+
+#[cfg(test)]
+use crate::errors::ShapeCreationError;
+
+/// The number of segments used to approximate a curved shape.
+///
+/// The `around` count is the number of segments around the main axis of the
+/// shape (e.g. the radial segments of a cylinder, or the major segments of a
+/// torus), and the `along` count is the number of segments across the
+/// remaining direction of its surface (e.g. the stacks of a cylinder, or the
+/// minor segments of a torus). By default, a resolution has 24 segments
+/// around and 12 segments along.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Resolution {
+    /// The number of segments around the main axis of the shape.
+    pub around: u32,
+
+    /// The number of segments along the surface of the shape.
+    pub along: u32,
+}
+
+impl Default for Resolution {
+    fn default() -> Self {
+        Resolution {
+            around: 24,
+            along: 12,
+        }
+    }
+}
+
+impl Resolution {
+    /// Create a new `Resolution` object.
+    pub fn new(around: u32, along: u32) -> Resolution {
+        Resolution { around, along }
+    }
+
+    /// Returns whether this resolution has at least as many segments as the
+    /// given minimum resolution, in both directions.
+    pub fn is_at_least(&self, min: Resolution) -> bool {
+        self.around >= min.around && self.along >= min.along
+    }
+}
+
+/// Implemented by the builders of curved shapes, allowing their resolution to
+/// be specified in the same way for all of them.
+///
+/// A builder given a resolution below its `MIN_RESOLUTION` fails to build
+/// with the same error as when the segments are given through its own
+/// functions, which is `ShapeCreationError::NotEnoughDivisionsInU` or
+/// `ShapeCreationError::NotEnoughDivisionsInV` depending on the direction that
+/// has too few segments. A shape without segments along its surface ignores
+/// the `along` count, and has a minimum `along` count of zero.
+pub trait WithResolution: Sized {
+    /// The smallest resolution that builds valid geometry for the shape.
+    const MIN_RESOLUTION: Resolution;
+
+    /// Specify the resolution of the shape.
+    fn resolution(self, resolution: Resolution) -> Self;
+}
+
+/// Ensure that a builder builds at its minimum resolution, and fails to build
+/// with the given errors when it has one segment too few around or along its
+/// surface. The `along` error is `None` for a shape that ignores the `along`
+/// count.
+#[cfg(test)]
+pub(crate) fn ensure_min_resolution<B, N, V, T>(
+    new: N,
+    build_vertices: V,
+    around: ShapeCreationError,
+    along: Option<ShapeCreationError>,
+) where
+    B: WithResolution,
+    N: Fn() -> B,
+    V: Fn(&B) -> Result<Vec<T>, ShapeCreationError>,
+{
+    use std::mem::discriminant;
+    let min = B::MIN_RESOLUTION;
+    assert!(build_vertices(&new().resolution(min)).is_ok());
+    assert!(
+        build_vertices(&new().resolution(Resolution::new(min.around, 0))).is_ok()
+            == along.is_none()
+    );
+    let mut cases = vec![(Resolution::new(min.around - 1, min.along), around)];
+    if let Some(along) = along {
+        cases.push((Resolution::new(min.around, min.along - 1), along));
+    }
+    for (resolution, expected) in cases {
+        match build_vertices(&new().resolution(resolution)) {
+            Err(error) => assert!(discriminant(&error) == discriminant(&expected)),
+            Ok(_) => panic!("Expected {:?}", expected),
+        }
+    }
+}
+
+#[test]
+pub fn ensure_resolution_compares_against_minimum() {
+    let min = Resolution::new(3, 2);
+    assert!(Resolution::default().is_at_least(min));
+    assert!(Resolution::new(3, 2).is_at_least(min));
+    assert!(!Resolution::new(2, 12).is_at_least(min));
+    assert!(!Resolution::new(24, 1).is_at_least(min));
+}
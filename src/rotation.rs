@@ -0,0 +1,52 @@
+//! A module containing the conversion of rotations from the quaternion types
+//! of math libraries.
+
+extern crate cgmath;
+
+use self::cgmath::*;
+use crate::math::{Math, Selected};
+
+/// A rotation given as a unit quaternion, which may be applied to any shape
+/// with its `rotate` function.
+///
+/// The trait is implemented for the quaternions of `cgmath`, of `mint` with the
+/// `mint` feature, and of `glam` with the `glam` feature. A rotation builds
+/// identical vertices whichever type it is given as.
+pub trait IntoQuaternion {
+    /// Returns the rotation as a `cgmath` quaternion.
+    fn into_quaternion(self) -> Quaternion<f32>;
+}
+
+impl IntoQuaternion for Quaternion<f32> {
+    fn into_quaternion(self) -> Quaternion<f32> {
+        self
+    }
+}
+
+#[cfg(feature = "mint")]
+impl IntoQuaternion for mint::Quaternion<f32> {
+    fn into_quaternion(self) -> Quaternion<f32> {
+        Quaternion::new(self.s, self.v.x, self.v.y, self.v.z)
+    }
+}
+
+#[cfg(feature = "glam")]
+impl IntoQuaternion for glam::Quat {
+    fn into_quaternion(self) -> Quaternion<f32> {
+        Quaternion::new(self.w, self.x, self.y, self.z)
+    }
+}
+
+/// Returns the rotation matrix of a quaternion, computed by the selected math.
+pub(crate) fn rotation_matrix(rotation: impl IntoQuaternion) -> Matrix4<f32> {
+    Selected::to_matrix4(Selected::rotation(Selected::quat(
+        rotation.into_quaternion(),
+    )))
+}
+
+#[test]
+pub fn ensure_quarter_turn_about_y_maps_x_onto_negative_z() {
+    let rotation = Quaternion::from_angle_y(Rad(std::f32::consts::FRAC_PI_2));
+    let x = rotation_matrix(rotation) * Vector4::<f32>::unit_x();
+    assert_ulps_eq!(x, -Vector4::<f32>::unit_z(), epsilon = 0.0001);
+}
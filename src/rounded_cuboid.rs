@@ -0,0 +1,466 @@
+//! A module for constructing rounded cuboid shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::errors::ShapeCreationError;
+use crate::facet::flatten_normals;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{
+    BuildAs, HasOptions, HasVertices, ShapeOptions, WithBackFaces, WithColors, WithLightmap,
+    WithModifiers, WithOrientation, WithShading, WithTangents, WithTexcoords, WithTransform,
+};
+use crate::shading::Shading;
+use crate::vertex::Vertex;
+
+use std::f32;
+
+/// A polygonal `RoundedCuboid` object.
+///
+/// This object is constructed using a `RoundedCuboidBuilder` object.
+pub struct RoundedCuboid {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows a `RoundedCuboid` object to be passed as a source of vertices.
+impl<'a> From<&'a RoundedCuboid> for glium::vertex::VerticesSource<'a> {
+    fn from(cuboid: &'a RoundedCuboid) -> glium::vertex::VerticesSource<'a> {
+        (&cuboid.vertices).into()
+    }
+}
+
+/// Allows a `RoundedCuboid` object to be passed as a source of indices.
+impl<'a> From<&'a RoundedCuboid> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a RoundedCuboid) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::TrianglesList,
+        }
+    }
+}
+
+/// Responsible for building and returning a `RoundedCuboid` object.
+///
+/// By default, the rounded cuboid is defined as a unit-cube with its
+/// centre-of-mass located at the origin, whose edges and corners are rounded
+/// with a radius of 0.1. The extents define the size of the bounding box of
+/// the shape, and are not affected by the corner radius. This can be overriden
+/// using the transformation methods on this object, though non-uniform scaling
+/// will distort the rounded edges (prefer to set the extents instead).
+///
+/// Each side is built as a single grid of flat centre, quarter-cylinder edge,
+/// and eighth-sphere corner patches. Edges and corners are split evenly
+/// between the sides that they join, and share identical vertex positions
+/// where they meet, such that the shape is watertight.
+///
+/// The resultant geometry is constructed to suit OpenGL defaults - assuming
+/// a right-handed coordinate system, front-facing polygons are defined in
+/// counter-clock-wise order. Vertex normals on the flat centre of each side
+/// point in the direction of the side, whereas the normals of the rounded
+/// edges and corners point away from the centres of their curvature (such that
+/// they appear smooth when lit). Vertex texture coordinates define a
+/// planar-projection on each side, oriented in the same way as a `Cuboid`.
+pub struct RoundedCuboidBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    extents: [f32; 3],
+    corner_radius: f32,
+    corner_segments: usize,
+    options: ShapeOptions,
+}
+
+impl Default for RoundedCuboidBuilder {
+    fn default() -> Self {
+        RoundedCuboidBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            extents: [1.0, 1.0, 1.0],
+            corner_radius: 0.1,
+            corner_segments: 4,
+            options: ShapeOptions {
+                shading: Shading::Smooth,
+                ..ShapeOptions::default()
+            },
+        }
+    }
+}
+
+impl RoundedCuboidBuilder {
+    /// Create a new `RoundedCuboidBuilder` object.
+    pub fn new() -> RoundedCuboidBuilder {
+        Default::default()
+    }
+
+    /// Specify the size of the rounded cuboid along the x, y, and z axes. By
+    /// default, the rounded cuboid fits within a unit-cube. All extents must
+    /// be greater than zero.
+    pub fn extents(mut self, x: f32, y: f32, z: f32) -> Self {
+        self.extents = [x, y, z];
+        self
+    }
+
+    /// Specify the radius of the rounded edges and corners. By default, the
+    /// radius is 0.1. The radius must not be negative, nor greater than half
+    /// of the smallest extent. Radii at either limit produce triangles with no
+    /// area.
+    pub fn corner_radius(mut self, radius: f32) -> Self {
+        self.corner_radius = radius;
+        self
+    }
+
+    /// Specify the number of segments used to round each half of an edge,
+    /// such that a quarter-cylinder edge is built from twice this number of
+    /// segments. By default, the builder will use 4 segments. At least 1
+    /// segment is required to build a valid rounded cuboid.
+    pub fn corner_segments(mut self, segments: usize) -> Self {
+        self.corner_segments = segments;
+        self
+    }
+
+    /// Build a new `RoundedCuboid` object.
+    pub fn build<F>(self, display: &F) -> Result<RoundedCuboid, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the shape vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// a `RoundedCuboid` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        let vertices = self.vertices_with::<Selected>()?;
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.options.check_shading(Self::SHADINGS)?;
+
+        // Ensure the parameters describe a valid rounded cuboid.
+        if self.corner_segments < 1 {
+            return Err(ShapeCreationError::NotEnoughDivisionsInU);
+        }
+        if self.extents.iter().any(|&e| e <= 0.0) {
+            return Err(ShapeCreationError::InvalidDimensions);
+        }
+        let radius = self.corner_radius;
+        let smallest = self.extents.iter().cloned().fold(f32::MAX, f32::min);
+        if radius < 0.0 || radius > smallest * 0.5 {
+            return Err(ShapeCreationError::InvalidRadii);
+        }
+
+        // Build the samples along each axis as (inner, tangent) pairs, where
+        // the inner coordinate lies on the box that remains after removing the
+        // radius from each side, and the tangent of the angle around the edge
+        // defines the direction of the surface away from it. Each side spans
+        // half of each adjoining edge, such that the tangents run from -1 to 0
+        // across the first half-edge and from 0 to 1 across the second. The
+        // tangents at either end are exact, such that neighbouring sides share
+        // identical vertex positions.
+        let k = self.corner_segments;
+        let tangent = |i: usize| {
+            if i == k {
+                1.0
+            } else {
+                (f32::consts::FRAC_PI_4 * (i as f32 / k as f32)).tan()
+            }
+        };
+        let samples = self
+            .extents
+            .iter()
+            .map(|&e| {
+                let inner = e * 0.5 - radius;
+                let lower = (0..=k).map(|i| (-inner, -tangent(k - i)));
+                let upper = (0..=k).map(|i| (inner, tangent(i)));
+                lower.chain(upper).collect::<Vec<(f32, f32)>>()
+            })
+            .collect::<Vec<Vec<(f32, f32)>>>();
+        let last = samples[0].len() - 1;
+
+        // Define each side by its normal axis and sign, followed by the signed
+        // axes along which the u and v coordinates increase (as with the
+        // cuboid), listed in the same order as the cuboid sides.
+        let sides = [
+            ((0, -1.0), (2, 1.0), (1, 1.0)),  // -X
+            ((0, 1.0), (2, -1.0), (1, 1.0)),  // +X
+            ((1, -1.0), (0, 1.0), (2, 1.0)),  // -Y
+            ((1, 1.0), (0, 1.0), (2, -1.0)),  // +Y
+            ((2, -1.0), (0, -1.0), (1, 1.0)), // -Z
+            ((2, 1.0), (0, 1.0), (1, 1.0)),   // +Z
+        ];
+
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<M>::new(self.matrix);
+
+        // Generate the vertices.
+        let mut vertices = Vec::<Vertex>::with_capacity(self.num_vertices());
+        let quad = [(0, 0), (1, 0), (1, 1), (0, 0), (1, 1), (0, 1)];
+
+        for &((n, n_sign), (a, a_sign), (b, b_sign)) in sides.iter() {
+            let sample = |axis: usize, sign: f32, index: usize| {
+                samples[axis][if sign < 0.0 { last - index } else { index }]
+            };
+            for j in 0..last {
+                for i in 0..last {
+                    for &(di, dj) in quad.iter() {
+                        let (inner_a, tangent_a) = sample(a, a_sign, i + di);
+                        let (inner_b, tangent_b) = sample(b, b_sign, j + dj);
+                        let mut inner = Vector3::<f32>::zero();
+                        let mut direction = Vector3::<f32>::zero();
+                        inner[n] = samples[n][last].0 * n_sign;
+                        inner[a] = inner_a;
+                        inner[b] = inner_b;
+                        direction[n] = n_sign;
+                        direction[a] = tangent_a;
+                        direction[b] = tangent_b;
+                        let normal = direction.normalize();
+                        let position = inner + normal * radius;
+                        let (ea, eb) = (self.extents[a], self.extents[b]);
+                        vertices.push(Vertex {
+                            position: transform.point(position),
+                            normal: transform.normal(normal),
+                            texcoord: [
+                                (position[a] * a_sign + ea * 0.5) / ea,
+                                (position[b] * b_sign + eb * 0.5) / eb,
+                            ],
+                        });
+                    }
+                }
+            }
+        }
+
+        if self.options.shading == Shading::Flat {
+            flatten_normals(&mut vertices);
+        }
+
+        self.options.map_texcoords(&mut vertices);
+        Ok(vertices)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    pub fn num_vertices(&self) -> usize {
+        let cells = self.corner_segments * 2 + 1;
+        6 * cells * cells * 6 * self.options.copies()
+    }
+}
+
+impl HasOptions for RoundedCuboidBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for RoundedCuboidBuilder {
+    type Shape = RoundedCuboid;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> RoundedCuboid {
+        RoundedCuboid { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for RoundedCuboidBuilder {}
+
+impl BuildAs for RoundedCuboidBuilder {}
+
+impl WithTangents for RoundedCuboidBuilder {}
+
+impl WithLightmap for RoundedCuboidBuilder {}
+
+impl WithOrientation for RoundedCuboidBuilder {}
+
+impl WithBackFaces for RoundedCuboidBuilder {}
+
+impl WithModifiers for RoundedCuboidBuilder {}
+
+impl WithTexcoords for RoundedCuboidBuilder {}
+
+impl WithColors for RoundedCuboidBuilder {}
+
+impl WithShading for RoundedCuboidBuilder {
+    /// By default, the rounded cuboid is smooth shaded. When flat shaded, each
+    /// vertex normal points in the direction of its respective triangle
+    /// instead.
+    const SHADINGS: &'static [Shading] = &[Shading::Flat, Shading::Smooth];
+}
+
+#[test]
+pub fn ensure_rounded_cuboid_bounding_box_matches_extents() {
+    let vertices = RoundedCuboidBuilder::new()
+        .extents(2.0, 1.0, 0.5)
+        .corner_radius(0.2)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let mut min = Vector3::<f32>::from_value(f32::MAX);
+    let mut max = Vector3::<f32>::from_value(f32::MIN);
+    for vertex in &vertices {
+        for axis in 0..3 {
+            min[axis] = min[axis].min(vertex.position[axis]);
+            max[axis] = max[axis].max(vertex.position[axis]);
+        }
+    }
+    assert_ulps_eq!(
+        min,
+        Vector3::<f32>::new(-1.0, -0.5, -0.25),
+        epsilon = 0.0001
+    );
+    assert_ulps_eq!(max, Vector3::<f32>::new(1.0, 0.5, 0.25), epsilon = 0.0001);
+}
+
+#[test]
+pub fn ensure_rounded_cuboid_lies_at_radius_from_inner_box() {
+    let vertices = RoundedCuboidBuilder::new()
+        .extents(2.0, 1.0, 0.5)
+        .corner_radius(0.2)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let inner = Vector3::<f32>::new(0.8, 0.3, 0.05);
+    for vertex in &vertices {
+        let pos = Vector3::<f32>::from(vertex.position);
+        let clamped = Vector3::<f32>::new(
+            pos.x.max(-inner.x).min(inner.x),
+            pos.y.max(-inner.y).min(inner.y),
+            pos.z.max(-inner.z).min(inner.z),
+        );
+        assert!(abs_diff_eq!(
+            (pos - clamped).magnitude(),
+            0.2,
+            epsilon = 0.0001
+        ));
+        let normal = Vector3::<f32>::from(vertex.normal);
+        assert_ulps_eq!(normal, (pos - clamped).normalize(), epsilon = 0.0001);
+    }
+}
+
+#[test]
+pub fn ensure_rounded_cuboid_is_watertight() {
+    use std::collections::HashMap;
+    let vertices = RoundedCuboidBuilder::new()
+        .extents(1.0, 2.0, 3.0)
+        .corner_segments(3)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let key = |vertex: &Vertex| vertex.position.map(f32::to_bits);
+    let mut edges = HashMap::new();
+    for chunk in vertices.chunks(3) {
+        for index in 0..3 {
+            let (a, b) = (key(&chunk[index]), key(&chunk[(index + 1) % 3]));
+            *edges
+                .entry(if a < b { (a, b) } else { (b, a) })
+                .or_insert(0) += 1;
+        }
+    }
+    for count in edges.values() {
+        assert_eq!(*count, 2);
+    }
+}
+
+#[test]
+pub fn ensure_rounded_cuboid_has_flat_side_centres() {
+    let vertices = RoundedCuboidBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        let pos = Vector3::<f32>::from(vertex.position);
+        let normal = Vector3::<f32>::from(vertex.normal);
+        let inside = (0..3)
+            .filter(|&axis| pos[axis].abs() <= 0.4 + 0.0001)
+            .count();
+        if inside == 2 {
+            assert!((0..3).any(|axis| abs_diff_eq!(normal[axis].abs(), 1.0)));
+        }
+    }
+}
+
+#[test]
+pub fn ensure_default_rounded_cuboid_has_ccw_triangles() {
+    let vertices = RoundedCuboidBuilder::new()
+        .corner_radius(0.25)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let eyepos = v0 + Vector3::<f32>::from(chunk[0].normal);
+        let e0 = v1 - v0;
+        let e1 = v2 - v0;
+        let n = e0.cross(e1);
+        assert!(n.dot(v0 - eyepos) <= 0.0);
+        assert!(n.dot(v1 - eyepos) <= 0.0);
+        assert!(n.dot(v2 - eyepos) <= 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_default_rounded_cuboid_has_uvs_in_unit_range() {
+    let vertices = RoundedCuboidBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        assert!(vertex.texcoord[0] >= -0.0001 && vertex.texcoord[0] <= 1.0001);
+        assert!(vertex.texcoord[1] >= -0.0001 && vertex.texcoord[1] <= 1.0001);
+    }
+}
+
+#[test]
+pub fn ensure_rounded_cuboid_with_invalid_radius_fails_to_build() {
+    for &radius in [-0.1, 0.26].iter() {
+        match RoundedCuboidBuilder::new()
+            .extents(1.0, 0.5, 1.0)
+            .corner_radius(radius)
+            .build_vertices()
+        {
+            Err(ShapeCreationError::InvalidRadii) => (),
+            _ => panic!("Expected InvalidRadii"),
+        }
+    }
+    match RoundedCuboidBuilder::new()
+        .corner_segments(0)
+        .build_vertices()
+    {
+        Err(ShapeCreationError::NotEnoughDivisionsInU) => (),
+        _ => panic!("Expected NotEnoughDivisionsInU"),
+    }
+}
+
+#[test]
+pub fn ensure_rounded_cuboid_applies_late_pipeline() {
+    crate::options::ensure_late_pipeline(
+        || RoundedCuboidBuilder::new().double_sided(),
+        RoundedCuboidBuilder::build_vertices,
+    );
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_rounded_cuboid() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = RoundedCuboidBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
@@ -0,0 +1,392 @@
+//! A module for constructing rounded rectangle shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::errors::ShapeCreationError;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{
+    BuildAs, HasOptions, HasVertices, ShapeOptions, WithBackFaces, WithColors, WithLightmap,
+    WithModifiers, WithOrientation, WithShading, WithTangents, WithTexcoords, WithTransform,
+};
+use crate::shading::Shading;
+use crate::vertex::Vertex;
+
+use std::f32;
+
+/// A polygonal `RoundedRect` plate.
+///
+/// This object is constructed using a `RoundedRectBuilder` object.
+pub struct RoundedRect {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows a `RoundedRect` object to be passed as a source of vertices.
+impl<'a> From<&'a RoundedRect> for glium::vertex::VerticesSource<'a> {
+    fn from(rect: &'a RoundedRect) -> glium::vertex::VerticesSource<'a> {
+        (&rect.vertices).into()
+    }
+}
+
+/// Allows a `RoundedRect` object to be passed as a source of indices.
+impl<'a> From<&'a RoundedRect> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a RoundedRect) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::TrianglesList,
+        }
+    }
+}
+
+/// Responsible for building and returning a `RoundedRect` object.
+///
+/// By default, the rounded rectangle is defined as a flat unit-square plate in
+/// the xy-plane, facing the positive z-axis, with corners rounded to a radius
+/// of 0.1, and with its centre located at the origin. This can be overriden
+/// using the dimension and transformation methods on this object. The plate is
+/// triangulated as a fan about its centre point, whose outline follows a
+/// circular arc around each corner.
+///
+/// The resultant geometry is constructed to suit OpenGL defaults - assuming
+/// a right-handed coordinate system, front-facing polygons are defined in
+/// counter-clock-wise order. Vertex normals point in the direction of the
+/// plate. Vertex texture coordinates define a planar-projection that maps the
+/// whole rectangle (including the area cut away from its corners) to the unit
+/// square, such that nine-slice style shaders may work from the texture
+/// coordinates.
+///
+/// The plate can optionally be made double-sided, in which case a reversed
+/// copy of the plate with flipped normals is appended, such that the plate can
+/// be seen from both sides with back-face culling enabled.
+pub struct RoundedRectBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    width: f32,
+    height: f32,
+    corner_radius: f32,
+    corner_segments: usize,
+    options: ShapeOptions,
+}
+
+impl Default for RoundedRectBuilder {
+    fn default() -> RoundedRectBuilder {
+        RoundedRectBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            width: 1.0,
+            height: 1.0,
+            corner_radius: 0.1,
+            corner_segments: 4,
+            options: ShapeOptions::default(),
+        }
+    }
+}
+
+impl RoundedRectBuilder {
+    /// Create a new `RoundedRectBuilder` object.
+    pub fn new() -> RoundedRectBuilder {
+        Default::default()
+    }
+
+    /// Specify the width of the rectangle along the x-axis. By default, the
+    /// width is 1. The width must be greater than zero.
+    pub fn width(mut self, width: f32) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Specify the height of the rectangle along the y-axis. By default, the
+    /// height is 1. The height must be greater than zero.
+    pub fn height(mut self, height: f32) -> Self {
+        self.height = height;
+        self
+    }
+
+    /// Specify the radius of the rounded corners. By default, the radius is
+    /// 0.1. The radius must not be negative, nor greater than half of the
+    /// smaller of the width and height. A radius of zero results in square
+    /// corners.
+    pub fn corner_radius(mut self, radius: f32) -> Self {
+        self.corner_radius = radius;
+        self
+    }
+
+    /// Specify the number of segments around the arc of each rounded corner.
+    /// By default, the builder will use 4 segments. At least 1 segment is
+    /// required to build a valid rounded rectangle.
+    pub fn corner_segments(mut self, segments: usize) -> Self {
+        self.corner_segments = segments;
+        self
+    }
+
+    /// Build a new `RoundedRect` object.
+    pub fn build<F>(self, display: &F) -> Result<RoundedRect, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the shape vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// a `RoundedRect` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        let vertices = self.vertices_with::<Selected>()?;
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.options.check_shading(Self::SHADINGS)?;
+
+        // Ensure the parameters produce valid plate geometry.
+        if self.corner_segments < 1 {
+            return Err(ShapeCreationError::NotEnoughDivisionsInU);
+        }
+        if self.width <= 0.0 || self.height <= 0.0 {
+            return Err(ShapeCreationError::InvalidDimensions);
+        }
+        if self.corner_radius < 0.0 || self.corner_radius * 2.0 > self.width.min(self.height) {
+            return Err(ShapeCreationError::InvalidRadii);
+        }
+
+        // Build the outline counter-clock-wise, starting with the arc of the
+        // top-right corner. Square corners are emitted as a single point.
+        let (hw, hh, r) = (self.width * 0.5, self.height * 0.5, self.corner_radius);
+        let centres = [
+            (hw - r, hh - r),
+            (r - hw, hh - r),
+            (r - hw, r - hh),
+            (hw - r, r - hh),
+        ];
+        let arc = self.num_corner_points();
+        let angle = f32::consts::FRAC_PI_2 / self.corner_segments as f32;
+        let mut outline = Vec::<Vector2<f32>>::with_capacity(arc * centres.len());
+        for (corner, &(cx, cy)) in centres.iter().enumerate() {
+            for x in 0..arc {
+                let a = (corner * self.corner_segments + x) as f32 * angle;
+                outline.push(Vector2::<f32>::new(cx + a.cos() * r, cy + a.sin() * r));
+            }
+        }
+
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<M>::new(self.matrix);
+
+        // Emit the face as a fan about the centre.
+        let mut vertices = Vec::<Vertex>::with_capacity(self.num_vertices());
+        let normal = transform.normal(Vector3::<f32>::new(0.0, 0.0, 1.0));
+        for index in 0..outline.len() {
+            let next = (index + 1) % outline.len();
+            for point in [Vector2::<f32>::zero(), outline[index], outline[next]].iter() {
+                let position = Vector3::<f32>::new(point.x, point.y, 0.0);
+                vertices.push(Vertex {
+                    position: transform.point(position),
+                    normal,
+                    texcoord: [point.x / self.width + 0.5, point.y / self.height + 0.5],
+                });
+            }
+        }
+
+        self.options.map_texcoords(&mut vertices);
+        Ok(vertices)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    pub fn num_vertices(&self) -> usize {
+        self.num_corner_points() * 4 * 3 * self.options.copies()
+    }
+
+    /// Returns the number of outline points around each corner.
+    fn num_corner_points(&self) -> usize {
+        if self.corner_radius > 0.0 {
+            self.corner_segments + 1
+        } else {
+            1
+        }
+    }
+}
+
+impl HasOptions for RoundedRectBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for RoundedRectBuilder {
+    type Shape = RoundedRect;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> RoundedRect {
+        RoundedRect { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for RoundedRectBuilder {}
+
+impl BuildAs for RoundedRectBuilder {}
+
+impl WithTangents for RoundedRectBuilder {}
+
+impl WithLightmap for RoundedRectBuilder {}
+
+impl WithOrientation for RoundedRectBuilder {}
+
+impl WithBackFaces for RoundedRectBuilder {}
+
+impl WithModifiers for RoundedRectBuilder {}
+
+impl WithTexcoords for RoundedRectBuilder {}
+
+impl WithColors for RoundedRectBuilder {}
+
+impl WithShading for RoundedRectBuilder {
+    /// By default, the rounded rectangle is flat shaded. Both modes are
+    /// supported, and build the same normals, since the rounded rectangle is
+    /// planar.
+    const SHADINGS: &'static [Shading] = &[Shading::Flat, Shading::Smooth];
+}
+
+#[test]
+pub fn ensure_default_rounded_rect_has_ccw_triangles() {
+    let vertices = RoundedRectBuilder::new()
+        .double_sided()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let eyepos = v0 + Vector3::<f32>::from(chunk[0].normal);
+        let e0 = v1 - v0;
+        let e1 = v2 - v0;
+        let n = e0.cross(e1);
+        assert!(n.dot(v0 - eyepos) <= 0.0);
+        assert!(n.dot(v1 - eyepos) <= 0.0);
+        assert!(n.dot(v2 - eyepos) <= 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_rounded_rect_area_matches_rounded_corners() {
+    let vertices = RoundedRectBuilder::new()
+        .width(2.0)
+        .height(1.0)
+        .corner_radius(0.25)
+        .corner_segments(64)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let mut area = 0.0;
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        area += (v1 - v0).cross(v2 - v0).z * 0.5;
+    }
+    let expected = 2.0 - (4.0 - f32::consts::PI) * 0.25 * 0.25;
+    assert!(abs_diff_eq!(area, expected, epsilon = 0.001));
+}
+
+#[test]
+pub fn ensure_rounded_rect_uvs_map_full_rect_to_unit_square() {
+    let vertices = RoundedRectBuilder::new()
+        .width(3.0)
+        .height(2.0)
+        .corner_radius(0.0)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    assert_eq!(vertices.len(), 4 * 3);
+    for vertex in &vertices {
+        let [x, y, _] = vertex.position;
+        assert_ulps_eq!(vertex.texcoord[0], x / 3.0 + 0.5);
+        assert_ulps_eq!(vertex.texcoord[1], y / 2.0 + 0.5);
+        assert!(vertex.texcoord[0] >= 0.0 && vertex.texcoord[0] <= 1.0);
+        assert!(vertex.texcoord[1] >= 0.0 && vertex.texcoord[1] <= 1.0);
+    }
+}
+
+#[test]
+pub fn ensure_double_sided_rounded_rect_preserves_front_face() {
+    let front = RoundedRectBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let builder = RoundedRectBuilder::new().double_sided();
+    let vertices = builder.build_vertices().expect("Failed to build vertices");
+    assert_eq!(vertices.len(), front.len() * 2);
+    assert_eq!(vertices.len(), builder.num_vertices());
+    for (a, b) in front.iter().zip(vertices.iter()) {
+        assert_eq!(a.position, b.position);
+        assert_eq!(a.normal, b.normal);
+    }
+    for vertex in &vertices[front.len()..] {
+        assert_eq!(vertex.normal, [0.0, 0.0, -1.0]);
+    }
+}
+
+#[test]
+pub fn ensure_rounded_rect_with_invalid_parameters_fails_to_build() {
+    match RoundedRectBuilder::new()
+        .corner_radius(0.51)
+        .build_vertices()
+    {
+        Err(ShapeCreationError::InvalidRadii) => (),
+        _ => panic!("Expected InvalidRadii"),
+    }
+    match RoundedRectBuilder::new().height(0.0).build_vertices() {
+        Err(ShapeCreationError::InvalidDimensions) => (),
+        _ => panic!("Expected InvalidDimensions"),
+    }
+    match RoundedRectBuilder::new()
+        .corner_segments(0)
+        .build_vertices()
+    {
+        Err(ShapeCreationError::NotEnoughDivisionsInU) => (),
+        _ => panic!("Expected NotEnoughDivisionsInU"),
+    }
+}
+
+#[test]
+pub fn ensure_rounded_rect_applies_late_pipeline() {
+    use crate::options::WithTexcoords;
+    use crate::uv_mapping::UvMapping;
+
+    crate::options::ensure_late_pipeline(
+        || {
+            RoundedRectBuilder::new()
+                .uv_mapping(UvMapping::Box)
+                .double_sided()
+        },
+        RoundedRectBuilder::build_vertices,
+    );
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_rounded_rect() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = RoundedRectBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
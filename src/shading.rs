@@ -0,0 +1,17 @@
+//! A module containing the shading modes shared by all shapes.
+
+/// The way in which the vertex normals of a shape are computed.
+///
+/// Each builder documents which of the modes it supports, and fails to build
+/// with `ShapeCreationError::UnsupportedShading` when given a mode that it
+/// does not support.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Shading {
+    /// Each vertex normal points in the direction of its respective face, such
+    /// that the shape appears faceted when lit.
+    Flat,
+    /// Each vertex normal follows the surface that the shape approximates (or
+    /// is averaged across the faces that meet at the vertex), such that the
+    /// shape appears smooth when lit.
+    Smooth,
+}
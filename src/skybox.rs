@@ -0,0 +1,320 @@
+//! A module for constructing skybox shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::errors::ShapeCreationError;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{
+    BuildAs, HasOptions, HasVertices, ShapeOptions, WithBackFaces, WithColors, WithLightmap,
+    WithModifiers, WithOrientation, WithShading, WithTangents, WithTexcoords, WithTransform,
+};
+use crate::shading::Shading;
+use crate::vertex::Vertex;
+
+/// A polygonal `Skybox` object.
+///
+/// This object is constructed using a `SkyboxBuilder` object.
+pub struct Skybox {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows a `Skybox` object to be passed as a source of vertices.
+impl<'a> From<&'a Skybox> for glium::vertex::VerticesSource<'a> {
+    fn from(skybox: &'a Skybox) -> glium::vertex::VerticesSource<'a> {
+        (&skybox.vertices).into()
+    }
+}
+
+/// Allows a `Skybox` object to be passed as a source of indices.
+impl<'a> From<&'a Skybox> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a Skybox) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::TrianglesList,
+        }
+    }
+}
+
+/// Responsible for building and returning a `Skybox` object.
+///
+/// By default, the skybox is defined as a unit-cube with its centre located at
+/// the origin, which is intended to be viewed from the inside. This can be
+/// overriden using the transformation methods on this object.
+///
+/// The resultant geometry is constructed to suit OpenGL defaults - assuming
+/// a right-handed coordinate system, front-facing polygons are defined in
+/// counter-clock-wise order when viewed from inside the cube, such that the
+/// skybox may be drawn with back-face culling enabled. Vertex normals point
+/// into the cube, in the direction of their respective face. Vertex texture
+/// coordinates define a planar-projection on each face, as viewed from inside
+/// the cube.
+///
+/// Without any transformation, the position of each vertex is also its
+/// direction from the centre of the cube, and may be used directly as the
+/// texture coordinate of a `samplerCube` lookup.
+pub struct SkyboxBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    options: ShapeOptions,
+}
+
+impl Default for SkyboxBuilder {
+    fn default() -> Self {
+        SkyboxBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            options: ShapeOptions::default(),
+        }
+    }
+}
+
+impl SkyboxBuilder {
+    /// Create a new `SkyboxBuilder` object.
+    pub fn new() -> SkyboxBuilder {
+        Default::default()
+    }
+
+    /// Build a new `Skybox` object.
+    pub fn build<F>(self, display: &F) -> Result<Skybox, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the shape vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// a `Skybox` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        let vertices = self.vertices_with::<Selected>()?;
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.options.check_shading(Self::SHADINGS)?;
+
+        // Define lookup-tables used during construction of the skybox geometry.
+        // These match the lookup-tables of the `Cuboid`, but each quad is wound
+        // in the opposite direction, such that it faces into the cube.
+        let index_lut = [
+            0, 4, 1, 5, // -X
+            6, 2, 7, 3, // +X
+            0, 2, 4, 6, // -Y
+            5, 7, 1, 3, // +Y
+            2, 0, 3, 1, // -Z
+            4, 6, 5, 7, // +Z
+        ];
+        let poly_lut = [0, 2, 1, 2, 3, 1];
+        let num_sides = 6;
+        let verts_per_side = 6;
+
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<M>::new(self.matrix);
+
+        // Generate skybox vertices.
+        let mut vertices = Vec::<Vertex>::with_capacity(self.num_vertices());
+
+        for side in 0..num_sides {
+            // Compute side normal, which points into the cube.
+            let mut normal = Vector3::<f32>::new(0.0, 0.0, 0.0);
+            normal[side / 2] = 1.0 - (((side % 2) * 2) as f32);
+
+            // Build side vertices. The u coordinate is mirrored, such that it
+            // increases from left to right when viewed from inside the cube.
+            for vert in 0..verts_per_side {
+                let coord = index_lut[poly_lut[vert] + (side * 4)];
+                let vpos = Vector3::<f32>::new(
+                    (((coord & 2) - 1) as f32) * 0.5,
+                    (((coord & 1) * 2 - 1) as f32) * 0.5,
+                    ((((coord >> 1) & 2) - 1) as f32) * 0.5,
+                );
+                vertices.push(Vertex {
+                    position: transform.point(vpos),
+                    normal: transform.normal(normal),
+                    texcoord: [
+                        1.0 - (poly_lut[vert] % 2) as f32,
+                        (poly_lut[vert] / 2) as f32,
+                    ],
+                });
+            }
+        }
+
+        self.options.map_texcoords(&mut vertices);
+        Ok(vertices)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    pub fn num_vertices(&self) -> usize {
+        6 * 6 * self.options.copies()
+    }
+}
+
+impl HasOptions for SkyboxBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for SkyboxBuilder {
+    type Shape = Skybox;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> Skybox {
+        Skybox { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for SkyboxBuilder {}
+
+impl BuildAs for SkyboxBuilder {}
+
+impl WithTangents for SkyboxBuilder {}
+
+impl WithLightmap for SkyboxBuilder {}
+
+impl WithOrientation for SkyboxBuilder {}
+
+impl WithBackFaces for SkyboxBuilder {}
+
+impl WithModifiers for SkyboxBuilder {}
+
+impl WithTexcoords for SkyboxBuilder {}
+
+impl WithColors for SkyboxBuilder {}
+
+impl WithShading for SkyboxBuilder {
+    /// Only flat shading is supported by the skybox.
+    const SHADINGS: &'static [Shading] = &[Shading::Flat];
+}
+
+#[test]
+pub fn ensure_default_skybox_matches_cuboid_positions() {
+    use crate::cuboid::CuboidBuilder;
+    let skybox = SkyboxBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let cuboid = CuboidBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    assert_eq!(skybox.len(), cuboid.len());
+    for (a, b) in skybox.chunks(3).zip(cuboid.chunks(3)) {
+        for (va, vb) in a.iter().zip([&b[0], &b[2], &b[1]].iter()) {
+            assert_eq!(va.position, vb.position);
+            assert_ulps_eq!(
+                Vector3::<f32>::from(va.normal),
+                -Vector3::<f32>::from(vb.normal)
+            );
+        }
+    }
+}
+
+#[test]
+pub fn ensure_default_skybox_has_inward_facing_normals() {
+    let vertices = SkyboxBuilder::new()
+        .scale(2.0, 2.0, 2.0)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        let position = Vector3::<f32>::from(vertex.position);
+        let normal = Vector3::<f32>::from(vertex.normal);
+        let inside = position + normal;
+        assert!(inside.x.abs() <= position.x.abs());
+        assert!(inside.y.abs() <= position.y.abs());
+        assert!(inside.z.abs() <= position.z.abs());
+    }
+}
+
+#[test]
+pub fn ensure_default_skybox_has_ccw_triangles_from_inside() {
+    let vertices = SkyboxBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let e0 = v1 - v0;
+        let e1 = v2 - v0;
+        let n = e0.cross(e1);
+        let eyepos = Vector3::<f32>::zero();
+        assert!(n.dot(v0 - eyepos) <= 0.0);
+        assert!(n.dot(v1 - eyepos) <= 0.0);
+        assert!(n.dot(v2 - eyepos) <= 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_default_skybox_has_faceted_normals() {
+    let vertices = SkyboxBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let n = (v1 - v0).cross(v2 - v0).normalize();
+        for vertex in chunk {
+            assert_ulps_eq!(n, Vector3::<f32>::from(vertex.normal));
+        }
+    }
+}
+
+#[test]
+pub fn ensure_default_skybox_has_uvs_in_unit_range() {
+    let vertices = SkyboxBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        assert!(vertex.texcoord[0] >= 0.0 && vertex.texcoord[0] <= 1.0);
+        assert!(vertex.texcoord[1] >= 0.0 && vertex.texcoord[1] <= 1.0);
+    }
+}
+
+#[test]
+pub fn ensure_skybox_applies_late_pipeline() {
+    use crate::options::WithTexcoords;
+    use crate::uv_mapping::UvMapping;
+
+    crate::options::ensure_late_pipeline(
+        || {
+            SkyboxBuilder::new()
+                .uv_mapping(UvMapping::Box)
+                .double_sided()
+        },
+        SkyboxBuilder::build_vertices,
+    );
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_skybox() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = SkyboxBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
@@ -5,6 +5,14 @@ extern crate glium;
 
 use self::cgmath::*;
 use crate::errors::ShapeCreationError;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{
+    BuildAs, HasOptions, HasVertices, ShapeOptions, WithBackFaces, WithCaps, WithColors,
+    WithLightmap, WithModifiers, WithOrientation, WithShading, WithTangents, WithTexcoords,
+    WithTransform,
+};
+use crate::resolution::{Resolution, WithResolution};
+use crate::shading::Shading;
 use crate::vertex::Vertex;
 
 use std::f32;
@@ -24,8 +32,8 @@ impl<'a> From<&'a Sphere> for glium::vertex::VerticesSource<'a> {
 }
 
 /// Allows a `Sphere` object to be passed as a source of indices.
-impl<'a> Into<glium::index::IndicesSource<'a>> for &'a Sphere {
-    fn into(self) -> glium::index::IndicesSource<'a> {
+impl<'a> From<&'a Sphere> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a Sphere) -> glium::index::IndicesSource<'a> {
         glium::index::IndicesSource::NoIndices {
             primitives: glium::index::PrimitiveType::TrianglesList,
         }
@@ -42,11 +50,33 @@ impl<'a> Into<glium::index::IndicesSource<'a>> for &'a Sphere {
 /// a right-handed coordinate system, front-facing polygons are defined in
 /// counter-clock-wise order. Vertex normals point in the direction of their
 /// respective face (such that the shape appears faceted when lit). Vertex
-/// texture coordinates define a spherical-projection on the object.
+/// texture coordinates define a spherical-projection on the object. Vertices
+/// along the seam are duplicated such that the seam has both a 0 and a 1 u
+/// coordinate. The rows about each pole are built from a single triangle per
+/// segment, with the pole duplicated for each segment, such that each copy
+/// takes the u coordinate of the middle of its segment.
+///
+/// The sphere may sweep only part of a turn about the y-axis (e.g. to build a
+/// wedge), in which case it is closed by a flat half-disc on each of its cut
+/// planes, with normals perpendicular to the plane. Texture coordinates on the
+/// cut faces map the distance from the axis to the u coordinate, and the
+/// height to the v coordinate (matching the direction of the v coordinate on
+/// the surface).
+///
+/// The sphere may also cover only a range of latitudes (e.g. to build a
+/// spherical zone), in which case the v coordinate is remapped from 0 to 1
+/// across the range, and the sphere is closed by a flat disc at each cut
+/// latitude, with normals along the y-axis. Texture coordinates on the discs
+/// define a planar-projection.
 pub struct SphereBuilder {
     matrix: cgmath::Matrix4<f32>,
     u_divisions: usize,
     v_divisions: usize,
+    start_angle: f32,
+    sweep_angle: f32,
+    min_latitude: f32,
+    max_latitude: f32,
+    options: ShapeOptions,
 }
 
 impl Default for SphereBuilder {
@@ -55,6 +85,11 @@ impl Default for SphereBuilder {
             matrix: cgmath::Matrix4::<f32>::identity(),
             u_divisions: 24,
             v_divisions: 12,
+            start_angle: 0.0,
+            sweep_angle: 2.0 * f32::consts::PI,
+            min_latitude: -f32::consts::FRAC_PI_2,
+            max_latitude: f32::consts::FRAC_PI_2,
+            options: ShapeOptions::default(),
         }
     }
 }
@@ -66,82 +101,40 @@ impl SphereBuilder {
     }
 
     /// Specify the number of divisions to make in the u direction (horizontal),
-    /// and v direction (vertical). By default, the builder will use 12 divisions
-    /// in both axes.
+    /// and v direction (vertical). By default, the builder will use 24 divisions
+    /// in the u axis and 12 divisions in the v axis. At least 3 divisions in u
+    /// and 2 divisions in v are required to build a valid sphere.
     pub fn with_divisions(mut self, u: usize, v: usize) -> Self {
         self.u_divisions = u;
         self.v_divisions = v;
         self
     }
 
-    /// Apply a scaling transformation to the shape.
-    ///
-    /// The `scale`, `translate`, and `rotate` functions accumulate, and are
-    /// not commutative. The transformation functions are intended to provide
-    /// flexibility in model-space. For per-instance world-space transformations,
-    /// one should prefer to share as few shapes as possible across multiple
-    /// instances, and instead rely on uniform constants in the shader and/or
-    /// instanced drawing.
-    pub fn scale(mut self, x: f32, y: f32, z: f32) -> Self {
-        self.matrix = cgmath::Matrix4::from_nonuniform_scale(x, y, z) * self.matrix;
+    /// Specify the angle at which the sphere starts, in radians measured
+    /// counter-clock-wise about the y-axis from the positive x-axis. By
+    /// default, the start angle is 0.
+    pub fn start_angle(mut self, radians: f32) -> Self {
+        self.start_angle = radians;
         self
     }
 
-    /// Apply a translation transformation to the shape.
-    ///
-    /// The `scale`, `translate`, and `rotate` functions accumulate, and are
-    /// not commutative. The transformation functions are intended to provide
-    /// flexibility in model-space. For per-instance world-space transformations,
-    /// one should prefer to share as few shapes as possible across multiple
-    /// instances, and instead rely on uniform constants in the shader and/or
-    /// instanced drawing.
-    pub fn translate(mut self, x: f32, y: f32, z: f32) -> Self {
-        self.matrix = cgmath::Matrix4::from_translation([x, y, z].into()) * self.matrix;
+    /// Specify the angle swept by the sphere about the y-axis, in radians. By
+    /// default, the sweep angle is a full turn. The sweep angle must be
+    /// greater than zero, and no greater than a full turn, to build a valid
+    /// sphere.
+    pub fn sweep_angle(mut self, radians: f32) -> Self {
+        self.sweep_angle = radians;
         self
     }
 
-    /// Apply a rotation transformation to the shape about the x-axis.
-    ///
-    /// The `scale`, `translate`, and `rotate` functions accumulate, and are
-    /// not commutative. The transformation functions are intended to provide
-    /// flexibility in model-space. For per-instance world-space transformations,
-    /// one should prefer to share as few shapes as possible across multiple
-    /// instances, and instead rely on uniform constants in the shader and/or
-    /// instanced drawing.
-    pub fn rotate_x(mut self, radians: f32) -> Self {
-        self.matrix = cgmath::Matrix4::<f32>::from(cgmath::Matrix3::<f32>::from_angle_x(
-            cgmath::Rad::<f32>(radians),
-        )) * self.matrix;
-        self
-    }
-
-    /// Apply a rotation transformation to the shape about the y-axis.
-    ///
-    /// The `scale`, `translate`, and `rotate` functions accumulate, and are
-    /// not commutative. The transformation functions are intended to provide
-    /// flexibility in model-space. For per-instance world-space transformations,
-    /// one should prefer to share as few shapes as possible across multiple
-    /// instances, and instead rely on uniform constants in the shader and/or
-    /// instanced drawing.
-    pub fn rotate_y(mut self, radians: f32) -> Self {
-        self.matrix = cgmath::Matrix4::<f32>::from(cgmath::Matrix3::<f32>::from_angle_y(
-            cgmath::Rad::<f32>(radians),
-        )) * self.matrix;
-        self
-    }
-
-    /// Apply a rotation transformation to the shape about the z-axis.
-    ///
-    /// The `scale`, `translate`, and `rotate` functions accumulate, and are
-    /// not commutative. The transformation functions are intended to provide
-    /// flexibility in model-space. For per-instance world-space transformations,
-    /// one should prefer to share as few shapes as possible across multiple
-    /// instances, and instead rely on uniform constants in the shader and/or
-    /// instanced drawing.
-    pub fn rotate_z(mut self, radians: f32) -> Self {
-        self.matrix = cgmath::Matrix4::<f32>::from(cgmath::Matrix3::<f32>::from_angle_z(
-            cgmath::Rad::<f32>(radians),
-        )) * self.matrix;
+    /// Specify the range of latitudes covered by the sphere, in radians
+    /// measured from the equator towards the positive y-axis. By default, the
+    /// sphere covers every latitude, from -π/2 to π/2. The minimum latitude
+    /// must be less than the maximum latitude, and both must lie within the
+    /// default range, to build a valid sphere.
+    pub fn latitude_range(mut self, min: f32, max: f32) -> Self {
+        self.min_latitude = min;
+        self.max_latitude = max;
         self
     }
 
@@ -150,12 +143,7 @@ impl SphereBuilder {
     where
         F: glium::backend::Facade,
     {
-        let vertices =
-            glium::vertex::VertexBuffer::<Vertex>::new(display, &self.build_vertices()?)?;
-
-        Ok(Sphere {
-            vertices: glium::vertex::VertexBufferAny::from(vertices),
-        })
+        Ok(self.shape(self.buffer(display)?))
     }
 
     /// Build the shape vertices and return them in a vector.
@@ -163,6 +151,16 @@ impl SphereBuilder {
     /// Useful if you wish to do other things with the vertices besides constructing
     /// a `Sphere` object (e.g. unit testing, further processing, etc).
     pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        let vertices = self.vertices_with::<Selected>()?;
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.options.check_shading(Self::SHADINGS)?;
+
         // Ensure there are enough divisions in u and v to produce valid
         // sphere geometry
         if self.u_divisions < 3 {
@@ -173,33 +171,70 @@ impl SphereBuilder {
             return Err(ShapeCreationError::NotEnoughDivisionsInV);
         }
 
-        // Build lookup tables.
-        let u_angle = 2.0 * f32::consts::PI / self.u_divisions as f32;
-        let v_angle = f32::consts::PI / self.v_divisions as f32;
+        let full = self.is_full_turn();
+        if self.sweep_angle <= 0.0 || !(full || self.sweep_angle < 2.0 * f32::consts::PI) {
+            return Err(ShapeCreationError::InvalidDimensions);
+        }
+
+        let (north, south) = (self.has_north_pole(), self.has_south_pole());
+        if !self.min_latitude.is_finite()
+            || !self.max_latitude.is_finite()
+            || self.min_latitude >= self.max_latitude
+            || (self.min_latitude < -f32::consts::FRAC_PI_2 && !south)
+            || (self.max_latitude > f32::consts::FRAC_PI_2 && !north)
+        {
+            return Err(ShapeCreationError::InvalidDimensions);
+        }
+
+        // Build lookup tables. The u angle increases clock-wise when viewed
+        // from above, so the table starts at the end of the sweep. A full turn
+        // reuses the first entry at the end of the table, such that the seam
+        // closes seamlessly.
+        let u_angle = self.sweep_angle / self.u_divisions as f32;
+        let u_start = if full {
+            -self.start_angle
+        } else {
+            -(self.start_angle + self.sweep_angle)
+        };
+        let top = if north {
+            f32::consts::FRAC_PI_2
+        } else {
+            self.max_latitude
+        };
+        let bottom = if south {
+            -f32::consts::FRAC_PI_2
+        } else {
+            self.min_latitude
+        };
+        let v_start = f32::consts::FRAC_PI_2 - top;
+        let v_angle = (top - bottom) / self.v_divisions as f32;
 
         fn sin_cos(val: f32) -> [f32; 2] {
             [val.sin(), val.cos()]
         }
 
         let u_tab = (0..=self.u_divisions)
-            .map(|x| sin_cos(((x % self.u_divisions) as f32) * u_angle))
+            .map(|x| {
+                let x = if full { x % self.u_divisions } else { x };
+                sin_cos(u_start + (x as f32) * u_angle)
+            })
             .collect::<Vec<[f32; 2]>>();
 
+        let is_pole = |v: usize| (v == 0 && north) || (v == self.v_divisions && south);
         let v_tab = (0..=self.v_divisions)
-            .map(|x| sin_cos((x as f32) * v_angle))
+            .map(|x| match x {
+                // Snap the poles onto the y-axis, such that every copy of a
+                // pole lies at the same position.
+                0 if north => [0.0, 1.0],
+                x if x == self.v_divisions && south => [0.0, -1.0],
+                x => sin_cos(v_start + (x as f32) * v_angle),
+            })
             .collect::<Vec<[f32; 2]>>();
 
         let indices = [0, 1, 2, 2, 1, 3];
 
-        // Compute the normal transformation matrix.
-        let normal_matrix = Matrix3::<f32>::from_cols(
-            self.matrix.x.truncate(),
-            self.matrix.y.truncate(),
-            self.matrix.z.truncate(),
-        )
-        .invert()
-        .unwrap_or_else(Matrix3::<f32>::identity)
-        .transpose();
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<M>::new(self.matrix);
 
         // Build vertex array.
         let total_num_verts = self.num_vertices();
@@ -234,9 +269,9 @@ impl SphereBuilder {
                 let lut_coords = [(u + 1, v), (u + 1, v + 1), (u, v), (u, v + 1)];
 
                 // Compute face index offset and count
-                let (offset, count) = if v == 0 {
+                let (offset, count) = if v == 0 && north {
                     (3, 3)
-                } else if v == self.v_divisions - 1 {
+                } else if v == self.v_divisions - 1 && south {
                     (0, 3)
                 } else {
                     (0, 6)
@@ -246,33 +281,142 @@ impl SphereBuilder {
                 let v0 = &verts[indices[offset]];
                 let v1 = &verts[indices[offset + 1]];
                 let v2 = &verts[indices[offset + 2]];
-                let normal = (v1 - v0).cross(v2 - v0).normalize();
+                let face_normal = (v1 - v0).cross(v2 - v0).normalize();
 
                 // Emit vertices.
                 for index in offset..offset + count {
                     let vpos = &verts[indices[index]];
-                    let pos = self.matrix * vpos.extend(1.0);
-                    let (u, v) = lut_coords[indices[index]];
+                    let normal = match self.options.shading {
+                        Shading::Flat => face_normal,
+                        Shading::Smooth => *vpos,
+                    };
+                    let (lu, lv) = lut_coords[indices[index]];
+                    let tu = if is_pole(lv) {
+                        u as f32 + 0.5
+                    } else {
+                        lu as f32
+                    };
                     vertices.push(Vertex {
-                        position: Point3::<f32>::from_homogeneous(pos).into(),
-                        normal: (normal_matrix * normal).normalize().into(),
+                        position: transform.point(*vpos),
+                        normal: transform.normal(normal),
                         texcoord: [
-                            u as f32 / self.u_divisions as f32,
-                            v as f32 / self.v_divisions as f32,
+                            tu / self.u_divisions as f32,
+                            lv as f32 / self.v_divisions as f32,
                         ],
                     });
                 }
             }
         }
 
-        assert!(vertices.len() == total_num_verts);
+        // Emit the discs at the cut latitudes as triangle fans about the
+        // y-axis, wound counter-clock-wise when viewed from outside.
+        let discs = [
+            (0, 1.0, !north && self.options.caps.caps_end()),
+            (
+                self.v_divisions,
+                -1.0,
+                !south && self.options.caps.caps_start(),
+            ),
+        ];
+        for &(v, sign, _) in discs.iter().filter(|disc| disc.2) {
+            let [r, y] = v_tab[v];
+            let normal = Vector3::<f32>::new(0.0, sign, 0.0);
+            let ring = |u: usize| Vector3::<f32>::new(u_tab[u][1] * r, y, u_tab[u][0] * r);
+            for u in 0..self.u_divisions {
+                let (u0, u1) = if sign > 0.0 { (u + 1, u) } else { (u, u + 1) };
+                let corners = [Vector3::<f32>::new(0.0, y, 0.0), ring(u0), ring(u1)];
+                for vpos in corners.iter() {
+                    vertices.push(Vertex {
+                        position: transform.point(*vpos),
+                        normal: transform.normal(normal),
+                        texcoord: [0.5 + vpos.x * 0.5, 0.5 - vpos.z * sign * 0.5],
+                    });
+                }
+            }
+        }
+
+        // Emit the cut faces, unless the sphere sweeps a full turn. Each is
+        // built from a quad per division in v, between the y-axis and the
+        // surface, except at the poles, where the quad collapses to a single
+        // triangle. The face at the end of the table is wound in the opposite
+        // direction, such that both faces are wound counter-clock-wise when
+        // viewed from outside.
+        for &(u, flip) in [(0, false), (self.u_divisions, true)]
+            .iter()
+            .take(self.num_cuts())
+        {
+            let [s, c] = u_tab[u];
+            let normal = if flip {
+                Vector3::<f32>::new(-s, 0.0, c)
+            } else {
+                Vector3::<f32>::new(s, 0.0, -c)
+            };
+            let meridian =
+                |v: usize| Vector3::<f32>::new(c * v_tab[v][0], v_tab[v][1], s * v_tab[v][0]);
+            let axis = |v: usize| Vector3::<f32>::new(0.0, v_tab[v][1], 0.0);
+            for v in 0..self.v_divisions {
+                let mut triangles = Vec::with_capacity(2);
+                if v != 0 || !north {
+                    triangles.push([axis(v), meridian(v), meridian(v + 1)]);
+                }
+                if v != self.v_divisions - 1 || !south {
+                    triangles.push([axis(v), meridian(v + 1), axis(v + 1)]);
+                }
+                for [a, b, c] in triangles {
+                    let corners = if flip { [a, c, b] } else { [a, b, c] };
+                    for vpos in corners.iter() {
+                        vertices.push(Vertex {
+                            position: transform.point(*vpos),
+                            normal: transform.normal(normal),
+                            texcoord: [
+                                Vector2::<f32>::new(vpos.x, vpos.z).magnitude(),
+                                (1.0 - vpos.y) * 0.5,
+                            ],
+                        });
+                    }
+                }
+            }
+        }
+
+        self.options.map_texcoords(&mut vertices);
         Ok(vertices)
     }
 
-    /// Returns the number of caps in the resultant sphere geometry. The current implementation
-    /// will always return 2.
+    /// Returns true if the sphere sweeps a full turn, such that it has no cut
+    /// faces.
+    fn is_full_turn(&self) -> bool {
+        ulps_eq!(self.sweep_angle, 2.0 * f32::consts::PI)
+    }
+
+    /// Returns true if the latitude range reaches the pole on the positive y-axis.
+    fn has_north_pole(&self) -> bool {
+        ulps_eq!(self.max_latitude, f32::consts::FRAC_PI_2)
+    }
+
+    /// Returns true if the latitude range reaches the pole on the negative y-axis.
+    fn has_south_pole(&self) -> bool {
+        ulps_eq!(self.min_latitude, -f32::consts::FRAC_PI_2)
+    }
+
+    /// Returns the number of caps (e.g. the triangles about each pole) in the resultant sphere
+    /// geometry. The resultant value will be 2, less the number of poles excluded by the
+    /// latitude range.
     pub fn num_caps(&self) -> usize {
-        2
+        self.has_north_pole() as usize + self.has_south_pole() as usize
+    }
+
+    /// Returns the number of flat discs at the cut latitudes in the resultant sphere geometry.
+    /// The resultant value will be 0 if the latitude range covers both poles, or its caps are
+    /// disabled by its cap mode.
+    pub fn num_latitude_caps(&self) -> usize {
+        let caps = self.options.caps;
+        (!self.has_north_pole() && caps.caps_end()) as usize
+            + (!self.has_south_pole() && caps.caps_start()) as usize
+    }
+
+    /// Returns the total number of vertices in each flat disc at a cut latitude.
+    pub fn num_vertices_per_latitude_cap(&self) -> usize {
+        3 * self.u_divisions
     }
 
     /// Returns the number of vertices generated for each cap face. The current implementation
@@ -303,10 +447,104 @@ impl SphereBuilder {
         self.num_vertices_per_slice_face() * self.u_divisions
     }
 
+    /// Returns the number of cut faces in the resultant sphere geometry. The resultant value will
+    /// be 0 if the sphere sweeps a full turn, and 2 otherwise.
+    pub fn num_cuts(&self) -> usize {
+        if self.is_full_turn() {
+            0
+        } else {
+            2
+        }
+    }
+
+    /// Returns the total number of vertices in each cut face.
+    pub fn num_vertices_per_cut(&self) -> usize {
+        (6 * self.v_divisions) - (3 * self.num_caps())
+    }
+
     /// Returns the total number of vertices that will be generated by the builder.
     pub fn num_vertices(&self) -> usize {
-        (self.num_vertices_per_slice() * self.num_slices())
+        ((self.num_vertices_per_slice() * self.num_slices())
             + (self.num_vertices_per_cap() * self.num_caps())
+            + (self.num_vertices_per_latitude_cap() * self.num_latitude_caps())
+            + (self.num_vertices_per_cut() * self.num_cuts()))
+            * self.options.copies()
+    }
+}
+
+impl HasOptions for SphereBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for SphereBuilder {
+    type Shape = Sphere;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> Sphere {
+        Sphere { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for SphereBuilder {}
+
+impl BuildAs for SphereBuilder {}
+
+impl WithTangents for SphereBuilder {}
+
+impl WithLightmap for SphereBuilder {}
+
+impl WithOrientation for SphereBuilder {}
+
+impl WithBackFaces for SphereBuilder {}
+
+impl WithModifiers for SphereBuilder {}
+
+/// The start of the sphere is the flat disc at its minimum latitude, and its
+/// end is the flat disc at its maximum latitude, either of which is only built
+/// when the latitude range of the sphere does not reach the pole.
+impl WithCaps for SphereBuilder {}
+
+impl WithTexcoords for SphereBuilder {}
+
+impl WithColors for SphereBuilder {}
+
+impl WithShading for SphereBuilder {
+    /// By default, the sphere is flat shaded. Both modes are supported, and
+    /// each vertex normal on the surface of the sphere points away from its
+    /// centre when smooth shaded.
+    const SHADINGS: &'static [Shading] = &[Shading::Flat, Shading::Smooth];
+}
+
+impl WithResolution for SphereBuilder {
+    /// At least 3 divisions in u, and 2 divisions in v.
+    const MIN_RESOLUTION: Resolution = Resolution {
+        around: 3,
+        along: 2,
+    };
+
+    /// Specify the number of divisions in the u direction (around the sphere),
+    /// and the v direction (from pole to pole).
+    fn resolution(mut self, resolution: Resolution) -> Self {
+        self.u_divisions = resolution.around as usize;
+        self.v_divisions = resolution.along as usize;
+        self
     }
 }
 
@@ -431,3 +669,419 @@ pub fn ensure_default_sphere_has_planar_quads() {
         }
     }
 }
+
+#[test]
+pub fn ensure_sphere_with_too_few_u_divisions_fails_to_build() {
+    let result = SphereBuilder::new().with_divisions(2, 12).build_vertices();
+    match result {
+        Err(ShapeCreationError::NotEnoughDivisionsInU) => (),
+        _ => panic!("Expected NotEnoughDivisionsInU"),
+    }
+}
+
+#[test]
+pub fn ensure_sphere_with_too_few_v_divisions_fails_to_build() {
+    let result = SphereBuilder::new().with_divisions(24, 1).build_vertices();
+    match result {
+        Err(ShapeCreationError::NotEnoughDivisionsInV) => (),
+        _ => panic!("Expected NotEnoughDivisionsInV"),
+    }
+}
+
+#[test]
+pub fn ensure_minimal_sphere_builds_expected_vertex_count() {
+    let builder = SphereBuilder::new().with_divisions(3, 2);
+    let vertices = builder.build_vertices().expect("Failed to build vertices");
+    assert_eq!(vertices.len(), builder.num_vertices());
+    assert_eq!(vertices.len(), 18);
+}
+
+#[test]
+pub fn ensure_sphere_with_invalid_resolution_fails_to_build() {
+    crate::resolution::ensure_min_resolution(
+        SphereBuilder::new,
+        SphereBuilder::build_vertices,
+        ShapeCreationError::NotEnoughDivisionsInU,
+        Some(ShapeCreationError::NotEnoughDivisionsInV),
+    );
+}
+
+#[test]
+pub fn ensure_partial_sphere_cut_faces_have_planar_normals() {
+    let (start, sweep) = (0.25, f32::consts::FRAC_PI_2);
+    let builder = SphereBuilder::new()
+        .with_divisions(6, 5)
+        .start_angle(start)
+        .sweep_angle(sweep);
+    let vertices = builder.build_vertices().expect("Failed to build vertices");
+    assert_eq!(vertices.len(), builder.num_vertices());
+    let cuts = &vertices[vertices.len() - 2 * builder.num_vertices_per_cut()..];
+    let faces = cuts.chunks(builder.num_vertices_per_cut());
+    for (chunk, a) in faces.zip([start + sweep, start].iter()) {
+        let radial = Vector3::<f32>::new(a.cos(), 0.0, -a.sin());
+        let normal = if *a == start {
+            radial.cross(Vector3::unit_y())
+        } else {
+            Vector3::unit_y().cross(radial)
+        };
+        for tri in chunk.chunks(3) {
+            let [v0, v1, v2] = [0, 1, 2].map(|k| Vector3::<f32>::from(tri[k].position));
+            assert!((v1 - v0).cross(v2 - v0).dot(normal) > 0.0);
+        }
+        for vertex in chunk {
+            let pos = Vector3::<f32>::from(vertex.position);
+            assert!(abs_diff_eq!(pos.dot(normal), 0.0, epsilon = 0.0001));
+            assert!(pos.dot(radial) > -0.0001);
+            assert_ulps_eq!(Vector3::from(vertex.normal), normal, epsilon = 0.0001);
+        }
+    }
+}
+
+#[test]
+pub fn ensure_partial_sphere_lies_within_its_sweep() {
+    let (start, sweep) = (1.0, 3.0);
+    let vertices = SphereBuilder::new()
+        .start_angle(start)
+        .sweep_angle(sweep)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        let pos = Vector3::<f32>::from(vertex.position);
+        if Vector2::<f32>::new(pos.x, pos.z).magnitude() < 0.0001 {
+            continue;
+        }
+        let mut angle = (-pos.z).atan2(pos.x) - start;
+        while angle < -0.0001 {
+            angle += 2.0 * f32::consts::PI;
+        }
+        assert!(angle <= sweep + 0.0001);
+    }
+}
+
+#[test]
+pub fn ensure_sphere_with_invalid_sweep_fails_to_build() {
+    for &sweep in [0.0, -1.0, 7.0, f32::NAN].iter() {
+        match SphereBuilder::new().sweep_angle(sweep).build_vertices() {
+            Err(ShapeCreationError::InvalidDimensions) => (),
+            _ => panic!("Expected InvalidDimensions"),
+        }
+    }
+}
+
+#[test]
+pub fn ensure_spherical_zone_covers_its_latitude_range() {
+    use crate::cap_mode::CapMode;
+    let (min, max) = (-0.3, 0.6);
+    let builder = SphereBuilder::new()
+        .latitude_range(min, max)
+        .caps(CapMode::Neither);
+    let vertices = builder.build_vertices().expect("Failed to build vertices");
+    assert_eq!(vertices.len(), builder.num_vertices());
+    assert_eq!(vertices.len(), 24 * 12 * 6);
+    let (mut v_min, mut v_max) = (f32::INFINITY, f32::NEG_INFINITY);
+    for vertex in &vertices {
+        let pos = Vector3::<f32>::from(vertex.position);
+        let latitude = pos.y.asin();
+        assert!(latitude >= min - 0.0001 && latitude <= max + 0.0001);
+        assert!(Vector3::<f32>::from(vertex.normal).dot(pos) > 0.0);
+
+        // The v coordinate increases from the maximum latitude to the minimum.
+        let v = vertex.texcoord[1];
+        assert!(abs_diff_eq!(
+            v,
+            (max - latitude) / (max - min),
+            epsilon = 0.0001
+        ));
+        v_min = v_min.min(v);
+        v_max = v_max.max(v);
+    }
+    assert_eq!((v_min, v_max), (0.0, 1.0));
+}
+
+#[test]
+pub fn ensure_spherical_zone_caps_are_planar() {
+    let builder = SphereBuilder::new()
+        .with_divisions(8, 4)
+        .latitude_range(-0.5, f32::consts::FRAC_PI_2);
+    assert_eq!(builder.num_caps(), 1);
+    assert_eq!(builder.num_latitude_caps(), 1);
+    let vertices = builder.build_vertices().expect("Failed to build vertices");
+    assert_eq!(vertices.len(), builder.num_vertices());
+    let disc = &vertices[vertices.len() - builder.num_vertices_per_latitude_cap()..];
+    for tri in disc.chunks(3) {
+        let [v0, v1, v2] = [0, 1, 2].map(|k| Vector3::<f32>::from(tri[k].position));
+        assert!((v1 - v0).cross(v2 - v0).y < 0.0);
+        for vertex in tri {
+            assert_ulps_eq!(vertex.position[1], -(0.5f32.sin()));
+            assert_eq!(vertex.normal, [0.0, -1.0, 0.0]);
+        }
+    }
+}
+
+#[test]
+pub fn ensure_closed_spherical_zone_is_watertight() {
+    use std::collections::HashMap;
+    for &sweep in [2.0 * f32::consts::PI, 2.5].iter() {
+        let vertices = SphereBuilder::new()
+            .with_divisions(7, 3)
+            .latitude_range(-0.4, 0.9)
+            .start_angle(0.5)
+            .sweep_angle(sweep)
+            .build_vertices()
+            .expect("Failed to build vertices");
+        let mut edges = HashMap::<([u32; 3], [u32; 3]), usize>::new();
+        for chunk in vertices.chunks(3) {
+            for k in 0..3 {
+                let a = chunk[k].position.map(f32::to_bits);
+                let b = chunk[(k + 1) % 3].position.map(f32::to_bits);
+                *edges
+                    .entry(if a < b { (a, b) } else { (b, a) })
+                    .or_insert(0) += 1;
+            }
+        }
+        assert!(edges.values().all(|&count| count == 2));
+    }
+}
+
+#[test]
+pub fn ensure_sphere_with_invalid_latitude_range_fails_to_build() {
+    for &(min, max) in [(0.5, 0.2), (0.3, 0.3), (-2.0, 0.0), (0.0, 2.0)].iter() {
+        match SphereBuilder::new()
+            .latitude_range(min, max)
+            .build_vertices()
+        {
+            Err(ShapeCreationError::InvalidDimensions) => (),
+            _ => panic!("Expected InvalidDimensions"),
+        }
+    }
+}
+
+#[test]
+pub fn ensure_sphere_uvs_do_not_wrap_across_seam() {
+    let vertices = SphereBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let us = chunk.iter().map(|v| v.texcoord[0]);
+        let min = us.clone().fold(f32::MAX, f32::min);
+        let max = us.fold(f32::MIN, f32::max);
+        assert!(max - min <= 0.5);
+    }
+}
+
+#[test]
+pub fn ensure_sphere_poles_are_fans_of_distinct_triangles() {
+    let vertices = SphereBuilder::new()
+        .with_divisions(8, 4)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let mut pole_us = Vec::new();
+    for chunk in vertices.chunks(3) {
+        let [v0, v1, v2] = [0, 1, 2].map(|k| Vector3::<f32>::from(chunk[k].position));
+        assert!((v1 - v0).cross(v2 - v0).magnitude() > 0.0001);
+
+        // Each pole vertex takes the u coordinate of the middle of its segment.
+        for (k, vertex) in chunk.iter().enumerate() {
+            if vertex.position[1].abs() == 1.0 {
+                let [a, b] = [(k + 1) % 3, (k + 2) % 3].map(|i| chunk[i].texcoord[0]);
+                assert_ulps_eq!(vertex.texcoord[0], (a + b) * 0.5);
+                pole_us.push((vertex.position[1] > 0.0, vertex.texcoord[0].to_bits()));
+            }
+        }
+    }
+    assert_eq!(pole_us.len(), 2 * 8);
+    pole_us.sort();
+    pole_us.dedup();
+    assert_eq!(pole_us.len(), 2 * 8);
+}
+
+#[test]
+pub fn ensure_smooth_sphere_has_normals_along_its_radii() {
+    let vertices = SphereBuilder::new()
+        .shading(Shading::Smooth)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in vertices.iter() {
+        let direction = Vector3::<f32>::from(vertex.position).normalize();
+        assert_ulps_eq!(
+            direction,
+            Vector3::<f32>::from(vertex.normal),
+            epsilon = 0.0001
+        );
+    }
+}
+
+#[test]
+pub fn ensure_spherical_uv_mapping_does_not_wrap_across_seam() {
+    use crate::options::WithTexcoords;
+    use crate::uv_mapping::UvMapping;
+
+    let vertices = SphereBuilder::new()
+        .rotate_y(0.3)
+        .uv_mapping(UvMapping::Spherical)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let us = chunk.iter().map(|v| v.texcoord[0]).collect::<Vec<f32>>();
+        let lo = us.iter().cloned().fold(f32::INFINITY, f32::min);
+        let hi = us.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+        assert!(hi - lo <= 0.5);
+        assert!(lo >= 0.0 && hi < 1.5);
+        for vertex in chunk {
+            let v = vertex.texcoord[1];
+            let expected = 0.5 + vertex.position[1].clamp(-1.0, 1.0).asin() / f32::consts::PI;
+            assert_ulps_eq!(v, expected, epsilon = 0.0001);
+        }
+    }
+}
+
+#[test]
+pub fn ensure_sphere_with_flipped_normals_has_inward_facing_normals() {
+    let builder = SphereBuilder::new().scale(2.0, 2.0, 2.0);
+    let original = builder.build_vertices().expect("Failed to build vertices");
+    let vertices = builder
+        .flip_normals()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for (vertex, original) in vertices.iter().zip(original.iter()) {
+        assert_eq!(vertex.position, original.position);
+        assert_eq!(vertex.texcoord, original.texcoord);
+        let position = Vector3::<f32>::from(vertex.position);
+        let normal = Vector3::<f32>::from(vertex.normal);
+        assert_eq!(normal, -Vector3::<f32>::from(original.normal));
+        // The outward facing check above holds only against the normal.
+        let outside = position - normal;
+        assert!(outside.x.abs() >= position.x.abs());
+        assert!(outside.y.abs() >= position.y.abs());
+        assert!(outside.z.abs() >= position.z.abs());
+        assert!(normal.dot(position) < 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_last_coloring_of_sphere_wins() {
+    let normals = SphereBuilder::new()
+        .rotate_x(0.5)
+        .color(1.0, 0.0, 0.0, 1.0)
+        .debug_color_from_normals()
+        .build_vertices_colored()
+        .expect("Failed to build vertices");
+    for vertex in &normals {
+        for axis in 0..3 {
+            assert_eq!(vertex.color[axis], vertex.normal[axis] * 0.5 + 0.5);
+        }
+        assert_eq!(vertex.color[3], 1.0);
+    }
+    let constant = SphereBuilder::new()
+        .rotate_x(0.5)
+        .debug_color_from_normals()
+        .color(1.0, 0.0, 0.0, 1.0)
+        .build_vertices_colored()
+        .expect("Failed to build vertices");
+    for vertex in &constant {
+        assert_eq!(vertex.color, [1.0, 0.0, 0.0, 1.0]);
+    }
+}
+
+#[test]
+pub fn ensure_inflated_smooth_sphere_stays_watertight() {
+    let builder = SphereBuilder::new().shading(Shading::Smooth);
+    let base = builder.build_vertices().expect("Failed to build vertices");
+    let vertices = builder
+        .offset_along_normals(0.25)
+        .build_vertices()
+        .expect("Failed to build vertices");
+
+    // Vertices that share a position before the offset share one after it.
+    let mut inflated = std::collections::HashMap::new();
+    for (vertex, base) in vertices.iter().zip(base.iter()) {
+        assert_eq!(vertex.normal, base.normal);
+        assert_ulps_eq!(
+            Vector3::<f32>::from(vertex.position).magnitude(),
+            1.25,
+            epsilon = 0.0001
+        );
+        let key = base.position.map(f32::to_bits);
+        let position = *inflated.entry(key).or_insert(vertex.position);
+        assert_eq!(vertex.position, position);
+    }
+}
+
+#[test]
+pub fn ensure_smooth_sphere_tangents_are_orthonormal() {
+    let vertices = SphereBuilder::new()
+        .shading(Shading::Smooth)
+        .build_vertices_with_tangents()
+        .expect("Failed to build vertices");
+    for vertex in vertices.iter() {
+        let normal = Vector3::<f32>::from(vertex.normal);
+        let tangent = Vector4::<f32>::from(vertex.tangent);
+        assert_ulps_eq!(tangent.truncate().magnitude(), 1.0, epsilon = 0.0001);
+        assert_ulps_eq!(tangent.truncate().dot(normal), 0.0, epsilon = 0.0001);
+        assert_eq!(tangent.w.abs(), 1.0);
+    }
+}
+
+#[test]
+pub fn ensure_sphere_builds_into_packed_vertex_type() {
+    #[derive(Copy, Clone, Debug)]
+    struct PackedVertex {
+        position: [f32; 3],
+        normal: [i8; 4],
+    }
+    implement_vertex!(PackedVertex, position, normal);
+    impl From<Vertex> for PackedVertex {
+        fn from(vertex: Vertex) -> Self {
+            let pack = |x: f32| (x * 127.0).round() as i8;
+            let [x, y, z] = vertex.normal;
+            PackedVertex {
+                position: vertex.position,
+                normal: [pack(x), pack(y), pack(z), 0],
+            }
+        }
+    }
+
+    let builder = SphereBuilder::new();
+    let vertices = builder.build_vertices().expect("Failed to build vertices");
+    let packed = builder
+        .build_vertices_as::<PackedVertex>()
+        .expect("Failed to build vertices");
+    assert_eq!(packed.len(), vertices.len());
+    for (packed, vertex) in packed.iter().zip(vertices.iter()) {
+        assert_eq!(packed.position, vertex.position);
+        let normal = Vector3::new(packed.normal[0], packed.normal[1], packed.normal[2])
+            .map(|x| x as f32 / 127.0);
+        assert_ulps_eq!(normal, Vector3::from(vertex.normal), epsilon = 0.01);
+    }
+}
+
+#[test]
+pub fn ensure_sphere_applies_late_pipeline() {
+    use crate::options::WithTexcoords;
+    use crate::uv_mapping::UvMapping;
+
+    crate::options::ensure_late_pipeline(
+        || {
+            SphereBuilder::new()
+                .uv_mapping(UvMapping::Box)
+                .double_sided()
+        },
+        SphereBuilder::build_vertices,
+    );
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_sphere() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = SphereBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
@@ -0,0 +1,424 @@
+//! A module for constructing staircase shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::cuboid::push_face;
+use crate::errors::ShapeCreationError;
+use crate::facet::{push_triangulated_polygon, triangulate};
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{
+    BuildAs, HasOptions, HasVertices, ShapeOptions, WithBackFaces, WithColors, WithLightmap,
+    WithModifiers, WithOrientation, WithShading, WithTangents, WithTexcoords, WithTransform,
+};
+use crate::shading::Shading;
+use crate::vertex::Vertex;
+
+/// A polygonal `Stairs` object.
+///
+/// This object is constructed using a `StairsBuilder` object.
+pub struct Stairs {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows a `Stairs` object to be passed as a source of vertices.
+impl<'a> From<&'a Stairs> for glium::vertex::VerticesSource<'a> {
+    fn from(stairs: &'a Stairs) -> glium::vertex::VerticesSource<'a> {
+        (&stairs.vertices).into()
+    }
+}
+
+/// Allows a `Stairs` object to be passed as a source of indices.
+impl<'a> From<&'a Stairs> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a Stairs) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::TrianglesList,
+        }
+    }
+}
+
+/// Responsible for building and returning a `Stairs` object.
+///
+/// By default, the staircase is defined as a flight of 4 steps that fits within
+/// a unit cube with its centre located at the origin. The steps span the width
+/// of the staircase along the x-axis, and ascend from the front of the
+/// staircase (facing the positive z-axis) towards the back, such that the first
+/// riser lies at `z = 0.5` and the last tread lies at `y = 0.5`. This can be
+/// overriden using the transformation methods on this object.
+///
+/// The staircase is emitted as a single solid, without faces between adjacent
+/// steps. Each riser and tread shares its edges with its neighbours and with
+/// the stepped faces on either side of the staircase, such that the mesh is
+/// watertight. The resultant geometry is constructed to suit OpenGL defaults -
+/// assuming a right-handed coordinate system, front-facing polygons are
+/// defined in counter-clock-wise order. Vertex normals point in the direction
+/// of their respective face (such that the shape appears faceted when lit).
+/// Vertex texture coordinates define a planar-projection on each face.
+pub struct StairsBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    steps: usize,
+    width: f32,
+    height: f32,
+    depth: f32,
+    options: ShapeOptions,
+}
+
+impl Default for StairsBuilder {
+    fn default() -> Self {
+        StairsBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            steps: 4,
+            width: 1.0,
+            height: 1.0,
+            depth: 1.0,
+            options: ShapeOptions::default(),
+        }
+    }
+}
+
+impl StairsBuilder {
+    /// Create a new `StairsBuilder` object.
+    pub fn new() -> StairsBuilder {
+        Default::default()
+    }
+
+    /// Specify the number of steps in the staircase. By default, the builder
+    /// will use 4 steps. At least 1 step is required to build a valid
+    /// staircase.
+    pub fn steps(mut self, steps: usize) -> Self {
+        self.steps = steps;
+        self
+    }
+
+    /// Specify the total width of the staircase along the x-axis. By default,
+    /// the width is 1. The width must be greater than zero to build a valid
+    /// staircase.
+    pub fn width(mut self, width: f32) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Specify the total height of the staircase along the y-axis, which is
+    /// divided evenly between the risers. By default, the height is 1. The
+    /// height must be greater than zero to build a valid staircase.
+    pub fn height(mut self, height: f32) -> Self {
+        self.height = height;
+        self
+    }
+
+    /// Specify the total depth of the staircase along the z-axis, which is
+    /// divided evenly between the treads. By default, the depth is 1. The
+    /// depth must be greater than zero to build a valid staircase.
+    pub fn depth(mut self, depth: f32) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    /// Build a new `Stairs` object.
+    pub fn build<F>(self, display: &F) -> Result<Stairs, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the shape vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// a `Stairs` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        let vertices = self.vertices_with::<Selected>()?;
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.options.check_shading(Self::SHADINGS)?;
+
+        // Ensure there are enough steps to produce valid staircase geometry.
+        if self.steps < 1 {
+            return Err(ShapeCreationError::NotEnoughDivisionsInU);
+        }
+        let valid = |x: f32| x.is_finite() && x > 0.0;
+        if !valid(self.width) || !valid(self.height) || !valid(self.depth) {
+            return Err(ShapeCreationError::InvalidDimensions);
+        }
+
+        // Compute the corners of the profile of each step, where the step at
+        // the given index has its riser at `z(index)` and its tread at
+        // `y(index + 1)`.
+        let n = self.steps;
+        let (half_width, half_height, half_depth) =
+            (self.width * 0.5, self.height * 0.5, self.depth * 0.5);
+        let y = |index: usize| self.height * index as f32 / n as f32 - half_height;
+        let z = |index: usize| half_depth - self.depth * index as f32 / n as f32;
+
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<M>::new(self.matrix);
+
+        // Emit the risers and treads, followed by the base and the back.
+        let mut vertices = Vec::<Vertex>::with_capacity(self.num_vertices());
+        let faces = (0..n)
+            .flat_map(|step| {
+                let riser = (
+                    5,
+                    Vector3::<f32>::new(-half_width, y(step), z(step)),
+                    Vector3::<f32>::new(half_width, y(step + 1), z(step)),
+                );
+                let tread = (
+                    3,
+                    Vector3::<f32>::new(-half_width, y(step + 1), z(step + 1)),
+                    Vector3::<f32>::new(half_width, y(step + 1), z(step)),
+                );
+                vec![riser, tread]
+            })
+            .chain(vec![
+                (
+                    2,
+                    Vector3::<f32>::new(-half_width, y(0), z(n)),
+                    Vector3::<f32>::new(half_width, y(0), z(0)),
+                ),
+                (
+                    4,
+                    Vector3::<f32>::new(-half_width, y(0), z(n)),
+                    Vector3::<f32>::new(half_width, y(n), z(n)),
+                ),
+            ])
+            .collect::<Vec<(usize, Vector3<f32>, Vector3<f32>)>>();
+        for &(side, min, max) in &faces {
+            push_face(&mut vertices, &transform, side, min, max);
+        }
+
+        // Emit the stepped profile on either side of the staircase. The profile
+        // is wound counter-clock-wise when viewed from the positive x-axis,
+        // running along the base to the back, then down the steps to the front.
+        let mut profile = vec![(z(0), y(0)), (z(n), y(0))];
+        for step in (0..n).rev() {
+            profile.push((z(step + 1), y(step + 1)));
+            profile.push((z(step), y(step + 1)));
+        }
+        let projected = profile
+            .iter()
+            .map(|&(z, y)| Vector2::<f32>::new(-z, y))
+            .collect::<Vec<Vector2<f32>>>();
+        let triangles = triangulate(&projected)?;
+        for &x in [half_width, -half_width].iter() {
+            let mut polygon = profile
+                .iter()
+                .map(|&(z, y)| Vector3::<f32>::new(x, y, z))
+                .collect::<Vec<Vector3<f32>>>();
+            let mut triangles = triangles.clone();
+            if x < 0.0 {
+                polygon.reverse();
+                let last = polygon.len() - 1;
+                for triangle in triangles.iter_mut() {
+                    *triangle = [last - triangle[0], last - triangle[2], last - triangle[1]];
+                }
+            }
+            push_triangulated_polygon(&mut vertices, &transform, &polygon, &triangles);
+        }
+
+        self.options.map_texcoords(&mut vertices);
+        Ok(vertices)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    pub fn num_vertices(&self) -> usize {
+        (((self.steps * 2 + 2) * 6) + (self.steps * 2 * 2 * 3)) * self.options.copies()
+    }
+}
+
+impl HasOptions for StairsBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for StairsBuilder {
+    type Shape = Stairs;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> Stairs {
+        Stairs { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for StairsBuilder {}
+
+impl BuildAs for StairsBuilder {}
+
+impl WithTangents for StairsBuilder {}
+
+impl WithLightmap for StairsBuilder {}
+
+impl WithOrientation for StairsBuilder {}
+
+impl WithBackFaces for StairsBuilder {}
+
+impl WithModifiers for StairsBuilder {}
+
+impl WithTexcoords for StairsBuilder {}
+
+impl WithColors for StairsBuilder {}
+
+impl WithShading for StairsBuilder {
+    /// Only flat shading is supported by the stairs.
+    const SHADINGS: &'static [Shading] = &[Shading::Flat];
+}
+
+#[test]
+pub fn ensure_default_stairs_fit_unit_cube() {
+    let vertices = StairsBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let mut min = Vector3::<f32>::from_value(1.0);
+    let mut max = Vector3::<f32>::from_value(-1.0);
+    for vertex in &vertices {
+        for axis in 0..3 {
+            min[axis] = min[axis].min(vertex.position[axis]);
+            max[axis] = max[axis].max(vertex.position[axis]);
+        }
+    }
+    assert_ulps_eq!(min, Vector3::<f32>::from_value(-0.5));
+    assert_ulps_eq!(max, Vector3::<f32>::from_value(0.5));
+}
+
+#[test]
+pub fn ensure_stairs_are_watertight() {
+    use std::collections::HashMap;
+    for &steps in [1, 3, 6].iter() {
+        let vertices = StairsBuilder::new()
+            .steps(steps)
+            .width(2.0)
+            .height(0.75)
+            .build_vertices()
+            .expect("Failed to build vertices");
+        let mut edges = HashMap::<([u32; 3], [u32; 3]), usize>::new();
+        for chunk in vertices.chunks(3) {
+            for k in 0..3 {
+                let a = chunk[k].position.map(f32::to_bits);
+                let b = chunk[(k + 1) % 3].position.map(f32::to_bits);
+                *edges
+                    .entry(if a < b { (a, b) } else { (b, a) })
+                    .or_insert(0) += 1;
+            }
+        }
+        assert!(edges.values().all(|&count| count == 2));
+    }
+}
+
+#[test]
+pub fn ensure_default_stairs_have_outward_facing_normals() {
+    let vertices = StairsBuilder::new()
+        .steps(3)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let centroid = (Vector3::<f32>::from(chunk[0].position)
+            + Vector3::<f32>::from(chunk[1].position)
+            + Vector3::<f32>::from(chunk[2].position))
+            / 3.0;
+        let normal = Vector3::<f32>::from(chunk[0].normal);
+
+        // Each face is on the boundary of the staircase, such that stepping
+        // a short distance along its normal leaves the solid.
+        let outside = centroid + normal * 0.01;
+        let step = ((0.5 - outside.z) * 3.0).floor();
+        let inside = outside.x.abs() < 0.5
+            && outside.z.abs() < 0.5
+            && outside.y > -0.5
+            && outside.y < (step + 1.0) / 3.0 - 0.5;
+        assert!(!inside);
+    }
+}
+
+#[test]
+pub fn ensure_default_stairs_have_uvs_in_unit_range() {
+    let vertices = StairsBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        assert!(vertex.texcoord[0] >= -0.0001 && vertex.texcoord[0] <= 1.0001);
+        assert!(vertex.texcoord[1] >= -0.0001 && vertex.texcoord[1] <= 1.0001);
+    }
+}
+
+#[test]
+pub fn ensure_default_stairs_have_ccw_triangles() {
+    let vertices = StairsBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let eyepos = v0 + Vector3::<f32>::from(chunk[0].normal);
+        let e0 = v1 - v0;
+        let e1 = v2 - v0;
+        let n = e0.cross(e1);
+        assert!(n.dot(v0 - eyepos) < 0.0);
+        assert!(n.dot(v1 - eyepos) < 0.0);
+        assert!(n.dot(v2 - eyepos) < 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_stairs_with_invalid_parameters_fail_to_build() {
+    match StairsBuilder::new().steps(0).build_vertices() {
+        Err(ShapeCreationError::NotEnoughDivisionsInU) => (),
+        _ => panic!("Expected NotEnoughDivisionsInU"),
+    }
+    match StairsBuilder::new().depth(0.0).build_vertices() {
+        Err(ShapeCreationError::InvalidDimensions) => (),
+        _ => panic!("Expected InvalidDimensions"),
+    }
+}
+
+#[test]
+pub fn ensure_stairs_applies_late_pipeline() {
+    use crate::options::WithTexcoords;
+    use crate::uv_mapping::UvMapping;
+
+    crate::options::ensure_late_pipeline(
+        || {
+            StairsBuilder::new()
+                .uv_mapping(UvMapping::Box)
+                .double_sided()
+        },
+        StairsBuilder::build_vertices,
+    );
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_stairs() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = StairsBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
@@ -0,0 +1,383 @@
+//! A module for constructing star prism shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::errors::ShapeCreationError;
+use crate::facet::push_polygon;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{
+    BuildAs, HasOptions, HasVertices, ShapeOptions, WithBackFaces, WithColors, WithLightmap,
+    WithModifiers, WithOrientation, WithShading, WithTangents, WithTexcoords, WithTransform,
+};
+use crate::shading::Shading;
+use crate::vertex::Vertex;
+
+use std::f32;
+
+/// A polygonal `StarPrism` object.
+///
+/// This object is constructed using a `StarPrismBuilder` object.
+pub struct StarPrism {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows a `StarPrism` object to be passed as a source of vertices.
+impl<'a> From<&'a StarPrism> for glium::vertex::VerticesSource<'a> {
+    fn from(star: &'a StarPrism) -> glium::vertex::VerticesSource<'a> {
+        (&star.vertices).into()
+    }
+}
+
+/// Allows a `StarPrism` object to be passed as a source of indices.
+impl<'a> From<&'a StarPrism> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a StarPrism) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::TrianglesList,
+        }
+    }
+}
+
+/// Responsible for building and returning a `StarPrism` object.
+///
+/// By default, the star prism is defined as a five-pointed star in the
+/// xy-plane, extruded along the z-axis, with its points on a circle of radius
+/// 0.5, its inner corners on a circle of radius 0.25, a thickness of 0.2, and
+/// its centre-of-mass located at the origin. The first point of the star lies
+/// on the positive y-axis. This can be overriden using the transformation
+/// methods on this object. The front and back caps are triangulated as fans
+/// about their centre points, which is valid as the star is star-shaped about
+/// its centre.
+///
+/// The resultant geometry is constructed to suit OpenGL defaults - assuming
+/// a right-handed coordinate system, front-facing polygons are defined in
+/// counter-clock-wise order. Vertex normals point in the direction of their
+/// respective face (such that the shape appears faceted when lit). Vertex
+/// texture coordinates define a planar-projection on each face.
+pub struct StarPrismBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    points: usize,
+    outer_radius: f32,
+    inner_radius: f32,
+    thickness: f32,
+    options: ShapeOptions,
+}
+
+impl Default for StarPrismBuilder {
+    fn default() -> Self {
+        StarPrismBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            points: 5,
+            outer_radius: 0.5,
+            inner_radius: 0.25,
+            thickness: 0.2,
+            options: ShapeOptions::default(),
+        }
+    }
+}
+
+impl StarPrismBuilder {
+    /// Create a new `StarPrismBuilder` object.
+    pub fn new() -> StarPrismBuilder {
+        Default::default()
+    }
+
+    /// Specify the number of points of the star. By default, the star has 5
+    /// points. At least 2 points are required to build a valid star prism.
+    pub fn points(mut self, points: usize) -> Self {
+        self.points = points;
+        self
+    }
+
+    /// Specify the radius of the circle through the points of the star. By
+    /// default, the radius is 0.5.
+    pub fn outer_radius(mut self, radius: f32) -> Self {
+        self.outer_radius = radius;
+        self
+    }
+
+    /// Specify the radius of the circle through the inner corners of the star.
+    /// By default, the radius is 0.25. The radius must be greater than zero,
+    /// and less than the outer radius, to build a valid star prism.
+    pub fn inner_radius(mut self, radius: f32) -> Self {
+        self.inner_radius = radius;
+        self
+    }
+
+    /// Specify the thickness of the star along the z-axis. By default, the
+    /// thickness is 0.2. The thickness must be greater than zero to build a
+    /// valid star prism.
+    pub fn thickness(mut self, thickness: f32) -> Self {
+        self.thickness = thickness;
+        self
+    }
+
+    /// Build a new `StarPrism` object.
+    pub fn build<F>(self, display: &F) -> Result<StarPrism, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the shape vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// a `StarPrism` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        let vertices = self.vertices_with::<Selected>()?;
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.options.check_shading(Self::SHADINGS)?;
+
+        // Ensure the parameters produce valid star geometry.
+        if self.points < 2 {
+            return Err(ShapeCreationError::NotEnoughDivisionsInU);
+        }
+        if self.inner_radius <= 0.0 || self.inner_radius >= self.outer_radius {
+            return Err(ShapeCreationError::InvalidRadii);
+        }
+        if self.thickness <= 0.0 {
+            return Err(ShapeCreationError::InvalidDimensions);
+        }
+
+        // Build the outline corners, alternating between the points and the
+        // inner corners counter-clock-wise from the positive y-axis.
+        let angle = f32::consts::PI / self.points as f32;
+        let corners = (0..self.points * 2)
+            .map(|x| {
+                let a = f32::consts::FRAC_PI_2 + x as f32 * angle;
+                let radius = [self.outer_radius, self.inner_radius][x % 2];
+                Vector2::<f32>::new(a.cos() * radius, a.sin() * radius)
+            })
+            .collect::<Vec<Vector2<f32>>>();
+        let depth = 0.5 * self.thickness;
+        let back = |c: Vector2<f32>| c.extend(-depth);
+        let front = |c: Vector2<f32>| c.extend(depth);
+
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<M>::new(self.matrix);
+
+        // Emit the side walls.
+        let mut vertices = Vec::<Vertex>::with_capacity(self.num_vertices());
+        for side in 0..corners.len() {
+            let (c0, c1) = (corners[side], corners[(side + 1) % corners.len()]);
+            let face = [back(c0), back(c1), front(c1), front(c0)];
+            push_polygon(&mut vertices, &transform, &face);
+        }
+
+        // Emit the caps. Each cap is fanned about its centre point by leading
+        // with the centre and closing the loop on the first corner.
+        let mut cap = vec![Vector3::<f32>::new(0.0, 0.0, depth)];
+        cap.extend(corners.iter().chain(corners.first()).map(|&c| front(c)));
+        push_polygon(&mut vertices, &transform, &cap);
+        let mut cap = vec![Vector3::<f32>::new(0.0, 0.0, -depth)];
+        cap.extend(
+            corners
+                .iter()
+                .chain(corners.first())
+                .rev()
+                .map(|&c| back(c)),
+        );
+        push_polygon(&mut vertices, &transform, &cap);
+
+        self.options.map_texcoords(&mut vertices);
+        Ok(vertices)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    pub fn num_vertices(&self) -> usize {
+        self.points * 2 * 4 * 3 * self.options.copies()
+    }
+}
+
+impl HasOptions for StarPrismBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for StarPrismBuilder {
+    type Shape = StarPrism;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> StarPrism {
+        StarPrism { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for StarPrismBuilder {}
+
+impl BuildAs for StarPrismBuilder {}
+
+impl WithTangents for StarPrismBuilder {}
+
+impl WithLightmap for StarPrismBuilder {}
+
+impl WithOrientation for StarPrismBuilder {}
+
+impl WithBackFaces for StarPrismBuilder {}
+
+impl WithModifiers for StarPrismBuilder {}
+
+impl WithTexcoords for StarPrismBuilder {}
+
+impl WithColors for StarPrismBuilder {}
+
+impl WithShading for StarPrismBuilder {
+    /// Only flat shading is supported by the star prism.
+    const SHADINGS: &'static [Shading] = &[Shading::Flat];
+}
+
+#[test]
+pub fn ensure_default_star_prism_is_watertight() {
+    use std::collections::HashMap;
+    let vertices = StarPrismBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let key = |v: &Vertex| Vector3::<f32>::from(v.position).map(f32::to_bits);
+    let mut edges = HashMap::<_, usize>::new();
+    for chunk in vertices.chunks(3) {
+        for i in 0..3 {
+            let (a, b) = (key(&chunk[i]), key(&chunk[(i + 1) % 3]));
+            let edge = if (a.x, a.y, a.z) < (b.x, b.y, b.z) {
+                (a, b)
+            } else {
+                (b, a)
+            };
+            *edges.entry(edge).or_insert(0) += 1;
+        }
+    }
+    for count in edges.values() {
+        assert_eq!(*count, 2);
+    }
+}
+
+#[test]
+pub fn ensure_default_star_prism_has_ccw_triangles() {
+    let vertices = StarPrismBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let eyepos = v0 + Vector3::<f32>::from(chunk[0].normal);
+        let e0 = v1 - v0;
+        let e1 = v2 - v0;
+        let n = e0.cross(e1);
+        assert!(n.dot(v0 - eyepos) <= 0.0);
+        assert!(n.dot(v1 - eyepos) <= 0.0);
+        assert!(n.dot(v2 - eyepos) <= 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_star_prism_front_cap_faces_positive_z() {
+    for points in 2..8 {
+        let vertices = StarPrismBuilder::new()
+            .points(points)
+            .build_vertices()
+            .expect("Failed to build vertices");
+        let caps = &vertices[points * 2 * 6..];
+        let (front, back) = caps.split_at(caps.len() / 2);
+        for (cap, sign) in [(front, 1.0), (back, -1.0)].iter() {
+            for chunk in cap.chunks(3) {
+                let v0 = Vector3::<f32>::from(chunk[0].position);
+                let v1 = Vector3::<f32>::from(chunk[1].position);
+                let v2 = Vector3::<f32>::from(chunk[2].position);
+                assert!((v1 - v0).cross(v2 - v0).z * sign > 0.0);
+                assert_ulps_eq!(
+                    Vector3::<f32>::from(chunk[0].normal),
+                    Vector3::<f32>::new(0.0, 0.0, *sign)
+                );
+            }
+        }
+    }
+}
+
+#[test]
+pub fn ensure_default_star_prism_has_faceted_normals() {
+    let vertices = StarPrismBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let n = (v1 - v0).cross(v2 - v0).normalize();
+        for vertex in chunk {
+            assert_ulps_eq!(n, Vector3::<f32>::from(vertex.normal), epsilon = 0.0001);
+        }
+    }
+}
+
+#[test]
+pub fn ensure_star_prism_with_invalid_parameters_fails_to_build() {
+    match StarPrismBuilder::new().points(1).build_vertices() {
+        Err(ShapeCreationError::NotEnoughDivisionsInU) => (),
+        _ => panic!("Expected NotEnoughDivisionsInU"),
+    }
+    for &inner in [0.5, 0.75, 0.0].iter() {
+        match StarPrismBuilder::new().inner_radius(inner).build_vertices() {
+            Err(ShapeCreationError::InvalidRadii) => (),
+            _ => panic!("Expected InvalidRadii"),
+        }
+    }
+    match StarPrismBuilder::new().thickness(0.0).build_vertices() {
+        Err(ShapeCreationError::InvalidDimensions) => (),
+        _ => panic!("Expected InvalidDimensions"),
+    }
+}
+
+#[test]
+pub fn ensure_star_prism_applies_late_pipeline() {
+    use crate::options::WithTexcoords;
+    use crate::uv_mapping::UvMapping;
+
+    crate::options::ensure_late_pipeline(
+        || {
+            StarPrismBuilder::new()
+                .uv_mapping(UvMapping::Box)
+                .double_sided()
+        },
+        StarPrismBuilder::build_vertices,
+    );
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_star_prism() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = StarPrismBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
@@ -0,0 +1,506 @@
+//! A module for constructing superellipsoid shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::errors::ShapeCreationError;
+use crate::facet::flatten_normals;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{
+    BuildAs, HasOptions, HasVertices, ShapeOptions, WithBackFaces, WithColors, WithLightmap,
+    WithModifiers, WithOrientation, WithShading, WithTangents, WithTexcoords, WithTransform,
+};
+use crate::resolution::{Resolution, WithResolution};
+use crate::shading::Shading;
+use crate::vertex::Vertex;
+
+use std::f32;
+
+/// A polygonal `Superellipsoid` object.
+///
+/// This object is constructed using a `SuperellipsoidBuilder` object.
+pub struct Superellipsoid {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows a `Superellipsoid` object to be passed as a source of vertices.
+impl<'a> From<&'a Superellipsoid> for glium::vertex::VerticesSource<'a> {
+    fn from(superellipsoid: &'a Superellipsoid) -> glium::vertex::VerticesSource<'a> {
+        (&superellipsoid.vertices).into()
+    }
+}
+
+/// Allows a `Superellipsoid` object to be passed as a source of indices.
+impl<'a> From<&'a Superellipsoid> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a Superellipsoid) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::TrianglesList,
+        }
+    }
+}
+
+/// Responsible for building and returning a `Superellipsoid` object.
+///
+/// The superellipsoid is defined by two exponents - the first controls the
+/// shape of its profile from pole to pole (along the y-axis), and the second
+/// controls the shape of its cross-section about the y-axis. Exponents of 1
+/// produce a sphere, exponents approaching 0 produce a cube, and an exponent
+/// of 2 produces straight edges between the axes (e.g. an octahedron when
+/// both exponents are 2). Exponents between these produce cylinders and
+/// pillow shapes. By default, both exponents are 1, and the superellipsoid is
+/// bounded by the cube from `(-1, -1, -1)` to `(1, 1, 1)` with its centre
+/// located at the origin (such that the default shape matches the default
+/// `Sphere`). This can be overriden using the transformation methods on this
+/// object.
+///
+/// The resultant geometry is constructed to suit OpenGL defaults - assuming
+/// a right-handed coordinate system, front-facing polygons are defined in
+/// counter-clock-wise order. Vertex normals are derived analytically from the
+/// surface (such that the shape appears smooth when lit). Vertex texture
+/// coordinates define a spherical-projection on the object, with the pole
+/// duplicated for each segment about it, such that each copy takes the u
+/// coordinate of the middle of its segment.
+pub struct SuperellipsoidBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    exponents: (f32, f32),
+    u_divisions: usize,
+    v_divisions: usize,
+    options: ShapeOptions,
+}
+
+impl Default for SuperellipsoidBuilder {
+    fn default() -> Self {
+        SuperellipsoidBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            exponents: (1.0, 1.0),
+            u_divisions: 24,
+            v_divisions: 12,
+            options: ShapeOptions {
+                shading: Shading::Smooth,
+                ..ShapeOptions::default()
+            },
+        }
+    }
+}
+
+impl SuperellipsoidBuilder {
+    /// Create a new `SuperellipsoidBuilder` object.
+    pub fn new() -> SuperellipsoidBuilder {
+        Default::default()
+    }
+
+    /// Specify the exponents of the superellipsoid, where `e1` controls the
+    /// profile from pole to pole and `e2` controls the cross-section about
+    /// the y-axis. By default, both exponents are 1. Both exponents must be
+    /// greater than zero to build a valid superellipsoid.
+    pub fn exponents(mut self, e1: f32, e2: f32) -> Self {
+        self.exponents = (e1, e2);
+        self
+    }
+
+    /// Specify the number of segments in the u direction (about the y-axis),
+    /// and v direction (from pole to pole). By default, the builder will use
+    /// 24 segments in the u axis and 12 segments in the v axis. At least 3
+    /// segments in u and 2 segments in v are required to build a valid
+    /// superellipsoid.
+    pub fn segments(mut self, u: usize, v: usize) -> Self {
+        self.u_divisions = u;
+        self.v_divisions = v;
+        self
+    }
+
+    /// Build a new `Superellipsoid` object.
+    pub fn build<F>(self, display: &F) -> Result<Superellipsoid, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the shape vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// a `Superellipsoid` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        let vertices = self.vertices_with::<Selected>()?;
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.options.check_shading(Self::SHADINGS)?;
+
+        // Ensure there are enough divisions in u and v to produce valid
+        // superellipsoid geometry.
+        if self.u_divisions < 3 {
+            return Err(ShapeCreationError::NotEnoughDivisionsInU);
+        }
+        if self.v_divisions < 2 {
+            return Err(ShapeCreationError::NotEnoughDivisionsInV);
+        }
+
+        let (e1, e2) = self.exponents;
+        if e1 <= 0.0 || e2 <= 0.0 || !e1.is_finite() || !e2.is_finite() {
+            return Err(ShapeCreationError::InvalidExponents);
+        }
+
+        // Build lookup tables. Values that should be zero are snapped to zero,
+        // as raising the rounding error of the trigonometric functions to a
+        // small exponent would otherwise displace the vertices on the axes.
+        let u_angle = 2.0 * f32::consts::PI / self.u_divisions as f32;
+        let v_angle = f32::consts::PI / self.v_divisions as f32;
+
+        fn sin_cos(val: f32) -> [f32; 2] {
+            let snap = |x: f32| if x.abs() < 1e-6 { 0.0 } else { x };
+            [snap(val.sin()), snap(val.cos())]
+        }
+
+        let u_tab = (0..=self.u_divisions)
+            .map(|x| sin_cos(((x % self.u_divisions) as f32) * u_angle))
+            .collect::<Vec<[f32; 2]>>();
+
+        let v_tab = (0..=self.v_divisions)
+            .map(|x| sin_cos((x as f32) * v_angle))
+            .collect::<Vec<[f32; 2]>>();
+
+        // Compute the position and normal for the given lookup table indices.
+        // The normal follows from the gradient of the implicit surface, and
+        // is undefined along the creases that form when an exponent is 2 or
+        // more, in which case the direction of the position is used instead.
+        let point = |u: usize, v: usize| {
+            let ([su, cu], [sv, cv]) = (u_tab[u], v_tab[v]);
+            let position = Vector3::<f32>::new(
+                signed_pow(cu, e2) * signed_pow(sv, e1),
+                signed_pow(cv, e1),
+                signed_pow(su, e2) * signed_pow(sv, e1),
+            );
+            let normal = Vector3::<f32>::new(
+                signed_pow(cu, 2.0 - e2) * signed_pow(sv, 2.0 - e1),
+                signed_pow(cv, 2.0 - e1),
+                signed_pow(su, 2.0 - e2) * signed_pow(sv, 2.0 - e1),
+            );
+            let normal = if normal.x.is_finite()
+                && normal.y.is_finite()
+                && normal.z.is_finite()
+                && normal.magnitude2() > 0.0
+            {
+                normal.normalize()
+            } else {
+                position.normalize()
+            };
+            (position, normal)
+        };
+
+        let indices = [0, 1, 2, 2, 1, 3];
+
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<M>::new(self.matrix);
+
+        // Build vertex array. The slices that touch the poles are emitted as a
+        // single triangle per segment, such that no triangle collapses onto
+        // the pole, with the pole taking the u coordinate of the middle of
+        // the segment.
+        let mut vertices = Vec::<Vertex>::with_capacity(self.num_vertices());
+        for v in 0..self.v_divisions {
+            for u in 0..self.u_divisions {
+                let lut_coords = [(u + 1, v), (u + 1, v + 1), (u, v), (u, v + 1)];
+
+                // Compute face index offset and count
+                let (offset, count) = if v == 0 {
+                    (3, 3)
+                } else if v == self.v_divisions - 1 {
+                    (0, 3)
+                } else {
+                    (0, 6)
+                };
+
+                // Emit vertices.
+                for &index in &indices[offset..offset + count] {
+                    let (lu, lv) = lut_coords[index];
+                    let (position, normal) = point(lu, lv);
+                    let tu = if lv == 0 || lv == self.v_divisions {
+                        u as f32 + 0.5
+                    } else {
+                        lu as f32
+                    };
+                    vertices.push(Vertex {
+                        position: transform.point(position),
+                        normal: transform.normal(normal),
+                        texcoord: [
+                            tu / self.u_divisions as f32,
+                            lv as f32 / self.v_divisions as f32,
+                        ],
+                    });
+                }
+            }
+        }
+
+        if self.options.shading == Shading::Flat {
+            flatten_normals(&mut vertices);
+        }
+
+        self.options.map_texcoords(&mut vertices);
+        Ok(vertices)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    pub fn num_vertices(&self) -> usize {
+        ((self.u_divisions * (self.v_divisions - 2) * 6) + (self.u_divisions * 2 * 3))
+            * self.options.copies()
+    }
+}
+
+/// Returns `x` raised to the power `e`, preserving the sign of `x`.
+fn signed_pow(x: f32, e: f32) -> f32 {
+    if x == 0.0 {
+        if e > 0.0 {
+            0.0
+        } else {
+            f32::INFINITY
+        }
+    } else {
+        x.signum() * x.abs().powf(e)
+    }
+}
+
+impl HasOptions for SuperellipsoidBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for SuperellipsoidBuilder {
+    type Shape = Superellipsoid;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> Superellipsoid {
+        Superellipsoid { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for SuperellipsoidBuilder {}
+
+impl BuildAs for SuperellipsoidBuilder {}
+
+impl WithTangents for SuperellipsoidBuilder {}
+
+impl WithLightmap for SuperellipsoidBuilder {}
+
+impl WithOrientation for SuperellipsoidBuilder {}
+
+impl WithBackFaces for SuperellipsoidBuilder {}
+
+impl WithModifiers for SuperellipsoidBuilder {}
+
+impl WithTexcoords for SuperellipsoidBuilder {}
+
+impl WithColors for SuperellipsoidBuilder {}
+
+impl WithShading for SuperellipsoidBuilder {
+    /// By default, the superellipsoid is smooth shaded. When flat shaded, each
+    /// vertex normal points in the direction of its respective triangle
+    /// instead.
+    const SHADINGS: &'static [Shading] = &[Shading::Flat, Shading::Smooth];
+}
+
+impl WithResolution for SuperellipsoidBuilder {
+    /// At least 3 segments in u, and 2 segments in v.
+    const MIN_RESOLUTION: Resolution = Resolution {
+        around: 3,
+        along: 2,
+    };
+
+    /// Specify the number of segments in the u direction (around the
+    /// superellipsoid), and the v direction (from pole to pole).
+    fn resolution(mut self, resolution: Resolution) -> Self {
+        self.u_divisions = resolution.around as usize;
+        self.v_divisions = resolution.along as usize;
+        self
+    }
+}
+
+#[test]
+pub fn ensure_default_superellipsoid_is_unit_sphere() {
+    let vertices = SuperellipsoidBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        let position = Vector3::<f32>::from(vertex.position);
+        assert_ulps_eq!(position.magnitude(), 1.0, epsilon = 0.0001);
+        assert_ulps_eq!(
+            Vector3::<f32>::from(vertex.normal),
+            position,
+            epsilon = 0.0001
+        );
+    }
+}
+
+#[test]
+pub fn ensure_superellipsoid_lies_on_implicit_surface() {
+    for &(e1, e2) in [(0.25, 0.5), (0.5, 1.5), (1.0, 2.0), (2.0, 0.1)].iter() {
+        let vertices = SuperellipsoidBuilder::new()
+            .exponents(e1, e2)
+            .build_vertices()
+            .expect("Failed to build vertices");
+        for vertex in &vertices {
+            let [x, y, z] = vertex.position;
+            let radial = x.abs().powf(2.0 / e2) + z.abs().powf(2.0 / e2);
+            let f = radial.powf(e2 / e1) + y.abs().powf(2.0 / e1);
+            assert!(abs_diff_eq!(f, 1.0, epsilon = 0.001));
+            for axis in vertex.position.iter() {
+                assert!(axis.abs() <= 1.0 + 0.0001);
+            }
+        }
+    }
+}
+
+#[test]
+pub fn ensure_superellipsoid_normals_follow_implicit_gradient() {
+    let (e1, e2) = (0.5, 1.5);
+    let vertices = SuperellipsoidBuilder::new()
+        .exponents(e1, e2)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        let [x, y, z] = vertex.position;
+        let radial = x.abs().powf(2.0 / e2) + z.abs().powf(2.0 / e2);
+        let scale = radial.powf(e2 / e1 - 1.0);
+        let gradient = Vector3::<f32>::new(
+            scale * x.signum() * x.abs().powf(2.0 / e2 - 1.0),
+            y.signum() * y.abs().powf(2.0 / e1 - 1.0),
+            scale * z.signum() * z.abs().powf(2.0 / e2 - 1.0),
+        )
+        .normalize();
+        let normal = Vector3::<f32>::from(vertex.normal);
+        assert_ulps_eq!(normal, gradient, epsilon = 0.001);
+    }
+}
+
+#[test]
+pub fn ensure_superellipsoid_has_ccw_triangles() {
+    for &(e1, e2) in [(1.0, 1.0), (0.2, 0.2), (0.5, 2.0), (3.0, 3.0)].iter() {
+        let vertices = SuperellipsoidBuilder::new()
+            .exponents(e1, e2)
+            .build_vertices()
+            .expect("Failed to build vertices");
+        for chunk in vertices.chunks(3) {
+            let v0 = Vector3::<f32>::from(chunk[0].position);
+            let v1 = Vector3::<f32>::from(chunk[1].position);
+            let v2 = Vector3::<f32>::from(chunk[2].position);
+            let eyepos = v0 + Vector3::<f32>::from(chunk[0].normal);
+            let e0 = v1 - v0;
+            let e1 = v2 - v0;
+            let n = e0.cross(e1);
+            assert!(n.magnitude() > 0.0);
+            assert!(n.dot(v0 - eyepos) <= 0.0);
+            assert!(n.dot(v1 - eyepos) <= 0.0);
+            assert!(n.dot(v2 - eyepos) <= 0.0);
+        }
+    }
+}
+
+#[test]
+pub fn ensure_superellipsoid_with_invalid_parameters_fails_to_build() {
+    match SuperellipsoidBuilder::new()
+        .segments(2, 12)
+        .build_vertices()
+    {
+        Err(ShapeCreationError::NotEnoughDivisionsInU) => (),
+        _ => panic!("Expected NotEnoughDivisionsInU"),
+    }
+    match SuperellipsoidBuilder::new()
+        .segments(24, 1)
+        .build_vertices()
+    {
+        Err(ShapeCreationError::NotEnoughDivisionsInV) => (),
+        _ => panic!("Expected NotEnoughDivisionsInV"),
+    }
+    for &(e1, e2) in [(0.0, 1.0), (1.0, -1.0), (f32::NAN, 1.0)].iter() {
+        match SuperellipsoidBuilder::new()
+            .exponents(e1, e2)
+            .build_vertices()
+        {
+            Err(ShapeCreationError::InvalidExponents) => (),
+            _ => panic!("Expected InvalidExponents"),
+        }
+    }
+}
+
+#[test]
+pub fn ensure_superellipsoid_poles_are_fans_of_distinct_triangles() {
+    let vertices = SuperellipsoidBuilder::new()
+        .exponents(0.5, 1.5)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let [v0, v1, v2] = [0, 1, 2].map(|k| Vector3::<f32>::from(chunk[k].position));
+        assert!((v1 - v0).cross(v2 - v0).magnitude() > 0.0);
+
+        // Each pole vertex takes the u coordinate of the middle of its segment.
+        for (k, vertex) in chunk.iter().enumerate() {
+            if vertex.position[1].abs() == 1.0 {
+                let [a, b] = [(k + 1) % 3, (k + 2) % 3].map(|i| chunk[i].texcoord[0]);
+                assert_ulps_eq!(vertex.texcoord[0], (a + b) * 0.5);
+            }
+        }
+    }
+}
+
+#[test]
+pub fn ensure_superellipsoid_applies_late_pipeline() {
+    use crate::options::WithTexcoords;
+    use crate::uv_mapping::UvMapping;
+
+    crate::options::ensure_late_pipeline(
+        || {
+            SuperellipsoidBuilder::new()
+                .uv_mapping(UvMapping::Box)
+                .double_sided()
+        },
+        SuperellipsoidBuilder::build_vertices,
+    );
+}
+
+#[test]
+pub fn ensure_superellipsoid_with_invalid_resolution_fails_to_build() {
+    crate::resolution::ensure_min_resolution(
+        SuperellipsoidBuilder::new,
+        SuperellipsoidBuilder::build_vertices,
+        ShapeCreationError::NotEnoughDivisionsInU,
+        Some(ShapeCreationError::NotEnoughDivisionsInV),
+    );
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_superellipsoid() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = SuperellipsoidBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
@@ -0,0 +1,220 @@
+//! A crate-private module for sweeping circular cross-sections along paths.
+
+extern crate cgmath;
+
+use self::cgmath::*;
+use crate::errors::ShapeCreationError;
+use crate::math::{Math, Transformation};
+use crate::vertex::Vertex;
+
+use std::f32;
+
+/// Describes a circular cross-section swept along a path of points.
+///
+/// The cross-section is oriented at each point using parallel-transport
+/// frames, such that the swept tube does not twist around corners. At each
+/// interior point, the cross-section is perpendicular to the average direction
+/// of its adjoining path segments. Open paths may be capped at either end,
+/// whereas closed paths join the last point back to the first, distributing
+/// any twist accumulated by the frames around the loop evenly along its length
+/// such that the tube meets itself seamlessly.
+pub(crate) struct Sweep<'a> {
+    pub path: &'a [Point3<f32>],
+    pub closed: bool,
+    pub radius: f32,
+    pub segments: usize,
+    pub caps: [bool; 2],
+}
+
+impl<'a> Sweep<'a> {
+    /// Ensure the sweep describes valid tube geometry.
+    pub fn validate(&self) -> Result<(), ShapeCreationError> {
+        // Ensure there are enough segments to produce valid tube geometry.
+        if self.segments < 3 {
+            return Err(ShapeCreationError::NotEnoughDivisionsInU);
+        }
+
+        // Ensure the path is long enough, and free of coincident points.
+        if self.path.len() < if self.closed { 3 } else { 2 } {
+            return Err(ShapeCreationError::NotEnoughPoints);
+        }
+        if (0..self.num_bands()).any(|i| ulps_eq!(self.path[i], self.path[self.next(i)])) {
+            return Err(ShapeCreationError::DuplicatePoints);
+        }
+        Ok(())
+    }
+
+    /// Returns the number of bands of quads along the length of the tube.
+    fn num_bands(&self) -> usize {
+        if self.closed {
+            self.path.len()
+        } else {
+            self.path.len().saturating_sub(1)
+        }
+    }
+
+    /// Returns the index of the point following the given point.
+    fn next(&self, point: usize) -> usize {
+        (point + 1) % self.path.len()
+    }
+
+    /// Returns the total number of vertices that will be generated by the sweep.
+    pub fn num_vertices(&self) -> usize {
+        let caps = if self.closed {
+            0
+        } else {
+            self.caps.iter().filter(|&&cap| cap).count()
+        };
+        (self.segments * 6 * self.num_bands()) + (self.segments * 3 * caps)
+    }
+
+    /// Emit the vertices of the swept tube, which must have been validated.
+    pub fn push<M: Math>(&self, vertices: &mut Vec<Vertex>, transform: &Transformation<M>) {
+        let count = self.path.len();
+        let bands = self.num_bands();
+
+        // Compute the direction of each path segment, the tangent at each
+        // point, and the arc-length at each point (including the end of the
+        // loop for a closed path).
+        let directions = (0..bands)
+            .map(|i| (self.path[self.next(i)] - self.path[i]).normalize())
+            .collect::<Vec<Vector3<f32>>>();
+        let tangents = (0..count)
+            .map(|i| {
+                let (prev, next) = if self.closed {
+                    (directions[(i + count - 1) % count], directions[i])
+                } else {
+                    (
+                        directions[i.saturating_sub(1)],
+                        directions[i.min(bands - 1)],
+                    )
+                };
+                let sum = prev + next;
+                if sum.magnitude2() > 0.000_001 {
+                    sum.normalize()
+                } else {
+                    next
+                }
+            })
+            .collect::<Vec<Vector3<f32>>>();
+        let mut arc = vec![0.0];
+        for i in 0..bands {
+            let length = arc[i] + (self.path[self.next(i)] - self.path[i]).magnitude();
+            arc.push(length);
+        }
+        let total = arc[bands];
+
+        // Build a parallel-transport frame at each point, by rotating the
+        // previous frame through the minimal arc between consecutive tangents.
+        // The initial normal is chosen such that a tube along the y-axis is
+        // oriented in the same way as a cylinder.
+        let first = tangents[0];
+        let reference = if first.x.abs() < 0.9 {
+            Vector3::<f32>::unit_x()
+        } else {
+            Vector3::<f32>::unit_y()
+        };
+        let transport = |normal: Vector3<f32>, from: Vector3<f32>, to: Vector3<f32>| {
+            let normal = Quaternion::<f32>::from_arc(from, to, None) * normal;
+            (normal - to * to.dot(normal)).normalize()
+        };
+        let mut normal = (reference - first * first.dot(reference)).normalize();
+        let mut normals = Vec::<Vector3<f32>>::with_capacity(count);
+        for (i, &tangent) in tangents.iter().enumerate() {
+            if i > 0 {
+                normal = transport(normal, tangents[i - 1], tangent);
+            }
+            normals.push(normal);
+        }
+
+        // For a closed path, measure the twist between the first frame and the
+        // last frame transported back around to the start, and unwind it
+        // gradually along the length of the tube.
+        let twist = if self.closed {
+            let end = transport(normals[count - 1], tangents[count - 1], first);
+            -first.dot(normals[0].cross(end)).atan2(normals[0].dot(end))
+        } else {
+            0.0
+        };
+        let frames = (0..count)
+            .map(|i| {
+                let (t, n) = (tangents[i], normals[i]);
+                let (s, c) = (twist * arc[i] / total).sin_cos();
+                let n = n * c + t.cross(n) * s;
+                (n, t.cross(n))
+            })
+            .collect::<Vec<(Vector3<f32>, Vector3<f32>)>>();
+
+        // Build a lookup table of (cos, sin) pairs around the tube.
+        let angle = 2.0 * f32::consts::PI / self.segments as f32;
+        let ring = (0..=self.segments)
+            .map(|x| {
+                let a = ((x % self.segments) as f32) * angle;
+                [a.cos(), a.sin()]
+            })
+            .collect::<Vec<[f32; 2]>>();
+        let offset = |point: usize, index: usize| {
+            let [c, s] = ring[index];
+            let (n, b) = frames[point];
+            n * c + b * s
+        };
+
+        let mut emit = |position: Point3<f32>, normal: Vector3<f32>, texcoord: [f32; 2]| {
+            vertices.push(Vertex {
+                position: transform.point(position),
+                normal: transform.normal(normal),
+                texcoord,
+            });
+        };
+
+        // Emit the side faces. Angles increase counter-clock-wise when viewed
+        // from the end of the path, such that the u coordinate increases from
+        // left to right when viewed from outside. The last band of a closed
+        // path reuses the first cross-section, but with a v coordinate of 1.
+        for band in 0..bands {
+            for seg in 0..self.segments {
+                let corners = [
+                    (seg, band),
+                    (seg + 1, band),
+                    (seg + 1, band + 1),
+                    (seg, band),
+                    (seg + 1, band + 1),
+                    (seg, band + 1),
+                ];
+                for &(index, point) in corners.iter() {
+                    let dir = offset(point % count, index);
+                    emit(
+                        self.path[point % count] + dir * self.radius,
+                        dir,
+                        [index as f32 / self.segments as f32, arc[point] / total],
+                    );
+                }
+            }
+        }
+
+        // Emit the caps at the start and end of an open path as triangle fans
+        // about the path end points, skipping any cap that is disabled.
+        if !self.closed {
+            let last = count - 1;
+            let caps = [
+                (0, -1.0, self.caps[0], [1, 0]),
+                (last, 1.0, self.caps[1], [0, 1]),
+            ];
+            for &(point, sign, _, order) in caps.iter().filter(|cap| cap.2) {
+                let normal = tangents[point] * sign;
+                for seg in 0..self.segments {
+                    let indices = [seg + order[0], seg + order[1]];
+                    emit(self.path[point], normal, [0.5, 0.5]);
+                    for &index in indices.iter() {
+                        let [c, s] = ring[index];
+                        emit(
+                            self.path[point] + offset(point, index) * self.radius,
+                            normal,
+                            [0.5 + c * 0.5, 0.5 + s * sign * 0.5],
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,164 @@
+//! A crate-private module for computing the tangents of shapes, for normal
+//! mapping.
+
+extern crate cgmath;
+
+use self::cgmath::*;
+use crate::vertex::{Vertex, VertexTangent};
+
+use std::collections::HashMap;
+
+/// Attach a tangent to each vertex of a list of triangles.
+pub(crate) fn with_tangents(vertices: &[Vertex]) -> Vec<VertexTangent> {
+    let triangles = (0..vertices.len() / 3).map(|index| [index * 3, index * 3 + 1, index * 3 + 2]);
+    accumulate(vertices, triangles)
+}
+
+/// Attach a tangent to each vertex of a triangle strip. The degenerate
+/// triangles that join the faces of a strip do not contribute to the tangents.
+pub(crate) fn with_strip_tangents(vertices: &[Vertex]) -> Vec<VertexTangent> {
+    let triangles =
+        (0..vertices.len().saturating_sub(2)).map(|index| [index, index + 1, index + 2]);
+    accumulate(vertices, triangles)
+}
+
+/// Attach a tangent to each vertex of a list of indexed triangles.
+pub(crate) fn with_indexed_tangents(vertices: &[Vertex], indices: &[u16]) -> Vec<VertexTangent> {
+    let triangles = indices.chunks(3).map(|triangle| {
+        [
+            triangle[0] as usize,
+            triangle[1] as usize,
+            triangle[2] as usize,
+        ]
+    });
+    accumulate(vertices, triangles)
+}
+
+/// Compute the tangents of the vertices from the given triangles.
+///
+/// The tangent of each triangle points along the direction in which its u
+/// coordinate increases, and its bitangent along the direction in which its v
+/// coordinate increases. The tangents and bitangents of the triangles are
+/// accumulated at each vertex, over all of the vertices that share its
+/// position, normal, and texture coordinate, such that smoothly shaded shapes
+/// have smooth tangents. The accumulated tangent is then made orthogonal to
+/// the normal, and its w component holds the handedness of the tangent frame:
+/// the bitangent is `cross(normal, tangent) * w`. Vertices whose triangles
+/// have degenerate texture coordinates take an arbitrary tangent that is
+/// orthogonal to the normal.
+fn accumulate<I>(vertices: &[Vertex], triangles: I) -> Vec<VertexTangent>
+where
+    I: Iterator<Item = [usize; 3]>,
+{
+    let key = |vertex: &Vertex| {
+        let bits = |x: f32| if x == 0.0 { 0 } else { x.to_bits() };
+        (
+            vertex.position.map(bits),
+            vertex.normal.map(bits),
+            vertex.texcoord.map(bits),
+        )
+    };
+    let mut sums = HashMap::new();
+    for triangle in triangles {
+        let [v0, v1, v2] = triangle.map(|index| &vertices[index]);
+        let p0 = Vector3::<f32>::from(v0.position);
+        let (e1, e2) = (
+            Vector3::<f32>::from(v1.position) - p0,
+            Vector3::<f32>::from(v2.position) - p0,
+        );
+        let (du1, dv1) = (
+            v1.texcoord[0] - v0.texcoord[0],
+            v1.texcoord[1] - v0.texcoord[1],
+        );
+        let (du2, dv2) = (
+            v2.texcoord[0] - v0.texcoord[0],
+            v2.texcoord[1] - v0.texcoord[1],
+        );
+        let r = du1 * dv2 - du2 * dv1;
+        let tangent = (e1 * dv2 - e2 * dv1) * r.signum();
+        let bitangent = (e2 * du1 - e1 * du2) * r.signum();
+        if r == 0.0 || !is_usable(tangent) || !is_usable(bitangent) {
+            continue;
+        }
+        for vertex in [v0, v1, v2].iter() {
+            let sum = sums
+                .entry(key(vertex))
+                .or_insert((Vector3::<f32>::zero(), Vector3::<f32>::zero()));
+            sum.0 += tangent.normalize();
+            sum.1 += bitangent.normalize();
+        }
+    }
+
+    vertices
+        .iter()
+        .map(|vertex| {
+            let normal = Vector3::<f32>::from(vertex.normal);
+            let (tangent, bitangent) = sums
+                .get(&key(vertex))
+                .cloned()
+                .unwrap_or((Vector3::zero(), Vector3::zero()));
+            let tangent = tangent - normal * normal.dot(tangent);
+            let tangent = if is_usable(tangent) {
+                tangent.normalize()
+            } else {
+                orthogonal(normal)
+            };
+            let w = if normal.cross(tangent).dot(bitangent) < 0.0 {
+                -1.0
+            } else {
+                1.0
+            };
+            VertexTangent {
+                position: vertex.position,
+                normal: vertex.normal,
+                tangent: tangent.extend(w).into(),
+                texcoord: vertex.texcoord,
+            }
+        })
+        .collect()
+}
+
+/// Returns whether a vector has a direction, such that it can be normalized.
+fn is_usable(v: Vector3<f32>) -> bool {
+    let length = v.magnitude2();
+    length > 1.0e-12 && length.is_finite()
+}
+
+/// Returns a unit vector that is orthogonal to the given normal, taken from
+/// the coordinate axis that lies furthest from the normal.
+fn orthogonal(normal: Vector3<f32>) -> Vector3<f32> {
+    let axis = if normal.x.abs() <= normal.y.abs() && normal.x.abs() <= normal.z.abs() {
+        Vector3::unit_x()
+    } else if normal.y.abs() <= normal.z.abs() {
+        Vector3::unit_y()
+    } else {
+        Vector3::unit_z()
+    };
+    let tangent = axis - normal * normal.dot(axis);
+    if is_usable(tangent) {
+        tangent.normalize()
+    } else {
+        Vector3::unit_x()
+    }
+}
+
+#[test]
+pub fn ensure_degenerate_texcoords_take_orthogonal_tangent() {
+    let vertex = |position: [f32; 3]| Vertex {
+        position,
+        normal: [0.0, 0.0, 1.0],
+        texcoord: [0.5, 0.5],
+    };
+    let vertices = [
+        vertex([0.0, 0.0, 0.0]),
+        vertex([1.0, 0.0, 0.0]),
+        vertex([0.0, 1.0, 0.0]),
+    ];
+    for vertex in with_tangents(&vertices) {
+        let tangent = Vector4::<f32>::from(vertex.tangent);
+        assert!(tangent.x.is_finite() && tangent.y.is_finite() && tangent.z.is_finite());
+        assert_ulps_eq!(tangent.truncate().magnitude(), 1.0);
+        assert_eq!(tangent.truncate().dot(Vector3::unit_z()), 0.0);
+        assert_eq!(tangent.w.abs(), 1.0);
+    }
+}
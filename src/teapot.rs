@@ -0,0 +1,860 @@
+//! A module for constructing Utah teapot shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::errors::ShapeCreationError;
+use crate::facet::flatten_normals;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{
+    BuildAs, HasOptions, HasVertices, ShapeOptions, WithBackFaces, WithColors, WithLightmap,
+    WithModifiers, WithOrientation, WithShading, WithTangents, WithTexcoords, WithTransform,
+};
+use crate::shading::Shading;
+use crate::vertex::Vertex;
+
+/// A polygonal `Teapot` object.
+///
+/// This object is constructed using a `TeapotBuilder` object.
+pub struct Teapot {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows a `Teapot` object to be passed as a source of vertices.
+impl<'a> From<&'a Teapot> for glium::vertex::VerticesSource<'a> {
+    fn from(teapot: &'a Teapot) -> glium::vertex::VerticesSource<'a> {
+        (&teapot.vertices).into()
+    }
+}
+
+/// Allows a `Teapot` object to be passed as a source of indices.
+impl<'a> From<&'a Teapot> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a Teapot) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::TrianglesList,
+        }
+    }
+}
+
+/// Responsible for building and returning a `Teapot` object.
+///
+/// The teapot is constructed by evaluating the 32 bicubic Bézier patches of
+/// the classic Utah teapot, with its lid pointing along the positive y-axis
+/// and its spout pointing along the positive x-axis. By default, the control
+/// points of the patches are centred at the origin and scaled to fit within
+/// the unit cube, such that the teapot spans roughly unit length from the back
+/// of its handle to the tip of its spout. This can be overriden using the
+/// transformation methods on this object.
+///
+/// As with the original data, the body is not joined to the handle, the spout,
+/// or the lid, but adjacent patches share their boundaries exactly, such that
+/// there are no cracks between them. The resultant geometry is constructed to
+/// suit OpenGL defaults - assuming a right-handed coordinate system,
+/// front-facing polygons are defined in counter-clock-wise order. Vertex
+/// normals are computed from the derivatives of each patch (such that the
+/// teapot appears smooth when lit). Vertex texture coordinates map each patch
+/// to the unit square, such that textures appear upright on the body.
+pub struct TeapotBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    tessellation: usize,
+    options: ShapeOptions,
+}
+
+impl Default for TeapotBuilder {
+    fn default() -> Self {
+        TeapotBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            tessellation: 8,
+            options: ShapeOptions {
+                shading: Shading::Smooth,
+                ..ShapeOptions::default()
+            },
+        }
+    }
+}
+
+impl TeapotBuilder {
+    /// Create a new `TeapotBuilder` object.
+    pub fn new() -> TeapotBuilder {
+        Default::default()
+    }
+
+    /// Specify the number of segments along each side of each patch. By
+    /// default, the builder will use 8 segments. At least 1 segment is
+    /// required to build a valid teapot.
+    pub fn tessellation(mut self, segments: usize) -> Self {
+        self.tessellation = segments;
+        self
+    }
+
+    /// Build a new `Teapot` object.
+    pub fn build<F>(self, display: &F) -> Result<Teapot, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the shape vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// a `Teapot` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        let vertices = self.vertices_with::<Selected>()?;
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.options.check_shading(Self::SHADINGS)?;
+
+        // Ensure there are enough segments to produce valid teapot geometry.
+        if self.tessellation < 1 {
+            return Err(ShapeCreationError::NotEnoughDivisionsInU);
+        }
+
+        // Rotate the control points such that the lid points along the y-axis,
+        // then centre them at the origin and fit them to the unit cube.
+        let points = CONTROL_POINTS
+            .iter()
+            .map(|p| (Vector3::<f32>::new(p[0], p[2], -p[1]) - CENTRE) * SCALE)
+            .collect::<Vec<Vector3<f32>>>();
+
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<M>::new(self.matrix);
+
+        // Build vertex array. Each patch is evaluated on a regular grid of
+        // parameters, where the u parameter selects between the rows of control
+        // points, and the v parameter selects between the columns. Quads whose
+        // first triangle would touch a collapsed row of control points (as found
+        // at the tip of the lid and the centre of the base) are emitted as a
+        // single triangle.
+        let n = self.tessellation;
+        let mut vertices = Vec::<Vertex>::with_capacity(self.num_vertices());
+        for patch in PATCHES.iter() {
+            let control = |i: usize, j: usize| points[patch[i * 4 + j]];
+            let collapsed = [0, 3]
+                .iter()
+                .map(|&i| (1..4).all(|j| control(i, j) == control(i, 0)))
+                .collect::<Vec<bool>>();
+
+            // Evaluate the grid of positions and normals over the patch.
+            let mut grid = Vec::<(Vector3<f32>, Vector3<f32>)>::with_capacity((n + 1) * (n + 1));
+            for a in 0..=n {
+                for b in 0..=n {
+                    grid.push(evaluate(&control, a, b, n));
+                }
+            }
+
+            let mut push = |a: usize, b: usize| {
+                let (position, normal) = grid[a * (n + 1) + b];
+                vertices.push(Vertex {
+                    position: transform.point(position),
+                    normal: transform.normal(normal),
+                    texcoord: [1.0 - b as f32 / n as f32, 1.0 - a as f32 / n as f32],
+                });
+            };
+            for a in 0..n {
+                for b in 0..n {
+                    if !(a == 0 && collapsed[0]) {
+                        push(a, b);
+                        push(a, b + 1);
+                        push(a + 1, b + 1);
+                    }
+                    if !(a + 1 == n && collapsed[1]) {
+                        push(a, b);
+                        push(a + 1, b + 1);
+                        push(a + 1, b);
+                    }
+                }
+            }
+        }
+
+        if self.options.shading == Shading::Flat {
+            flatten_normals(&mut vertices);
+        }
+
+        self.options.map_texcoords(&mut vertices);
+        Ok(vertices)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    pub fn num_vertices(&self) -> usize {
+        let n = self.tessellation;
+        let collapsed = PATCHES
+            .iter()
+            .map(|patch| {
+                [0, 4 * 3]
+                    .iter()
+                    .filter(|&&row| (1..4).all(|j| patch[row + j] == patch[row]))
+                    .count()
+            })
+            .sum::<usize>();
+        ((PATCHES.len() * n * n * 6) - (collapsed * n * 3)) * self.options.copies()
+    }
+}
+
+/// The centre of the bounds of the control points, after rotating the lid to
+/// point along the y-axis.
+const CENTRE: Vector3<f32> = Vector3::<f32> {
+    x: 0.2625,
+    y: 1.575,
+    z: 0.0,
+};
+
+/// The scale that fits the control points to the unit cube, which is limited
+/// by their extent along the x-axis.
+const SCALE: f32 = 1.0 / 6.525;
+
+/// Returns the cubic Bernstein polynomials at the parameter `t`, where `s` is
+/// `1 - t`. The polynomials are symmetric in `t` and `s`, such that evaluating
+/// a curve in reverse yields exactly the same positions.
+fn bernstein(t: f32, s: f32) -> [f32; 4] {
+    [s * s * s, 3.0 * (t * s) * s, 3.0 * (t * s) * t, t * t * t]
+}
+
+/// Returns the derivatives of the cubic Bernstein polynomials at the parameter
+/// `t`, where `s` is `1 - t`.
+fn bernstein_derivative(t: f32, s: f32) -> [f32; 4] {
+    [
+        -3.0 * s * s,
+        3.0 * s * (s - 2.0 * t),
+        3.0 * t * (2.0 * s - t),
+        3.0 * t * t,
+    ]
+}
+
+/// Returns the weighted sum of four points, summed in an order that is
+/// symmetric under reversal.
+fn combine(weights: &[f32; 4], points: &[Vector3<f32>; 4]) -> Vector3<f32> {
+    (points[0] * weights[0] + points[3] * weights[3])
+        + (points[1] * weights[1] + points[2] * weights[2])
+}
+
+/// Returns the position and outward-facing normal of a patch at the grid
+/// indices `(a, b)` of an `n` by `n` grid.
+///
+/// Both parameters are computed from integer ratios, such that patches that
+/// share a boundary evaluate exactly the same positions along it, regardless
+/// of the direction in which they traverse it. Where the derivatives vanish
+/// (i.e. at a collapsed row of control points), the normal is evaluated a
+/// small distance into the patch instead.
+fn evaluate<F>(control: &F, a: usize, b: usize, n: usize) -> (Vector3<f32>, Vector3<f32>)
+where
+    F: Fn(usize, usize) -> Vector3<f32>,
+{
+    let (u, su) = (a as f32 / n as f32, (n - a) as f32 / n as f32);
+    let (v, sv) = (b as f32 / n as f32, (n - b) as f32 / n as f32);
+    let rows = |weights: &[f32; 4]| {
+        let mut rows = [Vector3::<f32>::zero(); 4];
+        for (i, row) in rows.iter_mut().enumerate() {
+            *row = combine(
+                weights,
+                &[control(i, 0), control(i, 1), control(i, 2), control(i, 3)],
+            );
+        }
+        rows
+    };
+    let position = combine(&bernstein(u, su), &rows(&bernstein(v, sv)));
+
+    let normal_at = |u: f32, v: f32| {
+        let (bu, bv) = (bernstein(u, 1.0 - u), bernstein(v, 1.0 - v));
+        let du = combine(&bernstein_derivative(u, 1.0 - u), &rows(&bv));
+        let dv = combine(&bu, &rows(&bernstein_derivative(v, 1.0 - v)));
+        dv.cross(du)
+    };
+    let mut normal = normal_at(u, v);
+    if normal.magnitude2() < 1e-12 {
+        normal = normal_at(u + (0.5 - u) * 1e-3, v + (0.5 - v) * 1e-3);
+    }
+    (position, normal.normalize())
+}
+
+/// The control point indices of each of the 32 bicubic Bézier patches of the
+/// Utah teapot. Each patch indexes 4 rows of 4 control points, and the indices
+/// are zero-based.
+const PATCHES: [[usize; 16]; 32] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [3, 16, 17, 18, 7, 19, 20, 21, 11, 22, 23, 24, 15, 25, 26, 27],
+    [
+        18, 28, 29, 30, 21, 31, 32, 33, 24, 34, 35, 36, 27, 37, 38, 39,
+    ],
+    [30, 40, 41, 0, 33, 42, 43, 4, 36, 44, 45, 8, 39, 46, 47, 12],
+    [
+        12, 13, 14, 15, 48, 49, 50, 51, 52, 53, 54, 55, 56, 57, 58, 59,
+    ],
+    [
+        15, 25, 26, 27, 51, 60, 61, 62, 55, 63, 64, 65, 59, 66, 67, 68,
+    ],
+    [
+        27, 37, 38, 39, 62, 69, 70, 71, 65, 72, 73, 74, 68, 75, 76, 77,
+    ],
+    [
+        39, 46, 47, 12, 71, 78, 79, 48, 74, 80, 81, 52, 77, 82, 83, 56,
+    ],
+    [
+        56, 57, 58, 59, 84, 85, 86, 87, 88, 89, 90, 91, 92, 93, 94, 95,
+    ],
+    [
+        59, 66, 67, 68, 87, 96, 97, 98, 91, 99, 100, 101, 95, 102, 103, 104,
+    ],
+    [
+        68, 75, 76, 77, 98, 105, 106, 107, 101, 108, 109, 110, 104, 111, 112, 113,
+    ],
+    [
+        77, 82, 83, 56, 107, 114, 115, 84, 110, 116, 117, 88, 113, 118, 119, 92,
+    ],
+    [
+        120, 121, 122, 123, 124, 125, 126, 127, 128, 129, 130, 131, 132, 133, 134, 135,
+    ],
+    [
+        123, 136, 137, 120, 127, 138, 139, 124, 131, 140, 141, 128, 135, 142, 143, 132,
+    ],
+    [
+        132, 133, 134, 135, 144, 145, 146, 147, 148, 149, 150, 151, 68, 152, 153, 154,
+    ],
+    [
+        135, 142, 143, 132, 147, 155, 156, 144, 151, 157, 158, 148, 154, 159, 160, 68,
+    ],
+    [
+        161, 162, 163, 164, 165, 166, 167, 168, 169, 170, 171, 172, 173, 174, 175, 176,
+    ],
+    [
+        164, 177, 178, 161, 168, 179, 180, 165, 172, 181, 182, 169, 176, 183, 184, 173,
+    ],
+    [
+        173, 174, 175, 176, 185, 186, 187, 188, 189, 190, 191, 192, 193, 194, 195, 196,
+    ],
+    [
+        176, 183, 184, 173, 188, 197, 198, 185, 192, 199, 200, 189, 196, 201, 202, 193,
+    ],
+    [
+        203, 203, 203, 203, 206, 207, 208, 209, 210, 210, 210, 210, 211, 212, 213, 214,
+    ],
+    [
+        203, 203, 203, 203, 209, 216, 217, 218, 210, 210, 210, 210, 214, 219, 220, 221,
+    ],
+    [
+        203, 203, 203, 203, 218, 223, 224, 225, 210, 210, 210, 210, 221, 226, 227, 228,
+    ],
+    [
+        203, 203, 203, 203, 225, 229, 230, 206, 210, 210, 210, 210, 228, 231, 232, 211,
+    ],
+    [
+        211, 212, 213, 214, 233, 234, 235, 236, 237, 238, 239, 240, 241, 242, 243, 244,
+    ],
+    [
+        214, 219, 220, 221, 236, 245, 246, 247, 240, 248, 249, 250, 244, 251, 252, 253,
+    ],
+    [
+        221, 226, 227, 228, 247, 254, 255, 256, 250, 257, 258, 259, 253, 260, 261, 262,
+    ],
+    [
+        228, 231, 232, 211, 256, 263, 264, 233, 259, 265, 266, 237, 262, 267, 268, 241,
+    ],
+    [
+        269, 269, 269, 269, 278, 279, 280, 281, 274, 275, 276, 277, 270, 271, 272, 273,
+    ],
+    [
+        269, 269, 269, 269, 281, 288, 289, 290, 277, 285, 286, 287, 273, 282, 283, 284,
+    ],
+    [
+        269, 269, 269, 269, 290, 297, 298, 299, 287, 294, 295, 296, 284, 291, 292, 293,
+    ],
+    [
+        269, 269, 269, 269, 299, 304, 305, 278, 296, 302, 303, 274, 293, 300, 301, 270,
+    ],
+];
+
+/// The control points of the Utah teapot, as originally defined with the
+/// z-axis pointing up.
+const CONTROL_POINTS: [[f32; 3]; 306] = [
+    [1.4, 0.0, 2.4],
+    [1.4, -0.784, 2.4],
+    [0.784, -1.4, 2.4],
+    [0.0, -1.4, 2.4],
+    [1.3375, 0.0, 2.53125],
+    [1.3375, -0.749, 2.53125],
+    [0.749, -1.3375, 2.53125],
+    [0.0, -1.3375, 2.53125],
+    [1.4375, 0.0, 2.53125],
+    [1.4375, -0.805, 2.53125],
+    [0.805, -1.4375, 2.53125],
+    [0.0, -1.4375, 2.53125],
+    [1.5, 0.0, 2.4],
+    [1.5, -0.84, 2.4],
+    [0.84, -1.5, 2.4],
+    [0.0, -1.5, 2.4],
+    [-0.784, -1.4, 2.4],
+    [-1.4, -0.784, 2.4],
+    [-1.4, 0.0, 2.4],
+    [-0.749, -1.3375, 2.53125],
+    [-1.3375, -0.749, 2.53125],
+    [-1.3375, 0.0, 2.53125],
+    [-0.805, -1.4375, 2.53125],
+    [-1.4375, -0.805, 2.53125],
+    [-1.4375, 0.0, 2.53125],
+    [-0.84, -1.5, 2.4],
+    [-1.5, -0.84, 2.4],
+    [-1.5, 0.0, 2.4],
+    [-1.4, 0.784, 2.4],
+    [-0.784, 1.4, 2.4],
+    [0.0, 1.4, 2.4],
+    [-1.3375, 0.749, 2.53125],
+    [-0.749, 1.3375, 2.53125],
+    [0.0, 1.3375, 2.53125],
+    [-1.4375, 0.805, 2.53125],
+    [-0.805, 1.4375, 2.53125],
+    [0.0, 1.4375, 2.53125],
+    [-1.5, 0.84, 2.4],
+    [-0.84, 1.5, 2.4],
+    [0.0, 1.5, 2.4],
+    [0.784, 1.4, 2.4],
+    [1.4, 0.784, 2.4],
+    [0.749, 1.3375, 2.53125],
+    [1.3375, 0.749, 2.53125],
+    [0.805, 1.4375, 2.53125],
+    [1.4375, 0.805, 2.53125],
+    [0.84, 1.5, 2.4],
+    [1.5, 0.84, 2.4],
+    [1.75, 0.0, 1.875],
+    [1.75, -0.98, 1.875],
+    [0.98, -1.75, 1.875],
+    [0.0, -1.75, 1.875],
+    [2.0, 0.0, 1.35],
+    [2.0, -1.12, 1.35],
+    [1.12, -2.0, 1.35],
+    [0.0, -2.0, 1.35],
+    [2.0, 0.0, 0.9],
+    [2.0, -1.12, 0.9],
+    [1.12, -2.0, 0.9],
+    [0.0, -2.0, 0.9],
+    [-0.98, -1.75, 1.875],
+    [-1.75, -0.98, 1.875],
+    [-1.75, 0.0, 1.875],
+    [-1.12, -2.0, 1.35],
+    [-2.0, -1.12, 1.35],
+    [-2.0, 0.0, 1.35],
+    [-1.12, -2.0, 0.9],
+    [-2.0, -1.12, 0.9],
+    [-2.0, 0.0, 0.9],
+    [-1.75, 0.98, 1.875],
+    [-0.98, 1.75, 1.875],
+    [0.0, 1.75, 1.875],
+    [-2.0, 1.12, 1.35],
+    [-1.12, 2.0, 1.35],
+    [0.0, 2.0, 1.35],
+    [-2.0, 1.12, 0.9],
+    [-1.12, 2.0, 0.9],
+    [0.0, 2.0, 0.9],
+    [0.98, 1.75, 1.875],
+    [1.75, 0.98, 1.875],
+    [1.12, 2.0, 1.35],
+    [2.0, 1.12, 1.35],
+    [1.12, 2.0, 0.9],
+    [2.0, 1.12, 0.9],
+    [2.0, 0.0, 0.45],
+    [2.0, -1.12, 0.45],
+    [1.12, -2.0, 0.45],
+    [0.0, -2.0, 0.45],
+    [1.5, 0.0, 0.225],
+    [1.5, -0.84, 0.225],
+    [0.84, -1.5, 0.225],
+    [0.0, -1.5, 0.225],
+    [1.5, 0.0, 0.15],
+    [1.5, -0.84, 0.15],
+    [0.84, -1.5, 0.15],
+    [0.0, -1.5, 0.15],
+    [-1.12, -2.0, 0.45],
+    [-2.0, -1.12, 0.45],
+    [-2.0, 0.0, 0.45],
+    [-0.84, -1.5, 0.225],
+    [-1.5, -0.84, 0.225],
+    [-1.5, 0.0, 0.225],
+    [-0.84, -1.5, 0.15],
+    [-1.5, -0.84, 0.15],
+    [-1.5, 0.0, 0.15],
+    [-2.0, 1.12, 0.45],
+    [-1.12, 2.0, 0.45],
+    [0.0, 2.0, 0.45],
+    [-1.5, 0.84, 0.225],
+    [-0.84, 1.5, 0.225],
+    [0.0, 1.5, 0.225],
+    [-1.5, 0.84, 0.15],
+    [-0.84, 1.5, 0.15],
+    [0.0, 1.5, 0.15],
+    [1.12, 2.0, 0.45],
+    [2.0, 1.12, 0.45],
+    [0.84, 1.5, 0.225],
+    [1.5, 0.84, 0.225],
+    [0.84, 1.5, 0.15],
+    [1.5, 0.84, 0.15],
+    [-1.6, 0.0, 2.025],
+    [-1.6, -0.3, 2.025],
+    [-1.5, -0.3, 2.25],
+    [-1.5, 0.0, 2.25],
+    [-2.3, 0.0, 2.025],
+    [-2.3, -0.3, 2.025],
+    [-2.5, -0.3, 2.25],
+    [-2.5, 0.0, 2.25],
+    [-2.7, 0.0, 2.025],
+    [-2.7, -0.3, 2.025],
+    [-3.0, -0.3, 2.25],
+    [-3.0, 0.0, 2.25],
+    [-2.7, 0.0, 1.8],
+    [-2.7, -0.3, 1.8],
+    [-3.0, -0.3, 1.8],
+    [-3.0, 0.0, 1.8],
+    [-1.5, 0.3, 2.25],
+    [-1.6, 0.3, 2.025],
+    [-2.5, 0.3, 2.25],
+    [-2.3, 0.3, 2.025],
+    [-3.0, 0.3, 2.25],
+    [-2.7, 0.3, 2.025],
+    [-3.0, 0.3, 1.8],
+    [-2.7, 0.3, 1.8],
+    [-2.7, 0.0, 1.575],
+    [-2.7, -0.3, 1.575],
+    [-3.0, -0.3, 1.35],
+    [-3.0, 0.0, 1.35],
+    [-2.5, 0.0, 1.125],
+    [-2.5, -0.3, 1.125],
+    [-2.65, -0.3, 0.9375],
+    [-2.65, 0.0, 0.9375],
+    [-2.0, -0.3, 0.9],
+    [-1.9, -0.3, 0.6],
+    [-1.9, 0.0, 0.6],
+    [-3.0, 0.3, 1.35],
+    [-2.7, 0.3, 1.575],
+    [-2.65, 0.3, 0.9375],
+    [-2.5, 0.3, 1.125],
+    [-1.9, 0.3, 0.6],
+    [-2.0, 0.3, 0.9],
+    [1.7, 0.0, 1.425],
+    [1.7, -0.66, 1.425],
+    [1.7, -0.66, 0.6],
+    [1.7, 0.0, 0.6],
+    [2.6, 0.0, 1.425],
+    [2.6, -0.66, 1.425],
+    [3.1, -0.66, 0.825],
+    [3.1, 0.0, 0.825],
+    [2.3, 0.0, 2.1],
+    [2.3, -0.25, 2.1],
+    [2.4, -0.25, 2.025],
+    [2.4, 0.0, 2.025],
+    [2.7, 0.0, 2.4],
+    [2.7, -0.25, 2.4],
+    [3.3, -0.25, 2.4],
+    [3.3, 0.0, 2.4],
+    [1.7, 0.66, 0.6],
+    [1.7, 0.66, 1.425],
+    [3.1, 0.66, 0.825],
+    [2.6, 0.66, 1.425],
+    [2.4, 0.25, 2.025],
+    [2.3, 0.25, 2.1],
+    [3.3, 0.25, 2.4],
+    [2.7, 0.25, 2.4],
+    [2.8, 0.0, 2.475],
+    [2.8, -0.25, 2.475],
+    [3.525, -0.25, 2.49375],
+    [3.525, 0.0, 2.49375],
+    [2.9, 0.0, 2.475],
+    [2.9, -0.15, 2.475],
+    [3.45, -0.15, 2.5125],
+    [3.45, 0.0, 2.5125],
+    [2.8, 0.0, 2.4],
+    [2.8, -0.15, 2.4],
+    [3.2, -0.15, 2.4],
+    [3.2, 0.0, 2.4],
+    [3.525, 0.25, 2.49375],
+    [2.8, 0.25, 2.475],
+    [3.45, 0.15, 2.5125],
+    [2.9, 0.15, 2.475],
+    [3.2, 0.15, 2.4],
+    [2.8, 0.15, 2.4],
+    [0.0, 0.0, 3.15],
+    [0.0, -0.002, 3.15],
+    [0.002, 0.0, 3.15],
+    [0.8, 0.0, 3.15],
+    [0.8, -0.45, 3.15],
+    [0.45, -0.8, 3.15],
+    [0.0, -0.8, 3.15],
+    [0.0, 0.0, 2.85],
+    [0.2, 0.0, 2.7],
+    [0.2, -0.112, 2.7],
+    [0.112, -0.2, 2.7],
+    [0.0, -0.2, 2.7],
+    [-0.002, 0.0, 3.15],
+    [-0.45, -0.8, 3.15],
+    [-0.8, -0.45, 3.15],
+    [-0.8, 0.0, 3.15],
+    [-0.112, -0.2, 2.7],
+    [-0.2, -0.112, 2.7],
+    [-0.2, 0.0, 2.7],
+    [0.0, 0.002, 3.15],
+    [-0.8, 0.45, 3.15],
+    [-0.45, 0.8, 3.15],
+    [0.0, 0.8, 3.15],
+    [-0.2, 0.112, 2.7],
+    [-0.112, 0.2, 2.7],
+    [0.0, 0.2, 2.7],
+    [0.45, 0.8, 3.15],
+    [0.8, 0.45, 3.15],
+    [0.112, 0.2, 2.7],
+    [0.2, 0.112, 2.7],
+    [0.4, 0.0, 2.55],
+    [0.4, -0.224, 2.55],
+    [0.224, -0.4, 2.55],
+    [0.0, -0.4, 2.55],
+    [1.3, 0.0, 2.55],
+    [1.3, -0.728, 2.55],
+    [0.728, -1.3, 2.55],
+    [0.0, -1.3, 2.55],
+    [1.3, 0.0, 2.4],
+    [1.3, -0.728, 2.4],
+    [0.728, -1.3, 2.4],
+    [0.0, -1.3, 2.4],
+    [-0.224, -0.4, 2.55],
+    [-0.4, -0.224, 2.55],
+    [-0.4, 0.0, 2.55],
+    [-0.728, -1.3, 2.55],
+    [-1.3, -0.728, 2.55],
+    [-1.3, 0.0, 2.55],
+    [-0.728, -1.3, 2.4],
+    [-1.3, -0.728, 2.4],
+    [-1.3, 0.0, 2.4],
+    [-0.4, 0.224, 2.55],
+    [-0.224, 0.4, 2.55],
+    [0.0, 0.4, 2.55],
+    [-1.3, 0.728, 2.55],
+    [-0.728, 1.3, 2.55],
+    [0.0, 1.3, 2.55],
+    [-1.3, 0.728, 2.4],
+    [-0.728, 1.3, 2.4],
+    [0.0, 1.3, 2.4],
+    [0.224, 0.4, 2.55],
+    [0.4, 0.224, 2.55],
+    [0.728, 1.3, 2.55],
+    [1.3, 0.728, 2.55],
+    [0.728, 1.3, 2.4],
+    [1.3, 0.728, 2.4],
+    [0.0, 0.0, 0.0],
+    [1.5, 0.0, 0.15],
+    [1.5, 0.84, 0.15],
+    [0.84, 1.5, 0.15],
+    [0.0, 1.5, 0.15],
+    [1.5, 0.0, 0.075],
+    [1.5, 0.84, 0.075],
+    [0.84, 1.5, 0.075],
+    [0.0, 1.5, 0.075],
+    [1.425, 0.0, 0.0],
+    [1.425, 0.798, 0.0],
+    [0.798, 1.425, 0.0],
+    [0.0, 1.425, 0.0],
+    [-0.84, 1.5, 0.15],
+    [-1.5, 0.84, 0.15],
+    [-1.5, 0.0, 0.15],
+    [-0.84, 1.5, 0.075],
+    [-1.5, 0.84, 0.075],
+    [-1.5, 0.0, 0.075],
+    [-0.798, 1.425, 0.0],
+    [-1.425, 0.798, 0.0],
+    [-1.425, 0.0, 0.0],
+    [-1.5, -0.84, 0.15],
+    [-0.84, -1.5, 0.15],
+    [0.0, -1.5, 0.15],
+    [-1.5, -0.84, 0.075],
+    [-0.84, -1.5, 0.075],
+    [0.0, -1.5, 0.075],
+    [-1.425, -0.798, 0.0],
+    [-0.798, -1.425, 0.0],
+    [0.0, -1.425, 0.0],
+    [0.84, -1.5, 0.15],
+    [1.5, -0.84, 0.15],
+    [0.84, -1.5, 0.075],
+    [1.5, -0.84, 0.075],
+    [0.798, -1.425, 0.0],
+    [1.425, -0.798, 0.0],
+];
+
+impl HasOptions for TeapotBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for TeapotBuilder {
+    type Shape = Teapot;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> Teapot {
+        Teapot { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for TeapotBuilder {}
+
+impl BuildAs for TeapotBuilder {}
+
+impl WithTangents for TeapotBuilder {}
+
+impl WithLightmap for TeapotBuilder {}
+
+impl WithOrientation for TeapotBuilder {}
+
+impl WithBackFaces for TeapotBuilder {}
+
+impl WithModifiers for TeapotBuilder {}
+
+impl WithTexcoords for TeapotBuilder {}
+
+impl WithColors for TeapotBuilder {}
+
+impl WithShading for TeapotBuilder {
+    /// By default, the teapot is smooth shaded. When flat shaded, each vertex
+    /// normal points in the direction of its respective triangle instead.
+    const SHADINGS: &'static [Shading] = &[Shading::Flat, Shading::Smooth];
+}
+
+#[test]
+pub fn ensure_default_teapot_fits_unit_cube() {
+    let vertices = TeapotBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let (mut min, mut max) = (
+        Vector3::<f32>::from_value(1.0),
+        Vector3::<f32>::from_value(-1.0),
+    );
+    for vertex in &vertices {
+        let pos = Vector3::<f32>::from(vertex.position);
+        for axis in 0..3 {
+            assert!(pos[axis].abs() <= 0.5 + 0.0001);
+            min[axis] = min[axis].min(pos[axis]);
+            max[axis] = max[axis].max(pos[axis]);
+        }
+    }
+    assert!(max.x - min.x > 0.95);
+    assert!(max.y - min.y > 0.45);
+}
+
+#[test]
+pub fn ensure_default_teapot_has_ccw_triangles() {
+    let vertices = TeapotBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let eyepos = v0 + Vector3::<f32>::from(chunk[0].normal);
+        let e0 = v1 - v0;
+        let e1 = v2 - v0;
+        let n = e0.cross(e1);
+        assert!(n.dot(v0 - eyepos) <= 0.0);
+        assert!(n.dot(v1 - eyepos) <= 0.0);
+        assert!(n.dot(v2 - eyepos) <= 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_teapot_normals_are_unit_length() {
+    let vertices = TeapotBuilder::new()
+        .tessellation(3)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        assert_ulps_eq!(
+            Vector3::<f32>::from(vertex.normal).magnitude(),
+            1.0,
+            epsilon = 0.0001
+        );
+        assert!(vertex.texcoord[0] >= 0.0 && vertex.texcoord[0] <= 1.0);
+        assert!(vertex.texcoord[1] >= 0.0 && vertex.texcoord[1] <= 1.0);
+    }
+}
+
+#[test]
+pub fn ensure_teapot_patch_boundaries_are_watertight() {
+    use std::collections::HashMap;
+    for &tessellation in [2, 4, 7].iter() {
+        let vertices = TeapotBuilder::new()
+            .tessellation(tessellation)
+            .build_vertices()
+            .expect("Failed to build vertices");
+        let key = |p: [f32; 3]| p.map(|x| (x * 100_000.0).round() as i32);
+        let mut edges = HashMap::<([i32; 3], [i32; 3]), usize>::new();
+        for chunk in vertices.chunks(3) {
+            for k in 0..3 {
+                let a = key(chunk[k].position);
+                let b = key(chunk[(k + 1) % 3].position);
+                assert!(a != b);
+                *edges
+                    .entry(if a < b { (a, b) } else { (b, a) })
+                    .or_insert(0) += 1;
+            }
+        }
+
+        // Only the rim of the body, the rim of the lid, both ends of the spout,
+        // and both ends of the handle are open, and each spans 4 or 2 patches.
+        assert!(edges.values().all(|&count| count <= 2));
+        let open = edges.values().filter(|&&count| count == 1).count();
+        assert_eq!(open, 16 * tessellation);
+    }
+}
+
+#[test]
+pub fn ensure_teapot_with_no_tessellation_fails_to_build() {
+    match TeapotBuilder::new().tessellation(0).build_vertices() {
+        Err(ShapeCreationError::NotEnoughDivisionsInU) => (),
+        _ => panic!("Expected NotEnoughDivisionsInU"),
+    }
+}
+
+#[test]
+pub fn ensure_teapot_applies_late_pipeline() {
+    use crate::options::WithTexcoords;
+    use crate::uv_mapping::UvMapping;
+
+    crate::options::ensure_late_pipeline(
+        || {
+            TeapotBuilder::new()
+                .uv_mapping(UvMapping::Box)
+                .double_sided()
+        },
+        TeapotBuilder::build_vertices,
+    );
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_teapot() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = TeapotBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
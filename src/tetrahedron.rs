@@ -0,0 +1,318 @@
+//! A module for constructing tetrahedron shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::errors::ShapeCreationError;
+use crate::facet::push_polygon;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{
+    BuildAs, HasOptions, HasVertices, ShapeOptions, WithBackFaces, WithColors, WithLightmap,
+    WithModifiers, WithOrientation, WithShading, WithTangents, WithTexcoords, WithTransform,
+};
+use crate::shading::Shading;
+use crate::vertex::Vertex;
+
+/// A polygonal `Tetrahedron` object.
+///
+/// This object is constructed using a `TetrahedronBuilder` object.
+pub struct Tetrahedron {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows a `Tetrahedron` object to be passed as a source of vertices.
+impl<'a> From<&'a Tetrahedron> for glium::vertex::VerticesSource<'a> {
+    fn from(tetrahedron: &'a Tetrahedron) -> glium::vertex::VerticesSource<'a> {
+        (&tetrahedron.vertices).into()
+    }
+}
+
+/// Allows a `Tetrahedron` object to be passed as a source of indices.
+impl<'a> From<&'a Tetrahedron> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a Tetrahedron) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::TrianglesList,
+        }
+    }
+}
+
+/// Responsible for building and returning a `Tetrahedron` object.
+///
+/// By default, the tetrahedron is defined as a regular tetrahedron inscribed
+/// in a unit-sphere (e.g. each vertex lies at a distance of 1 from the origin),
+/// with its centre-of-mass located at the origin. This can be overriden using
+/// the transformation methods on this object.
+///
+/// The resultant geometry is constructed to suit OpenGL defaults - assuming
+/// a right-handed coordinate system, front-facing polygons are defined in
+/// counter-clock-wise order. Vertex normals point in the direction of their
+/// respective face (such that the shape appears faceted when lit). Vertex
+/// texture coordinates define a planar-projection on each face.
+pub struct TetrahedronBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    options: ShapeOptions,
+}
+
+impl Default for TetrahedronBuilder {
+    fn default() -> Self {
+        TetrahedronBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            options: ShapeOptions::default(),
+        }
+    }
+}
+
+impl TetrahedronBuilder {
+    /// Create a new `TetrahedronBuilder` object.
+    pub fn new() -> TetrahedronBuilder {
+        Default::default()
+    }
+
+    /// Build a new `Tetrahedron` object.
+    pub fn build<F>(self, display: &F) -> Result<Tetrahedron, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the shape vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// a `Tetrahedron` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        let vertices = self.vertices_with::<Selected>()?;
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.options.check_shading(Self::SHADINGS)?;
+
+        // Define the vertices of the tetrahedron, which occupy alternate corners
+        // of a cube, projected onto the unit sphere.
+        let s = 1.0 / 3.0f32.sqrt();
+        let points = [
+            Vector3::<f32>::new(s, s, s),
+            Vector3::<f32>::new(s, -s, -s),
+            Vector3::<f32>::new(-s, s, -s),
+            Vector3::<f32>::new(-s, -s, s),
+        ];
+
+        // Define the faces of the tetrahedron, wound counter-clock-wise when
+        // viewed from outside.
+        let faces = [[2, 0, 1], [1, 0, 3], [3, 0, 2], [2, 1, 3]];
+
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<M>::new(self.matrix);
+
+        // Emit each face.
+        let mut vertices = Vec::<Vertex>::with_capacity(self.num_vertices());
+        for face in faces.iter() {
+            let polygon = face
+                .iter()
+                .map(|&i| points[i])
+                .collect::<Vec<Vector3<f32>>>();
+            push_polygon(&mut vertices, &transform, &polygon);
+        }
+
+        self.options.map_texcoords(&mut vertices);
+        Ok(vertices)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    pub fn num_vertices(&self) -> usize {
+        4 * 3 * self.options.copies()
+    }
+}
+
+impl HasOptions for TetrahedronBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for TetrahedronBuilder {
+    type Shape = Tetrahedron;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> Tetrahedron {
+        Tetrahedron { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for TetrahedronBuilder {}
+
+impl BuildAs for TetrahedronBuilder {}
+
+impl WithTangents for TetrahedronBuilder {}
+
+impl WithLightmap for TetrahedronBuilder {}
+
+impl WithOrientation for TetrahedronBuilder {}
+
+impl WithBackFaces for TetrahedronBuilder {}
+
+impl WithModifiers for TetrahedronBuilder {}
+
+impl WithTexcoords for TetrahedronBuilder {}
+
+impl WithColors for TetrahedronBuilder {}
+
+impl WithShading for TetrahedronBuilder {
+    /// Only flat shading is supported by the tetrahedron.
+    const SHADINGS: &'static [Shading] = &[Shading::Flat];
+}
+
+#[test]
+pub fn ensure_default_tetrahedron_has_centroid_at_origin() {
+    let vertices = TetrahedronBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let mut sum = Vector3::<f32>::zero();
+    for vertex in &vertices {
+        sum += Vector3::<f32>::from(vertex.position);
+    }
+    assert_ulps_eq!(sum, Vector3::<f32>::zero(), epsilon = 0.0001);
+}
+
+#[test]
+pub fn ensure_default_tetrahedron_has_unit_outward_face_normals() {
+    let vertices = TetrahedronBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let centroid = (Vector3::<f32>::from(chunk[0].position)
+            + Vector3::<f32>::from(chunk[1].position)
+            + Vector3::<f32>::from(chunk[2].position))
+            / 3.0;
+        for vertex in chunk {
+            let normal = Vector3::<f32>::from(vertex.normal);
+            assert!(abs_diff_eq!(normal.magnitude(), 1.0, epsilon = 0.0001));
+            assert!(normal.dot(centroid) > 0.0);
+        }
+    }
+}
+
+#[test]
+pub fn ensure_default_tetrahedron_has_ccw_triangles() {
+    let vertices = TetrahedronBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let eyepos = v0 + Vector3::<f32>::from(chunk[0].normal);
+        let e0 = v1 - v0;
+        let e1 = v2 - v0;
+        let n = e0.cross(e1);
+        assert!(n.dot(v0 - eyepos) <= 0.0);
+        assert!(n.dot(v1 - eyepos) <= 0.0);
+        assert!(n.dot(v2 - eyepos) <= 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_default_tetrahedron_has_faceted_normals() {
+    let vertices = TetrahedronBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let n = (v1 - v0).cross(v2 - v0).normalize();
+        for vertex in chunk {
+            assert_ulps_eq!(n, Vector3::<f32>::from(vertex.normal), epsilon = 0.0001);
+        }
+    }
+}
+
+#[test]
+pub fn ensure_default_tetrahedron_has_uvs_in_unit_range() {
+    let vertices = TetrahedronBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        assert!(vertex.texcoord[0] >= -0.0001 && vertex.texcoord[0] <= 1.0001);
+        assert!(vertex.texcoord[1] >= -0.0001 && vertex.texcoord[1] <= 1.0001);
+    }
+}
+
+#[test]
+pub fn ensure_default_tetrahedron_is_inscribed_in_unit_sphere() {
+    let vertices = TetrahedronBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    assert_eq!(vertices.len(), 12);
+    for vertex in &vertices {
+        let pos = Vector3::<f32>::from(vertex.position);
+        assert!(abs_diff_eq!(pos.magnitude(), 1.0, epsilon = 0.0001));
+    }
+}
+
+#[test]
+pub fn ensure_smooth_tetrahedron_fails_to_build() {
+    match TetrahedronBuilder::new()
+        .shading(Shading::Smooth)
+        .build_vertices()
+    {
+        Err(ShapeCreationError::UnsupportedShading) => (),
+        _ => panic!("Expected UnsupportedShading"),
+    }
+}
+
+#[test]
+pub fn ensure_tetrahedron_applies_late_pipeline() {
+    use crate::options::WithTexcoords;
+    use crate::uv_mapping::UvMapping;
+
+    crate::options::ensure_late_pipeline(
+        || {
+            TetrahedronBuilder::new()
+                .uv_mapping(UvMapping::Box)
+                .double_sided()
+        },
+        TetrahedronBuilder::build_vertices,
+    );
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_tetrahedron() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = TetrahedronBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
+
+#[test]
+pub fn ensure_tetrahedron_supports_its_shadings() {
+    crate::options::ensure_shadings(TetrahedronBuilder::new, TetrahedronBuilder::build_vertices);
+}
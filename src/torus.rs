@@ -0,0 +1,628 @@
+//! A module for constructing torus shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::errors::ShapeCreationError;
+use crate::facet::flatten_normals;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{
+    BuildAs, HasOptions, HasVertices, ShapeOptions, WithBackFaces, WithColors, WithLightmap,
+    WithModifiers, WithOrientation, WithShading, WithTangents, WithTexcoords, WithTransform,
+};
+use crate::resolution::{Resolution, WithResolution};
+use crate::shading::Shading;
+use crate::vertex::Vertex;
+
+use std::f32;
+
+/// A polygonal `Torus` object.
+///
+/// This object is constructed using a `TorusBuilder` object.
+pub struct Torus {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows a `Torus` object to be passed as a source of vertices.
+impl<'a> From<&'a Torus> for glium::vertex::VerticesSource<'a> {
+    fn from(torus: &'a Torus) -> glium::vertex::VerticesSource<'a> {
+        (&torus.vertices).into()
+    }
+}
+
+/// Allows a `Torus` object to be passed as a source of indices.
+impl<'a> From<&'a Torus> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a Torus) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::TrianglesList,
+        }
+    }
+}
+
+/// Responsible for building and returning a `Torus` object.
+///
+/// By default, the torus lies in the xz-plane with its centre-of-mass located
+/// at the origin. It has a major radius (the distance from the origin to the
+/// centre of the tube) of 0.375 and a minor radius (the radius of the tube) of
+/// 0.125, such that it fits exactly within a unit-diameter sphere. This can be
+/// overriden using the radius and transformation methods on this object.
+///
+/// The resultant geometry is constructed to suit OpenGL defaults - assuming
+/// a right-handed coordinate system, front-facing polygons are defined in
+/// counter-clock-wise order. Vertex normals point outward from the centre-line
+/// of the tube (such that the shape appears smooth when lit). Vertex texture
+/// coordinates map the angle around the major circle to the u coordinate, and
+/// the angle around the tube to the v coordinate. Vertices along the seams are
+/// duplicated such that each seam has both a 0 and a 1 texture coordinate.
+///
+/// The torus may sweep only part of a turn about the y-axis (e.g. to build a
+/// half torus), in which case it is closed by a flat disc across the tube at
+/// each of its cut planes, with normals perpendicular to the plane. Texture
+/// coordinates on the cut faces define a planar-projection of the disc.
+pub struct TorusBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    major_radius: f32,
+    minor_radius: f32,
+    major_segments: usize,
+    minor_segments: usize,
+    start_angle: f32,
+    sweep_angle: f32,
+    options: ShapeOptions,
+}
+
+impl Default for TorusBuilder {
+    fn default() -> Self {
+        TorusBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            major_radius: 0.375,
+            minor_radius: 0.125,
+            major_segments: 24,
+            minor_segments: 12,
+            start_angle: 0.0,
+            sweep_angle: 2.0 * f32::consts::PI,
+            options: ShapeOptions {
+                shading: Shading::Smooth,
+                ..ShapeOptions::default()
+            },
+        }
+    }
+}
+
+impl TorusBuilder {
+    /// Create a new `TorusBuilder` object.
+    pub fn new() -> TorusBuilder {
+        Default::default()
+    }
+
+    /// Specify the major radius of the torus (the distance from the centre of
+    /// the torus to the centre of the tube). By default, this is 0.375.
+    pub fn major_radius(mut self, radius: f32) -> Self {
+        self.major_radius = radius;
+        self
+    }
+
+    /// Specify the minor radius of the torus (the radius of the tube). By
+    /// default, this is 0.125.
+    pub fn minor_radius(mut self, radius: f32) -> Self {
+        self.minor_radius = radius;
+        self
+    }
+
+    /// Specify the number of segments around the major circle of the torus.
+    /// By default, the builder will use 24 segments. At least 3 segments are
+    /// required to build a valid torus.
+    pub fn major_segments(mut self, segments: usize) -> Self {
+        self.major_segments = segments;
+        self
+    }
+
+    /// Specify the number of segments around the tube of the torus. By default,
+    /// the builder will use 12 segments. At least 3 segments are required to
+    /// build a valid torus.
+    pub fn minor_segments(mut self, segments: usize) -> Self {
+        self.minor_segments = segments;
+        self
+    }
+
+    /// Specify the angle at which the torus starts, in radians measured
+    /// counter-clock-wise about the y-axis from the positive x-axis. By
+    /// default, the start angle is 0.
+    pub fn start_angle(mut self, radians: f32) -> Self {
+        self.start_angle = radians;
+        self
+    }
+
+    /// Specify the angle swept by the torus about the y-axis, in radians. By
+    /// default, the sweep angle is a full turn. The sweep angle must be
+    /// greater than zero, and no greater than a full turn, to build a valid
+    /// torus.
+    pub fn sweep_angle(mut self, radians: f32) -> Self {
+        self.sweep_angle = radians;
+        self
+    }
+
+    /// Build a new `Torus` object.
+    pub fn build<F>(self, display: &F) -> Result<Torus, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the shape vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// a `Torus` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        let vertices = self.vertices_with::<Selected>()?;
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.options.check_shading(Self::SHADINGS)?;
+
+        // Ensure there are enough segments to produce valid torus geometry.
+        if self.major_segments < 3 {
+            return Err(ShapeCreationError::NotEnoughDivisionsInU);
+        }
+
+        if self.minor_segments < 3 {
+            return Err(ShapeCreationError::NotEnoughDivisionsInV);
+        }
+
+        let full = self.is_full_turn();
+        if self.sweep_angle <= 0.0 || !(full || self.sweep_angle < 2.0 * f32::consts::PI) {
+            return Err(ShapeCreationError::InvalidDimensions);
+        }
+
+        // Build lookup tables of (cos, sin) pairs. The last entry of each
+        // table wraps around to the first (unless the torus sweeps only part
+        // of a turn) such that seam positions match.
+        fn cos_sin_table(segments: usize, start: f32, sweep: f32, wrap: bool) -> Vec<[f32; 2]> {
+            let angle = sweep / segments as f32;
+            (0..=segments)
+                .map(|x| {
+                    let x = if wrap { x % segments } else { x };
+                    let a = start + (x as f32) * angle;
+                    [a.cos(), a.sin()]
+                })
+                .collect()
+        }
+
+        let u_tab = cos_sin_table(
+            self.major_segments,
+            self.start_angle,
+            self.sweep_angle,
+            full,
+        );
+        let v_tab = cos_sin_table(self.minor_segments, 0.0, 2.0 * f32::consts::PI, true);
+        let point = |[cu, su]: [f32; 2], [cv, sv]: [f32; 2]| {
+            let centre = Vector3::<f32>::new(cu, 0.0, -su) * self.major_radius;
+            let normal = Vector3::<f32>::new(cv * cu, sv, -cv * su);
+            (centre, normal, centre + normal * self.minor_radius)
+        };
+
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<M>::new(self.matrix);
+
+        // Build vertex array. The major angle increases counter-clock-wise
+        // when viewed from above, and the minor angle increases upwards from
+        // the outer equator of the torus.
+        let mut vertices = Vec::<Vertex>::with_capacity(self.num_vertices());
+        let quad = [(0, 0), (1, 0), (1, 1), (0, 0), (1, 1), (0, 1)];
+
+        for u in 0..self.major_segments {
+            for v in 0..self.minor_segments {
+                for &(du, dv) in quad.iter() {
+                    let (_, normal, pos) = point(u_tab[u + du], v_tab[v + dv]);
+                    vertices.push(Vertex {
+                        position: transform.point(pos),
+                        normal: transform.normal(normal),
+                        texcoord: [
+                            (u + du) as f32 / self.major_segments as f32,
+                            (v + dv) as f32 / self.minor_segments as f32,
+                        ],
+                    });
+                }
+            }
+        }
+
+        // Emit the cut faces, unless the torus sweeps a full turn. Each is
+        // built as a fan of triangles about the centre of the tube, with the
+        // face at the end of the sweep wound in the opposite direction, such
+        // that both faces are wound counter-clock-wise when viewed from
+        // outside.
+        if !full {
+            for &(u, flip) in [(0, false), (self.major_segments, true)].iter() {
+                let [cu, su] = u_tab[u];
+                let normal = if flip {
+                    Vector3::<f32>::new(-su, 0.0, -cu)
+                } else {
+                    Vector3::<f32>::new(su, 0.0, cu)
+                };
+                for v in 0..self.minor_segments {
+                    let (v0, v1) = if flip { (v + 1, v) } else { (v, v + 1) };
+                    let (centre, _, p0) = point(u_tab[u], v_tab[v0]);
+                    let (_, _, p1) = point(u_tab[u], v_tab[v1]);
+                    let corners = [(centre, [0.0, 0.0]), (p0, v_tab[v0]), (p1, v_tab[v1])];
+                    for &(pos, [cv, sv]) in corners.iter() {
+                        vertices.push(Vertex {
+                            position: transform.point(pos),
+                            normal: transform.normal(normal),
+                            texcoord: [0.5 + cv * 0.5, 0.5 + sv * 0.5],
+                        });
+                    }
+                }
+            }
+        }
+
+        if self.options.shading == Shading::Flat {
+            flatten_normals(&mut vertices);
+        }
+
+        self.options.map_texcoords(&mut vertices);
+        Ok(vertices)
+    }
+
+    /// Returns true if the torus sweeps a full turn, such that it has no cut
+    /// faces.
+    fn is_full_turn(&self) -> bool {
+        ulps_eq!(self.sweep_angle, 2.0 * f32::consts::PI)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    pub fn num_vertices(&self) -> usize {
+        let cuts = if self.is_full_turn() {
+            0
+        } else {
+            2 * self.minor_segments * 3
+        };
+        ((self.major_segments * self.minor_segments * 6) + cuts) * self.options.copies()
+    }
+}
+
+impl HasOptions for TorusBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for TorusBuilder {
+    type Shape = Torus;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> Torus {
+        Torus { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for TorusBuilder {}
+
+impl BuildAs for TorusBuilder {}
+
+impl WithTangents for TorusBuilder {}
+
+impl WithLightmap for TorusBuilder {}
+
+impl WithOrientation for TorusBuilder {}
+
+impl WithBackFaces for TorusBuilder {}
+
+impl WithModifiers for TorusBuilder {}
+
+impl WithTexcoords for TorusBuilder {}
+
+impl WithColors for TorusBuilder {}
+
+impl WithShading for TorusBuilder {
+    /// By default, the torus is smooth shaded. When flat shaded, each vertex
+    /// normal points in the direction of its respective triangle instead.
+    const SHADINGS: &'static [Shading] = &[Shading::Flat, Shading::Smooth];
+}
+
+impl WithResolution for TorusBuilder {
+    /// At least 3 segments around the major circle, and 3 segments around the
+    /// tube.
+    const MIN_RESOLUTION: Resolution = Resolution {
+        around: 3,
+        along: 3,
+    };
+
+    /// Specify the number of segments around the major circle of the torus,
+    /// and the number of segments around its tube.
+    fn resolution(mut self, resolution: Resolution) -> Self {
+        self.major_segments = resolution.around as usize;
+        self.minor_segments = resolution.along as usize;
+        self
+    }
+}
+
+#[test]
+pub fn ensure_default_torus_has_centroid_at_origin() {
+    let vertices = TorusBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let mut sum = Vector3::<f32>::zero();
+    for vertex in &vertices {
+        sum += Vector3::<f32>::from(vertex.position);
+    }
+    assert_ulps_eq!(sum, Vector3::<f32>::zero(), epsilon = 0.0001);
+}
+
+#[test]
+pub fn ensure_torus_vertices_lie_on_tube_surface() {
+    let vertices = TorusBuilder::new()
+        .major_radius(2.0)
+        .minor_radius(0.5)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        let pos = Vector3::<f32>::from(vertex.position);
+        let centre = Vector3::<f32>::new(pos.x, 0.0, pos.z).normalize() * 2.0;
+        assert!(abs_diff_eq!(
+            (pos - centre).magnitude(),
+            0.5,
+            epsilon = 0.0001
+        ));
+    }
+}
+
+#[test]
+pub fn ensure_torus_normals_point_away_from_tube_centre() {
+    let vertices = TorusBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        let pos = Vector3::<f32>::from(vertex.position);
+        let centre = Vector3::<f32>::new(pos.x, 0.0, pos.z).normalize() * 0.375;
+        let expected = (pos - centre).normalize();
+        assert_ulps_eq!(
+            Vector3::<f32>::from(vertex.normal),
+            expected,
+            epsilon = 0.0001
+        );
+    }
+}
+
+#[test]
+pub fn ensure_default_torus_has_uvs_in_unit_range() {
+    let vertices = TorusBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        assert!(vertex.texcoord[0] >= 0.0);
+        assert!(vertex.texcoord[1] >= 0.0);
+        assert!(vertex.texcoord[0] <= 1.0);
+        assert!(vertex.texcoord[1] <= 1.0);
+    }
+}
+
+#[test]
+pub fn ensure_torus_uvs_do_not_wrap_across_seams() {
+    let vertices = TorusBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        for axis in 0..2 {
+            let coords = chunk.iter().map(|v| v.texcoord[axis]);
+            let min = coords.clone().fold(f32::MAX, f32::min);
+            let max = coords.fold(f32::MIN, f32::max);
+            assert!(max - min <= 0.5);
+        }
+    }
+}
+
+#[test]
+pub fn ensure_default_torus_has_ccw_triangles() {
+    let vertices = TorusBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let eyepos = v0 + Vector3::<f32>::from(chunk[0].normal);
+        let e0 = v1 - v0;
+        let e1 = v2 - v0;
+        let n = e0.cross(e1);
+        assert!(n.dot(v0 - eyepos) <= 0.0);
+        assert!(n.dot(v1 - eyepos) <= 0.0);
+        assert!(n.dot(v2 - eyepos) <= 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_torus_with_too_few_segments_fails_to_build() {
+    match TorusBuilder::new().major_segments(0).build_vertices() {
+        Err(ShapeCreationError::NotEnoughDivisionsInU) => (),
+        _ => panic!("Expected NotEnoughDivisionsInU"),
+    }
+    match TorusBuilder::new().minor_segments(2).build_vertices() {
+        Err(ShapeCreationError::NotEnoughDivisionsInV) => (),
+        _ => panic!("Expected NotEnoughDivisionsInV"),
+    }
+}
+
+#[test]
+pub fn ensure_torus_with_invalid_resolution_fails_to_build() {
+    crate::resolution::ensure_min_resolution(
+        TorusBuilder::new,
+        TorusBuilder::build_vertices,
+        ShapeCreationError::NotEnoughDivisionsInU,
+        Some(ShapeCreationError::NotEnoughDivisionsInV),
+    );
+}
+
+#[test]
+pub fn ensure_partial_torus_is_watertight() {
+    use std::collections::HashMap;
+    let vertices = TorusBuilder::new()
+        .major_segments(8)
+        .minor_segments(6)
+        .start_angle(0.5)
+        .sweep_angle(f32::consts::PI)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let mut edges = HashMap::<([u32; 3], [u32; 3]), usize>::new();
+    for chunk in vertices.chunks(3) {
+        for k in 0..3 {
+            let a = chunk[k].position.map(f32::to_bits);
+            let b = chunk[(k + 1) % 3].position.map(f32::to_bits);
+            *edges
+                .entry(if a < b { (a, b) } else { (b, a) })
+                .or_insert(0) += 1;
+        }
+    }
+    assert!(edges.values().all(|&count| count == 2));
+}
+
+#[test]
+pub fn ensure_partial_torus_cut_faces_have_planar_normals() {
+    let (start, sweep) = (0.25, 4.0);
+    let builder = TorusBuilder::new()
+        .major_segments(7)
+        .minor_segments(5)
+        .start_angle(start)
+        .sweep_angle(sweep);
+    let vertices = builder.build_vertices().expect("Failed to build vertices");
+    assert_eq!(vertices.len(), builder.num_vertices());
+    let cuts = &vertices[7 * 5 * 6..];
+    assert_eq!(cuts.len(), 2 * 5 * 3);
+    for (chunk, a) in cuts.chunks(5 * 3).zip([start, start + sweep].iter()) {
+        let radial = Vector3::<f32>::new(a.cos(), 0.0, -a.sin());
+        let normal = if *a == start {
+            radial.cross(Vector3::unit_y())
+        } else {
+            Vector3::unit_y().cross(radial)
+        };
+        for tri in chunk.chunks(3) {
+            let [v0, v1, v2] = [0, 1, 2].map(|k| Vector3::<f32>::from(tri[k].position));
+            assert!((v1 - v0).cross(v2 - v0).dot(normal) > 0.0);
+        }
+        for vertex in chunk {
+            let pos = Vector3::<f32>::from(vertex.position);
+            assert!(abs_diff_eq!(pos.dot(normal), 0.0, epsilon = 0.0001));
+            assert_ulps_eq!(Vector3::from(vertex.normal), normal, epsilon = 0.0001);
+        }
+    }
+}
+
+#[test]
+pub fn ensure_torus_with_invalid_sweep_fails_to_build() {
+    for &sweep in [0.0, -1.0, 7.0, f32::NAN].iter() {
+        match TorusBuilder::new().sweep_angle(sweep).build_vertices() {
+            Err(ShapeCreationError::InvalidDimensions) => (),
+            _ => panic!("Expected InvalidDimensions"),
+        }
+    }
+}
+
+#[test]
+pub fn ensure_flat_shaded_torus_has_faceted_normals() {
+    let smooth = TorusBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let vertices = TorusBuilder::new()
+        .shading(Shading::Flat)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    assert_eq!(vertices.len(), smooth.len());
+    for (chunk, smooth) in vertices.chunks(3).zip(smooth.chunks(3)) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let n = (v1 - v0).cross(v2 - v0).normalize();
+        for (vertex, original) in chunk.iter().zip(smooth.iter()) {
+            assert_eq!(vertex.position, original.position);
+            assert_eq!(vertex.texcoord, original.texcoord);
+            assert_ulps_eq!(n, Vector3::<f32>::from(vertex.normal), epsilon = 0.0001);
+        }
+    }
+}
+
+#[test]
+pub fn ensure_colored_torus_keeps_its_vertices() {
+    let expected = TorusBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let vertices = TorusBuilder::new()
+        .color(0.25, 0.5, 0.75, 1.0)
+        .build_vertices_colored()
+        .expect("Failed to build vertices");
+    assert_eq!(vertices.len(), expected.len());
+    for (vertex, expected) in vertices.iter().zip(expected.iter()) {
+        assert_eq!(vertex.position, expected.position);
+        assert_eq!(vertex.normal, expected.normal);
+        assert_eq!(vertex.texcoord, expected.texcoord);
+        assert_eq!(vertex.color, [0.25, 0.5, 0.75, 1.0]);
+    }
+}
+
+#[test]
+pub fn ensure_radial_array_of_tori_is_centred_on_its_axis() {
+    use crate::axis::Axis;
+
+    let builder = TorusBuilder::new()
+        .rotate_y(0.4)
+        .translate(0.25, -0.5, 0.0)
+        .radial_array(3, Axis::Z, 2.0);
+    let vertices = builder.build_vertices().expect("Failed to build vertices");
+    assert_eq!(vertices.len(), builder.num_vertices());
+    let mut centroid = Vector3::<f32>::zero();
+    for vertex in &vertices {
+        centroid += Vector3::<f32>::from(vertex.position);
+    }
+    centroid /= vertices.len() as f32;
+    assert_ulps_eq!(centroid.x, 0.0, epsilon = 0.0001);
+    assert_ulps_eq!(centroid.y, 0.0, epsilon = 0.0001);
+}
+
+#[test]
+pub fn ensure_torus_applies_late_pipeline() {
+    use crate::options::WithTexcoords;
+    use crate::uv_mapping::UvMapping;
+
+    crate::options::ensure_late_pipeline(
+        || {
+            TorusBuilder::new()
+                .uv_mapping(UvMapping::Box)
+                .double_sided()
+        },
+        TorusBuilder::build_vertices,
+    );
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_torus() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = TorusBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
@@ -0,0 +1,432 @@
+//! A module for constructing torus knot shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::errors::ShapeCreationError;
+use crate::facet::flatten_normals;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{
+    BuildAs, HasOptions, HasVertices, ShapeOptions, WithBackFaces, WithColors, WithLightmap,
+    WithModifiers, WithOrientation, WithShading, WithTangents, WithTexcoords, WithTransform,
+};
+use crate::resolution::{Resolution, WithResolution};
+use crate::shading::Shading;
+use crate::sweep::Sweep;
+use crate::vertex::Vertex;
+
+use std::f32;
+
+/// A polygonal `TorusKnot` object.
+///
+/// This object is constructed using a `TorusKnotBuilder` object.
+pub struct TorusKnot {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows a `TorusKnot` object to be passed as a source of vertices.
+impl<'a> From<&'a TorusKnot> for glium::vertex::VerticesSource<'a> {
+    fn from(knot: &'a TorusKnot) -> glium::vertex::VerticesSource<'a> {
+        (&knot.vertices).into()
+    }
+}
+
+/// Allows a `TorusKnot` object to be passed as a source of indices.
+impl<'a> From<&'a TorusKnot> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a TorusKnot) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::TrianglesList,
+        }
+    }
+}
+
+/// Responsible for building and returning a `TorusKnot` object.
+///
+/// The torus knot is constructed by sweeping a circular cross-section along a
+/// closed curve that winds `p` times around the y-axis and `q` times through
+/// the hole of a torus centred at the origin. The torus has the same radii as
+/// the default `Torus` (a major radius of 0.375 and a minor radius of 0.125),
+/// and by default the knot is a (2, 3) trefoil knot with a tube radius of 0.05.
+/// This can be overriden using the transformation methods on this object.
+///
+/// The cross-section is oriented along the curve using parallel-transport
+/// frames, such that the tube twists as little as possible, and meets itself
+/// seamlessly where the curve is closed.
+///
+/// The resultant geometry is constructed to suit OpenGL defaults - assuming
+/// a right-handed coordinate system, front-facing polygons are defined in
+/// counter-clock-wise order. Vertex normals point radially outward from the
+/// centre of the tube (such that the knot appears smooth when lit). Vertex
+/// texture coordinates map the angle around the tube to the u coordinate, and
+/// the proportion of the arc-length along the curve to the v coordinate.
+pub struct TorusKnotBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    p: usize,
+    q: usize,
+    tube_radius: f32,
+    path_segments: usize,
+    tube_segments: usize,
+    options: ShapeOptions,
+}
+
+impl Default for TorusKnotBuilder {
+    fn default() -> Self {
+        TorusKnotBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            p: 2,
+            q: 3,
+            tube_radius: 0.05,
+            path_segments: 128,
+            tube_segments: 12,
+            options: ShapeOptions {
+                shading: Shading::Smooth,
+                ..ShapeOptions::default()
+            },
+        }
+    }
+}
+
+impl TorusKnotBuilder {
+    /// Create a new `TorusKnotBuilder` object.
+    pub fn new() -> TorusKnotBuilder {
+        Default::default()
+    }
+
+    /// Specify the number of times the knot winds around the y-axis. By
+    /// default, the knot winds around the axis 2 times. The winding numbers
+    /// must be positive and coprime to build a valid torus knot.
+    pub fn p(mut self, p: usize) -> Self {
+        self.p = p;
+        self
+    }
+
+    /// Specify the number of times the knot winds through the hole of the
+    /// torus. By default, the knot winds through the hole 3 times. The winding
+    /// numbers must be positive and coprime to build a valid torus knot.
+    pub fn q(mut self, q: usize) -> Self {
+        self.q = q;
+        self
+    }
+
+    /// Specify the radius of the tube. By default, the radius is 0.05. The
+    /// radius must be greater than zero to build a valid torus knot.
+    pub fn tube_radius(mut self, radius: f32) -> Self {
+        self.tube_radius = radius;
+        self
+    }
+
+    /// Specify the number of segments along the length of the knot. By
+    /// default, the builder will use 128 segments. At least 3 segments are
+    /// required to build a valid torus knot.
+    pub fn path_segments(mut self, segments: usize) -> Self {
+        self.path_segments = segments;
+        self
+    }
+
+    /// Specify the number of radial segments around the tube. By default,
+    /// the builder will use 12 segments. At least 3 segments are required to
+    /// build a valid torus knot.
+    pub fn tube_segments(mut self, segments: usize) -> Self {
+        self.tube_segments = segments;
+        self
+    }
+
+    /// Build a new `TorusKnot` object.
+    pub fn build<F>(self, display: &F) -> Result<TorusKnot, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the shape vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// a `TorusKnot` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        let vertices = self.vertices_with::<Selected>()?;
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.options.check_shading(Self::SHADINGS)?;
+
+        // Ensure the winding numbers describe a single closed knot.
+        let gcd = |mut a: usize, mut b: usize| {
+            while b != 0 {
+                let r = a % b;
+                a = b;
+                b = r;
+            }
+            a
+        };
+        if self.p == 0 || self.q == 0 || gcd(self.p, self.q) != 1 {
+            return Err(ShapeCreationError::InvalidWindingNumbers);
+        }
+
+        // Ensure the tube is large enough to produce valid geometry.
+        if self.tube_radius <= 0.0 {
+            return Err(ShapeCreationError::InvalidRadii);
+        }
+        if self.path_segments < 3 {
+            return Err(ShapeCreationError::NotEnoughDivisionsInV);
+        }
+
+        let path = self.path();
+        let sweep = self.sweep(&path);
+        sweep.validate()?;
+
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<M>::new(self.matrix);
+
+        let mut vertices = Vec::<Vertex>::with_capacity(self.num_vertices());
+        sweep.push(&mut vertices, &transform);
+
+        if self.options.shading == Shading::Flat {
+            flatten_normals(&mut vertices);
+        }
+
+        self.options.map_texcoords(&mut vertices);
+        Ok(vertices)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    pub fn num_vertices(&self) -> usize {
+        self.path_segments * self.tube_segments * 6 * self.options.copies()
+    }
+
+    /// Returns the points along the curve of the knot, which lies on the
+    /// surface of a torus about the y-axis.
+    fn path(&self) -> Vec<Point3<f32>> {
+        let angle = 2.0 * f32::consts::PI / self.path_segments as f32;
+        (0..self.path_segments)
+            .map(|x| {
+                let t = x as f32 * angle;
+                let (sp, cp) = (self.p as f32 * t).sin_cos();
+                let (sq, cq) = (self.q as f32 * t).sin_cos();
+                let r = 0.375 + 0.125 * cq;
+                Point3::<f32>::new(r * cp, 0.125 * sq, -r * sp)
+            })
+            .collect()
+    }
+
+    /// Returns a description of the circular cross-section swept along the curve.
+    fn sweep<'a>(&self, path: &'a [Point3<f32>]) -> Sweep<'a> {
+        Sweep {
+            path,
+            closed: true,
+            radius: self.tube_radius,
+            segments: self.tube_segments,
+            caps: [false, false],
+        }
+    }
+}
+
+impl HasOptions for TorusKnotBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for TorusKnotBuilder {
+    type Shape = TorusKnot;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> TorusKnot {
+        TorusKnot { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for TorusKnotBuilder {}
+
+impl BuildAs for TorusKnotBuilder {}
+
+impl WithTangents for TorusKnotBuilder {}
+
+impl WithLightmap for TorusKnotBuilder {}
+
+impl WithOrientation for TorusKnotBuilder {}
+
+impl WithBackFaces for TorusKnotBuilder {}
+
+impl WithModifiers for TorusKnotBuilder {}
+
+impl WithTexcoords for TorusKnotBuilder {}
+
+impl WithColors for TorusKnotBuilder {}
+
+impl WithShading for TorusKnotBuilder {
+    /// By default, the torus knot is smooth shaded. When flat shaded, each
+    /// vertex normal points in the direction of its respective triangle
+    /// instead.
+    const SHADINGS: &'static [Shading] = &[Shading::Flat, Shading::Smooth];
+}
+
+impl WithResolution for TorusKnotBuilder {
+    /// At least 3 segments around the tube, and 3 segments along the knot.
+    const MIN_RESOLUTION: Resolution = Resolution {
+        around: 3,
+        along: 3,
+    };
+
+    /// Specify the number of segments around the tube of the torus knot, and
+    /// the number of segments along its length.
+    fn resolution(mut self, resolution: Resolution) -> Self {
+        self.tube_segments = resolution.around as usize;
+        self.path_segments = resolution.along as usize;
+        self
+    }
+}
+
+#[test]
+pub fn ensure_default_torus_knot_is_watertight() {
+    use std::collections::HashMap;
+    let vertices = TorusKnotBuilder::new()
+        .path_segments(64)
+        .tube_segments(6)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let key = |v: &Vertex| Vector3::<f32>::from(v.position).map(f32::to_bits);
+    let mut edges = HashMap::<_, usize>::new();
+    for chunk in vertices.chunks(3) {
+        for i in 0..3 {
+            let (a, b) = (key(&chunk[i]), key(&chunk[(i + 1) % 3]));
+            let edge = if (a.x, a.y, a.z) < (b.x, b.y, b.z) {
+                (a, b)
+            } else {
+                (b, a)
+            };
+            *edges.entry(edge).or_insert(0) += 1;
+        }
+    }
+    for count in edges.values() {
+        assert_eq!(*count, 2);
+    }
+}
+
+#[test]
+pub fn ensure_default_torus_knot_has_ccw_triangles() {
+    let vertices = TorusKnotBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let eyepos = v0 + Vector3::<f32>::from(chunk[0].normal);
+        let e0 = v1 - v0;
+        let e1 = v2 - v0;
+        let n = e0.cross(e1);
+        assert!(n.dot(v0 - eyepos) <= 0.0);
+        assert!(n.dot(v1 - eyepos) <= 0.0);
+        assert!(n.dot(v2 - eyepos) <= 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_torus_knot_normals_point_away_from_curve() {
+    let builder = TorusKnotBuilder::new().p(3).q(5);
+    let path = builder.path();
+    let vertices = builder.build_vertices().expect("Failed to build vertices");
+    for vertex in &vertices {
+        let pos = Point3::<f32>::from(vertex.position);
+        let normal = Vector3::<f32>::from(vertex.normal);
+        let centre = pos - normal * 0.05;
+        let nearest = path
+            .iter()
+            .map(|p| (p - centre).magnitude())
+            .fold(f32::MAX, f32::min);
+        assert!(nearest < 0.0001);
+        assert!(abs_diff_eq!(normal.magnitude(), 1.0, epsilon = 0.0001));
+    }
+}
+
+#[test]
+pub fn ensure_torus_knot_has_expected_vertex_count() {
+    let builder = TorusKnotBuilder::new().path_segments(50).tube_segments(7);
+    let vertices = builder.build_vertices().expect("Failed to build vertices");
+    assert_eq!(vertices.len(), 50 * 7 * 6);
+    assert_eq!(vertices.len(), builder.num_vertices());
+}
+
+#[test]
+pub fn ensure_torus_knot_with_invalid_winding_numbers_fails_to_build() {
+    for &(p, q) in [(2, 4), (0, 3), (3, 0), (3, 3)].iter() {
+        match TorusKnotBuilder::new().p(p).q(q).build_vertices() {
+            Err(ShapeCreationError::InvalidWindingNumbers) => (),
+            _ => panic!("Expected InvalidWindingNumbers"),
+        }
+    }
+    match TorusKnotBuilder::new().tube_radius(0.0).build_vertices() {
+        Err(ShapeCreationError::InvalidRadii) => (),
+        _ => panic!("Expected InvalidRadii"),
+    }
+    match TorusKnotBuilder::new().path_segments(2).build_vertices() {
+        Err(ShapeCreationError::NotEnoughDivisionsInV) => (),
+        _ => panic!("Expected NotEnoughDivisionsInV"),
+    }
+    match TorusKnotBuilder::new().tube_segments(2).build_vertices() {
+        Err(ShapeCreationError::NotEnoughDivisionsInU) => (),
+        _ => panic!("Expected NotEnoughDivisionsInU"),
+    }
+}
+
+#[test]
+pub fn ensure_torus_knot_applies_late_pipeline() {
+    use crate::options::WithTexcoords;
+    use crate::uv_mapping::UvMapping;
+
+    crate::options::ensure_late_pipeline(
+        || {
+            TorusKnotBuilder::new()
+                .uv_mapping(UvMapping::Box)
+                .double_sided()
+        },
+        TorusKnotBuilder::build_vertices,
+    );
+}
+
+#[test]
+pub fn ensure_torus_knot_with_invalid_resolution_fails_to_build() {
+    crate::resolution::ensure_min_resolution(
+        TorusKnotBuilder::new,
+        TorusKnotBuilder::build_vertices,
+        ShapeCreationError::NotEnoughDivisionsInU,
+        Some(ShapeCreationError::NotEnoughDivisionsInV),
+    );
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_torus_knot() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = TorusKnotBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
@@ -0,0 +1,408 @@
+//! A module for constructing truncated pyramid shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::errors::ShapeCreationError;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{
+    BuildAs, HasOptions, HasVertices, ShapeOptions, WithBackFaces, WithColors, WithLightmap,
+    WithModifiers, WithOrientation, WithShading, WithTangents, WithTexcoords, WithTransform,
+};
+use crate::shading::Shading;
+use crate::vertex::Vertex;
+
+/// A polygonal `TruncatedPyramid` object.
+///
+/// This object is constructed using a `TruncatedPyramidBuilder` object.
+pub struct TruncatedPyramid {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows a `TruncatedPyramid` object to be passed as a source of vertices.
+impl<'a> From<&'a TruncatedPyramid> for glium::vertex::VerticesSource<'a> {
+    fn from(pyramid: &'a TruncatedPyramid) -> glium::vertex::VerticesSource<'a> {
+        (&pyramid.vertices).into()
+    }
+}
+
+/// Allows a `TruncatedPyramid` object to be passed as a source of indices.
+impl<'a> From<&'a TruncatedPyramid> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a TruncatedPyramid) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::TrianglesList,
+        }
+    }
+}
+
+/// Responsible for building and returning a `TruncatedPyramid` object.
+///
+/// By default, the truncated pyramid is defined with a unit-square base, a
+/// square top with edges of length 0.5, and unit height, aligned to the y-axis
+/// with its vertical centre located at the origin. The top and bottom faces
+/// are rectangles whose edges are aligned to the x and z axes. This can be
+/// overriden using the transformation methods on this object.
+///
+/// When the top and bottom extents are equal, the geometry is identical to that
+/// produced by a correspondingly scaled `CuboidBuilder`.
+///
+/// The resultant geometry is constructed to suit OpenGL defaults - assuming
+/// a right-handed coordinate system, front-facing polygons are defined in
+/// counter-clock-wise order. Vertex normals point in the direction of their
+/// respective face (such that the shape appears faceted when lit). Vertex
+/// texture coordinates define a planar-projection on each face, stretched to
+/// fit the unit square.
+pub struct TruncatedPyramidBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    bottom_extents: [f32; 2],
+    top_extents: [f32; 2],
+    height: f32,
+    options: ShapeOptions,
+}
+
+impl Default for TruncatedPyramidBuilder {
+    fn default() -> Self {
+        TruncatedPyramidBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            bottom_extents: [1.0, 1.0],
+            top_extents: [0.5, 0.5],
+            height: 1.0,
+            options: ShapeOptions::default(),
+        }
+    }
+}
+
+impl TruncatedPyramidBuilder {
+    /// Create a new `TruncatedPyramidBuilder` object.
+    pub fn new() -> TruncatedPyramidBuilder {
+        Default::default()
+    }
+
+    /// Specify the lengths of the edges of the bottom face along the x and z
+    /// axes. By default, the bottom face is a unit square. Both extents must
+    /// be greater than zero.
+    pub fn bottom_extents(mut self, x: f32, z: f32) -> Self {
+        self.bottom_extents = [x, z];
+        self
+    }
+
+    /// Specify the lengths of the edges of the top face along the x and z
+    /// axes. By default, the top face is a square with edges of length 0.5.
+    /// Both extents must be greater than zero.
+    pub fn top_extents(mut self, x: f32, z: f32) -> Self {
+        self.top_extents = [x, z];
+        self
+    }
+
+    /// Specify the distance between the bottom and top faces. By default, the
+    /// height is 1. The height must be greater than zero.
+    pub fn height(mut self, height: f32) -> Self {
+        self.height = height;
+        self
+    }
+
+    /// Build a new `TruncatedPyramid` object.
+    pub fn build<F>(self, display: &F) -> Result<TruncatedPyramid, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the shape vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// a `TruncatedPyramid` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        let vertices = self.vertices_with::<Selected>()?;
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.options.check_shading(Self::SHADINGS)?;
+
+        // Ensure the dimensions describe a shape with a non-zero volume.
+        let extents = [self.bottom_extents, self.top_extents];
+        if self.height <= 0.0 || extents.iter().flatten().any(|&e| e <= 0.0) {
+            return Err(ShapeCreationError::InvalidDimensions);
+        }
+
+        // Define lookup-tables used during construction of the geometry. These
+        // match those of the cuboid, where bit 0 of each corner index selects
+        // the top or bottom face, bit 1 the x sign, and bit 2 the z sign.
+        let index_lut = [
+            0, 4, 1, 5, // -X
+            6, 2, 7, 3, // +X
+            0, 2, 4, 6, // -Y
+            5, 7, 1, 3, // +Y
+            2, 0, 3, 1, // -Z
+            4, 6, 5, 7, // +Z
+        ];
+        let poly_lut = [0, 1, 2, 2, 1, 3];
+        let num_sides = 6;
+        let verts_per_side = 6;
+        let corner = |coord: usize| {
+            let [x, z] = extents[coord & 1];
+            Vector3::<f32>::new(
+                (((coord & 2) as f32) - 1.0) * x * 0.5,
+                ((((coord & 1) * 2) as f32) - 1.0) * self.height * 0.5,
+                ((((coord >> 1) & 2) as f32) - 1.0) * z * 0.5,
+            )
+        };
+
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<M>::new(self.matrix);
+
+        // Generate the vertices.
+        let mut vertices = Vec::<Vertex>::with_capacity(verts_per_side * num_sides);
+
+        for side in 0..num_sides {
+            let quad = [0, 1, 2, 3].map(|k| corner(index_lut[k + (side * 4)]));
+
+            // Compute side normal using Newell's method, with the quad corners
+            // visited counter-clock-wise.
+            let mut normal = Vector3::<f32>::zero();
+            for &(a, b) in [(0, 1), (1, 3), (3, 2), (2, 0)].iter() {
+                let (a, b) = (quad[a], quad[b]);
+                normal.x += (a.y - b.y) * (a.z + b.z);
+                normal.y += (a.z - b.z) * (a.x + b.x);
+                normal.z += (a.x - b.x) * (a.y + b.y);
+            }
+            let normal = normal.normalize();
+
+            // Project the corners onto the side, and stretch them to fit the
+            // unit square. The u axis runs along the first edge of the side.
+            let u_axis = (quad[1] - quad[0]).normalize();
+            let v_axis = normal.cross(u_axis);
+            let projected = quad.map(|p| Vector2::<f32>::new(p.dot(u_axis), p.dot(v_axis)));
+            let mut min = projected[0];
+            let mut max = projected[0];
+            for p in &projected {
+                min.x = min.x.min(p.x);
+                min.y = min.y.min(p.y);
+                max.x = max.x.max(p.x);
+                max.y = max.y.max(p.y);
+            }
+            let extent = max - min;
+
+            // Build side vertices.
+            for &k in poly_lut.iter() {
+                let uv = projected[k] - min;
+                vertices.push(Vertex {
+                    position: transform.point(quad[k]),
+                    normal: transform.normal(normal),
+                    texcoord: [uv.x / extent.x, uv.y / extent.y],
+                });
+            }
+        }
+
+        self.options.map_texcoords(&mut vertices);
+        Ok(vertices)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    pub fn num_vertices(&self) -> usize {
+        6 * 6 * self.options.copies()
+    }
+}
+
+impl HasOptions for TruncatedPyramidBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for TruncatedPyramidBuilder {
+    type Shape = TruncatedPyramid;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> TruncatedPyramid {
+        TruncatedPyramid { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for TruncatedPyramidBuilder {}
+
+impl BuildAs for TruncatedPyramidBuilder {}
+
+impl WithTangents for TruncatedPyramidBuilder {}
+
+impl WithLightmap for TruncatedPyramidBuilder {}
+
+impl WithOrientation for TruncatedPyramidBuilder {}
+
+impl WithBackFaces for TruncatedPyramidBuilder {}
+
+impl WithModifiers for TruncatedPyramidBuilder {}
+
+impl WithTexcoords for TruncatedPyramidBuilder {}
+
+impl WithColors for TruncatedPyramidBuilder {}
+
+impl WithShading for TruncatedPyramidBuilder {
+    /// Only flat shading is supported by the truncated pyramid.
+    const SHADINGS: &'static [Shading] = &[Shading::Flat];
+}
+
+#[test]
+pub fn ensure_default_truncated_pyramid_has_correct_dimensions() {
+    let vertices = TruncatedPyramidBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        let pos = Vector3::<f32>::from(vertex.position);
+        let half_extent = if pos.y > 0.0 { 0.25 } else { 0.5 };
+        assert!(abs_diff_eq!(pos.x.abs(), half_extent));
+        assert!(abs_diff_eq!(pos.y.abs(), 0.5));
+        assert!(abs_diff_eq!(pos.z.abs(), half_extent));
+    }
+}
+
+#[test]
+pub fn ensure_truncated_pyramid_with_equal_extents_matches_cuboid() {
+    use crate::cuboid::CuboidBuilder;
+    let pyramid = TruncatedPyramidBuilder::new()
+        .bottom_extents(1.0, 1.0)
+        .top_extents(1.0, 1.0)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let cuboid = CuboidBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    assert_eq!(pyramid.len(), cuboid.len());
+    for (a, b) in pyramid.iter().zip(cuboid.iter()) {
+        assert_eq!(a.position, b.position);
+        assert_eq!(a.normal, b.normal);
+        assert_eq!(a.texcoord, b.texcoord);
+    }
+}
+
+#[test]
+pub fn ensure_default_truncated_pyramid_has_outward_facing_normals() {
+    let vertices = TruncatedPyramidBuilder::new()
+        .bottom_extents(2.0, 1.0)
+        .top_extents(0.5, 1.5)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let centroid = (Vector3::<f32>::from(chunk[0].position)
+            + Vector3::<f32>::from(chunk[1].position)
+            + Vector3::<f32>::from(chunk[2].position))
+            / 3.0;
+        let normal = Vector3::<f32>::from(chunk[0].normal);
+        assert!(normal.dot(centroid) > 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_default_truncated_pyramid_has_uvs_in_unit_range() {
+    let vertices = TruncatedPyramidBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        assert!(vertex.texcoord[0] >= -0.0001 && vertex.texcoord[0] <= 1.0001);
+        assert!(vertex.texcoord[1] >= -0.0001 && vertex.texcoord[1] <= 1.0001);
+    }
+}
+
+#[test]
+pub fn ensure_default_truncated_pyramid_has_ccw_triangles() {
+    let vertices = TruncatedPyramidBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let eyepos = v0 + Vector3::<f32>::from(chunk[0].normal);
+        let e0 = v1 - v0;
+        let e1 = v2 - v0;
+        let n = e0.cross(e1);
+        assert!(n.dot(v0 - eyepos) <= 0.0);
+        assert!(n.dot(v1 - eyepos) <= 0.0);
+        assert!(n.dot(v2 - eyepos) <= 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_default_truncated_pyramid_has_faceted_normals() {
+    let vertices = TruncatedPyramidBuilder::new()
+        .bottom_extents(2.0, 1.0)
+        .top_extents(0.5, 1.5)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let n = (v1 - v0).cross(v2 - v0).normalize();
+        for vertex in chunk {
+            assert_ulps_eq!(n, Vector3::<f32>::from(vertex.normal), epsilon = 0.0001);
+        }
+    }
+}
+
+#[test]
+pub fn ensure_truncated_pyramid_with_invalid_dimensions_fails_to_build() {
+    let builders = [
+        TruncatedPyramidBuilder::new().height(0.0),
+        TruncatedPyramidBuilder::new().bottom_extents(-1.0, 1.0),
+        TruncatedPyramidBuilder::new().top_extents(0.5, 0.0),
+    ];
+    for builder in builders.iter() {
+        match builder.build_vertices() {
+            Err(ShapeCreationError::InvalidDimensions) => (),
+            _ => panic!("Expected InvalidDimensions"),
+        }
+    }
+}
+
+#[test]
+pub fn ensure_truncated_pyramid_applies_late_pipeline() {
+    use crate::options::WithTexcoords;
+    use crate::uv_mapping::UvMapping;
+
+    crate::options::ensure_late_pipeline(
+        || {
+            TruncatedPyramidBuilder::new()
+                .uv_mapping(UvMapping::Box)
+                .double_sided()
+        },
+        TruncatedPyramidBuilder::build_vertices,
+    );
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_truncated_pyramid() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = TruncatedPyramidBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
@@ -0,0 +1,460 @@
+//! A module for constructing tube shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::errors::ShapeCreationError;
+use crate::facet::flatten_normals;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{
+    BuildAs, HasOptions, HasVertices, ShapeOptions, WithBackFaces, WithCaps, WithColors,
+    WithLightmap, WithModifiers, WithOrientation, WithShading, WithTangents, WithTexcoords,
+    WithTransform,
+};
+use crate::resolution::{Resolution, WithResolution};
+use crate::shading::Shading;
+use crate::sweep::Sweep;
+use crate::vertex::Vertex;
+
+/// A polygonal `Tube` object.
+///
+/// This object is constructed using a `TubeBuilder` object.
+pub struct Tube {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows a `Tube` object to be passed as a source of vertices.
+impl<'a> From<&'a Tube> for glium::vertex::VerticesSource<'a> {
+    fn from(tube: &'a Tube) -> glium::vertex::VerticesSource<'a> {
+        (&tube.vertices).into()
+    }
+}
+
+/// Allows a `Tube` object to be passed as a source of indices.
+impl<'a> From<&'a Tube> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a Tube) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::TrianglesList,
+        }
+    }
+}
+
+/// Responsible for building and returning a `Tube` object.
+///
+/// The tube is constructed by sweeping a circular cross-section along a path
+/// of points. By default, the path is a single straight line of unit length
+/// from `(0, -0.5, 0)` to `(0, 0.5, 0)`, and the cross-section has a radius of
+/// 0.5, such that the default tube matches a unit cylinder. This can be
+/// overriden using the transformation methods on this object.
+///
+/// The cross-section is oriented at each point along the path using
+/// parallel-transport frames, such that the tube does not twist around
+/// corners. At each interior point, the cross-section is perpendicular to the
+/// average direction of its adjoining path segments.
+///
+/// The resultant geometry is constructed to suit OpenGL defaults - assuming
+/// a right-handed coordinate system, front-facing polygons are defined in
+/// counter-clock-wise order. Vertex normals on the side of the tube point
+/// radially outward (such that the side appears smooth when lit), whereas the
+/// normals of each cap point along the path (such that the caps appear faceted
+/// against the side). Vertex texture coordinates map the angle around the
+/// tube to the u coordinate, and the proportion of the arc-length along the
+/// path to the v coordinate. Caps define a planar-projection.
+pub struct TubeBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    path: Vec<Point3<f32>>,
+    radius: f32,
+    segments: usize,
+    options: ShapeOptions,
+}
+
+impl Default for TubeBuilder {
+    fn default() -> Self {
+        TubeBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            path: vec![
+                Point3::<f32>::new(0.0, -0.5, 0.0),
+                Point3::<f32>::new(0.0, 0.5, 0.0),
+            ],
+            radius: 0.5,
+            segments: 24,
+            options: ShapeOptions {
+                shading: Shading::Smooth,
+                ..ShapeOptions::default()
+            },
+        }
+    }
+}
+
+impl TubeBuilder {
+    /// Create a new `TubeBuilder` object.
+    pub fn new() -> TubeBuilder {
+        Default::default()
+    }
+
+    /// Specify the path of points along which the tube is swept. At least two
+    /// points are required to build a valid tube, and consecutive points must
+    /// not be coincident.
+    pub fn path(mut self, points: &[impl Into<Point3<f32>> + Copy]) -> Self {
+        self.path = points.iter().map(|&point| point.into()).collect();
+        self
+    }
+
+    /// Specify the radius of the cross-section of the tube. By default, the
+    /// radius is 0.5.
+    pub fn radius(mut self, radius: f32) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    /// Specify the number of radial segments around the tube. By default,
+    /// the builder will use 24 segments. At least 3 segments are required to
+    /// build a valid tube.
+    pub fn segments(mut self, segments: usize) -> Self {
+        self.segments = segments;
+        self
+    }
+
+    /// Build a new `Tube` object.
+    pub fn build<F>(self, display: &F) -> Result<Tube, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the shape vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// a `Tube` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        let vertices = self.vertices_with::<Selected>()?;
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.options.check_shading(Self::SHADINGS)?;
+
+        let sweep = self.sweep();
+        sweep.validate()?;
+
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<M>::new(self.matrix);
+
+        let mut vertices = Vec::<Vertex>::with_capacity(self.num_vertices());
+        sweep.push(&mut vertices, &transform);
+
+        if self.options.shading == Shading::Flat {
+            flatten_normals(&mut vertices);
+        }
+
+        self.options.map_texcoords(&mut vertices);
+        Ok(vertices)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    pub fn num_vertices(&self) -> usize {
+        self.sweep().num_vertices() * self.options.copies()
+    }
+
+    /// Returns a description of the circular cross-section swept along the path.
+    fn sweep(&self) -> Sweep<'_> {
+        Sweep {
+            path: &self.path,
+            closed: false,
+            radius: self.radius,
+            segments: self.segments,
+            caps: [self.options.caps.caps_start(), self.options.caps.caps_end()],
+        }
+    }
+}
+
+impl HasOptions for TubeBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for TubeBuilder {
+    type Shape = Tube;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> Tube {
+        Tube { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for TubeBuilder {}
+
+impl BuildAs for TubeBuilder {}
+
+impl WithTangents for TubeBuilder {}
+
+impl WithLightmap for TubeBuilder {}
+
+impl WithOrientation for TubeBuilder {}
+
+impl WithBackFaces for TubeBuilder {}
+
+impl WithModifiers for TubeBuilder {}
+
+/// The start of the tube is at the first point of its path, and its end is at
+/// the last point of its path.
+impl WithCaps for TubeBuilder {}
+
+impl WithTexcoords for TubeBuilder {}
+
+impl WithColors for TubeBuilder {}
+
+impl WithShading for TubeBuilder {
+    /// By default, the tube is smooth shaded. When flat shaded, each vertex
+    /// normal points in the direction of its respective triangle instead.
+    const SHADINGS: &'static [Shading] = &[Shading::Flat, Shading::Smooth];
+}
+
+impl WithResolution for TubeBuilder {
+    /// At least 3 segments around the tube. The tube has no segments along its
+    /// path, which runs through its points, such that any `along` count is
+    /// accepted.
+    const MIN_RESOLUTION: Resolution = Resolution {
+        around: 3,
+        along: 0,
+    };
+
+    /// Specify the number of segments around the tube. The `along` count is
+    /// ignored.
+    fn resolution(mut self, resolution: Resolution) -> Self {
+        self.segments = resolution.around as usize;
+        self
+    }
+}
+
+#[test]
+pub fn ensure_default_tube_matches_cylinder_sides() {
+    use crate::cylinder::CylinderBuilder;
+    let tube = TubeBuilder::new()
+        .segments(7)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let cylinder = CylinderBuilder::new()
+        .segments(7)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    assert_eq!(tube.len(), cylinder.len());
+    for (a, b) in tube.iter().zip(cylinder.iter()).take(7 * 6) {
+        assert_ulps_eq!(
+            Vector3::<f32>::from(a.position),
+            Vector3::<f32>::from(b.position),
+            epsilon = 0.0001
+        );
+        assert_ulps_eq!(
+            Vector3::<f32>::from(a.normal),
+            Vector3::<f32>::from(b.normal),
+            epsilon = 0.0001
+        );
+        assert_ulps_eq!(
+            Vector2::<f32>::from(a.texcoord),
+            Vector2::<f32>::from(b.texcoord),
+            epsilon = 0.0001
+        );
+    }
+}
+
+#[test]
+pub fn ensure_tube_does_not_twist_around_planar_corners() {
+    use crate::cap_mode::CapMode;
+    let vertices = TubeBuilder::new()
+        .path(&[
+            Point3::<f32>::new(0.0, 0.0, 0.0),
+            Point3::<f32>::new(0.0, 1.0, 0.0),
+            Point3::<f32>::new(1.0, 1.0, 0.0),
+            Point3::<f32>::new(1.0, 2.0, 0.0),
+            Point3::<f32>::new(2.0, 2.0, 0.0),
+        ])
+        .radius(0.1)
+        .segments(8)
+        .caps(CapMode::Neither)
+        .build_vertices()
+        .expect("Failed to build vertices");
+
+    // The first corner of the first segment around each section of the tube
+    // lies at an angle of zero, so its normal must remain in the plane of the
+    // path.
+    for vertex in vertices.chunks(6 * 8).map(|chunk| &chunk[0]) {
+        assert!(abs_diff_eq!(vertex.normal[2], 0.0, epsilon = 0.0001));
+    }
+}
+
+#[test]
+pub fn ensure_tube_has_ccw_triangles() {
+    let vertices = TubeBuilder::new()
+        .path(&[
+            Point3::<f32>::new(0.0, 0.0, 0.0),
+            Point3::<f32>::new(0.0, 1.0, 0.0),
+            Point3::<f32>::new(1.0, 1.5, 0.5),
+            Point3::<f32>::new(1.0, 2.0, 2.0),
+        ])
+        .radius(0.1)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let eyepos = v0 + Vector3::<f32>::from(chunk[0].normal);
+        let e0 = v1 - v0;
+        let e1 = v2 - v0;
+        let n = e0.cross(e1);
+        assert!(n.dot(v0 - eyepos) <= 0.0);
+        assert!(n.dot(v1 - eyepos) <= 0.0);
+        assert!(n.dot(v2 - eyepos) <= 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_tube_v_coordinate_follows_arc_length() {
+    use crate::cap_mode::CapMode;
+    let builder = TubeBuilder::new()
+        .path(&[
+            Point3::<f32>::new(0.0, 0.0, 0.0),
+            Point3::<f32>::new(0.0, 1.0, 0.0),
+            Point3::<f32>::new(3.0, 1.0, 0.0),
+        ])
+        .caps(CapMode::Neither)
+        .segments(4);
+    let vertices = builder.build_vertices().expect("Failed to build vertices");
+    for vertex in &vertices {
+        let expected = if vertex.position[1] < 0.5 {
+            0.0
+        } else if vertex.position[0] < 1.5 {
+            0.25
+        } else {
+            1.0
+        };
+        assert!(abs_diff_eq!(vertex.texcoord[1], expected, epsilon = 0.0001));
+        assert!(vertex.texcoord[0] >= 0.0 && vertex.texcoord[0] <= 1.0);
+    }
+}
+
+#[test]
+pub fn ensure_tube_with_caps_disabled_has_no_caps() {
+    use crate::cap_mode::CapMode;
+    let builder = TubeBuilder::new().segments(5).caps(CapMode::Neither);
+    let vertices = builder.build_vertices().expect("Failed to build vertices");
+    assert_eq!(vertices.len(), 5 * 6);
+    assert_eq!(vertices.len(), builder.num_vertices());
+}
+
+#[test]
+pub fn ensure_tube_with_invalid_path_fails_to_build() {
+    match TubeBuilder::new()
+        .path(&[Point3::<f32>::new(0.0, 0.0, 0.0)])
+        .build_vertices()
+    {
+        Err(ShapeCreationError::NotEnoughPoints) => (),
+        _ => panic!("Expected NotEnoughPoints"),
+    }
+    match TubeBuilder::new()
+        .path(&[
+            Point3::<f32>::new(0.0, 0.0, 0.0),
+            Point3::<f32>::new(1.0, 0.0, 0.0),
+            Point3::<f32>::new(1.0, 0.0, 0.0),
+        ])
+        .build_vertices()
+    {
+        Err(ShapeCreationError::DuplicatePoints) => (),
+        _ => panic!("Expected DuplicatePoints"),
+    }
+    match TubeBuilder::new().segments(2).build_vertices() {
+        Err(ShapeCreationError::NotEnoughDivisionsInU) => (),
+        _ => panic!("Expected NotEnoughDivisionsInU"),
+    }
+}
+
+#[test]
+pub fn ensure_tube_caps_can_be_disabled_separately() {
+    use crate::cap_mode::CapMode;
+    let builder = TubeBuilder::new().segments(5);
+    assert_eq!(builder.num_vertices(), 5 * 6 + 5 * 3 * 2);
+    for builder in [
+        builder.caps(CapMode::End),
+        TubeBuilder::new().segments(5).caps(CapMode::Start),
+    ]
+    .iter()
+    {
+        assert_eq!(builder.num_vertices(), 5 * 6 + 5 * 3);
+        let vertices = builder.build_vertices().expect("Failed to build vertices");
+        assert_eq!(vertices.len(), builder.num_vertices());
+
+        // The remaining cap lies at the enabled end of the tube.
+        let y = if builder.options.caps.caps_start() {
+            -0.5
+        } else {
+            0.5
+        };
+        for vertex in &vertices[5 * 6..] {
+            assert!(abs_diff_eq!(vertex.position[1], y, epsilon = 0.0001));
+            assert_ulps_eq!(
+                Vector3::<f32>::from(vertex.normal),
+                Vector3::<f32>::new(0.0, y * 2.0, 0.0),
+                epsilon = 0.0001
+            );
+        }
+    }
+}
+
+#[test]
+pub fn ensure_tube_applies_late_pipeline() {
+    use crate::options::WithTexcoords;
+    use crate::uv_mapping::UvMapping;
+
+    crate::options::ensure_late_pipeline(
+        || TubeBuilder::new().uv_mapping(UvMapping::Box).double_sided(),
+        TubeBuilder::build_vertices,
+    );
+}
+
+#[test]
+pub fn ensure_tube_with_invalid_resolution_fails_to_build() {
+    crate::resolution::ensure_min_resolution(
+        TubeBuilder::new,
+        TubeBuilder::build_vertices,
+        ShapeCreationError::NotEnoughDivisionsInU,
+        None,
+    );
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_tube() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = TubeBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
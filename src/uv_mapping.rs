@@ -0,0 +1,225 @@
+//! A module containing the texture coordinate projections shared by all shapes.
+
+extern crate cgmath;
+
+use self::cgmath::*;
+use crate::axis::Axis;
+use crate::vertex::Vertex;
+
+use std::f32;
+
+/// A projection from which the texture coordinates of a shape are computed,
+/// replacing the native texture coordinates of the shape.
+///
+/// The projection is applied to the transformed positions of the vertices,
+/// and is fitted to the bounding box of the shape. Planar projections map the
+/// u coordinate to the right and the v coordinate upwards, as viewed from the
+/// positive end of the projected axis: the u coordinate increases along the
+/// negative z-axis for `Axis::X`, and along the positive x-axis otherwise, and
+/// the v coordinate increases along the negative z-axis for `Axis::Y`, and
+/// along the positive y-axis otherwise. Each face of a box projection is
+/// mapped in the same way, as viewed from outside the face.
+///
+/// The spherical and cylindrical projections map the angle about their axis
+/// to the u coordinate, increasing counter-clock-wise when viewed from the
+/// positive end of the axis. The seam lies on the positive z-axis about the
+/// y-axis, on the positive y-axis about the x-axis, and on the positive x-axis
+/// about the z-axis. Triangles that cross the seam take a u coordinate greater
+/// than 1 on the far side of the seam (effectively duplicating the seam), such
+/// that textures sampled with a repeating wrap mode do not smear across the
+/// seam. Vertices that lie on the axis take the average u coordinate of the
+/// other vertices of their triangle.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum UvMapping {
+    /// Project the shape onto a plane perpendicular to the given axis.
+    Planar { axis: Axis },
+    /// Project the shape onto a sphere about the centre of its bounding box,
+    /// with its poles on the y-axis. The v coordinate increases with latitude,
+    /// from 0 at the south pole to 1 at the north pole.
+    Spherical,
+    /// Project the shape onto a cylinder along the given axis, through the
+    /// centre of its bounding box. The v coordinate increases along the axis,
+    /// from 0 at the bottom of the bounding box to 1 at its top.
+    Cylindrical { axis: Axis },
+    /// Project each triangle onto the face of the bounding box that it faces
+    /// most directly, as determined by its face normal.
+    Box,
+}
+
+impl UvMapping {
+    /// Replace the texture coordinates of a list of triangles with those of
+    /// the projection.
+    pub(crate) fn apply(self, vertices: &mut [Vertex]) {
+        let positions = vertices
+            .iter()
+            .map(|vertex| Vector3::<f32>::from(vertex.position))
+            .collect::<Vec<Vector3<f32>>>();
+        for (vertex, texcoord) in vertices.iter_mut().zip(self.texcoords(&positions)) {
+            vertex.texcoord = texcoord;
+        }
+    }
+
+    /// Compute the texture coordinates of the projection for the positions of
+    /// a list of triangles.
+    pub(crate) fn texcoords(self, positions: &[Vector3<f32>]) -> Vec<[f32; 2]> {
+        if positions.is_empty() {
+            return Vec::new();
+        }
+
+        // Find the bounding box of the shape, and a function that fits a
+        // coordinate along one of its axes to the unit range.
+        let mut min = positions[0];
+        let mut max = positions[0];
+        for p in positions {
+            for axis in 0..3 {
+                min[axis] = min[axis].min(p[axis]);
+                max[axis] = max[axis].max(p[axis]);
+            }
+        }
+        let fit = |p: Vector3<f32>, axis: usize| {
+            let extent = max[axis] - min[axis];
+            if extent > 0.0 {
+                (p[axis] - min[axis]) / extent
+            } else {
+                0.0
+            }
+        };
+        let centre = (min + max) * 0.5;
+        let size = (max - min).magnitude();
+
+        // Project a position onto a plane, as viewed from the given end of the
+        // given axis.
+        let planar = |p: Vector3<f32>, axis: usize, positive: bool| match (axis, positive) {
+            (0, true) => [1.0 - fit(p, 2), fit(p, 1)],
+            (0, false) => [fit(p, 2), fit(p, 1)],
+            (1, true) => [fit(p, 0), 1.0 - fit(p, 2)],
+            (1, false) => [fit(p, 0), fit(p, 2)],
+            (2, true) => [fit(p, 0), fit(p, 1)],
+            _ => [1.0 - fit(p, 0), fit(p, 1)],
+        };
+
+        let mut texcoords = Vec::with_capacity(positions.len());
+        for triangle in positions.chunks(3) {
+            match self {
+                UvMapping::Planar { axis } => {
+                    let axis = axis.index();
+                    texcoords.extend(triangle.iter().map(|&p| planar(p, axis, true)));
+                }
+                UvMapping::Box => {
+                    let normal = (triangle[1] - triangle[0]).cross(triangle[2] - triangle[0]);
+                    let axis = (0..3)
+                        .max_by(|&a, &b| normal[a].abs().partial_cmp(&normal[b].abs()).unwrap())
+                        .unwrap_or(1);
+                    let positive = normal[axis] >= 0.0;
+                    texcoords.extend(triangle.iter().map(|&p| planar(p, axis, positive)));
+                }
+                UvMapping::Spherical => {
+                    let around = triangle
+                        .iter()
+                        .map(|&p| angle(p - centre, 1, size))
+                        .collect::<Vec<Option<f32>>>();
+                    let us = wrap(&around);
+                    texcoords.extend(triangle.iter().zip(us).map(|(&p, u)| {
+                        let d = p - centre;
+                        let length = d.magnitude();
+                        let latitude = if length > 0.0 {
+                            (d.y / length).clamp(-1.0, 1.0).asin()
+                        } else {
+                            0.0
+                        };
+                        [u, 0.5 + latitude / f32::consts::PI]
+                    }));
+                }
+                UvMapping::Cylindrical { axis } => {
+                    let axis = axis.index();
+                    let around = triangle
+                        .iter()
+                        .map(|&p| angle(p - centre, axis, size))
+                        .collect::<Vec<Option<f32>>>();
+                    let us = wrap(&around);
+                    texcoords.extend(triangle.iter().zip(us).map(|(&p, u)| [u, fit(p, axis)]));
+                }
+            }
+        }
+        texcoords
+    }
+}
+
+/// Apply a texture coordinate transformation, in homogeneous coordinates, to
+/// the texture coordinates of a list of vertices.
+pub(crate) fn transform_texcoords(matrix: &Matrix3<f32>, vertices: &mut [Vertex]) {
+    for vertex in vertices.iter_mut() {
+        vertex.texcoord = transform_texcoord(matrix, vertex.texcoord);
+    }
+}
+
+/// Apply a texture coordinate transformation, in homogeneous coordinates, to
+/// a single texture coordinate. The identity transformation leaves the
+/// texture coordinate untouched.
+pub(crate) fn transform_texcoord(matrix: &Matrix3<f32>, texcoord: [f32; 2]) -> [f32; 2] {
+    if *matrix == Matrix3::<f32>::identity() {
+        return texcoord;
+    }
+    let t = matrix * Vector3::<f32>::new(texcoord[0], texcoord[1], 1.0);
+    [t.x, t.y]
+}
+
+/// Returns the proportion of a turn about the given axis of a direction from
+/// the axis, counted from the seam, or `None` if the direction lies on the
+/// axis (relative to the given size of the shape).
+fn angle(d: Vector3<f32>, axis: usize, size: f32) -> Option<f32> {
+    // The two axes perpendicular to the axis, in right-handed order.
+    let (a, b) = ((axis + 1) % 3, (axis + 2) % 3);
+    if Vector2::<f32>::new(d[a], d[b]).magnitude() <= size * 1.0e-6 {
+        None
+    } else {
+        let turn = d[b].atan2(d[a]) / (2.0 * f32::consts::PI);
+        Some(if turn < 0.0 { turn + 1.0 } else { turn })
+    }
+}
+
+/// Returns the u coordinates of a triangle from the proportions of a turn of
+/// its vertices, pushing the vertices that lie before the seam onto its far
+/// side when the triangle crosses the seam, and giving vertices on the axis
+/// the average u coordinate of the others.
+fn wrap(around: &[Option<f32>]) -> Vec<f32> {
+    let known = around.iter().flatten().cloned().collect::<Vec<f32>>();
+    let lo = known.iter().cloned().fold(f32::INFINITY, f32::min);
+    let hi = known.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+    let unwrap = |u: f32| if hi - lo > 0.5 && u < 0.5 { u + 1.0 } else { u };
+    let average = if known.is_empty() {
+        0.5
+    } else {
+        known.iter().map(|&u| unwrap(u)).sum::<f32>() / known.len() as f32
+    };
+    around.iter().map(|u| u.map_or(average, unwrap)).collect()
+}
+
+#[test]
+pub fn ensure_planar_mapping_fits_bounding_box() {
+    let positions = [
+        Vector3::<f32>::new(-1.0, 0.0, 3.0),
+        Vector3::<f32>::new(1.0, 0.0, 3.0),
+        Vector3::<f32>::new(1.0, 2.0, 3.0),
+    ];
+    let texcoords = UvMapping::Planar { axis: Axis::Z }.texcoords(&positions);
+    assert_eq!(texcoords, vec![[0.0, 0.0], [1.0, 0.0], [1.0, 1.0]]);
+}
+
+#[test]
+pub fn ensure_cylindrical_mapping_duplicates_seam() {
+    // A triangle just either side of the seam on the positive z-axis.
+    let positions = [
+        Vector3::<f32>::new(-0.1, 0.0, 1.0),
+        Vector3::<f32>::new(0.1, 0.0, 1.0),
+        Vector3::<f32>::new(0.0, 1.0, 0.0),
+        Vector3::<f32>::new(0.0, -1.0, -1.0),
+        Vector3::<f32>::new(1.0, 0.0, 0.0),
+        Vector3::<f32>::new(-1.0, 0.0, 0.0),
+    ];
+    let texcoords = UvMapping::Cylindrical { axis: Axis::Y }.texcoords(&positions);
+    let us = texcoords.iter().map(|t| t[0]).collect::<Vec<f32>>();
+    assert!(us[0] > 0.9 && us[0] < 1.0);
+    assert!(us[1] > 1.0 && us[1] < 1.1);
+    assert_ulps_eq!(us[2], (us[0] + us[1]) * 0.5);
+}
@@ -1,4 +1,4 @@
-//! A module containing the vertex structure shared across all shapes.
+//! A module containing the vertex structures shared across all shapes.
 
 extern crate glium;
 
@@ -11,3 +11,221 @@ pub struct Vertex {
 }
 
 implement_vertex!(Vertex, position, normal, texcoord);
+
+#[cfg(feature = "mint")]
+impl From<(mint::Point3<f32>, mint::Vector3<f32>, mint::Vector2<f32>)> for Vertex {
+    /// Create a vertex from its position, normal, and texture coordinate.
+    fn from(
+        (position, normal, texcoord): (mint::Point3<f32>, mint::Vector3<f32>, mint::Vector2<f32>),
+    ) -> Self {
+        Vertex {
+            position: position.into(),
+            normal: normal.into(),
+            texcoord: texcoord.into(),
+        }
+    }
+}
+
+#[cfg(feature = "mint")]
+impl From<Vertex> for (mint::Point3<f32>, mint::Vector3<f32>, mint::Vector2<f32>) {
+    /// Returns the position, normal, and texture coordinate of a vertex.
+    fn from(vertex: Vertex) -> Self {
+        (
+            vertex.position.into(),
+            vertex.normal.into(),
+            vertex.texcoord.into(),
+        )
+    }
+}
+
+#[cfg(feature = "mint")]
+impl Vertex {
+    /// Returns the position of the vertex as a `mint` point.
+    pub fn mint_position(&self) -> mint::Point3<f32> {
+        self.position.into()
+    }
+
+    /// Returns the normal of the vertex as a `mint` vector.
+    pub fn mint_normal(&self) -> mint::Vector3<f32> {
+        self.normal.into()
+    }
+
+    /// Returns the texture coordinate of the vertex as a `mint` vector.
+    pub fn mint_texcoord(&self) -> mint::Vector2<f32> {
+        self.texcoord.into()
+    }
+
+    /// Specify the position of the vertex, given as any type that converts
+    /// into a `mint` point.
+    pub fn set_mint_position(&mut self, position: impl Into<mint::Point3<f32>>) {
+        self.position = position.into().into();
+    }
+
+    /// Specify the normal of the vertex, given as any type that converts into
+    /// a `mint` vector.
+    pub fn set_mint_normal(&mut self, normal: impl Into<mint::Vector3<f32>>) {
+        self.normal = normal.into().into();
+    }
+
+    /// Specify the texture coordinate of the vertex, given as any type that
+    /// converts into a `mint` vector.
+    pub fn set_mint_texcoord(&mut self, texcoord: impl Into<mint::Vector2<f32>>) {
+        self.texcoord = texcoord.into().into();
+    }
+}
+
+#[cfg(feature = "glam")]
+impl From<(glam::Vec3, glam::Vec3, glam::Vec2)> for Vertex {
+    /// Create a vertex from its position, normal, and texture coordinate.
+    fn from((position, normal, texcoord): (glam::Vec3, glam::Vec3, glam::Vec2)) -> Self {
+        Vertex {
+            position: position.into(),
+            normal: normal.into(),
+            texcoord: texcoord.into(),
+        }
+    }
+}
+
+#[cfg(feature = "glam")]
+impl From<Vertex> for (glam::Vec3, glam::Vec3, glam::Vec2) {
+    /// Returns the position, normal, and texture coordinate of a vertex.
+    fn from(vertex: Vertex) -> Self {
+        (
+            vertex.position.into(),
+            vertex.normal.into(),
+            vertex.texcoord.into(),
+        )
+    }
+}
+
+/// The vertex structure of shapes that carry a color at each vertex.
+///
+/// It extends the `Vertex` structure with an RGBA color.
+#[derive(Copy, Clone, Debug)]
+pub struct VertexColored {
+    pub position: [f32; 3],
+    pub normal: [f32; 3],
+    pub texcoord: [f32; 2],
+    pub color: [f32; 4],
+}
+
+implement_vertex!(VertexColored, position, normal, texcoord, color);
+
+/// The vertex structure of shapes that carry a tangent at each vertex, for
+/// normal mapping.
+///
+/// It extends the `Vertex` structure with a unit tangent that is orthogonal to
+/// the normal, and points in the direction in which the u texture coordinate
+/// increases. The w component of the tangent holds the handedness of the
+/// tangent frame (either 1 or -1), such that the bitangent, which points in the
+/// direction in which the v texture coordinate increases, is
+/// `cross(normal, tangent.xyz) * tangent.w`.
+#[derive(Copy, Clone, Debug)]
+pub struct VertexTangent {
+    pub position: [f32; 3],
+    pub normal: [f32; 3],
+    pub tangent: [f32; 4],
+    pub texcoord: [f32; 2],
+}
+
+implement_vertex!(VertexTangent, position, normal, tangent, texcoord);
+
+/// The vertex structure of shapes that carry a second set of texture
+/// coordinates for lightmapping.
+///
+/// It extends the `Vertex` structure with lightmap texture coordinates, which
+/// lay out every face of the shape in its own region of the unit square, such
+/// that no two faces share a texel.
+#[derive(Copy, Clone, Debug)]
+pub struct VertexLightmapped {
+    pub position: [f32; 3],
+    pub normal: [f32; 3],
+    pub texcoord: [f32; 2],
+    pub texcoord1: [f32; 2],
+}
+
+implement_vertex!(VertexLightmapped, position, normal, texcoord, texcoord1);
+
+#[cfg(feature = "mint")]
+#[test]
+pub fn ensure_vertex_round_trips_through_mint() {
+    let vertex = Vertex {
+        position: [1.0, 2.0, 3.0],
+        normal: [0.0, 1.0, 0.0],
+        texcoord: [0.25, 0.75],
+    };
+    let (position, normal, texcoord): (mint::Point3<f32>, mint::Vector3<f32>, mint::Vector2<f32>) =
+        vertex.into();
+    assert_eq!(
+        position,
+        mint::Point3 {
+            x: 1.0,
+            y: 2.0,
+            z: 3.0
+        }
+    );
+    assert_eq!(
+        normal,
+        mint::Vector3 {
+            x: 0.0,
+            y: 1.0,
+            z: 0.0
+        }
+    );
+    assert_eq!(texcoord, mint::Vector2 { x: 0.25, y: 0.75 });
+    let round_trip = Vertex::from((position, normal, texcoord));
+    assert_eq!(round_trip.position, vertex.position);
+    assert_eq!(round_trip.normal, vertex.normal);
+    assert_eq!(round_trip.texcoord, vertex.texcoord);
+}
+
+#[cfg(feature = "mint")]
+#[test]
+pub fn ensure_vertex_attributes_convert_into_mint() {
+    let vertex = Vertex {
+        position: [1.0, 2.0, 3.0],
+        normal: [0.0, 1.0, 0.0],
+        texcoord: [0.25, 0.75],
+    };
+    assert_eq!(
+        vertex.mint_position(),
+        mint::Point3 {
+            x: 1.0,
+            y: 2.0,
+            z: 3.0
+        }
+    );
+    assert_eq!(
+        vertex.mint_normal(),
+        mint::Vector3 {
+            x: 0.0,
+            y: 1.0,
+            z: 0.0
+        }
+    );
+    assert_eq!(vertex.mint_texcoord(), mint::Vector2 { x: 0.25, y: 0.75 });
+}
+
+#[cfg(feature = "mint")]
+#[test]
+pub fn ensure_vertex_attributes_convert_from_mint() {
+    let mut vertex = Vertex {
+        position: [0.0; 3],
+        normal: [0.0; 3],
+        texcoord: [0.0; 2],
+    };
+    vertex.set_mint_position(mint::Point3 {
+        x: 1.0,
+        y: 2.0,
+        z: 3.0,
+    });
+    vertex.set_mint_normal(mint::Vector3 {
+        x: 0.0,
+        y: 0.0,
+        z: -1.0,
+    });
+    vertex.set_mint_texcoord(mint::Vector2 { x: 0.5, y: 0.125 });
+    assert_eq!(vertex.position, [1.0, 2.0, 3.0]);
+    assert_eq!(vertex.normal, [0.0, 0.0, -1.0]);
+    assert_eq!(vertex.texcoord, [0.5, 0.125]);
+}
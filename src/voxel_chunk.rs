@@ -0,0 +1,393 @@
+//! A module for constructing voxel chunk shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::cuboid::push_face;
+use crate::errors::ShapeCreationError;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{
+    BuildAs, HasOptions, HasVertices, ShapeOptions, WithBackFaces, WithColors, WithLightmap,
+    WithModifiers, WithOrientation, WithShading, WithTangents, WithTexcoords, WithTransform,
+};
+use crate::shading::Shading;
+use crate::vertex::Vertex;
+
+/// A polygonal `VoxelChunk` object.
+///
+/// This object is constructed using a `VoxelChunkBuilder` object.
+pub struct VoxelChunk {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows a `VoxelChunk` object to be passed as a source of vertices.
+impl<'a> From<&'a VoxelChunk> for glium::vertex::VerticesSource<'a> {
+    fn from(chunk: &'a VoxelChunk) -> glium::vertex::VerticesSource<'a> {
+        (&chunk.vertices).into()
+    }
+}
+
+/// Allows a `VoxelChunk` object to be passed as a source of indices.
+impl<'a> From<&'a VoxelChunk> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a VoxelChunk) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::TrianglesList,
+        }
+    }
+}
+
+/// The source of the filled voxels of a `VoxelChunkBuilder`.
+enum Voxels {
+    Slice(Vec<bool>),
+    Function(Box<dyn Fn(usize, usize, usize) -> bool>),
+}
+
+/// Responsible for building and returning a `VoxelChunk` object.
+///
+/// The chunk is constructed from a regular grid of voxels, each of which is a
+/// unit cube that is either filled or empty, and only the faces between a
+/// filled voxel and an empty voxel (or the outside of the grid) are emitted.
+/// The grid is centred at the origin, such that a filled voxel at `(x, y, z)`
+/// in a grid of dimensions `(w, h, d)` spans from `(x - w / 2, y - h / 2,
+/// z - d / 2)` to one unit beyond along each axis. By default, the chunk is a
+/// single filled voxel, which is identical to the default `Cuboid`. This can be
+/// overriden using the voxel and transformation methods on this object.
+///
+/// Each face is emitted in the same way as a side of a `Cuboid`, such that the
+/// resultant geometry is constructed to suit OpenGL defaults - assuming a
+/// right-handed coordinate system, front-facing polygons are defined in
+/// counter-clock-wise order. Vertex normals point in the direction of their
+/// respective face (such that the shape appears faceted when lit). Vertex
+/// texture coordinates map each face to the unit square. Faces are emitted
+/// voxel by voxel (with the x index varying fastest, followed by the y index),
+/// and in the same order of sides as a `Cuboid` within each voxel.
+pub struct VoxelChunkBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    dimensions: [usize; 3],
+    voxels: Voxels,
+    options: ShapeOptions,
+}
+
+impl Default for VoxelChunkBuilder {
+    fn default() -> Self {
+        VoxelChunkBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            dimensions: [1, 1, 1],
+            voxels: Voxels::Slice(vec![true]),
+            options: ShapeOptions::default(),
+        }
+    }
+}
+
+impl VoxelChunkBuilder {
+    /// Create a new `VoxelChunkBuilder` object.
+    pub fn new() -> VoxelChunkBuilder {
+        Default::default()
+    }
+
+    /// Specify the number of voxels along the x, y and z axes. By default, the
+    /// grid is a single voxel. The grid must have at least one voxel along each
+    /// axis to build a valid chunk.
+    pub fn dimensions(mut self, x: usize, y: usize, z: usize) -> Self {
+        self.dimensions = [x, y, z];
+        self
+    }
+
+    /// Specify which voxels are filled, as a slice indexed by
+    /// `x + (y * w) + (z * w * h)` for a grid of dimensions `(w, h, d)`. The
+    /// length of the slice must match the number of voxels in the grid to
+    /// build a valid chunk.
+    pub fn voxels(mut self, voxels: &[bool]) -> Self {
+        self.voxels = Voxels::Slice(voxels.to_vec());
+        self
+    }
+
+    /// Specify which voxels are filled, as a function of the `(x, y, z)` indices
+    /// of each voxel. The function is evaluated for each voxel in the grid when
+    /// the chunk is built.
+    pub fn voxels_fn<F>(mut self, function: F) -> Self
+    where
+        F: Fn(usize, usize, usize) -> bool + 'static,
+    {
+        self.voxels = Voxels::Function(Box::new(function));
+        self
+    }
+
+    /// Build a new `VoxelChunk` object.
+    pub fn build<F>(self, display: &F) -> Result<VoxelChunk, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the chunk vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// a `VoxelChunk` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        let vertices = self.vertices_with::<Selected>()?;
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.options.check_shading(Self::SHADINGS)?;
+
+        let filled = self.filled()?;
+        let faces = self.exposed_faces(&filled);
+
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<M>::new(self.matrix);
+
+        // Emit each exposed face.
+        let [w, h, d] = self.dimensions;
+        let offset = Vector3::<f32>::new(w as f32, h as f32, d as f32) * 0.5;
+        let mut vertices = Vec::<Vertex>::with_capacity(faces.len() * 6);
+        for &([x, y, z], side) in &faces {
+            let min = Vector3::<f32>::new(x as f32, y as f32, z as f32) - offset;
+            let max = min + Vector3::<f32>::from_value(1.0);
+            push_face(&mut vertices, &transform, side, min, max);
+        }
+
+        self.options.map_texcoords(&mut vertices);
+        Ok(vertices)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    ///
+    /// As the number of faces depends upon which voxels are filled, this
+    /// requires the faces to be found, and returns 0 if the chunk would fail
+    /// to build.
+    pub fn num_vertices(&self) -> usize {
+        self.filled()
+            .map(|filled| self.exposed_faces(&filled).len() * 6)
+            .unwrap_or(0)
+            * self.options.copies()
+    }
+
+    /// Returns whether each voxel of the grid is filled.
+    fn filled(&self) -> Result<Vec<bool>, ShapeCreationError> {
+        let [w, h, d] = self.dimensions;
+        if w == 0 || h == 0 || d == 0 {
+            return Err(ShapeCreationError::InvalidDimensions);
+        }
+        match &self.voxels {
+            Voxels::Slice(voxels) if voxels.len() == w * h * d => Ok(voxels.clone()),
+            Voxels::Slice(_) => Err(ShapeCreationError::InvalidDimensions),
+            Voxels::Function(function) => Ok((0..d)
+                .flat_map(|z| (0..h).flat_map(move |y| (0..w).map(move |x| (x, y, z))))
+                .map(|(x, y, z)| function(x, y, z))
+                .collect()),
+        }
+    }
+
+    /// Returns the faces of the filled voxels that are not shared with another
+    /// filled voxel, as the indices of the voxel, and the side of the face.
+    fn exposed_faces(&self, filled: &[bool]) -> Vec<([usize; 3], usize)> {
+        let [w, h, d] = self.dimensions;
+        let is_filled = |x: usize, y: usize, z: usize| filled[x + (y * w) + (z * w * h)];
+        let mut faces = Vec::new();
+        for z in 0..d {
+            for y in 0..h {
+                for x in 0..w {
+                    if !is_filled(x, y, z) {
+                        continue;
+                    }
+                    let voxel = [x, y, z];
+                    for side in 0..6 {
+                        // Find the neighbouring voxel across this side, if it
+                        // lies within the grid.
+                        let axis = side / 2;
+                        let mut neighbour = voxel;
+                        let exposed = if side % 2 == 0 {
+                            if voxel[axis] == 0 {
+                                true
+                            } else {
+                                neighbour[axis] -= 1;
+                                !is_filled(neighbour[0], neighbour[1], neighbour[2])
+                            }
+                        } else if voxel[axis] + 1 == self.dimensions[axis] {
+                            true
+                        } else {
+                            neighbour[axis] += 1;
+                            !is_filled(neighbour[0], neighbour[1], neighbour[2])
+                        };
+                        if exposed {
+                            faces.push((voxel, side));
+                        }
+                    }
+                }
+            }
+        }
+        faces
+    }
+}
+
+impl HasOptions for VoxelChunkBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for VoxelChunkBuilder {
+    type Shape = VoxelChunk;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> VoxelChunk {
+        VoxelChunk { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for VoxelChunkBuilder {}
+
+impl BuildAs for VoxelChunkBuilder {}
+
+impl WithTangents for VoxelChunkBuilder {}
+
+impl WithLightmap for VoxelChunkBuilder {}
+
+impl WithOrientation for VoxelChunkBuilder {}
+
+impl WithBackFaces for VoxelChunkBuilder {}
+
+impl WithModifiers for VoxelChunkBuilder {}
+
+impl WithTexcoords for VoxelChunkBuilder {}
+
+impl WithColors for VoxelChunkBuilder {}
+
+impl WithShading for VoxelChunkBuilder {
+    /// Only flat shading is supported by the voxel chunk.
+    const SHADINGS: &'static [Shading] = &[Shading::Flat];
+}
+
+#[test]
+pub fn ensure_single_voxel_chunk_matches_cuboid() {
+    let chunk = VoxelChunkBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let cuboid = crate::cuboid::CuboidBuilder::new()
+        .build_vertices()
+        .expect("Failed to build cuboid vertices");
+    assert_eq!(chunk.len(), cuboid.len());
+    for (a, b) in chunk.iter().zip(cuboid.iter()) {
+        assert_eq!(a.position, b.position);
+        assert_eq!(a.normal, b.normal);
+        assert_eq!(a.texcoord, b.texcoord);
+    }
+}
+
+#[test]
+pub fn ensure_voxel_chunk_culls_interior_faces() {
+    let builder = VoxelChunkBuilder::new()
+        .dimensions(3, 2, 4)
+        .voxels_fn(|_, _, _| true);
+    let vertices = builder.build_vertices().expect("Failed to build vertices");
+
+    // Only the faces on the surface of the 3x2x4 box remain.
+    let faces = 2 * ((3 * 2) + (2 * 4) + (3 * 4));
+    assert_eq!(vertices.len(), faces * 6);
+    assert_eq!(vertices.len(), builder.num_vertices());
+    for vertex in &vertices {
+        let [x, y, z] = vertex.position;
+        let normal = Vector3::from(vertex.normal);
+        let on_surface = (x.abs() == 1.5 && normal.x == x.signum())
+            || (y.abs() == 1.0 && normal.y == y.signum())
+            || (z.abs() == 2.0 && normal.z == z.signum());
+        assert!(on_surface);
+    }
+}
+
+#[test]
+pub fn ensure_voxel_chunk_is_watertight() {
+    // A hollow, irregular set of voxels.
+    let builder = VoxelChunkBuilder::new()
+        .dimensions(5, 5, 5)
+        .voxels_fn(|x, y, z| (x * 7 + y * 3 + z * 5) % 4 != 0);
+    let vertices = builder.build_vertices().expect("Failed to build vertices");
+    let mut edges = std::collections::HashMap::<([u32; 3], [u32; 3]), i32>::new();
+    for chunk in vertices.chunks(3) {
+        for index in 0..3 {
+            let a = chunk[index].position.map(f32::to_bits);
+            let b = chunk[(index + 1) % 3].position.map(f32::to_bits);
+            *edges.entry((a, b)).or_insert(0) += 1;
+        }
+    }
+
+    // Each edge of a closed surface (including the diagonal that is shared by
+    // the triangles of each face) is matched by an edge in the opposite
+    // direction.
+    for (&(a, b), &count) in &edges {
+        assert_eq!(count, edges.get(&(b, a)).cloned().unwrap_or(0));
+    }
+}
+
+#[test]
+pub fn ensure_voxel_chunk_with_mismatched_voxels_fails_to_build() {
+    match VoxelChunkBuilder::new()
+        .dimensions(2, 2, 2)
+        .voxels(&[true; 7])
+        .build_vertices()
+    {
+        Err(ShapeCreationError::InvalidDimensions) => (),
+        _ => panic!("Expected InvalidDimensions"),
+    }
+    match VoxelChunkBuilder::new()
+        .dimensions(0, 2, 2)
+        .voxels(&[])
+        .build_vertices()
+    {
+        Err(ShapeCreationError::InvalidDimensions) => (),
+        _ => panic!("Expected InvalidDimensions"),
+    }
+}
+
+#[test]
+pub fn ensure_voxel_chunk_applies_late_pipeline() {
+    use crate::options::WithTexcoords;
+    use crate::uv_mapping::UvMapping;
+
+    crate::options::ensure_late_pipeline(
+        || {
+            VoxelChunkBuilder::new()
+                .uv_mapping(UvMapping::Box)
+                .double_sided()
+        },
+        VoxelChunkBuilder::build_vertices,
+    );
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_voxel_chunk() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = VoxelChunkBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
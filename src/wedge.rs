@@ -0,0 +1,357 @@
+//! A module for constructing wedge shapes.
+
+extern crate cgmath;
+extern crate glium;
+
+use self::cgmath::*;
+use crate::errors::ShapeCreationError;
+use crate::facet::push_polygon;
+use crate::math::{Math, Selected, Transformation};
+use crate::options::{
+    BuildAs, HasOptions, HasVertices, ShapeOptions, WithBackFaces, WithColors, WithLightmap,
+    WithModifiers, WithOrientation, WithShading, WithTangents, WithTexcoords, WithTransform,
+};
+use crate::shading::Shading;
+use crate::vertex::Vertex;
+
+/// A polygonal `Wedge` object.
+///
+/// This object is constructed using a `WedgeBuilder` object.
+pub struct Wedge {
+    vertices: glium::vertex::VertexBufferAny,
+}
+
+/// Allows a `Wedge` object to be passed as a source of vertices.
+impl<'a> From<&'a Wedge> for glium::vertex::VerticesSource<'a> {
+    fn from(wedge: &'a Wedge) -> glium::vertex::VerticesSource<'a> {
+        (&wedge.vertices).into()
+    }
+}
+
+/// Allows a `Wedge` object to be passed as a source of indices.
+impl<'a> From<&'a Wedge> for glium::index::IndicesSource<'a> {
+    fn from(_: &'a Wedge) -> glium::index::IndicesSource<'a> {
+        glium::index::IndicesSource::NoIndices {
+            primitives: glium::index::PrimitiveType::TrianglesList,
+        }
+    }
+}
+
+/// Responsible for building and returning a `Wedge` object.
+///
+/// By default, the wedge is defined as a unit cube cut diagonally in half,
+/// such that its cross-section is a right-angled triangle in the xy-plane that
+/// is extruded along the z-axis. The right angle lies on the negative x side
+/// of the base, and the sloped face rises from the positive x edge of the base
+/// to the top of the face along the negative x side. The wedge is positioned
+/// such that its centroid is located at the origin (e.g. the base lies at
+/// `y = -1/3`, and the vertical face lies at `x = -1/3`). This can be
+/// overriden using the transformation methods on this object.
+///
+/// The resultant geometry is constructed to suit OpenGL defaults - assuming
+/// a right-handed coordinate system, front-facing polygons are defined in
+/// counter-clock-wise order. Vertex normals point in the direction of their
+/// respective face (such that the shape appears faceted when lit). Vertex
+/// texture coordinates define a planar-projection on each face.
+pub struct WedgeBuilder {
+    matrix: cgmath::Matrix4<f32>,
+    options: ShapeOptions,
+}
+
+impl Default for WedgeBuilder {
+    fn default() -> Self {
+        WedgeBuilder {
+            matrix: cgmath::Matrix4::<f32>::identity(),
+            options: ShapeOptions::default(),
+        }
+    }
+}
+
+impl WedgeBuilder {
+    /// Create a new `WedgeBuilder` object.
+    pub fn new() -> WedgeBuilder {
+        Default::default()
+    }
+
+    /// Build a new `Wedge` object.
+    pub fn build<F>(self, display: &F) -> Result<Wedge, ShapeCreationError>
+    where
+        F: glium::backend::Facade,
+    {
+        Ok(self.shape(self.buffer(display)?))
+    }
+
+    /// Build the shape vertices and return them in a vector.
+    ///
+    /// Useful if you wish to do other things with the vertices besides constructing
+    /// a `Wedge` object (e.g. unit testing, further processing, etc).
+    pub fn build_vertices(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        let vertices = self.vertices_with::<Selected>()?;
+        assert!(vertices.len() == self.num_vertices());
+        Ok(vertices)
+    }
+
+    /// Build the shape vertices, transformed by the given math, before the
+    /// options are applied.
+    fn surface<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.options.check_shading(Self::SHADINGS)?;
+
+        // Build the corners of the cross-section, offset such that their
+        // centroid lies at the origin.
+        let (near, far) = (-1.0 / 3.0, 2.0 / 3.0);
+        let corner = Vector2::<f32>::new(near, near);
+        let run = Vector2::<f32>::new(far, near);
+        let rise = Vector2::<f32>::new(near, far);
+        let front = |c: Vector2<f32>| Vector3::<f32>::new(c.x, c.y, 0.5);
+        let back = |c: Vector2<f32>| Vector3::<f32>::new(c.x, c.y, -0.5);
+
+        // Compute the transformation of the positions and normals.
+        let transform = Transformation::<M>::new(self.matrix);
+
+        // Emit the base, the vertical face and the sloped face, followed by
+        // the front and back caps.
+        let faces = [
+            vec![front(corner), back(corner), back(run), front(run)],
+            vec![front(corner), front(rise), back(rise), back(corner)],
+            vec![front(run), back(run), back(rise), front(rise)],
+            vec![front(corner), front(run), front(rise)],
+            vec![back(corner), back(rise), back(run)],
+        ];
+        let mut vertices = Vec::<Vertex>::with_capacity(self.num_vertices());
+        for face in &faces {
+            push_polygon(&mut vertices, &transform, face);
+        }
+
+        self.options.map_texcoords(&mut vertices);
+        Ok(vertices)
+    }
+
+    /// Returns the total number of vertices that will be generated by the builder.
+    pub fn num_vertices(&self) -> usize {
+        ((3 * 2 * 3) + (2 * 3)) * self.options.copies()
+    }
+}
+
+impl HasOptions for WedgeBuilder {
+    fn options(&self) -> &ShapeOptions {
+        &self.options
+    }
+
+    fn options_mut(&mut self) -> &mut ShapeOptions {
+        &mut self.options
+    }
+}
+
+impl HasVertices for WedgeBuilder {
+    type Shape = Wedge;
+
+    fn shape(&self, vertices: glium::vertex::VertexBufferAny) -> Wedge {
+        Wedge { vertices }
+    }
+
+    fn matrix(&self) -> Matrix4<f32> {
+        self.matrix
+    }
+
+    fn matrix_mut(&mut self) -> &mut Matrix4<f32> {
+        &mut self.matrix
+    }
+
+    fn surface_with<M: Math>(&self) -> Result<Vec<Vertex>, ShapeCreationError> {
+        self.surface::<M>()
+    }
+}
+
+impl WithTransform for WedgeBuilder {}
+
+impl BuildAs for WedgeBuilder {}
+
+impl WithTangents for WedgeBuilder {}
+
+impl WithLightmap for WedgeBuilder {}
+
+impl WithOrientation for WedgeBuilder {}
+
+impl WithBackFaces for WedgeBuilder {}
+
+impl WithModifiers for WedgeBuilder {}
+
+impl WithTexcoords for WedgeBuilder {}
+
+impl WithColors for WedgeBuilder {}
+
+impl WithShading for WedgeBuilder {
+    /// Only flat shading is supported by the wedge.
+    const SHADINGS: &'static [Shading] = &[Shading::Flat];
+}
+
+#[test]
+pub fn ensure_default_wedge_has_centroid_at_origin() {
+    let vertices = WedgeBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let mut corners = Vec::<Vector3<f32>>::new();
+    for vertex in &vertices {
+        let pos = Vector3::<f32>::from(vertex.position);
+        if !corners.iter().any(|c| ulps_eq!(*c, pos)) {
+            corners.push(pos);
+        }
+    }
+    assert_eq!(corners.len(), 6);
+    let sum = corners
+        .iter()
+        .fold(Vector3::<f32>::zero(), |sum, &c| sum + c);
+    assert_ulps_eq!(sum / 6.0, Vector3::<f32>::zero(), epsilon = 0.0001);
+    for c in &corners {
+        assert!(abs_diff_eq!(c.z.abs(), 0.5));
+    }
+}
+
+#[test]
+pub fn ensure_default_wedge_has_slope_at_45_degrees() {
+    let vertices = WedgeBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let slope = vertices
+        .iter()
+        .map(|vertex| Vector3::<f32>::from(vertex.normal))
+        .filter(|normal| normal.x > 0.0001 && normal.y > 0.0001)
+        .collect::<Vec<Vector3<f32>>>();
+    assert_eq!(slope.len(), 6);
+    for normal in &slope {
+        let angle = Rad::acos(normal.dot(Vector3::<f32>::unit_y()));
+        assert_ulps_eq!(angle, Rad(std::f32::consts::FRAC_PI_4), epsilon = 0.0001);
+        assert!(abs_diff_eq!(normal.z, 0.0));
+    }
+}
+
+#[test]
+pub fn ensure_default_wedge_has_outward_facing_normals() {
+    let vertices = WedgeBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let centroid = (Vector3::<f32>::from(chunk[0].position)
+            + Vector3::<f32>::from(chunk[1].position)
+            + Vector3::<f32>::from(chunk[2].position))
+            / 3.0;
+        let normal = Vector3::<f32>::from(chunk[0].normal);
+        assert!(normal.dot(centroid) > 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_default_wedge_has_uvs_in_unit_range() {
+    let vertices = WedgeBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for vertex in &vertices {
+        assert!(vertex.texcoord[0] >= -0.0001 && vertex.texcoord[0] <= 1.0001);
+        assert!(vertex.texcoord[1] >= -0.0001 && vertex.texcoord[1] <= 1.0001);
+    }
+}
+
+#[test]
+pub fn ensure_default_wedge_has_ccw_triangles() {
+    let vertices = WedgeBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let eyepos = v0 + Vector3::<f32>::from(chunk[0].normal);
+        let e0 = v1 - v0;
+        let e1 = v2 - v0;
+        let n = e0.cross(e1);
+        assert!(n.dot(v0 - eyepos) <= 0.0);
+        assert!(n.dot(v1 - eyepos) <= 0.0);
+        assert!(n.dot(v2 - eyepos) <= 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_default_wedge_has_faceted_normals() {
+    let vertices = WedgeBuilder::new()
+        .build_vertices()
+        .expect("Failed to build vertices");
+    for chunk in vertices.chunks(3) {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let n = (v1 - v0).cross(v2 - v0).normalize();
+        for vertex in chunk {
+            assert_ulps_eq!(n, Vector3::<f32>::from(vertex.normal), epsilon = 0.0001);
+        }
+    }
+}
+
+#[test]
+pub fn ensure_mirrored_wedge_halves_are_front_facing() {
+    use crate::mirror_plane::MirrorPlane;
+
+    let base = WedgeBuilder::new()
+        .translate(0.0, 0.0, 1.5)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    let vertices = WedgeBuilder::new()
+        .translate(0.0, 0.0, 1.5)
+        .mirror(MirrorPlane::Xy)
+        .build_vertices()
+        .expect("Failed to build vertices");
+    assert_eq!(vertices.len(), base.len() * 2);
+    let (front, mirrored) = vertices.split_at(base.len());
+    for (chunk, expected) in mirrored.chunks(3).zip(base.chunks(3)) {
+        // The reflected triangle has its last two vertices swapped.
+        for (vertex, expected) in chunk.iter().zip([0, 2, 1].iter().map(|&i| expected[i])) {
+            let [x, y, z] = expected.position;
+            assert_eq!(vertex.position, [x, y, -z]);
+            let [x, y, z] = expected.normal;
+            assert_eq!(vertex.normal, [x, y, -z]);
+        }
+    }
+    for (chunk, centre) in front
+        .chunks(3)
+        .map(|chunk| (chunk, 1.5))
+        .chain(mirrored.chunks(3).map(|chunk| (chunk, -1.5)))
+    {
+        let v0 = Vector3::<f32>::from(chunk[0].position);
+        let v1 = Vector3::<f32>::from(chunk[1].position);
+        let v2 = Vector3::<f32>::from(chunk[2].position);
+        let normal = Vector3::<f32>::from(chunk[0].normal);
+        assert!((v1 - v0).cross(v2 - v0).dot(normal) > 0.0);
+        let centroid = (v0 + v1 + v2) / 3.0 - Vector3::<f32>::new(0.0, 0.0, centre);
+        assert!(normal.dot(centroid) > 0.0);
+    }
+}
+
+#[test]
+pub fn ensure_wedge_applies_late_pipeline() {
+    use crate::options::WithTexcoords;
+    use crate::uv_mapping::UvMapping;
+
+    crate::options::ensure_late_pipeline(
+        || {
+            WedgeBuilder::new()
+                .uv_mapping(UvMapping::Box)
+                .double_sided()
+        },
+        WedgeBuilder::build_vertices,
+    );
+}
+
+#[cfg(feature = "glam")]
+#[test]
+pub fn ensure_glam_builds_identical_wedge() {
+    use crate::math::{Cgmath, Glam};
+
+    let builder = WedgeBuilder::new()
+        .scale(1.5, 0.75, 2.0)
+        .rotate_y(0.7)
+        .rotate_x(0.3)
+        .translate(0.25, -0.5, 1.0);
+    crate::math::ensure_glam_matches_cgmath(
+        builder.vertices_with::<Cgmath>(),
+        builder.vertices_with::<Glam>(),
+    );
+}
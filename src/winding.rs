@@ -0,0 +1,17 @@
+//! A module containing the winding orders shared by all shapes.
+
+/// The order in which the vertices of the front face of each triangle are
+/// wound, when viewed from the front.
+///
+/// The normals of a shape point outwards regardless of its winding order, such
+/// that a clock-wise shape is lit in the same way as its counter-clock-wise
+/// counterpart, and need only be drawn with the matching
+/// `glium::draw_parameters::BackfaceCullingMode`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Winding {
+    /// The front faces are wound counter-clock-wise, as expected by OpenGL by
+    /// default.
+    Ccw,
+    /// The front faces are wound clock-wise.
+    Cw,
+}